@@ -6,15 +6,21 @@ use gpui::{
     ParentElement as _, Render, SharedString, StatefulInteractiveElement, Styled, View,
     ViewContext, VisualContext as _,
 };
-use language::{LanguageRegistry, LanguageServerBinaryStatus};
+use language::{Language, LanguageRegistry, LanguageServerBinaryStatus};
 use project::{LanguageServerProgress, Project};
 use smallvec::SmallVec;
 use std::{cmp::Reverse, fmt::Write, sync::Arc};
 use ui::prelude::*;
 use util::ResultExt;
-use workspace::{item::ItemHandle, StatusItemView, Workspace};
+use workspace::{
+    item::ItemHandle, notifications::simple_message_notification::MessageNotification,
+    StatusItemView, Workspace,
+};
 
-actions!(activity_indicator, [ShowErrorMessage]);
+actions!(
+    activity_indicator,
+    [ShowErrorMessage, CancelLanguageServerDownloads]
+);
 
 const DOWNLOAD_ICON: &str = "icons/download.svg";
 const WARNING_ICON: &str = "icons/warning.svg";
@@ -26,11 +32,13 @@ pub enum Event {
 pub struct ActivityIndicator {
     statuses: Vec<LspStatus>,
     project: Model<Project>,
+    languages: Arc<LanguageRegistry>,
     auto_updater: Option<Model<AutoUpdater>>,
 }
 
 struct LspStatus {
     name: Arc<str>,
+    language: Arc<Language>,
     status: LanguageServerBinaryStatus,
 }
 
@@ -45,6 +53,14 @@ struct Content {
     icon: Option<&'static str>,
     message: String,
     on_click: Option<Arc<dyn Fn(&mut ActivityIndicator, &mut ViewContext<ActivityIndicator>)>>,
+    /// A button shown after the message, for actions that shouldn't be taken by clicking
+    /// the message by mistake.
+    button: Option<ContentButton>,
+}
+
+struct ContentButton {
+    label: &'static str,
+    on_click: Arc<dyn Fn(&mut ActivityIndicator, &mut ViewContext<ActivityIndicator>)>,
 }
 
 impl ActivityIndicator {
@@ -63,6 +79,7 @@ impl ActivityIndicator {
                         this.statuses.retain(|s| s.name != language.name());
                         this.statuses.push(LspStatus {
                             name: language.name(),
+                            language,
                             status: event,
                         });
                         cx.notify();
@@ -80,30 +97,55 @@ impl ActivityIndicator {
             Self {
                 statuses: Default::default(),
                 project: project.clone(),
+                languages,
                 auto_updater,
             }
         });
 
-        cx.subscribe(&this, move |workspace, _, event, cx| match event {
-            Event::ShowError { lsp_name, error } => {
-                if let Some(buffer) = project
-                    .update(cx, |project, cx| project.create_buffer(error, None, cx))
-                    .log_err()
-                {
-                    buffer.update(cx, |buffer, cx| {
-                        buffer.edit(
-                            [(0..0, format!("Language server error: {}\n\n", lsp_name))],
-                            None,
-                            cx,
-                        );
-                    });
-                    workspace.add_item(
-                        Box::new(
-                            cx.new_view(|cx| Editor::for_buffer(buffer, Some(project.clone()), cx)),
-                        ),
-                        cx,
-                    );
-                }
+        cx.subscribe(&this, {
+            let project = project.clone();
+            move |workspace, _, event, cx| match event {
+                Event::ShowError { lsp_name, error } => open_error(
+                    workspace,
+                    &project,
+                    format!("Language server error: {}", lsp_name),
+                    error,
+                    cx,
+                ),
+            }
+        })
+        .detach();
+
+        cx.subscribe(&project, |workspace, project, event, cx| {
+            if let project::Event::LanguageServerCrashed {
+                language_server_id,
+                message,
+                report,
+            } = event
+            {
+                let workspace_handle = cx.view().downgrade();
+                let report = Arc::<str>::from(format!("{message}\n\n{report}"));
+                workspace.show_notification(language_server_id.0, cx, |cx| {
+                    cx.new_view(|_| {
+                        MessageNotification::new(message.clone())
+                            .with_click_message("View Logs")
+                            .on_click(move |cx| {
+                                let project = project.clone();
+                                let report = report.clone();
+                                workspace_handle
+                                    .update(cx, |workspace, cx| {
+                                        open_error(
+                                            workspace,
+                                            &project,
+                                            "Language server crash".to_string(),
+                                            &report,
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                            })
+                    })
+                });
             }
         })
         .detach();
@@ -126,6 +168,34 @@ impl ActivityIndicator {
         cx.notify();
     }
 
+    fn cancel_downloads(&mut self, _: &CancelLanguageServerDownloads, cx: &mut ViewContext<Self>) {
+        for status in &self.statuses {
+            if let LanguageServerBinaryStatus::Downloading { .. } = status.status {
+                self.languages
+                    .cancel_language_server_downloads(&status.language);
+            }
+        }
+        cx.notify();
+    }
+
+    fn retry_cancelled_downloads(&mut self, cx: &mut ViewContext<Self>) {
+        let mut languages = Vec::new();
+        self.statuses.retain(|status| {
+            if let LanguageServerBinaryStatus::Cancelled = status.status {
+                languages.push(status.language.clone());
+                false
+            } else {
+                true
+            }
+        });
+        self.project.update(cx, |project, cx| {
+            for language in languages {
+                project.restart_language_servers_for_language(language, cx);
+            }
+        });
+        cx.notify();
+    }
+
     fn dismiss_error_message(&mut self, _: &DismissErrorMessage, cx: &mut ViewContext<Self>) {
         if let Some(updater) = &self.auto_updater {
             updater.update(cx, |updater, cx| {
@@ -194,32 +264,63 @@ impl ActivityIndicator {
                 icon: None,
                 message,
                 on_click: None,
+                button: None,
             };
         }
 
         // Show any language server installation info.
         let mut downloading = SmallVec::<[_; 3]>::new();
+        let mut downloaded_bytes = 0;
+        let mut total_bytes = Some(0);
         let mut checking_for_update = SmallVec::<[_; 3]>::new();
         let mut failed = SmallVec::<[_; 3]>::new();
+        let mut cancelled = SmallVec::<[_; 3]>::new();
         for status in &self.statuses {
             let name = status.name.clone();
             match status.status {
                 LanguageServerBinaryStatus::CheckingForUpdate => checking_for_update.push(name),
-                LanguageServerBinaryStatus::Downloading => downloading.push(name),
+                LanguageServerBinaryStatus::Downloading {
+                    downloaded_bytes: downloaded,
+                    total_bytes: total,
+                } => {
+                    downloading.push(name);
+                    downloaded_bytes += downloaded;
+                    total_bytes = total_bytes.zip(total).map(|(sum, total)| sum + total);
+                }
                 LanguageServerBinaryStatus::Failed { .. } => failed.push(name),
+                LanguageServerBinaryStatus::Cancelled => cancelled.push(name),
                 LanguageServerBinaryStatus::Downloaded | LanguageServerBinaryStatus::Cached => {}
             }
         }
 
         if !downloading.is_empty() {
+            let mut message = format!(
+                "Downloading {} language server{}",
+                downloading.join(", "),
+                if downloading.len() > 1 { "s" } else { "" }
+            );
+            match total_bytes {
+                Some(total_bytes) if total_bytes > 0 => write!(
+                    &mut message,
+                    " ({:.1} of {:.1} MB)",
+                    megabytes(downloaded_bytes),
+                    megabytes(total_bytes)
+                )
+                .unwrap(),
+                _ if downloaded_bytes > 0 => {
+                    write!(&mut message, " ({:.1} MB)", megabytes(downloaded_bytes)).unwrap()
+                }
+                _ => {}
+            }
+            message.push_str("...");
             return Content {
                 icon: Some(DOWNLOAD_ICON),
-                message: format!(
-                    "Downloading {} language server{}...",
-                    downloading.join(", "),
-                    if downloading.len() > 1 { "s" } else { "" }
-                ),
+                message,
                 on_click: None,
+                button: Some(ContentButton {
+                    label: "Cancel",
+                    on_click: Arc::new(|this, cx| this.cancel_downloads(&Default::default(), cx)),
+                }),
             };
         } else if !checking_for_update.is_empty() {
             return Content {
@@ -234,6 +335,7 @@ impl ActivityIndicator {
                     }
                 ),
                 on_click: None,
+                button: None,
             };
         } else if !failed.is_empty() {
             return Content {
@@ -246,6 +348,21 @@ impl ActivityIndicator {
                 on_click: Some(Arc::new(|this, cx| {
                     this.show_error_message(&Default::default(), cx)
                 })),
+                button: None,
+            };
+        } else if !cancelled.is_empty() {
+            return Content {
+                icon: Some(WARNING_ICON),
+                message: format!(
+                    "Cancelled the download of {} language server{}.",
+                    cancelled.join(", "),
+                    if cancelled.len() > 1 { "s" } else { "" }
+                ),
+                on_click: None,
+                button: Some(ContentButton {
+                    label: "Retry",
+                    on_click: Arc::new(|this, cx| this.retry_cancelled_downloads(cx)),
+                }),
             };
         }
 
@@ -256,16 +373,19 @@ impl ActivityIndicator {
                     icon: Some(DOWNLOAD_ICON),
                     message: "Checking for Zed updates…".to_string(),
                     on_click: None,
+                    button: None,
                 },
                 AutoUpdateStatus::Downloading => Content {
                     icon: Some(DOWNLOAD_ICON),
                     message: "Downloading Zed update…".to_string(),
                     on_click: None,
+                    button: None,
                 },
                 AutoUpdateStatus::Installing => Content {
                     icon: Some(DOWNLOAD_ICON),
                     message: "Installing Zed update…".to_string(),
                     on_click: None,
+                    button: None,
                 },
                 AutoUpdateStatus::Updated => Content {
                     icon: None,
@@ -273,6 +393,7 @@ impl ActivityIndicator {
                     on_click: Some(Arc::new(|_, cx| {
                         workspace::restart(&Default::default(), cx)
                     })),
+                    button: None,
                 },
                 AutoUpdateStatus::Errored => Content {
                     icon: Some(WARNING_ICON),
@@ -280,6 +401,7 @@ impl ActivityIndicator {
                     on_click: Some(Arc::new(|this, cx| {
                         this.dismiss_error_message(&Default::default(), cx)
                     })),
+                    button: None,
                 },
                 AutoUpdateStatus::Idle => Default::default(),
             };
@@ -289,6 +411,32 @@ impl ActivityIndicator {
     }
 }
 
+/// Opens a new buffer with the given error, under the given header.
+fn open_error(
+    workspace: &mut Workspace,
+    project: &Model<Project>,
+    header: String,
+    error: &str,
+    cx: &mut ViewContext<Workspace>,
+) {
+    if let Some(buffer) = project
+        .update(cx, |project, cx| project.create_buffer(error, None, cx))
+        .log_err()
+    {
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, format!("{header}\n\n"))], None, cx);
+        });
+        workspace.add_item(
+            Box::new(cx.new_view(|cx| Editor::for_buffer(buffer, Some(project.clone()), cx))),
+            cx,
+        );
+    }
+}
+
+fn megabytes(bytes: u64) -> f64 {
+    bytes as f64 / 1_000_000.
+}
+
 impl EventEmitter<Event> for ActivityIndicator {}
 
 impl Render for ActivityIndicator {
@@ -298,7 +446,8 @@ impl Render for ActivityIndicator {
         let mut result = h_flex()
             .id("activity-indicator")
             .on_action(cx.listener(Self::show_error_message))
-            .on_action(cx.listener(Self::dismiss_error_message));
+            .on_action(cx.listener(Self::dismiss_error_message))
+            .on_action(cx.listener(Self::cancel_downloads));
 
         if let Some(on_click) = content.on_click {
             result = result
@@ -311,6 +460,12 @@ impl Render for ActivityIndicator {
         result
             .children(content.icon.map(|icon| svg().path(icon)))
             .child(Label::new(SharedString::from(content.message)).size(LabelSize::Small))
+            .children(content.button.map(|button| {
+                let on_click = button.on_click;
+                Button::new("activity-indicator-button", button.label)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(move |this, _, cx| on_click(this, cx)))
+            }))
     }
 }
 
@@ -6,6 +6,7 @@ use futures::{
 use gpui::{AppContext, BackgroundExecutor};
 use isahc::{http::StatusCode, Request, RequestExt};
 use parking_lot::RwLock;
+use secrets::{delete_secret, read_secret, write_secret, SecretScope};
 use serde::{Deserialize, Serialize};
 use std::{
     env,
@@ -13,7 +14,10 @@ use std::{
     io,
     sync::Arc,
 };
-use util::ResultExt;
+use util::{
+    audit_log::{self, AuditEvent, AuditEventKind},
+    ResultExt,
+};
 
 use crate::{
     auth::{CredentialProvider, ProviderCredential},
@@ -117,6 +121,14 @@ pub async fn stream_completion(
     let (tx, rx) = futures::channel::mpsc::unbounded::<Result<OpenAiResponseStreamEvent>>();
 
     let json_data = request.data()?;
+    audit_log::record(
+        AuditEvent::new(
+            AuditEventKind::Request,
+            "assistant",
+            format!("Completion request to {OPEN_AI_API_URL}"),
+        )
+        .content(&json_data),
+    );
     let mut response = Request::post(format!("{OPEN_AI_API_URL}/chat/completions"))
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key))
@@ -232,7 +244,7 @@ impl CredentialProvider for OpenAiCompletionProvider {
                 if let Some(api_key) = env::var("OPENAI_API_KEY").log_err() {
                     async move { ProviderCredential::Credentials { api_key } }.boxed()
                 } else {
-                    let credentials = cx.read_credentials(OPEN_AI_API_URL);
+                    let credentials = read_secret(SecretScope::OpenAi, OPEN_AI_API_URL, cx);
                     async move {
                         if let Some(Some((_, api_key))) = credentials.await.log_err() {
                             if let Some(api_key) = String::from_utf8(api_key).log_err() {
@@ -265,9 +277,13 @@ impl CredentialProvider for OpenAiCompletionProvider {
         *self.credential.write() = credential.clone();
         let credential = credential.clone();
         let write_credentials = match credential {
-            ProviderCredential::Credentials { api_key } => {
-                Some(cx.write_credentials(OPEN_AI_API_URL, "Bearer", api_key.as_bytes()))
-            }
+            ProviderCredential::Credentials { api_key } => Some(write_secret(
+                SecretScope::OpenAi,
+                OPEN_AI_API_URL,
+                "Bearer",
+                api_key.as_bytes(),
+                cx,
+            )),
             _ => None,
         };
 
@@ -281,7 +297,7 @@ impl CredentialProvider for OpenAiCompletionProvider {
 
     fn delete_credentials(&self, cx: &mut AppContext) -> BoxFuture<()> {
         *self.credential.write() = ProviderCredential::NoCredentials;
-        let delete_credentials = cx.delete_credentials(OPEN_AI_API_URL);
+        let delete_credentials = delete_secret(SecretScope::OpenAi, OPEN_AI_API_URL, cx);
         async move {
             delete_credentials.await.log_err();
         }
@@ -12,6 +12,7 @@ use lazy_static::lazy_static;
 use parking_lot::{Mutex, RwLock};
 use parse_duration::parse;
 use postage::watch;
+use secrets::{delete_secret, read_secret, write_secret, SecretScope};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::env;
@@ -19,6 +20,7 @@ use std::ops::Add;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tiktoken_rs::{cl100k_base, CoreBPE};
+use util::audit_log::{self, AuditEvent, AuditEventKind};
 use util::http::{HttpClient, Request};
 use util::ResultExt;
 
@@ -170,7 +172,7 @@ impl CredentialProvider for OpenAiEmbeddingProvider {
                 if let Some(api_key) = env::var("OPENAI_API_KEY").log_err() {
                     async move { ProviderCredential::Credentials { api_key } }.boxed()
                 } else {
-                    let credentials = cx.read_credentials(OPEN_AI_API_URL);
+                    let credentials = read_secret(SecretScope::OpenAi, OPEN_AI_API_URL, cx);
                     async move {
                         if let Some(Some((_, api_key))) = credentials.await.log_err() {
                             if let Some(api_key) = String::from_utf8(api_key).log_err() {
@@ -203,9 +205,13 @@ impl CredentialProvider for OpenAiEmbeddingProvider {
         *self.credential.write() = credential.clone();
         let credential = credential.clone();
         let write_credentials = match credential {
-            ProviderCredential::Credentials { api_key } => {
-                Some(cx.write_credentials(OPEN_AI_API_URL, "Bearer", api_key.as_bytes()))
-            }
+            ProviderCredential::Credentials { api_key } => Some(write_secret(
+                SecretScope::OpenAi,
+                OPEN_AI_API_URL,
+                "Bearer",
+                api_key.as_bytes(),
+                cx,
+            )),
             _ => None,
         };
 
@@ -219,7 +225,7 @@ impl CredentialProvider for OpenAiEmbeddingProvider {
 
     fn delete_credentials(&self, cx: &mut AppContext) -> BoxFuture<()> {
         *self.credential.write() = ProviderCredential::NoCredentials;
-        let delete_credentials = cx.delete_credentials(OPEN_AI_API_URL);
+        let delete_credentials = delete_secret(SecretScope::OpenAi, OPEN_AI_API_URL, cx);
         async move {
             delete_credentials.await.log_err();
         }
@@ -252,6 +258,16 @@ impl EmbeddingProvider for OpenAiEmbeddingProvider {
         let mut rate_limiting = false;
         let mut request_timeout: u64 = 15;
         let mut response: Response<AsyncBody>;
+        // The spans can add up to whole projects, so only their size is recorded.
+        audit_log::record(AuditEvent::new(
+            AuditEventKind::Request,
+            "semantic index",
+            format!(
+                "Embedding request to {OPEN_AI_API_URL} with {} spans ({} bytes of code)",
+                spans.len(),
+                spans.iter().map(|span| span.len()).sum::<usize>()
+            ),
+        ));
         while request_number < MAX_RETRIES {
             response = self
                 .send_request(
@@ -0,0 +1,273 @@
+use editor::{actions::MoveToEnd, Editor, EditorEvent};
+use futures::StreamExt;
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, IntoElement, Model,
+    Render, Subscription, Task, View, ViewContext, VisualContext, WindowContext,
+};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Label};
+use util::{
+    audit_log::{self, AuditEvent, AuditEventKind},
+    ResultExt,
+};
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+actions!(audit_log, [OpenAuditLog]);
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuditLogSettings {
+    pub record_content: bool,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct AuditLogSettingsContent {
+    /// Whether to record the data sent to AI providers in full. Otherwise only its
+    /// size and SHA-256 hash are recorded, so that the log doesn't hold a copy of
+    /// the code that was sent.
+    ///
+    /// Default: false
+    record_content: Option<bool>,
+}
+
+impl Settings for AuditLogSettings {
+    const KEY: Option<&'static str> = Some("audit_log");
+
+    type FileContent = AuditLogSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+pub fn init(cx: &mut AppContext) {
+    AuditLogSettings::register(cx);
+    audit_log::set_record_content(AuditLogSettings::get_global(cx).record_content);
+    cx.observe_global::<SettingsStore>(|cx| {
+        audit_log::set_record_content(AuditLogSettings::get_global(cx).record_content);
+    })
+    .detach();
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenAuditLog, cx| {
+            let project = workspace.project().clone();
+            let view = cx.new_view(|cx| AuditLogView::new(project, cx));
+            workspace.add_item(Box::new(view), cx);
+        });
+    })
+    .detach();
+}
+
+/// Shows the commands that Zed ran and the data that it sent on its own, for the
+/// project's worktrees and for Zed as a whole, starting with the ones recorded in
+/// earlier sessions.
+pub struct AuditLogView {
+    editor: View<Editor>,
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    _update_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl AuditLogView {
+    pub fn new(project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_read_only(true);
+            editor.set_show_copilot_suggestions(false);
+            editor
+        });
+
+        // Subscribe before loading, so that no event is missed in between. The events
+        // that were both loaded and received are only shown once.
+        let mut new_events = audit_log::subscribe();
+        let subscribed_at = unix_timestamp();
+        let update_task = cx.spawn(|this, mut cx| async move {
+            let events = audit_log::load().await.log_err().unwrap_or_default();
+            let mut shown_events = events
+                .iter()
+                .filter(|event| event.timestamp >= subscribed_at)
+                .cloned()
+                .collect::<Vec<_>>();
+            if this
+                .update(&mut cx, |this, cx| this.append_events(&events, cx))
+                .is_err()
+            {
+                return;
+            }
+            while let Some(event) = new_events.next().await {
+                if let Some(ix) = shown_events.iter().position(|shown| *shown == event) {
+                    shown_events.remove(ix);
+                    continue;
+                }
+                if this
+                    .update(&mut cx, |this, cx| this.append_events(&[event], cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let focus_handle = cx.focus_handle();
+        let subscriptions = vec![
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+            cx.on_focus(&focus_handle, |this, cx| cx.focus_view(&this.editor)),
+        ];
+
+        Self {
+            editor,
+            project,
+            focus_handle,
+            _update_task: update_task,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn append_events(&mut self, events: &[AuditEvent], cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        let roots = project
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path())
+            .collect::<Vec<_>>();
+        let local_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+        let text = events
+            .iter()
+            .filter(|event| {
+                event.project_root.as_ref().map_or(true, |project_root| {
+                    roots.iter().any(|root| project_root.starts_with(root))
+                })
+            })
+            .map(|event| format_event(event, local_offset))
+            .collect::<String>();
+        if text.is_empty() {
+            return;
+        }
+
+        self.editor.update(cx, |editor, cx| {
+            editor.set_read_only(false);
+            let end = editor.buffer().read(cx).len(cx);
+            editor.edit([(end..end, text)], cx);
+            editor.set_read_only(true);
+            editor.move_to_end(&MoveToEnd, cx);
+        });
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Formats an event as a line with its local time, kind, source and summary,
+/// followed by its indented details.
+fn format_event(event: &AuditEvent, local_offset: UtcOffset) -> String {
+    let time = OffsetDateTime::from_unix_timestamp(event.timestamp as i64)
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+        .to_offset(local_offset);
+    let kind = match event.kind {
+        AuditEventKind::Command => "command",
+        AuditEventKind::Download => "download",
+        AuditEventKind::Request => "request",
+    };
+    let mut text = format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}  {kind:<8}  {}: {}\n",
+        time.year(),
+        u8::from(time.month()),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+        event.source,
+        event.summary,
+    );
+    if let Some(project_root) = &event.project_root {
+        writeln!(text, "    project: {}", project_root.display()).unwrap();
+    }
+    if let Some(details) = &event.details {
+        for line in details.lines() {
+            writeln!(text, "    {line}").unwrap();
+        }
+    }
+    text
+}
+
+impl Render for AuditLogView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        self.editor
+            .update(cx, |editor, cx| editor.render(cx).into_any_element())
+    }
+}
+
+impl FocusableView for AuditLogView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<EditorEvent> for AuditLogView {}
+
+impl Item for AuditLogView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext<'_>) -> AnyElement {
+        Label::new("Audit Log")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_event() {
+        let event = AuditEvent {
+            timestamp: 1704103200,
+            kind: AuditEventKind::Command,
+            source: "hooks".into(),
+            project_root: Some("/projects/a".into()),
+            summary: "cargo fmt".into(),
+            details: Some("sh -c \"cargo fmt\" (in /projects/a)".into()),
+        };
+        assert_eq!(
+            format_event(&event, UtcOffset::UTC),
+            "2024-01-01 10:00:00  command   hooks: cargo fmt\n    \
+             project: /projects/a\n    \
+             sh -c \"cargo fmt\" (in /projects/a)\n"
+        );
+    }
+}
@@ -14,7 +14,23 @@ pub enum CliRequest {
     //
     // Since Zed CLI has to be installed separately, there can be situations when old CLI is
     // querying new Zed editors, support both formats by using `String` here and parsing it on Zed side later.
-    Open { paths: Vec<String>, wait: bool },
+    Open {
+        paths: Vec<String>,
+        wait: bool,
+    },
+    /// Shows decorations from an external tool in the editors of the files they belong to,
+    /// replacing the ones previously set under the same namespace.
+    ///
+    /// The decorations are sent as the JSON that the tool wrote, and parsed on Zed side,
+    /// for the same reason as the paths of `Open`.
+    SetDecorations {
+        namespace: String,
+        decorations: String,
+        working_directory: String,
+    },
+    ClearDecorations {
+        namespace: String,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
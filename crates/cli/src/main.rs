@@ -36,6 +36,17 @@ struct Args {
     /// Custom Zed.app path
     #[clap(short, long)]
     bundle_path: Option<PathBuf>,
+    /// Show the decorations in the given JSON file, or `-` for stdin, in the editors of
+    /// the files they belong to. Replaces the decorations previously shown under the same
+    /// namespace.
+    #[clap(long, value_name = "FILE", conflicts_with = "clear_decorations")]
+    decorate: Option<PathBuf>,
+    /// Remove the decorations shown under the namespace.
+    #[clap(long)]
+    clear_decorations: bool,
+    /// The namespace of the decorations, so that each tool replaces and clears only its own.
+    #[clap(long, default_value = "default")]
+    namespace: String,
 }
 
 fn parse_path_with_position(
@@ -46,6 +57,12 @@ fn parse_path_with_position(
     })
 }
 
+/// Remote files are downloaded by Zed itself, and can't have a `:line:row` suffix.
+fn is_remote_url(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    path.starts_with("sftp://") || path.starts_with("scp://")
+}
+
 #[derive(Debug, Deserialize)]
 struct InfoPlist {
     #[serde(rename = "CFBundleShortVersionString")]
@@ -62,12 +79,18 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if let Some(request) = decorations_request(&args)? {
+        let (tx, rx) = bundle.launch()?;
+        tx.send(request)?;
+        return wait_for_exit(rx);
+    }
+
     for path in args
         .paths_with_position
         .iter()
         .map(|path_with_position| &path_with_position.path_like)
     {
-        if !path.exists() {
+        if !path.exists() && !is_remote_url(path) {
             touch(path.as_path())?;
         }
     }
@@ -79,6 +102,9 @@ fn main() -> Result<()> {
             .paths_with_position
             .into_iter()
             .map(|path_with_position| {
+                if is_remote_url(&path_with_position.path_like) {
+                    return Ok(path_with_position.path_like.display().to_string());
+                }
                 let path_with_position = path_with_position.map_path_like(|path| {
                     fs::canonicalize(&path)
                         .with_context(|| format!("path {path:?} canonicalization"))
@@ -89,6 +115,31 @@ fn main() -> Result<()> {
         wait: args.wait,
     })?;
 
+    wait_for_exit(rx)
+}
+
+fn decorations_request(args: &Args) -> Result<Option<CliRequest>> {
+    if let Some(path) = &args.decorate {
+        let decorations = if path == Path::new("-") {
+            io::read_to_string(io::stdin())?
+        } else {
+            fs::read_to_string(path).with_context(|| format!("reading decorations {path:?}"))?
+        };
+        Ok(Some(CliRequest::SetDecorations {
+            namespace: args.namespace.clone(),
+            decorations,
+            working_directory: std::env::current_dir()?.display().to_string(),
+        }))
+    } else if args.clear_decorations {
+        Ok(Some(CliRequest::ClearDecorations {
+            namespace: args.namespace.clone(),
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+fn wait_for_exit(rx: IpcReceiver<CliResponse>) -> Result<()> {
     while let Ok(response) = rx.recv() {
         match response {
             CliResponse::Ping => {}
@@ -11,6 +11,7 @@ use async_tungstenite::tungstenite::{
     http::{Request, StatusCode},
 };
 use collections::HashMap;
+use feature_flags::FeatureFlagAppExt;
 use futures::{
     channel::oneshot, future::LocalBoxFuture, AsyncReadExt, FutureExt, SinkExt, StreamExt,
     TryFutureExt as _, TryStreamExt,
@@ -25,6 +26,7 @@ use rand::prelude::*;
 use release_channel::{AppVersion, ReleaseChannel};
 use rpc::proto::{AnyTypedEnvelope, EntityMessage, EnvelopedMessage, PeerId, RequestMessage};
 use schemars::JsonSchema;
+use secrets::{delete_secret, read_secret, write_secret, SecretScope};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use settings::{Settings, SettingsStore};
@@ -101,9 +103,16 @@ impl Settings for ClientSettings {
 
 pub fn init_settings(cx: &mut AppContext) {
     TelemetrySettings::register(cx);
+    FeatureFlagSettings::register(cx);
     cx.update_global(|store: &mut SettingsStore, cx| {
         store.register_setting::<ClientSettings>(cx);
     });
+
+    cx.override_flags(FeatureFlagSettings::get_global(cx).0.clone());
+    cx.observe_global::<SettingsStore>(|cx| {
+        cx.override_flags(FeatureFlagSettings::get_global(cx).0.clone());
+    })
+    .detach();
 }
 
 pub fn init(client: &Arc<Client>, cx: &mut AppContext) {
@@ -420,6 +429,26 @@ impl settings::Settings for TelemetrySettings {
     }
 }
 
+/// Turns experimental features on or off by the name of their feature flag, in place
+/// of the flags that the server rolls out to the signed-in user.
+#[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct FeatureFlagSettings(pub HashMap<String, bool>);
+
+impl settings::Settings for FeatureFlagSettings {
+    const KEY: Option<&'static str> = Some("feature_flags");
+
+    type FileContent = Self;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
 impl Client {
     pub fn new(http: Arc<ZedHttpClient>, cx: &mut AppContext) -> Arc<Self> {
         let client = Arc::new(Self {
@@ -1403,7 +1432,10 @@ async fn read_credentials_from_keychain(cx: &AsyncAppContext) -> Option<Credenti
     }
 
     let (user_id, access_token) = cx
-        .update(|cx| cx.read_credentials(&ClientSettings::get_global(cx).server_url))
+        .update(|cx| {
+            let server_url = ClientSettings::get_global(cx).server_url.clone();
+            read_secret(SecretScope::ZedAccount, &server_url, cx)
+        })
         .log_err()?
         .await
         .log_err()??;
@@ -1419,18 +1451,24 @@ async fn write_credentials_to_keychain(
     cx: &AsyncAppContext,
 ) -> Result<()> {
     cx.update(move |cx| {
-        cx.write_credentials(
-            &ClientSettings::get_global(cx).server_url,
+        let server_url = ClientSettings::get_global(cx).server_url.clone();
+        write_secret(
+            SecretScope::ZedAccount,
+            &server_url,
             &credentials.user_id.to_string(),
             credentials.access_token.as_bytes(),
+            cx,
         )
     })?
     .await
 }
 
 async fn delete_credentials_from_keychain(cx: &AsyncAppContext) -> Result<()> {
-    cx.update(move |cx| cx.delete_credentials(&ClientSettings::get_global(cx).server_url))?
-        .await
+    cx.update(move |cx| {
+        let server_url = ClientSettings::get_global(cx).server_url.clone();
+        delete_secret(SecretScope::ZedAccount, &server_url, cx)
+    })?
+    .await
 }
 
 const WORKTREE_URL_PREFIX: &str = "zed://worktrees/";
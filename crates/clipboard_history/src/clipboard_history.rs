@@ -0,0 +1,343 @@
+use editor::{
+    clipboard_history::{ClipboardHistory, ClipboardHistoryEntry},
+    Editor,
+};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AnyElement, AppContext, ClipboardItem, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, Subscription, Task, View, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use settings::Settings;
+use std::sync::Arc;
+use theme::ThemeSettings;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(edit, [PasteFromHistory]);
+actions!(clipboard_history, [TogglePinned]);
+
+const MAX_PREVIEW_LINES: usize = 8;
+const MAX_LABEL_LEN: usize = 120;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(ClipboardHistoryPicker::register)
+        .detach();
+}
+
+/// A picker listing the text recently copied or cut from editors, to paste into the
+/// active editor. Pinned entries are listed first and never expire.
+///
+/// A secondary confirmation marks entries, which are then pasted together, one after
+/// another in the order they were marked.
+pub struct ClipboardHistoryPicker {
+    picker: View<Picker<ClipboardHistoryDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ClipboardHistoryPicker {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::toggle);
+    }
+
+    fn toggle(workspace: &mut Workspace, _: &PasteFromHistory, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let delegate = ClipboardHistoryDelegate {
+            editor: editor.downgrade(),
+            entries: history_entries(cx),
+            matches: Vec::new(),
+            marked_entry_ids: Vec::new(),
+            selected_index: 0,
+        };
+        workspace.toggle_modal(cx, |cx| Self::new(delegate, cx));
+    }
+
+    fn new(delegate: ClipboardHistoryDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+
+    fn toggle_pinned(&mut self, _: &TogglePinned, cx: &mut ViewContext<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let delegate = &picker.delegate;
+            let Some(entry_id) = delegate
+                .matches
+                .get(delegate.selected_index)
+                .and_then(|hit| delegate.entries.get(hit.candidate_id))
+                .map(|entry| entry.id)
+            else {
+                return;
+            };
+            cx.default_global::<ClipboardHistory>()
+                .toggle_pinned(entry_id);
+            picker.delegate.entries = history_entries(cx);
+            picker.refresh(cx);
+        });
+    }
+}
+
+impl ModalView for ClipboardHistoryPicker {}
+impl EventEmitter<DismissEvent> for ClipboardHistoryPicker {}
+
+impl FocusableView for ClipboardHistoryPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ClipboardHistoryPicker {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ClipboardHistory")
+            .w(rems(34.))
+            .on_action(cx.listener(Self::toggle_pinned))
+            .child(self.picker.clone())
+    }
+}
+
+/// Returns the entries of the clipboard history, with the pinned ones first.
+fn history_entries(cx: &mut AppContext) -> Vec<ClipboardHistoryEntry> {
+    let mut entries = cx
+        .default_global::<ClipboardHistory>()
+        .entries()
+        .cloned()
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| !entry.pinned);
+    entries
+}
+
+pub struct ClipboardHistoryDelegate {
+    editor: WeakView<Editor>,
+    entries: Vec<ClipboardHistoryEntry>,
+    matches: Vec<StringMatch>,
+    marked_entry_ids: Vec<usize>,
+    selected_index: usize,
+}
+
+impl ClipboardHistoryDelegate {
+    fn selected_entry(&self) -> Option<&ClipboardHistoryEntry> {
+        self.matches
+            .get(self.selected_index)
+            .and_then(|hit| self.entries.get(hit.candidate_id))
+    }
+
+    /// The marked entries joined together, or the selected entry if none are marked.
+    fn item_to_paste(&self) -> Option<ClipboardItem> {
+        if self.marked_entry_ids.is_empty() {
+            return self.selected_entry().map(|entry| entry.item.clone());
+        }
+
+        let text = self
+            .marked_entry_ids
+            .iter()
+            .filter_map(|id| self.entries.iter().find(|entry| entry.id == *id))
+            .map(|entry| entry.item.text().as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(ClipboardItem::new(text))
+    }
+}
+
+impl PickerDelegate for ClipboardHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Search clipboard history...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| {
+                let string = entry_label(entry);
+                StringMatchCandidate {
+                    id: ix,
+                    char_bag: string.chars().collect(),
+                    string,
+                }
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if secondary {
+            let Some(entry_id) = self.selected_entry().map(|entry| entry.id) else {
+                return;
+            };
+            if let Some(ix) = self.marked_entry_ids.iter().position(|id| *id == entry_id) {
+                self.marked_entry_ids.remove(ix);
+            } else {
+                self.marked_entry_ids.push(entry_id);
+            }
+            cx.notify();
+            return;
+        }
+
+        let Some(item) = self.item_to_paste() else {
+            return;
+        };
+        self.editor
+            .update(cx, |editor, cx| editor.paste_item(&item, true, cx))
+            .log_err();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = &self.matches[ix];
+        let entry = &self.entries[hit.candidate_id];
+        let mark_position = self.marked_entry_ids.iter().position(|id| *id == entry.id);
+        let line_count = entry.item.text().lines().count();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(
+                    h_flex()
+                        .gap_2()
+                        .children(mark_position.map(|position| {
+                            Label::new(format!("{}", position + 1))
+                                .size(LabelSize::Small)
+                                .color(Color::Accent)
+                        }))
+                        .child(HighlightedLabel::new(
+                            hit.string.clone(),
+                            hit.positions.clone(),
+                        )),
+                )
+                .end_slot(
+                    h_flex()
+                        .gap_2()
+                        .when(line_count > 1, |this| {
+                            this.child(
+                                Label::new(format!("{line_count} lines"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(entry.pinned, |this| {
+                            this.child(
+                                Icon::new(IconName::Check)
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let entry = self.selected_entry()?;
+        let text = entry.item.text();
+        let mut preview = text
+            .lines()
+            .take(MAX_PREVIEW_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.lines().count() > MAX_PREVIEW_LINES {
+            preview.push_str("\n…");
+        }
+
+        Some(
+            v_flex()
+                .px_3()
+                .pb_2()
+                .gap_1()
+                .child(
+                    Label::new(if entry.pinned { "Pinned" } else { "Preview" })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    div()
+                        .font(ThemeSettings::get_global(cx).buffer_font.family.clone())
+                        .text_ui_sm()
+                        .child(preview),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+/// Shows an entry on a single line, with line breaks replaced by `⏎`.
+fn entry_label(entry: &ClipboardHistoryEntry) -> String {
+    let text = entry.item.text().trim();
+    let mut label = String::new();
+    for (ix, line) in text.lines().enumerate() {
+        if ix > 0 {
+            label.push_str(" ⏎ ");
+        }
+        label.push_str(line.trim());
+        if label.chars().count() > MAX_LABEL_LEN {
+            break;
+        }
+    }
+    if label.chars().count() > MAX_LABEL_LEN {
+        label = label.chars().take(MAX_LABEL_LEN).collect();
+        label.push('…');
+    }
+    label
+}
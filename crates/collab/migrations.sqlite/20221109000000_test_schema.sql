@@ -287,7 +287,8 @@ CREATE UNIQUE INDEX "index_channel_buffer_collaborators_on_channel_id_connection
 
 CREATE TABLE "feature_flags" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
-    "flag" TEXT NOT NULL UNIQUE
+    "flag" TEXT NOT NULL UNIQUE,
+    "rollout_percentage" INTEGER NOT NULL DEFAULT 0
 );
 
 CREATE INDEX "index_feature_flags" ON "feature_flags" ("id");
@@ -0,0 +1 @@
+ALTER TABLE "feature_flags" ADD COLUMN "rollout_percentage" INTEGER NOT NULL DEFAULT 0;
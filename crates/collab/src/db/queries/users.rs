@@ -269,7 +269,28 @@ impl Database {
         .await
     }
 
-    /// Returns the active flags for the user.
+    /// Rolls the flag out to the given percentage of users. Users keep the flag as the
+    /// percentage grows, because each of them is assigned a fixed bucket per flag.
+    pub async fn set_flag_rollout_percentage(
+        &self,
+        flag: FlagId,
+        rollout_percentage: i32,
+    ) -> Result<()> {
+        self.transaction(|tx| async move {
+            feature_flag::Entity::update_many()
+                .filter(feature_flag::Column::Id.eq(flag))
+                .set(feature_flag::ActiveModel {
+                    rollout_percentage: ActiveValue::set(rollout_percentage.clamp(0, 100)),
+                    ..Default::default()
+                })
+                .exec(&*tx)
+                .await?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Returns the active flags for the user, including the ones rolled out to them.
     pub async fn get_user_flags(&self, user: UserId) -> Result<Vec<String>> {
         self.transaction(|tx| async move {
             #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -277,7 +298,7 @@ impl Database {
                 Flag,
             }
 
-            let flags = user::Model {
+            let mut flags: Vec<String> = user::Model {
                 id: user,
                 ..Default::default()
             }
@@ -288,8 +309,33 @@ impl Database {
             .all(&*tx)
             .await?;
 
+            let rolled_out_flags = feature_flag::Entity::find()
+                .filter(feature_flag::Column::RolloutPercentage.gt(0))
+                .all(&*tx)
+                .await?;
+            for flag in rolled_out_flags {
+                if !flags.contains(&flag.flag)
+                    && rollout_bucket(user, &flag.flag) < flag.rollout_percentage
+                {
+                    flags.push(flag.flag);
+                }
+            }
+
             Ok(flags)
         })
         .await
     }
 }
+
+/// Assigns the user to one of a hundred buckets for the flag, with a hash that stays
+/// the same across server versions. Hashing the flag's name along with the user's id
+/// spreads each flag's rollout over a different set of users.
+pub(crate) fn rollout_bucket(user: UserId, flag: &str) -> i32 {
+    // FNV-1a
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in flag.bytes().chain(user.to_proto().to_le_bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    (hash % 100) as i32
+}
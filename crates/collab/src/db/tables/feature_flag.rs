@@ -8,6 +8,9 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: FlagId,
     pub flag: String,
+    /// The percentage of all users that have the flag, in addition to the ones it
+    /// was added to.
+    pub rollout_percentage: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
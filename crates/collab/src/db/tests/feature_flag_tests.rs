@@ -1,5 +1,5 @@
 use crate::{
-    db::{Database, NewUserParams},
+    db::{queries::users::rollout_bucket, Database, NewUserParams},
     test_both_dbs,
 };
 use std::sync::Arc;
@@ -56,3 +56,61 @@ async fn test_get_user_flags(db: &Arc<Database>) {
     user_2_flags.sort();
     assert_eq!(user_2_flags, &[CHANNELS_ALPHA]);
 }
+
+test_both_dbs!(
+    test_flag_rollout,
+    test_flag_rollout_postgres,
+    test_flag_rollout_sqlite
+);
+
+async fn test_flag_rollout(db: &Arc<Database>) {
+    let mut users = Vec::new();
+    for i in 1..=20 {
+        let user = db
+            .create_user(
+                &format!("user{i}@example.com"),
+                false,
+                NewUserParams {
+                    github_login: format!("user{i}"),
+                    github_user_id: i,
+                },
+            )
+            .await
+            .unwrap()
+            .user_id;
+        users.push(user);
+    }
+
+    const INLAY_HINTS: &'static str = "inlay-hints";
+    let flag = db.create_user_flag(INLAY_HINTS).await.unwrap();
+    db.add_user_flag(users[0], flag).await.unwrap();
+
+    let mut previous_users = vec![users[0]];
+    for percentage in [0, 30, 60, 100] {
+        db.set_flag_rollout_percentage(flag, percentage)
+            .await
+            .unwrap();
+        let mut flagged_users = Vec::new();
+        for user in &users {
+            let flags = db.get_user_flags(*user).await.unwrap();
+            if flags == [INLAY_HINTS] {
+                flagged_users.push(*user);
+            } else {
+                assert_eq!(flags, Vec::<String>::new());
+            }
+        }
+
+        for user in &users[1..] {
+            assert_eq!(
+                flagged_users.contains(user),
+                rollout_bucket(*user, INLAY_HINTS) < percentage
+            );
+        }
+        // Growing a rollout never takes the flag away from a user.
+        assert!(previous_users
+            .iter()
+            .all(|user| flagged_users.contains(user)));
+        previous_users = flagged_users;
+    }
+    assert_eq!(previous_users, users);
+}
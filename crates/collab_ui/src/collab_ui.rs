@@ -39,6 +39,10 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     chat_panel::init(cx);
     notification_panel::init(cx);
     notifications::init(&app_state, cx);
+
+    cx.on_action(toggle_mute);
+    cx.on_action(toggle_deafen);
+    cx.on_action(leave_call);
 }
 
 pub fn toggle_screen_sharing(_: &ToggleScreenSharing, cx: &mut WindowContext) {
@@ -91,6 +95,12 @@ pub fn toggle_deafen(_: &ToggleDeafen, cx: &mut AppContext) {
     }
 }
 
+pub fn leave_call(_: &LeaveCall, cx: &mut AppContext) {
+    ActiveCall::global(cx)
+        .update(cx, |call, cx| call.hang_up(cx))
+        .detach_and_log_err(cx);
+}
+
 fn notification_window_options(
     screen: Rc<dyn PlatformDisplay>,
     window_size: Size<Pixels>,
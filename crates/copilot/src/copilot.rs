@@ -1,7 +1,5 @@
 pub mod request;
 use anyhow::{anyhow, Context as _, Result};
-use async_compression::futures::bufread::GzipDecoder;
-use async_tar::Archive;
 use collections::{HashMap, HashSet};
 use futures::{channel::oneshot, future::Shared, Future, FutureExt, TryFutureExt};
 use gpui::{
@@ -18,7 +16,7 @@ use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
 use request::StatusNotification;
 use settings::SettingsStore;
-use smol::{fs, io::BufReader, stream::StreamExt};
+use smol::{fs, stream::StreamExt};
 use std::{
     any::TypeId,
     ffi::OsString,
@@ -28,8 +26,12 @@ use std::{
     sync::Arc,
 };
 use util::{
-    async_maybe, fs::remove_matching, github::latest_github_release, http::HttpClient, paths,
-    ResultExt,
+    archive::{self, ArchiveType},
+    async_maybe,
+    fs::remove_matching,
+    github::latest_github_release,
+    http::HttpClient,
+    paths, ResultExt,
 };
 
 // HACK: This type is only defined in `copilot` since it is the earliest ancestor
@@ -989,19 +991,20 @@ async fn get_copilot_lsp(http: Arc<dyn HttpClient>) -> anyhow::Result<PathBuf> {
             let dist_dir = version_dir.join("dist");
             fs::create_dir_all(dist_dir.as_path()).await?;
 
-            let url = &release
+            let asset = release
                 .assets
                 .get(0)
-                .context("Github release for copilot contained no assets")?
-                .browser_download_url;
-
-            let mut response = http
-                .get(url, Default::default(), true)
-                .await
-                .context("error downloading copilot release")?;
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
-            let archive = Archive::new(decompressed_bytes);
-            archive.unpack(dist_dir).await?;
+                .context("Github release for copilot contained no assets")?;
+
+            archive::download_archive(
+                &*http,
+                &asset.browser_download_url,
+                ArchiveType::TarGz,
+                asset.sha256(),
+                &dist_dir,
+            )
+            .await
+            .context("error downloading copilot release")?;
 
             remove_matching(&paths::COPILOT_DIR, |entry| entry != version_dir).await;
         }
@@ -4,6 +4,7 @@ mod toolbar_controls;
 
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
 use editor::{
     diagnostic_block_renderer,
     display_map::{BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock},
@@ -13,10 +14,10 @@ use editor::{
 };
 use futures::future::try_join_all;
 use gpui::{
-    actions, div, svg, AnyElement, AnyView, AppContext, Context, EventEmitter, FocusHandle,
-    FocusableView, HighlightStyle, InteractiveElement, IntoElement, Model, ParentElement, Render,
-    SharedString, Styled, StyledText, Subscription, Task, View, ViewContext, VisualContext,
-    WeakView, WindowContext,
+    actions, div, svg, AnyElement, AnyView, AppContext, AsyncAppContext, Context, EventEmitter,
+    FocusHandle, FocusableView, Global, HighlightStyle, InteractiveElement, IntoElement, Model,
+    ParentElement, Render, SharedString, Styled, StyledText, Subscription, Task, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
 };
 use language::{
     Anchor, Bias, Buffer, Diagnostic, DiagnosticEntry, DiagnosticSeverity, Point, Selection,
@@ -25,6 +26,7 @@ use language::{
 use lsp::LanguageServerId;
 use project::{DiagnosticSummary, Project, ProjectPath};
 use project_diagnostics_settings::ProjectDiagnosticsSettings;
+use serde::{Deserialize, Serialize};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -37,22 +39,100 @@ use std::{
 use theme::ActiveTheme;
 pub use toolbar_controls::ToolbarControls;
 use ui::{h_flex, prelude::*, Icon, IconName, Label};
-use util::TryFutureExt;
+use util::{ResultExt, TryFutureExt};
 use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
+    notifications::NotifyTaskExt,
     ItemNavHistory, Pane, ToolbarItemLocation, Workspace,
 };
 
-actions!(diagnostics, [Deploy, ToggleWarnings]);
+actions!(
+    diagnostics,
+    [
+        Deploy,
+        Refresh,
+        ToggleWarnings,
+        ToggleActiveFileOnly,
+        ToggleSavedOnly
+    ]
+);
 
 const CONTEXT_LINE_COUNT: u32 = 1;
+const DIAGNOSTICS_MODES_KEY: &str = "ProjectDiagnosticsModes";
 
 pub fn init(cx: &mut AppContext) {
     ProjectDiagnosticsSettings::register(cx);
+    cx.set_global(LastDiagnosticsModes(None));
+    cx.spawn(DiagnosticsModes::load).detach();
     cx.observe_new_views(ProjectDiagnosticsEditor::register)
         .detach();
 }
 
+/// The modes that diagnostics are shown in.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DiagnosticsModes {
+    pub include_warnings: bool,
+    pub active_file_only: bool,
+    pub saved_only: bool,
+}
+
+/// The modes last toggled in any diagnostics view, which new views and the status bar
+/// start from. They're kept in the key-value store rather than the settings, which only
+/// provide the defaults.
+struct LastDiagnosticsModes(Option<DiagnosticsModes>);
+
+impl Global for LastDiagnosticsModes {}
+
+impl DiagnosticsModes {
+    pub(crate) fn get(cx: &AppContext) -> Self {
+        cx.try_global::<LastDiagnosticsModes>()
+            .and_then(|modes| modes.0)
+            .unwrap_or_else(|| {
+                let settings = ProjectDiagnosticsSettings::get_global(cx);
+                Self {
+                    include_warnings: settings.include_warnings,
+                    active_file_only: settings.active_file_only,
+                    saved_only: settings.saved_only,
+                }
+            })
+    }
+
+    async fn load(mut cx: AsyncAppContext) {
+        let Some(modes) = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(DIAGNOSTICS_MODES_KEY) })
+            .await
+            .log_err()
+            .flatten()
+            .and_then(|modes| serde_json::from_str::<Self>(&modes).log_err())
+        else {
+            return;
+        };
+        cx.update(|cx| {
+            // Modes toggled while they were being read take precedence.
+            if cx.global::<LastDiagnosticsModes>().0.is_none() {
+                cx.set_global(LastDiagnosticsModes(Some(modes)));
+            }
+        })
+        .ok();
+    }
+
+    fn save(self, cx: &mut AppContext) {
+        cx.set_global(LastDiagnosticsModes(Some(self)));
+        let Some(modes) = serde_json::to_string(&self).log_err() else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                KEY_VALUE_STORE
+                    .write_kvp(DIAGNOSTICS_MODES_KEY.into(), modes)
+                    .await
+                    .log_err()
+            })
+            .detach();
+    }
+}
+
 struct ProjectDiagnosticsEditor {
     project: Model<Project>,
     workspace: WeakView<Workspace>,
@@ -64,6 +144,12 @@ struct ProjectDiagnosticsEditor {
     paths_to_update: HashMap<LanguageServerId, HashSet<ProjectPath>>,
     current_diagnostics: HashMap<LanguageServerId, HashSet<ProjectPath>>,
     include_warnings: bool,
+    active_file_only: bool,
+    saved_only: bool,
+    active_path: Option<ProjectPath>,
+    /// Paths whose buffers had unsaved changes when their diagnostics were updated in
+    /// `saved_only` mode, along with a subscription that updates them once they're saved.
+    paths_awaiting_save: HashMap<ProjectPath, Subscription>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -93,13 +179,18 @@ impl EventEmitter<EditorEvent> for ProjectDiagnosticsEditor {}
 impl Render for ProjectDiagnosticsEditor {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl Element {
         let child = if self.path_states.is_empty() {
+            let message = if self.active_file_only {
+                "No problems in active file"
+            } else {
+                "No problems in workspace"
+            };
             div()
                 .bg(cx.theme().colors().editor_background)
                 .flex()
                 .items_center()
                 .justify_center()
                 .size_full()
-                .child(Label::new("No problems in workspace"))
+                .child(Label::new(message))
         } else {
             div().size_full().child(self.editor.clone())
         };
@@ -108,6 +199,8 @@ impl Render for ProjectDiagnosticsEditor {
             .track_focus(&self.focus_handle)
             .size_full()
             .on_action(cx.listener(Self::toggle_warnings))
+            .on_action(cx.listener(Self::toggle_active_file_only))
+            .on_action(cx.listener(Self::toggle_saved_only))
             .child(child)
     }
 }
@@ -115,6 +208,7 @@ impl Render for ProjectDiagnosticsEditor {
 impl ProjectDiagnosticsEditor {
     fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
         workspace.register_action(Self::deploy);
+        workspace.register_action(Self::refresh);
     }
 
     fn new(
@@ -143,9 +237,17 @@ impl ProjectDiagnosticsEditor {
                         this.update_excerpts(Some(*language_server_id), cx);
                     }
                 }
+                project::Event::ActiveEntryChanged(Some(entry_id)) => {
+                    let active_path = this.project.read(cx).path_for_entry(*entry_id, cx);
+                    if active_path.is_some() && active_path != this.active_path {
+                        this.active_path = active_path;
+                        if this.active_file_only {
+                            this.recheck_all_paths(cx);
+                        }
+                    }
+                }
                 _ => {}
             });
-
         let focus_handle = cx.focus_handle();
 
         let focus_in_subscription =
@@ -173,6 +275,10 @@ impl ProjectDiagnosticsEditor {
 
         let project = project_handle.read(cx);
         let summary = project.diagnostic_summary(false, cx);
+        let active_path = project
+            .active_entry()
+            .and_then(|entry_id| project.path_for_entry(entry_id, cx));
+        let modes = DiagnosticsModes::get(cx);
         let mut this = Self {
             project: project_handle,
             summary,
@@ -182,7 +288,11 @@ impl ProjectDiagnosticsEditor {
             editor,
             path_states: Default::default(),
             paths_to_update: HashMap::default(),
-            include_warnings: ProjectDiagnosticsSettings::get_global(cx).include_warnings,
+            include_warnings: modes.include_warnings,
+            active_file_only: modes.active_file_only,
+            saved_only: modes.saved_only,
+            active_path,
+            paths_awaiting_save: HashMap::default(),
             current_diagnostics: HashMap::default(),
             _subscriptions: vec![
                 project_event_subscription,
@@ -206,8 +316,46 @@ impl ProjectDiagnosticsEditor {
         }
     }
 
+    /// Asks the language servers to check the whole project again, since they only
+    /// notice the files that change outside of Zed once they're opened.
+    fn refresh(workspace: &mut Workspace, _: &Refresh, cx: &mut ViewContext<Workspace>) {
+        workspace
+            .project()
+            .update(cx, |project, cx| project.refresh_diagnostics(cx))
+            .detach_and_notify_err(cx);
+    }
+
     fn toggle_warnings(&mut self, _: &ToggleWarnings, cx: &mut ViewContext<Self>) {
         self.include_warnings = !self.include_warnings;
+        self.modes_changed(cx);
+    }
+
+    fn toggle_active_file_only(&mut self, _: &ToggleActiveFileOnly, cx: &mut ViewContext<Self>) {
+        self.active_file_only = !self.active_file_only;
+        self.modes_changed(cx);
+    }
+
+    fn toggle_saved_only(&mut self, _: &ToggleSavedOnly, cx: &mut ViewContext<Self>) {
+        self.saved_only = !self.saved_only;
+        if !self.saved_only {
+            self.paths_awaiting_save.clear();
+        }
+        self.modes_changed(cx);
+    }
+
+    /// Remembers this view's modes for new diagnostics views and the status bar, and
+    /// rechecks the paths with the new modes.
+    fn modes_changed(&mut self, cx: &mut ViewContext<Self>) {
+        DiagnosticsModes {
+            include_warnings: self.include_warnings,
+            active_file_only: self.active_file_only,
+            saved_only: self.saved_only,
+        }
+        .save(cx);
+        self.recheck_all_paths(cx);
+    }
+
+    fn recheck_all_paths(&mut self, cx: &mut ViewContext<Self>) {
         self.paths_to_update = self.current_diagnostics.clone();
         self.update_excerpts(None, cx);
         cx.notify();
@@ -319,6 +467,29 @@ impl ProjectDiagnosticsEditor {
         .detach();
     }
 
+    fn update_when_saved(
+        &mut self,
+        path: ProjectPath,
+        buffer: Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.paths_awaiting_save.contains_key(&path) {
+            return;
+        }
+
+        let subscription = cx.subscribe(&buffer, {
+            let path = path.clone();
+            move |this, buffer, event, cx| {
+                if let language::Event::Saved = event {
+                    if this.paths_awaiting_save.remove(&path).is_some() {
+                        this.populate_excerpts(path.clone(), None, buffer, cx);
+                    }
+                }
+            }
+        });
+        self.paths_awaiting_save.insert(path, subscription);
+    }
+
     fn populate_excerpts(
         &mut self,
         path: ProjectPath,
@@ -326,6 +497,12 @@ impl ProjectDiagnosticsEditor {
         buffer: Model<Buffer>,
         cx: &mut ViewContext<Self>,
     ) {
+        if self.saved_only && buffer.read(cx).is_dirty() {
+            self.update_when_saved(path, buffer, cx);
+            return;
+        }
+
+        let include_path = !self.active_file_only || self.active_path.as_ref() == Some(&path);
         let was_empty = self.path_states.is_empty();
         let snapshot = buffer.read(cx).snapshot();
         let path_ix = match self.path_states.binary_search_by_key(&&path, |e| &e.path) {
@@ -369,7 +546,8 @@ impl ProjectDiagnosticsEditor {
                 .diagnostic_groups(language_server_id)
                 .into_iter()
                 .filter(|(_, group)| {
-                    group.entries[group.primary_ix].diagnostic.severity <= max_severity
+                    include_path
+                        && group.entries[group.primary_ix].diagnostic.severity <= max_severity
                 })
                 .peekable();
             loop {
@@ -892,6 +1070,7 @@ mod tests {
     use project::FakeFs;
     use serde_json::json;
     use settings::SettingsStore;
+    use std::path::Path;
     use unindent::Unindent as _;
 
     #[gpui::test]
@@ -1569,6 +1748,97 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_diagnostics_active_file_only(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<ProjectDiagnosticsSettings>(cx, |settings| {
+                    settings.active_file_only = Some(true);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/test",
+            json!({
+                "a.rs": "const A: i32 = 'a';",
+                "b.rs": "const B: i32 = 'b';",
+            }),
+        )
+        .await;
+
+        let server_id = LanguageServerId(0);
+        let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let workspace = window.root(cx).unwrap();
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let path_a = ProjectPath {
+            worktree_id,
+            path: Path::new("a.rs").into(),
+        };
+        let path_b = ProjectPath {
+            worktree_id,
+            path: Path::new("b.rs").into(),
+        };
+
+        project.update(cx, |project, cx| {
+            for path in ["/test/a.rs", "/test/b.rs"] {
+                project
+                    .update_diagnostic_entries(
+                        server_id,
+                        PathBuf::from(path),
+                        None,
+                        vec![DiagnosticEntry {
+                            range: Unclipped(PointUtf16::new(0, 15))
+                                ..Unclipped(PointUtf16::new(0, 18)),
+                            diagnostic: Diagnostic {
+                                message: "mismatched types".to_string(),
+                                severity: DiagnosticSeverity::ERROR,
+                                is_primary: true,
+                                group_id: 0,
+                                ..Default::default()
+                            },
+                        }],
+                        cx,
+                    )
+                    .unwrap();
+            }
+            project.set_active_path(Some(path_a.clone()), cx);
+        });
+
+        let view = window.build_view(cx, |cx| {
+            ProjectDiagnosticsEditor::new(project.clone(), workspace.downgrade(), cx)
+        });
+        cx.executor().run_until_parked();
+        view.update(cx, |view, _| {
+            let paths = view
+                .path_states
+                .iter()
+                .map(|state| state.path.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(paths, [path_a.clone()]);
+        });
+
+        // Activating another file shows its diagnostics instead.
+        project.update(cx, |project, cx| {
+            project.set_active_path(Some(path_b.clone()), cx);
+        });
+        cx.executor().run_until_parked();
+        view.update(cx, |view, _| {
+            let paths = view
+                .path_states
+                .iter()
+                .map(|state| state.path.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(paths, [path_b.clone()]);
+        });
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
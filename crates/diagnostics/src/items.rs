@@ -1,23 +1,28 @@
 use collections::HashSet;
-use editor::Editor;
+use editor::{Editor, EditorEvent};
 use gpui::{
-    rems, EventEmitter, IntoElement, ParentElement, Render, Styled, Subscription, View,
+    rems, EventEmitter, IntoElement, Model, ParentElement, Render, Styled, Subscription, View,
     ViewContext, WeakView,
 };
 use language::Diagnostic;
 use lsp::LanguageServerId;
+use project::{DiagnosticSummary, Project, ProjectPath};
+use settings::SettingsStore;
 use ui::{h_flex, prelude::*, Button, ButtonLike, Color, Icon, IconName, Label, Tooltip};
 use workspace::{item::ItemHandle, StatusItemView, ToolbarItemEvent, Workspace};
 
-use crate::{Deploy, ProjectDiagnosticsEditor};
+use crate::{Deploy, DiagnosticsModes, LastDiagnosticsModes, ProjectDiagnosticsEditor};
 
 pub struct DiagnosticIndicator {
-    summary: project::DiagnosticSummary,
+    summary: DiagnosticSummary,
+    project: Model<Project>,
+    active_path: Option<ProjectPath>,
     active_editor: Option<WeakView<Editor>>,
     workspace: WeakView<Workspace>,
     current_diagnostic: Option<Diagnostic>,
     in_progress_checks: HashSet<LanguageServerId>,
     _observe_active_editor: Option<Subscription>,
+    _active_editor_events: Option<Subscription>,
 }
 
 impl Render for DiagnosticIndicator {
@@ -121,22 +126,46 @@ impl DiagnosticIndicator {
 
             project::Event::DiskBasedDiagnosticsFinished { language_server_id }
             | project::Event::LanguageServerRemoved(language_server_id) => {
-                this.summary = project.read(cx).diagnostic_summary(false, cx);
+                this.update_summary(cx);
                 this.in_progress_checks.remove(language_server_id);
                 cx.notify();
             }
 
-            project::Event::DiagnosticsUpdated { .. } => {
-                this.summary = project.read(cx).diagnostic_summary(false, cx);
-                cx.notify();
+            project::Event::DiagnosticsUpdated { path, .. } => {
+                // In `saved_only` mode, the summary is updated once the buffer is saved.
+                let has_unsaved_changes = DiagnosticsModes::get(cx).saved_only
+                    && project
+                        .update(cx, |project, cx| project.get_open_buffer(path, cx))
+                        .map_or(false, |buffer| buffer.read(cx).is_dirty());
+                if !has_unsaved_changes {
+                    this.update_summary(cx);
+                }
+            }
+
+            project::Event::ActiveEntryChanged(Some(entry_id)) => {
+                let active_path = project.read(cx).path_for_entry(*entry_id, cx);
+                if active_path.is_some() && active_path != this.active_path {
+                    this.active_path = active_path;
+                    this.update_summary(cx);
+                }
             }
 
             _ => {}
         })
         .detach();
+        cx.observe_global::<SettingsStore>(Self::update_summary)
+            .detach();
+        cx.observe_global::<LastDiagnosticsModes>(Self::update_summary)
+            .detach();
 
-        Self {
-            summary: project.read(cx).diagnostic_summary(false, cx),
+        let active_path = project
+            .read(cx)
+            .active_entry()
+            .and_then(|entry_id| project.read(cx).path_for_entry(entry_id, cx));
+        let mut this = Self {
+            summary: DiagnosticSummary::default(),
+            project: project.clone(),
+            active_path,
             in_progress_checks: project
                 .read(cx)
                 .language_servers_running_disk_based_diagnostics()
@@ -145,6 +174,39 @@ impl DiagnosticIndicator {
             workspace: workspace.weak_handle(),
             current_diagnostic: None,
             _observe_active_editor: None,
+            _active_editor_events: None,
+        };
+        this.update_summary(cx);
+        this
+    }
+
+    /// Recomputes the diagnostic counts, limited to the active file and to errors
+    /// according to the last toggled diagnostics modes.
+    fn update_summary(&mut self, cx: &mut ViewContext<Self>) {
+        let modes = DiagnosticsModes::get(cx);
+        let project = self.project.read(cx);
+        let mut summary = if modes.active_file_only {
+            project
+                .diagnostic_summaries(false, cx)
+                .filter(|(path, _, _)| self.active_path.as_ref() == Some(path))
+                .fold(
+                    DiagnosticSummary::default(),
+                    |mut summary, (_, _, path_summary)| {
+                        summary.error_count += path_summary.error_count;
+                        summary.warning_count += path_summary.warning_count;
+                        summary
+                    },
+                )
+        } else {
+            project.diagnostic_summary(false, cx)
+        };
+        if !modes.include_warnings {
+            summary.warning_count = 0;
+        }
+
+        if summary != self.summary {
+            self.summary = summary;
+            cx.notify();
         }
     }
 
@@ -184,11 +246,18 @@ impl StatusItemView for DiagnosticIndicator {
         if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
             self.active_editor = Some(editor.downgrade());
             self._observe_active_editor = Some(cx.observe(&editor, Self::update));
+            self._active_editor_events =
+                Some(cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| {
+                    if event == &EditorEvent::Saved {
+                        this.update_summary(cx);
+                    }
+                }));
             self.update(editor, cx);
         } else {
             self.active_editor = None;
             self.current_diagnostic = None;
             self._observe_active_editor = None;
+            self._active_editor_events = None;
         }
         cx.notify();
     }
@@ -1,9 +1,11 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct ProjectDiagnosticsSettings {
     pub include_warnings: bool,
+    pub active_file_only: bool,
+    pub saved_only: bool,
 }
 
 /// Diagnostics configuration.
@@ -12,7 +14,16 @@ pub struct ProjectDiagnosticsSettingsContent {
     /// Whether to show warnings or not by default.
     ///
     /// Default: true
-    include_warnings: Option<bool>,
+    pub include_warnings: Option<bool>,
+    /// Whether to only show the diagnostics of the active file by default.
+    ///
+    /// Default: false
+    pub active_file_only: Option<bool>,
+    /// Whether to only update a file's diagnostics once it's saved by default,
+    /// hiding the transient errors reported while typing.
+    ///
+    /// Default: false
+    pub saved_only: Option<bool>,
 }
 
 impl settings::Settings for ProjectDiagnosticsSettings {
@@ -1,5 +1,5 @@
 use crate::ProjectDiagnosticsEditor;
-use gpui::{div, EventEmitter, ParentElement, Render, ViewContext, WeakView};
+use gpui::{EventEmitter, ParentElement, Render, View, ViewContext, WeakView};
 use ui::prelude::*;
 use ui::{IconButton, IconName, Tooltip};
 use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
@@ -10,30 +10,73 @@ pub struct ToolbarControls {
 
 impl Render for ToolbarControls {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let include_warnings = self
+        let (include_warnings, active_file_only, saved_only) = self
             .editor
             .as_ref()
             .and_then(|editor| editor.upgrade())
-            .map(|editor| editor.read(cx).include_warnings)
-            .unwrap_or(false);
+            .map(|editor| {
+                let editor = editor.read(cx);
+                (
+                    editor.include_warnings,
+                    editor.active_file_only,
+                    editor.saved_only,
+                )
+            })
+            .unwrap_or((false, false, false));
 
-        let tooltip = if include_warnings {
+        let warnings_tooltip = if include_warnings {
             "Exclude Warnings"
         } else {
             "Include Warnings"
         };
+        let active_file_tooltip = if active_file_only {
+            "Show All Files"
+        } else {
+            "Show Active File Only"
+        };
+        let saved_tooltip = if saved_only {
+            "Update Diagnostics While Typing"
+        } else {
+            "Update Diagnostics On Save"
+        };
 
-        div().child(
-            IconButton::new("toggle-warnings", IconName::ExclamationTriangle)
-                .tooltip(move |cx| Tooltip::text(tooltip, cx))
-                .on_click(cx.listener(|this, _, cx| {
-                    if let Some(editor) = this.editor.as_ref().and_then(|editor| editor.upgrade()) {
-                        editor.update(cx, |editor, cx| {
-                            editor.toggle_warnings(&Default::default(), cx);
-                        });
-                    }
-                })),
-        )
+        h_flex()
+            .gap_1()
+            .child(
+                IconButton::new("toggle-active-file-only", IconName::File)
+                    .selected(active_file_only)
+                    .tooltip(move |cx| Tooltip::text(active_file_tooltip, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(editor) = this.editor() {
+                            editor.update(cx, |editor, cx| {
+                                editor.toggle_active_file_only(&Default::default(), cx);
+                            });
+                        }
+                    })),
+            )
+            .child(
+                IconButton::new("toggle-saved-only", IconName::Check)
+                    .selected(saved_only)
+                    .tooltip(move |cx| Tooltip::text(saved_tooltip, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(editor) = this.editor() {
+                            editor.update(cx, |editor, cx| {
+                                editor.toggle_saved_only(&Default::default(), cx);
+                            });
+                        }
+                    })),
+            )
+            .child(
+                IconButton::new("toggle-warnings", IconName::ExclamationTriangle)
+                    .tooltip(move |cx| Tooltip::text(warnings_tooltip, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(editor) = this.editor() {
+                            editor.update(cx, |editor, cx| {
+                                editor.toggle_warnings(&Default::default(), cx);
+                            });
+                        }
+                    })),
+            )
     }
 }
 
@@ -62,4 +105,8 @@ impl ToolbarControls {
     pub fn new() -> Self {
         ToolbarControls { editor: None }
     }
+
+    fn editor(&self) -> Option<View<ProjectDiagnosticsEditor>> {
+        self.editor.as_ref()?.upgrade()
+    }
 }
@@ -121,6 +121,7 @@ gpui::actions!(
     [
         AddSelectionAbove,
         AddSelectionBelow,
+        ApplyQuickFixToAllExcerpts,
         Backspace,
         Cancel,
         ConfirmRename,
@@ -153,10 +154,13 @@ gpui::actions!(
         DisplayCursorNames,
         DuplicateLine,
         ExpandMacroRecursively,
+        ExtractComponent,
         FindAllReferences,
         Fold,
+        FoldJsonRecords,
         FoldSelectedRanges,
         Format,
+        FormatJsonSelection,
         GoToDefinition,
         GoToDefinitionSplit,
         GoToDiagnostic,
@@ -172,6 +176,7 @@ gpui::actions!(
         JoinLines,
         LineDown,
         LineUp,
+        MinifyJsonSelection,
         MoveDown,
         MoveLeft,
         MoveLineDown,
@@ -183,8 +188,10 @@ gpui::actions!(
         MoveToEnd,
         MoveToEndOfLine,
         MoveToEndOfParagraph,
+        MoveToNextJsonRecord,
         MoveToNextSubwordEnd,
         MoveToNextWordEnd,
+        MoveToPreviousJsonRecord,
         MoveToPreviousSubwordStart,
         MoveToPreviousWordStart,
         MoveToStartOfParagraph,
@@ -192,15 +199,19 @@ gpui::actions!(
         Newline,
         NewlineAbove,
         NewlineBelow,
+        NewlineWithoutComment,
         NextScreen,
         OpenExcerpts,
+        OpenGitBlameCommit,
         OpenPermalinkToLine,
         Outdent,
         PageDown,
         PageUp,
         Paste,
+        PasteWithoutFormatting,
         Redo,
         RedoSelection,
+        RemoveUnusedImports,
         Rename,
         RestartLanguageServer,
         RevealInFinder,
@@ -208,6 +219,8 @@ gpui::actions!(
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorTop,
+        ScrollPopoverDown,
+        ScrollPopoverUp,
         SelectAll,
         SelectAllMatches,
         SelectDown,
@@ -227,7 +240,11 @@ gpui::actions!(
         SelectUp,
         ShowCharacterPalette,
         ShowCompletions,
+        ShowSignatureHelp,
         ShuffleLines,
+        SignatureHelpNext,
+        SignatureHelpPrev,
+        SortImports,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
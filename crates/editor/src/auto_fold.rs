@@ -0,0 +1,363 @@
+use std::{ops::Range, time::Duration};
+
+use gpui::Task;
+use language::{
+    language_settings::{language_settings, AutoFoldSettings},
+    BufferSnapshot, Import, ToOffset,
+};
+use project::FoldingRange;
+use ui::prelude::*;
+use util::ResultExt;
+
+use crate::{imports::import_blocks, Editor, EditorMode};
+
+#[derive(Default)]
+pub(crate) struct AutoFoldState {
+    /// Whether the file was folded, which only happens once, so that the folds that
+    /// are opened stay open.
+    folded: bool,
+    /// Waits for a language server to start, so that its folding ranges are used,
+    /// before folding the file with the syntax tree anyway.
+    language_server_timeout: Option<Task<()>>,
+    language_server_timed_out: bool,
+    fold_task: Option<Task<()>>,
+}
+
+impl AutoFoldState {
+    /// Copies whether the file was folded from the editor being cloned, so that a
+    /// split doesn't fold it again and close the folds opened in the original.
+    pub(crate) fn clone_state(&mut self, other: &AutoFoldState) {
+        if other.folded {
+            *self = Self {
+                folded: true,
+                ..Default::default()
+            };
+        }
+    }
+}
+
+/// How long to wait for the file's language server to start before folding it
+/// without the server's folding ranges.
+const LANGUAGE_SERVER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Folds the imports and regions of the editor's file, as configured for its
+/// language, once the language is known, the file is parsed and its language
+/// server, if it has one, is running.
+pub(crate) fn auto_fold(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.auto_fold.folded || editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(buffer) = editor.buffer.read(cx).as_singleton() else {
+        return;
+    };
+    // The imports are found in the syntax tree when the language server doesn't
+    // report them, so the file is only folded once it's parsed.
+    let Some(language) = buffer.read(cx).language().cloned() else {
+        return;
+    };
+    if buffer.read(cx).is_parsing() {
+        return;
+    }
+
+    let snapshot = buffer.read(cx).snapshot();
+    let settings = language_settings(snapshot.language(), snapshot.file(), cx).auto_fold;
+    if !settings.imports && !settings.regions {
+        editor.auto_fold.folded = true;
+        return;
+    }
+
+    // Servers start after the file is opened, and the file is folded again when one
+    // is added, unless it takes too long or never starts.
+    let awaiting_language_server = {
+        let project = project.read(cx);
+        project.is_local()
+            && !language.lsp_adapters().is_empty()
+            && project
+                .language_servers_for_buffer(buffer.read(cx), cx)
+                .next()
+                .is_none()
+    };
+    if awaiting_language_server && !editor.auto_fold.language_server_timed_out {
+        if editor.auto_fold.language_server_timeout.is_none() {
+            editor.auto_fold.language_server_timeout =
+                Some(cx.spawn(|editor, mut cx| async move {
+                    cx.background_executor()
+                        .timer(LANGUAGE_SERVER_TIMEOUT)
+                        .await;
+                    editor
+                        .update(&mut cx, |editor, cx| {
+                            editor.auto_fold.language_server_timed_out = true;
+                            auto_fold(editor, cx);
+                        })
+                        .ok();
+                }));
+        }
+        return;
+    }
+    editor.auto_fold.folded = true;
+    editor.auto_fold.language_server_timeout = None;
+
+    let folding_ranges = project.update(cx, |project, cx| project.folding_ranges(&buffer, cx));
+    editor.auto_fold.fold_task = Some(cx.spawn(|editor, mut cx| async move {
+        let folding_ranges = folding_ranges.await.log_err().unwrap_or_default();
+        editor
+            .update(&mut cx, |editor, cx| {
+                let snapshot = buffer.read(cx).snapshot();
+                let ranges = auto_fold_ranges(&snapshot, &folding_ranges, settings);
+                editor.fold_ranges(ranges, false, cx);
+            })
+            .log_err();
+    }));
+}
+
+/// Returns the ranges to fold, preferring the folding ranges of the language server
+/// over the syntax tree and the `#region` comments for each kind that it reports.
+fn auto_fold_ranges(
+    snapshot: &BufferSnapshot,
+    folding_ranges: &[FoldingRange],
+    settings: AutoFoldSettings,
+) -> Vec<Range<usize>> {
+    let folding_ranges_of_kind = |kind: lsp::FoldingRangeKind| {
+        folding_ranges
+            .iter()
+            .filter(|range| range.kind.as_ref() == Some(&kind))
+            .map(|range| range.range.start.to_offset(snapshot)..range.range.end.to_offset(snapshot))
+            .collect::<Vec<_>>()
+    };
+
+    let mut ranges = Vec::new();
+    let text = snapshot.text();
+    if settings.imports {
+        let import_ranges = folding_ranges_of_kind(lsp::FoldingRangeKind::Imports);
+        if import_ranges.is_empty() {
+            ranges.extend(import_fold_ranges(&text, &snapshot.imports()));
+        } else {
+            ranges.extend(import_ranges);
+        }
+    }
+    if settings.regions {
+        let region_ranges = folding_ranges_of_kind(lsp::FoldingRangeKind::Region);
+        if region_ranges.is_empty() {
+            ranges.extend(region_fold_ranges(&text));
+        } else {
+            ranges.extend(region_ranges);
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+/// Returns a range for each block of imports that spans several lines, which keeps
+/// the first line visible.
+fn import_fold_ranges(text: &str, imports: &[Import]) -> Vec<Range<usize>> {
+    import_blocks(text, imports)
+        .into_iter()
+        .filter_map(|block| {
+            let start = line_end(text, imports[block.start].range.start);
+            let end = line_end(text, imports[block.end - 1].range.end);
+            (start < end).then_some(start..end)
+        })
+        .collect()
+}
+
+/// Returns a range for each `#region` comment and its matching `#endregion`, which
+/// keeps the line of the `#region` visible.
+fn region_fold_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut region_starts = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_end = offset + line.trim_end_matches(['\n', '\r']).len();
+        if is_marker_line(line, "#region") {
+            region_starts.push(line_end);
+        } else if is_marker_line(line, "#endregion") {
+            if let Some(start) = region_starts.pop() {
+                ranges.push(start..line_end);
+            }
+        }
+        offset += line.len();
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+/// Whether the line starts with the marker, after its comment delimiters, such as in
+/// `// #region Helpers` or `<!-- #endregion -->`.
+fn is_marker_line(line: &str, marker: &str) -> bool {
+    let Some(ix) = line.find(marker) else {
+        return false;
+    };
+    let after_marker = &line[ix + marker.len()..];
+    line[..ix].chars().all(|c| !c.is_alphanumeric())
+        && after_marker
+            .chars()
+            .next()
+            .map_or(true, |c| !c.is_alphanumeric())
+}
+
+fn line_end(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .find('\n')
+        .map_or(text.len(), |len| offset + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::build_editor_with_project};
+    use futures::StreamExt as _;
+    use gpui::{Context as _, TestAppContext};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use multi_buffer::MultiBuffer;
+    use project::{FakeFs, Project};
+    use std::sync::Arc;
+    use unindent::Unindent as _;
+
+    #[gpui::test]
+    async fn test_auto_fold_waits_for_language_server(cx: &mut TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.auto_fold = Some(AutoFoldSettings {
+                imports: false,
+                regions: true,
+            });
+        });
+
+        let mut language = Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        );
+        let mut fake_servers = language
+            .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }))
+            .await;
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file("/file.rs", "fn a() {\n    b();\n}\n".into())
+            .await;
+        let project = Project::test(fs, ["/file.rs".as_ref()], cx).await;
+        project.update(cx, |project, _| project.languages().add(Arc::new(language)));
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer("/file.rs", cx))
+            .await
+            .unwrap();
+
+        // The editor is opened before the server starts, and there are no regions in
+        // the syntax tree, so folding the file now would fold nothing.
+        let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+        let (editor, cx) =
+            cx.add_window_view(|cx| build_editor_with_project(project.clone(), buffer, cx));
+
+        let fake_server = fake_servers.next().await.unwrap();
+        let mut folding_requests = fake_server
+            .handle_request::<lsp::request::FoldingRangeRequest, _, _>(|_, _| async move {
+                Ok(Some(vec![lsp::FoldingRange {
+                    start_line: 0,
+                    start_character: None,
+                    end_line: 1,
+                    end_character: None,
+                    kind: Some(lsp::FoldingRangeKind::Region),
+                    collapsed_text: None,
+                }]))
+            });
+        folding_requests.next().await.unwrap();
+        cx.run_until_parked();
+
+        editor.update(cx, |editor, cx| {
+            assert_eq!(editor.display_text(cx), "fn a() {⋯\n}\n");
+        });
+
+        // Splits keep the folds that were opened after the file was folded.
+        let clone = editor.update(cx, |editor, cx| {
+            editor.unfold_ranges([0..editor.buffer.read(cx).len(cx)], true, false, cx);
+            cx.new_view(|cx| editor.clone(cx))
+        });
+        cx.run_until_parked();
+        clone.update(cx, |clone, cx| {
+            assert_eq!(clone.display_text(cx), "fn a() {\n    b();\n}\n");
+        });
+    }
+
+    fn folded(text: &str, ranges: Vec<Range<usize>>) -> String {
+        let mut text = text.to_string();
+        for range in ranges.into_iter().rev() {
+            text.replace_range(range, "⋯");
+        }
+        text
+    }
+
+    #[test]
+    fn test_import_fold_ranges() {
+        let text = r#"
+            import { a } from "a";
+            import { b } from "b";
+
+            import { c } from "c";
+            // A single import isn't folded.
+            import { d } from "d";
+
+            export const e = a + b + c + d;
+        "#
+        .unindent();
+        let imports = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|path| {
+                let statement = format!("import {{ {path} }} from \"{path}\";");
+                let start = text.find(&statement).unwrap();
+                Import {
+                    range: start..start + statement.len(),
+                    path: path.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            folded(&text, import_fold_ranges(&text, &imports)),
+            r#"
+            import { a } from "a";⋯
+            // A single import isn't folded.
+            import { d } from "d";
+
+            export const e = a + b + c + d;
+            "#
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn test_region_fold_ranges() {
+        let text = "
+            // #region Helpers
+            function a() {}
+            function b() {}
+            //#endregion
+            const regions = \"#region\";
+            <!-- #region -->
+            <p></p>
+            <!-- #endregion -->
+        "
+        .unindent();
+        assert_eq!(
+            folded(&text, region_fold_ranges(&text)),
+            "
+            // #region Helpers⋯
+            const regions = \"#region\";
+            <!-- #region -->⋯
+            "
+            .unindent()
+        );
+    }
+}
@@ -1,9 +1,11 @@
-use crate::EditorSettings;
+use crate::{editor_settings::CursorBlinkStyle, EditorSettings};
 use gpui::ModelContext;
 use settings::Settings;
 use settings::SettingsStore;
 use smol::Timer;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+const CURSOR_FADE_DURATION: Duration = Duration::from_millis(200);
 
 pub struct BlinkManager {
     blink_interval: Duration,
@@ -11,6 +13,8 @@ pub struct BlinkManager {
     blink_epoch: usize,
     blinking_paused: bool,
     visible: bool,
+    /// When the cursors last started fading in or out, if they were toggled by blinking.
+    fade_started_at: Option<Instant>,
     enabled: bool,
 }
 
@@ -28,6 +32,7 @@ impl BlinkManager {
             blink_epoch: 0,
             blinking_paused: false,
             visible: true,
+            fade_started_at: None,
             enabled: false,
         }
     }
@@ -60,6 +65,7 @@ impl BlinkManager {
         if EditorSettings::get_global(cx).cursor_blink {
             if epoch == self.blink_epoch && self.enabled && !self.blinking_paused {
                 self.visible = !self.visible;
+                self.fade_started_at = Some(Instant::now());
                 cx.notify();
 
                 let epoch = self.next_blink_epoch();
@@ -79,6 +85,7 @@ impl BlinkManager {
     }
 
     pub fn show_cursor(&mut self, cx: &mut ModelContext<'_, BlinkManager>) {
+        self.fade_started_at = None;
         if !self.visible {
             self.visible = true;
             cx.notify();
@@ -104,4 +111,21 @@ impl BlinkManager {
     pub fn visible(&self) -> bool {
         self.visible
     }
+
+    /// The opacity of the cursors, between 0 and 1. With the `phase` blink style, cursors
+    /// fade in and out instead of being toggled.
+    pub fn opacity(&self, style: CursorBlinkStyle) -> f32 {
+        let fade_progress = match (style, self.fade_started_at) {
+            (CursorBlinkStyle::Phase, Some(fade_started_at)) => {
+                (fade_started_at.elapsed().as_secs_f32() / CURSOR_FADE_DURATION.as_secs_f32())
+                    .min(1.)
+            }
+            _ => 1.,
+        };
+        if self.visible {
+            fade_progress
+        } else {
+            1. - fade_progress
+        }
+    }
 }
@@ -0,0 +1,73 @@
+use crate::EditorSettings;
+use gpui::{AppContext, ClipboardItem, Global};
+use settings::Settings;
+use std::collections::VecDeque;
+
+/// The text most recently copied or cut from editors, newest first, so that it can be
+/// pasted again after the clipboard has been overwritten.
+#[derive(Default)]
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardHistoryEntry>,
+    next_entry_id: usize,
+}
+
+impl Global for ClipboardHistory {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClipboardHistoryEntry {
+    pub id: usize,
+    pub item: ClipboardItem,
+    pub pinned: bool,
+}
+
+impl ClipboardHistory {
+    pub fn entries(&self) -> impl Iterator<Item = &ClipboardHistoryEntry> {
+        self.entries.iter()
+    }
+
+    /// Adds an item to the front of the history, dropping the oldest unpinned entries
+    /// beyond `max_entries`. Pinned entries are kept regardless of their age.
+    pub fn push(&mut self, item: ClipboardItem, max_entries: usize) {
+        // Copying the same text again moves its entry to the front.
+        let mut pinned = false;
+        if let Some(ix) = self
+            .entries
+            .iter()
+            .position(|entry| entry.item.text() == item.text())
+        {
+            pinned = self.entries.remove(ix).map_or(false, |entry| entry.pinned);
+        }
+
+        self.entries.push_front(ClipboardHistoryEntry {
+            id: self.next_entry_id,
+            item,
+            pinned,
+        });
+        self.next_entry_id += 1;
+
+        let mut unpinned_count = 0;
+        self.entries.retain(|entry| {
+            if entry.pinned {
+                true
+            } else {
+                unpinned_count += 1;
+                unpinned_count <= max_entries
+            }
+        });
+    }
+
+    pub fn toggle_pinned(&mut self, entry_id: usize) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == entry_id) {
+            entry.pinned = !entry.pinned;
+        }
+    }
+}
+
+pub(crate) fn record(item: ClipboardItem, contains_private_text: bool, cx: &mut AppContext) {
+    let settings = EditorSettings::get_global(cx).clipboard_history;
+    if settings.max_entries == 0 || (contains_private_text && settings.exclude_private_files) {
+        return;
+    }
+    cx.default_global::<ClipboardHistory>()
+        .push(item, settings.max_entries);
+}
@@ -0,0 +1,283 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Context as _, Result};
+use collections::BTreeMap;
+use gpui::{AnyElement, Task};
+use language::{CodeLens, Point, ToPoint};
+use serde::Deserialize;
+use settings::Settings;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::SpawnInTerminal;
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Editor, EditorMode, EditorSettings,
+};
+
+const CODE_LENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Commands that servers expect the client to implement for showing a list of locations,
+/// with `[uri, position, locations]` arguments.
+const SHOW_REFERENCES_COMMANDS: &[&str] = &[
+    "rust-analyzer.showReferences",
+    "editor.action.showReferences",
+];
+const RUST_ANALYZER_RUN_COMMAND: &str = "rust-analyzer.runSingle";
+/// Commands that need a debugger, which isn't available, so their lenses aren't shown.
+const DEBUG_COMMANDS: &[&str] = &["rust-analyzer.debugSingle"];
+
+#[derive(Default)]
+pub(crate) struct CodeLensState {
+    enabled: bool,
+    blocks: Vec<BlockId>,
+    refresh_task: Option<Task<()>>,
+}
+
+impl CodeLensState {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Re-queries the code lenses for the editor's buffer and renders them above the
+/// lines they belong to, replacing the previously displayed ones.
+pub(crate) fn refresh_code_lens(editor: &mut Editor, debounce: bool, cx: &mut ViewContext<Editor>) {
+    let enabled = editor.mode == EditorMode::Full && EditorSettings::get_global(cx).code_lens;
+    editor.code_lens.enabled = enabled;
+    let project = editor.project.clone().filter(|_| enabled);
+    let buffer = editor.buffer.read(cx).as_singleton();
+    let (Some(project), Some(buffer)) = (project, buffer) else {
+        editor.code_lens.refresh_task = None;
+        clear_code_lens(editor, cx);
+        return;
+    };
+
+    editor.code_lens.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(CODE_LENS_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let lenses = if let Ok(lenses) =
+            project.update(&mut cx, |project, cx| project.code_lens(&buffer, cx))
+        {
+            lenses.await.log_err()
+        } else {
+            None
+        };
+        let Some(lenses) = lenses else {
+            return;
+        };
+
+        editor
+            .update(&mut cx, |editor, cx| display_code_lens(editor, lenses, cx))
+            .log_err();
+    }));
+}
+
+fn clear_code_lens(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.code_lens.blocks.is_empty() {
+        return;
+    }
+    let blocks = editor.code_lens.blocks.drain(..).collect();
+    editor.remove_blocks(blocks, None, cx);
+}
+
+fn display_code_lens(editor: &mut Editor, lenses: Vec<CodeLens>, cx: &mut ViewContext<Editor>) {
+    clear_code_lens(editor, cx);
+
+    let multi_buffer = editor.buffer.read(cx).snapshot(cx);
+    let Some((excerpt_id, _, buffer)) = multi_buffer.as_singleton() else {
+        return;
+    };
+
+    let mut lenses_by_row = BTreeMap::<u32, Vec<CodeLens>>::default();
+    for lens in lenses {
+        let is_debug_lens = lens.lsp_lens.command.as_ref().map_or(false, |command| {
+            DEBUG_COMMANDS.contains(&command.command.as_str())
+        });
+        if is_debug_lens {
+            continue;
+        }
+        let row = lens.range.start.to_point(buffer).row;
+        lenses_by_row.entry(row).or_default().push(lens);
+    }
+
+    let blocks = lenses_by_row
+        .into_iter()
+        .map(|(row, lenses)| {
+            let indent = buffer.indent_size_for_line(row);
+            let position = buffer.anchor_before(Point::new(row, indent.len));
+            BlockProperties {
+                style: BlockStyle::Flex,
+                position: multi_buffer.anchor_in_excerpt(*excerpt_id, position),
+                height: 1,
+                render: Arc::new(move |cx: &mut BlockContext| render_code_lens(&lenses, cx)),
+                disposition: BlockDisposition::Above,
+            }
+        })
+        .collect::<Vec<_>>();
+    editor.code_lens.blocks = editor.insert_blocks(blocks, None, cx);
+}
+
+fn render_code_lens(lenses: &[CodeLens], cx: &mut BlockContext) -> AnyElement {
+    let editor = cx.view.downgrade();
+    let mut children = Vec::new();
+    for (ix, lens) in lenses.iter().enumerate() {
+        let Some(command) = lens.lsp_lens.command.as_ref() else {
+            continue;
+        };
+        if !children.is_empty() {
+            children.push(
+                Label::new("|")
+                    .size(LabelSize::Small)
+                    .color(Color::Disabled)
+                    .into_any_element(),
+            );
+        }
+        let editor = editor.clone();
+        let lens = lens.clone();
+        children.push(
+            Button::new(("code-lens", ix), command.title.clone())
+                .style(ButtonStyle::Transparent)
+                .label_size(LabelSize::Small)
+                .color(Color::Muted)
+                .on_click(move |_, cx| {
+                    editor
+                        .update(cx, |editor, cx| execute_code_lens(editor, lens.clone(), cx))
+                        .ok();
+                })
+                .into_any_element(),
+        );
+    }
+
+    h_flex()
+        .id(cx.block_id)
+        .pl(cx.anchor_x)
+        .gap_1()
+        .children(children)
+        .into_any_element()
+}
+
+fn execute_code_lens(editor: &mut Editor, lens: CodeLens, cx: &mut ViewContext<Editor>) {
+    let Some(command) = lens.lsp_lens.command.clone() else {
+        return;
+    };
+    let arguments = command.arguments.unwrap_or_default();
+
+    if SHOW_REFERENCES_COMMANDS.contains(&command.command.as_str()) {
+        show_references(editor, &lens, &command.title, arguments, cx).detach_and_log_err(cx);
+    } else if command.command == RUST_ANALYZER_RUN_COMMAND {
+        if let Some(action) = rust_analyzer_runnable(arguments).log_err() {
+            cx.dispatch_action(Box::new(action));
+        }
+    } else {
+        execute_server_command(editor, lens, command.title, cx);
+    }
+}
+
+fn show_references(
+    editor: &mut Editor,
+    lens: &CodeLens,
+    title: &str,
+    mut arguments: Vec<serde_json::Value>,
+    cx: &mut ViewContext<Editor>,
+) -> Task<Result<()>> {
+    let (Some(project), Some(workspace), Some(buffer)) = (
+        editor.project.clone(),
+        editor.workspace(),
+        editor.buffer.read(cx).as_singleton(),
+    ) else {
+        return Task::ready(Ok(()));
+    };
+    if arguments.len() < 3 {
+        return Task::ready(Err(anyhow!("missing locations in code lens arguments")));
+    }
+    let locations = match serde_json::from_value::<Vec<lsp::Location>>(arguments.swap_remove(2)) {
+        Ok(locations) => locations,
+        Err(error) => return Task::ready(Err(error.into())),
+    };
+
+    let replica_id = editor.replica_id(cx);
+    let workspace = workspace.downgrade();
+    let title = title.to_string();
+    let open_locations = project.update(cx, |project, cx| {
+        project.open_lsp_locations(&buffer, lens.server_id, locations, cx)
+    });
+    cx.spawn(|_, mut cx| async move {
+        let locations = open_locations.await?;
+        if locations.is_empty() {
+            return Ok(());
+        }
+        workspace.update(&mut cx, |workspace, cx| {
+            Editor::open_locations_in_multibuffer(
+                workspace, locations, replica_id, title, false, cx,
+            );
+        })
+    })
+}
+
+fn execute_server_command(
+    editor: &mut Editor,
+    lens: CodeLens,
+    title: String,
+    cx: &mut ViewContext<Editor>,
+) {
+    let (Some(project), Some(workspace), Some(buffer)) = (
+        editor.project.clone(),
+        editor.workspace(),
+        editor.buffer.read(cx).as_singleton(),
+    ) else {
+        return;
+    };
+    let execute = project.update(cx, |project, cx| {
+        project.execute_code_lens(buffer, lens, cx)
+    });
+    let workspace = workspace.downgrade();
+    cx.spawn(|editor, cx| async move {
+        let transaction = execute.await?;
+        Editor::open_project_transaction(&editor, workspace, transaction, title, cx).await
+    })
+    .detach_and_log_err(cx);
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RustAnalyzerRunnable {
+    label: String,
+    args: RustAnalyzerCargoRunnable,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RustAnalyzerCargoRunnable {
+    workspace_root: Option<PathBuf>,
+    #[serde(default)]
+    cargo_args: Vec<String>,
+    #[serde(default)]
+    cargo_extra_args: Vec<String>,
+    #[serde(default)]
+    executable_args: Vec<String>,
+}
+
+fn rust_analyzer_runnable(mut arguments: Vec<serde_json::Value>) -> Result<SpawnInTerminal> {
+    let runnable = arguments
+        .drain(..)
+        .next()
+        .context("missing runnable in code lens arguments")?;
+    let runnable = serde_json::from_value::<RustAnalyzerRunnable>(runnable)?;
+    let mut args = runnable.args.cargo_args;
+    args.extend(runnable.args.cargo_extra_args);
+    if !runnable.args.executable_args.is_empty() {
+        args.push("--".to_string());
+        args.extend(runnable.args.executable_args);
+    }
+    Ok(SpawnInTerminal {
+        label: runnable.label,
+        command: "cargo".to_string(),
+        args,
+        cwd: runnable.args.workspace_root,
+    })
+}
@@ -26,20 +26,24 @@ mod wrap_map;
 use crate::EditorStyle;
 use crate::{
     hover_links::InlayHighlight, movement::TextLayoutDetails, Anchor, AnchorRangeExt, InlayId,
-    MultiBuffer, MultiBufferSnapshot, ToOffset, ToPoint,
+    MultiBuffer, MultiBufferSnapshot, ToOffset, ToPoint, MAX_LINE_LEN,
 };
 pub use block_map::{BlockMap, BlockPoint};
 use collections::{BTreeMap, HashMap, HashSet};
 use fold_map::FoldMap;
-use gpui::{Font, HighlightStyle, Hsla, LineLayout, Model, ModelContext, Pixels, UnderlineStyle};
+use gpui::{
+    Font, HighlightStyle, Hsla, LineLayout, Model, ModelContext, Pixels, StrikethroughStyle,
+    UnderlineStyle,
+};
 use inlay_map::InlayMap;
 use language::{
-    language_settings::language_settings, OffsetUtf16, Point, Subscription as BufferSubscription,
+    language_settings::language_settings, Chunk, OffsetUtf16, Point,
+    Subscription as BufferSubscription,
 };
 use lsp::DiagnosticSeverity;
 use std::{any::TypeId, borrow::Cow, fmt::Debug, num::NonZeroU32, ops::Range, sync::Arc};
 use sum_tree::{Bias, TreeMap};
-use tab_map::TabMap;
+use tab_map::{TabMap, TabPoint};
 
 use wrap_map::WrapMap;
 
@@ -461,6 +465,11 @@ impl DisplaySnapshot {
         fold_point.to_inlay_point(&self.fold_snapshot)
     }
 
+    fn display_point_to_tab_point(&self, point: DisplayPoint) -> TabPoint {
+        let wrap_point = self.block_snapshot.to_wrap_point(point.0);
+        self.wrap_snapshot.to_tab_point(wrap_point)
+    }
+
     pub fn display_point_to_fold_point(&self, point: DisplayPoint, bias: Bias) -> FoldPoint {
         let block_point = point.0;
         let wrap_point = self.block_snapshot.to_wrap_point(block_point);
@@ -533,50 +542,38 @@ impl DisplaySnapshot {
             Some(editor_style.inlays_style),
             Some(editor_style.suggestions_style),
         )
-        .map(|chunk| {
-            let mut highlight_style = chunk
-                .syntax_highlight_id
-                .and_then(|id| id.style(&editor_style.syntax));
-
-            if let Some(chunk_highlight) = chunk.highlight_style {
-                if let Some(highlight_style) = highlight_style.as_mut() {
-                    highlight_style.highlight(chunk_highlight);
-                } else {
-                    highlight_style = Some(chunk_highlight);
-                }
-            }
-
-            let mut diagnostic_highlight = HighlightStyle::default();
-
-            if chunk.is_unnecessary {
-                diagnostic_highlight.fade_out = Some(UNNECESSARY_CODE_FADE);
-            }
-
-            if let Some(severity) = chunk.diagnostic_severity {
-                // Omit underlines for HINT/INFO diagnostics on 'unnecessary' code.
-                if severity <= DiagnosticSeverity::WARNING || !chunk.is_unnecessary {
-                    let diagnostic_color =
-                        super::diagnostic_style(severity, true, &editor_style.status);
-                    diagnostic_highlight.underline = Some(UnderlineStyle {
-                        color: Some(diagnostic_color),
-                        thickness: 1.0.into(),
-                        wavy: true,
-                    });
-                }
-            }
-
-            if let Some(highlight_style) = highlight_style.as_mut() {
-                highlight_style.highlight(diagnostic_highlight);
-            } else {
-                highlight_style = Some(diagnostic_highlight);
-            }
+        .map(|chunk| highlight_chunk(chunk, editor_style))
+    }
 
-            HighlightedChunk {
-                chunk: chunk.text,
-                style: highlight_style,
-                is_tab: chunk.is_tab,
-            }
-        })
+    /// Returns the highlighted chunks of the given columns of a display row, without
+    /// iterating over the rest of the row like [`Self::highlighted_chunks`] does, so
+    /// that part of a very long line can be laid out quickly. The start column must
+    /// be valid, while an end column inside of a character is moved to its start.
+    pub fn highlighted_chunks_in_row<'a>(
+        &'a self,
+        display_row: u32,
+        columns: Range<u32>,
+        language_aware: bool,
+        editor_style: &'a EditorStyle,
+    ) -> impl Iterator<Item = HighlightedChunk<'a>> {
+        let line_len = self.line_len(display_row);
+        let start = self.display_point_to_tab_point(DisplayPoint::new(display_row, columns.start));
+        let end = DisplayPoint::new(display_row, columns.end.min(line_len));
+        let end = self.display_point_to_tab_point(DisplayPoint(
+            self.block_snapshot.clip_point(end.0, Bias::Left),
+        ));
+        self.tab_snapshot
+            .chunks(
+                start..end,
+                language_aware,
+                Highlights {
+                    text_highlights: Some(&self.text_highlights),
+                    inlay_highlights: Some(&self.inlay_highlights),
+                    inlay_highlight_style: Some(editor_style.inlays_style),
+                    suggestion_highlight_style: Some(editor_style.suggestions_style),
+                },
+            )
+            .map(|chunk| highlight_chunk(chunk, editor_style))
     }
 
     pub fn layout_row(
@@ -594,8 +591,20 @@ impl DisplaySnapshot {
         let mut runs = Vec::new();
         let mut line = String::new();
 
-        let range = display_row..display_row + 1;
-        for chunk in self.highlighted_chunks(range, false, &editor_style) {
+        // Only lay out the start of very long rows, like the editor element does.
+        let chunks: Box<dyn Iterator<Item = HighlightedChunk> + '_> =
+            if self.line_len(display_row) as usize > MAX_LINE_LEN {
+                Box::new(self.highlighted_chunks_in_row(
+                    display_row,
+                    0..MAX_LINE_LEN as u32,
+                    false,
+                    &editor_style,
+                ))
+            } else {
+                let range = display_row..display_row + 1;
+                Box::new(self.highlighted_chunks(range, false, &editor_style))
+            };
+        for chunk in chunks {
             line.push_str(chunk.chunk);
 
             let text_style = if let Some(style) = chunk.style {
@@ -629,7 +638,17 @@ impl DisplaySnapshot {
         text_layout_details: &TextLayoutDetails,
     ) -> Pixels {
         let line = self.layout_row(display_point.row(), text_layout_details);
-        line.x_for_index(display_point.column() as usize)
+        let column = display_point.column() as usize;
+        let line_len = self.line_len(display_point.row()) as usize;
+        if column > line.len && line_len > line.len {
+            // Estimate the position of columns after the laid out start of a long row.
+            let overflow = column.min(line_len) - line.len;
+            let byte_advance =
+                estimated_byte_advance(line.width, line.len, em_advance(text_layout_details));
+            line.width + byte_advance * overflow as f32
+        } else {
+            line.x_for_index(column)
+        }
     }
 
     pub fn display_column_for_x(
@@ -639,7 +658,17 @@ impl DisplaySnapshot {
         details: &TextLayoutDetails,
     ) -> u32 {
         let layout_line = self.layout_row(display_row, details);
-        layout_line.closest_index_for_x(x) as u32
+        let line_len = self.line_len(display_row);
+        if x > layout_line.width && line_len as usize > layout_line.len {
+            let byte_advance =
+                estimated_byte_advance(layout_line.width, layout_line.len, em_advance(details));
+            let overflow = ((x - layout_line.width) / byte_advance).round() as u32;
+            let column = (layout_line.len as u32 + overflow).min(line_len);
+            self.clip_point(DisplayPoint::new(display_row, column), Bias::Left)
+                .column()
+        } else {
+            layout_line.closest_index_for_x(x) as u32
+        }
     }
 
     pub fn chars_at(
@@ -913,6 +942,85 @@ impl DisplaySnapshot {
     }
 }
 
+/// Returns the average width of the bytes of a laid out line, for estimating the positions
+/// of the columns of a long line that weren't laid out. Columns are bytes, so multibyte
+/// characters such as CJK or emoji would be estimated as too wide from the font's `m`.
+pub(crate) fn estimated_byte_advance(width: Pixels, len: usize, em_advance: Pixels) -> Pixels {
+    if len == 0 {
+        em_advance
+    } else {
+        width * (1. / len as f32)
+    }
+}
+
+fn em_advance(details: &TextLayoutDetails) -> Pixels {
+    let font_size = details
+        .editor_style
+        .text
+        .font_size
+        .to_pixels(details.rem_size);
+    let font_id = details
+        .text_system
+        .resolve_font(&details.editor_style.text.font());
+    details
+        .text_system
+        .advance(font_id, font_size, 'm')
+        .map_or(Pixels::ZERO, |advance| advance.width)
+}
+
+fn highlight_chunk<'a>(chunk: Chunk<'a>, editor_style: &EditorStyle) -> HighlightedChunk<'a> {
+    let mut highlight_style = chunk
+        .syntax_highlight_id
+        .and_then(|id| id.style(&editor_style.syntax));
+
+    if let Some(chunk_highlight) = chunk.highlight_style {
+        if let Some(highlight_style) = highlight_style.as_mut() {
+            highlight_style.highlight(chunk_highlight);
+        } else {
+            highlight_style = Some(chunk_highlight);
+        }
+    }
+
+    let mut diagnostic_highlight = HighlightStyle::default();
+
+    if chunk.is_unnecessary {
+        diagnostic_highlight.fade_out = Some(UNNECESSARY_CODE_FADE);
+    }
+
+    if chunk.is_deprecated {
+        diagnostic_highlight.strikethrough = Some(StrikethroughStyle {
+            thickness: 1.0.into(),
+            color: None,
+        });
+    }
+
+    if let Some(severity) = chunk.diagnostic_severity {
+        // Omit underlines for HINT/INFO diagnostics on 'unnecessary' or 'deprecated'
+        // code, which are already styled.
+        if severity <= DiagnosticSeverity::WARNING || !(chunk.is_unnecessary || chunk.is_deprecated)
+        {
+            let diagnostic_color = super::diagnostic_style(severity, true, &editor_style.status);
+            diagnostic_highlight.underline = Some(UnderlineStyle {
+                color: Some(diagnostic_color),
+                thickness: 1.0.into(),
+                wavy: true,
+            });
+        }
+    }
+
+    if let Some(highlight_style) = highlight_style.as_mut() {
+        highlight_style.highlight(diagnostic_highlight);
+    } else {
+        highlight_style = Some(diagnostic_highlight);
+    }
+
+    HighlightedChunk {
+        chunk: chunk.text,
+        style: highlight_style,
+        is_tab: chunk.is_tab,
+    }
+}
+
 #[derive(Copy, Clone, Default, Eq, Ord, PartialOrd, PartialEq)]
 pub struct DisplayPoint(BlockPoint);
 
@@ -1751,6 +1859,47 @@ pub mod tests {
         assert("aˇαˇ", cx);
     }
 
+    #[gpui::test]
+    async fn test_x_for_long_lines_with_multibyte_chars(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| init_test(cx, |_| {}));
+        let mut cx = EditorTestContext::new(cx).await;
+
+        // Only the start of these rows is laid out, so the positions past it are estimated.
+        // Every repetition of a row's unit takes the same number of columns: 3 for the CJK
+        // character, 4 for the emoji and 4 for the `a` followed by a tab.
+        let units = [("中", 3), ("😀", 4), ("a\t", 4)];
+        let text = units
+            .iter()
+            .map(|(unit, _)| unit.repeat(MAX_LINE_LEN))
+            .collect::<Vec<_>>()
+            .join("\n");
+        cx.set_state(&format!("ˇ{text}"));
+        cx.update_editor(|editor, cx| {
+            let details = editor.text_layout_details(cx);
+            let snapshot = editor.snapshot(cx).display_snapshot;
+            for (row, (_, unit_len)) in units.into_iter().enumerate() {
+                let row = row as u32;
+                let x_for_column = |column: u32| {
+                    snapshot.x_for_display_point(DisplayPoint::new(row, column), &details)
+                };
+                let unit_width = x_for_column(unit_len);
+                let line_len = snapshot.line_len(row);
+                assert!(line_len as usize > MAX_LINE_LEN);
+
+                for unit_count in [1, MAX_LINE_LEN as u32 / unit_len + 10, line_len / unit_len] {
+                    let column = unit_count * unit_len;
+                    let x = x_for_column(column);
+                    let expected_x = unit_width * unit_count as f32;
+                    assert!(
+                        (x - expected_x).0.abs() <= expected_x.0 * 0.01,
+                        "row {row}, column {column}: {x:?} instead of {expected_x:?}"
+                    );
+                    assert_eq!(snapshot.display_column_for_x(row, x, &details), column);
+                }
+            }
+        });
+    }
+
     #[gpui::test]
     fn test_tabs_with_multibyte_chars(cx: &mut gpui::AppContext) {
         init_test(cx, |_| {});
@@ -64,6 +64,14 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn color<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::Color(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -306,6 +314,7 @@ impl<'a> Iterator for InlayChunks<'a> {
                 let mut highlight_style = match inlay.id {
                     InlayId::Suggestion(_) => self.suggestion_highlight_style,
                     InlayId::Hint(_) => self.inlay_highlight_style,
+                    InlayId::Color(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
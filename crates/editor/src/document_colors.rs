@@ -0,0 +1,318 @@
+use std::{any::TypeId, mem, time::Duration};
+
+use collections::HashSet;
+use gpui::{HighlightStyle, Hsla, Rgba, Task};
+use project::DocumentColor;
+use settings::Settings;
+use ui::prelude::*;
+use util::{post_inc, ResultExt};
+
+use crate::{
+    display_map::Inlay, hover_links::InlayHighlight, Anchor, Editor, EditorMode, EditorSettings,
+    InlayId, ToOffset,
+};
+
+const DOCUMENT_COLORS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// The text of the inlay shown before each color, whose square is painted in
+/// that color.
+const SWATCH: &str = "■ ";
+
+enum DocumentColorSwatches {}
+
+#[derive(Default)]
+pub(crate) struct DocumentColorsState {
+    enabled: bool,
+    swatches: Vec<ColorSwatch>,
+    refresh_task: Option<Task<()>>,
+}
+
+struct ColorSwatch {
+    id: InlayId,
+    position: Anchor,
+    color: lsp::Color,
+}
+
+impl DocumentColorsState {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Re-queries the colors of the editor's buffer and shows a swatch before each of
+/// them, keeping the displayed swatches whose colors haven't changed.
+pub(crate) fn refresh_document_colors(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    let enabled = editor.mode == EditorMode::Full && EditorSettings::get_global(cx).document_colors;
+    editor.document_colors.enabled = enabled;
+    let project = editor.project.clone().filter(|_| enabled);
+    let buffer = editor.buffer.read(cx).as_singleton();
+    let (Some(project), Some(buffer)) = (project, buffer) else {
+        editor.document_colors.refresh_task = None;
+        clear_document_colors(editor, cx);
+        return;
+    };
+
+    editor.document_colors.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(DOCUMENT_COLORS_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let colors = if let Ok(colors) =
+            project.update(&mut cx, |project, cx| project.document_colors(&buffer, cx))
+        {
+            colors.await.log_err()
+        } else {
+            None
+        };
+        let Some(colors) = colors else {
+            return;
+        };
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                display_document_colors(editor, colors, cx)
+            })
+            .log_err();
+    }));
+}
+
+fn clear_document_colors(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.clear_highlights::<DocumentColorSwatches>(cx);
+    if editor.document_colors.swatches.is_empty() {
+        return;
+    }
+    let swatches = editor
+        .document_colors
+        .swatches
+        .drain(..)
+        .map(|swatch| swatch.id)
+        .collect();
+    editor.splice_inlay_hints(swatches, Vec::new(), cx);
+}
+
+fn display_document_colors(
+    editor: &mut Editor,
+    colors: Vec<DocumentColor>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let multi_buffer = editor.buffer.read(cx).snapshot(cx);
+    let Some((excerpt_id, _, _)) = multi_buffer.as_singleton() else {
+        clear_document_colors(editor, cx);
+        return;
+    };
+
+    let colors = colors
+        .into_iter()
+        .map(|color| {
+            let position = multi_buffer.anchor_in_excerpt(*excerpt_id, color.range.start);
+            let key = swatch_key(position.to_offset(&multi_buffer), &color.color);
+            (key, position, color.color)
+        })
+        .collect::<Vec<_>>();
+    let new_keys = colors
+        .iter()
+        .map(|(key, _, _)| *key)
+        .collect::<HashSet<_>>();
+
+    // Swatches that are still at the same offset with the same color are kept, so that
+    // a refresh after each edit only touches the colors that changed.
+    let mut displayed_keys = HashSet::default();
+    let mut swatches = Vec::new();
+    let mut to_remove = Vec::new();
+    for swatch in mem::take(&mut editor.document_colors.swatches) {
+        let key = swatch_key(swatch.position.to_offset(&multi_buffer), &swatch.color);
+        if new_keys.contains(&key) && displayed_keys.insert(key) {
+            swatches.push(swatch);
+        } else {
+            to_remove.push(swatch.id);
+        }
+    }
+
+    let mut to_insert = Vec::new();
+    for (key, position, color) in colors {
+        if displayed_keys.insert(key) {
+            let inlay = Inlay::color(post_inc(&mut editor.next_inlay_id), position, SWATCH);
+            swatches.push(ColorSwatch {
+                id: inlay.id,
+                position,
+                color,
+            });
+            to_insert.push(inlay);
+        }
+    }
+
+    if !to_remove.is_empty() || !to_insert.is_empty() {
+        editor.splice_inlay_hints(to_remove, to_insert, cx);
+    }
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.clear_highlights(TypeId::of::<DocumentColorSwatches>());
+        for swatch in &swatches {
+            display_map.highlight_inlays(
+                TypeId::of::<DocumentColorSwatches>(),
+                vec![InlayHighlight {
+                    inlay: swatch.id,
+                    inlay_position: swatch.position,
+                    range: 0..SWATCH.trim_end().len(),
+                }],
+                HighlightStyle {
+                    color: Some(lsp_color_to_hsla(swatch.color)),
+                    ..Default::default()
+                },
+            );
+        }
+    });
+    editor.document_colors.swatches = swatches;
+}
+
+fn swatch_key(offset: usize, color: &lsp::Color) -> (usize, [u32; 4]) {
+    (
+        offset,
+        [color.red, color.green, color.blue, color.alpha].map(f32::to_bits),
+    )
+}
+
+fn lsp_color_to_hsla(color: lsp::Color) -> Hsla {
+    Rgba {
+        r: color.red,
+        g: color.green,
+        b: color.blue,
+        a: color.alpha,
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use indoc::indoc;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[gpui::test]
+    async fn test_document_color_swatches(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                color_provider: Some(lsp::ColorProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {r#"
+            let a = "#ff0000";ˇ
+            let b = "#0000ff";
+        "#});
+
+        let red = lsp::Color {
+            red: 1.,
+            green: 0.,
+            blue: 0.,
+            alpha: 1.,
+        };
+        let blue = lsp::Color {
+            red: 0.,
+            green: 0.,
+            blue: 1.,
+            alpha: 1.,
+        };
+        let green = lsp::Color {
+            red: 0.,
+            green: 1.,
+            blue: 0.,
+            alpha: 1.,
+        };
+        let color_at = |row, color| lsp::ColorInformation {
+            range: lsp::Range::new(lsp::Position::new(row, 9), lsp::Position::new(row, 16)),
+            color,
+        };
+
+        let colors = Arc::new(Mutex::new(vec![color_at(0, red), color_at(1, blue)]));
+        cx.handle_request::<lsp::request::DocumentColor, _, _>({
+            let colors = colors.clone();
+            move |_, _, _| {
+                let colors = colors.lock().clone();
+                async move { Ok(colors) }
+            }
+        });
+
+        cx.update_editor(|editor, cx| refresh_document_colors(editor, false, cx));
+        cx.run_until_parked();
+        let displayed = cx.update_editor(|editor, cx| {
+            assert_eq!(
+                editor.display_text(cx),
+                indoc! {r#"
+                    let a = "■ #ff0000";
+                    let b = "■ #0000ff";
+                "#}
+            );
+            displayed_swatches(editor, cx)
+        });
+        assert_eq!(displayed.len(), 2);
+        assert_eq!(displayed[0].1, lsp_color_to_hsla(red));
+        assert_eq!(displayed[1].1, lsp_color_to_hsla(blue));
+
+        // Only the swatch whose color changed is replaced.
+        *colors.lock() = vec![color_at(0, red), color_at(1, green)];
+        cx.update_editor(|editor, cx| refresh_document_colors(editor, false, cx));
+        cx.run_until_parked();
+        let refreshed = cx.update_editor(|editor, cx| {
+            assert_eq!(editor.document_colors.swatches.len(), 2);
+            displayed_swatches(editor, cx)
+        });
+        assert_eq!(refreshed.len(), 2);
+        assert_eq!(refreshed[0], displayed[0]);
+        assert_ne!(refreshed[1].0, displayed[1].0);
+        assert_eq!(refreshed[1].1, lsp_color_to_hsla(green));
+
+        *colors.lock() = Vec::new();
+        cx.update_editor(|editor, cx| refresh_document_colors(editor, false, cx));
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| {
+            assert_eq!(
+                editor.display_text(cx),
+                indoc! {r#"
+                    let a = "#ff0000";
+                    let b = "#0000ff";
+                "#}
+            );
+            assert!(displayed_swatches(editor, cx).is_empty());
+        });
+    }
+
+    /// Returns the swatch inlays in the editor, in buffer order, with the color that
+    /// each one is highlighted in.
+    fn displayed_swatches(editor: &Editor, cx: &ViewContext<Editor>) -> Vec<(InlayId, Hsla)> {
+        let display_map = editor.display_map.read(cx);
+        let highlights = display_map.inlay_highlights::<DocumentColorSwatches>();
+        let multi_buffer = editor.buffer.read(cx).snapshot(cx);
+        let mut swatches = display_map
+            .current_inlays()
+            .filter(|inlay| matches!(inlay.id, InlayId::Color(_)))
+            .map(|inlay| {
+                let (style, _) = highlights
+                    .and_then(|highlights| highlights.get(&inlay.id))
+                    .expect("swatch is not highlighted");
+                (
+                    inlay.position.to_offset(&multi_buffer),
+                    inlay.id,
+                    style.color.unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        swatches.sort_by_key(|(offset, _, _)| *offset);
+        swatches
+            .into_iter()
+            .map(|(_, id, color)| (id, color))
+            .collect()
+    }
+}
@@ -13,10 +13,15 @@
 //!
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides it's behaviour.
 pub mod actions;
+mod auto_fold;
 mod blink_manager;
+pub mod clipboard_history;
+mod code_lens;
 pub mod display_map;
+mod document_colors;
 mod editor_settings;
 mod element;
+pub mod external_decorations;
 mod inlay_hint_cache;
 
 mod debounced_delay;
@@ -24,13 +29,18 @@ mod git;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
+mod imports;
 pub mod items;
+mod json_tools;
+mod jsx_tools;
+mod linked_editing_ranges;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod signature_help;
 
 #[cfg(test)]
 mod editor_tests;
@@ -40,32 +50,45 @@ use ::git::diff::DiffHunk;
 pub(crate) use actions::*;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Context as _, Result};
+use auto_fold::{auto_fold, AutoFoldState};
 use blink_manager::BlinkManager;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
+use code_lens::{refresh_code_lens, CodeLensState};
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use copilot::Copilot;
 use debounced_delay::DebouncedDelay;
 pub use display_map::DisplayPoint;
 use display_map::*;
+use document_colors::{refresh_document_colors, DocumentColorsState};
 pub use editor_settings::EditorSettings;
 use element::LineWithInvisibles;
 pub use element::{Cursor, EditorElement, HighlightedRange, HighlightedRangeLine};
-use futures::FutureExt;
+use external_decorations::{refresh_external_decorations, ExternalDecorationState};
+use futures::{FutureExt, StreamExt};
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::diff_hunk_to_display;
+pub use git::commit_view::CommitView;
+use git::{
+    commit_message::{
+        autocorrect_commit_message, refresh_commit_message, refresh_commit_message_lints,
+        CommitMessageState,
+    },
+    diff_hunk_to_display,
+    inline_blame::{blame_row, refresh_inline_blame, InlineBlameState},
+};
 use gpui::{
     div, impl_actions, point, prelude::*, px, relative, rems, size, uniform_list, Action,
     AnyElement, AppContext, AsyncWindowContext, BackgroundExecutor, Bounds, ClipboardItem, Context,
     DispatchPhase, ElementId, EventEmitter, FocusHandle, FocusableView, FontId, FontStyle,
     FontWeight, HighlightStyle, Hsla, InteractiveText, KeyContext, Model, MouseButton,
-    ParentElement, Pixels, Render, SharedString, Styled, StyledText, Subscription, Task, TextStyle,
-    UnderlineStyle, UniformListScrollHandle, View, ViewContext, ViewInputHandler, VisualContext,
-    WeakView, WhiteSpace, WindowContext,
+    ParentElement, Pixels, Render, ScrollHandle, SharedString, StrikethroughStyle, Styled,
+    StyledText, Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View,
+    ViewContext, ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
+use imports::sort_imports_by_syntax;
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
 pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
@@ -74,10 +97,14 @@ use language::{
     language_settings::{self, all_language_settings, InlayHintSettings},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CodeAction,
     CodeLabel, Completion, CursorShape, Diagnostic, Documentation, IndentKind, IndentSize,
-    Language, OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
+    Language, LanguageScope, LineEnding, OffsetRangeExt, Point, Selection, SelectionGoal,
+    TransactionId,
 };
 
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
+use linked_editing_ranges::{
+    linked_edits, refresh_linked_editing_ranges, LinkedEditingRangesState,
+};
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use mouse_context_menu::MouseContextMenu;
 use movement::TextLayoutDetails;
@@ -88,13 +115,19 @@ pub use multi_buffer::{
 };
 use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
-use project::{FormatTrigger, Location, Project, ProjectPath, ProjectTransaction};
+use project::{
+    repository::{GitRepository, RepoPath},
+    FormatTrigger, Location, Project, ProjectPath, ProjectTransaction,
+};
 use rand::prelude::*;
 use rpc::proto::*;
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
+use signature_help::{
+    hide_signature_help, refresh_signature_help, signature_help_on_input, SignatureHelpState,
+};
 use smallvec::SmallVec;
 use snippet::Snippet;
 use std::{
@@ -121,7 +154,10 @@ use ui::{
 };
 use util::{maybe, post_inc, RangeExt, ResultExt, TryFutureExt};
 use workspace::Toast;
-use workspace::{searchable::SearchEvent, ItemNavHistory, Pane, SplitDirection, ViewId, Workspace};
+use workspace::{
+    notifications::NotifyTaskExt, searchable::SearchEvent, ItemNavHistory, Pane, SplitDirection,
+    ViewId, Workspace,
+};
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LINE_LEN: usize = 1024;
@@ -136,6 +172,23 @@ pub const DOCUMENT_HIGHLIGHTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis
 
 pub(crate) const FORMAT_TIMEOUT: Duration = Duration::from_secs(2);
 
+const OPEN_COMMIT_FAILURE_TOAST_ID: usize = 0x2b1a3e7c;
+
+/// Servers suffix source action kinds with their language, such as tsserver's
+/// `source.removeUnusedImports.ts`.
+const REMOVE_UNUSED_IMPORTS_KIND: &str = "source.removeUnusedImports";
+const ORGANIZE_IMPORTS_KIND: &str = "source.organizeImports";
+/// How many code action requests are sent at once when applying a quick fix to all
+/// excerpts.
+const MAX_CONCURRENT_QUICK_FIX_REQUESTS: usize = 8;
+/// How far back from the cursor an opening tag is looked for when closing it.
+const MAX_TAG_LEN: usize = 1024;
+/// The HTML elements that can't have contents, and so don't have closing tags.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
 pub fn render_parsed_markdown(
     element_id: impl Into<ElementId>,
     parsed: &language::ParsedMarkdown,
@@ -203,6 +256,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Color(usize),
 }
 
 impl InlayId {
@@ -210,6 +264,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Color(id) => *id,
         }
     }
 }
@@ -390,6 +445,13 @@ pub struct Editor {
     completion_documentation_pre_resolve_debounce: DebouncedDelay,
     available_code_actions: Option<(Model<Buffer>, Arc<[CodeAction]>)>,
     code_actions_task: Option<Task<()>>,
+    code_lens: CodeLensState,
+    document_colors: DocumentColorsState,
+    auto_fold: AutoFoldState,
+    linked_editing_ranges: LinkedEditingRangesState,
+    external_decorations: ExternalDecorationState,
+    commit_message: CommitMessageState,
+    inline_blame: InlineBlameState,
     document_highlights_task: Option<Task<()>>,
     pending_rename: Option<RenameState>,
     searchable: bool,
@@ -400,9 +462,11 @@ pub struct Editor {
     keymap_context_layers: BTreeMap<TypeId, KeyContext>,
     input_enabled: bool,
     read_only: bool,
+    masked: bool,
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
+    signature_help_state: SignatureHelpState,
     gutter_hovered: bool,
     hovered_link_state: Option<HoveredLinkState>,
     copilot_state: CopilotState,
@@ -712,6 +776,7 @@ struct CompletionsMenu {
     matches: Arc<[StringMatch]>,
     selected_item: usize,
     scroll_handle: UniformListScrollHandle,
+    documentation_scroll_handle: ScrollHandle,
     selected_completion_documentation_resolve_debounce: Arc<Mutex<DebouncedDelay>>,
 }
 
@@ -719,6 +784,8 @@ impl CompletionsMenu {
     fn select_first(&mut self, project: Option<&Model<Project>>, cx: &mut ViewContext<Editor>) {
         self.selected_item = 0;
         self.scroll_handle.scroll_to_item(self.selected_item);
+        self.documentation_scroll_handle
+            .set_offset(Default::default());
         self.attempt_resolve_selected_completion_documentation(project, cx);
         cx.notify();
     }
@@ -730,6 +797,8 @@ impl CompletionsMenu {
             self.selected_item = self.matches.len() - 1;
         }
         self.scroll_handle.scroll_to_item(self.selected_item);
+        self.documentation_scroll_handle
+            .set_offset(Default::default());
         self.attempt_resolve_selected_completion_documentation(project, cx);
         cx.notify();
     }
@@ -741,6 +810,8 @@ impl CompletionsMenu {
             self.selected_item = 0;
         }
         self.scroll_handle.scroll_to_item(self.selected_item);
+        self.documentation_scroll_handle
+            .set_offset(Default::default());
         self.attempt_resolve_selected_completion_documentation(project, cx);
         cx.notify();
     }
@@ -748,6 +819,8 @@ impl CompletionsMenu {
     fn select_last(&mut self, project: Option<&Model<Project>>, cx: &mut ViewContext<Editor>) {
         self.selected_item = self.matches.len() - 1;
         self.scroll_handle.scroll_to_item(self.selected_item);
+        self.documentation_scroll_handle
+            .set_offset(Default::default());
         self.attempt_resolve_selected_completion_documentation(project, cx);
         cx.notify();
     }
@@ -872,6 +945,7 @@ impl CompletionsMenu {
             };
             multiline_docs.map(|div| {
                 div.id("multiline_docs")
+                    .track_scroll(&self.documentation_scroll_handle)
                     .max_h(max_height)
                     .flex_1()
                     .px_1p5()
@@ -910,8 +984,22 @@ impl CompletionsMenu {
                             &None
                         };
 
+                        let deprecated_highlight = completion.is_deprecated().then(|| {
+                            (
+                                0..completion.label.text.len(),
+                                HighlightStyle {
+                                    strikethrough: Some(StrikethroughStyle {
+                                        thickness: 1.0.into(),
+                                        color: None,
+                                    }),
+                                    ..Default::default()
+                                },
+                            )
+                        });
                         let highlights = gpui::combine_highlights(
-                            mat.ranges().map(|range| (range, FontWeight::BOLD.into())),
+                            mat.ranges()
+                                .map(|range| (range, FontWeight::BOLD.into()))
+                                .chain(deprecated_highlight),
                             styled_runs_for_code_label(&completion.label, &style.syntax).map(
                                 |(range, mut highlight)| {
                                     // Ignore font weight for syntax highlighting, as we'll use it
@@ -1130,7 +1218,7 @@ impl CodeActionsMenu {
                                             },
                                             cx,
                                         )
-                                        .map(|task| task.detach_and_log_err(cx));
+                                        .map(|task| task.detach_and_notify_err(cx));
                                 }),
                             )
                             // TASK: It would be good to make lsp_action.title a SharedString to avoid allocating here.
@@ -1379,7 +1467,11 @@ impl Editor {
         });
         clone.selections.clone_state(&self.selections);
         clone.scroll_manager.clone_state(&self.scroll_manager);
+        clone.auto_fold.clone_state(&self.auto_fold);
         clone.searchable = self.searchable;
+        clone.read_only = self.read_only;
+        clone.soft_wrap_mode_override = self.soft_wrap_mode_override;
+        clone.show_wrap_guides = self.show_wrap_guides;
         clone
     }
 
@@ -1410,11 +1502,25 @@ impl Editor {
                         cx.emit(EditorEvent::TitleChanged);
                     }));
                 }
-                project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
-                    };
-                }));
+                project_subscriptions.push(cx.subscribe(
+                    project,
+                    |editor, _, event, cx| match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::LanguageServerAdded(_) => {
+                            refresh_code_lens(editor, true, cx);
+                            refresh_document_colors(editor, true, cx);
+                            auto_fold(editor, cx);
+                        }
+                        project::Event::DiskBasedDiagnosticsFinished { .. } => {
+                            refresh_code_lens(editor, true, cx);
+                            refresh_document_colors(editor, true, cx);
+                        }
+                        _ => {}
+                    },
+                ));
             }
         }
 
@@ -1468,6 +1574,13 @@ impl Editor {
             next_inlay_id: 0,
             available_code_actions: Default::default(),
             code_actions_task: Default::default(),
+            code_lens: Default::default(),
+            document_colors: Default::default(),
+            auto_fold: Default::default(),
+            linked_editing_ranges: Default::default(),
+            external_decorations: Default::default(),
+            commit_message: Default::default(),
+            inline_blame: Default::default(),
             document_highlights_task: Default::default(),
             pending_rename: Default::default(),
             searchable: true,
@@ -1478,10 +1591,12 @@ impl Editor {
             keymap_context_layers: Default::default(),
             input_enabled: true,
             read_only: false,
+            masked: false,
             use_autoclose: true,
             leader_peer_id: None,
             remote_id: None,
             hover_state: Default::default(),
+            signature_help_state: Default::default(),
             hovered_link_state: Default::default(),
             copilot_state: Default::default(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
@@ -1500,6 +1615,9 @@ impl Editor {
                 cx.observe(&display_map, Self::on_display_map_changed),
                 cx.observe(&blink_manager, |_, _, cx| cx.notify()),
                 cx.observe_global::<SettingsStore>(Self::settings_changed),
+                cx.observe_global::<external_decorations::ExternalDecorations>(
+                    refresh_external_decorations,
+                ),
                 observe_buffer_font_size_adjustment(cx, |_, cx| cx.notify()),
                 cx.observe_window_activation(|editor, cx| {
                     let active = cx.is_window_active();
@@ -1519,6 +1637,12 @@ impl Editor {
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
+        refresh_code_lens(&mut this, false, cx);
+        refresh_document_colors(&mut this, false, cx);
+        refresh_external_decorations(&mut this, cx);
+        auto_fold(&mut this, cx);
+        refresh_commit_message(&mut this, cx);
+        refresh_inline_blame(&mut this, cx);
 
         if mode == EditorMode::Full {
             let should_auto_hide_scrollbars = cx.should_auto_hide_scrollbars();
@@ -1545,7 +1669,13 @@ impl Editor {
             match self.context_menu.read().as_ref() {
                 Some(ContextMenu::Completions(_)) => {
                     key_context.add("menu");
-                    key_context.add("showing_completions")
+                    key_context.add("showing_completions");
+                    key_context.set(
+                        "accept_completion_on",
+                        EditorSettings::get_global(cx)
+                            .accept_completion_on
+                            .key_context_value(),
+                    );
                 }
                 Some(ContextMenu::CodeActions(_)) => {
                     key_context.add("menu");
@@ -1554,6 +1684,12 @@ impl Editor {
                 None => {}
             }
         }
+        if self.hover_state.visible() {
+            key_context.add("showing_hover");
+        }
+        if self.signature_help_state.is_visible() {
+            key_context.add("showing_signature_help");
+        }
 
         for layer in self.keymap_context_layers.values() {
             key_context.extend(layer);
@@ -1769,6 +1905,13 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    /// Hides the whole text behind a redaction, and disables copying it, such as for
+    /// entering passwords.
+    pub fn set_masked(&mut self, masked: bool, cx: &mut ViewContext<Self>) {
+        self.masked = masked;
+        cx.notify();
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -1870,6 +2013,7 @@ impl Editor {
             }
 
             hide_hover(self, cx);
+            refresh_signature_help(self, cx);
 
             if old_cursor_position.to_display_point(&display_map).row()
                 != new_cursor_position.to_display_point(&display_map).row()
@@ -1879,8 +2023,10 @@ impl Editor {
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
             refresh_matching_bracket_highlights(self, cx);
+            refresh_linked_editing_ranges(self, cx);
             self.discard_copilot_suggestion(cx);
         }
+        refresh_inline_blame(self, cx);
 
         self.blink_manager.update(cx, BlinkManager::pause_blinking);
         cx.emit(EditorEvent::SelectionsChanged { local });
@@ -2278,6 +2424,46 @@ impl Editor {
         self.selections.pending_anchor().is_some() || self.columnar_selection_tail.is_some()
     }
 
+    pub fn scroll_popover_up(&mut self, _: &ScrollPopoverUp, cx: &mut ViewContext<Self>) {
+        self.scroll_popover(-1., cx);
+    }
+
+    pub fn scroll_popover_down(&mut self, _: &ScrollPopoverDown, cx: &mut ViewContext<Self>) {
+        self.scroll_popover(1., cx);
+    }
+
+    /// Scrolls the documentation shown in the completions menu, the hover popovers or the
+    /// signature help popover, in that order of precedence, without moving the focus out
+    /// of the editor. Hover documentation takes precedence over a hovered diagnostic.
+    fn scroll_popover(&mut self, direction: f32, cx: &mut ViewContext<Self>) {
+        const POPOVER_SCROLL_LINES: f32 = 3.;
+
+        let completions_scroll_handle = match self.context_menu.read().as_ref() {
+            Some(ContextMenu::Completions(menu)) => Some(menu.documentation_scroll_handle.clone()),
+            _ => None,
+        };
+        let scroll_handle = completions_scroll_handle
+            .or_else(|| {
+                let popover = self.hover_state.info_popover.as_ref()?;
+                Some(popover.scroll_handle.clone())
+            })
+            .or_else(|| {
+                let popover = self.hover_state.diagnostic_popover.as_ref()?;
+                Some(popover.scroll_handle.clone())
+            })
+            .or_else(|| self.signature_help_state.scroll_handle());
+        let (Some(scroll_handle), Some(style)) = (scroll_handle, self.style.as_ref()) else {
+            cx.propagate();
+            return;
+        };
+
+        let line_height = style.text.line_height_in_pixels(cx.rem_size());
+        let mut offset = scroll_handle.offset();
+        offset.y = (offset.y - line_height * POPOVER_SCROLL_LINES * direction).min(Pixels::ZERO);
+        scroll_handle.set_offset(offset);
+        cx.notify();
+    }
+
     pub fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
         if self.take_rename(false, cx).is_some() {
             return;
@@ -2291,6 +2477,10 @@ impl Editor {
             return;
         }
 
+        if hide_signature_help(self, cx) {
+            return;
+        }
+
         if self.discard_copilot_suggestion(cx) {
             return;
         }
@@ -2320,6 +2510,12 @@ impl Editor {
             return;
         }
 
+        if self.is_completion_commit_character(&text, cx) {
+            if let Some(task) = self.confirm_completion(&ConfirmCompletion::default(), cx) {
+                task.detach_and_log_err(cx);
+            }
+        }
+
         let selections = self.selections.all_adjusted(cx);
         let mut brace_inserted = false;
         let mut edits = Vec::new();
@@ -2440,10 +2636,12 @@ impl Editor {
             edits.push((selection.start..selection.end, text.clone()));
         }
 
+        let linked_edits = linked_edits(self, &edits, &snapshot);
         drop(snapshot);
         self.transact(cx, |this, cx| {
             this.buffer.update(cx, |buffer, cx| {
                 buffer.edit(edits, this.autoindent_mode.clone(), cx);
+                buffer.edit(linked_edits, None, cx);
             });
 
             let new_anchor_selections = new_selections.iter().map(|e| &e.0);
@@ -2490,6 +2688,12 @@ impl Editor {
             let had_active_copilot_suggestion = this.has_active_copilot_suggestion(cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
 
+            if this.expand_doc_comment(&text, cx) {
+                return;
+            }
+            this.auto_close_tags(&text, cx);
+            autocorrect_commit_message(this, &text, cx);
+
             if !brace_inserted && EditorSettings::get_global(cx).use_on_type_format {
                 if let Some(on_type_format_task) =
                     this.trigger_on_type_formatting(text.to_string(), cx)
@@ -2507,11 +2711,151 @@ impl Editor {
                 this.trigger_completion_on_input(&text, cx);
                 this.refresh_copilot_suggestions(true, cx);
             }
+            signature_help_on_input(this, &text, cx);
+        });
+
+        // Typing whitespace ends the undo group, so the next word starts a new one.
+        if EditorSettings::get_global(cx).undo_by_word
+            && !text.is_empty()
+            && text.chars().all(char::is_whitespace)
+        {
+            self.finalize_last_transaction(cx);
+        }
+    }
+
+    /// Replaces a doc comment trigger, such as `/**`, that was just typed on its own line
+    /// with a doc comment skeleton for the item on the next line.
+    fn expand_doc_comment(&mut self, text: &str, cx: &mut ViewContext<Self>) -> bool {
+        if text.is_empty() || self.selections.count() != 1 {
+            return false;
+        }
+        let selection = self.selections.newest::<Point>(cx);
+        if !selection.is_empty() {
+            return false;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let cursor = selection.head();
+        let Some(scope) = snapshot.language_scope_at(cursor) else {
+            return false;
+        };
+        let Some(doc_comment) = scope.doc_comment() else {
+            return false;
+        };
+        let indent = snapshot.indent_size_for_line(cursor.row);
+        let line_start = Point::new(cursor.row, indent.len);
+        let line_end = Point::new(cursor.row, snapshot.line_len(cursor.row));
+        if !doc_comment.trigger.ends_with(text) || cursor < line_start {
+            return false;
+        }
+        let prefix = snapshot
+            .text_for_range(line_start..cursor)
+            .collect::<String>();
+        let suffix = snapshot
+            .text_for_range(cursor..line_end)
+            .collect::<String>();
+        // Typing the trigger may have autoclosed a block comment.
+        let suffix_was_autoclosed = scope.brackets().any(|(pair, enabled)| {
+            enabled && doc_comment.trigger.starts_with(pair.start.as_str()) && suffix == pair.end
+        });
+        if prefix != *doc_comment.trigger || !(suffix.is_empty() || suffix_was_autoclosed) {
+            return false;
+        }
+
+        let Some(item_row) =
+            (cursor.row + 1..=snapshot.max_point().row).find(|row| !snapshot.is_line_blank(*row))
+        else {
+            return false;
+        };
+        let Some(item) = snapshot
+            .point_to_buffer_offset(Point::new(item_row, 0))
+            .and_then(|(buffer, offset)| {
+                buffer.documented_item_at_row(buffer.offset_to_point(offset).row)
+            })
+        else {
+            return false;
+        };
+        let indent_text = snapshot
+            .text_for_range(Point::new(cursor.row, 0)..line_start)
+            .collect::<String>();
+        let Some(snippet) = doc_comment
+            .skeleton(&item, &indent_text)
+            .and_then(|skeleton| Snippet::parse(&skeleton).log_err())
+        else {
+            return false;
+        };
+
+        let range = line_start.to_offset(&snapshot)..line_end.to_offset(&snapshot);
+        // The skeleton is already indented, and its lines are aligned with the trigger.
+        self.insert_snippet_with_autoindent(&[range], snippet, None, cx)
+            .log_err()
+            .is_some()
+    }
+
+    /// Inserts the closing tag after each cursor that follows an opening tag that was just
+    /// completed by typing `>`, in languages where `auto_close_tags` is enabled.
+    fn auto_close_tags(&mut self, text: &str, cx: &mut ViewContext<Self>) {
+        if text != ">" {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<usize>(cx);
+        let mut edits = Vec::new();
+        let mut new_cursors = Vec::with_capacity(selections.len());
+        let mut inserted_len = 0;
+        for selection in &selections {
+            let cursor = selection.head();
+            new_cursors.push(cursor + inserted_len);
+            if !selection.is_empty() || !snapshot.settings_at(cursor, cx).auto_close_tags {
+                continue;
+            }
+
+            let tag_start = snapshot.clip_offset(cursor.saturating_sub(MAX_TAG_LEN), Bias::Left);
+            let prefix = snapshot
+                .text_for_range(tag_start..cursor)
+                .collect::<String>();
+            let Some(name) = closing_tag_name(&prefix) else {
+                continue;
+            };
+            let closing_tag = format!("</{name}>");
+            let suffix_end = snapshot.clip_offset(cursor + closing_tag.len(), Bias::Right);
+            if snapshot
+                .text_for_range(cursor..suffix_end)
+                .collect::<String>()
+                == closing_tag
+            {
+                continue;
+            }
+            inserted_len += closing_tag.len();
+            edits.push((cursor..cursor, closing_tag));
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        self.change_selections(None, cx, |s| {
+            s.select_ranges(new_cursors.into_iter().map(|cursor| cursor..cursor))
         });
     }
 
     pub fn newline(&mut self, _: &Newline, cx: &mut ViewContext<Self>) {
+        self.insert_newline(true, cx);
+    }
+
+    pub fn newline_without_comment(
+        &mut self,
+        _: &NewlineWithoutComment,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.insert_newline(false, cx);
+    }
+
+    fn insert_newline(&mut self, extend_comment: bool, cx: &mut ViewContext<Self>) {
         self.transact(cx, |this, cx| {
+            let mut block_comment_edits = Vec::new();
             let (edits, selection_fixup_info): (Vec<_>, Vec<_>) = {
                 let selections = this.selections.all::<usize>(cx);
                 let multi_buffer = this.buffer.read(cx);
@@ -2523,9 +2867,14 @@ impl Editor {
                         let mut indent = buffer.indent_size_for_line(start_point.row);
                         indent.len = cmp::min(indent.len, start_point.column);
                         let start = selection.start;
-                        let end = selection.end;
+                        let mut end = selection.end;
                         let is_cursor = start == end;
                         let language_scope = buffer.language_scope_at(start);
+                        let is_comment_extension_enabled = extend_comment
+                            && is_cursor
+                            && multi_buffer
+                                .settings_at(start, cx)
+                                .extend_comment_on_newline;
                         let (comment_delimiter, insert_extra_newline) = if let Some(language) =
                             &language_scope
                         {
@@ -2559,11 +2908,9 @@ impl Editor {
                                         )
                                 });
                             // Comment extension on newline is allowed only for cursor selections
-                            let comment_delimiter = language.line_comment_prefixes().filter(|_| {
-                                let is_comment_extension_enabled =
-                                    multi_buffer.settings_at(0, cx).extend_comment_on_newline;
-                                is_cursor && is_comment_extension_enabled
-                            });
+                            let comment_delimiter = language
+                                .line_comment_prefixes()
+                                .filter(|_| is_comment_extension_enabled);
                             let get_comment_delimiter = |delimiters: &[Arc<str>]| {
                                 let max_len_of_delimiter =
                                     delimiters.iter().map(|delimiter| delimiter.len()).max()?;
@@ -2604,6 +2951,42 @@ impl Editor {
                             (None, false)
                         };
 
+                        if comment_delimiter.is_none() && is_comment_extension_enabled {
+                            if let Some(continuation) = language_scope.as_ref().and_then(|scope| {
+                                block_comment_continuation(&buffer, scope, start_point)
+                            }) {
+                                let mut new_text = format!(
+                                    "\n{}{}",
+                                    indent.chars().collect::<String>(),
+                                    continuation.line_prefix
+                                );
+                                if let Some(closing_indent) = &continuation.closing_indent {
+                                    // Move the end of the comment to its own line.
+                                    end += buffer
+                                        .chars_at(end)
+                                        .take_while(|c| c.is_whitespace() && *c != '\n')
+                                        .map(|c| c.len_utf8())
+                                        .sum::<usize>();
+                                    new_text.push('\n');
+                                    new_text.extend(indent.chars());
+                                    new_text.push_str(closing_indent);
+                                }
+                                // Autoindent would misalign the comment's line prefix.
+                                block_comment_edits.push((
+                                    buffer.anchor_before(start)..buffer.anchor_after(end),
+                                    new_text,
+                                ));
+                                let anchor = buffer.anchor_after(end);
+                                return (
+                                    None,
+                                    (
+                                        continuation.closing_indent.is_some(),
+                                        selection.map(|_| anchor),
+                                    ),
+                                );
+                            }
+                        }
+
                         let capacity_for_delimiter = comment_delimiter
                             .as_deref()
                             .map(str::len)
@@ -2622,14 +3005,15 @@ impl Editor {
                         let anchor = buffer.anchor_after(end);
                         let new_selection = selection.map(|_| anchor);
                         (
-                            (start..end, new_text),
+                            Some((start..end, new_text)),
                             (insert_extra_newline, new_selection),
                         )
                     })
                     .unzip()
             };
 
-            this.edit_with_autoindent(edits, cx);
+            this.edit_with_autoindent(edits.into_iter().flatten(), cx);
+            this.edit(block_comment_edits, cx);
             let buffer = this.buffer.read(cx).snapshot(cx);
             let new_selections = selection_fixup_info
                 .into_iter()
@@ -2790,6 +3174,12 @@ impl Editor {
         let text: Arc<str> = text.into();
         self.transact(cx, |this, cx| {
             let old_selections = this.selections.all_adjusted(cx);
+            let snapshot = this.buffer.read(cx).snapshot(cx);
+            let edits = old_selections
+                .iter()
+                .map(|s| (s.start..s.end, text.clone()))
+                .collect::<Vec<_>>();
+            let linked_edits = linked_edits(this, &edits, &snapshot);
             let selection_anchors = this.buffer.update(cx, |buffer, cx| {
                 let anchors = {
                     let snapshot = buffer.read(cx);
@@ -2801,13 +3191,8 @@ impl Editor {
                         })
                         .collect::<Vec<_>>()
                 };
-                buffer.edit(
-                    old_selections
-                        .iter()
-                        .map(|s| (s.start..s.end, text.clone())),
-                    autoindent_mode,
-                    cx,
-                );
+                buffer.edit(edits, autoindent_mode, cx);
+                buffer.edit(linked_edits, None, cx);
                 anchors
             });
 
@@ -3026,6 +3411,7 @@ impl Editor {
             .current_inlays()
             .filter(move |inlay| {
                 Some(inlay.id) != self.copilot_state.suggestion.as_ref().map(|h| h.id)
+                    && !matches!(inlay.id, InlayId::Color(_))
             })
             .cloned()
             .collect()
@@ -3204,6 +3590,7 @@ impl Editor {
                         matches: Vec::new().into(),
                         selected_item: 0,
                         scroll_handle: UniformListScrollHandle::new(),
+                        documentation_scroll_handle: ScrollHandle::new(),
                         selected_completion_documentation_resolve_debounce: Arc::new(Mutex::new(
                             DebouncedDelay::new(),
                         )),
@@ -3281,6 +3668,33 @@ impl Editor {
         self.completion_tasks.push((id, task));
     }
 
+    /// Whether typing `text` should first accept the selected completion,
+    /// because it is one of the completion's commit characters.
+    fn is_completion_commit_character(&self, text: &str, cx: &AppContext) -> bool {
+        if !EditorSettings::get_global(cx).completion_commit_characters {
+            return false;
+        }
+        let mut chars = text.chars();
+        if chars.next().is_none() || chars.next().is_some() {
+            return false;
+        }
+
+        let context_menu = self.context_menu.read();
+        let Some(ContextMenu::Completions(menu)) = context_menu.as_ref() else {
+            return false;
+        };
+        let Some(mat) = menu.matches.get(menu.selected_item) else {
+            return false;
+        };
+        let completions = menu.completions.read();
+        completions
+            .get(mat.candidate_id)
+            .and_then(|completion| completion.lsp_completion.commit_characters.as_ref())
+            .map_or(false, |commit_characters| {
+                commit_characters.iter().any(|character| character == text)
+            })
+    }
+
     pub fn confirm_completion(
         &mut self,
         action: &ConfirmCompletion,
@@ -3313,14 +3727,21 @@ impl Editor {
         };
         let selections = self.selections.all::<usize>(cx);
         let buffer = buffer_handle.read(cx);
-        let old_range = completion.old_range.to_offset(buffer);
-        let old_text = buffer.text_for_range(old_range.clone()).collect::<String>();
+        let mut old_range = completion.old_range.to_offset(buffer);
 
         let newest_selection = self.selections.newest_anchor();
         if newest_selection.start.buffer_id != Some(buffer_handle.read(cx).remote_id()) {
             return None;
         }
 
+        if !EditorSettings::get_global(cx).completion_replaces_following_word {
+            let cursor = newest_selection.end.text_anchor.to_offset(buffer);
+            if old_range.contains(&cursor) {
+                old_range.end = cursor;
+            }
+        }
+        let old_text = buffer.text_for_range(old_range.clone()).collect::<String>();
+
         let lookbehind = newest_selection
             .start
             .text_anchor
@@ -3465,21 +3886,252 @@ impl Editor {
         let title = action.lsp_action.title.clone();
         let buffer = actions_menu.buffer;
         let workspace = self.workspace()?;
+        let project = workspace.read(cx).project().clone();
 
-        let apply_code_actions = workspace
+        // Refactors that move code to another file need the user to pick that file.
+        let destination = if project
             .read(cx)
-            .project()
-            .clone()
-            .update(cx, |project, cx| {
-                project.apply_code_action(buffer, action, true, cx)
-            });
+            .code_action_requires_destination(&buffer, &action, cx)
+        {
+            let start_dir = buffer
+                .read(cx)
+                .file()
+                .and_then(|file| Some(file.as_local()?.abs_path(cx).parent()?.to_path_buf()))
+                .unwrap_or_else(|| util::paths::HOME.clone());
+            Some(cx.prompt_for_new_path(&start_dir))
+        } else {
+            None
+        };
+
         let workspace = workspace.downgrade();
-        Some(cx.spawn(|editor, cx| async move {
-            let project_transaction = apply_code_actions.await?;
+        Some(cx.spawn(|editor, mut cx| async move {
+            let mut action = action;
+            if let Some(destination) = destination {
+                let Some(destination) = destination.await? else {
+                    return Ok(());
+                };
+                project.update(&mut cx, |project, cx| {
+                    project.set_code_action_destination(&buffer, &mut action, &destination, cx)
+                })?;
+            }
+            let project_transaction = project
+                .update(&mut cx, |project, cx| {
+                    project.apply_code_action(buffer, action, true, cx)
+                })?
+                .await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        }))
+    }
+
+    /// Applies the source action with which the language server removes every unused
+    /// import from the buffer containing the newest cursor.
+    pub fn remove_unused_imports(
+        &mut self,
+        _: &RemoveUnusedImports,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let (buffer, _) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(self.selections.newest_anchor().head(), cx)?;
+        let workspace = self.workspace()?;
+        let project = workspace.read(cx).project().clone();
+        let actions = project.update(cx, |project, cx| {
+            let len = buffer.read(cx).len();
+            project.code_actions(&buffer, 0..len, cx)
+        });
+
+        let workspace = workspace.downgrade();
+        Some(cx.spawn(|editor, mut cx| async move {
+            let action = actions
+                .await?
+                .into_iter()
+                .find(|action| {
+                    action.lsp_action.kind.as_ref().map_or(false, |kind| {
+                        kind.as_str().starts_with(REMOVE_UNUSED_IMPORTS_KIND)
+                    })
+                })
+                .ok_or_else(|| anyhow!("the language server can't remove unused imports"))?;
+            let title = action.lsp_action.title.clone();
+            let project_transaction = project
+                .update(&mut cx, |project, cx| {
+                    project.apply_code_action(buffer, action, true, cx)
+                })?
+                .await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        }))
+    }
+
+    /// Sorts the imports of the buffer containing the newest cursor with the language
+    /// server's source action for organizing imports. When the server doesn't have
+    /// one, the import statements matched by the language's imports query are grouped
+    /// and sorted instead.
+    pub fn sort_imports(
+        &mut self,
+        _: &SortImports,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let (buffer, _) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(self.selections.newest_anchor().head(), cx)?;
+        let workspace = self.workspace()?;
+        let project = workspace.read(cx).project().clone();
+        let actions = project.update(cx, |project, cx| {
+            let len = buffer.read(cx).len();
+            project.code_actions(&buffer, 0..len, cx)
+        });
+
+        let workspace = workspace.downgrade();
+        Some(cx.spawn(|editor, mut cx| async move {
+            let action = actions
+                .await
+                .log_err()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|action| {
+                    action.lsp_action.kind.as_ref().map_or(false, |kind| {
+                        kind.as_str().starts_with(ORGANIZE_IMPORTS_KIND)
+                    })
+                });
+            let Some(action) = action else {
+                return editor.update(&mut cx, |_, cx| sort_imports_by_syntax(&buffer, cx))?;
+            };
+
+            let title = action.lsp_action.title.clone();
+            let project_transaction = project
+                .update(&mut cx, |project, cx| {
+                    project.apply_code_action(buffer, action, true, cx)
+                })?
+                .await?;
             Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
         }))
     }
 
+    /// Applies the quick fix at the newest cursor to every diagnostic like the one it
+    /// fixes in the editor's excerpts, such as all the diagnostics of a kind in the
+    /// project diagnostics, and reports how many of them were fixed.
+    ///
+    /// The quick fix is the one selected in the code actions menu, or else the first
+    /// one available at the cursor. Each diagnostic gets the code action with the same
+    /// title, or the preferred one if the quick fix was preferred.
+    pub fn apply_quick_fix_to_all_excerpts(
+        &mut self,
+        _: &ApplyQuickFixToAllExcerpts,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let project = self.project.clone()?;
+        let (buffer, position) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(self.selections.newest_anchor().head(), cx)?;
+        let reference_diagnostic = buffer
+            .read(cx)
+            .snapshot()
+            .diagnostics_in_range::<_, usize>(position..position, false)
+            .find(|entry| entry.diagnostic.is_primary)?
+            .diagnostic;
+        let selected_action = match self.context_menu.read().as_ref() {
+            Some(ContextMenu::CodeActions(menu)) if menu.buffer == buffer => {
+                menu.actions.get(menu.selected_item).cloned()
+            }
+            _ => None,
+        };
+        let reference_action = selected_action.or_else(|| {
+            let (actions_buffer, actions) = self.available_code_actions.as_ref()?;
+            if *actions_buffer != buffer {
+                return None;
+            }
+            actions.iter().find(|action| is_quick_fix(action)).cloned()
+        })?;
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut targets = Vec::<(Model<Buffer>, Range<text::Anchor>)>::new();
+        for (_, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            for entry in buffer_snapshot
+                .diagnostics_in_range::<_, usize>(excerpt_range.context.clone(), false)
+            {
+                if !entry.diagnostic.is_primary
+                    || !is_same_kind_of_diagnostic(&entry.diagnostic, &reference_diagnostic)
+                {
+                    continue;
+                }
+                let range = buffer_snapshot.anchor_before(entry.range.start)
+                    ..buffer_snapshot.anchor_after(entry.range.end);
+                // Excerpts can overlap, such as the ones of nearby search results.
+                if !targets.iter().any(|(target_buffer, target_range)| {
+                    *target_buffer == buffer && *target_range == range
+                }) {
+                    targets.push((buffer.clone(), range));
+                }
+            }
+        }
+
+        let title = reference_action.lsp_action.title.clone();
+        let workspace = self.workspace().map(|workspace| workspace.downgrade());
+        Some(cx.spawn(|_, mut cx| async move {
+            let mut request_cx = cx.clone();
+            let responses = futures::stream::iter(targets)
+                .map(|(buffer, range)| {
+                    let actions = project.update(&mut request_cx, |project, cx| {
+                        project.code_actions(&buffer, range, cx)
+                    });
+                    async move { anyhow::Ok((buffer, actions?.await?)) }
+                })
+                .buffered(MAX_CONCURRENT_QUICK_FIX_REQUESTS)
+                .collect::<Vec<_>>()
+                .await;
+
+            // The fixes are applied from the last diagnostic to the first, so that the
+            // edits of one don't move the ones that the server computed for the next.
+            let (mut fixed, mut unmatched, mut failed) = (0, 0, 0);
+            for response in responses.into_iter().rev() {
+                let result = async {
+                    let (buffer, actions) = response?;
+                    let Some(action) = matching_quick_fix(actions, &reference_action) else {
+                        return Ok(false);
+                    };
+                    project
+                        .update(&mut cx, |project, cx| {
+                            project.apply_code_action(buffer, action, true, cx)
+                        })?
+                        .await?;
+                    anyhow::Ok(true)
+                }
+                .await;
+                match result {
+                    Ok(true) => fixed += 1,
+                    Ok(false) => unmatched += 1,
+                    Err(error) => {
+                        log::error!("failed to apply quick fix {title:?}: {error:#}");
+                        failed += 1;
+                    }
+                }
+            }
+
+            let mut message = format!(
+                "Applied \"{title}\" to {fixed} of {} diagnostics",
+                fixed + unmatched + failed
+            );
+            if unmatched > 0 {
+                message.push_str(&format!(", {unmatched} had no matching fix"));
+            }
+            if failed > 0 {
+                message.push_str(&format!(", {failed} failed"));
+            }
+            if let Some(workspace) = workspace {
+                workspace.update(&mut cx, |workspace, cx| {
+                    workspace.show_toast(Toast::new(0x9a1c4f17, message), cx)
+                })?;
+            }
+            Ok(())
+        }))
+    }
+
     async fn open_project_transaction(
         this: &WeakView<Editor>,
         workspace: WeakView<Workspace>,
@@ -4087,6 +4739,21 @@ impl Editor {
         insertion_ranges: &[Range<usize>],
         snippet: Snippet,
         cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.insert_snippet_with_autoindent(
+            insertion_ranges,
+            snippet,
+            Some(AutoindentMode::EachLine),
+            cx,
+        )
+    }
+
+    fn insert_snippet_with_autoindent(
+        &mut self,
+        insertion_ranges: &[Range<usize>],
+        snippet: Snippet,
+        autoindent_mode: Option<AutoindentMode>,
+        cx: &mut ViewContext<Self>,
     ) -> Result<()> {
         let tabstops = self.buffer.update(cx, |buffer, cx| {
             let snippet_text: Arc<str> = snippet.text.clone().into();
@@ -4095,7 +4762,7 @@ impl Editor {
                     .iter()
                     .cloned()
                     .map(|range| (range, snippet_text.clone())),
-                Some(AutoindentMode::EachLine),
+                autoindent_mode,
                 cx,
             );
 
@@ -5160,6 +5827,9 @@ impl Editor {
     }
 
     pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
+        if self.masked {
+            return;
+        }
         let mut text = String::new();
         let buffer = self.buffer.read(cx).snapshot(cx);
         let mut selections = self.selections.all::<Point>(cx);
@@ -5191,17 +5861,23 @@ impl Editor {
                 });
             }
         }
+        let contains_private_text = selections_contain_private_text(&buffer, &selections);
 
         self.transact(cx, |this, cx| {
             this.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select(selections);
             });
             this.insert("", cx);
-            cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+            let item = ClipboardItem::new(text).with_metadata(clipboard_selections);
+            cx.write_to_clipboard(item.clone());
+            clipboard_history::record(item, contains_private_text, cx);
         });
     }
 
     pub fn copy(&mut self, _: &Copy, cx: &mut ViewContext<Self>) {
+        if self.masked {
+            return;
+        }
         let selections = self.selections.all::<Point>(cx);
         let buffer = self.buffer.read(cx).read(cx);
         let mut text = String::new();
@@ -5235,82 +5911,140 @@ impl Editor {
                 });
             }
         }
+        let contains_private_text = selections_contain_private_text(&buffer, &selections);
+        drop(buffer);
 
-        cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+        let item = ClipboardItem::new(text).with_metadata(clipboard_selections);
+        cx.write_to_clipboard(item.clone());
+        clipboard_history::record(item, contains_private_text, cx);
     }
 
     pub fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
+        self.do_paste(true, cx);
+    }
+
+    /// Pastes the clipboard contents as they are, without adjusting their indentation.
+    pub fn paste_without_formatting(
+        &mut self,
+        _: &PasteWithoutFormatting,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.do_paste(false, cx);
+    }
+
+    fn do_paste(&mut self, auto_indent: bool, cx: &mut ViewContext<Self>) {
+        if let Some(item) = cx.read_from_clipboard() {
+            self.paste_item(&item, auto_indent, cx);
+        }
+    }
+
+    /// Pastes the given item as if it had been read from the clipboard, without
+    /// writing it to the clipboard.
+    pub fn paste_item(
+        &mut self,
+        item: &ClipboardItem,
+        auto_indent: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
         if self.read_only(cx) {
             return;
         }
 
         self.transact(cx, |this, cx| {
-            if let Some(item) = cx.read_from_clipboard() {
-                let clipboard_text = Cow::Borrowed(item.text());
-                if let Some(mut clipboard_selections) = item.metadata::<Vec<ClipboardSelection>>() {
-                    let old_selections = this.selections.all::<usize>(cx);
-                    let all_selections_were_entire_line =
-                        clipboard_selections.iter().all(|s| s.is_entire_line);
-                    let first_selection_indent_column =
-                        clipboard_selections.first().map(|s| s.first_line_indent);
-                    if clipboard_selections.len() != old_selections.len() {
-                        clipboard_selections.drain(..);
-                    }
-
-                    this.buffer.update(cx, |buffer, cx| {
-                        let snapshot = buffer.read(cx);
-                        let mut start_offset = 0;
-                        let mut edits = Vec::new();
-                        let mut original_indent_columns = Vec::new();
-                        let line_mode = this.selections.line_mode;
-                        for (ix, selection) in old_selections.iter().enumerate() {
-                            let to_insert;
-                            let entire_line;
-                            let original_indent_column;
-                            if let Some(clipboard_selection) = clipboard_selections.get(ix) {
-                                let end_offset = start_offset + clipboard_selection.len;
-                                to_insert = &clipboard_text[start_offset..end_offset];
-                                entire_line = clipboard_selection.is_entire_line;
-                                start_offset = end_offset + 1;
-                                original_indent_column =
-                                    Some(clipboard_selection.first_line_indent);
-                            } else {
-                                to_insert = clipboard_text.as_str();
-                                entire_line = all_selections_were_entire_line;
-                                original_indent_column = first_selection_indent_column
-                            }
+            // Text copied from other applications may use other line endings, which
+            // would be counted in the lines distributed across selections.
+            let mut clipboard_text = item.text().clone();
+            LineEnding::normalize(&mut clipboard_text);
+            let old_selections = this.selections.all::<usize>(cx);
+            // Zed's own metadata describes the text as it was copied, so it's dropped
+            // if the text had to be normalized.
+            let clipboard_selections = (clipboard_text == *item.text())
+                .then(|| item.metadata::<Vec<ClipboardSelection>>())
+                .flatten()
+                .or_else(|| clipboard_selections_for_lines(&clipboard_text, &old_selections));
 
-                            // If the corresponding selection was empty when this slice of the
-                            // clipboard text was written, then the entire line containing the
-                            // selection was copied. If this selection is also currently empty,
-                            // then paste the line before the current line of the buffer.
-                            let range = if selection.is_empty() && !line_mode && entire_line {
-                                let column = selection.start.to_point(&snapshot).column as usize;
-                                let line_start = selection.start - column;
-                                line_start..line_start
-                            } else {
-                                selection.range()
-                            };
+            // When the destination's language can indent each line from its syntax,
+            // pasted lines are reindented for the scope they land in. Otherwise, the
+            // pasted block keeps its lines' indentation relative to the first one.
+            let reindent_each_line = auto_indent && {
+                let snapshot = this.buffer.read(cx).snapshot(cx);
+                old_selections.iter().all(|selection| {
+                    snapshot
+                        .language_at(selection.start)
+                        .and_then(|language| language.grammar())
+                        .map_or(false, |grammar| grammar.has_indents_query())
+                })
+            };
+            if let Some(mut clipboard_selections) = clipboard_selections {
+                let all_selections_were_entire_line =
+                    clipboard_selections.iter().all(|s| s.is_entire_line);
+                let first_selection_indent_column =
+                    clipboard_selections.first().map(|s| s.first_line_indent);
+                if clipboard_selections.len() != old_selections.len() {
+                    clipboard_selections.drain(..);
+                }
 
-                            edits.push((range, to_insert));
-                            original_indent_columns.extend(original_indent_column);
+                this.buffer.update(cx, |buffer, cx| {
+                    let snapshot = buffer.read(cx);
+                    let mut start_offset = 0;
+                    let mut edits = Vec::new();
+                    let mut original_indent_columns = Vec::new();
+                    let line_mode = this.selections.line_mode;
+                    for (ix, selection) in old_selections.iter().enumerate() {
+                        let to_insert;
+                        let entire_line;
+                        let original_indent_column;
+                        if let Some(clipboard_selection) = clipboard_selections.get(ix) {
+                            let end_offset = start_offset + clipboard_selection.len;
+                            to_insert = &clipboard_text[start_offset..end_offset];
+                            entire_line = clipboard_selection.is_entire_line;
+                            start_offset = end_offset + 1;
+                            original_indent_column = Some(clipboard_selection.first_line_indent);
+                        } else {
+                            to_insert = clipboard_text.as_str();
+                            entire_line = all_selections_were_entire_line;
+                            original_indent_column = first_selection_indent_column
                         }
-                        drop(snapshot);
 
-                        buffer.edit(
-                            edits,
-                            Some(AutoindentMode::Block {
-                                original_indent_columns,
-                            }),
-                            cx,
-                        );
-                    });
+                        // If the corresponding selection was empty when this slice of the
+                        // clipboard text was written, then the entire line containing the
+                        // selection was copied. If this selection is also currently empty,
+                        // then paste the line before the current line of the buffer.
+                        let range = if selection.is_empty() && !line_mode && entire_line {
+                            let column = selection.start.to_point(&snapshot).column as usize;
+                            let line_start = selection.start - column;
+                            line_start..line_start
+                        } else {
+                            selection.range()
+                        };
 
-                    let selections = this.selections.all::<usize>(cx);
-                    this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
-                } else {
-                    this.insert(&clipboard_text, cx);
-                }
+                        edits.push((range, to_insert));
+                        original_indent_columns.extend(original_indent_column);
+                    }
+                    drop(snapshot);
+
+                    let autoindent_mode = if reindent_each_line {
+                        Some(AutoindentMode::EachLine)
+                    } else {
+                        auto_indent.then(|| AutoindentMode::Block {
+                            original_indent_columns,
+                        })
+                    };
+                    buffer.edit(edits, autoindent_mode, cx);
+                });
+
+                let selections = this.selections.all::<usize>(cx);
+                this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+            } else if reindent_each_line {
+                this.insert_with_autoindent_mode(
+                    &clipboard_text,
+                    Some(AutoindentMode::EachLine),
+                    cx,
+                );
+            } else if auto_indent {
+                this.insert(&clipboard_text, cx);
+            } else {
+                this.insert_with_autoindent_mode(&clipboard_text, None, cx);
             }
         });
     }
@@ -8481,6 +9215,108 @@ impl Editor {
         }
     }
 
+    /// Returns the Git repository of the editor's file, and the file's path in it.
+    fn git_repository_for_buffer(
+        &self,
+        cx: &AppContext,
+    ) -> Result<(Arc<Mutex<dyn GitRepository>>, RepoPath)> {
+        let project = self.project.as_ref().ok_or_else(|| anyhow!("no project"))?;
+        let buffer = self
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .ok_or_else(|| anyhow!("not a singleton buffer"))?;
+        let file = project::File::from_dyn(buffer.read(cx).file())
+            .ok_or_else(|| anyhow!("buffer has no file"))?;
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path.clone(),
+        };
+        project
+            .read(cx)
+            .git_repository_for_path(&project_path, cx)
+            .ok_or_else(|| anyhow!("file is not in a Git repository"))
+    }
+
+    pub(crate) fn is_in_git_repository(&self, cx: &AppContext) -> bool {
+        self.git_repository_for_buffer(cx).is_ok()
+    }
+
+    fn open_commit_for_blamed_line(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<Task<Result<()>>> {
+        let workspace = self.workspace().ok_or_else(|| anyhow!("no workspace"))?;
+        let (repository, repo_path) = self.git_repository_for_buffer(cx)?;
+
+        // The row is blamed as of the buffer's contents, so that unsaved edits above it
+        // don't shift it onto another line of the committed file.
+        let buffer = self
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .ok_or_else(|| anyhow!("not a singleton buffer"))?;
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let workspace = workspace.downgrade();
+        Ok(cx.spawn(|editor, mut cx| async move {
+            let sha = blame_row(editor, repository.clone(), repo_path, buffer, row, &mut cx)
+                .await?
+                .map(|commit| commit.sha)
+                .ok_or_else(|| anyhow!("line {} has not been committed yet", row + 1))?;
+            cx.update(|cx| CommitView::open(sha, repository, workspace, cx))?
+                .await
+        }))
+    }
+
+    pub fn open_git_blame_commit(&mut self, _: &OpenGitBlameCommit, cx: &mut ViewContext<Self>) {
+        let task = self.open_commit_for_blamed_line(cx);
+        self.show_open_commit_failure(task, cx);
+    }
+
+    /// Opens the commit with the given SHA from the repository of the editor's file.
+    pub(crate) fn open_git_commit(&mut self, sha: String, cx: &mut ViewContext<Self>) {
+        let task = self.open_commit(sha, cx);
+        self.show_open_commit_failure(task, cx);
+    }
+
+    fn open_commit(&mut self, sha: String, cx: &mut ViewContext<Self>) -> Result<Task<Result<()>>> {
+        let workspace = self.workspace().ok_or_else(|| anyhow!("no workspace"))?;
+        let (repository, _) = self.git_repository_for_buffer(cx)?;
+        Ok(CommitView::open(sha, repository, workspace.downgrade(), cx))
+    }
+
+    fn show_open_commit_failure(
+        &mut self,
+        task: Result<Task<Result<()>>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        cx.spawn(|editor, mut cx| async move {
+            let result = match task {
+                Ok(task) => task.await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                let message = format!("Failed to open blame commit: {err}");
+
+                Err::<(), anyhow::Error>(err).log_err();
+
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        if let Some(workspace) = editor.workspace() {
+                            workspace.update(cx, |workspace, cx| {
+                                workspace.show_toast(
+                                    Toast::new(OPEN_COMMIT_FAILURE_TOAST_ID, message),
+                                    cx,
+                                )
+                            })
+                        }
+                    })
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
     pub fn highlight_rows(&mut self, rows: Option<Range<u32>>) {
         self.highlighted_rows = rows;
     }
@@ -8702,6 +9538,12 @@ impl Editor {
         display_snapshot: &DisplaySnapshot,
         cx: &mut ViewContext<Self>,
     ) -> Vec<Range<DisplayPoint>> {
+        if self.masked {
+            return vec![
+                search_range.start.to_display_point(display_snapshot)
+                    ..search_range.end.to_display_point(display_snapshot),
+            ];
+        }
         display_snapshot
             .buffer_snapshot
             .redacted_ranges(search_range, |file| {
@@ -8760,9 +9602,16 @@ impl Editor {
         }
     }
 
-    pub fn show_local_cursors(&self, cx: &WindowContext) -> bool {
-        (self.read_only(cx) || self.blink_manager.read(cx).visible())
-            && self.focus_handle.is_focused(cx)
+    /// The opacity of the local cursors, which is 0 when they're hidden.
+    pub fn local_cursor_opacity(&self, cx: &WindowContext) -> f32 {
+        if !self.focus_handle.is_focused(cx) {
+            0.
+        } else if self.read_only(cx) {
+            1.
+        } else {
+            let style = EditorSettings::get_global(cx).cursor_blink_style;
+            self.blink_manager.read(cx).opacity(style)
+        }
     }
 
     fn on_buffer_changed(&mut self, _: Model<MultiBuffer>, cx: &mut ViewContext<Self>) {
@@ -8781,6 +9630,10 @@ impl Editor {
             } => {
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
+                refresh_code_lens(self, true, cx);
+                refresh_document_colors(self, true, cx);
+                refresh_commit_message_lints(self, cx);
+                refresh_inline_blame(self, cx);
                 if self.has_active_copilot_suggestion(cx) {
                     self.update_visible_copilot_suggestion(cx);
                 }
@@ -8836,16 +9689,23 @@ impl Editor {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
-            multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
+            multi_buffer::Event::Reparsed => {
+                auto_fold(self, cx);
+                cx.emit(EditorEvent::Reparsed);
+            }
             multi_buffer::Event::LanguageChanged => {
+                refresh_commit_message(self, cx);
+                auto_fold(self, cx);
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
-            multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
+            multi_buffer::Event::FileHandleChanged => {
+                refresh_external_decorations(self, cx);
                 cx.emit(EditorEvent::TitleChanged)
             }
+            multi_buffer::Event::Reloaded => cx.emit(EditorEvent::TitleChanged),
             multi_buffer::Event::DiffBaseChanged => cx.emit(EditorEvent::DiffBaseChanged),
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
             multi_buffer::Event::DiagnosticsUpdated => {
@@ -8872,6 +9732,18 @@ impl Editor {
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
+        let code_lens_changed = editor_settings.code_lens != self.code_lens.is_enabled();
+        let document_colors_changed =
+            editor_settings.document_colors != self.document_colors.is_enabled();
+        if code_lens_changed {
+            refresh_code_lens(self, false, cx);
+        }
+        if document_colors_changed {
+            refresh_document_colors(self, false, cx);
+        }
+        if editor_settings.inline_blame != self.inline_blame.is_enabled() {
+            refresh_inline_blame(self, cx);
+        }
         cx.notify();
     }
 
@@ -9264,6 +10136,7 @@ impl Editor {
             .update(cx, |buffer, cx| buffer.remove_active_selections(cx));
         self.hide_context_menu(cx);
         hide_hover(self, cx);
+        hide_signature_help(self, cx);
         cx.emit(EditorEvent::Blurred);
         cx.notify();
     }
@@ -9413,6 +10286,125 @@ fn consume_contiguous_rows(
     (start_row, end_row)
 }
 
+fn selections_contain_private_text(
+    buffer: &MultiBufferSnapshot,
+    selections: &[Selection<Point>],
+) -> bool {
+    selections.iter().any(|selection| {
+        [selection.start, selection.end].into_iter().any(|point| {
+            buffer
+                .file_at(point)
+                .map_or(false, |file| file.is_private())
+        })
+    })
+}
+
+/// When text without clipboard metadata, such as a multi-cursor copy that went through
+/// another application, has exactly one line per selection, pastes one line at each
+/// selection instead of the whole text at every selection.
+fn clipboard_selections_for_lines(
+    text: &str,
+    selections: &[Selection<usize>],
+) -> Option<Vec<ClipboardSelection>> {
+    if selections.len() < 2 {
+        return None;
+    }
+
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    let lines = text.split('\n').collect::<Vec<_>>();
+    if lines.len() != selections.len() {
+        return None;
+    }
+
+    Some(
+        lines
+            .into_iter()
+            .map(|line| ClipboardSelection {
+                len: line.len(),
+                is_entire_line: false,
+                first_line_indent: (line.len() - line.trim_start().len()) as u32,
+            })
+            .collect(),
+    )
+}
+
+struct BlockCommentContinuation {
+    line_prefix: String,
+    /// The indentation of the comment's end delimiter, if it followed the cursor and
+    /// is moved to its own line.
+    closing_indent: Option<String>,
+}
+
+/// Determines how a block comment is continued when a newline is inserted at the
+/// given position, on the line that starts the comment or on one of its prefixed lines.
+fn block_comment_continuation(
+    buffer: &MultiBufferSnapshot,
+    scope: &LanguageScope,
+    position: Point,
+) -> Option<BlockCommentContinuation> {
+    if scope.override_name() != Some("comment") {
+        return None;
+    }
+    let (comment_start, comment_end) = scope.block_comment_delimiters()?;
+    let comment_start = comment_start.trim_end();
+    let comment_end = comment_end.trim_start();
+    let line_prefix = scope.block_comment_line_prefix()?;
+    let trimmed_line_prefix = line_prefix.trim_end();
+
+    let line_before_cursor = buffer
+        .text_for_range(Point::new(position.row, 0)..position)
+        .collect::<String>();
+    let line_after_cursor = buffer
+        .text_for_range(position..Point::new(position.row, buffer.line_len(position.row)))
+        .collect::<String>();
+    let text = line_before_cursor.trim_start();
+    if text.contains(comment_end) {
+        return None;
+    }
+
+    if text.starts_with(comment_start) {
+        // Align the line prefix with the inside of the start delimiter, as in ` * `.
+        let alignment = " ".repeat(
+            comment_start
+                .len()
+                .saturating_sub(trimmed_line_prefix.len()),
+        );
+        let closing_indent = line_after_cursor
+            .trim_start()
+            .starts_with(comment_end)
+            .then(|| alignment.clone());
+        Some(BlockCommentContinuation {
+            line_prefix: format!("{alignment}{line_prefix}"),
+            closing_indent,
+        })
+    } else if text.starts_with(trimmed_line_prefix) {
+        Some(BlockCommentContinuation {
+            line_prefix: line_prefix.to_string(),
+            closing_indent: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns the name of the element whose opening tag ends the given text, unless the
+/// tag closes itself or the element is void, such as `<br>`.
+fn closing_tag_name(text: &str) -> Option<&str> {
+    let text = text.strip_suffix('>')?;
+    let tag = &text[text.rfind('<')? + 1..];
+    if tag.ends_with('/') {
+        return None;
+    }
+    let name = tag.split(char::is_whitespace).next()?;
+    let mut chars = name.chars();
+    let is_name = chars.next()?.is_ascii_alphabetic()
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'));
+    if !is_name || VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str()) {
+        return None;
+    }
+    Some(name)
+}
+
 fn ending_row(next_selection: &Selection<Point>, display_map: &DisplaySnapshot) -> u32 {
     if next_selection.end.column > 0 || next_selection.is_empty() {
         display_map.next_line_boundary(next_selection.end).0.row + 1
@@ -9421,6 +10413,35 @@ fn ending_row(next_selection: &Selection<Point>, display_map: &DisplaySnapshot)
     }
 }
 
+fn is_quick_fix(action: &CodeAction) -> bool {
+    action.lsp_action.kind.as_ref().map_or(false, |kind| {
+        kind.as_str()
+            .starts_with(lsp::CodeActionKind::QUICKFIX.as_str())
+    })
+}
+
+/// Whether two diagnostics report the same problem, which is identified by its code
+/// when the server gives one.
+fn is_same_kind_of_diagnostic(a: &Diagnostic, b: &Diagnostic) -> bool {
+    a.source == b.source && a.code == b.code && (a.code.is_some() || a.message == b.message)
+}
+
+fn matching_quick_fix(mut actions: Vec<CodeAction>, reference: &CodeAction) -> Option<CodeAction> {
+    let ix = actions
+        .iter()
+        .position(|action| action.lsp_action.title == reference.lsp_action.title)
+        .or_else(|| {
+            if reference.lsp_action.is_preferred != Some(true) {
+                return None;
+            }
+            actions.iter().position(|action| {
+                action.lsp_action.is_preferred == Some(true)
+                    && action.lsp_action.kind == reference.lsp_action.kind
+            })
+        })?;
+    Some(actions.swap_remove(ix))
+}
+
 impl EditorSnapshot {
     pub fn remote_selections_in_range<'a>(
         &'a self,
@@ -9561,7 +10582,7 @@ impl Render for Editor {
                 font_size: rems(0.875).into(),
                 font_weight: FontWeight::NORMAL,
                 font_style: FontStyle::Normal,
-                line_height: relative(settings.buffer_line_height.value()),
+                line_height: relative(settings.buffer_line_height(cx).value()),
                 background_color: None,
                 underline: None,
                 strikethrough: None,
@@ -9575,7 +10596,7 @@ impl Render for Editor {
                 font_size: settings.buffer_font_size(cx).into(),
                 font_weight: FontWeight::NORMAL,
                 font_style: FontStyle::Normal,
-                line_height: relative(settings.buffer_line_height.value()),
+                line_height: relative(settings.buffer_line_height(cx).value()),
                 background_color: None,
                 underline: None,
                 strikethrough: None,
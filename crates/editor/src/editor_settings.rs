@@ -1,3 +1,5 @@
+use collections::HashMap;
+use gpui::Modifiers;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
@@ -5,17 +7,64 @@ use settings::Settings;
 #[derive(Deserialize)]
 pub struct EditorSettings {
     pub cursor_blink: bool,
+    pub cursor_blink_style: CursorBlinkStyle,
     pub hover_popover_enabled: bool,
+    pub code_lens: bool,
+    pub inline_blame: bool,
+    pub document_colors: bool,
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
     pub completion_documentation_secondary_query_debounce: u64,
+    pub accept_completion_on: AcceptCompletionOn,
+    pub completion_commit_characters: bool,
+    pub completion_replaces_following_word: bool,
     pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
     pub vertical_scroll_margin: f32,
+    pub scroll_beyond_last_line: bool,
+    pub smooth_scroll: SmoothScroll,
     pub relative_line_numbers: bool,
     pub seed_search_query_from_cursor: SeedQuerySetting,
     pub redact_private_values: bool,
+    pub multi_cursor_modifier: MultiCursorModifier,
+    pub middle_click_paste: bool,
+    pub undo_by_word: bool,
+    pub clipboard_history: ClipboardHistorySettings,
+    pub commit_message: CommitMessageSettings,
+}
+
+/// How the cursor blinks in the editor.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorBlinkStyle {
+    /// Toggle the cursor on and off.
+    Blink,
+    /// Fade the cursor in and out.
+    Phase,
+}
+
+/// Which keys accept the selected item in the completions menu.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AcceptCompletionOn {
+    /// Both `enter` and `tab` accept the completion.
+    EnterAndTab,
+    /// Only `enter` accepts the completion; `tab` inserts a tab.
+    Enter,
+    /// Only `tab` accepts the completion; `enter` inserts a newline.
+    Tab,
+}
+
+impl AcceptCompletionOn {
+    /// The value of the `accept_completion_on` key context entry.
+    pub fn key_context_value(&self) -> &'static str {
+        match self {
+            AcceptCompletionOn::EnterAndTab => "enter_and_tab",
+            AcceptCompletionOn::Enter => "enter",
+            AcceptCompletionOn::Tab => "tab",
+        }
+    }
 }
 
 /// When to populate a new search's query based on the text under the cursor.
@@ -30,6 +79,38 @@ pub enum SeedQuerySetting {
     Never,
 }
 
+/// The modifier to hold when clicking to add a cursor. The other one of `alt`
+/// and `cmd` goes to the definition of the symbol under the mouse.
+///
+/// Default: alt
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiCursorModifier {
+    /// Alt-click adds a cursor and cmd-click goes to definition.
+    Alt,
+    /// Cmd-click adds a cursor and alt-click goes to definition.
+    CmdOrCtrl,
+}
+
+impl MultiCursorModifier {
+    /// Whether clicking with the given modifiers adds a cursor.
+    pub fn adds_cursor(self, modifiers: &Modifiers) -> bool {
+        match self {
+            MultiCursorModifier::Alt => modifiers.alt,
+            MultiCursorModifier::CmdOrCtrl => modifiers.command,
+        }
+    }
+
+    /// Whether clicking with the given modifiers goes to the definition of the
+    /// symbol under the mouse.
+    pub fn goes_to_definition(self, modifiers: &Modifiers) -> bool {
+        match self {
+            MultiCursorModifier::Alt => modifiers.command,
+            MultiCursorModifier::CmdOrCtrl => modifiers.alt,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Toolbar {
     pub breadcrumbs: bool,
@@ -43,6 +124,59 @@ pub struct Scrollbar {
     pub selections: bool,
     pub symbols_selections: bool,
     pub diagnostics: bool,
+    pub cursors: bool,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ClipboardHistorySettings {
+    pub max_entries: usize,
+    pub exclude_private_files: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CommitMessageSettings {
+    pub autocorrect: bool,
+    pub abbreviations: HashMap<String, String>,
+    pub spelling_corrections: HashMap<String, String>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SmoothScroll {
+    pub enabled: bool,
+    pub duration: u64,
+    pub easing: ScrollEasing,
+}
+
+/// The easing curve of smooth scrolling animations.
+///
+/// Default: ease_out
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollEasing {
+    /// Scroll at a constant speed.
+    Linear,
+    /// Start quickly and slow down towards the end.
+    EaseOut,
+    /// Speed up at the start and slow down towards the end.
+    EaseInOut,
+}
+
+impl ScrollEasing {
+    /// Maps the elapsed fraction of an animation to the fraction of the distance covered.
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            ScrollEasing::Linear => t,
+            ScrollEasing::EaseOut => 1. - (1. - t).powi(3),
+            ScrollEasing::EaseInOut => {
+                if t < 0.5 {
+                    4. * t * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+        }
+    }
 }
 
 /// When to show the scrollbar in the editor.
@@ -68,11 +202,30 @@ pub struct EditorSettingsContent {
     ///
     /// Default: true
     pub cursor_blink: Option<bool>,
+    /// How the cursor blinks, when blinking is enabled.
+    ///
+    /// Default: blink
+    pub cursor_blink_style: Option<CursorBlinkStyle>,
     /// Whether to show the informational hover box when moving the mouse
     /// over symbols in the editor.
     ///
     /// Default: true
     pub hover_popover_enabled: Option<bool>,
+    /// Whether to show code lenses provided by language servers (such as
+    /// reference counts or "Run test" commands) above symbols in the editor.
+    ///
+    /// Default: true
+    pub code_lens: Option<bool>,
+    /// Whether to show the commit that last changed the line of the cursor
+    /// at the end of the line, for files in a Git repository.
+    ///
+    /// Default: true
+    pub inline_blame: Option<bool>,
+    /// Whether to show a swatch before the colors that language servers find in
+    /// a buffer, such as CSS color values.
+    ///
+    /// Default: true
+    pub document_colors: Option<bool>,
     /// Whether to pop the completions menu while typing in an editor without
     /// explicitly requesting it.
     ///
@@ -88,6 +241,21 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 300 ms
     pub completion_documentation_secondary_query_debounce: Option<u64>,
+    /// Which keys accept the selected item in the completions menu.
+    ///
+    /// Default: enter_and_tab
+    pub accept_completion_on: Option<AcceptCompletionOn>,
+    /// Whether typing one of the selected completion's commit characters, as
+    /// reported by the language server (such as `.` after a TypeScript
+    /// identifier), accepts the completion before inserting the character.
+    ///
+    /// Default: false
+    pub completion_commit_characters: Option<bool>,
+    /// Whether accepting a completion replaces the rest of the word following
+    /// the cursor, rather than inserting in front of it.
+    ///
+    /// Default: true
+    pub completion_replaces_following_word: Option<bool>,
     /// Whether to use additional LSP queries to format (and amend) the code after
     /// every "trigger" symbol input, defined by LSP server capabilities.
     ///
@@ -102,6 +270,13 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 3.
     pub vertical_scroll_margin: Option<f32>,
+    /// Whether the editor can be scrolled past its last line, up to the point
+    /// where the last line is at the top of the viewport.
+    ///
+    /// Default: true
+    pub scroll_beyond_last_line: Option<bool>,
+    /// Smooth scrolling related settings
+    pub smooth_scroll: Option<SmoothScrollContent>,
     /// Whether the line numbers on editors gutter are relative or not.
     ///
     /// Default: false
@@ -117,6 +292,26 @@ pub struct EditorSettingsContent {
     ///
     /// Default: false
     pub redact_private_values: Option<bool>,
+
+    /// The modifier to hold when clicking to add a cursor. The other one of
+    /// `alt` and `cmd` goes to definition when clicking.
+    ///
+    /// Default: alt
+    pub multi_cursor_modifier: Option<MultiCursorModifier>,
+    /// Whether clicking the middle mouse button pastes the clipboard at the
+    /// mouse position. Only applies on Linux.
+    ///
+    /// Default: true
+    pub middle_click_paste: Option<bool>,
+    /// Whether undo takes back typed text a word at a time, rather than everything
+    /// typed without pausing.
+    ///
+    /// Default: false
+    pub undo_by_word: Option<bool>,
+    /// Clipboard history related settings
+    pub clipboard_history: Option<ClipboardHistoryContent>,
+    /// Settings for writing git commit messages
+    pub commit_message: Option<CommitMessageContent>,
 }
 
 // Toolbar related settings
@@ -155,6 +350,64 @@ pub struct ScrollbarContent {
     ///
     /// Default: true
     pub diagnostics: Option<bool>,
+    /// Whether to show the cursor positions of collaborators in the scrollbar.
+    ///
+    /// Default: true
+    pub cursors: Option<bool>,
+}
+
+/// Clipboard history related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ClipboardHistoryContent {
+    /// The number of recent copies to keep for `edit: paste from history`, not
+    /// counting pinned entries. Set to 0 to disable the clipboard history.
+    ///
+    /// Default: 50
+    pub max_entries: Option<usize>,
+    /// Whether to leave text copied from private files, as defined by the
+    /// private_files setting, out of the clipboard history.
+    ///
+    /// Default: true
+    pub exclude_private_files: Option<bool>,
+}
+
+/// Settings for writing git commit messages
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CommitMessageContent {
+    /// Whether to correct words as they're typed in a commit message: expanding
+    /// abbreviations anywhere in the message, and fixing misspellings in its subject.
+    ///
+    /// Default: true
+    pub autocorrect: Option<bool>,
+    /// Words that are expanded when typed in a commit message, such as
+    /// `"impl": "implement"`.
+    ///
+    /// Default: {}
+    pub abbreviations: Option<HashMap<String, String>>,
+    /// Misspellings that are fixed when typed in the subject of a commit message,
+    /// in addition to Zed's list of common English misspellings. Add the ones of
+    /// the languages you write commit messages in.
+    ///
+    /// Default: {}
+    pub spelling_corrections: Option<HashMap<String, String>>,
+}
+
+/// Smooth scrolling related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SmoothScrollContent {
+    /// Whether to animate scrolling by lines or pages with the mouse wheel
+    /// and the keyboard. Trackpad scrolling is never animated.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The duration of the scroll animation, in milliseconds.
+    ///
+    /// Default: 120
+    pub duration: Option<u64>,
+    /// The easing curve of the scroll animation.
+    ///
+    /// Default: ease_out
+    pub easing: Option<ScrollEasing>,
 }
 
 impl Settings for EditorSettings {
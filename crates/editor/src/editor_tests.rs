@@ -1,5 +1,6 @@
 use super::*;
 use crate::{
+    clipboard_history::ClipboardHistory,
     scroll::scroll_amount::ScrollAmount,
     test::{
         assert_text_with_selections, build_editor, editor_lsp_test_context::EditorLspTestContext,
@@ -15,8 +16,8 @@ use language::{
     language_settings::{AllLanguageSettings, AllLanguageSettingsContent, LanguageSettingsContent},
     BracketPairConfig,
     Capability::ReadWrite,
-    FakeLspAdapter, LanguageConfig, LanguageConfigOverride, LanguageMatcher, LanguageRegistry,
-    Override, Point,
+    DocCommentConfig, DocCommentStyle, FakeLspAdapter, LanguageConfig, LanguageConfigOverride,
+    LanguageMatcher, LanguageRegistry, Override, Point,
 };
 use parking_lot::Mutex;
 use project::project_settings::{LspSettings, ProjectSettings};
@@ -224,6 +225,40 @@ fn test_undo_redo_with_selection_restoration(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_undo_by_word(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.undo_by_word = Some(true);
+            });
+        })
+    });
+
+    let buffer = cx.new_model(|cx| {
+        let mut buffer =
+            language::Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), "");
+        // Group everything that's typed in the test, unless undoing by word.
+        buffer.set_group_interval(Duration::from_secs(60));
+        buffer
+    });
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    _ = cx.add_window(|cx| {
+        let mut editor = build_editor(buffer.clone(), cx);
+        for text in ["a", "b", " ", "c", "d"] {
+            editor.handle_input(text, cx);
+        }
+        assert_eq!(editor.text(cx), "ab cd");
+
+        editor.undo(&Undo, cx);
+        assert_eq!(editor.text(cx), "ab ");
+        editor.undo(&Undo, cx);
+        assert_eq!(editor.text(cx), "");
+        editor
+    });
+}
+
 #[gpui::test]
 fn test_ime_composition(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1487,6 +1522,54 @@ async fn test_scroll_page_up_page_down(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_scroll_beyond_last_line(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    let line_height = cx.editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 4. * line_height));
+    cx.set_state("ˇone\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten");
+
+    // By default, the last line can be scrolled to the top of the viewport.
+    cx.update_editor(|editor, cx| editor.set_scroll_position(point(0., 9.), cx));
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.snapshot(cx).scroll_position(), point(0., 9.));
+    });
+
+    // Otherwise, scrolling stops once the last line is at the bottom of the viewport.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.scroll_beyond_last_line = Some(false);
+            });
+        })
+    });
+    cx.update_editor(|editor, cx| editor.set_scroll_position(point(0., 9.), cx));
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.snapshot(cx).scroll_position(), point(0., 6.));
+    });
+
+    // The limit applies as soon as the position is set, without waiting for a layout,
+    // and to keyboard scrolling as well.
+    cx.update_editor(|editor, cx| {
+        editor.set_scroll_position(point(0., 8.), cx);
+        assert_eq!(editor.scroll_position(cx), point(0., 6.));
+
+        editor.set_scroll_position(point(0., 4.), cx);
+        editor.scroll_screen(&ScrollAmount::Page(1.), cx);
+        assert_eq!(editor.scroll_position(cx), point(0., 6.));
+        editor.scroll_screen(&ScrollAmount::Line(1.), cx);
+        assert_eq!(editor.scroll_position(cx), point(0., 6.));
+    });
+}
+
 #[gpui::test]
 async fn test_autoscroll(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -1989,6 +2072,176 @@ async fn test_newline_comments(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_newline_block_comments(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(4)
+    });
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                block_comment: Some(("/* ".into(), " */".into())),
+                block_comment_line_prefix: Some("* ".into()),
+                ..LanguageConfig::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_override_query("(block_comment) @comment")
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // The end of the comment is moved to its own line.
+    cx.set_state(indoc! {"
+        fn a() {
+            /**ˇ */
+        }
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            /**
+             * ˇ
+             */
+        }
+    "});
+
+    cx.set_state(indoc! {"
+        fn a() {
+            /*
+             * Fooˇ
+             */
+        }
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            /*
+             * Foo
+             * ˇ
+             */
+        }
+    "});
+
+    // Comments aren't continued after they end, or when the modifier is held.
+    cx.set_state(indoc! {"
+        fn a() {
+            /* Foo */ˇ
+        }
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            /* Foo */
+            ˇ
+        }
+    "});
+
+    cx.set_state(indoc! {"
+        fn a() {
+            /*
+             * Fooˇ
+             */
+        }
+    "});
+    cx.update_editor(|e, cx| e.newline_without_comment(&NewlineWithoutComment, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            /*
+             * Foo
+             ˇ
+             */
+        }
+    "});
+}
+
+#[gpui::test]
+async fn test_doc_comment_skeleton(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                line_comments: vec!["// ".into(), "/// ".into()],
+                doc_comment: Some(DocCommentConfig {
+                    trigger: "///".into(),
+                    style: DocCommentStyle::Rustdoc,
+                }),
+                ..LanguageConfig::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_documentation_query(indoc! {r#"
+            (function_item) @item
+            (function_item
+                parameters: (parameters
+                    (parameter
+                        pattern: (identifier) @param))) @item
+            (function_item
+                return_type: (_) @returns) @item
+        "#})
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        impl Counter {
+            //ˇ
+            fn add(&mut self, amount: usize) -> usize {
+                self.count += amount;
+                self.count
+            }
+        }
+    "});
+    cx.executor().run_until_parked();
+
+    cx.update_editor(|editor, cx| {
+        editor.handle_input("/", cx);
+        editor.handle_input("Adds to the count.", cx);
+        editor.move_to_next_snippet_tabstop(cx);
+        editor.handle_input("How much to add.", cx);
+        editor.move_to_next_snippet_tabstop(cx);
+        editor.handle_input("The new count.", cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        impl Counter {
+            /// Adds to the count.
+            ///
+            /// # Arguments
+            ///
+            /// * `amount` - How much to add.
+            ///
+            /// # Returns
+            ///
+            /// The new count.ˇ
+            fn add(&mut self, amount: usize) -> usize {
+                self.count += amount;
+                self.count
+            }
+        }
+    "});
+
+    // The trigger is only expanded above an item that can be documented.
+    cx.set_state(indoc! {"
+        //ˇ
+        struct Counter {
+            count: usize,
+        }
+    "});
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.handle_input("/", cx));
+    cx.assert_editor_state(indoc! {"
+        ///ˇ
+        struct Counter {
+            count: usize,
+        }
+    "});
+}
+
 #[gpui::test]
 fn test_insert_with_old_selections(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3460,6 +3713,91 @@ async fn test_clipboard(cx: &mut gpui::TestAppContext) {
         tˇhe lazy dog"});
 }
 
+#[gpui::test]
+async fn test_masked_editor(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.write_to_clipboard(ClipboardItem::new("clipboard".into()));
+    cx.set_state("«hunter2ˇ»");
+    cx.update_editor(|editor, cx| {
+        editor.set_masked(true, cx);
+        let snapshot = editor.snapshot(cx);
+        let buffer_snapshot = snapshot.buffer_snapshot.clone();
+        let redacted_ranges = editor.redacted_ranges(
+            buffer_snapshot.anchor_before(0)..buffer_snapshot.anchor_after(buffer_snapshot.len()),
+            &snapshot.display_snapshot,
+            cx,
+        );
+        assert_eq!(
+            redacted_ranges,
+            [DisplayPoint::new(0, 0)..DisplayPoint::new(0, 7)]
+        );
+
+        // The masked text can't be copied or cut.
+        editor.copy(&Copy, cx);
+        editor.cut(&Cut, cx);
+    });
+    cx.assert_editor_state("«hunter2ˇ»");
+    assert_eq!(
+        cx.read_from_clipboard().map(|item| item.text().to_owned()),
+        Some("clipboard".into())
+    );
+}
+
+#[gpui::test]
+async fn test_clipboard_history(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let history_texts = |cx: &mut EditorTestContext| {
+        cx.update(|cx| {
+            cx.default_global::<ClipboardHistory>()
+                .entries()
+                .map(|entry| entry.item.text().clone())
+                .collect::<Vec<_>>()
+        })
+    };
+
+    cx.set_state("«oneˇ» two three");
+    cx.update_editor(|e, cx| e.copy(&Copy, cx));
+    cx.set_state("one «twoˇ» three");
+    cx.update_editor(|e, cx| e.cut(&Cut, cx));
+    assert_eq!(history_texts(&mut cx), ["two", "one"]);
+
+    // Copying the same text again moves it to the front instead of duplicating it.
+    cx.set_state("«oneˇ»  three");
+    cx.update_editor(|e, cx| e.copy(&Copy, cx));
+    assert_eq!(history_texts(&mut cx), ["one", "two"]);
+
+    // Pinned entries outlive the entry limit.
+    cx.update(|cx| {
+        let history = cx.default_global::<ClipboardHistory>();
+        let two_id = history.entries().nth(1).unwrap().id;
+        history.toggle_pinned(two_id);
+        history.push(ClipboardItem::new("three".into()), 1);
+    });
+    assert_eq!(history_texts(&mut cx), ["three", "two"]);
+
+    // Pasting an entry from the history leaves the clipboard untouched.
+    let item = cx.update(|cx| {
+        cx.default_global::<ClipboardHistory>()
+            .entries()
+            .nth(1)
+            .unwrap()
+            .item
+            .clone()
+    });
+    cx.set_state("one ˇ three");
+    cx.update_editor(|e, cx| e.paste_item(&item, true, cx));
+    cx.assert_editor_state("one twoˇ three");
+    assert_eq!(
+        cx.update(|cx| cx.read_from_clipboard())
+            .map(|item| item.text().clone()),
+        Some("one".into())
+    );
+}
+
 #[gpui::test]
 async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -3575,56 +3913,206 @@ async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-fn test_select_all(cx: &mut TestAppContext) {
+async fn test_paste_without_formatting(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
 
-    let view = cx.add_window(|cx| {
-        let buffer = MultiBuffer::build_simple("abc\nde\nfgh", cx);
-        build_editor(buffer, cx)
-    });
-    _ = view.update(cx, |view, cx| {
-        view.select_all(&SelectAll, cx);
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            &[DisplayPoint::new(0, 0)..DisplayPoint::new(2, 3)]
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(indoc! {"
+        const a: B = (
+            c(),
+            «d(
+                e,
+            )ˇ»
         );
-    });
+    "});
+    cx.update_editor(|e, cx| e.cut(&Cut, cx));
+
+    // The copied lines keep their original indentation.
+    cx.set_state(indoc! {"
+        ˇ
+        const a: B = (
+            c(),
+        );
+    "});
+    cx.update_editor(|e, cx| e.paste_without_formatting(&PasteWithoutFormatting, cx));
+    cx.assert_editor_state(indoc! {"
+        d(
+                e,
+            )ˇ
+        const a: B = (
+            c(),
+        );
+    "});
 }
 
 #[gpui::test]
-fn test_select_line(cx: &mut TestAppContext) {
+async fn test_paste_lines_into_multiple_selections(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
 
-    let view = cx.add_window(|cx| {
-        let buffer = MultiBuffer::build_simple(&sample_text(6, 5, 'a'), cx);
-        build_editor(buffer, cx)
-    });
-    _ = view.update(cx, |view, cx| {
-        view.change_selections(None, cx, |s| {
-            s.select_display_ranges([
-                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
-                DisplayPoint::new(0, 2)..DisplayPoint::new(0, 2),
-                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 0),
-                DisplayPoint::new(4, 2)..DisplayPoint::new(4, 2),
-            ])
-        });
-        view.select_line(&SelectLine, cx);
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            vec![
-                DisplayPoint::new(0, 0)..DisplayPoint::new(2, 0),
-                DisplayPoint::new(4, 0)..DisplayPoint::new(5, 0),
-            ]
-        );
-    });
+    let mut cx = EditorTestContext::new(cx).await;
 
-    _ = view.update(cx, |view, cx| {
-        view.select_line(&SelectLine, cx);
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            vec![
-                DisplayPoint::new(0, 0)..DisplayPoint::new(3, 0),
-                DisplayPoint::new(4, 0)..DisplayPoint::new(5, 5),
+    // Text copied from another application has one line per selection.
+    cx.update_editor(|_, cx| cx.write_to_clipboard(ClipboardItem::new("one\ntwo\nthree\n".into())));
+    cx.set_state(indoc! {"
+        a ˇ
+        b ˇ
+        c ˇ
+    "});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        a oneˇ
+        b twoˇ
+        c threeˇ
+    "});
+
+    // When the line count doesn't match, the whole text is pasted at each selection.
+    cx.update_editor(|_, cx| cx.write_to_clipboard(ClipboardItem::new("one\ntwo".into())));
+    cx.set_state(indoc! {"
+        a ˇ
+        b ˇ
+        c ˇ
+    "});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        a one
+        twoˇ
+        b one
+        twoˇ
+        c one
+        twoˇ
+    "});
+
+    // Windows line endings are normalized before the lines are distributed.
+    cx.update_editor(|_, cx| {
+        cx.write_to_clipboard(ClipboardItem::new("one\r\ntwo\r\nthree\r\n".into()))
+    });
+    cx.set_state(indoc! {"
+        a ˇ
+        b ˇ
+        c ˇ
+    "});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        a oneˇ
+        b twoˇ
+        c threeˇ
+    "});
+}
+
+#[gpui::test]
+async fn test_paste_reindents_lines_for_scope(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(4)
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_indents_query(r#"(_ "(" ")" @end) @indent"#)
+        .unwrap(),
+    );
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Each line is indented for its scope, not just relative to the first line.
+    cx.update_editor(|_, cx| {
+        cx.write_to_clipboard(ClipboardItem::new("d(\r\ne,\r\n        f\r\n)".into()))
+    });
+    cx.set_state(indoc! {"
+        const a: B = (
+            c(),
+            ˇ
+        );
+    "});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        const a: B = (
+            c(),
+            d(
+                e,
+                f
+            )ˇ
+        );
+    "});
+
+    // Pasting without formatting keeps the lines as they are.
+    cx.set_state(indoc! {"
+        const a: B = (
+            c(),
+            ˇ
+        );
+    "});
+    cx.update_editor(|e, cx| e.paste_without_formatting(&PasteWithoutFormatting, cx));
+    cx.assert_editor_state(indoc! {"
+        const a: B = (
+            c(),
+            d(
+        e,
+                f
+        )ˇ
+        );
+    "});
+}
+
+#[gpui::test]
+fn test_select_all(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("abc\nde\nfgh", cx);
+        build_editor(buffer, cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        view.select_all(&SelectAll, cx);
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            &[DisplayPoint::new(0, 0)..DisplayPoint::new(2, 3)]
+        );
+    });
+}
+
+#[gpui::test]
+fn test_select_line(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(6, 5, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
+                DisplayPoint::new(0, 2)..DisplayPoint::new(0, 2),
+                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 0),
+                DisplayPoint::new(4, 2)..DisplayPoint::new(4, 2),
+            ])
+        });
+        view.select_line(&SelectLine, cx);
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            vec![
+                DisplayPoint::new(0, 0)..DisplayPoint::new(2, 0),
+                DisplayPoint::new(4, 0)..DisplayPoint::new(5, 0),
+            ]
+        );
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.select_line(&SelectLine, cx);
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            vec![
+                DisplayPoint::new(0, 0)..DisplayPoint::new(3, 0),
+                DisplayPoint::new(4, 0)..DisplayPoint::new(5, 5),
             ]
         );
     });
@@ -4854,6 +5342,78 @@ async fn test_autoclose_with_overrides(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_auto_close_tags(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.auto_close_tags = Some(true)
+    });
+    {
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state(indoc! {r#"
+            <div class="a > b"ˇ
+            <br ˇ
+            <img/ˇ
+            <spanˇ</span>
+            a <ˇ
+        "#});
+        cx.update_editor(|editor, cx| editor.handle_input(">", cx));
+        cx.assert_editor_state(indoc! {r#"
+            <div class="a > b">ˇ</div>
+            <br >ˇ
+            <img/>ˇ
+            <span>ˇ</span>
+            a <>ˇ
+        "#});
+
+        // Closing tags aren't inserted after text that isn't an opening tag.
+        cx.set_state("<p>text</pˇ");
+        cx.update_editor(|editor, cx| editor.handle_input(">", cx));
+        cx.assert_editor_state("<p>text</p>ˇ");
+    }
+
+    update_test_language_settings(cx, |settings| {
+        settings.defaults.auto_close_tags = Some(false);
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("<pˇ");
+    cx.update_editor(|editor, cx| editor.handle_input(">", cx));
+    cx.assert_editor_state("<p>ˇ");
+}
+
+#[gpui::test]
+async fn test_linked_edits(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("<divˇ>text</div>");
+    cx.update_editor(|editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor.linked_editing_ranges.ranges = vec![
+            snapshot.anchor_before(1)..snapshot.anchor_after(4),
+            snapshot.anchor_before(11)..snapshot.anchor_after(14),
+        ];
+    });
+
+    cx.update_editor(|editor, cx| editor.handle_input("s", cx));
+    cx.assert_editor_state("<divsˇ>text</divs>");
+
+    cx.update_editor(|editor, cx| {
+        editor.backspace(&Backspace, cx);
+        editor.backspace(&Backspace, cx);
+    });
+    cx.assert_editor_state("<diˇ>text</di>");
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("<divˇ>text</div>");
+
+    // Typing a space before an attribute unlinks the tag names.
+    cx.update_editor(|editor, cx| {
+        editor.handle_input(" ", cx);
+        editor.handle_input("id", cx);
+    });
+    cx.assert_editor_state("<div idˇ>text</div>");
+}
+
 #[gpui::test]
 async fn test_surround_with_pair(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -5919,6 +6479,181 @@ async fn test_completion(cx: &mut gpui::TestAppContext) {
     apply_additional_edits.await.unwrap();
 }
 
+#[gpui::test]
+async fn test_scroll_completion_documentation(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                trigger_characters: Some(vec![".".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state("oneˇ");
+    cx.simulate_keystroke(".");
+    handle_completion_request(
+        &mut cx,
+        "one.|<>",
+        vec!["first_completion", "second_completion"],
+    )
+    .await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+
+    cx.update_editor(|editor, cx| {
+        let line_height = editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size());
+        let scroll_handle = match editor.context_menu.read().as_ref() {
+            Some(ContextMenu::Completions(menu)) => menu.documentation_scroll_handle.clone(),
+            _ => panic!("expected the completions menu to be shown"),
+        };
+
+        editor.scroll_popover_down(&ScrollPopoverDown, cx);
+        assert_eq!(scroll_handle.offset().y, -line_height * 3.);
+
+        // Selecting another completion shows its documentation from the top.
+        editor.context_menu_next(&Default::default(), cx);
+        assert_eq!(scroll_handle.offset().y, Pixels::ZERO);
+    });
+}
+
+#[gpui::test]
+async fn test_completion_commit_characters_and_following_word(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions::default()),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let mut requests = cx.handle_request::<lsp::request::Completion, _, _>(|_, _, _| async move {
+        Ok(Some(lsp::CompletionResponse::Array(vec![
+            lsp::CompletionItem {
+                label: "format".to_string(),
+                text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                    range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 3)),
+                    new_text: "format".to_string(),
+                })),
+                commit_characters: Some(vec![".".to_string()]),
+                ..Default::default()
+            },
+        ])))
+    });
+
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.completion_commit_characters = Some(true);
+                settings.completion_replaces_following_word = Some(false);
+            });
+        })
+    });
+    cx.set_state("fˇoo");
+    cx.update_editor(|editor, cx| {
+        editor.show_completions(&ShowCompletions, cx);
+    });
+    requests.next().await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.simulate_keystroke(".");
+    cx.assert_editor_state("format.ˇoo");
+    assert!(cx.editor(|e, _| e.context_menu.read().is_none()));
+
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.completion_commit_characters = Some(false);
+                settings.completion_replaces_following_word = Some(true);
+            });
+        })
+    });
+    cx.set_state("fˇoo");
+    cx.update_editor(|editor, cx| {
+        editor.show_completions(&ShowCompletions, cx);
+    });
+    requests.next().await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.update_editor(|editor, cx| {
+        editor
+            .confirm_completion(&ConfirmCompletion::default(), cx)
+            .unwrap()
+    })
+    .detach();
+    cx.assert_editor_state("formatˇ");
+}
+
+#[gpui::test]
+async fn test_completion_server_commit_characters(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                all_commit_characters: Some(vec![";".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let mut requests = cx.handle_request::<lsp::request::Completion, _, _>(|_, _, _| async move {
+        Ok(Some(lsp::CompletionResponse::Array(vec![
+            lsp::CompletionItem {
+                label: "format".to_string(),
+                text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                    range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 1)),
+                    new_text: "format".to_string(),
+                })),
+                ..Default::default()
+            },
+        ])))
+    });
+
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.completion_commit_characters = Some(true);
+            });
+        })
+    });
+
+    // Items without commit characters of their own use the server's.
+    cx.set_state("fˇ");
+    cx.update_editor(|editor, cx| {
+        editor.show_completions(&ShowCompletions, cx);
+    });
+    requests.next().await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.simulate_keystroke(";");
+    cx.assert_editor_state("format;ˇ");
+
+    // Other characters are typed without accepting the completion.
+    cx.set_state("fˇ");
+    cx.update_editor(|editor, cx| {
+        editor.show_completions(&ShowCompletions, cx);
+    });
+    requests.next().await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.simulate_keystroke(".");
+    cx.assert_editor_state("f.ˇ");
+}
+
 #[gpui::test]
 async fn test_toggle_comment(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7081,6 +7816,158 @@ async fn test_following_with_multiple_excerpts(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_apply_quick_fix_to_all_excerpts(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        fn main() {
+            let ˇa = 1;
+            let b = 2;
+            let c: u8 = -1;
+        }
+    "});
+    let diagnostic = |row, columns: Range<u32>, code: &str| lsp::Diagnostic {
+        range: lsp::Range::new(
+            lsp::Position::new(row, columns.start),
+            lsp::Position::new(row, columns.end),
+        ),
+        severity: Some(lsp::DiagnosticSeverity::WARNING),
+        code: Some(lsp::NumberOrString::String(code.into())),
+        message: format!("{code} diagnostic"),
+        ..Default::default()
+    };
+    cx.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: cx.buffer_lsp_url.clone(),
+        version: None,
+        diagnostics: vec![
+            diagnostic(1, 8..9, "unused_variables"),
+            diagnostic(2, 8..9, "unused_variables"),
+            diagnostic(3, 16..18, "E0600"),
+        ],
+    });
+    cx.handle_request::<lsp::request::CodeActionRequest, _, _>(|url, params, _| async move {
+        let edit = lsp::TextEdit::new(
+            lsp::Range::new(params.range.start, params.range.start),
+            "_".into(),
+        );
+        Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+            lsp::CodeAction {
+                title: "Prefix with an underscore".into(),
+                kind: Some(lsp::CodeActionKind::QUICKFIX),
+                edit: Some(lsp::WorkspaceEdit {
+                    changes: Some([(url, vec![edit])].into_iter().collect()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )]))
+    });
+    cx.executor().advance_clock(CODE_ACTIONS_DEBOUNCE_TIMEOUT);
+    cx.run_until_parked();
+
+    // Only the diagnostics of the same kind as the one at the cursor are fixed.
+    cx.update_editor(|editor, cx| {
+        editor
+            .apply_quick_fix_to_all_excerpts(&ApplyQuickFixToAllExcerpts, cx)
+            .unwrap()
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        cx.update_editor(|editor, cx| editor.text(cx)),
+        indoc! {"
+            fn main() {
+                let _a = 1;
+                let _b = 2;
+                let c: u8 = -1;
+            }
+        "}
+    );
+}
+
+#[gpui::test]
+async fn test_remove_unused_imports(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        use std::fmt;
+        use std::io;
+
+        fn main() {
+            io::stdout();ˇ
+        }
+    "});
+
+    // Without a source action for it, unused imports can't be removed.
+    let mut requests =
+        cx.handle_request::<lsp::request::CodeActionRequest, _, _>(|_, _, _| async move {
+            Ok(Some(Vec::new()))
+        });
+    let task = cx
+        .update_editor(|editor, cx| editor.remove_unused_imports(&RemoveUnusedImports, cx))
+        .unwrap();
+    requests.next().await;
+    assert!(task.await.is_err());
+
+    // The action is looked up in the whole buffer, and its kind may have a language suffix.
+    let mut requests =
+        cx.handle_request::<lsp::request::CodeActionRequest, _, _>(|url, params, _| async move {
+            assert_eq!(params.range.start, lsp::Position::new(0, 0));
+            assert_eq!(params.range.end, lsp::Position::new(6, 0));
+            let edit = lsp::TextEdit::new(
+                lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(1, 0)),
+                String::new(),
+            );
+            Ok(Some(vec![
+                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                    title: "Organize imports".into(),
+                    kind: Some(lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+                    ..Default::default()
+                }),
+                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                    title: "Remove unused imports".into(),
+                    kind: Some("source.removeUnusedImports.rs".into()),
+                    edit: Some(lsp::WorkspaceEdit {
+                        changes: Some([(url, vec![edit])].into_iter().collect()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            ]))
+        });
+    let task = cx
+        .update_editor(|editor, cx| editor.remove_unused_imports(&RemoveUnusedImports, cx))
+        .unwrap();
+    requests.next().await;
+    task.await.unwrap();
+    cx.assert_editor_state(indoc! {"
+        use std::io;
+
+        fn main() {
+            io::stdout();ˇ
+        }
+    "});
+}
+
 #[gpui::test]
 async fn go_to_prev_overlapping_diagnostic(
     executor: BackgroundExecutor,
@@ -8101,6 +8988,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
                 initialization_options: Some(json!({
                     "some other init value": false
                 })),
+                settings: None,
             },
         );
     });
@@ -8118,6 +9006,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
+                settings: None,
             },
         );
     });
@@ -8135,6 +9024,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
+                settings: None,
             },
         );
     });
@@ -8150,6 +9040,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             language_server_name.into(),
             LspSettings {
                 initialization_options: None,
+                settings: None,
             },
         );
     });
@@ -8253,6 +9144,82 @@ async fn test_completions_with_additional_edits(cx: &mut gpui::TestAppContext) {
     cx.assert_editor_state(indoc! {"fn main() { let a = Some(2)ˇ; }"});
 }
 
+#[gpui::test]
+async fn test_completions_with_lazily_resolved_additional_edits(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                resolve_provider: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        fn main() {
+            let map = HashMˇ
+        }
+    "});
+
+    let mut request = cx.handle_request::<lsp::request::Completion, _, _>(|_, _, _| async move {
+        Ok(Some(lsp::CompletionResponse::List(lsp::CompletionList {
+            is_incomplete: false,
+            item_defaults: Some(lsp::CompletionListItemDefaults {
+                data: Some(json!({ "id": 1 })),
+                ..Default::default()
+            }),
+            items: vec![lsp::CompletionItem {
+                label: "HashMap".into(),
+                ..Default::default()
+            }],
+        })))
+    });
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    request.next().await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+
+    let apply_additional_edits = cx.update_editor(|editor, cx| {
+        editor
+            .confirm_completion(&ConfirmCompletion::default(), cx)
+            .unwrap()
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let map = HashMapˇ
+        }
+    "});
+
+    // The import is only computed when the item is resolved, which requires the
+    // data from the completion list's defaults.
+    cx.handle_request::<lsp::request::ResolveCompletionItem, _, _>(|_, item, _| async move {
+        assert_eq!(item.data, Some(json!({ "id": 1 })));
+        Ok(lsp::CompletionItem {
+            additional_text_edits: Some(vec![lsp::TextEdit::new(
+                lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                "use std::collections::HashMap;\n\n".to_string(),
+            )]),
+            ..item
+        })
+    })
+    .next()
+    .await
+    .unwrap();
+    apply_additional_edits.await.unwrap();
+    cx.assert_editor_state(indoc! {"
+        use std::collections::HashMap;
+
+        fn main() {
+            let map = HashMapˇ
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_completions_in_languages_with_extra_word_characters(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -8426,6 +9393,231 @@ async fn test_document_format_with_prettier(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_open_blame_commit(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/repo",
+        json!({
+            ".git": {},
+            "main.rs": "one\ntwo\nthree\n",
+        }),
+    )
+    .await;
+    fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+        let path = RepoPath::from(Path::new("main.rs"));
+        state
+            .head_contents
+            .insert(path.clone(), "one\ntwo\nthree\n".into());
+        state
+            .blames
+            .insert(path, vec!["aaa".into(), "bbb".into(), "ccc".into()]);
+        for sha in ["aaa", "bbb", "ccc"] {
+            state.commits.insert(
+                sha.into(),
+                project::repository::CommitDetails {
+                    sha: sha.into(),
+                    message: format!("Commit {sha}"),
+                    ..Default::default()
+                },
+            );
+        }
+    });
+
+    let project = Project::test(fs, ["/repo".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    cx.executor().run_until_parked();
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let editor = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_path((worktree_id, "main.rs"), None, true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    assert!(editor.update(cx, |editor, cx| editor.is_in_git_repository(cx)));
+
+    let active_commit_summary = |cx: &mut VisualTestContext| {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .active_item(cx)
+                    .filter(|item| item.downcast::<CommitView>().is_some())
+                    .and_then(|item| item.tab_tooltip_text(cx))
+            })
+            .unwrap()
+    };
+
+    // Unsaved lines above the cursor don't change which commit its line is blamed on.
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", cx);
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 1)..Point::new(2, 1)])
+        });
+        editor.open_git_blame_commit(&OpenGitBlameCommit, cx);
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(active_commit_summary(cx), Some("Commit bbb".into()));
+
+    // Unsaved lines have no commit to open.
+    workspace
+        .update(cx, |workspace, cx| workspace.activate_item(&editor, cx))
+        .unwrap();
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.open_git_blame_commit(&OpenGitBlameCommit, cx);
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(active_commit_summary(cx), None);
+}
+
+#[gpui::test]
+async fn test_inline_blame(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/repo",
+        json!({
+            ".git": {},
+            "main.rs": "one\r\ntwo\r\nthree\r\n",
+        }),
+    )
+    .await;
+    fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+        let path = RepoPath::from(Path::new("main.rs"));
+        state.head_sha = Some("ccc".into());
+        state
+            .head_contents
+            .insert(path.clone(), "one\r\ntwo\r\nthree\r\n".into());
+        state
+            .blames
+            .insert(path, vec!["aaa".into(), "bbb".into(), "ccc".into()]);
+        for sha in ["aaa", "bbb", "ccc"] {
+            state.commits.insert(
+                sha.into(),
+                project::repository::CommitDetails {
+                    sha: sha.into(),
+                    author_name: "Ada".into(),
+                    message: format!("Commit {sha}"),
+                    diff: "diff".into(),
+                    ..Default::default()
+                },
+            );
+        }
+    });
+
+    let project = Project::test(fs.clone(), ["/repo".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    cx.executor().run_until_parked();
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let editor = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_path((worktree_id, "main.rs"), None, true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let blamed_commit = |cx: &mut VisualTestContext| {
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.executor().run_until_parked();
+        editor.update(cx, |editor, _| {
+            editor.inline_blame.blame().map(|blame| {
+                (
+                    blame.row,
+                    blame.commit.sha.clone(),
+                    blame.commit.diff.clone(),
+                )
+            })
+        })
+    };
+
+    // The file's CRLF line endings at HEAD don't keep its rows from being blamed, and
+    // the blamed commit is loaded without its diff.
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(1, 1)..Point::new(1, 1)])
+        });
+    });
+    assert_eq!(
+        blamed_commit(cx),
+        Some((1, "bbb".to_string(), String::new()))
+    );
+
+    // Edited lines aren't annotated.
+    editor.update(cx, |editor, cx| editor.handle_input("X", cx));
+    assert_eq!(blamed_commit(cx), None);
+
+    // Moving to another line blames that line instead.
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+        });
+    });
+    assert_eq!(
+        blamed_commit(cx),
+        Some((2, "ccc".to_string(), String::new()))
+    );
+
+    // The file is only blamed again once HEAD moves.
+    let move_cursor = |column, cx: &mut VisualTestContext| {
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(2, column)..Point::new(2, column)])
+            });
+        });
+    };
+    fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+        state.blames.insert(
+            RepoPath::from(Path::new("main.rs")),
+            vec!["aaa".into(), "aaa".into(), "aaa".into()],
+        );
+    });
+    move_cursor(1, cx);
+    assert_eq!(
+        blamed_commit(cx),
+        Some((2, "ccc".to_string(), String::new()))
+    );
+    fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+        state.head_sha = Some("ddd".into());
+    });
+    move_cursor(2, cx);
+    assert_eq!(
+        blamed_commit(cx),
+        Some((2, "aaa".to_string(), String::new()))
+    );
+
+    // Clicking the annotation opens its commit.
+    editor.update(cx, |editor, cx| editor.open_git_commit("ccc".into(), cx));
+    cx.executor().run_until_parked();
+    let active_commit_summary = workspace
+        .update(cx, |workspace, cx| {
+            workspace
+                .active_item(cx)
+                .filter(|item| item.downcast::<CommitView>().is_some())
+                .and_then(|item| item.tab_tooltip_text(cx))
+        })
+        .unwrap();
+    assert_eq!(active_commit_summary, Some("Commit ccc".into()));
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(row as u32, column as u32);
     point..point
@@ -1,20 +1,20 @@
 use crate::{
     display_map::{
-        BlockContext, BlockStyle, DisplaySnapshot, FoldStatus, HighlightedChunk, ToDisplayPoint,
-        TransformBlock,
+        estimated_byte_advance, BlockContext, BlockStyle, DisplaySnapshot, FoldStatus,
+        HighlightedChunk, ToDisplayPoint, TransformBlock,
     },
     editor_settings::ShowScrollbar,
-    git::{diff_hunk_to_display, DisplayDiffHunk},
+    git::{diff_hunk_to_display, inline_blame::render_inline_blame, DisplayDiffHunk},
     hover_popover::{
         self, hover_at, HOVER_POPOVER_GAP, MIN_POPOVER_CHARACTER_WIDTH, MIN_POPOVER_LINE_HEIGHT,
     },
     items::BufferSearchHighlights,
     mouse_context_menu,
-    scroll::scroll_amount::ScrollAmount,
-    CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode,
-    EditorSettings, EditorSnapshot, EditorStyle, HalfPageDown, HalfPageUp, HoveredCursor, LineDown,
-    LineUp, OpenExcerpts, PageDown, PageUp, Point, SelectPhase, Selection, SoftWrap, ToPoint,
-    CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
+    scroll::{scroll_amount::ScrollAmount, Autoscroll},
+    signature_help, CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite,
+    Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle, HalfPageDown, HalfPageUp,
+    HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp, Paste, Point, SelectPhase,
+    Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
 };
 use anyhow::Result;
 use collections::{BTreeMap, HashMap};
@@ -52,7 +52,7 @@ use theme::{ActiveTheme, PlayerColor};
 use ui::prelude::*;
 use ui::{h_flex, ButtonLike, ButtonStyle, IconButton, Tooltip};
 use util::ResultExt;
-use workspace::item::Item;
+use workspace::{item::Item, notifications::NotifyTaskExt};
 
 struct SelectionLayout {
     head: DisplayPoint,
@@ -139,6 +139,8 @@ impl EditorElement {
         });
 
         crate::rust_analyzer_ext::apply_related_actions(view, cx);
+        crate::json_tools::apply_related_actions(view, cx);
+        crate::jsx_tools::apply_related_actions(view, cx);
         register_action(view, cx, Editor::move_left);
         register_action(view, cx, Editor::move_right);
         register_action(view, cx, Editor::move_down);
@@ -151,6 +153,7 @@ impl EditorElement {
         register_action(view, cx, Editor::newline);
         register_action(view, cx, Editor::newline_above);
         register_action(view, cx, Editor::newline_below);
+        register_action(view, cx, Editor::newline_without_comment);
         register_action(view, cx, Editor::backspace);
         register_action(view, cx, Editor::delete);
         register_action(view, cx, Editor::tab);
@@ -184,6 +187,7 @@ impl EditorElement {
         register_action(view, cx, Editor::cut);
         register_action(view, cx, Editor::copy);
         register_action(view, cx, Editor::paste);
+        register_action(view, cx, Editor::paste_without_formatting);
         register_action(view, cx, Editor::undo);
         register_action(view, cx, Editor::redo);
         register_action(view, cx, Editor::move_page_up);
@@ -273,12 +277,18 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
+        register_action(view, cx, Editor::scroll_popover_up);
+        register_action(view, cx, Editor::scroll_popover_down);
+        register_action(view, cx, signature_help::show_signature_help);
+        register_action(view, cx, signature_help::signature_help_next);
+        register_action(view, cx, signature_help::signature_help_prev);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
         register_action(view, cx, Editor::copy_highlight_json);
         register_action(view, cx, Editor::copy_permalink_to_line);
         register_action(view, cx, Editor::open_permalink_to_line);
+        register_action(view, cx, Editor::open_git_blame_commit);
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.format(action, cx) {
                 task.detach_and_log_err(cx);
@@ -297,7 +307,28 @@ impl EditorElement {
         });
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.confirm_code_action(action, cx) {
-                task.detach_and_log_err(cx);
+                task.detach_and_notify_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.apply_quick_fix_to_all_excerpts(action, cx) {
+                task.detach_and_notify_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.remove_unused_imports(action, cx) {
+                task.detach_and_notify_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.sort_imports(action, cx) {
+                task.detach_and_notify_err(cx);
             } else {
                 cx.propagate();
             }
@@ -429,10 +460,11 @@ impl EditorElement {
                 cx,
             );
         } else {
+            let multi_cursor_modifier = EditorSettings::get_global(cx).multi_cursor_modifier;
             editor.select(
                 SelectPhase::Begin {
                     position,
-                    add: modifiers.alt,
+                    add: multi_cursor_modifier.adds_cursor(&modifiers),
                     click_count,
                 },
                 cx,
@@ -442,6 +474,38 @@ impl EditorElement {
         cx.stop_propagation();
     }
 
+    fn mouse_middle_down(
+        editor: &mut Editor,
+        event: &MouseDownEvent,
+        position_map: &PositionMap,
+        text_bounds: Bounds<Pixels>,
+        stacking_order: &StackingOrder,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        if !cfg!(target_os = "linux") || !EditorSettings::get_global(cx).middle_click_paste {
+            return;
+        }
+        if cx.default_prevented()
+            || !text_bounds.contains(&event.position)
+            || !cx.was_top_layer(&event.position, stacking_order)
+        {
+            return;
+        }
+
+        let point_for_position = position_map.point_for_position(text_bounds, event.position);
+        editor.select(
+            SelectPhase::Begin {
+                position: point_for_position.previous_valid,
+                add: false,
+                click_count: 1,
+            },
+            cx,
+        );
+        editor.select(SelectPhase::End, cx);
+        editor.paste(&Paste, cx);
+        cx.stop_propagation();
+    }
+
     fn mouse_right_down(
         editor: &mut Editor,
         event: &MouseDownEvent,
@@ -478,9 +542,10 @@ impl EditorElement {
             editor.select(SelectPhase::End, cx);
         }
 
+        let multi_cursor_modifier = EditorSettings::get_global(cx).multi_cursor_modifier;
         if interactive_bounds.visibly_contains(&event.position, cx)
             && !pending_nonempty_selections
-            && event.modifiers.command
+            && multi_cursor_modifier.goes_to_definition(&event.modifiers)
             && text_bounds.contains(&event.position)
             && cx.was_top_layer(&event.position, stacking_order)
         {
@@ -673,6 +738,19 @@ impl EditorElement {
                 cx.paint_quad(fill(Bounds { origin, size }, highlighted_line_bg));
             }
 
+            for (rows, background) in &layout.decorated_rows {
+                let origin = point(
+                    bounds.origin.x,
+                    bounds.origin.y + (layout.position_map.line_height * rows.start as f32)
+                        - scroll_top,
+                );
+                let size = size(
+                    bounds.size.width,
+                    layout.position_map.line_height * rows.len() as f32,
+                );
+                cx.paint_quad(fill(Bounds { origin, size }, *background));
+            }
+
             let scroll_left =
                 layout.position_map.snapshot.scroll_position().x * layout.position_map.em_width;
 
@@ -758,6 +836,20 @@ impl EditorElement {
                 }
             }
 
+            for (row, icon) in layout.decoration_icons.drain(..) {
+                let mut icon = icon.into_any_element();
+                let available_space = size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                );
+                let icon_size = icon.measure(available_space, cx);
+
+                let x = ((layout.gutter_padding + layout.gutter_margin) - icon_size.width) / 2.;
+                let y =
+                    row as f32 * line_height - scroll_top + (line_height - icon_size.height) / 2.;
+                icon.draw(bounds.origin + point(x, y), available_space, cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.take() {
                 let mut button = indicator.button.into_any_element();
                 let available_space = size(
@@ -932,7 +1024,6 @@ impl EditorElement {
                             .position_map
                             .line_layouts
                             .get((row - layout.visible_display_row_range.start) as usize)
-                            .map(|l| &l.line)
                         else {
                             continue;
                         };
@@ -1034,15 +1125,24 @@ impl EditorElement {
                             invisible_display_ranges.push(selection.range.clone());
                         }
 
-                        if !selection.is_local || self.editor.read(cx).show_local_cursors(cx) {
+                        let cursor_opacity = if selection.is_local {
+                            self.editor.read(cx).local_cursor_opacity(cx)
+                        } else {
+                            1.
+                        };
+                        if selection.is_newest && cursor_opacity > 0. && cursor_opacity < 1. {
+                            // Keep repainting while the cursor fades in or out.
+                            let editor = self.editor.clone();
+                            cx.on_next_frame(move |cx| editor.update(cx, |_, cx| cx.notify()));
+                        }
+                        if cursor_opacity > 0. {
                             let cursor_position = selection.head;
                             if layout
                                 .visible_display_row_range
                                 .contains(&cursor_position.row())
                             {
                                 let cursor_row_layout = &layout.position_map.line_layouts
-                                    [(cursor_position.row() - start_row) as usize]
-                                    .line;
+                                    [(cursor_position.row() - start_row) as usize];
                                 let cursor_column = cursor_position.column() as usize;
 
                                 let cursor_character_x =
@@ -1070,7 +1170,7 @@ impl EditorElement {
                                             cx.text_system()
                                                 .shape_line(
                                                     text,
-                                                    cursor_row_layout.font_size,
+                                                    cursor_row_layout.line.font_size,
                                                     &[TextRun {
                                                         len,
                                                         font: self.style.text.font(),
@@ -1101,8 +1201,10 @@ impl EditorElement {
                                     });
                                 }
 
+                                let mut color = selection_style.cursor;
+                                color.fade_out(1. - cursor_opacity);
                                 cursors.push(Cursor {
-                                    color: selection_style.cursor,
+                                    color,
                                     block_width,
                                     origin: point(x, y),
                                     line_height: layout.position_map.line_height,
@@ -1138,6 +1240,24 @@ impl EditorElement {
 
                 cx.with_z_index(0, |cx| self.paint_redactions(text_bounds, &layout, cx));
 
+                if let Some((row, mut inline_blame)) = layout.inline_blame.take() {
+                    let line_height = layout.position_map.line_height;
+                    let line_layout = &layout.position_map.line_layouts[(row - start_row) as usize];
+                    let available_space = size(
+                        AvailableSpace::MinContent,
+                        AvailableSpace::Definite(line_height),
+                    );
+                    let inline_blame_size = inline_blame.measure(available_space, cx);
+                    let x = line_layout.width()
+                        + INLINE_BLAME_PADDING_EM_WIDTHS * layout.position_map.em_width
+                        - layout.position_map.scroll_position.x;
+                    let y = row as f32 * line_height - layout.position_map.scroll_position.y
+                        + (line_height - inline_blame_size.height) / 2.;
+                    cx.with_z_index(1, |cx| {
+                        inline_blame.draw(content_origin + point(x, y), available_space, cx)
+                    });
+                }
+
                 cx.with_z_index(1, |cx| {
                     for cursor in cursors {
                         cursor.paint(content_origin, cx);
@@ -1186,7 +1306,7 @@ impl EditorElement {
             let context_menu_size = context_menu.measure(available_space, cx);
 
             let cursor_row_layout =
-                &layout.position_map.line_layouts[(position.row() - start_row) as usize].line;
+                &layout.position_map.line_layouts[(position.row() - start_row) as usize];
             let x = cursor_row_layout.x_for_index(position.column() as usize)
                 - layout.position_map.scroll_position.x;
             let y = (position.row() + 1) as f32 * layout.position_map.line_height
@@ -1213,7 +1333,7 @@ impl EditorElement {
 
             // This is safe because we check on layout whether the required row is available
             let hovered_row_layout =
-                &layout.position_map.line_layouts[(position.row() - start_row) as usize].line;
+                &layout.position_map.line_layouts[(position.row() - start_row) as usize];
 
             // Minimum required size: Take the first popover, and add 1.5 times the minimum popover
             // height. This is the size we will use to decide whether to render popovers above or below
@@ -1331,6 +1451,7 @@ impl EditorElement {
         let thumb_bottom = y_for_row(row_range.end) + first_row_y_offset;
         let track_bounds = Bounds::from_corners(point(left, top), point(right, bottom));
         let thumb_bounds = Bounds::from_corners(point(left, thumb_top), point(right, thumb_bottom));
+        let mut markers = Vec::new();
 
         if layout.show_scrollbars {
             cx.paint_quad(quad(
@@ -1364,6 +1485,11 @@ impl EditorElement {
                         end_y = start_y + px(1.);
                     }
                     let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
+                    markers.push(ScrollbarMarker {
+                        top: start_y,
+                        bottom: end_y,
+                        target: *range.start(),
+                    });
                     cx.paint_quad(quad(
                         bounds,
                         Corners::default(),
@@ -1401,6 +1527,11 @@ impl EditorElement {
                         end_y = start_y + px(1.);
                     }
                     let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
+                    markers.push(ScrollbarMarker {
+                        top: start_y,
+                        bottom: end_y,
+                        target: start_display,
+                    });
 
                     cx.paint_quad(quad(
                         bounds,
@@ -1440,6 +1571,12 @@ impl EditorElement {
                     }
                     let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
 
+                    markers.push(ScrollbarMarker {
+                        top: start_y,
+                        bottom: end_y,
+                        target: start_display,
+                    });
+
                     let color = match hunk.status() {
                         DiffHunkStatus::Added => cx.theme().status().created,
                         DiffHunkStatus::Modified => cx.theme().status().modified,
@@ -1497,6 +1634,12 @@ impl EditorElement {
                     }
                     let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
 
+                    markers.push(ScrollbarMarker {
+                        top: start_y,
+                        bottom: end_y,
+                        target: start_display,
+                    });
+
                     let color = match diagnostic.diagnostic.severity {
                         DiagnosticSeverity::ERROR => cx.theme().status().error,
                         DiagnosticSeverity::WARNING => cx.theme().status().warning,
@@ -1518,6 +1661,29 @@ impl EditorElement {
                 }
             }
 
+            for (cursor, color) in &layout.scrollbar_cursors {
+                let start_y = y_for_row(cursor.row() as f32);
+                let end_y = start_y + px(2.).max(row_height);
+                let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
+                markers.push(ScrollbarMarker {
+                    top: start_y,
+                    bottom: end_y,
+                    target: *cursor,
+                });
+                cx.paint_quad(quad(
+                    bounds,
+                    Corners::default(),
+                    *color,
+                    Edges {
+                        top: Pixels::ZERO,
+                        right: px(1.),
+                        bottom: Pixels::ZERO,
+                        left: px(1.),
+                    },
+                    cx.theme().colors().scrollbar_thumb_border,
+                ));
+            }
+
             cx.paint_quad(quad(
                 thumb_bounds,
                 Corners::default(),
@@ -1602,7 +1768,12 @@ impl EditorElement {
                             editor.scroll_manager.set_is_dragging_scrollbar(true, cx);
 
                             let y = event.position.y;
-                            if y < thumb_top || thumb_bottom < y {
+                            if let Some(target) = scrollbar_marker_at(&markers, y) {
+                                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                    s.select_display_ranges([target..target])
+                                });
+                                editor.scroll_manager.set_is_dragging_scrollbar(false, cx);
+                            } else if y < thumb_top || thumb_bottom < y {
                                 let center_row =
                                     ((y - top) * max_row as f32 / height).round() as u32;
                                 let top_row = center_row
@@ -1654,7 +1825,7 @@ impl EditorElement {
                     .into_iter()
                     .map(|row| {
                         let line_layout =
-                            &layout.position_map.line_layouts[(row - start_row) as usize].line;
+                            &layout.position_map.line_layouts[(row - start_row) as usize];
                         HighlightedRangeLine {
                             start_x: if row == range.start.row() {
                                 content_origin.x
@@ -1668,7 +1839,7 @@ impl EditorElement {
                                     + line_layout.x_for_index(range.end.column() as usize)
                                     - layout.position_map.scroll_position.x
                             } else {
-                                content_origin.x + line_layout.width + line_end_overshoot
+                                content_origin.x + line_layout.width() + line_end_overshoot
                                     - layout.position_map.scroll_position.x
                             },
                         }
@@ -1885,6 +2056,8 @@ impl EditorElement {
         rows: Range<u32>,
         line_number_layouts: &[Option<ShapedLine>],
         snapshot: &EditorSnapshot,
+        em_width: Pixels,
+        em_advance: Pixels,
         cx: &ViewContext<Editor>,
     ) -> Vec<LineWithInvisibles> {
         if rows.start >= rows.end {
@@ -1919,21 +2092,81 @@ impl EditorElement {
                         .log_err()
                 })
                 .map(|line| LineWithInvisibles {
+                    len: line.len,
                     line,
                     invisibles: Vec::new(),
+                    start_column: 0,
+                    em_advance,
                 })
                 .collect()
         } else {
-            let chunks = snapshot.highlighted_chunks(rows.clone(), true, &self.style);
-            LineWithInvisibles::from_chunks(
-                chunks,
-                &self.style.text,
-                MAX_LINE_LEN,
-                rows.len() as usize,
-                line_number_layouts,
-                snapshot.mode,
-                cx,
-            )
+            // Long lines are shaped from a column a little to the left of the viewport,
+            // so that the shaped text covers the viewport.
+            let scroll_left = snapshot.scroll_position().x * em_width;
+
+            let mut layouts = Vec::with_capacity(rows.len());
+            let mut row = rows.start;
+            while row < rows.end {
+                let line_number_layouts = &line_number_layouts[(row - rows.start) as usize..];
+                let line_len = snapshot.line_len(row);
+                if line_len as usize > MAX_LINE_LEN {
+                    // Columns are bytes, so estimate where the viewport starts from how wide
+                    // the bytes at the start of the row are.
+                    let byte_advance = layout_line(row, snapshot, &self.style, cx)
+                        .map_or(em_advance, |line| {
+                            estimated_byte_advance(line.width, line.len, em_advance)
+                        });
+                    let first_column = ((scroll_left / byte_advance) as u32)
+                        .saturating_sub(MAX_LINE_LEN as u32 / 4);
+                    let start_column = snapshot
+                        .clip_point(
+                            DisplayPoint::new(row, first_column.min(line_len)),
+                            Bias::Left,
+                        )
+                        .column();
+                    let chunks = snapshot.highlighted_chunks_in_row(
+                        row,
+                        start_column..start_column + MAX_LINE_LEN as u32,
+                        true,
+                        &self.style,
+                    );
+                    let mut line_layouts = LineWithInvisibles::from_chunks(
+                        chunks,
+                        &self.style.text,
+                        start_column as usize,
+                        MAX_LINE_LEN,
+                        1,
+                        line_number_layouts,
+                        snapshot.mode,
+                        em_advance,
+                        cx,
+                    );
+                    for layout in &mut line_layouts {
+                        layout.len = line_len as usize;
+                    }
+                    layouts.extend(line_layouts);
+                    row += 1;
+                } else {
+                    // Lay out the following short lines together.
+                    let end_row = (row + 1..rows.end)
+                        .find(|row| snapshot.line_len(*row) as usize > MAX_LINE_LEN)
+                        .unwrap_or(rows.end);
+                    let chunks = snapshot.highlighted_chunks(row..end_row, true, &self.style);
+                    layouts.extend(LineWithInvisibles::from_chunks(
+                        chunks,
+                        &self.style.text,
+                        0,
+                        MAX_LINE_LEN,
+                        (end_row - row) as usize,
+                        line_number_layouts,
+                        snapshot.mode,
+                        em_advance,
+                        cx,
+                    ));
+                    row = end_row;
+                }
+            }
+            layouts
         }
     }
 
@@ -1968,12 +2201,12 @@ impl EditorElement {
 
                 let editor_width = text_width - gutter_dimensions.margin - overscroll.width - em_width;
                 let wrap_width = match editor.soft_wrap_mode(cx) {
-                    SoftWrap::None => (MAX_LINE_LEN / 2) as f32 * em_advance,
-                    SoftWrap::EditorWidth => editor_width,
-                    SoftWrap::Column(column) => editor_width.min(column as f32 * em_advance),
+                    SoftWrap::None => None,
+                    SoftWrap::EditorWidth => Some(editor_width),
+                    SoftWrap::Column(column) => Some(editor_width.min(column as f32 * em_advance)),
                 };
 
-                if editor.set_wrap_width(Some(wrap_width), cx) {
+                if editor.set_wrap_width(wrap_width, cx) {
                     editor.snapshot(cx)
                 } else {
                     snapshot
@@ -2023,6 +2256,11 @@ impl EditorElement {
             let is_singleton = editor.is_singleton(cx);
 
             let highlighted_rows = editor.highlighted_rows();
+            let (decorated_rows, decoration_icons) = editor.external_decorations.layout(
+                start_row..end_row,
+                &snapshot.display_snapshot,
+                cx.theme().status(),
+            );
             let highlighted_ranges = editor.background_highlights_in_range(
                 start_anchor..end_anchor,
                 &snapshot.display_snapshot,
@@ -2139,6 +2377,30 @@ impl EditorElement {
             }
 
             let scrollbar_settings = EditorSettings::get_global(cx).scrollbar;
+            let scrollbar_cursors = match editor.collaboration_hub() {
+                Some(collaboration_hub) if is_singleton && scrollbar_settings.cursors => {
+                    let range = Anchor::min()..Anchor::max();
+                    snapshot
+                        .remote_selections_in_range(&range, collaboration_hub, cx)
+                        .filter(|selection| Some(selection.peer_id) != editor.leader_peer_id)
+                        .map(|selection| {
+                            let color = match selection.participant_index {
+                                Some(participant_index) => cx
+                                    .theme()
+                                    .players()
+                                    .color_for_participant(participant_index.0),
+                                None => cx.theme().players().absent(),
+                            };
+                            let cursor = selection
+                                .selection
+                                .head()
+                                .to_display_point(&snapshot.display_snapshot);
+                            (cursor, color.cursor)
+                        })
+                        .collect()
+                }
+                _ => Vec::new(),
+            };
             let show_scrollbars = match scrollbar_settings.show {
                 ShowScrollbar::Auto => {
                     // Git
@@ -2153,6 +2415,9 @@ impl EditorElement {
                     // Diagnostics
                     (is_singleton && scrollbar_settings.diagnostics && snapshot.buffer_snapshot.has_diagnostics())
                     ||
+                    // Collaborator cursors
+                    !scrollbar_cursors.is_empty()
+                    ||
                     // Scrollmanager
                     editor.scroll_manager.scrollbars_visible()
                 }
@@ -2189,16 +2454,30 @@ impl EditorElement {
             let scrollbar_row_range = scroll_position.y..(scroll_position.y + height_in_lines);
 
             let mut max_visible_line_width = Pixels::ZERO;
-            let line_layouts = self.layout_lines(start_row..end_row, &line_numbers, &snapshot, cx);
+            let mut line_layouts = self.layout_lines(
+                start_row..end_row,
+                &line_numbers,
+                &snapshot,
+                em_width,
+                em_advance,
+                cx,
+            );
             for line_with_invisibles in &line_layouts {
-                if line_with_invisibles.line.width > max_visible_line_width {
-                    max_visible_line_width = line_with_invisibles.line.width;
+                if line_with_invisibles.width() > max_visible_line_width {
+                    max_visible_line_width = line_with_invisibles.width();
                 }
             }
 
-            let longest_line_width = layout_line(snapshot.longest_row(), &snapshot, &style, cx)
-                .unwrap()
-                .width;
+            let longest_row = snapshot.longest_row();
+            let longest_line = layout_line(longest_row, &snapshot, &style, cx).unwrap();
+            let mut longest_line_width = longest_line.width;
+            // Only the start of long lines is shaped, so estimate the width of the rest.
+            let longest_line_len = snapshot.line_len(longest_row) as usize;
+            if longest_line_len > longest_line.len {
+                longest_line_width +=
+                    estimated_byte_advance(longest_line.width, longest_line.len, em_advance)
+                        * (longest_line_len - longest_line.len) as f32;
+            }
             let scroll_width = longest_line_width.max(max_visible_line_width) + overscroll.width;
 
             let editor_view = cx.view().clone();
@@ -2224,9 +2503,10 @@ impl EditorElement {
             })
             });
 
+            let max_scroll_top = editor.max_scroll_top(&snapshot.display_snapshot, cx);
             let scroll_max = point(
                 f32::from((scroll_width - text_size.width) / em_width).max(0.0),
-                max_row as f32,
+                max_scroll_top,
             );
 
             let clamped = editor.scroll_manager.clamp_scroll_left(scroll_max.x);
@@ -2246,6 +2526,15 @@ impl EditorElement {
 
             if clamped || autoscrolled {
                 snapshot = editor.snapshot(cx);
+                // The lines are shaped around the horizontal scroll position.
+                line_layouts = self.layout_lines(
+                    start_row..end_row,
+                    &line_numbers,
+                    &snapshot,
+                    em_width,
+                    em_advance,
+                    cx,
+                );
             }
 
             let mut context_menu = None;
@@ -2279,6 +2568,20 @@ impl EditorElement {
             }
 
             let visible_rows = start_row..start_row + line_layouts.len() as u32;
+
+            // The annotation goes after the blamed line's last display row, so that it
+            // follows soft-wrapped text.
+            let inline_blame = newest_selection_head.and_then(|head| {
+                let blame = editor.inline_blame.blame()?;
+                if head.to_point(&snapshot.display_snapshot).row != blame.row {
+                    return None;
+                }
+                let line_end = Point::new(blame.row, snapshot.buffer_snapshot.line_len(blame.row));
+                let row = line_end.to_display_point(&snapshot.display_snapshot).row();
+                visible_rows
+                    .contains(&row)
+                    .then(|| (row, render_inline_blame(blame, cx)))
+            });
             let max_size = size(
                 (120. * em_width) // Default size
                     .min(bounds.size.width / 2.) // Shrink to half of the editor width
@@ -2294,12 +2597,19 @@ impl EditorElement {
                 editor.hover_state.render(
                 &snapshot,
                 &style,
-                visible_rows,
+                visible_rows.clone(),
                 max_size,
                 editor.workspace.as_ref().map(|(w, _)| w.clone()),
                 cx,
             )
             };
+            let hover = hover.or_else(|| {
+                let position = newest_selection_head
+                    .filter(|head| visible_rows.contains(&head.row()))?;
+                editor
+                    .signature_help_state
+                    .render(position, &style, max_size, cx)
+            });
 
             let editor_view = cx.view().clone();
             let fold_indicators = cx.with_element_context(|cx| {
@@ -2370,12 +2680,15 @@ impl EditorElement {
                 gutter_padding: gutter_dimensions.padding,
                 text_size,
                 scrollbar_row_range,
+                scrollbar_cursors,
                 show_scrollbars,
                 is_singleton,
                 max_row,
                 gutter_margin: gutter_dimensions.margin,
                 active_rows,
                 highlighted_rows,
+                decorated_rows,
+                decoration_icons,
                 highlighted_ranges,
                 redacted_ranges,
                 line_numbers,
@@ -2384,6 +2697,7 @@ impl EditorElement {
                 selections,
                 context_menu,
                 code_actions_indicator,
+                inline_blame,
                 fold_indicators,
                 tab_invisible,
                 space_invisible,
@@ -2433,7 +2747,6 @@ impl EditorElement {
                     let anchor_x = text_x
                         + if rows.contains(&align_to.row()) {
                             line_layouts[(align_to.row() - rows.start) as usize]
-                                .line
                                 .x_for_index(align_to.column() as usize)
                         } else {
                             layout_line(align_to.row(), snapshot, style, cx)
@@ -2665,22 +2978,26 @@ impl EditorElement {
 
                         let line_height = position_map.line_height;
                         let max_glyph_width = position_map.em_width;
-                        let (delta, axis) = match delta {
+                        let (delta, axis, animate) = match delta {
                             gpui::ScrollDelta::Pixels(mut pixels) => {
                                 //Trackpad
                                 let axis = position_map.snapshot.ongoing_scroll.filter(&mut pixels);
-                                (pixels, axis)
+                                (pixels, axis, false)
                             }
 
                             gpui::ScrollDelta::Lines(lines) => {
                                 //Not trackpad
                                 let pixels =
                                     point(lines.x * max_glyph_width, lines.y * line_height);
-                                (pixels, None)
+                                (pixels, None, true)
                             }
                         };
 
-                        let scroll_position = position_map.snapshot.scroll_position();
+                        let scroll_position = if animate {
+                            editor.scroll_target_position(cx)
+                        } else {
+                            position_map.snapshot.scroll_position()
+                        };
                         let x = f32::from(
                             (scroll_position.x * max_glyph_width - delta.x) / max_glyph_width,
                         );
@@ -2688,7 +3005,11 @@ impl EditorElement {
                             f32::from((scroll_position.y * line_height - delta.y) / line_height);
                         let scroll_position =
                             point(x, y).clamp(&point(0., 0.), &position_map.scroll_max);
-                        editor.scroll(scroll_position, axis, cx);
+                        if animate {
+                            editor.animate_scroll_position(scroll_position, cx);
+                        } else {
+                            editor.scroll(scroll_position, axis, cx);
+                        }
                         cx.stop_propagation();
                     });
                 }
@@ -2736,6 +3057,16 @@ impl EditorElement {
                         MouseButton::Right => editor.update(cx, |editor, cx| {
                             Self::mouse_right_down(editor, event, &position_map, text_bounds, cx);
                         }),
+                        MouseButton::Middle => editor.update(cx, |editor, cx| {
+                            Self::mouse_middle_down(
+                                editor,
+                                event,
+                                &position_map,
+                                text_bounds,
+                                &stacking_order,
+                                cx,
+                            );
+                        }),
                         _ => {}
                     };
                 }
@@ -2804,20 +3135,34 @@ impl EditorElement {
     }
 }
 
+/// A display line shaped for painting.
+///
+/// Only up to `MAX_LINE_LEN` bytes of a line are shaped, starting at a column near
+/// the left edge of the viewport, so that very long lines such as minified code are
+/// laid out in constant time. The positions of the columns outside of the shaped text
+/// are estimated from the average width of the bytes of the shaped text, so that lines
+/// of multibyte characters such as CJK or emoji aren't estimated as too wide.
 #[derive(Debug)]
 pub(crate) struct LineWithInvisibles {
     pub line: ShapedLine,
     invisibles: Vec<Invisible>,
+    /// The column that the shaped text starts at.
+    start_column: usize,
+    /// The length of the whole line.
+    len: usize,
+    em_advance: Pixels,
 }
 
 impl LineWithInvisibles {
     fn from_chunks<'a>(
         chunks: impl Iterator<Item = HighlightedChunk<'a>>,
         text_style: &TextStyle,
+        start_column: usize,
         max_line_len: usize,
         max_line_count: usize,
         line_number_layouts: &[Option<ShapedLine>],
         editor_mode: EditorMode,
+        em_advance: Pixels,
         cx: &WindowContext,
     ) -> Vec<Self> {
         let mut layouts = Vec::with_capacity(max_line_count);
@@ -2827,6 +3172,7 @@ impl LineWithInvisibles {
         let mut non_whitespace_added = false;
         let mut row = 0;
         let mut line_exceeded_max_len = false;
+        let mut column = start_column;
         let font_size = text_style.font_size.to_pixels(cx.rem_size());
 
         for highlighted_chunk in chunks.chain([HighlightedChunk {
@@ -2843,6 +3189,9 @@ impl LineWithInvisibles {
                     layouts.push(Self {
                         line: shaped_line,
                         invisibles: invisibles.drain(..).collect(),
+                        start_column,
+                        len: column,
+                        em_advance,
                     });
 
                     line.clear();
@@ -2850,11 +3199,13 @@ impl LineWithInvisibles {
                     row += 1;
                     line_exceeded_max_len = false;
                     non_whitespace_added = false;
+                    column = start_column;
                     if row == max_line_count {
                         return layouts;
                     }
                 }
 
+                column += line_chunk.len();
                 if !line_chunk.is_empty() && !line_exceeded_max_len {
                     let text_style = if let Some(style) = highlighted_chunk.style {
                         Cow::Owned(text_style.clone().highlight(style))
@@ -2919,6 +3270,47 @@ impl LineWithInvisibles {
         layouts
     }
 
+    fn start_x(&self) -> Pixels {
+        self.byte_advance() * self.start_column as f32
+    }
+
+    fn byte_advance(&self) -> Pixels {
+        estimated_byte_advance(self.line.width, self.line.len, self.em_advance)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn width(&self) -> Pixels {
+        self.x_for_index(self.len)
+    }
+
+    pub(crate) fn x_for_index(&self, index: usize) -> Pixels {
+        let end_column = self.start_column + self.line.len;
+        if index < self.start_column {
+            self.byte_advance() * index as f32
+        } else if index <= end_column {
+            self.start_x() + self.line.x_for_index(index - self.start_column)
+        } else {
+            let overflow = cmp::min(index, self.len).saturating_sub(end_column);
+            self.start_x() + self.line.width + self.byte_advance() * overflow as f32
+        }
+    }
+
+    pub(crate) fn index_for_x(&self, x: Pixels) -> Option<usize> {
+        let start_x = self.start_x();
+        if x < start_x {
+            return Some((x / self.byte_advance()) as usize);
+        }
+        if let Some(index) = self.line.index_for_x(x - start_x) {
+            return Some(self.start_column + index);
+        }
+        let end_column = self.start_column + self.line.len;
+        let index = end_column + ((x - start_x - self.line.width) / self.byte_advance()) as usize;
+        (index < self.len).then_some(index)
+    }
+
     fn draw(
         &self,
         layout: &LayoutState,
@@ -2933,7 +3325,11 @@ impl LineWithInvisibles {
 
         self.line
             .paint(
-                content_origin + gpui::point(-layout.position_map.scroll_position.x, line_y),
+                content_origin
+                    + gpui::point(
+                        self.start_x() - layout.position_map.scroll_position.x,
+                        line_y,
+                    ),
                 line_height,
                 cx,
             )
@@ -2974,7 +3370,7 @@ impl LineWithInvisibles {
                 Invisible::Whitespace { line_offset } => (line_offset, &layout.space_invisible),
             };
 
-            let x_offset = self.line.x_for_index(token_offset);
+            let x_offset = self.start_x() + self.line.x_for_index(token_offset);
             let invisible_offset =
                 (layout.position_map.em_width - invisible_symbol.width).max(Pixels::ZERO) / 2.0;
             let origin = content_origin
@@ -2984,7 +3380,8 @@ impl LineWithInvisibles {
                 );
 
             if let Some(allowed_regions) = allowed_invisibles_regions {
-                let invisible_point = DisplayPoint::new(row, token_offset as u32);
+                let invisible_point =
+                    DisplayPoint::new(row, (self.start_column + token_offset) as u32);
                 if !allowed_regions
                     .iter()
                     .any(|region| region.start <= invisible_point && invisible_point < region.end)
@@ -3159,6 +3556,10 @@ pub struct LayoutState {
     visible_display_row_range: Range<u32>,
     active_rows: BTreeMap<u32, bool>,
     highlighted_rows: Option<Range<u32>>,
+    /// The display rows highlighted by external tools, with their background colors.
+    decorated_rows: Vec<(Range<u32>, Hsla)>,
+    /// The gutter icons added by external tools, by display row.
+    decoration_icons: Vec<(u32, Icon)>,
     line_numbers: Vec<Option<ShapedLine>>,
     display_hunks: Vec<DisplayDiffHunk>,
     blocks: Vec<BlockLayout>,
@@ -3166,11 +3567,15 @@ pub struct LayoutState {
     redacted_ranges: Vec<Range<DisplayPoint>>,
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     scrollbar_row_range: Range<f32>,
+    /// The positions of collaborators' cursors and their colors, shown in the scrollbar.
+    scrollbar_cursors: Vec<(DisplayPoint, Hsla)>,
     show_scrollbars: bool,
     is_singleton: bool,
     max_row: u32,
     context_menu: Option<(DisplayPoint, AnyElement)>,
     code_actions_indicator: Option<CodeActionsIndicator>,
+    /// The annotation with the commit that last changed the cursor's line, by display row.
+    inline_blame: Option<(u32, AnyElement)>,
     hover_popovers: Option<(DisplayPoint, Vec<AnyElement>)>,
     fold_indicators: Vec<Option<IconButton>>,
     tab_invisible: ShapedLine,
@@ -3188,6 +3593,32 @@ struct CodeActionsIndicator {
     button: IconButton,
 }
 
+/// A mark painted in the scrollbar, and the position that clicking it jumps to.
+struct ScrollbarMarker {
+    top: Pixels,
+    bottom: Pixels,
+    target: DisplayPoint,
+}
+
+/// How far the inline blame annotation is from the end of its line.
+const INLINE_BLAME_PADDING_EM_WIDTHS: f32 = 4.;
+
+/// How far from a thin scrollbar marker a click still hits it.
+const SCROLLBAR_MARKER_HIT_SLOP: Pixels = px(2.);
+
+/// Returns the target of the marker under the given y coordinate. Markers that were
+/// painted later are on top, so they win.
+fn scrollbar_marker_at(markers: &[ScrollbarMarker], y: Pixels) -> Option<DisplayPoint> {
+    markers
+        .iter()
+        .rev()
+        .find(|marker| {
+            marker.top - SCROLLBAR_MARKER_HIT_SLOP <= y
+                && y <= marker.bottom + SCROLLBAR_MARKER_HIT_SLOP
+        })
+        .map(|marker| marker.target)
+}
+
 struct PositionMap {
     size: Size<Pixels>,
     line_height: Pixels,
@@ -3232,12 +3663,11 @@ impl PositionMap {
         let (column, x_overshoot_after_line_end) = if let Some(line) = self
             .line_layouts
             .get(row as usize - scroll_position.y as usize)
-            .map(|&LineWithInvisibles { ref line, .. }| line)
         {
             if let Some(ix) = line.index_for_x(x) {
                 (ix as u32, px(0.))
             } else {
-                (line.len as u32, px(0.).max(x - line.width))
+                (line.len() as u32, px(0.).max(x - line.width()))
             }
         } else {
             (0, x)
@@ -3271,7 +3701,15 @@ fn layout_line(
     style: &EditorStyle,
     cx: &WindowContext,
 ) -> Result<ShapedLine> {
-    let mut line = snapshot.line(row);
+    // Avoid copying the rest of very long lines, which aren't shaped.
+    let mut line = String::new();
+    for chunk in snapshot.text_chunks(row) {
+        let line_end = chunk.find('\n');
+        line.push_str(&chunk[..line_end.unwrap_or(chunk.len())]);
+        if line_end.is_some() || line.len() > MAX_LINE_LEN {
+            break;
+        }
+    }
 
     if line.len() > MAX_LINE_LEN {
         let mut len = MAX_LINE_LEN;
@@ -3282,11 +3720,12 @@ fn layout_line(
         line.truncate(len);
     }
 
+    let len = line.len();
     cx.text_system().shape_line(
         line.into(),
         style.text.font_size.to_pixels(cx.rem_size()),
         &[TextRun {
-            len: snapshot.line_len(row) as usize,
+            len,
             font: style.text.font(),
             color: Hsla::default(),
             background_color: None,
@@ -3789,6 +4228,129 @@ mod tests {
         assert_eq!(local_selections[1].head, DisplayPoint::new(10, 0));
     }
 
+    #[gpui::test]
+    fn test_layout_long_lines(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let text = format!("short\n{}\nshort", "a".repeat(10_000));
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&text, cx);
+            Editor::new(EditorMode::Full, buffer, None, cx)
+        });
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        window
+            .update(cx, |editor, cx| {
+                editor.set_scroll_position(gpui::Point::new(5000., 0.), cx)
+            })
+            .unwrap();
+
+        let mut element = EditorElement::new(&editor, style);
+        let state = cx
+            .update_window(window.into(), |view, cx| {
+                cx.with_element_context(|cx| {
+                    cx.with_view_id(view.entity_id(), |cx| {
+                        element.compute_layout(
+                            Bounds {
+                                origin: point(px(500.), px(500.)),
+                                size: size(px(500.), px(500.)),
+                            },
+                            cx,
+                        )
+                    })
+                })
+            })
+            .unwrap();
+
+        // The long line isn't wrapped, and only the part of it around the viewport is shaped.
+        let line_layouts = &state.position_map.line_layouts;
+        assert_eq!(line_layouts.len(), 3);
+        assert_eq!(line_layouts[0].start_column, 0);
+        assert_eq!(line_layouts[0].len(), 5);
+        let long_line = &line_layouts[1];
+        assert_eq!(long_line.len(), 10_000);
+        assert!(long_line.start_column > 0);
+        assert!(long_line.line.len <= MAX_LINE_LEN);
+
+        for column in [
+            long_line.start_column / 2,
+            long_line.start_column + 10,
+            long_line.start_column + long_line.line.len + 10,
+        ] {
+            let x = long_line.x_for_index(column);
+            assert_eq!(long_line.index_for_x(x + px(0.1)), Some(column));
+        }
+        assert!(long_line.width() > long_line.x_for_index(9_999));
+        assert_eq!(long_line.index_for_x(long_line.width() + px(1.)), None);
+    }
+
+    #[gpui::test]
+    fn test_layout_long_lines_with_multibyte_chars(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        // The number of columns that each repetition of a row's unit takes.
+        let units = [("中", 3), ("😀", 4), ("a\t", 4)];
+        let text = units
+            .iter()
+            .map(|(unit, _)| unit.repeat(10_000))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&text, cx);
+            Editor::new(EditorMode::Full, buffer, None, cx)
+        });
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        window
+            .update(cx, |editor, cx| {
+                editor.set_scroll_position(gpui::Point::new(5000., 0.), cx)
+            })
+            .unwrap();
+
+        let mut element = EditorElement::new(&editor, style);
+        let state = cx
+            .update_window(window.into(), |view, cx| {
+                cx.with_element_context(|cx| {
+                    cx.with_view_id(view.entity_id(), |cx| {
+                        element.compute_layout(
+                            Bounds {
+                                origin: point(px(500.), px(500.)),
+                                size: size(px(500.), px(500.)),
+                            },
+                            cx,
+                        )
+                    })
+                })
+            })
+            .unwrap();
+
+        let scroll_left = state.position_map.scroll_position.x;
+        for (line, (_, unit_len)) in state.position_map.line_layouts.iter().zip(units) {
+            // The shaped text covers the viewport, even though the columns are wider than
+            // the estimated width of a column would be from the font's `m`.
+            assert!(line.start_column > 0);
+            assert!(line.start_x() <= scroll_left);
+            assert!(line.start_x() + line.line.width > scroll_left + px(500.));
+
+            let shaped_unit = line.start_column.next_multiple_of(unit_len);
+            let unit_width =
+                line.x_for_index(shaped_unit + unit_len) - line.x_for_index(shaped_unit);
+            for column in [
+                line.start_column / 2 / unit_len * unit_len,
+                shaped_unit + 10 * unit_len,
+                (line.start_column + line.line.len) / unit_len * unit_len + 10 * unit_len,
+                line.len(),
+            ] {
+                let x = line.x_for_index(column);
+                let expected_x = unit_width * (column / unit_len) as f32;
+                assert!(
+                    (x - expected_x).0.abs() <= unit_width.0,
+                    "column {column}: {x:?} instead of {expected_x:?}"
+                );
+            }
+        }
+    }
+
     #[gpui::test]
     fn test_layout_with_placeholder_text_and_blocks(cx: &mut TestAppContext) {
         init_test(cx, |_| {});
@@ -4002,6 +4564,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_scrollbar_marker_at() {
+        let markers = [
+            ScrollbarMarker {
+                top: px(10.),
+                bottom: px(30.),
+                target: DisplayPoint::new(1, 0),
+            },
+            ScrollbarMarker {
+                top: px(20.),
+                bottom: px(21.),
+                target: DisplayPoint::new(2, 4),
+            },
+        ];
+
+        assert_eq!(scrollbar_marker_at(&markers, px(0.)), None);
+        assert_eq!(
+            scrollbar_marker_at(&markers, px(9.)),
+            Some(DisplayPoint::new(1, 0))
+        );
+        assert_eq!(
+            scrollbar_marker_at(&markers, px(15.)),
+            Some(DisplayPoint::new(1, 0))
+        );
+        // Thin markers can be clicked a little above or below them, and the marker
+        // painted last is on top.
+        assert_eq!(
+            scrollbar_marker_at(&markers, px(22.5)),
+            Some(DisplayPoint::new(2, 4))
+        );
+        assert_eq!(
+            scrollbar_marker_at(&markers, px(31.)),
+            Some(DisplayPoint::new(1, 0))
+        );
+        assert_eq!(scrollbar_marker_at(&markers, px(33.)), None);
+    }
+
     fn collect_invisibles_from_new_editor(
         cx: &mut TestAppContext,
         editor_mode: EditorMode,
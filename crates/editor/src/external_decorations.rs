@@ -0,0 +1,295 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{AppContext, Global, Hsla};
+use language::Point;
+use serde::Deserialize;
+use theme::StatusColors;
+use ui::prelude::*;
+
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, DisplaySnapshot,
+        ToDisplayPoint,
+    },
+    Anchor, Editor, EditorMode,
+};
+
+/// A decoration that an external tool, such as a CI service or a profiler, adds to a
+/// range of lines through the CLI.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ExternalDecoration {
+    /// The path of the decorated file. Relative paths are resolved against the
+    /// working directory of the CLI.
+    pub path: PathBuf,
+    /// The first decorated line, starting at 1.
+    pub line: u32,
+    /// The last decorated line. Defaults to `line`.
+    pub end_line: Option<u32>,
+    #[serde(default)]
+    pub severity: DecorationSeverity,
+    /// Whether to highlight the background of the lines.
+    #[serde(default)]
+    pub highlight: bool,
+    /// Whether to show an icon in the gutter next to the first line.
+    #[serde(default)]
+    pub gutter_icon: bool,
+    /// A message shown below the last line.
+    pub annotation: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecorationSeverity {
+    Error,
+    Warning,
+    #[default]
+    Info,
+}
+
+impl DecorationSeverity {
+    fn color(self) -> Color {
+        match self {
+            DecorationSeverity::Error => Color::Error,
+            DecorationSeverity::Warning => Color::Warning,
+            DecorationSeverity::Info => Color::Info,
+        }
+    }
+
+    fn background(self, status: &StatusColors) -> Hsla {
+        match self {
+            DecorationSeverity::Error => status.error_background,
+            DecorationSeverity::Warning => status.warning_background,
+            DecorationSeverity::Info => status.info_background,
+        }
+    }
+
+    fn icon(self) -> IconName {
+        match self {
+            DecorationSeverity::Error => IconName::XCircle,
+            DecorationSeverity::Warning => IconName::ExclamationTriangle,
+            DecorationSeverity::Info => IconName::Bolt,
+        }
+    }
+}
+
+/// The decorations of every external tool, keyed by the namespace that the tool
+/// replaces and clears its own decorations under.
+#[derive(Default)]
+pub(crate) struct ExternalDecorations {
+    namespaces: BTreeMap<String, Vec<ExternalDecoration>>,
+}
+
+impl Global for ExternalDecorations {}
+
+/// Parses a JSON array of decorations sent by the CLI.
+pub fn parse_external_decorations(
+    json: &str,
+    working_directory: &Path,
+) -> Result<Vec<ExternalDecoration>> {
+    let mut decorations = serde_json::from_str::<Vec<ExternalDecoration>>(json)?;
+    for decoration in &mut decorations {
+        decoration.path = working_directory.join(&decoration.path);
+    }
+    Ok(decorations)
+}
+
+/// Replaces the decorations in the given namespace.
+pub fn set_external_decorations(
+    namespace: String,
+    decorations: Vec<ExternalDecoration>,
+    cx: &mut AppContext,
+) {
+    cx.default_global::<ExternalDecorations>()
+        .namespaces
+        .insert(namespace, decorations);
+}
+
+pub fn clear_external_decorations(namespace: &str, cx: &mut AppContext) {
+    cx.default_global::<ExternalDecorations>()
+        .namespaces
+        .remove(namespace);
+}
+
+struct LineDecoration {
+    range: Range<Anchor>,
+    severity: DecorationSeverity,
+    highlight: bool,
+    gutter_icon: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct ExternalDecorationState {
+    decorations: Vec<LineDecoration>,
+    blocks: Vec<BlockId>,
+}
+
+impl ExternalDecorationState {
+    /// Returns the display rows to highlight with their background colors, and the
+    /// gutter icons by display row, within the given rows.
+    pub(crate) fn layout(
+        &self,
+        rows: Range<u32>,
+        snapshot: &DisplaySnapshot,
+        status: &StatusColors,
+    ) -> (Vec<(Range<u32>, Hsla)>, Vec<(u32, Icon)>) {
+        let mut highlighted_rows = Vec::new();
+        let mut icons = Vec::new();
+        for decoration in &self.decorations {
+            let start_row = decoration.range.start.to_display_point(snapshot).row();
+            let end_row = decoration.range.end.to_display_point(snapshot).row() + 1;
+            if start_row >= rows.end || end_row <= rows.start {
+                continue;
+            }
+            if decoration.highlight {
+                highlighted_rows.push((
+                    start_row.max(rows.start)..end_row.min(rows.end),
+                    decoration.severity.background(status),
+                ));
+            }
+            if decoration.gutter_icon && rows.contains(&start_row) {
+                let icon = Icon::new(decoration.severity.icon())
+                    .size(IconSize::Small)
+                    .color(decoration.severity.color());
+                icons.push((start_row, icon));
+            }
+        }
+        (highlighted_rows, icons)
+    }
+}
+
+/// Shows the external decorations of the editor's file, replacing the ones that
+/// were shown before.
+pub(crate) fn refresh_external_decorations(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.external_decorations.decorations.clear();
+    if !editor.external_decorations.blocks.is_empty() {
+        let blocks = editor.external_decorations.blocks.drain(..).collect();
+        editor.remove_blocks(blocks, None, cx);
+    }
+    cx.notify();
+
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(namespaces) = cx.try_global::<ExternalDecorations>() else {
+        return;
+    };
+    let Some(buffer) = editor.buffer.read(cx).as_singleton() else {
+        return;
+    };
+    let Some(abs_path) = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx))
+    else {
+        return;
+    };
+    let decorations = namespaces
+        .namespaces
+        .values()
+        .flatten()
+        .filter(|decoration| decoration.path == abs_path)
+        .cloned()
+        .collect::<Vec<_>>();
+    if decorations.is_empty() {
+        return;
+    }
+
+    let multi_buffer = editor.buffer.read(cx).snapshot(cx);
+    let Some((excerpt_id, _, buffer)) = multi_buffer.as_singleton() else {
+        return;
+    };
+    let max_row = buffer.max_point().row;
+    let mut blocks = Vec::new();
+    for decoration in decorations {
+        let start_row = decoration.line.saturating_sub(1).min(max_row);
+        let end_row = decoration
+            .end_line
+            .map_or(start_row, |line| line.saturating_sub(1).min(max_row))
+            .max(start_row);
+        let start = buffer.anchor_before(Point::new(start_row, 0));
+        let end = buffer.anchor_after(Point::new(end_row, buffer.line_len(end_row)));
+        editor
+            .external_decorations
+            .decorations
+            .push(LineDecoration {
+                range: multi_buffer.anchor_in_excerpt(*excerpt_id, start)
+                    ..multi_buffer.anchor_in_excerpt(*excerpt_id, end),
+                severity: decoration.severity,
+                highlight: decoration.highlight,
+                gutter_icon: decoration.gutter_icon,
+            });
+
+        if let Some(annotation) = decoration.annotation {
+            let indent = buffer.indent_size_for_line(end_row);
+            let position = buffer.anchor_after(Point::new(end_row, indent.len));
+            let annotation: SharedString = annotation.into();
+            let color = decoration.severity.color();
+            blocks.push(BlockProperties {
+                style: BlockStyle::Flex,
+                position: multi_buffer.anchor_in_excerpt(*excerpt_id, position),
+                height: annotation.lines().count().clamp(1, u8::MAX as usize) as u8,
+                render: Arc::new(move |cx: &mut BlockContext| {
+                    div()
+                        .id(cx.block_id)
+                        .pl(cx.anchor_x)
+                        .child(
+                            Label::new(annotation.clone())
+                                .size(LabelSize::Small)
+                                .color(color),
+                        )
+                        .into_any_element()
+                }),
+                disposition: BlockDisposition::Below,
+            });
+        }
+    }
+    editor.external_decorations.blocks = editor.insert_blocks(blocks, None, cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_external_decorations() {
+        let decorations = parse_external_decorations(
+            r#"[
+                {"path": "src/main.rs", "line": 3, "severity": "error", "gutter_icon": true},
+                {"path": "/tmp/lib.rs", "line": 1, "end_line": 4, "highlight": true, "annotation": "hot"}
+            ]"#,
+            Path::new("/code/app"),
+        )
+        .unwrap();
+        assert_eq!(
+            decorations,
+            [
+                ExternalDecoration {
+                    path: "/code/app/src/main.rs".into(),
+                    line: 3,
+                    end_line: None,
+                    severity: DecorationSeverity::Error,
+                    highlight: false,
+                    gutter_icon: true,
+                    annotation: None,
+                },
+                ExternalDecoration {
+                    path: "/tmp/lib.rs".into(),
+                    line: 1,
+                    end_line: Some(4),
+                    severity: DecorationSeverity::Info,
+                    highlight: true,
+                    gutter_icon: false,
+                    annotation: Some("hot".into()),
+                },
+            ]
+        );
+        assert!(parse_external_decorations("{}", Path::new("/")).is_err());
+    }
+}
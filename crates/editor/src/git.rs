@@ -1,3 +1,6 @@
+pub(crate) mod commit_message;
+pub mod commit_view;
+pub(crate) mod inline_blame;
 pub mod permalink;
 
 use std::ops::Range;
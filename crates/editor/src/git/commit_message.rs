@@ -0,0 +1,595 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Result};
+use futures::{future::Shared, FutureExt};
+use gpui::{AppContext, HighlightStyle, Model, SharedString, Task, UnderlineStyle};
+use language::{Buffer, CodeLabel, Completion, Point, ToPoint};
+use parking_lot::{Mutex, RwLock};
+use project::{repository::CommitAuthor, Fs};
+use settings::Settings;
+use ui::prelude::*;
+use util::ResultExt;
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockProperties, BlockStyle},
+    editor_settings::CommitMessageSettings,
+    Anchor, CompletionProvider, Editor, EditorSettings,
+};
+
+/// The length that git tools truncate commit subjects at.
+const SUBJECT_LINE_LENGTH: usize = 50;
+/// The length that commit bodies are wrapped at, so they fit an indented `git log`.
+const BODY_LINE_LENGTH: usize = 72;
+/// The line that `git commit --verbose` inserts above the diff, below which git
+/// ignores everything.
+const SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+const CO_AUTHOR_TRAILER: &str = "Co-authored-by:";
+/// The number of recent commits whose authors are suggested as co-authors.
+const CO_AUTHOR_COMMIT_LIMIT: usize = 1000;
+
+enum CommitMessageLints {}
+
+pub(crate) fn is_commit_message(buffer: &Buffer) -> bool {
+    buffer
+        .language()
+        .map_or(false, |language| language.name().as_ref() == "Git Commit")
+}
+
+/// Underlines the parts of a commit message that don't follow git's conventions: the
+/// end of a subject longer than 50 characters or ending with a period, a missing blank
+/// line after the subject, and the end of body lines longer than 72 characters.
+pub(crate) fn refresh_commit_message_lints(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let text = editor.buffer.read(cx).as_singleton().and_then(|buffer| {
+        let buffer = buffer.read(cx);
+        is_commit_message(buffer).then(|| buffer.text())
+    });
+    let Some(text) = text else {
+        editor.clear_highlights::<CommitMessageLints>(cx);
+        return;
+    };
+
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let ranges = commit_message_lints(&text)
+        .into_iter()
+        .map(|range| snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end))
+        .collect();
+    let style = HighlightStyle {
+        underline: Some(UnderlineStyle {
+            color: Some(cx.theme().status().warning),
+            thickness: px(1.),
+            wavy: true,
+        }),
+        ..Default::default()
+    };
+    editor.highlight_text::<CommitMessageLints>(ranges, style, cx);
+}
+
+fn commit_message_lints(text: &str) -> Vec<Range<usize>> {
+    let mut lints = Vec::new();
+    let mut subject_row = None;
+    let mut offset = 0;
+    for (row, line) in text.split('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len() + 1;
+        if line == SCISSORS_LINE {
+            break;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let mut line_lints = Vec::new();
+        match subject_row {
+            // Git strips the blank lines before the subject.
+            None if line.trim().is_empty() => {}
+            None => {
+                subject_row = Some(row);
+                line_lints.extend(overflow(line, SUBJECT_LINE_LENGTH));
+                if line.trim_end().ends_with('.') {
+                    let period = line.trim_end().len() - 1;
+                    line_lints.push(period..period + 1);
+                }
+            }
+            Some(subject_row) if row == subject_row + 1 && !line.trim().is_empty() => {
+                line_lints.push(0..line.len());
+            }
+            Some(_) => line_lints.extend(overflow(line, BODY_LINE_LENGTH)),
+        }
+        lints.extend(
+            line_lints
+                .into_iter()
+                .map(|range| range.start + line_start..range.end + line_start),
+        );
+    }
+    lints
+}
+
+/// Returns the range of the characters past the given length.
+fn overflow(line: &str, length: usize) -> Option<Range<usize>> {
+    let (start, _) = line.char_indices().nth(length)?;
+    Some(start..line.len())
+}
+
+/// The most lines of the staged changes that are shown beneath a commit message. Blocks
+/// are at most 255 lines high.
+const MAX_DIFF_LINES: usize = 200;
+/// Common English misspellings, fixed when they're typed in the subject of a commit message.
+const SPELLING_CORRECTIONS: &[(&str, &str)] = &[
+    ("accross", "across"),
+    ("adress", "address"),
+    ("agian", "again"),
+    ("arguement", "argument"),
+    ("begining", "beginning"),
+    ("beleive", "believe"),
+    ("calender", "calendar"),
+    ("commited", "committed"),
+    ("comming", "coming"),
+    ("definately", "definitely"),
+    ("dependancy", "dependency"),
+    ("enviroment", "environment"),
+    ("occured", "occurred"),
+    ("occurence", "occurrence"),
+    ("paramter", "parameter"),
+    ("recieve", "receive"),
+    ("refrence", "reference"),
+    ("remvoe", "remove"),
+    ("retreive", "retrieve"),
+    ("seperate", "separate"),
+    ("succesful", "successful"),
+    ("sucess", "success"),
+    ("teh", "the"),
+    ("thier", "their"),
+    ("udpate", "update"),
+    ("untill", "until"),
+    ("wich", "which"),
+];
+
+/// What an editor shows for the commit message it edits.
+#[derive(Default)]
+pub(crate) struct CommitMessageState {
+    is_set_up: bool,
+    _load_staged_diff: Option<Task<()>>,
+}
+
+/// Sets up an editor once its buffer turns out to be a commit message: co-authors are
+/// completed from the repository's history, and the staged changes are shown read-only
+/// beneath the message.
+pub(crate) fn refresh_commit_message(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    refresh_commit_message_lints(editor, cx);
+    if editor.commit_message.is_set_up {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    // Commit messages are written in the git directory, such as `.git/COMMIT_EDITMSG`.
+    let Some((git_dir, text)) = editor.buffer.read(cx).as_singleton().and_then(|buffer| {
+        let buffer = buffer.read(cx);
+        if !is_commit_message(buffer) {
+            return None;
+        }
+        let path = buffer.file()?.as_local()?.abs_path(cx);
+        Some((path.parent()?.to_path_buf(), buffer.text()))
+    }) else {
+        return;
+    };
+    editor.commit_message.is_set_up = true;
+
+    let fs = project.read(cx).fs().clone();
+    editor.set_completion_provider(Box::new(CommitMessageCompletionProvider {
+        fs: fs.clone(),
+        git_dir: git_dir.clone(),
+        authors: Mutex::new(None),
+    }));
+
+    // `git commit --verbose` writes the diff into the message itself.
+    if text.split('\n').any(|line| line == SCISSORS_LINE) {
+        return;
+    }
+    let staged_diff = cx
+        .background_executor()
+        .spawn(async move { open_repository(fs.as_ref(), &git_dir)?.lock().staged_diff() });
+    editor.commit_message._load_staged_diff = Some(cx.spawn(|editor, mut cx| async move {
+        let Some(diff) = staged_diff.await.log_err() else {
+            return;
+        };
+        let lines = staged_diff_lines(&diff);
+        if lines.is_empty() {
+            return;
+        }
+        editor
+            .update(&mut cx, |editor, cx| {
+                let block = BlockProperties {
+                    position: Anchor::max(),
+                    height: lines.len() as u8,
+                    style: BlockStyle::Flex,
+                    render: Arc::new(move |cx| render_staged_diff(&lines, cx)),
+                    disposition: BlockDisposition::Below,
+                };
+                editor.insert_blocks([block], None, cx);
+            })
+            .ok();
+    }));
+}
+
+fn open_repository(
+    fs: &dyn Fs,
+    git_dir: &Path,
+) -> Result<Arc<Mutex<dyn project::repository::GitRepository>>> {
+    fs.open_repo(git_dir)
+        .ok_or_else(|| anyhow!("{git_dir:?} is not a git directory"))
+}
+
+/// Returns the lines of the staged changes to show, led by a heading and limited to
+/// [`MAX_DIFF_LINES`].
+fn staged_diff_lines(diff: &str) -> Vec<SharedString> {
+    let line_count = diff.lines().count();
+    if line_count == 0 {
+        return Vec::new();
+    }
+    let mut lines = vec![SharedString::from("Changes to be committed:")];
+    lines.extend(
+        diff.lines()
+            .take(MAX_DIFF_LINES)
+            .map(|line| SharedString::from(line.to_string())),
+    );
+    if line_count > MAX_DIFF_LINES {
+        lines.push(format!("… {} more lines", line_count - MAX_DIFF_LINES).into());
+    }
+    lines
+}
+
+fn render_staged_diff(lines: &[SharedString], cx: &mut BlockContext) -> AnyElement {
+    let status = cx.theme().status();
+    let muted = cx.theme().colors().text_muted;
+    v_flex()
+        .pl(cx.gutter_width)
+        .font(cx.editor_style.text.font_family.clone())
+        .children(lines.iter().enumerate().map(|(ix, line)| {
+            let color = match line.chars().next() {
+                _ if ix == 0 => muted,
+                Some('+') if !line.starts_with("+++") => status.created,
+                Some('-') if !line.starts_with("---") => status.deleted,
+                _ => muted,
+            };
+            div()
+                .h(cx.line_height)
+                .whitespace_nowrap()
+                .overflow_hidden()
+                .text_color(color)
+                .child(line.clone())
+        }))
+        .into_any_element()
+}
+
+/// Completes the recent authors of the repository in the `Co-authored-by:` trailers of
+/// a commit message. Commit messages have no language server, so this provides all of
+/// their completions.
+struct CommitMessageCompletionProvider {
+    fs: Arc<dyn Fs>,
+    git_dir: PathBuf,
+    /// The recent authors, which are read from the repository's history once.
+    authors: Mutex<Option<Shared<Task<Arc<[CommitAuthor]>>>>>,
+}
+
+impl CommitMessageCompletionProvider {
+    fn authors(&self, cx: &AppContext) -> Shared<Task<Arc<[CommitAuthor]>>> {
+        self.authors
+            .lock()
+            .get_or_insert_with(|| {
+                let fs = self.fs.clone();
+                let git_dir = self.git_dir.clone();
+                cx.background_executor()
+                    .spawn(async move {
+                        open_repository(fs.as_ref(), &git_dir)
+                            .and_then(|repository| {
+                                repository.lock().recent_authors(CO_AUTHOR_COMMIT_LIMIT)
+                            })
+                            .log_err()
+                            .unwrap_or_default()
+                            .into()
+                    })
+                    .shared()
+            })
+            .clone()
+    }
+}
+
+impl CompletionProvider for CommitMessageCompletionProvider {
+    fn completions(
+        &self,
+        buffer: &Model<Buffer>,
+        buffer_position: text::Anchor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let Some(start) = co_author_start(buffer.read(cx), buffer_position) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let authors = self.authors(cx);
+        cx.background_executor().spawn(async move {
+            Ok(authors
+                .await
+                .iter()
+                .map(|author| co_author_completion(author, start..buffer_position))
+                .collect())
+        })
+    }
+
+    fn resolve_completions(
+        &self,
+        _: Vec<usize>,
+        _: Arc<RwLock<Box<[Completion]>>>,
+        _: &mut ViewContext<Editor>,
+    ) -> Task<Result<bool>> {
+        Task::ready(Ok(false))
+    }
+
+    fn apply_additional_edits_for_completion(
+        &self,
+        _: Model<Buffer>,
+        _: Completion,
+        _: bool,
+        _: &mut ViewContext<Editor>,
+    ) -> Task<Result<Option<language::Transaction>>> {
+        Task::ready(Ok(None))
+    }
+}
+
+/// Returns where the value of the `Co-authored-by:` trailer that the position is in
+/// starts, or `None` when the position isn't in one.
+fn co_author_start(buffer: &Buffer, position: text::Anchor) -> Option<text::Anchor> {
+    let point = position.to_point(buffer);
+    let line = buffer
+        .text_for_range(Point::new(point.row, 0)..point)
+        .collect::<String>();
+    let value_column = co_author_value_column(&line)?;
+    Some(buffer.anchor_before(Point::new(point.row, value_column as u32)))
+}
+
+fn co_author_value_column(line: &str) -> Option<usize> {
+    let trailer = line.get(..CO_AUTHOR_TRAILER.len())?;
+    if !trailer.eq_ignore_ascii_case(CO_AUTHOR_TRAILER) {
+        return None;
+    }
+    Some(line.len() - line[trailer.len()..].trim_start().len())
+}
+
+fn co_author_completion(author: &CommitAuthor, old_range: Range<text::Anchor>) -> Completion {
+    let text = author.to_string();
+    Completion {
+        old_range,
+        new_text: text.clone(),
+        label: CodeLabel::plain(text.clone(), None),
+        documentation: None,
+        server_id: None,
+        lsp_completion: lsp::CompletionItem {
+            label: text,
+            kind: Some(lsp::CompletionItemKind::TEXT),
+            ..Default::default()
+        },
+    }
+}
+
+/// Corrects the word before the cursor when a character that ends it is typed in a
+/// commit message: abbreviations are expanded anywhere in the message, and misspellings
+/// are fixed in its subject.
+pub(crate) fn autocorrect_commit_message(
+    editor: &mut Editor,
+    text: &str,
+    cx: &mut ViewContext<Editor>,
+) {
+    let settings = &EditorSettings::get_global(cx).commit_message;
+    if !settings.autocorrect
+        || text.is_empty()
+        || text.chars().any(is_word_char)
+        || editor.selections.count() != 1
+    {
+        return;
+    }
+    let Some(buffer) = editor.buffer.read(cx).as_singleton() else {
+        return;
+    };
+    let buffer = buffer.read(cx);
+    if !is_commit_message(buffer) {
+        return;
+    }
+    let selection = editor.selections.newest::<usize>(cx);
+    let Some(word_end) = selection.head().checked_sub(text.len()) else {
+        return;
+    };
+    if !selection.is_empty() {
+        return;
+    }
+
+    let line_start = buffer.point_to_offset(Point::new(buffer.offset_to_point(word_end).row, 0));
+    let text_before_line = buffer.text_for_range(0..line_start).collect::<String>();
+    let line_prefix = buffer
+        .text_for_range(line_start..word_end)
+        .collect::<String>();
+    let Some((word_start, correction)) = correct_word(&text_before_line, &line_prefix, settings)
+    else {
+        return;
+    };
+    let range = line_start + word_start..word_end;
+    editor.buffer.update(cx, |buffer, cx| {
+        buffer.edit([(range, correction)], None, cx);
+    });
+}
+
+/// Returns the start of the word at the end of `line_prefix` and its correction, if it
+/// has one. `text_before_line` is the message before the line that the word is on.
+fn correct_word(
+    text_before_line: &str,
+    line_prefix: &str,
+    settings: &CommitMessageSettings,
+) -> Option<(usize, String)> {
+    if line_prefix.starts_with('#')
+        || text_before_line
+            .split('\n')
+            .any(|line| line == SCISSORS_LINE)
+    {
+        return None;
+    }
+    let (word_start, _) = line_prefix
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word_char(*c))
+        .last()?;
+    let word = &line_prefix[word_start..];
+
+    if let Some(expansion) = settings.abbreviations.get(word) {
+        return Some((word_start, expansion.clone()));
+    }
+    // Git strips the comments and blank lines before the subject.
+    let is_subject = text_before_line
+        .split('\n')
+        .all(|line| line.trim().is_empty() || line.starts_with('#'));
+    if !is_subject {
+        return None;
+    }
+    let lowercase_word = word.to_lowercase();
+    let correction = settings
+        .spelling_corrections
+        .get(word)
+        .or_else(|| settings.spelling_corrections.get(&lowercase_word))
+        .map(String::as_str)
+        .or_else(|| {
+            SPELLING_CORRECTIONS
+                .iter()
+                .find(|(misspelling, _)| *misspelling == lowercase_word)
+                .map(|(_, correction)| *correction)
+        })?;
+    Some((word_start, match_capitalization(word, correction)))
+}
+
+/// Capitalizes the correction if the word it replaces is capitalized.
+fn match_capitalization(word: &str, correction: &str) -> String {
+    let mut correction_chars = correction.chars();
+    match (word.chars().next(), correction_chars.next()) {
+        (Some(first), Some(correction_first)) if first.is_uppercase() => correction_first
+            .to_uppercase()
+            .chain(correction_chars)
+            .collect(),
+        _ => correction.to_string(),
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\''
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext};
+    use indoc::indoc;
+    use language::{Language, LanguageConfig};
+
+    fn lints(text: &str) -> Vec<&str> {
+        commit_message_lints(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_commit_message_lints() {
+        assert_eq!(
+            lints(indoc! {"
+
+                Add a subject that goes past the fifty character limit.
+                Body without a blank line
+                # A comment that is long enough to pass the seventy-two characters of body lines
+                A body line that is long enough to pass the seventy-two character limit by far
+
+                Co-authored-by: Jane Doe <jane@example.com>
+                # ------------------------ >8 ------------------------
+                diff --git a/a_file_whose_name_is_long_enough_to_pass_the_limit_of_the_body b/a
+            "}),
+            ["imit.", ".", "Body without a blank line", "by far",]
+        );
+        assert_eq!(
+            lints("Fix the build\n\nDescribe the fix\n"),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn test_correct_word() {
+        let settings = CommitMessageSettings {
+            autocorrect: true,
+            abbreviations: [("impl".to_string(), "implement".to_string())]
+                .into_iter()
+                .collect(),
+            spelling_corrections: [("tambien".to_string(), "también".to_string())]
+                .into_iter()
+                .collect(),
+        };
+        let correct =
+            |text_before_line, line_prefix| correct_word(text_before_line, line_prefix, &settings);
+
+        assert_eq!(correct("", "Fix teh"), Some((4, "the".into())));
+        assert_eq!(correct("", "Teh"), Some((0, "The".into())));
+        assert_eq!(correct("# comment\n\n", "Fix teh"), Some((4, "the".into())));
+        assert_eq!(correct("", "Arreglar tambien"), Some((9, "también".into())));
+        // Misspellings are only fixed in the subject, and abbreviations everywhere.
+        assert_eq!(correct("Subject\n\n", "Fix teh"), None);
+        assert_eq!(
+            correct("Subject\n\n", "Also impl"),
+            Some((5, "implement".into()))
+        );
+        assert_eq!(correct("", "Fix the"), None);
+        assert_eq!(correct("", "# teh"), None);
+        assert_eq!(
+            correct(&format!("Subject\n{SCISSORS_LINE}\n"), "impl"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_co_author_value_column() {
+        assert_eq!(co_author_value_column("Co-authored-by: Ja"), Some(16));
+        assert_eq!(co_author_value_column("co-authored-by:"), Some(15));
+        assert_eq!(co_author_value_column("Signed-off-by: Ja"), None);
+        assert_eq!(co_author_value_column("Co-author"), None);
+    }
+
+    #[test]
+    fn test_staged_diff_lines() {
+        assert!(staged_diff_lines("").is_empty());
+        assert_eq!(
+            staged_diff_lines("+a\n-b\n"),
+            ["Changes to be committed:", "+a", "-b"]
+        );
+        let long_diff = "+line\n".repeat(MAX_DIFF_LINES + 3);
+        let lines = staged_diff_lines(&long_diff);
+        assert_eq!(lines.len(), MAX_DIFF_LINES + 2);
+        assert_eq!(lines.last().unwrap().as_ref(), "… 3 more lines");
+    }
+
+    #[gpui::test]
+    async fn test_autocorrect_commit_message(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        let language = Language::new(
+            LanguageConfig {
+                name: "Git Commit".into(),
+                ..Default::default()
+            },
+            None,
+        );
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(Arc::new(language)), cx));
+
+        cx.set_state("ˇ");
+        cx.simulate_input("Fix teh recieve bug\n\nteh ");
+        cx.assert_editor_state("Fix the receive bug\n\nteh ˇ");
+
+        cx.set_state("ˇ");
+        cx.simulate_input("Teh.");
+        cx.assert_editor_state("The.ˇ");
+    }
+}
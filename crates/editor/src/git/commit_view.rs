@@ -0,0 +1,259 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, IntoElement, ParentElement,
+    Render, SharedString, Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView,
+    WindowContext,
+};
+use parking_lot::Mutex;
+use project::repository::{short_sha, CommitDetails, GitRepository, RepoPath};
+use time::OffsetDateTime;
+use ui::{prelude::*, Button, Label};
+use util::ResultExt;
+use workspace::{item::Item, searchable::SearchableItemHandle, Workspace};
+
+use crate::{Editor, EditorEvent};
+
+/// A read-only item showing the message, metadata and full diff of a single commit.
+pub struct CommitView {
+    commit: CommitDetails,
+    /// The file that the diff is restricted to, if any.
+    path: Option<RepoPath>,
+    repository: Arc<Mutex<dyn GitRepository>>,
+    workspace: WeakView<Workspace>,
+    editor: View<Editor>,
+    _editor_subscription: Subscription,
+}
+
+impl CommitView {
+    /// Loads the commit with the given SHA in the background and opens it in the active pane,
+    /// activating an existing view of the same commit if there is one.
+    pub fn open(
+        sha: String,
+        repository: Arc<Mutex<dyn GitRepository>>,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        Self::open_impl(sha, None, repository, workspace, cx)
+    }
+
+    /// Like [`CommitView::open`], but only shows the changes the commit made to the given file.
+    pub fn open_for_path(
+        sha: String,
+        path: RepoPath,
+        repository: Arc<Mutex<dyn GitRepository>>,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        Self::open_impl(sha, Some(path), repository, workspace, cx)
+    }
+
+    fn open_impl(
+        sha: String,
+        path: Option<RepoPath>,
+        repository: Arc<Mutex<dyn GitRepository>>,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        let commit = cx.background_executor().spawn({
+            let repository = repository.clone();
+            let path = path.clone();
+            async move {
+                let repository = repository.lock();
+                match &path {
+                    Some(path) => repository.show_commit_for_path(&sha, path),
+                    None => repository.show_commit(&sha),
+                }
+            }
+        });
+        cx.spawn(|mut cx| async move {
+            let commit = commit.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let existing = workspace.items_of_type::<CommitView>(cx).find(|view| {
+                    let view = view.read(cx);
+                    view.commit.sha == commit.sha && view.path == path
+                });
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, cx);
+                    return;
+                }
+
+                let workspace_handle = cx.view().downgrade();
+                let view = cx
+                    .new_view(|cx| CommitView::new(commit, path, repository, workspace_handle, cx));
+                workspace.add_item(Box::new(view), cx);
+            })
+        })
+    }
+
+    fn new(
+        commit: CommitDetails,
+        path: Option<RepoPath>,
+        repository: Arc<Mutex<dyn GitRepository>>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let contents = format!("{}\n\n{}", commit.message.trim_end(), commit.diff);
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_text(contents, cx);
+            editor.set_read_only(true);
+            editor.set_show_copilot_suggestions(false);
+            editor
+        });
+        let _editor_subscription = cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        });
+        Self {
+            commit,
+            path,
+            repository,
+            workspace,
+            editor,
+            _editor_subscription,
+        }
+    }
+
+    fn open_parent(&mut self, sha: String, cx: &mut ViewContext<Self>) {
+        CommitView::open_impl(
+            sha,
+            self.path.clone(),
+            self.repository.clone(),
+            self.workspace.clone(),
+            cx,
+        )
+        .detach_and_log_err(cx);
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let commit = &self.commit;
+        let parents = commit.parent_shas.iter().enumerate().map(|(ix, sha)| {
+            let parent_sha = sha.clone();
+            Button::new(("commit-parent", ix), short_sha(sha).to_string())
+                .label_size(LabelSize::Small)
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.open_parent(parent_sha.clone(), cx);
+                }))
+        });
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(commit.summary().to_string()))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(commit.sha.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(format!("{} <{}>", commit.author_name, commit.author_email))
+                            .size(LabelSize::Small),
+                    )
+                    .children(
+                        format_commit_timestamp(commit.commit_timestamp).map(|date| {
+                            Label::new(date).size(LabelSize::Small).color(Color::Muted)
+                        }),
+                    ),
+            )
+            .when(!commit.parent_shas.is_empty(), |this| {
+                this.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Label::new("Parents:")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .children(parents),
+                )
+            })
+    }
+}
+
+fn format_commit_timestamp(timestamp: i64) -> Option<String> {
+    let timestamp = OffsetDateTime::from_unix_timestamp(timestamp).log_err()?;
+    Some(format!(
+        "{}-{:02}-{:02} {:02}:{:02} UTC",
+        timestamp.year(),
+        u8::from(timestamp.month()),
+        timestamp.day(),
+        timestamp.hour(),
+        timestamp.minute()
+    ))
+}
+
+impl EventEmitter<EditorEvent> for CommitView {}
+
+impl FocusableView for CommitView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for CommitView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .child(self.render_header(cx))
+            .child(self.editor.clone())
+    }
+}
+
+impl Item for CommitView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(workspace::item::ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        let title = match self.path.as_ref().and_then(|path| path.file_name()) {
+            Some(file_name) => format!(
+                "Commit {}: {}",
+                self.commit.short_sha(),
+                file_name.to_string_lossy()
+            ),
+            None => format!("Commit {}", self.commit.short_sha()),
+        };
+        Label::new(title)
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.commit.summary().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("commit view")
+    }
+
+    fn as_searchable(&self, _: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: std::any::TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<gpui::AnyView> {
+        if type_id == std::any::TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == std::any::TypeId::of::<Editor>() {
+            Some(self.editor.clone().into())
+        } else {
+            None
+        }
+    }
+}
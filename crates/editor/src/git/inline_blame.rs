@@ -0,0 +1,227 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use gpui::{AnyElement, AsyncWindowContext, Model, MouseButton, Task, WeakView};
+use language::{Buffer, Point};
+use parking_lot::Mutex;
+use project::repository::{CommitDetails, FileBlame, GitRepository, RepoPath};
+use settings::Settings;
+use time::OffsetDateTime;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+
+use crate::{Editor, EditorMode, EditorSettings};
+
+const INLINE_BLAME_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Default)]
+pub(crate) struct InlineBlameState {
+    enabled: bool,
+    blame: Option<InlineBlame>,
+    /// The blame of the buffer's file, which is reused until HEAD moves.
+    file_blame: Option<Arc<FileBlame>>,
+    /// The rows at HEAD of the buffer's rows, which are reused until either changes.
+    head_rows: Option<HeadRows>,
+    refresh_task: Option<Task<()>>,
+}
+
+struct HeadRows {
+    head_sha: String,
+    version: clock::Global,
+    rows: Arc<Vec<Option<u32>>>,
+}
+
+/// The commit that last changed the line of the newest cursor.
+pub(crate) struct InlineBlame {
+    /// The buffer row that the commit was blamed for.
+    pub row: u32,
+    pub commit: CommitDetails,
+}
+
+impl InlineBlameState {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn blame(&self) -> Option<&InlineBlame> {
+        self.blame.as_ref()
+    }
+}
+
+/// Blames the line of the newest cursor in the background, as of the buffer's current
+/// contents, and annotates the end of the line with the commit once it's known.
+pub(crate) fn refresh_inline_blame(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let enabled = editor.mode == EditorMode::Full
+        && editor.buffer.read(cx).is_singleton()
+        && EditorSettings::get_global(cx).inline_blame;
+    editor.inline_blame.enabled = enabled;
+    if !enabled {
+        editor.inline_blame.refresh_task = None;
+        clear_inline_blame(editor, cx);
+        return;
+    }
+
+    let row = editor.selections.newest::<Point>(cx).head().row;
+    if editor
+        .inline_blame
+        .blame
+        .as_ref()
+        .map_or(false, |blame| blame.row != row)
+    {
+        clear_inline_blame(editor, cx);
+    }
+
+    editor.inline_blame.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(INLINE_BLAME_DEBOUNCE_TIMEOUT)
+            .await;
+
+        let Some((repository, repo_path, buffer)) = editor
+            .update(&mut cx, |editor, cx| {
+                let (repository, repo_path) = editor.git_repository_for_buffer(cx).ok()?;
+                let buffer = editor.buffer.read(cx).as_singleton()?;
+                Some((repository, repo_path, buffer))
+            })
+            .ok()
+            .flatten()
+        else {
+            editor
+                .update(&mut cx, |editor, cx| clear_inline_blame(editor, cx))
+                .ok();
+            return;
+        };
+
+        let commit = blame_row(editor.clone(), repository, repo_path, buffer, row, &mut cx)
+            .await
+            .log_err()
+            .flatten();
+        editor
+            .update(&mut cx, |editor, cx| {
+                editor.inline_blame.blame = commit.map(|commit| InlineBlame { row, commit });
+                cx.notify();
+            })
+            .ok();
+    }));
+}
+
+/// Returns the commit that last changed the given row of the buffer's current contents,
+/// which may not be saved yet. Rows that changed since HEAD have no commit.
+///
+/// The file is only blamed again once HEAD moves, and the buffer's rows are only mapped
+/// to the ones at HEAD again once it's edited, so that moving the cursor is cheap.
+pub(crate) async fn blame_row(
+    editor: WeakView<Editor>,
+    repository: Arc<Mutex<dyn GitRepository>>,
+    repo_path: RepoPath,
+    buffer: Model<Buffer>,
+    row: u32,
+    cx: &mut AsyncWindowContext,
+) -> Result<Option<CommitDetails>> {
+    let head_sha = repository.lock().head_sha();
+    let (file_blame, head_rows) = editor.update(cx, |editor, cx| {
+        let state = &editor.inline_blame;
+        let file_blame = state
+            .file_blame
+            .clone()
+            .filter(|blame| blame.path == repo_path && Some(&blame.head_sha) == head_sha.as_ref());
+        let version = buffer.read(cx).version();
+        let head_rows = state
+            .head_rows
+            .as_ref()
+            .filter(|head_rows| {
+                file_blame.is_some()
+                    && Some(&head_rows.head_sha) == head_sha.as_ref()
+                    && head_rows.version == version
+            })
+            .map(|head_rows| head_rows.rows.clone());
+        (file_blame, head_rows)
+    })?;
+
+    let file_blame = match file_blame {
+        Some(file_blame) => file_blame,
+        None => {
+            // The repository is only locked to start the blame, not while it runs.
+            let blame = repository.lock().blame_at_head(&repo_path);
+            let file_blame = Arc::new(
+                cx.background_executor()
+                    .spawn(async move { blame() })
+                    .await?,
+            );
+            editor.update(cx, |editor, _| {
+                editor.inline_blame.file_blame = Some(file_blame.clone());
+            })?;
+            file_blame
+        }
+    };
+    let head_rows = match head_rows {
+        Some(head_rows) => head_rows,
+        None => {
+            let (contents, version) =
+                buffer.read_with(cx, |buffer, _| (buffer.as_rope().clone(), buffer.version()))?;
+            let rows = cx
+                .background_executor()
+                .spawn({
+                    let file_blame = file_blame.clone();
+                    async move { file_blame.head_rows(&contents.to_string()) }
+                })
+                .await?;
+            let rows = Arc::new(rows);
+            editor.update(cx, |editor, _| {
+                editor.inline_blame.head_rows = Some(HeadRows {
+                    head_sha: file_blame.head_sha.clone(),
+                    version,
+                    rows: rows.clone(),
+                });
+            })?;
+            rows
+        }
+    };
+
+    Ok(head_rows
+        .get(row as usize)
+        .copied()
+        .flatten()
+        .and_then(|head_row| file_blame.commit_for_head_row(head_row))
+        .cloned())
+}
+
+fn clear_inline_blame(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.inline_blame.blame.take().is_some() {
+        cx.notify();
+    }
+}
+
+/// Renders the annotation shown after the blamed line. Clicking it opens the commit.
+pub(crate) fn render_inline_blame(blame: &InlineBlame, cx: &mut ViewContext<Editor>) -> AnyElement {
+    let commit = &blame.commit;
+    let mut text = commit.author_name.clone();
+    if let Some(date) = format_commit_date(commit.commit_timestamp) {
+        text.push_str(", ");
+        text.push_str(&date);
+    }
+    text.push_str(" • ");
+    text.push_str(commit.summary());
+
+    let tooltip = format!("Open Commit {}", commit.short_sha());
+    let sha = commit.sha.clone();
+    h_flex()
+        .id("inline-blame")
+        .cursor_pointer()
+        .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+        .child(Label::new(text).color(Color::Muted))
+        .tooltip(move |cx| Tooltip::text(tooltip.clone(), cx))
+        .on_click(cx.listener(move |editor, _, cx| {
+            editor.open_git_commit(sha.clone(), cx);
+        }))
+        .into_any_element()
+}
+
+fn format_commit_date(timestamp: i64) -> Option<String> {
+    let timestamp = OffsetDateTime::from_unix_timestamp(timestamp).log_err()?;
+    Some(format!(
+        "{}-{:02}-{:02}",
+        timestamp.year(),
+        u8::from(timestamp.month()),
+        timestamp.day()
+    ))
+}
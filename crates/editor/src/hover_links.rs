@@ -1,19 +1,21 @@
 use crate::{
     element::PointForPosition,
     hover_popover::{self, InlayHover},
-    Anchor, Editor, EditorSnapshot, GoToDefinition, GoToTypeDefinition, InlayId, SelectPhase,
+    Anchor, Editor, EditorSettings, EditorSnapshot, GoToDefinition, GoToTypeDefinition, InlayId,
+    SelectPhase,
 };
 use gpui::{px, AsyncWindowContext, Model, Modifiers, Task, ViewContext};
 use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink,
-    ResolveState,
+    DocumentLinkTarget, HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip,
+    Location, LocationLink, ResolveState,
 };
+use settings::Settings;
 use std::ops::Range;
 use theme::ActiveTheme as _;
-use util::TryFutureExt;
+use util::{ResultExt, TryFutureExt};
 
 #[derive(Debug)]
 pub struct HoveredLinkState {
@@ -92,7 +94,9 @@ impl Editor {
         modifiers: Modifiers,
         cx: &mut ViewContext<Self>,
     ) {
-        if !modifiers.command || self.has_pending_selection() {
+        let multi_cursor_modifier = EditorSettings::get_global(cx).multi_cursor_modifier;
+        let goes_to_definition = multi_cursor_modifier.goes_to_definition(&modifiers);
+        if !goes_to_definition || self.has_pending_selection() {
             self.hide_hovered_link(cx);
             return;
         }
@@ -112,7 +116,7 @@ impl Editor {
                     &snapshot,
                     point_for_position,
                     self,
-                    modifiers.command,
+                    goes_to_definition,
                     modifiers.shift,
                     cx,
                 );
@@ -138,7 +142,11 @@ impl Editor {
                     cx.focus(&self.focus_handle);
                 }
 
-                self.navigate_to_hover_links(hovered_link_state.links, modifiers.alt, cx);
+                // Holding the multi-cursor modifier as well opens the definition in a split.
+                let split = EditorSettings::get_global(cx)
+                    .multi_cursor_modifier
+                    .adds_cursor(&modifiers);
+                self.navigate_to_hover_links(hovered_link_state.links, split, cx);
                 return;
             }
         }
@@ -422,7 +430,39 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    // Prefer the links reported by the language server, such as import specifiers,
+                    // falling back to detecting plain URLs in the text.
+                    let document_link = if let Some(project) = project.as_ref() {
+                        project
+                            .update(&mut cx, |project, cx| {
+                                project.document_link(&buffer, buffer_position, cx)
+                            })?
+                            .await
+                            .log_err()
+                            .flatten()
+                    } else {
+                        None
+                    };
+
+                    if let Some(document_link) = document_link {
+                        let start = snapshot
+                            .anchor_in_excerpt(excerpt_id.clone(), document_link.range.start);
+                        let end =
+                            snapshot.anchor_in_excerpt(excerpt_id.clone(), document_link.range.end);
+                        let link = match document_link.target {
+                            DocumentLinkTarget::Url(url) => HoverLink::Url(url),
+                            DocumentLinkTarget::Location(target) => HoverLink::Text(LocationLink {
+                                origin: Some(Location {
+                                    buffer: buffer.clone(),
+                                    range: document_link.range,
+                                }),
+                                target,
+                            }),
+                        };
+                        Some((Some(RangeInEditor::Text(start..end)), vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.update(&mut cx, |_, _| {
                             let start =
                                 snapshot.anchor_in_excerpt(excerpt_id.clone(), url_range.start);
@@ -633,6 +673,7 @@ mod tests {
     use super::*;
     use crate::{
         display_map::ToDisplayPoint,
+        editor_settings::MultiCursorModifier,
         editor_tests::init_test,
         inlay_hint_cache::tests::{cached_hint_labels, visible_hint_labels},
         test::editor_lsp_test_context::EditorLspTestContext,
@@ -643,6 +684,7 @@ mod tests {
     use indoc::indoc;
     use language::language_settings::InlayHintSettings;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
+    use settings::SettingsStore;
     use util::assert_set_eq;
     use workspace::item::Item;
 
@@ -1155,4 +1197,104 @@ mod tests {
             Some("https://zed.dev/channel/had-(oops)".into())
         );
     }
+
+    #[gpui::test]
+    async fn test_remapped_multi_cursor_modifier(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.multi_cursor_modifier = Some(MultiCursorModifier::CmdOrCtrl);
+                });
+            });
+        });
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            Let's test a [complex](https://zed.dev/channel/had-(oops)) caseˇ.
+        "});
+        let screen_coord = cx.pixel_position(indoc! {"
+            Let's test a [complex](https://zed.dev/channel/had-(ˇoops)) case.
+        "});
+
+        // Cmd-click adds a cursor instead of following the link.
+        cx.simulate_mouse_move(screen_coord, Modifiers::command());
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            Let's test a [complex](https://zed.dev/channel/had-(oops)) case.
+        "});
+        cx.simulate_click(screen_coord, Modifiers::command());
+        cx.assert_editor_state(indoc! {"
+            Let's test a [complex](https://zed.dev/channel/had-(ˇoops)) caseˇ.
+        "});
+        assert_eq!(cx.opened_url(), None);
+
+        // Alt-click follows the link.
+        let alt = Modifiers {
+            alt: true,
+            ..Default::default()
+        };
+        cx.simulate_mouse_move(screen_coord, alt);
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            Let's test a [complex](«https://zed.dev/channel/had-(oops)ˇ») case.
+        "});
+        cx.simulate_click(screen_coord, alt);
+        assert_eq!(
+            cx.opened_url(),
+            Some("https://zed.dev/channel/had-(oops)".into())
+        );
+    }
+
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(false),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            // See the docs for seˇrde.
+        "});
+
+        let hover_point = cx.pixel_position(indoc! {"
+            // See the docs for serˇde.
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            // See the docs for «serde».
+        "});
+
+        let mut requests = cx.handle_request::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: Some("https://docs.rs/serde".parse().unwrap()),
+                    tooltip: None,
+                    data: None,
+                }]))
+            },
+        );
+
+        cx.simulate_mouse_move(hover_point, Modifiers::command());
+        requests.next().await;
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            // See the docs for «serde».
+        "});
+
+        cx.simulate_click(hover_point, Modifiers::command());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+    }
 }
@@ -7,8 +7,8 @@ use crate::{
 use futures::FutureExt;
 use gpui::{
     div, px, AnyElement, CursorStyle, Hsla, InteractiveElement, IntoElement, Model, MouseButton,
-    ParentElement, Pixels, SharedString, Size, StatefulInteractiveElement, Styled, Task,
-    ViewContext, WeakView,
+    ParentElement, Pixels, ScrollHandle, SharedString, Size, StatefulInteractiveElement, Styled,
+    Task, ViewContext, WeakView,
 };
 use language::{markdown, Bias, DiagnosticEntry, Language, LanguageRegistry, ParsedMarkdown};
 
@@ -111,6 +111,7 @@ pub fn hover_at_inlay(editor: &mut Editor, inlay_hover: InlayHover, cx: &mut Vie
                     symbol_range: RangeInEditor::Inlay(inlay_hover.range.clone()),
                     blocks,
                     parsed_content,
+                    scroll_handle: ScrollHandle::new(),
                 };
 
                 this.update(&mut cx, |this, cx| {
@@ -285,6 +286,7 @@ fn show_hover(
                     local_diagnostic.map(|local_diagnostic| DiagnosticPopover {
                         local_diagnostic,
                         primary_diagnostic,
+                        scroll_handle: ScrollHandle::new(),
                     });
             })?;
 
@@ -316,6 +318,7 @@ fn show_hover(
                         symbol_range: RangeInEditor::Text(range),
                         blocks,
                         parsed_content,
+                        scroll_handle: ScrollHandle::new(),
                     })
                 }
 
@@ -484,6 +487,7 @@ pub struct InfoPopover {
     symbol_range: RangeInEditor,
     pub blocks: Vec<HoverBlock>,
     parsed_content: ParsedMarkdown,
+    pub(crate) scroll_handle: ScrollHandle,
 }
 
 impl InfoPopover {
@@ -499,11 +503,15 @@ impl InfoPopover {
             .elevation_2(cx)
             .p_2()
             .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
             .max_w(max_size.width)
             .max_h(max_size.height)
             // Prevent a mouse move on the popover from being propagated to the editor,
             // because that would dismiss the popover.
             .on_mouse_move(|_, cx| cx.stop_propagation())
+            // Prevent a mouse down on the popover from being propagated to the editor,
+            // because that would move the cursor.
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
             .child(crate::render_parsed_markdown(
                 "content",
                 &self.parsed_content,
@@ -519,6 +527,7 @@ impl InfoPopover {
 pub struct DiagnosticPopover {
     local_diagnostic: DiagnosticEntry<Anchor>,
     primary_diagnostic: Option<DiagnosticEntry<Anchor>>,
+    pub(crate) scroll_handle: ScrollHandle,
 }
 
 impl DiagnosticPopover {
@@ -567,6 +576,7 @@ impl DiagnosticPopover {
             .id("diagnostic")
             .elevation_2(cx)
             .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
             .px_2()
             .py_1()
             .bg(diagnostic_colors.background)
@@ -913,6 +923,101 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_scroll_hover_popovers_from_keyboard(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn teˇst() { println!(); }
+        "});
+        let range = cx.text_anchor_range(indoc! {"
+            fn «test»() { println!(); }
+        "});
+        cx.update_buffer(|buffer, cx| {
+            let snapshot = buffer.text_snapshot();
+            let set = DiagnosticSet::from_sorted_entries(
+                vec![DiagnosticEntry {
+                    range,
+                    diagnostic: Diagnostic {
+                        message: "A test diagnostic message.".to_string(),
+                        ..Default::default()
+                    },
+                }],
+                &snapshot,
+            );
+            buffer.update_diagnostics(LanguageServerId(0), set, cx);
+        });
+
+        let range = cx.lsp_range(indoc! {"
+            fn «test»() { println!(); }
+        "});
+        let mut requests =
+            cx.handle_request::<lsp::request::HoverRequest, _, _>(move |_, _, _| async move {
+                Ok(Some(lsp::Hover {
+                    contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                        kind: lsp::MarkupKind::Markdown,
+                        value: "some docs".to_string(),
+                    }),
+                    range: Some(range),
+                }))
+            });
+        cx.update_editor(|editor, cx| hover(editor, &Hover, cx));
+        requests.next().await;
+        cx.condition(|editor, _| {
+            editor.hover_state.info_popover.is_some()
+                && editor.hover_state.diagnostic_popover.is_some()
+        })
+        .await;
+
+        cx.update_editor(|editor, cx| {
+            assert!(editor.key_context(cx).contains("showing_hover"));
+            let line_height = editor
+                .style()
+                .unwrap()
+                .text
+                .line_height_in_pixels(cx.rem_size());
+
+            // The documentation is scrolled before the diagnostic.
+            editor.scroll_popover_down(&crate::ScrollPopoverDown, cx);
+            let info_scroll_handle = editor
+                .hover_state
+                .info_popover
+                .as_ref()
+                .unwrap()
+                .scroll_handle
+                .clone();
+            let diagnostic_scroll_handle = editor
+                .hover_state
+                .diagnostic_popover
+                .as_ref()
+                .unwrap()
+                .scroll_handle
+                .clone();
+            assert_eq!(info_scroll_handle.offset().y, -line_height * 3.);
+            assert_eq!(diagnostic_scroll_handle.offset().y, Pixels::ZERO);
+
+            // Scrolling up stops at the top.
+            editor.scroll_popover_up(&crate::ScrollPopoverUp, cx);
+            editor.scroll_popover_up(&crate::ScrollPopoverUp, cx);
+            assert_eq!(info_scroll_handle.offset().y, Pixels::ZERO);
+
+            // Without documentation, the diagnostic is scrolled.
+            editor.hover_state.info_popover = None;
+            assert!(editor.key_context(cx).contains("showing_hover"));
+            editor.scroll_popover_down(&crate::ScrollPopoverDown, cx);
+            assert_eq!(diagnostic_scroll_handle.offset().y, -line_height * 3.);
+        });
+    }
+
     #[gpui::test]
     fn test_render_blocks(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -0,0 +1,230 @@
+use std::{cmp::Ordering, ops::Range};
+
+use anyhow::{anyhow, Result};
+use gpui::{AppContext, Model};
+use itertools::Itertools;
+use language::{language_settings::ImportGroups, Buffer, Import};
+
+/// Groups and sorts the import statements matched by the imports query of the
+/// buffer's language, separating the groups with blank lines.
+pub(crate) fn sort_imports_by_syntax(buffer: &Model<Buffer>, cx: &mut AppContext) -> Result<()> {
+    let snapshot = buffer.read(cx).snapshot();
+    let imports = snapshot.imports();
+    let Some(first_import) = imports.first() else {
+        return Err(anyhow!("no imports were found to sort"));
+    };
+    let groups = &snapshot
+        .settings_at(first_import.range.start, cx)
+        .import_groups;
+    let edits = sort_import_edits(&snapshot.text(), &imports, groups);
+    if !edits.is_empty() {
+        buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+    }
+    Ok(())
+}
+
+/// Returns the edits that sort each block of imports, so that the statements are
+/// never moved past the comments and other items that end a block.
+fn sort_import_edits(
+    text: &str,
+    imports: &[Import],
+    groups: &ImportGroups,
+) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    for block in import_blocks(text, imports) {
+        let block = &imports[block];
+        let range = block[0].range.start..block[block.len() - 1].range.end;
+        let sorted = sort_block(text, block, groups);
+        if text[range.clone()] != sorted {
+            edits.push((range, sorted));
+        }
+    }
+    edits
+}
+
+/// Returns the indices of the imports in each block of imports, which are the
+/// imports that are only separated by whitespace.
+pub(crate) fn import_blocks(text: &str, imports: &[Import]) -> Vec<Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut block_start = 0;
+    for ix in 1..=imports.len() {
+        let block_ends = imports.get(ix).map_or(true, |import| {
+            !text[imports[ix - 1].range.end..import.range.start]
+                .trim()
+                .is_empty()
+        });
+        if block_ends {
+            blocks.push(block_start..ix);
+            block_start = ix;
+        }
+    }
+    blocks
+}
+
+fn sort_block(text: &str, block: &[Import], groups: &ImportGroups) -> String {
+    let mut grouped: [Vec<&Import>; 3] = Default::default();
+    for import in block {
+        grouped[group_index(&import.path, groups)].push(import);
+    }
+    grouped
+        .iter_mut()
+        .filter(|imports| !imports.is_empty())
+        .map(|imports| {
+            imports.sort_by(|a, b| compare_paths(&a.path, &b.path));
+            imports
+                .iter()
+                .map(|import| &text[import.range.clone()])
+                .dedup()
+                .join("\n")
+        })
+        .join("\n\n")
+}
+
+/// Returns 0 for the standard library, 1 for external packages and 2 for the
+/// project's own modules.
+fn group_index(path: &str, groups: &ImportGroups) -> usize {
+    let path = normalize_path(path);
+    let matches = |prefix: &String| {
+        path.strip_prefix(prefix.as_str()).map_or(false, |rest| {
+            // A prefix that is a name only matches whole path segments, so that
+            // `std` doesn't match `stdx`.
+            !prefix.ends_with(is_identifier_char) || !rest.starts_with(is_identifier_char)
+        })
+    };
+    if groups.std.iter().any(matches) {
+        0
+    } else if groups.internal.iter().any(matches) {
+        2
+    } else {
+        1
+    }
+}
+
+fn compare_paths(a: &str, b: &str) -> Ordering {
+    let (a, b) = (normalize_path(a), normalize_path(b));
+    a.to_lowercase()
+        .cmp(&b.to_lowercase())
+        .then_with(|| a.cmp(b))
+}
+
+fn normalize_path(path: &str) -> &str {
+    path.trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .trim_start_matches("::")
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent as _;
+
+    fn imports(text: &str, statement_prefix: &str) -> Vec<Import> {
+        let mut imports = Vec::new();
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            let statement = line.trim_end();
+            if let Some(path) = statement.strip_prefix(statement_prefix) {
+                let path = path.split([';', ' ']).next().unwrap();
+                imports.push(Import {
+                    range: offset..offset + statement.len(),
+                    path: path.to_string(),
+                });
+            }
+            offset += line.len();
+        }
+        imports
+    }
+
+    fn sort(text: &str, statement_prefix: &str, groups: &ImportGroups) -> String {
+        let mut text = text.to_string();
+        let edits = sort_import_edits(&text, &imports(&text, statement_prefix), groups);
+        for (range, new_text) in edits.into_iter().rev() {
+            text.replace_range(range, &new_text);
+        }
+        text
+    }
+
+    #[test]
+    fn test_sort_rust_imports() {
+        let groups = ImportGroups {
+            std: vec!["std".into(), "core".into()],
+            internal: vec!["crate".into(), "super".into()],
+        };
+        let text = "
+            use crate::editor::Editor;
+            use serde::Serialize;
+            use std::sync::Arc;
+            use stdx::Thing;
+            use anyhow::Result;
+
+            use super::*;
+            use std::sync::Arc;
+            use core::fmt;
+
+            // Imports after a comment are sorted separately.
+            use zed::Zed;
+            use gpui::App;
+
+            fn main() {}
+        "
+        .unindent();
+        assert_eq!(
+            sort(&text, "use ", &groups),
+            "
+            use core::fmt;
+            use std::sync::Arc;
+
+            use anyhow::Result;
+            use serde::Serialize;
+            use stdx::Thing;
+
+            use crate::editor::Editor;
+            use super::*;
+
+            // Imports after a comment are sorted separately.
+            use gpui::App;
+            use zed::Zed;
+
+            fn main() {}
+            "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn test_sort_typescript_imports() {
+        let groups = ImportGroups {
+            std: vec!["node:".into()],
+            internal: vec![".".into(), "@/".into()],
+        };
+        let text = r#"
+            from "./utils";
+            from "react";
+            from "@/components/Button";
+            from "node:fs";
+            from "Lodash";
+            from "axios";
+        "#
+        .unindent();
+        assert_eq!(
+            sort(&text, "from ", &groups),
+            r#"
+            from "node:fs";
+
+            from "axios";
+            from "Lodash";
+            from "react";
+
+            from "./utils";
+            from "@/components/Button";
+            "#
+            .unindent()
+        );
+
+        let sorted = sort(&text, "from ", &groups);
+        assert_eq!(sort(&sorted, "from ", &groups), sorted);
+    }
+}
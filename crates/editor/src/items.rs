@@ -1,7 +1,7 @@
 use crate::{
-    editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll,
-    Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
-    NavigationData, ToPoint as _,
+    editor_settings::SeedQuerySetting, json_tools, persistence::DB, scroll::ScrollAnchor, Anchor,
+    Autoscroll, Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer,
+    MultiBufferSnapshot, NavigationData, SoftWrap, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -12,8 +12,8 @@ use gpui::{
     Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use language::{
-    proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, CharKind, OffsetRangeExt,
-    Point, SelectionGoal,
+    language_settings, proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, CharKind,
+    OffsetRangeExt, Point, SelectionGoal,
 };
 use project::repository::GitFileStatus;
 use project::{search::SearchQuery, FormatTrigger, Item as _, Project, ProjectPath};
@@ -41,10 +41,13 @@ use workspace::{
 use workspace::{
     item::{FollowableItem, Item, ItemEvent, ItemHandle, ProjectItem},
     searchable::{Direction, SearchEvent, SearchableItem, SearchableItemHandle},
-    ItemId, ItemNavHistory, Pane, ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
+    ItemId, ItemNavHistory, Pane, Toast, ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
 };
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
+/// Editors suggest turning on soft wrap when their file has lines longer than this.
+const SOFT_WRAP_SUGGESTION_LINE_LEN: u32 = 10_000;
+const SOFT_WRAP_SUGGESTION_TOAST_ID: usize = 0x50f7_3a9b;
 
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
@@ -831,6 +834,26 @@ impl Item for Editor {
             text: filename,
             highlights: None,
         }];
+
+        // The keys of a JSON document are its symbols, but they don't say which array
+        // elements the cursor is in, so show the path of the value under the cursor.
+        if buffer
+            .language()
+            .map_or(false, |language| json_tools::is_json_language(language))
+        {
+            let snapshot = multibuffer.snapshot(cx);
+            if let Some(path) = snapshot
+                .point_to_buffer_offset(cursor)
+                .and_then(|(buffer, offset)| json_tools::json_path_at(buffer, offset))
+            {
+                breadcrumbs.push(BreadcrumbText {
+                    text: path,
+                    highlights: None,
+                });
+            }
+            return Some(breadcrumbs);
+        }
+
         breadcrumbs.extend(symbols.into_iter().map(|symbol| BreadcrumbText {
             text: symbol.text,
             highlights: Some(symbol.highlight_ranges),
@@ -878,6 +901,12 @@ impl Item for Editor {
                 }
             })
             .detach();
+
+            if buffer.read(cx).text_summary().longest_row_chars > SOFT_WRAP_SUGGESTION_LINE_LEN
+                && matches!(self.soft_wrap_mode(cx), SoftWrap::None)
+            {
+                suggest_soft_wrap(workspace.weak_handle(), cx);
+            }
         }
     }
 
@@ -964,6 +993,30 @@ impl Item for Editor {
     }
 }
 
+/// Suggests turning on soft wrap in an editor whose file has very long lines, such as
+/// minified code, which are hard to read without it.
+fn suggest_soft_wrap(workspace: WeakView<Workspace>, cx: &mut ViewContext<Editor>) {
+    let editor = cx.view().downgrade();
+    cx.defer(move |_, cx| {
+        workspace
+            .update(cx, |workspace, cx| {
+                let toast = Toast::new(
+                    SOFT_WRAP_SUGGESTION_TOAST_ID,
+                    "This file has very long lines, which are easier to read with soft wrap.",
+                )
+                .on_click("Turn on soft wrap", move |cx| {
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.set_soft_wrap_mode(language_settings::SoftWrap::EditorWidth, cx)
+                        })
+                        .ok();
+                });
+                workspace.show_toast(toast, cx);
+            })
+            .ok();
+    });
+}
+
 impl ProjectItem for Editor {
     type Item = Buffer;
 
@@ -0,0 +1,601 @@
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use gpui::{View, ViewContext, WindowContext};
+use language::{BufferSnapshot, Language, SelectionGoal};
+use multi_buffer::{MultiBufferSnapshot, ToPoint as _};
+use serde_json::Value;
+use workspace::Toast;
+
+use crate::{
+    display_map::ToDisplayPoint, element::register_action, scroll::Autoscroll, Bias, Editor,
+    FoldJsonRecords, FormatJsonSelection, MinifyJsonSelection, MoveToNextJsonRecord,
+    MoveToPreviousJsonRecord,
+};
+
+const INVALID_JSON_TOAST_ID: usize = 0x4a50_7e1d;
+
+pub fn apply_related_actions(editor: &View<Editor>, cx: &mut WindowContext) {
+    let is_json_related = editor.update(cx, |editor, cx| {
+        editor
+            .buffer()
+            .read(cx)
+            .all_buffers()
+            .iter()
+            .any(|b| match b.read(cx).language() {
+                Some(l) => is_json_language(l),
+                None => false,
+            })
+    });
+
+    if is_json_related {
+        register_action(editor, cx, format_json_selection);
+        register_action(editor, cx, minify_json_selection);
+        register_action(editor, cx, fold_json_records);
+        register_action(editor, cx, move_to_next_json_record);
+        register_action(editor, cx, move_to_previous_json_record);
+    }
+}
+
+pub(crate) fn is_json_language(language: &Language) -> bool {
+    matches!(language.name().as_ref(), "JSON" | "JSON Lines")
+}
+
+/// Pretty-prints the JSON in each selection, or in the whole buffer when nothing is
+/// selected, with the buffer's indentation settings.
+pub fn format_json_selection(
+    editor: &mut Editor,
+    _: &FormatJsonSelection,
+    cx: &mut ViewContext<Editor>,
+) {
+    transform_json_selections(editor, cx, format_json);
+}
+
+/// Removes the whitespace from the JSON in each selection, or in the whole buffer when
+/// nothing is selected, writing each value on its own line.
+pub fn minify_json_selection(
+    editor: &mut Editor,
+    _: &MinifyJsonSelection,
+    cx: &mut ViewContext<Editor>,
+) {
+    transform_json_selections(editor, cx, |text, _| minify_json(text));
+}
+
+/// Folds the contents of every top-level value, so that each record of a JSON Lines
+/// file takes up a single line, even when soft wrap is on.
+pub fn fold_json_records(editor: &mut Editor, _: &FoldJsonRecords, cx: &mut ViewContext<Editor>) {
+    let multi_buffer = editor.buffer().read(cx).snapshot(cx);
+    let Some((_, _, buffer)) = multi_buffer.as_singleton() else {
+        return;
+    };
+    let fold_ranges = json_record_ranges(buffer)
+        .into_iter()
+        .filter(|range| range.len() > 2)
+        .map(|range| range.start + 1..range.end - 1)
+        .collect::<Vec<_>>();
+    editor.fold_ranges(fold_ranges, true, cx);
+}
+
+pub fn move_to_next_json_record(
+    editor: &mut Editor,
+    _: &MoveToNextJsonRecord,
+    cx: &mut ViewContext<Editor>,
+) {
+    move_to_json_record(editor, cx, |starts, offset| {
+        starts.iter().copied().find(|start| *start > offset)
+    });
+}
+
+pub fn move_to_previous_json_record(
+    editor: &mut Editor,
+    _: &MoveToPreviousJsonRecord,
+    cx: &mut ViewContext<Editor>,
+) {
+    move_to_json_record(editor, cx, |starts, offset| {
+        starts.iter().copied().rev().find(|start| *start < offset)
+    });
+}
+
+fn move_to_json_record(
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+    target: impl Fn(&[usize], usize) -> Option<usize>,
+) {
+    let multi_buffer = editor.buffer().read(cx).snapshot(cx);
+    let Some((_, _, buffer)) = multi_buffer.as_singleton() else {
+        return;
+    };
+    let starts = json_record_ranges(buffer)
+        .into_iter()
+        .map(|range| range.start)
+        .collect::<Vec<_>>();
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.move_cursors_with(|map, head, goal| {
+            let offset = head.to_offset(map, Bias::Left);
+            match target(&starts, offset) {
+                Some(offset) => (offset.to_display_point(map), SelectionGoal::None),
+                None => (head, goal),
+            }
+        });
+    });
+}
+
+fn transform_json_selections(
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+    transform: impl Fn(&str, &str) -> Result<String>,
+) {
+    let buffer = editor.buffer().read(cx).snapshot(cx);
+    let selections = editor.selections.all::<usize>(cx);
+    let transform_buffer = selections.iter().all(|selection| selection.is_empty());
+    let ranges = if transform_buffer {
+        vec![0..buffer.len()]
+    } else {
+        selections
+            .iter()
+            .filter(|selection| !selection.is_empty())
+            .map(|selection| selection.range())
+            .collect()
+    };
+
+    let mut edits = Vec::new();
+    let mut new_selections = Vec::new();
+    let mut selection_adjustment = 0isize;
+    for range in ranges {
+        // Leave the whitespace around the selected values where it is.
+        let text = buffer.text_for_range(range.clone()).collect::<String>();
+        let leading_whitespace = text.len() - text.trim_start().len();
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let start = range.start + leading_whitespace;
+        let range = start..start + text.len();
+
+        let settings = buffer.settings_at(range.start, cx);
+        let indent = if settings.hard_tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(settings.tab_size.get() as usize)
+        };
+        let new_text = match transform(text, &indent) {
+            Ok(new_text) => indent_continuation_lines(&buffer, range.start, new_text),
+            Err(error) => {
+                show_invalid_json_toast(editor, error, cx);
+                return;
+            }
+        };
+
+        let start = (range.start as isize + selection_adjustment) as usize;
+        new_selections.push(start..start + new_text.len());
+        selection_adjustment += new_text.len() as isize - range.len() as isize;
+        edits.push((range, new_text));
+    }
+    if edits.is_empty() {
+        return;
+    }
+
+    editor.transact(cx, |editor, cx| {
+        editor
+            .buffer()
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        if !transform_buffer {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_selections)
+            });
+        }
+    });
+}
+
+/// Indents every line after the first one as deep as the line the text starts on,
+/// so that values nested in a document stay aligned with their surroundings.
+fn indent_continuation_lines(buffer: &MultiBufferSnapshot, offset: usize, text: String) -> String {
+    let row = offset.to_point(buffer).row;
+    let indent = buffer.indent_size_for_line(row).chars().collect::<String>();
+    if indent.is_empty() {
+        text
+    } else {
+        text.replace('\n', &format!("\n{indent}"))
+    }
+}
+
+fn show_invalid_json_toast(editor: &Editor, error: anyhow::Error, cx: &mut ViewContext<Editor>) {
+    let message = format!("Invalid JSON: {error}");
+    if let Some(workspace) = editor.workspace() {
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(Toast::new(INVALID_JSON_TOAST_ID, message), cx)
+        })
+    }
+}
+
+/// Parses a sequence of JSON values, such as a single document or the records of a
+/// JSON Lines file.
+fn parse_json_values(text: &str) -> Result<Vec<Value>> {
+    let values = serde_json::Deserializer::from_str(text)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.is_empty() {
+        Err(anyhow!("no JSON values found"))
+    } else {
+        Ok(values)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum JsonTokenKind {
+    Punctuation,
+    /// A string, number or literal.
+    Value,
+    LineComment,
+    BlockComment,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct JsonToken<'a> {
+    kind: JsonTokenKind,
+    text: &'a str,
+    /// Whether the token was the first one on its line.
+    starts_line: bool,
+}
+
+impl JsonToken<'_> {
+    fn is_comment(&self) -> bool {
+        matches!(
+            self.kind,
+            JsonTokenKind::LineComment | JsonTokenKind::BlockComment
+        )
+    }
+
+    /// Whether the token ends a value, after which the next top-level value starts.
+    fn ends_value(&self) -> bool {
+        self.kind == JsonTokenKind::Value || matches!(self.text, "}" | "]")
+    }
+}
+
+/// Splits JSON, including the comments and trailing commas of JSONC, into tokens.
+fn tokenize_json(text: &str) -> Result<Vec<JsonToken>> {
+    let mut tokens = Vec::new();
+    let mut starts_line = true;
+    let mut offset = 0;
+    while let Some(c) = text[offset..].chars().next() {
+        let rest = &text[offset..];
+        if c.is_whitespace() {
+            starts_line |= c == '\n';
+            offset += c.len_utf8();
+            continue;
+        }
+
+        let (kind, len) = if matches!(c, '{' | '}' | '[' | ']' | ':' | ',') {
+            (JsonTokenKind::Punctuation, 1)
+        } else if c == '"' {
+            let mut escaped = false;
+            let end = rest[1..]
+                .find(|c: char| {
+                    let is_end = c == '"' && !escaped;
+                    escaped = c == '\\' && !escaped;
+                    is_end
+                })
+                .ok_or_else(|| anyhow!("unterminated string"))?;
+            (JsonTokenKind::Value, end + 2)
+        } else if rest.starts_with("//") {
+            (
+                JsonTokenKind::LineComment,
+                rest.find('\n').unwrap_or(rest.len()),
+            )
+        } else if rest.starts_with("/*") {
+            let end = rest[2..]
+                .find("*/")
+                .ok_or_else(|| anyhow!("unterminated comment"))?;
+            (JsonTokenKind::BlockComment, end + 4)
+        } else {
+            let len = rest
+                .find(|c: char| c.is_whitespace() || "{}[]:,\"/".contains(c))
+                .unwrap_or(rest.len());
+            (JsonTokenKind::Value, len.max(c.len_utf8()))
+        };
+        tokens.push(JsonToken {
+            kind,
+            text: rest[..len].trim_end(),
+            starts_line,
+        });
+        starts_line = false;
+        offset += len;
+    }
+    Ok(tokens)
+}
+
+/// Tokenizes the given JSON, after checking that it's a sequence of valid values.
+/// Comments and trailing commas are allowed, as in JSONC.
+fn parse_json_tokens(text: &str) -> Result<Vec<JsonToken>> {
+    let tokens = tokenize_json(text)?;
+    let significant_tokens = tokens
+        .iter()
+        .filter(|token| !token.is_comment())
+        .collect::<Vec<_>>();
+    let mut checked_text = String::new();
+    for (ix, token) in significant_tokens.iter().enumerate() {
+        let is_trailing_comma = token.text == ","
+            && significant_tokens
+                .get(ix + 1)
+                .map_or(false, |next| matches!(next.text, "}" | "]"));
+        if !is_trailing_comma {
+            checked_text.push_str(token.text);
+            checked_text.push(' ');
+        }
+    }
+    parse_json_values(&checked_text)?;
+    Ok(tokens)
+}
+
+/// Reformats JSON from its tokens, so that numbers, duplicate keys and comments are
+/// kept exactly as they were written.
+fn format_json(text: &str, indent: &str) -> Result<String> {
+    let tokens = parse_json_tokens(text)?;
+    let mut formatted = String::new();
+    let mut depth = 0;
+    let mut line_break = false;
+    let mut previous: Option<&JsonToken> = None;
+    for token in &tokens {
+        if matches!(token.text, "}" | "]") {
+            depth -= 1;
+            // Empty objects and arrays stay on one line.
+            if previous.map_or(false, |previous| matches!(previous.text, "{" | "[")) {
+                line_break = false;
+            } else {
+                line_break = true;
+            }
+        }
+
+        if token.is_comment() && !token.starts_line && previous.is_some() {
+            // Comments after a value stay on its line.
+            if !formatted.ends_with(' ') {
+                formatted.push(' ');
+            }
+        } else if line_break || (token.starts_line && token.is_comment() && previous.is_some()) {
+            formatted.push('\n');
+            formatted.push_str(&indent.repeat(depth));
+            line_break = false;
+        } else if previous.map_or(false, |previous| {
+            previous.kind == JsonTokenKind::BlockComment && !matches!(token.text, "," | ":")
+        }) {
+            formatted.push(' ');
+        }
+        formatted.push_str(token.text);
+
+        match token.kind {
+            JsonTokenKind::Punctuation => match token.text {
+                "{" | "[" => {
+                    depth += 1;
+                    line_break = true;
+                }
+                "," => line_break = true,
+                ":" => formatted.push(' '),
+                _ => {}
+            },
+            JsonTokenKind::LineComment => line_break = true,
+            JsonTokenKind::BlockComment => line_break |= token.starts_line,
+            JsonTokenKind::Value => {}
+        }
+        if depth == 0 && token.ends_value() {
+            line_break = true;
+        }
+        previous = Some(token);
+    }
+    Ok(formatted)
+}
+
+fn minify_json(text: &str) -> Result<String> {
+    let tokens = parse_json_tokens(text)?;
+    let mut minified = String::new();
+    let mut depth = 0;
+    let mut line_break = false;
+    for token in &tokens {
+        if line_break {
+            minified.push('\n');
+            line_break = false;
+        }
+        minified.push_str(token.text);
+        match token.text {
+            "{" | "[" => depth += 1,
+            "}" | "]" => depth -= 1,
+            _ => {}
+        }
+        // Line comments end at the end of the line, and each top-level value is
+        // written on its own line.
+        line_break = token.kind == JsonTokenKind::LineComment || (depth == 0 && token.ends_value());
+    }
+    Ok(minified)
+}
+
+/// Returns the ranges of the top-level values in a JSON buffer, which are the records
+/// of a JSON Lines file.
+fn json_record_ranges(buffer: &BufferSnapshot) -> Vec<Range<usize>> {
+    let Some(layer) = buffer.syntax_layers().next() else {
+        return Vec::new();
+    };
+    let root = layer.node();
+    let mut cursor = root.walk();
+    let ranges = root
+        .named_children(&mut cursor)
+        .filter(|node| node.kind() != "comment")
+        .map(|node| node.byte_range())
+        .collect();
+    ranges
+}
+
+/// Returns the path of the value at the given offset in a JSON buffer, such as
+/// `foo.bar[3].baz`.
+pub(crate) fn json_path_at(buffer: &BufferSnapshot, offset: usize) -> Option<String> {
+    let layer = buffer.syntax_layers().next()?;
+    let mut node = layer.node().descendant_for_byte_range(offset, offset)?;
+    let mut segments = Vec::new();
+    while let Some(parent) = node.parent() {
+        match parent.kind() {
+            "pair" => {
+                let key = parent.child_by_field_name("key")?;
+                let key = buffer.text_for_range(key.byte_range()).collect::<String>();
+                segments.push(key_segment(&key));
+            }
+            "array" if node.is_named() && node.kind() != "comment" => {
+                let mut index = 0;
+                let mut sibling = node.prev_named_sibling();
+                while let Some(previous) = sibling {
+                    if previous.kind() != "comment" {
+                        index += 1;
+                    }
+                    sibling = previous.prev_named_sibling();
+                }
+                segments.push(format!("[{index}]"));
+            }
+            _ => {}
+        }
+        node = parent;
+    }
+
+    if segments.is_empty() {
+        return None;
+    }
+    let mut path = segments.into_iter().rev().collect::<String>();
+    if path.starts_with('.') {
+        path.remove(0);
+    }
+    Some(path)
+}
+
+/// Returns the path segment for a key, written as a property when it's an identifier
+/// and as a quoted index otherwise.
+fn key_segment(key: &str) -> String {
+    let key =
+        serde_json::from_str::<String>(key).unwrap_or_else(|_| key.trim_matches('"').to_string());
+    let mut chars = key.chars();
+    let is_identifier = chars
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        format!(".{key}")
+    } else {
+        format!("[{}]", Value::String(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context, TestAppContext};
+    use language::{Buffer, LanguageConfig, LanguageMatcher};
+    use std::sync::Arc;
+    use text::BufferId;
+
+    #[test]
+    fn test_format_and_minify_json() {
+        assert_eq!(
+            format_json(r#"{"b": [1, 2], "a": {"c": null}}"#, "  ").unwrap(),
+            "{\n  \"b\": [\n    1,\n    2\n  ],\n  \"a\": {\n    \"c\": null\n  }\n}"
+        );
+        assert_eq!(
+            format_json("{\"a\":1}\n{\"a\":2}", "\t").unwrap(),
+            "{\n\t\"a\": 1\n}\n{\n\t\"a\": 2\n}"
+        );
+        assert_eq!(
+            minify_json("{\n  \"b\": [1, 2],\n  \"a\": true\n}\n[ ]").unwrap(),
+            "{\"b\":[1,2],\"a\":true}\n[]"
+        );
+        assert!(format_json("{\"a\": }", "  ").is_err());
+        assert!(format_json("{\"a\": \"b}", "  ").is_err());
+        assert!(format_json("{} /* a", "  ").is_err());
+        assert!(minify_json("   ").is_err());
+    }
+
+    #[test]
+    fn test_format_json_preserves_tokens() {
+        // Numbers are written as they were, rather than as floats.
+        assert_eq!(
+            format_json("[1.50, 1.0E+2, 12345678901234567890123]", "  ").unwrap(),
+            "[\n  1.50,\n  1.0E+2,\n  12345678901234567890123\n]"
+        );
+        assert_eq!(minify_json("[ 1.50, -0.0 ]").unwrap(), "[1.50,-0.0]");
+
+        // Duplicate keys are all kept, in order.
+        assert_eq!(
+            format_json(r#"{"a": 1, "a": 2}"#, "  ").unwrap(),
+            "{\n  \"a\": 1,\n  \"a\": 2\n}"
+        );
+        assert_eq!(
+            minify_json("{ \"a\": 1,\n \"a\": 2 }").unwrap(),
+            r#"{"a":1,"a":2}"#
+        );
+
+        // JSONC's comments and trailing commas are kept.
+        let jsonc = "// settings\n{\"a\": 1, // one\n\"b\": [], /* two */ \"c\": /* three */ {\"d\": \"//\",},\n  // four\n}";
+        assert_eq!(
+            format_json(jsonc, "  ").unwrap(),
+            "// settings\n\
+             {\n  \
+               \"a\": 1, // one\n  \
+               \"b\": [], /* two */\n  \
+               \"c\": /* three */ {\n    \
+                 \"d\": \"//\",\n  \
+               },\n  \
+               // four\n\
+             }"
+        );
+        assert_eq!(
+            minify_json(jsonc).unwrap(),
+            "// settings\n{\"a\":1,// one\n\"b\":[],/* two */\"c\":/* three */{\"d\":\"//\",},// four\n}"
+        );
+    }
+
+    #[test]
+    fn test_key_segment() {
+        assert_eq!(key_segment(r#""foo""#), ".foo");
+        assert_eq!(key_segment(r#""_id2""#), "._id2");
+        assert_eq!(key_segment(r#""a b""#), r#"["a b"]"#);
+        assert_eq!(key_segment(r#""2fa""#), r#"["2fa"]"#);
+        assert_eq!(key_segment(r#""quo\"te""#), r#"["quo\"te"]"#);
+    }
+
+    #[gpui::test]
+    async fn test_json_path_and_records(cx: &mut TestAppContext) {
+        let language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "JSON".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["json".into()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_json::language()),
+        ));
+        let text = r#"{"foo": {"bar": [1, 2, 33, {"baz": true}], "a b": "c"}}
+{"id": 2}"#;
+        let buffer = cx.new_model(|cx| {
+            Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text)
+                .with_language(language, cx)
+        });
+        cx.condition(&buffer, |buffer, _| !buffer.is_parsing())
+            .await;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+        // Looks up the path inside the first occurrence of the given text.
+        let path_at = |needle: &str| json_path_at(&snapshot, text.find(needle).unwrap() + 1);
+        assert_eq!(path_at("true").as_deref(), Some("foo.bar[3].baz"));
+        assert_eq!(path_at("33").as_deref(), Some("foo.bar[2]"));
+        assert_eq!(path_at("\"c\"").as_deref(), Some(r#"foo["a b"]"#));
+        assert_eq!(path_at("\"id\"").as_deref(), Some("id"));
+        assert_eq!(json_path_at(&snapshot, 0), None);
+
+        let records = json_record_ranges(&snapshot);
+        assert_eq!(
+            records
+                .iter()
+                .map(|range| &text[range.clone()])
+                .collect::<Vec<_>>(),
+            [
+                r#"{"foo": {"bar": [1, 2, 33, {"baz": true}], "a b": "c"}}"#,
+                r#"{"id": 2}"#
+            ]
+        );
+    }
+}
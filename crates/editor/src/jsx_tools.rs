@@ -0,0 +1,602 @@
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use collections::HashSet;
+use gpui::{View, ViewContext, WindowContext};
+use itertools::Itertools;
+use language::{BufferSnapshot, Language};
+use tree_sitter::Node;
+use util::ResultExt;
+use workspace::notifications::NotifyTaskExt;
+
+use crate::{element::register_action, scroll::Autoscroll, Editor, ExtractComponent};
+
+/// Servers prefix the kinds of their extraction refactors with this, such as
+/// `refactor.extract.function`.
+const EXTRACT_KIND: &str = "refactor.extract";
+const COMPONENT_NAME: &str = "ExtractedComponent";
+
+pub fn apply_related_actions(editor: &View<Editor>, cx: &mut WindowContext) {
+    let is_jsx_related = editor.update(cx, |editor, cx| {
+        editor
+            .buffer()
+            .read(cx)
+            .all_buffers()
+            .iter()
+            .any(|b| match b.read(cx).language() {
+                Some(l) => is_jsx_language(l),
+                None => false,
+            })
+    });
+
+    if is_jsx_related {
+        register_action(editor, cx, extract_component);
+    }
+}
+
+pub(crate) fn is_jsx_language(language: &Language) -> bool {
+    matches!(language.name().as_ref(), "TSX" | "JavaScript")
+}
+
+/// Moves the JSX in the newest selection into a new component, which receives the
+/// variables that the JSX uses from the enclosing functions as props. The language
+/// server's refactor is applied when it has one.
+pub fn extract_component(editor: &mut Editor, _: &ExtractComponent, cx: &mut ViewContext<Editor>) {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let range = editor.selections.newest::<usize>(cx).range();
+    let project = editor.project.clone();
+    let workspace = editor.workspace().map(|workspace| workspace.downgrade());
+
+    cx.spawn(|editor, mut cx| async move {
+        if let Some((project, workspace)) = project.zip(workspace) {
+            let actions = project.update(&mut cx, |project, cx| {
+                project.code_actions(&buffer, range.clone(), cx)
+            })?;
+            let action = actions
+                .await
+                .log_err()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|action| {
+                    let is_extraction = action
+                        .lsp_action
+                        .kind
+                        .as_ref()
+                        .map_or(false, |kind| kind.as_str().starts_with(EXTRACT_KIND));
+                    is_extraction && action.lsp_action.title.to_lowercase().contains("component")
+                });
+            if let Some(action) = action {
+                let title = action.lsp_action.title.clone();
+                let project_transaction = project
+                    .update(&mut cx, |project, cx| {
+                        project.apply_code_action(buffer, action, true, cx)
+                    })?
+                    .await?;
+                return Editor::open_project_transaction(
+                    &editor,
+                    workspace,
+                    project_transaction,
+                    title,
+                    cx,
+                )
+                .await;
+            }
+        }
+
+        editor.update(&mut cx, |editor, cx| {
+            extract_component_by_syntax(editor, range, cx)
+        })?
+    })
+    .detach_and_notify_err(cx);
+}
+
+/// Extracts the JSX elements around the given range with the buffer's syntax tree, and
+/// selects the new component's name at its declaration and its call site so that it
+/// can be renamed right away.
+fn extract_component_by_syntax(
+    editor: &mut Editor,
+    selection: Range<usize>,
+    cx: &mut ViewContext<Editor>,
+) -> Result<()> {
+    let buffer = editor
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .ok_or_else(|| anyhow!("components can only be extracted in a single buffer"))?;
+    let snapshot = buffer.read(cx).snapshot();
+    let extraction = component_extraction(&snapshot, selection)
+        .ok_or_else(|| anyhow!("select JSX elements to extract them into a component"))?;
+    let indent = snapshot
+        .language_indent_size_at(extraction.range.start, cx)
+        .chars()
+        .collect::<String>();
+    let text = snapshot.text();
+    let name = unique_component_name(&text);
+    let edits = component_edits(&text, &extraction, &name, &indent);
+    let name_ranges = component_name_ranges(&extraction, &edits, &name);
+
+    editor.transact(cx, |editor, cx| {
+        editor
+            .buffer()
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_ranges(name_ranges)
+        });
+    });
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct ComponentExtraction {
+    /// The range of the JSX that's moved into the component.
+    range: Range<usize>,
+    /// Whether the JSX isn't a single element, and so has to be wrapped in a fragment.
+    needs_fragment: bool,
+    /// The variables that the JSX uses from the functions around it, in the order they
+    /// are first used.
+    props: Vec<String>,
+    /// The end of the top-level statement containing the JSX, after which the
+    /// component is declared.
+    declaration_offset: usize,
+    is_typescript: bool,
+}
+
+/// Returns the JSX to extract for the given selection: the smallest element around it,
+/// or the children of that element that the selection overlaps.
+fn component_extraction(
+    buffer: &BufferSnapshot,
+    selection: Range<usize>,
+) -> Option<ComponentExtraction> {
+    let layer = buffer.syntax_layers().find(|layer| {
+        let node = layer.node();
+        is_jsx_language(layer.language)
+            && node.start_byte() <= selection.start
+            && selection.end <= node.end_byte()
+    })?;
+    let root = layer.node();
+
+    let mut element = root.descendant_for_byte_range(selection.start, selection.end)?;
+    while !is_jsx_element(element) {
+        element = element.parent()?;
+    }
+
+    let mut nodes = vec![element];
+    if !selection.is_empty() {
+        let mut cursor = element.walk();
+        let children = element
+            .named_children(&mut cursor)
+            .filter(|child| {
+                child.start_byte() < selection.end
+                    && child.end_byte() > selection.start
+                    && (child.kind() != "jsx_text" || !node_text(buffer, *child).trim().is_empty())
+            })
+            .collect::<Vec<_>>();
+        let overlaps_tags = children
+            .iter()
+            .any(|child| matches!(child.kind(), "jsx_opening_element" | "jsx_closing_element"));
+        if !children.is_empty() && !overlaps_tags {
+            nodes = children;
+        }
+    }
+    let range = nodes[0].start_byte()..nodes[nodes.len() - 1].end_byte();
+
+    // The variables declared inside the JSX, such as the parameters of callbacks,
+    // don't have to be passed to the component.
+    let mut inner_bindings = HashSet::default();
+    for node in &nodes {
+        collect_bindings(*node, None, buffer, &mut inner_bindings);
+    }
+    let mut outer_bindings = HashSet::default();
+    let mut ancestor = Some(element);
+    while let Some(node) = ancestor {
+        if is_function(node) {
+            for field in ["parameters", "parameter", "body"] {
+                if let Some(child) = node.child_by_field_name(field) {
+                    collect_bindings(child, Some(&range), buffer, &mut outer_bindings);
+                }
+            }
+        }
+        ancestor = node.parent();
+    }
+
+    let mut references = Vec::new();
+    for node in &nodes {
+        collect_references(*node, buffer, &mut references);
+    }
+    let props = references
+        .into_iter()
+        .filter(|name| outer_bindings.contains(name) && !inner_bindings.contains(name))
+        .collect();
+
+    let mut statement = element;
+    while let Some(parent) = statement.parent() {
+        if parent == root {
+            break;
+        }
+        statement = parent;
+    }
+
+    Some(ComponentExtraction {
+        range,
+        needs_fragment: nodes.len() > 1 || !is_jsx_element(nodes[0]),
+        props,
+        declaration_offset: statement.end_byte(),
+        is_typescript: layer.language.name().as_ref() == "TSX",
+    })
+}
+
+/// Returns the edits that replace the JSX with a use of the component, and declare the
+/// component after the statement containing it.
+fn component_edits(
+    text: &str,
+    extraction: &ComponentExtraction,
+    name: &str,
+    indent: &str,
+) -> Vec<(Range<usize>, String)> {
+    let props = &extraction.props;
+    let call_site = if props.is_empty() {
+        format!("<{name} />")
+    } else {
+        let attributes = props.iter().map(|prop| format!("{prop}={{{prop}}}"));
+        format!("<{name} {} />", attributes.format(" "))
+    };
+    let parameters = if props.is_empty() {
+        String::new()
+    } else if extraction.is_typescript {
+        let types = props.iter().map(|prop| format!("{prop}: any"));
+        format!("{{ {} }}: {{ {} }}", props.join(", "), types.format("; "))
+    } else {
+        format!("{{ {} }}", props.join(", "))
+    };
+
+    let mut lines = dedented_lines(text, extraction.range.clone());
+    if extraction.needs_fragment {
+        lines = std::iter::once("<>".to_string())
+            .chain(lines.into_iter().map(|line| format!("{indent}{line}")))
+            .chain(std::iter::once("</>".to_string()))
+            .collect();
+    }
+    let body = lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{indent}{indent}{line}")
+            }
+        })
+        .join("\n");
+    let declaration =
+        format!("\n\nfunction {name}({parameters}) {{\n{indent}return (\n{body}\n{indent});\n}}");
+
+    vec![
+        (extraction.range.clone(), call_site),
+        (
+            extraction.declaration_offset..extraction.declaration_offset,
+            declaration,
+        ),
+    ]
+}
+
+/// Returns the ranges of the component's name at its call site and its declaration,
+/// once the edits are applied.
+fn component_name_ranges(
+    extraction: &ComponentExtraction,
+    edits: &[(Range<usize>, String)],
+    name: &str,
+) -> Vec<Range<usize>> {
+    let call_site_start = extraction.range.start + "<".len();
+    let declaration_start = extraction.declaration_offset + edits[0].1.len()
+        - extraction.range.len()
+        + "\n\nfunction ".len();
+    vec![
+        call_site_start..call_site_start + name.len(),
+        declaration_start..declaration_start + name.len(),
+    ]
+}
+
+/// Returns the lines of the given range without the indentation that they share with
+/// the line where the range starts.
+fn dedented_lines(text: &str, range: Range<usize>) -> Vec<String> {
+    let indent_len = |line: &str| line.len() - line.trim_start().len();
+    let line_start = text[..range.start].rfind('\n').map_or(0, |ix| ix + 1);
+    let first_line_indent = indent_len(&text[line_start..range.start]);
+    let lines = text[range].lines().collect::<Vec<_>>();
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent_len(line))
+        .chain(Some(first_line_indent))
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .enumerate()
+        .map(|(ix, line)| {
+            if ix == 0 {
+                line.to_string()
+            } else {
+                line[common_indent.min(indent_len(line))..].to_string()
+            }
+        })
+        .collect()
+}
+
+fn unique_component_name(text: &str) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => COMPONENT_NAME.to_string(),
+            n => format!("{COMPONENT_NAME}{n}"),
+        })
+        .find(|name| !text.contains(name.as_str()))
+        .unwrap()
+}
+
+/// Collects the names of the variables declared in a node and its descendants, except
+/// for the ones declared in the excluded range.
+fn collect_bindings(
+    node: Node,
+    excluded: Option<&Range<usize>>,
+    buffer: &BufferSnapshot,
+    names: &mut HashSet<String>,
+) {
+    if excluded.map_or(false, |excluded| {
+        excluded.start <= node.start_byte() && node.end_byte() <= excluded.end
+    }) {
+        return;
+    }
+
+    let pattern = match node.kind() {
+        "variable_declarator"
+        | "function_declaration"
+        | "generator_function_declaration"
+        | "class_declaration" => node.child_by_field_name("name"),
+        "required_parameter" | "optional_parameter" => node.child_by_field_name("pattern"),
+        "arrow_function" | "catch_clause" => node.child_by_field_name("parameter"),
+        "for_in_statement" => node.child_by_field_name("left"),
+        _ => None,
+    };
+    if let Some(pattern) = pattern {
+        collect_pattern_names(pattern, buffer, names);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        // JavaScript parameters aren't wrapped in a node of their own.
+        if node.kind() == "formal_parameters"
+            && matches!(
+                child.kind(),
+                "identifier"
+                    | "object_pattern"
+                    | "array_pattern"
+                    | "assignment_pattern"
+                    | "rest_pattern"
+            )
+        {
+            collect_pattern_names(child, buffer, names);
+        }
+        collect_bindings(child, excluded, buffer, names);
+    }
+}
+
+fn collect_pattern_names(pattern: Node, buffer: &BufferSnapshot, names: &mut HashSet<String>) {
+    match pattern.kind() {
+        "identifier" | "shorthand_property_identifier_pattern" => {
+            names.insert(node_text(buffer, pattern));
+        }
+        "assignment_pattern" | "object_assignment_pattern" => {
+            if let Some(left) = pattern.child_by_field_name("left") {
+                collect_pattern_names(left, buffer, names);
+            }
+        }
+        "pair_pattern" => {
+            if let Some(value) = pattern.child_by_field_name("value") {
+                collect_pattern_names(value, buffer, names);
+            }
+        }
+        _ => {
+            let mut cursor = pattern.walk();
+            for child in pattern.named_children(&mut cursor) {
+                collect_pattern_names(child, buffer, names);
+            }
+        }
+    }
+}
+
+fn collect_references(node: Node, buffer: &BufferSnapshot, references: &mut Vec<String>) {
+    if matches!(node.kind(), "identifier" | "shorthand_property_identifier") {
+        let name = node_text(buffer, node);
+        // Lowercase tag names are HTML elements rather than components.
+        let is_html_tag = node.parent().map_or(false, |parent| {
+            matches!(
+                parent.kind(),
+                "jsx_opening_element" | "jsx_closing_element" | "jsx_self_closing_element"
+            )
+        }) && name.starts_with(|c: char| c.is_lowercase());
+        if !is_html_tag && !references.contains(&name) {
+            references.push(name);
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_references(child, buffer, references);
+    }
+}
+
+fn node_text(buffer: &BufferSnapshot, node: Node) -> String {
+    buffer.text_for_range(node.byte_range()).collect()
+}
+
+fn is_jsx_element(node: Node) -> bool {
+    matches!(
+        node.kind(),
+        "jsx_element" | "jsx_self_closing_element" | "jsx_fragment"
+    )
+}
+
+fn is_function(node: Node) -> bool {
+    matches!(
+        node.kind(),
+        "function_declaration"
+            | "generator_function_declaration"
+            | "function_expression"
+            | "function"
+            | "generator_function"
+            | "arrow_function"
+            | "method_definition"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context, TestAppContext};
+    use language::{Buffer, LanguageConfig};
+    use std::sync::Arc;
+    use text::BufferId;
+
+    const TEXT: &str = r#"import { Button } from "./button";
+
+export function List({ items, title }: Props) {
+  const count = items.length;
+  return (
+    <div>
+      <h1>{title}</h1>
+      <ul>
+        {items.map((item) => (
+          <li key={item.id}>{item.name}</li>
+        ))}
+      </ul>
+      <Button onClick={() => console.log(count)} />
+    </div>
+  );
+}
+"#;
+
+    async fn snapshot(cx: &mut TestAppContext) -> BufferSnapshot {
+        let language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "TSX".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_typescript::language_tsx()),
+        ));
+        let buffer = cx.new_model(|cx| {
+            Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), TEXT)
+                .with_language(language, cx)
+        });
+        cx.condition(&buffer, |buffer, _| !buffer.is_parsing())
+            .await;
+        buffer.read_with(cx, |buffer, _| buffer.snapshot())
+    }
+
+    fn apply_edits(text: &str, edits: &[(Range<usize>, String)]) -> String {
+        let mut text = text.to_string();
+        for (range, new_text) in edits.iter().rev() {
+            text.replace_range(range.clone(), new_text);
+        }
+        text
+    }
+
+    #[gpui::test]
+    async fn test_extract_element(cx: &mut TestAppContext) {
+        let snapshot = snapshot(cx).await;
+        let cursor = TEXT.find("ul>").unwrap();
+        let extraction = component_extraction(&snapshot, cursor..cursor).unwrap();
+        assert_eq!(extraction.props, ["items"]);
+        assert!(!extraction.needs_fragment);
+
+        let edits = component_edits(TEXT, &extraction, "ItemList", "  ");
+        let text = apply_edits(TEXT, &edits);
+        assert_eq!(
+            text,
+            r#"import { Button } from "./button";
+
+export function List({ items, title }: Props) {
+  const count = items.length;
+  return (
+    <div>
+      <h1>{title}</h1>
+      <ItemList items={items} />
+      <Button onClick={() => console.log(count)} />
+    </div>
+  );
+}
+
+function ItemList({ items }: { items: any }) {
+  return (
+    <ul>
+      {items.map((item) => (
+        <li key={item.id}>{item.name}</li>
+      ))}
+    </ul>
+  );
+}
+"#
+        );
+        let name_ranges = component_name_ranges(&extraction, &edits, "ItemList");
+        assert_eq!(
+            name_ranges
+                .iter()
+                .map(|range| &text[range.clone()])
+                .collect::<Vec<_>>(),
+            ["ItemList", "ItemList"]
+        );
+        assert_eq!(name_ranges[1].start, text.find("ItemList(").unwrap());
+    }
+
+    #[gpui::test]
+    async fn test_extract_sibling_elements(cx: &mut TestAppContext) {
+        let snapshot = snapshot(cx).await;
+        let start = TEXT.find("1>{title}").unwrap();
+        let end = TEXT.find("<Button").unwrap() + 2;
+        let extraction = component_extraction(&snapshot, start..end).unwrap();
+        assert_eq!(extraction.props, ["title", "items", "count"]);
+        assert!(extraction.needs_fragment);
+        assert_eq!(
+            &TEXT[extraction.range.clone()],
+            r#"<h1>{title}</h1>
+      <ul>
+        {items.map((item) => (
+          <li key={item.id}>{item.name}</li>
+        ))}
+      </ul>
+      <Button onClick={() => console.log(count)} />"#
+        );
+
+        let edits = component_edits(TEXT, &extraction, "Content", "  ");
+        assert_eq!(
+            edits[1].1,
+            r#"
+
+function Content({ title, items, count }: { title: any; items: any; count: any }) {
+  return (
+    <>
+      <h1>{title}</h1>
+      <ul>
+        {items.map((item) => (
+          <li key={item.id}>{item.name}</li>
+        ))}
+      </ul>
+      <Button onClick={() => console.log(count)} />
+    </>
+  );
+}"#
+        );
+
+        let cursor = TEXT.find("items.length").unwrap();
+        assert_eq!(component_extraction(&snapshot, cursor..cursor), None);
+    }
+
+    #[test]
+    fn test_unique_component_name() {
+        assert_eq!(unique_component_name("<App />"), "ExtractedComponent");
+        assert_eq!(
+            unique_component_name("<ExtractedComponent /><ExtractedComponent2 />"),
+            "ExtractedComponent3"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use gpui::{Task, ViewContext};
+use multi_buffer::{Anchor, MultiBufferSnapshot, ToOffset};
+use util::ResultExt;
+
+use crate::Editor;
+
+const LINKED_EDITING_RANGES_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+pub(crate) struct LinkedEditingRangesState {
+    /// The ranges that are edited together, one of which contains the newest cursor.
+    pub(crate) ranges: Vec<Range<Anchor>>,
+    refresh_task: Option<Task<()>>,
+}
+
+/// Asks the language server for the ranges linked to the one at the newest cursor, such
+/// as the names of an HTML element's opening and closing tags.
+pub(crate) fn refresh_linked_editing_ranges(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let head = editor.selections.newest_anchor().head();
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let cursor = head.to_offset(&snapshot);
+    // The server links the same ranges while the cursor stays in one of them.
+    let contains_cursor = editor.linked_editing_ranges.ranges.iter().any(|range| {
+        range.start.to_offset(&snapshot) <= cursor && cursor <= range.end.to_offset(&snapshot)
+    });
+    if contains_cursor {
+        return;
+    }
+    editor.linked_editing_ranges.ranges.clear();
+
+    let project = editor.project.clone();
+    let buffer = editor.buffer.read(cx).text_anchor_for_position(head, cx);
+    let (Some(project), Some((buffer, position))) = (project, buffer) else {
+        editor.linked_editing_ranges.refresh_task = None;
+        return;
+    };
+    if !buffer.read(cx).settings_at(position, cx).linked_edits {
+        editor.linked_editing_ranges.refresh_task = None;
+        return;
+    }
+
+    editor.linked_editing_ranges.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(LINKED_EDITING_RANGES_DEBOUNCE_TIMEOUT)
+            .await;
+        let Some(ranges) = project
+            .update(&mut cx, |project, cx| {
+                project.linked_editing_ranges(&buffer, position, cx)
+            })
+            .ok()
+        else {
+            return;
+        };
+        let Some(ranges) = ranges.await.log_err() else {
+            return;
+        };
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer.read(cx).snapshot(cx);
+                editor.linked_editing_ranges.ranges = ranges
+                    .into_iter()
+                    .map(|range| {
+                        snapshot.anchor_in_excerpt(head.excerpt_id, range.start)
+                            ..snapshot.anchor_in_excerpt(head.excerpt_id, range.end)
+                    })
+                    .collect();
+            })
+            .ok();
+    }));
+}
+
+/// Returns the edits that repeat the given ones in the ranges linked to the ranges they
+/// were made in. Typing text that can't be part of a tag name, such as the space before
+/// an attribute, unlinks the ranges.
+pub(crate) fn linked_edits<T: ToOffset>(
+    editor: &mut Editor,
+    edits: &[(Range<T>, Arc<str>)],
+    snapshot: &MultiBufferSnapshot,
+) -> Vec<(Range<Anchor>, Arc<str>)> {
+    let state = &mut editor.linked_editing_ranges;
+    if state.ranges.is_empty() {
+        return Vec::new();
+    }
+    if edits
+        .iter()
+        .any(|(_, text)| !text.chars().all(is_linked_char))
+    {
+        state.ranges.clear();
+        return Vec::new();
+    }
+
+    let ranges = state
+        .ranges
+        .iter()
+        .map(|range| range.start.to_offset(snapshot)..range.end.to_offset(snapshot))
+        .collect::<Vec<_>>();
+    let edits = edits
+        .iter()
+        .map(|(range, text)| {
+            (
+                range.start.to_offset(snapshot)..range.end.to_offset(snapshot),
+                text,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut linked_edits = Vec::<(Range<usize>, Arc<str>)>::new();
+    for (edit_range, text) in &edits {
+        let Some(ix) = ranges
+            .iter()
+            .position(|range| range.start <= edit_range.start && edit_range.end <= range.end)
+        else {
+            continue;
+        };
+        let start = edit_range.start - ranges[ix].start;
+        let end = edit_range.end - ranges[ix].start;
+        for (other_ix, other_range) in ranges.iter().enumerate() {
+            if other_ix == ix {
+                continue;
+            }
+            let linked_range = (other_range.start + start).min(other_range.end)
+                ..(other_range.start + end).min(other_range.end);
+            // Another cursor may already be making the same edit.
+            let is_duplicate = edits.iter().any(|(range, _)| *range == linked_range)
+                || linked_edits.iter().any(|(range, _)| *range == linked_range);
+            if !is_duplicate {
+                linked_edits.push((linked_range, (*text).clone()));
+            }
+        }
+    }
+
+    linked_edits
+        .into_iter()
+        .map(|(range, text)| {
+            (
+                snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end),
+                text,
+            )
+        })
+        .collect()
+}
+
+fn is_linked_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')
+}
@@ -1,6 +1,7 @@
 use crate::{
-    DisplayPoint, Editor, EditorMode, FindAllReferences, GoToDefinition, GoToTypeDefinition,
-    Rename, RevealInFinder, SelectMode, ToggleCodeActions,
+    ApplyQuickFixToAllExcerpts, DisplayPoint, Editor, EditorMode, FindAllReferences,
+    GoToDefinition, GoToTypeDefinition, OpenGitBlameCommit, Rename, RevealInFinder, SelectMode,
+    ToggleCodeActions,
 };
 use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
 
@@ -44,8 +45,12 @@ pub fn deploy_context_menu(
             s.set_pending_display_range(point..point, SelectMode::Character);
         });
 
+        // Quick fixes can be applied to all the excerpts of diagnostics and search results.
+        let is_multibuffer = !editor.buffer().read(cx).is_singleton();
+        let is_in_git_repository = editor.is_in_git_repository(cx);
         ui::ContextMenu::build(cx, |menu, _cx| {
-            menu.action("Rename Symbol", Box::new(Rename))
+            let menu = menu
+                .action("Rename Symbol", Box::new(Rename))
                 .action("Go to Definition", Box::new(GoToDefinition))
                 .action("Go to Type Definition", Box::new(GoToTypeDefinition))
                 .action("Find All References", Box::new(FindAllReferences))
@@ -54,9 +59,22 @@ pub fn deploy_context_menu(
                     Box::new(ToggleCodeActions {
                         deployed_from_indicator: false,
                     }),
+                );
+            let menu = if is_multibuffer {
+                menu.action(
+                    "Apply Quick Fix to All Excerpts",
+                    Box::new(ApplyQuickFixToAllExcerpts),
                 )
-                .separator()
-                .action("Reveal in Finder", Box::new(RevealInFinder))
+            } else {
+                menu
+            };
+            let menu = menu.separator();
+            let menu = if is_in_git_repository {
+                menu.action("Open Blame Commit", Box::new(OpenGitBlameCommit))
+            } else {
+                menu
+            };
+            menu.action("Reveal in Finder", Box::new(RevealInFinder))
         })
     };
     let context_menu_focus = context_menu.focus_handle(cx);
@@ -4,6 +4,7 @@ pub(crate) mod scroll_amount;
 
 use crate::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
+    editor_settings::ScrollEasing,
     hover_popover::hide_hover,
     persistence::DB,
     Anchor, DisplayPoint, Editor, EditorEvent, EditorMode, EditorSettings, InlayHintRefreshReason,
@@ -127,10 +128,41 @@ impl OngoingScroll {
     }
 }
 
+/// An animated scroll between two scroll positions, used for smooth scrolling.
+#[derive(Clone, Copy, Debug)]
+struct ScrollAnimation {
+    from: gpui::Point<f32>,
+    to: gpui::Point<f32>,
+    started_at: Instant,
+    duration: Duration,
+    easing: ScrollEasing,
+}
+
+impl ScrollAnimation {
+    /// Returns the scroll position at the given time, and whether the animation is finished.
+    fn position_at(&self, now: Instant) -> (gpui::Point<f32>, bool) {
+        let elapsed = now.saturating_duration_since(self.started_at);
+        if elapsed >= self.duration {
+            return (self.to, true);
+        }
+
+        let progress = self
+            .easing
+            .ease(elapsed.as_secs_f32() / self.duration.as_secs_f32());
+        let position = point(
+            self.from.x + (self.to.x - self.from.x) * progress,
+            self.from.y + (self.to.y - self.from.y) * progress,
+        );
+        (position, false)
+    }
+}
+
 pub struct ScrollManager {
     pub(crate) vertical_scroll_margin: f32,
     anchor: ScrollAnchor,
     ongoing: OngoingScroll,
+    animation: Option<ScrollAnimation>,
+    animation_frame_requested: bool,
     autoscroll_request: Option<(Autoscroll, bool)>,
     last_autoscroll: Option<(gpui::Point<f32>, f32, f32, AutoscrollStrategy)>,
     show_scrollbars: bool,
@@ -145,6 +177,8 @@ impl ScrollManager {
             vertical_scroll_margin: EditorSettings::get_global(cx).vertical_scroll_margin,
             anchor: ScrollAnchor::new(),
             ongoing: OngoingScroll::new(),
+            animation: None,
+            animation_frame_requested: false,
             autoscroll_request: None,
             show_scrollbars: true,
             hide_scrollbar_task: None,
@@ -176,15 +210,36 @@ impl ScrollManager {
         self.anchor.scroll_position(snapshot)
     }
 
+    /// The last row that can be scrolled to the top of the viewport. Unless scrolling beyond
+    /// the last line is allowed, it is the row that keeps the last line at the bottom.
+    pub(crate) fn max_scroll_top(
+        &self,
+        map: &DisplaySnapshot,
+        scroll_beyond_last_line: bool,
+    ) -> f32 {
+        let max_row = map.max_point().row() as f32;
+        match self.visible_line_count {
+            Some(visible_lines) if !scroll_beyond_last_line => {
+                (max_row - visible_lines + 1.).max(0.)
+            }
+            _ => max_row,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn set_scroll_position(
         &mut self,
-        scroll_position: gpui::Point<f32>,
+        mut scroll_position: gpui::Point<f32>,
         map: &DisplaySnapshot,
+        scroll_beyond_last_line: bool,
         local: bool,
         autoscroll: bool,
         workspace_id: Option<i64>,
         cx: &mut ViewContext<Editor>,
     ) {
+        scroll_position.y = scroll_position
+            .y
+            .min(self.max_scroll_top(map, scroll_beyond_last_line));
         let (new_anchor, top_row) = if scroll_position.y <= 0. {
             (
                 ScrollAnchor {
@@ -225,6 +280,8 @@ impl ScrollManager {
         cx: &mut ViewContext<Editor>,
     ) {
         self.anchor = anchor;
+        // Any scroll that's not part of the animation interrupts it.
+        self.animation = None;
         cx.emit(EditorEvent::ScrollPositionChanged { local, autoscroll });
         self.show_scrollbar(cx);
         self.autoscroll_request.take();
@@ -310,6 +367,18 @@ impl Editor {
         cx.notify();
     }
 
+    /// Whether the last line can be scrolled up to the top of the viewport. Auto-height
+    /// editors never scroll past their content.
+    pub(crate) fn scroll_beyond_last_line(&self, cx: &AppContext) -> bool {
+        !matches!(self.mode, EditorMode::AutoHeight { .. })
+            && EditorSettings::get_global(cx).scroll_beyond_last_line
+    }
+
+    pub(crate) fn max_scroll_top(&self, display_map: &DisplaySnapshot, cx: &AppContext) -> f32 {
+        self.scroll_manager
+            .max_scroll_top(display_map, self.scroll_beyond_last_line(cx))
+    }
+
     pub fn visible_line_count(&self) -> Option<f32> {
         self.scroll_manager.visible_line_count
     }
@@ -368,9 +437,11 @@ impl Editor {
     ) {
         hide_hover(self, cx);
         let workspace_id = self.workspace.as_ref().map(|workspace| workspace.1);
+        let scroll_beyond_last_line = self.scroll_beyond_last_line(cx);
         self.scroll_manager.set_scroll_position(
             scroll_position,
             &display_map,
+            scroll_beyond_last_line,
             local,
             autoscroll,
             workspace_id,
@@ -385,6 +456,60 @@ impl Editor {
         self.scroll_manager.anchor.scroll_position(&display_map)
     }
 
+    /// The position the editor is scrolling to, which is the end of the ongoing smooth
+    /// scroll if there is one, so that consecutive scrolls add up.
+    pub(crate) fn scroll_target_position(&self, cx: &mut ViewContext<Self>) -> gpui::Point<f32> {
+        match self.scroll_manager.animation {
+            Some(animation) => animation.to,
+            None => self.scroll_position(cx),
+        }
+    }
+
+    /// Scrolls to the given position, animating the scroll if smooth scrolling is enabled.
+    pub fn animate_scroll_position(
+        &mut self,
+        scroll_position: gpui::Point<f32>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let settings = EditorSettings::get_global(cx).smooth_scroll;
+        if !settings.enabled || settings.duration == 0 {
+            self.set_scroll_position(scroll_position, cx);
+            return;
+        }
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let from = self.scroll_manager.anchor.scroll_position(&display_map);
+        let to = point(
+            scroll_position.x,
+            scroll_position.y.min(self.max_scroll_top(&display_map, cx)),
+        );
+        self.scroll_manager.animation = Some(ScrollAnimation {
+            from,
+            to,
+            started_at: Instant::now(),
+            duration: Duration::from_millis(settings.duration),
+            easing: settings.easing,
+        });
+        if !self.scroll_manager.animation_frame_requested {
+            self.step_scroll_animation(cx);
+        }
+    }
+
+    fn step_scroll_animation(&mut self, cx: &mut ViewContext<Self>) {
+        self.scroll_manager.animation_frame_requested = false;
+        let Some(animation) = self.scroll_manager.animation.take() else {
+            return;
+        };
+
+        let (position, finished) = animation.position_at(Instant::now());
+        self.set_scroll_position(position, cx);
+        if !finished {
+            self.scroll_manager.animation = Some(animation);
+            self.scroll_manager.animation_frame_requested = true;
+            cx.on_next_frame(|editor, cx| editor.step_scroll_animation(cx));
+        }
+    }
+
     pub fn set_scroll_anchor(&mut self, scroll_anchor: ScrollAnchor, cx: &mut ViewContext<Self>) {
         hide_hover(self, cx);
         let workspace_id = self.workspace.as_ref().map(|workspace| workspace.1);
@@ -423,9 +548,9 @@ impl Editor {
             return;
         }
 
-        let cur_position = self.scroll_position(cx);
+        let cur_position = self.scroll_target_position(cx);
         let new_pos = cur_position + point(0., amount.lines(self));
-        self.set_scroll_position(new_pos, cx);
+        self.animate_scroll_position(new_pos, cx);
     }
 
     /// Returns an ordering. The newest selection is:
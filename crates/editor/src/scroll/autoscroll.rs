@@ -65,11 +65,7 @@ impl Editor {
         let visible_lines = f32::from(viewport_height / line_height);
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let mut scroll_position = self.scroll_manager.scroll_position(&display_map);
-        let max_scroll_top = if matches!(self.mode, EditorMode::AutoHeight { .. }) {
-            (display_map.max_point().row() as f32 - visible_lines + 1.).max(0.)
-        } else {
-            display_map.max_point().row() as f32
-        };
+        let max_scroll_top = self.max_scroll_top(&display_map, cx);
         if scroll_position.y > max_scroll_top {
             scroll_position.y = max_scroll_top;
             self.set_scroll_position(scroll_position, cx);
@@ -218,13 +214,10 @@ impl Editor {
                     let end_column = cmp::min(display_map.line_len(head.row()), head.column() + 3);
                     target_left = target_left.min(
                         layouts[(head.row() - start_row) as usize]
-                            .line
                             .x_for_index(start_column as usize),
                     );
                     target_right = target_right.max(
-                        layouts[(head.row() - start_row) as usize]
-                            .line
-                            .x_for_index(end_column as usize)
+                        layouts[(head.row() - start_row) as usize].x_for_index(end_column as usize)
                             + max_glyph_width,
                     );
                 }
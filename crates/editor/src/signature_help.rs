@@ -0,0 +1,362 @@
+use gpui::{
+    div, AnyElement, FontWeight, HighlightStyle, InteractiveElement, IntoElement, MouseButton,
+    ParentElement, Pixels, ScrollHandle, Size, StatefulInteractiveElement, Styled, StyledText,
+    Task, ViewContext,
+};
+use project::{SignatureHelp, SignatureInformation};
+use ui::prelude::*;
+use util::ResultExt;
+
+use crate::{
+    actions::{MoveDown, MoveUp, ShowSignatureHelp, SignatureHelpNext, SignatureHelpPrev},
+    DisplayPoint, Editor, EditorMode, EditorStyle,
+};
+
+#[derive(Default)]
+pub(crate) struct SignatureHelpState {
+    popover: Option<SignatureHelpPopover>,
+    task: Option<Task<()>>,
+}
+
+struct SignatureHelpPopover {
+    help: SignatureHelp,
+    /// Whether the active signature was picked by cycling through the overloads, in which case
+    /// it's kept when the popover is refreshed.
+    pinned: bool,
+    scroll_handle: ScrollHandle,
+}
+
+impl SignatureHelpState {
+    pub(crate) fn is_visible(&self) -> bool {
+        self.popover.is_some()
+    }
+
+    pub(crate) fn scroll_handle(&self) -> Option<ScrollHandle> {
+        Some(self.popover.as_ref()?.scroll_handle.clone())
+    }
+
+    pub(crate) fn render(
+        &self,
+        position: DisplayPoint,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<(DisplayPoint, Vec<AnyElement>)> {
+        let popover = self.popover.as_ref()?;
+        Some((position, vec![popover.render(style, max_size, cx)]))
+    }
+}
+
+/// Bindable action which requests signature help at the most recent selection head.
+pub fn show_signature_help(
+    editor: &mut Editor,
+    _: &ShowSignatureHelp,
+    cx: &mut ViewContext<Editor>,
+) {
+    request_signature_help(editor, cx);
+}
+
+/// Shows the next overload, or moves the cursor down if there's only a single signature.
+pub fn signature_help_next(
+    editor: &mut Editor,
+    _: &SignatureHelpNext,
+    cx: &mut ViewContext<Editor>,
+) {
+    if !cycle_signatures(editor, 1, cx) {
+        editor.move_down(&MoveDown, cx);
+    }
+}
+
+/// Shows the previous overload, or moves the cursor up if there's only a single signature.
+pub fn signature_help_prev(
+    editor: &mut Editor,
+    _: &SignatureHelpPrev,
+    cx: &mut ViewContext<Editor>,
+) {
+    if !cycle_signatures(editor, -1, cx) {
+        editor.move_up(&MoveUp, cx);
+    }
+}
+
+/// Requests signature help if the inserted text ends with one of the language server's
+/// trigger characters. Visible popovers are refreshed when the selections change instead.
+pub(crate) fn signature_help_on_input(
+    editor: &mut Editor,
+    text: &str,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full || editor.signature_help_state.is_visible() {
+        return;
+    }
+    let Some(project) = editor.project.as_ref() else {
+        return;
+    };
+    let head = editor.selections.newest_anchor().head();
+    let Some((buffer, _)) = editor.buffer.read(cx).text_anchor_for_position(head, cx) else {
+        return;
+    };
+    let is_trigger = project
+        .read(cx)
+        .signature_help_trigger_characters(&buffer, cx)
+        .iter()
+        .any(|trigger| text.ends_with(trigger.as_str()));
+    if is_trigger {
+        request_signature_help(editor, cx);
+    }
+}
+
+/// Re-requests the signature help for the new cursor position, hiding the popover when the
+/// cursor leaves the call.
+pub(crate) fn refresh_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.signature_help_state.is_visible() {
+        request_signature_help(editor, cx);
+    }
+}
+
+pub(crate) fn hide_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
+    editor.signature_help_state.task = None;
+    let was_visible = editor.signature_help_state.popover.take().is_some();
+    if was_visible {
+        cx.notify();
+    }
+    was_visible
+}
+
+fn request_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let head = editor.selections.newest_anchor().head();
+    let Some((buffer, buffer_position)) = editor.buffer.read(cx).text_anchor_for_position(head, cx)
+    else {
+        return;
+    };
+
+    let request = project.update(cx, |project, cx| {
+        project.signature_help(&buffer, buffer_position, cx)
+    });
+    editor.signature_help_state.task = Some(cx.spawn(|editor, mut cx| async move {
+        let help = request.await.log_err().flatten();
+        editor
+            .update(&mut cx, |editor, cx| {
+                let state = &mut editor.signature_help_state;
+                state.popover = help.map(|mut help| {
+                    let previous = state.popover.as_ref().filter(|previous| {
+                        previous.pinned && previous.help.signatures == help.signatures
+                    });
+                    let pinned = previous.is_some();
+                    if let Some(previous) = previous {
+                        help.active_signature = previous.help.active_signature;
+                    }
+                    let scroll_handle = state
+                        .popover
+                        .as_ref()
+                        .map_or_else(ScrollHandle::new, |previous| previous.scroll_handle.clone());
+                    SignatureHelpPopover {
+                        help,
+                        pinned,
+                        scroll_handle,
+                    }
+                });
+                cx.notify();
+            })
+            .ok();
+    }));
+}
+
+fn cycle_signatures(editor: &mut Editor, delta: isize, cx: &mut ViewContext<Editor>) -> bool {
+    let Some(popover) = editor.signature_help_state.popover.as_mut() else {
+        return false;
+    };
+    let count = popover.help.signatures.len();
+    if count < 2 {
+        return false;
+    }
+
+    let active_signature = popover.help.active_signature as isize + delta;
+    popover.help.active_signature = active_signature.rem_euclid(count as isize) as usize;
+    popover.pinned = true;
+    cx.notify();
+    true
+}
+
+impl SignatureHelpPopover {
+    fn render(
+        &self,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        cx: &mut ViewContext<Editor>,
+    ) -> AnyElement {
+        let count = self.help.signatures.len();
+        let active_signature = self.help.active_signature;
+        let signature = &self.help.signatures[active_signature];
+
+        div()
+            .id("signature_help_popover")
+            .elevation_2(cx)
+            .p_2()
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .max_w(max_size.width)
+            .max_h(max_size.height)
+            // Prevent a mouse move on the popover from being propagated to the editor,
+            // because that would dismiss the hover popovers.
+            .on_mouse_move(|_, cx| cx.stop_propagation())
+            // Prevent a mouse down on the popover from being propagated to the editor,
+            // because that would move the cursor.
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .child(
+                h_flex()
+                    .gap_2()
+                    .when(count > 1, |this| {
+                        this.child(
+                            Label::new(format!("{}/{}", active_signature + 1, count))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(render_signature_label(signature, style)),
+            )
+            .when_some(signature.documentation.clone(), |this, documentation| {
+                this.child(
+                    div().pt_1().child(
+                        Label::new(documentation)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+fn render_signature_label(signature: &SignatureInformation, style: &EditorStyle) -> StyledText {
+    let active_parameter = signature
+        .active_parameter
+        .and_then(|ix| signature.parameters.get(ix))
+        .filter(|range| signature.label.get(range.clone()).is_some());
+    let highlights = active_parameter.map(|range| {
+        (
+            range.clone(),
+            HighlightStyle {
+                font_weight: Some(FontWeight::BOLD),
+                color: Some(style.local_player.cursor),
+                ..Default::default()
+            },
+        )
+    });
+    StyledText::new(signature.label.clone()).with_highlights(&style.text, highlights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use indoc::indoc;
+    use smol::stream::StreamExt;
+
+    #[gpui::test]
+    async fn test_signature_help_overload_cycling(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { fooˇ }
+        "});
+        let mut requests =
+            cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, _, _| async move {
+                Ok(Some(lsp::SignatureHelp {
+                    signatures: vec![
+                        lsp::SignatureInformation {
+                            label: "foo(a: u32)".to_string(),
+                            documentation: None,
+                            parameters: Some(vec![lsp::ParameterInformation {
+                                label: lsp::ParameterLabel::Simple("a: u32".to_string()),
+                                documentation: None,
+                            }]),
+                            active_parameter: None,
+                        },
+                        lsp::SignatureInformation {
+                            label: "foo(a: u32, b: u32)".to_string(),
+                            documentation: None,
+                            parameters: Some(vec![
+                                lsp::ParameterInformation {
+                                    label: lsp::ParameterLabel::Simple("a: u32".to_string()),
+                                    documentation: None,
+                                },
+                                lsp::ParameterInformation {
+                                    label: lsp::ParameterLabel::LabelOffsets([12, 18]),
+                                    documentation: None,
+                                },
+                            ]),
+                            active_parameter: None,
+                        },
+                    ],
+                    active_signature: Some(0),
+                    active_parameter: Some(0),
+                }))
+            });
+
+        cx.update_editor(|editor, cx| editor.handle_input("(", cx));
+        requests.next().await;
+        cx.run_until_parked();
+
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            assert_eq!(popover.help.active_signature, 0);
+            assert_eq!(popover.help.signatures[0].parameters, vec![4..10]);
+            assert_eq!(popover.help.signatures[1].parameters, vec![4..10, 12..18]);
+        });
+
+        cx.update_editor(|editor, cx| signature_help_next(editor, &SignatureHelpNext, cx));
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            assert_eq!(popover.help.active_signature, 1);
+            assert!(popover.pinned);
+        });
+
+        // The chosen overload is kept when the popover is refreshed with the same signatures.
+        cx.update_editor(|editor, cx| editor.handle_input("1", cx));
+        requests.next().await;
+        cx.run_until_parked();
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            assert_eq!(popover.help.active_signature, 1);
+        });
+
+        cx.update_editor(|editor, cx| signature_help_prev(editor, &SignatureHelpPrev, cx));
+        cx.update_editor(|editor, cx| signature_help_prev(editor, &SignatureHelpPrev, cx));
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            assert_eq!(popover.help.active_signature, 1);
+        });
+
+        // The popover can be scrolled from the keyboard.
+        cx.update_editor(|editor, cx| {
+            let line_height = editor
+                .style()
+                .unwrap()
+                .text
+                .line_height_in_pixels(cx.rem_size());
+            editor.scroll_popover_down(&crate::actions::ScrollPopoverDown, cx);
+            let scroll_handle = editor.signature_help_state.scroll_handle().unwrap();
+            assert_eq!(scroll_handle.offset().y, -line_height * 3.);
+            editor.scroll_popover_up(&crate::actions::ScrollPopoverUp, cx);
+            assert_eq!(scroll_handle.offset().y, Pixels::ZERO);
+        });
+
+        cx.update_editor(|editor, cx| editor.cancel(&crate::actions::Cancel, cx));
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.is_visible()));
+    }
+}
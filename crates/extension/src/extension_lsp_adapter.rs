@@ -0,0 +1,212 @@
+use crate::{extension_manifest::ExtensionPermissions, wasm_host::LazyWasmExtension};
+use anyhow::{anyhow, bail, Context as _, Result};
+use async_trait::async_trait;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    any::Any,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt;
+
+/// A language server that's provided by the WASM module of an extension, which
+/// downloads the server into its work directory and returns the command to start it.
+pub struct ExtensionLspAdapter {
+    extension: Arc<LazyWasmExtension>,
+    language_server_id: LanguageServerName,
+}
+
+#[derive(Serialize)]
+struct LanguageServerRequest<'a> {
+    language_server_id: &'a str,
+    /// The worktree root that the server is started for, when there is one.
+    worktree_root: Option<&'a Path>,
+}
+
+#[derive(Deserialize)]
+struct LanguageServerCommand {
+    command: PathBuf,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+impl ExtensionLspAdapter {
+    pub fn new(extension: Arc<LazyWasmExtension>, language_server_id: Arc<str>) -> Self {
+        Self {
+            extension,
+            language_server_id: LanguageServerName(language_server_id),
+        }
+    }
+
+    async fn language_server_binary(&self) -> Result<LanguageServerBinary> {
+        let extension = self.extension.get().await?;
+        let command: LanguageServerCommand = extension
+            .call(
+                "language_server_command",
+                None,
+                &LanguageServerRequest {
+                    language_server_id: &self.language_server_id.0,
+                    worktree_root: None,
+                },
+            )
+            .await?
+            .ok_or_else(|| {
+                anyhow!(
+                    "extension {} doesn't export language_server_command",
+                    extension.manifest().id
+                )
+            })?;
+
+        let manifest = extension.manifest();
+        let path = resolve_command(
+            &command.command,
+            &extension.work_dir().await,
+            &manifest.permissions,
+        )
+        .with_context(|| {
+            format!(
+                "extension {} may not start {:?}",
+                manifest.id, command.command
+            )
+        })?;
+        Ok(LanguageServerBinary {
+            path,
+            arguments: command.args.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+/// Returns the program that a language server is started with. The server runs outside
+/// of the sandbox, so it has to be a program that the extension downloaded into its
+/// work directory, unless the extension's permissions name it.
+fn resolve_command(
+    command: &Path,
+    work_dir: &Path,
+    permissions: &ExtensionPermissions,
+) -> Result<PathBuf> {
+    if let Some(name) = command.to_str() {
+        if permissions.commands.iter().any(|allowed| allowed == name) {
+            return which::which(name).with_context(|| format!("{name} isn't installed"));
+        }
+    }
+
+    if command
+        .components()
+        .any(|component| component == Component::ParentDir)
+    {
+        bail!("{command:?} must not contain `..`");
+    }
+    // Commands that the extension downloaded are relative to its work directory.
+    let path = if command.is_relative() {
+        work_dir.join(command)
+    } else {
+        command.to_path_buf()
+    };
+    let path = std::fs::canonicalize(&path).with_context(|| format!("{path:?} doesn't exist"))?;
+    if !path.starts_with(std::fs::canonicalize(work_dir)?) {
+        bail!("{path:?} is outside of the extension's work directory");
+    }
+    Ok(path)
+}
+
+#[async_trait]
+impl LspAdapter for ExtensionLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        self.language_server_id.clone()
+    }
+
+    fn short_name(&self) -> &'static str {
+        "extension"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        // The extension keeps track of the versions of its server.
+        Ok(Box::new(()))
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        self.language_server_binary().await
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        self.language_server_binary().await.log_err()
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    async fn root_specific_initialization_options(&self, root_path: &Path) -> Option<Value> {
+        let extension = self.extension.get().await.log_err()?;
+        extension
+            .call(
+                "language_server_initialization_options",
+                Some(root_path),
+                &LanguageServerRequest {
+                    language_server_id: &self.language_server_id.0,
+                    worktree_root: Some(root_path),
+                },
+            )
+            .await
+            .log_err()
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_command() {
+        let work_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(work_dir.path().join("bin")).unwrap();
+        std::fs::write(work_dir.path().join("bin").join("server"), "").unwrap();
+        let work_dir = std::fs::canonicalize(work_dir.path()).unwrap();
+        let permissions = ExtensionPermissions::default();
+
+        assert_eq!(
+            resolve_command(Path::new("bin/server"), &work_dir, &permissions).unwrap(),
+            work_dir.join("bin").join("server")
+        );
+        assert_eq!(
+            resolve_command(&work_dir.join("bin/server"), &work_dir, &permissions).unwrap(),
+            work_dir.join("bin").join("server")
+        );
+        assert!(resolve_command(Path::new("bin/../bin/server"), &work_dir, &permissions).is_err());
+        assert!(resolve_command(Path::new("bin/missing"), &work_dir, &permissions).is_err());
+
+        // Programs outside of the work directory need a permission, whether they're
+        // given as a path or as a name to look up on the PATH.
+        assert!(resolve_command(Path::new("/bin/sh"), &work_dir, &permissions).is_err());
+        assert!(resolve_command(Path::new("sh"), &work_dir, &permissions).is_err());
+        #[cfg(unix)]
+        {
+            let permissions = ExtensionPermissions {
+                commands: vec!["sh".into()],
+                ..Default::default()
+            };
+            assert!(resolve_command(Path::new("sh"), &work_dir, &permissions).is_ok());
+            assert!(resolve_command(Path::new("/bin/sh"), &work_dir, &permissions).is_err());
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+/// The `extension.toml` at the root of an extension, which describes it and the
+/// language servers that its WASM module provides.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ExtensionManifest {
+    pub id: Arc<str>,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// The path of the extension's WASM module, relative to the extension.
+    #[serde(default)]
+    pub lib: Option<PathBuf>,
+    /// The language servers that the module starts, by their names.
+    #[serde(default)]
+    pub language_servers: BTreeMap<Arc<str>, LanguageServerManifestEntry>,
+    #[serde(default)]
+    pub permissions: ExtensionPermissions,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LanguageServerManifestEntry {
+    /// The name of the language whose files the server is started for, which is one
+    /// of the languages of an extension.
+    pub language: Arc<str>,
+}
+
+/// What the extension's module may access through Zed, since it has no access to the
+/// filesystem or the network of its own. It can always read and write its own work
+/// directory.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ExtensionPermissions {
+    /// Whether the module may read the files of the worktree that a language server
+    /// is started for.
+    #[serde(default)]
+    pub read_worktree: bool,
+    /// The hosts that the module may download from, such as `github.com`, which
+    /// include their subdomains. `*` allows every host. Programs that the module
+    /// downloads can be started as language servers, which run outside of the sandbox.
+    #[serde(default)]
+    pub network: Vec<String>,
+    /// The programs outside of the work directory that the module may start its
+    /// language servers with, such as `node`, which are looked up on the `PATH`.
+    /// Without them, the module may only start the programs that it downloaded. The
+    /// module chooses their arguments, so a program like `node` or `sh` can run any code.
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+impl ExtensionPermissions {
+    /// Describes what the extension may do, for the user to review.
+    pub fn descriptions(&self) -> Vec<String> {
+        let mut descriptions = Vec::new();
+        if self.read_worktree {
+            descriptions.push("Can read the files of your projects".to_string());
+        }
+        if !self.network.is_empty() {
+            descriptions.push(format!(
+                "Can download programs from {}, and run them on your computer outside of the sandbox",
+                self.network.join(", ")
+            ));
+        }
+        if !self.commands.is_empty() {
+            descriptions.push(format!(
+                "Can run these programs on your computer outside of the sandbox, with any arguments: {}",
+                self.commands.join(", ")
+            ));
+        }
+        descriptions
+    }
+
+    pub fn allows_host(&self, host: &str) -> bool {
+        self.network.iter().any(|allowed| {
+            allowed == "*"
+                || host.eq_ignore_ascii_case(allowed)
+                || host
+                    .to_ascii_lowercase()
+                    .ends_with(&format!(".{}", allowed.to_ascii_lowercase()))
+        })
+    }
+}
@@ -1,10 +1,12 @@
-use anyhow::{Context as _, Result};
+use anyhow::{anyhow, Context as _, Result};
 use collections::HashMap;
-use fs::Fs;
+use extension_lsp_adapter::ExtensionLspAdapter;
+use fs::{CopyOptions, Fs, RemoveOptions};
 use futures::StreamExt as _;
 use gpui::{actions, AppContext, Context, Global, Model, ModelContext, Task};
 use language::{
-    LanguageConfig, LanguageMatcher, LanguageQueries, LanguageRegistry, QUERY_FILENAME_PREFIXES,
+    LanguageConfig, LanguageMatcher, LanguageQueries, LanguageRegistry, LspAdapter,
+    QUERY_FILENAME_PREFIXES,
 };
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
@@ -15,7 +17,21 @@ use std::{
     time::Duration,
 };
 use theme::{ThemeRegistry, ThemeSettings};
-use util::{paths::EXTENSIONS_DIR, ResultExt};
+use util::{
+    http::HttpClient,
+    paths::{EXTENSIONS_DIR, USER_LANGUAGES_DIR},
+    ResultExt,
+};
+use wasm_host::{LazyWasmExtension, WasmHost};
+
+mod extension_lsp_adapter;
+mod extension_manifest;
+mod user_languages;
+mod wasm_host;
+
+pub use extension_manifest::{
+    ExtensionManifest, ExtensionPermissions, LanguageServerManifestEntry,
+};
 
 #[cfg(test)]
 mod extension_store_test;
@@ -27,6 +43,7 @@ pub struct ExtensionStore {
     manifest_path: PathBuf,
     language_registry: Arc<LanguageRegistry>,
     theme_registry: Arc<ThemeRegistry>,
+    wasm_host: Option<Arc<WasmHost>>,
     _watch_extensions_dir: [Task<()>; 2],
 }
 
@@ -39,6 +56,16 @@ pub struct Manifest {
     pub grammars: HashMap<Arc<str>, GrammarManifestEntry>,
     pub languages: HashMap<Arc<str>, LanguageManifestEntry>,
     pub themes: HashMap<String, ThemeManifestEntry>,
+    /// The `extension.toml` of each extension that has one, by the extension's directory.
+    #[serde(default)]
+    pub extensions: HashMap<Arc<str>, ExtensionManifest>,
+}
+
+/// An extension in the `installed` directory, with its `extension.toml` if it has one.
+#[derive(Clone, Debug)]
+pub struct InstalledExtension {
+    pub dir_name: Arc<str>,
+    pub manifest: Option<ExtensionManifest>,
 }
 
 #[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Deserialize, Serialize)]
@@ -65,6 +92,7 @@ actions!(zed, [ReloadExtensions]);
 
 pub fn init(
     fs: Arc<fs::RealFs>,
+    http_client: Arc<dyn HttpClient>,
     language_registry: Arc<LanguageRegistry>,
     theme_registry: Arc<ThemeRegistry>,
     cx: &mut AppContext,
@@ -73,12 +101,21 @@ pub fn init(
         ExtensionStore::new(
             EXTENSIONS_DIR.clone(),
             fs.clone(),
+            http_client,
             language_registry.clone(),
             theme_registry,
             cx,
         )
     });
 
+    cx.background_executor()
+        .spawn(user_languages::load_user_languages(
+            USER_LANGUAGES_DIR.as_path(),
+            fs.clone(),
+            language_registry.clone(),
+        ))
+        .detach_and_log_err(cx);
+
     cx.on_action(|_: &ReloadExtensions, cx| {
         let store = cx.global::<GlobalExtensionStore>().0.clone();
         store
@@ -90,13 +127,19 @@ pub fn init(
 }
 
 impl ExtensionStore {
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalExtensionStore>().0.clone()
+    }
+
     pub fn new(
         extensions_dir: PathBuf,
         fs: Arc<dyn Fs>,
+        http_client: Arc<dyn HttpClient>,
         language_registry: Arc<LanguageRegistry>,
         theme_registry: Arc<ThemeRegistry>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
+        let wasm_host = WasmHost::new(http_client, extensions_dir.join("work")).log_err();
         let mut this = Self {
             manifest: Default::default(),
             extensions_dir: extensions_dir.join("installed"),
@@ -104,6 +147,7 @@ impl ExtensionStore {
             fs,
             language_registry,
             theme_registry,
+            wasm_host,
             _watch_extensions_dir: [Task::ready(()), Task::ready(())],
         };
         this._watch_extensions_dir = this.watch_extensions_dir(cx);
@@ -140,6 +184,94 @@ impl ExtensionStore {
         }
     }
 
+    pub fn installed_extensions(&self) -> Vec<InstalledExtension> {
+        let manifest = self.manifest.read();
+        let mut extensions = manifest
+            .grammars
+            .values()
+            .map(|grammar| grammar.extension.as_str())
+            .chain(
+                manifest
+                    .languages
+                    .values()
+                    .map(|language| language.extension.as_str()),
+            )
+            .chain(
+                manifest
+                    .themes
+                    .values()
+                    .map(|theme| theme.extension.as_str()),
+            )
+            .chain(manifest.extensions.keys().map(AsRef::as_ref))
+            .map(|dir_name| InstalledExtension {
+                dir_name: dir_name.into(),
+                manifest: manifest.extensions.get(dir_name).cloned(),
+            })
+            .collect::<Vec<_>>();
+        extensions.sort_by(|a, b| a.dir_name.cmp(&b.dir_name));
+        extensions.dedup_by(|a, b| a.dir_name == b.dir_name);
+        extensions
+    }
+
+    /// Reads the `extension.toml` of an extension that's being developed, so that its
+    /// permissions can be reviewed before it's installed.
+    pub fn dev_extension_manifest(
+        &self,
+        source_dir: PathBuf,
+        cx: &ModelContext<Self>,
+    ) -> Task<Result<ExtensionManifest>> {
+        let fs = self.fs.clone();
+        cx.background_executor()
+            .spawn(async move { load_dev_extension_manifest(fs.as_ref(), &source_dir).await })
+    }
+
+    /// Copies an extension that's being developed into the `installed` directory,
+    /// under the id in its `extension.toml`.
+    pub fn install_dev_extension(
+        &mut self,
+        source_dir: PathBuf,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let extensions_dir = self.extensions_dir.clone();
+        cx.spawn(|this, mut cx| async move {
+            let manifest = load_dev_extension_manifest(fs.as_ref(), &source_dir).await?;
+            let target_dir = extensions_dir.join(manifest.id.as_ref());
+            fs.remove_dir(
+                &target_dir,
+                RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await?;
+            copy_dir(fs.as_ref(), &source_dir, &target_dir).await?;
+            this.update(&mut cx, |this, cx| this.reload(cx))?.await
+        })
+    }
+
+    /// Removes an extension from the `installed` directory. Its languages and themes
+    /// stay available until Zed restarts.
+    pub fn uninstall_extension(
+        &mut self,
+        dir_name: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let extension_dir = self.extensions_dir.join(dir_name.as_ref());
+        cx.spawn(|this, mut cx| async move {
+            fs.remove_dir(
+                &extension_dir,
+                RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await?;
+            this.update(&mut cx, |this, cx| this.reload(cx))?.await
+        })
+    }
+
     fn manifest_updated(&mut self, manifest: Manifest, cx: &mut ModelContext<Self>) {
         self.language_registry
             .register_wasm_grammars(manifest.grammars.iter().map(|(grammar_name, grammar)| {
@@ -148,6 +280,31 @@ impl ExtensionStore {
                 (grammar_name.clone(), grammar_path)
             }));
 
+        // The language servers of an extension only start for the languages of
+        // extensions, since those of Zed's own languages are registered upfront.
+        let mut lsp_adapters_by_language = HashMap::<Arc<str>, Vec<Arc<dyn LspAdapter>>>::default();
+        if let Some(wasm_host) = &self.wasm_host {
+            for (extension_name, extension_manifest) in &manifest.extensions {
+                if extension_manifest.lib.is_none() {
+                    continue;
+                }
+                let extension = LazyWasmExtension::new(
+                    wasm_host.clone(),
+                    Arc::new(extension_manifest.clone()),
+                    self.extensions_dir.join(extension_name.as_ref()),
+                );
+                for (language_server_id, language_server) in &extension_manifest.language_servers {
+                    lsp_adapters_by_language
+                        .entry(language_server.language.clone())
+                        .or_default()
+                        .push(Arc::new(ExtensionLspAdapter::new(
+                            extension.clone(),
+                            language_server_id.clone(),
+                        )));
+                }
+            }
+        }
+
         for (language_name, language) in &manifest.languages {
             let mut language_path = self.extensions_dir.clone();
             language_path.extend([language.extension.as_ref(), language.path.as_path()]);
@@ -155,7 +312,9 @@ impl ExtensionStore {
                 language_name.clone(),
                 language.grammar.clone(),
                 language.matcher.clone(),
-                vec![],
+                lsp_adapters_by_language
+                    .remove(language_name)
+                    .unwrap_or_default(),
                 move || {
                     let config = std::fs::read(language_path.join("config.toml"))?;
                     let config: LanguageConfig = ::toml::from_slice(&config)?;
@@ -182,6 +341,7 @@ impl ExtensionStore {
             })
             .detach();
         *self.manifest.write() = manifest;
+        cx.notify();
     }
 
     fn watch_extensions_dir(&self, cx: &mut ModelContext<Self>) -> [Task<()>; 2] {
@@ -284,6 +444,22 @@ impl ExtensionStore {
                             continue;
                         };
 
+                        if let Ok(extension_manifest) =
+                            fs.load(&extension_dir.join("extension.toml")).await
+                        {
+                            if let Some(extension_manifest) =
+                                ::toml::from_str::<ExtensionManifest>(&extension_manifest)
+                                    .with_context(|| {
+                                        format!("invalid manifest of extension {extension_name}")
+                                    })
+                                    .log_err()
+                            {
+                                manifest
+                                    .extensions
+                                    .insert(extension_name.into(), extension_manifest);
+                            }
+                        }
+
                         if let Ok(mut grammar_paths) =
                             fs.read_dir(&extension_dir.join("grammars")).await
                         {
@@ -379,6 +555,51 @@ impl ExtensionStore {
     }
 }
 
+async fn load_dev_extension_manifest(fs: &dyn Fs, source_dir: &Path) -> Result<ExtensionManifest> {
+    let manifest = fs
+        .load(&source_dir.join("extension.toml"))
+        .await
+        .with_context(|| format!("{source_dir:?} has no extension.toml"))?;
+    let manifest = ::toml::from_str::<ExtensionManifest>(&manifest)?;
+    if manifest.id.is_empty() || manifest.id.contains(['/', '\\', '.']) {
+        return Err(anyhow!("invalid extension id {:?}", manifest.id));
+    }
+    Ok(manifest)
+}
+
+async fn copy_dir(fs: &dyn Fs, source_dir: &Path, target_dir: &Path) -> Result<()> {
+    let mut dirs = vec![(source_dir.to_path_buf(), target_dir.to_path_buf())];
+    while let Some((source_dir, target_dir)) = dirs.pop() {
+        fs.create_dir(&target_dir).await?;
+        let mut entries = fs.read_dir(&source_dir).await?;
+        while let Some(source_path) = entries.next().await {
+            let source_path = source_path?;
+            let Some(file_name) = source_path.file_name() else {
+                continue;
+            };
+            let target_path = target_dir.join(file_name);
+            let is_dir = fs
+                .metadata(&source_path)
+                .await?
+                .map_or(false, |metadata| metadata.is_dir);
+            if is_dir {
+                dirs.push((source_path, target_path));
+            } else {
+                fs.copy_file(
+                    &source_path,
+                    &target_path,
+                    CopyOptions {
+                        overwrite: true,
+                        ignore_if_exists: false,
+                    },
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
     let mut result = LanguageQueries::default();
     if let Some(entries) = std::fs::read_dir(root_path).log_err() {
@@ -1,12 +1,17 @@
 use crate::{
-    ExtensionStore, GrammarManifestEntry, LanguageManifestEntry, Manifest, ThemeManifestEntry,
+    user_languages, ExtensionManifest, ExtensionPermissions, ExtensionStore, GrammarManifestEntry,
+    LanguageManifestEntry, LanguageServerManifestEntry, Manifest, ThemeManifestEntry,
 };
 use fs::FakeFs;
 use gpui::{Context, TestAppContext};
 use language::{LanguageMatcher, LanguageRegistry};
 use serde_json::json;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use theme::ThemeRegistry;
+use util::http::FakeHttpClient;
 
 #[gpui::test]
 async fn test_extension_store(cx: &mut TestAppContext) {
@@ -53,6 +58,18 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                     }
                 },
                 "zed-ruby": {
+                    "extension.toml": r#"
+                        id = "zed-ruby"
+                        name = "Ruby"
+                        version = "0.1.0"
+                        lib = "ruby.wasm"
+
+                        [language_servers.solargraph]
+                        language = "Ruby"
+
+                        [permissions]
+                        network = ["rubygems.org"]
+                    "#,
                     "grammars": {
                         "ruby.wasm": "",
                         "embedded_template.wasm": "",
@@ -160,6 +177,32 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         ]
         .into_iter()
         .collect(),
+        extensions: [(
+            "zed-ruby".into(),
+            ExtensionManifest {
+                id: "zed-ruby".into(),
+                name: "Ruby".into(),
+                version: "0.1.0".into(),
+                description: None,
+                authors: Vec::new(),
+                lib: Some("ruby.wasm".into()),
+                language_servers: [(
+                    "solargraph".into(),
+                    LanguageServerManifestEntry {
+                        language: "Ruby".into(),
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                permissions: ExtensionPermissions {
+                    read_worktree: false,
+                    network: vec!["rubygems.org".into()],
+                    commands: Vec::new(),
+                },
+            },
+        )]
+        .into_iter()
+        .collect(),
     };
 
     let language_registry = Arc::new(LanguageRegistry::test());
@@ -169,6 +212,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         ExtensionStore::new(
             PathBuf::from("/the-extension-dir"),
             fs.clone(),
+            FakeHttpClient::with_404_response(),
             language_registry.clone(),
             theme_registry.clone(),
             cx,
@@ -181,6 +225,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         assert_eq!(manifest.grammars, expected_manifest.grammars);
         assert_eq!(manifest.languages, expected_manifest.languages);
         assert_eq!(manifest.themes, expected_manifest.themes);
+        assert_eq!(manifest.extensions, expected_manifest.extensions);
 
         assert_eq!(
             language_registry.language_names(),
@@ -237,6 +282,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         assert_eq!(manifest.grammars, expected_manifest.grammars);
         assert_eq!(manifest.languages, expected_manifest.languages);
         assert_eq!(manifest.themes, expected_manifest.themes);
+        assert_eq!(manifest.extensions, expected_manifest.extensions);
 
         assert_eq!(
             theme_registry.list_names(false),
@@ -260,6 +306,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         ExtensionStore::new(
             PathBuf::from("/the-extension-dir"),
             fs.clone(),
+            FakeHttpClient::with_404_response(),
             language_registry.clone(),
             theme_registry.clone(),
             cx,
@@ -272,6 +319,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         assert_eq!(manifest.grammars, expected_manifest.grammars);
         assert_eq!(manifest.languages, expected_manifest.languages);
         assert_eq!(manifest.themes, expected_manifest.themes);
+        assert_eq!(manifest.extensions, expected_manifest.extensions);
 
         assert_eq!(
             language_registry.language_names(),
@@ -295,3 +343,48 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         assert_eq!(fs.metadata_call_count(), prev_fs_metadata_call_count + 2);
     });
 }
+
+#[gpui::test]
+async fn test_load_user_languages(cx: &mut TestAppContext) {
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/config/languages",
+        json!({
+            "gleam": {
+                "config.toml": r#"
+                    name = "Gleam"
+                    grammar = "gleam"
+                    path_suffixes = ["gleam"]
+                "#,
+                "gleam.wasm": "",
+                "highlights.scm": "",
+            },
+            "plain-text": {
+                "config.toml": r#"
+                    name = "Plain Text"
+                    path_suffixes = ["txt"]
+                "#,
+            },
+            "not-a-language.txt": "",
+        }),
+    )
+    .await;
+
+    let language_registry = Arc::new(LanguageRegistry::test());
+    user_languages::load_user_languages(
+        Path::new("/config/languages"),
+        fs.clone(),
+        language_registry.clone(),
+    )
+    .await
+    .unwrap();
+
+    // Languages that Zed already has are never replaced.
+    assert_eq!(language_registry.language_names(), ["Gleam", "Plain Text"]);
+
+    // A missing directory has no languages.
+    user_languages::load_user_languages(Path::new("/missing"), fs, language_registry.clone())
+        .await
+        .unwrap();
+    assert_eq!(language_registry.language_names(), ["Gleam", "Plain Text"]);
+}
@@ -0,0 +1,142 @@
+//! Languages that are added by placing a compiled tree-sitter grammar and its queries
+//! in a directory of the config dir, such as `~/.config/zed/languages/foo/`, rather
+//! than by installing an extension.
+
+use anyhow::{anyhow, Context as _, Result};
+use fs::Fs;
+use futures::StreamExt as _;
+use language::{LanguageConfig, LanguageRegistry};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt;
+
+use crate::load_plugin_queries;
+
+/// Registers the language in each directory of `languages_dir`, which contains its
+/// `config.toml`, its grammar and its queries. Languages that Zed already has are
+/// skipped, so that their grammars are never replaced.
+pub async fn load_user_languages(
+    languages_dir: &Path,
+    fs: Arc<dyn Fs>,
+    language_registry: Arc<LanguageRegistry>,
+) -> Result<()> {
+    if !is_dir(fs.as_ref(), languages_dir).await {
+        return Ok(());
+    }
+    let mut language_paths = fs
+        .read_dir(languages_dir)
+        .await
+        .context("failed to read languages directory")?;
+    while let Some(language_path) = language_paths.next().await {
+        let language_path = language_path?;
+        if is_dir(fs.as_ref(), &language_path).await {
+            load_user_language(language_path, fs.as_ref(), &language_registry)
+                .await
+                .log_err();
+        }
+    }
+    Ok(())
+}
+
+async fn load_user_language(
+    language_path: PathBuf,
+    fs: &dyn Fs,
+    language_registry: &LanguageRegistry,
+) -> Result<()> {
+    let config = fs
+        .load(&language_path.join("config.toml"))
+        .await
+        .with_context(|| format!("failed to load config of language {language_path:?}"))?;
+    let config = ::toml::from_str::<LanguageConfig>(&config)?;
+    if language_registry
+        .language_names()
+        .iter()
+        .any(|name| name.as_str() == config.name.as_ref())
+    {
+        log::warn!(
+            "skipping language {language_path:?}, since {} already exists",
+            config.name
+        );
+        return Ok(());
+    }
+
+    let mut grammar_name = None;
+    let mut entries = fs.read_dir(&language_path).await?;
+    while let Some(entry) = entries.next().await {
+        let grammar_path = entry?;
+        let Some(extension) = grammar_path.extension().and_then(OsStr::to_str) else {
+            continue;
+        };
+        if extension != "wasm" && extension != std::env::consts::DLL_EXTENSION {
+            continue;
+        }
+        let Some(file_name) = grammar_path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+        // The grammar is named by the config's `grammar`, or else by its file, as in
+        // `foo.wasm` or `libtree-sitter-foo.so`.
+        let name: Arc<str> = config.grammar.clone().unwrap_or_else(|| {
+            let file_name = file_name.strip_prefix("lib").unwrap_or(file_name);
+            file_name
+                .strip_prefix("tree-sitter-")
+                .unwrap_or(file_name)
+                .into()
+        });
+        if extension == "wasm" {
+            language_registry.register_wasm_grammars([(name.clone(), grammar_path)]);
+        } else {
+            let grammar = load_native_grammar(&grammar_path, &name)?;
+            language_registry.register_native_grammars([(name.clone(), grammar)]);
+        }
+        grammar_name = Some(name);
+        break;
+    }
+    if grammar_name.is_none() {
+        log::warn!("language {language_path:?} has no grammar, so it won't be highlighted");
+    }
+
+    language_registry.register_language(
+        config.name.clone(),
+        grammar_name,
+        config.matcher.clone(),
+        vec![],
+        move || {
+            let config = std::fs::read(language_path.join("config.toml"))?;
+            let config: LanguageConfig = ::toml::from_slice(&config)?;
+            let queries = load_plugin_queries(&language_path);
+            Ok((config, queries))
+        },
+    );
+    Ok(())
+}
+
+async fn is_dir(fs: &dyn Fs, path: &Path) -> bool {
+    fs.metadata(path)
+        .await
+        .ok()
+        .flatten()
+        .map_or(false, |metadata| metadata.is_dir)
+}
+
+/// Loads a grammar compiled as a shared library, whose language is returned by its
+/// `tree_sitter_<name>` function.
+fn load_native_grammar(path: &Path, name: &str) -> Result<tree_sitter::Language> {
+    let symbol_name = format!("tree_sitter_{}", name.replace('-', "_"));
+    // SAFETY: the library's language function is declared the way the grammar crates
+    // declare it. The library is never unloaded, since the language points into it.
+    unsafe {
+        let library = libloading::Library::new(path)
+            .with_context(|| format!("failed to load grammar {path:?}"))?;
+        let language = {
+            let language_fn = library
+                .get::<unsafe extern "C" fn() -> tree_sitter::Language>(symbol_name.as_bytes())
+                .map_err(|_| anyhow!("grammar {path:?} has no {symbol_name} function"))?;
+            language_fn()
+        };
+        std::mem::forget(library);
+        Ok(language)
+    }
+}
@@ -0,0 +1,804 @@
+//! Runs the WASM modules of extensions. Modules get no WASI, so they can only reach
+//! the filesystem and the network through the host functions of the `zed` module,
+//! which check the permissions in the extension's manifest.
+//!
+//! Values cross the boundary as JSON in the module's memory. A module exports its
+//! `memory`, an `alloc(len: u32) -> u32` function that returns a buffer of `len`
+//! bytes, and functions that take the pointer and length of their JSON input and
+//! return the pointer and length of their JSON output, packed into a `u64` as
+//! `ptr << 32 | len`. Outputs are serialized results, like `{"Ok": ...}` or
+//! `{"Err": "message"}`.
+//!
+//! A module's memory is limited, and a call is interrupted once the module has run for
+//! too long, so that a misbehaving module can't hang the language servers that it
+//! provides.
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use futures::{io::BufReader, lock::Mutex, AsyncReadExt as _};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use util::{
+    archive::download_verified_with,
+    audit_log::{self, AuditEvent, AuditEventKind},
+    http::HttpClient,
+};
+use wasmtime::{
+    AsContext, AsContextMut, Caller, Config, Engine, Extern, Instance, Linker, Memory, Module,
+    Store, StoreLimits, StoreLimitsBuilder, UpdateDeadline,
+};
+
+use crate::extension_manifest::ExtensionManifest;
+
+/// How often a running module yields to the executor, so that a busy extension never
+/// blocks the thread that it runs on.
+const EPOCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How many redirects `http_get` follows, checking each of their hosts.
+const MAX_REDIRECTS: usize = 5;
+
+/// The resources that a module may use.
+#[derive(Clone, Copy, Debug)]
+struct WasmLimits {
+    /// The size that the module's memory may grow to.
+    max_memory_bytes: usize,
+    /// How long a call may run the module's code for. The time spent in host functions,
+    /// such as downloads, doesn't count.
+    max_call_duration: Duration,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            max_memory_bytes: 128 * 1024 * 1024,
+            max_call_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+impl WasmLimits {
+    fn max_call_epochs(&self) -> u64 {
+        (self.max_call_duration.as_millis() / EPOCH_INTERVAL.as_millis()).max(1) as u64
+    }
+}
+
+pub struct WasmHost {
+    engine: Engine,
+    linker: Arc<Linker<WasmState>>,
+    http_client: Arc<dyn HttpClient>,
+    /// The directory under which each extension gets a work directory of its own.
+    work_dir: PathBuf,
+    limits: WasmLimits,
+}
+
+/// An extension whose module is only compiled once it's first called, which is shared
+/// by the language servers that the extension provides.
+pub struct LazyWasmExtension {
+    host: Arc<WasmHost>,
+    manifest: Arc<ExtensionManifest>,
+    extension_dir: PathBuf,
+    extension: Mutex<Option<Arc<WasmExtension>>>,
+}
+
+pub struct WasmExtension {
+    manifest: Arc<ExtensionManifest>,
+    store: Mutex<Store<WasmState>>,
+    instance: Instance,
+    max_call_epochs: u64,
+    /// Set once a call has trapped or run out of time, after which the instance is
+    /// replaced by a new one.
+    trapped: AtomicBool,
+}
+
+struct WasmState {
+    manifest: Arc<ExtensionManifest>,
+    work_dir: PathBuf,
+    /// The worktree of the current call, which the module may read when permitted.
+    worktree_root: Option<PathBuf>,
+    http_client: Arc<dyn HttpClient>,
+    limits: StoreLimits,
+    /// How many more epochs the current call may run for before it's interrupted.
+    remaining_call_epochs: u64,
+}
+
+#[derive(Deserialize)]
+struct DownloadFileRequest {
+    url: String,
+    /// The path to write to, relative to the work directory.
+    path: PathBuf,
+    /// The SHA-256 checksum that the download must match, before it's decompressed.
+    #[serde(default)]
+    sha256: Option<String>,
+    #[serde(default)]
+    gzip: bool,
+    #[serde(default)]
+    executable: bool,
+}
+
+impl WasmHost {
+    pub fn new(http_client: Arc<dyn HttpClient>, work_dir: PathBuf) -> Result<Arc<Self>> {
+        Self::with_limits(http_client, work_dir, WasmLimits::default())
+    }
+
+    fn with_limits(
+        http_client: Arc<dyn HttpClient>,
+        work_dir: PathBuf,
+        limits: WasmLimits,
+    ) -> Result<Arc<Self>> {
+        let mut config = Config::new();
+        config.async_support(true).epoch_interruption(true);
+        let engine = Engine::new(&config)?;
+
+        let ticking_engine = engine.weak();
+        std::thread::Builder::new()
+            .name("ExtensionEpochs".into())
+            .spawn(move || {
+                while let Some(engine) = ticking_engine.upgrade() {
+                    engine.increment_epoch();
+                    drop(engine);
+                    std::thread::sleep(EPOCH_INTERVAL);
+                }
+            })?;
+
+        let mut linker = Linker::new(&engine);
+        add_host_functions(&mut linker)?;
+        Ok(Arc::new(Self {
+            engine,
+            linker: Arc::new(linker),
+            http_client,
+            work_dir,
+            limits,
+        }))
+    }
+
+    /// Compiles and instantiates the module of an extension, which is slow, so it
+    /// should only happen once the extension is used.
+    pub async fn load_extension(
+        &self,
+        manifest: Arc<ExtensionManifest>,
+        extension_dir: &Path,
+    ) -> Result<WasmExtension> {
+        let lib = manifest
+            .lib
+            .as_ref()
+            .ok_or_else(|| anyhow!("extension {} has no WASM module", manifest.id))?;
+        let wasm_bytes = smol::fs::read(extension_dir.join(lib))
+            .await
+            .with_context(|| format!("failed to read the module of extension {}", manifest.id))?;
+        let module = Module::new(&self.engine, wasm_bytes)?;
+
+        let work_dir = self.work_dir.join(manifest.id.as_ref());
+        smol::fs::create_dir_all(&work_dir).await?;
+        let max_call_epochs = self.limits.max_call_epochs();
+        let mut store = Store::new(
+            &self.engine,
+            WasmState {
+                manifest: manifest.clone(),
+                work_dir,
+                worktree_root: None,
+                http_client: self.http_client.clone(),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(self.limits.max_memory_bytes)
+                    .instances(1)
+                    .build(),
+                remaining_call_epochs: max_call_epochs,
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        // Yield to the executor on every epoch, and trap once a call has used up its
+        // epochs.
+        store.set_epoch_deadline(1);
+        store.epoch_deadline_callback(|mut store| {
+            let state = store.data_mut();
+            if state.remaining_call_epochs == 0 {
+                bail!("extension {} took too long to respond", state.manifest.id);
+            }
+            state.remaining_call_epochs -= 1;
+            Ok(UpdateDeadline::Yield(1))
+        });
+        let instance = self.linker.instantiate_async(&mut store, &module).await?;
+        Ok(WasmExtension {
+            manifest,
+            store: Mutex::new(store),
+            instance,
+            max_call_epochs,
+            trapped: AtomicBool::new(false),
+        })
+    }
+}
+
+impl LazyWasmExtension {
+    pub fn new(
+        host: Arc<WasmHost>,
+        manifest: Arc<ExtensionManifest>,
+        extension_dir: PathBuf,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            host,
+            manifest,
+            extension_dir,
+            extension: Mutex::new(None),
+        })
+    }
+
+    pub fn manifest(&self) -> &Arc<ExtensionManifest> {
+        &self.manifest
+    }
+
+    pub async fn get(&self) -> Result<Arc<WasmExtension>> {
+        let mut extension = self.extension.lock().await;
+        if let Some(extension) = extension
+            .as_ref()
+            .filter(|extension| !extension.trapped.load(Ordering::SeqCst))
+        {
+            return Ok(extension.clone());
+        }
+        let loaded = Arc::new(
+            self.host
+                .load_extension(self.manifest.clone(), &self.extension_dir)
+                .await?,
+        );
+        *extension = Some(loaded.clone());
+        Ok(loaded)
+    }
+}
+
+impl WasmExtension {
+    pub fn manifest(&self) -> &Arc<ExtensionManifest> {
+        &self.manifest
+    }
+
+    pub async fn work_dir(&self) -> PathBuf {
+        self.store.lock().await.data().work_dir.clone()
+    }
+
+    /// Calls an exported function of the module, or returns `None` if it doesn't export
+    /// one with that name.
+    pub async fn call<I: Serialize, O: DeserializeOwned>(
+        &self,
+        function_name: &str,
+        worktree_root: Option<&Path>,
+        input: &I,
+    ) -> Result<Option<O>> {
+        let mut store = self.store.lock().await;
+        if self
+            .instance
+            .get_export(&mut *store, function_name)
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let state = store.data_mut();
+        state.worktree_root = worktree_root.map(Path::to_path_buf);
+        state.remaining_call_epochs = self.max_call_epochs;
+
+        let output = self.call_export(&mut store, function_name, input).await;
+        store.data_mut().worktree_root = None;
+        let output = output.map_err(|error| {
+            self.trapped.store(true, Ordering::SeqCst);
+            error.context(format!(
+                "failed to call {function_name} in extension {}",
+                self.manifest.id
+            ))
+        })?;
+
+        let output: Result<O, String> = serde_json::from_slice(&output).with_context(|| {
+            format!(
+                "invalid output of {function_name} in extension {}",
+                self.manifest.id
+            )
+        })?;
+        output.map(Some).map_err(|error| {
+            anyhow!(
+                "{function_name} failed in extension {}: {error}",
+                self.manifest.id
+            )
+        })
+    }
+
+    async fn call_export<I: Serialize>(
+        &self,
+        store: &mut Store<WasmState>,
+        function_name: &str,
+        input: &I,
+    ) -> Result<Vec<u8>> {
+        let function = self
+            .instance
+            .get_typed_func::<(u32, u32), u64>(&mut *store, function_name)?;
+        let alloc = self
+            .instance
+            .get_typed_func::<u32, u32>(&mut *store, "alloc")?;
+        let memory = self
+            .instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| anyhow!("extension {} exports no memory", self.manifest.id))?;
+
+        let input = serde_json::to_vec(input)?;
+        let input_ptr = alloc.call_async(&mut *store, input.len() as u32).await?;
+        memory.write(&mut *store, input_ptr as usize, &input)?;
+        let output = function
+            .call_async(&mut *store, (input_ptr, input.len() as u32))
+            .await?;
+        read_guest_bytes(&*store, memory, output)
+    }
+}
+
+fn add_host_functions(linker: &mut Linker<WasmState>) -> Result<()> {
+    linker.func_wrap(
+        "zed",
+        "log",
+        |mut caller: Caller<'_, WasmState>, ptr: u32, len: u32| -> Result<()> {
+            let memory = guest_memory(&mut caller)?;
+            let message = read_guest_bytes(&caller, memory, pack(ptr, len))?;
+            log::info!(
+                "extension {}: {}",
+                caller.data().manifest.id,
+                String::from_utf8_lossy(&message)
+            );
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap_async(
+        "zed",
+        "read_file",
+        |mut caller: Caller<'_, WasmState>, (ptr, len): (u32, u32)| {
+            Box::new(async move {
+                let path: PathBuf = read_guest_json(&mut caller, ptr, len)?;
+                let result = match readable_path(caller.data(), &path) {
+                    Ok(path) => smol::fs::read_to_string(&path)
+                        .await
+                        .map_err(|error| error.to_string()),
+                    Err(error) => Err(error.to_string()),
+                };
+                write_guest_json(&mut caller, &result).await
+            })
+        },
+    )?;
+
+    linker.func_wrap_async(
+        "zed",
+        "http_get",
+        |mut caller: Caller<'_, WasmState>, (ptr, len): (u32, u32)| {
+            Box::new(async move {
+                let url: String = read_guest_json(&mut caller, ptr, len)?;
+                let state = caller.data();
+                audit_log::record(extension_event(
+                    state,
+                    AuditEventKind::Request,
+                    format!("GET {url}"),
+                ));
+                let result = http_get(&state.manifest, state.http_client.as_ref(), &url)
+                    .await
+                    .and_then(|body| Ok(String::from_utf8(body)?))
+                    .map_err(|error| error.to_string());
+                write_guest_json(&mut caller, &result).await
+            })
+        },
+    )?;
+
+    linker.func_wrap_async(
+        "zed",
+        "download_file",
+        |mut caller: Caller<'_, WasmState>, (ptr, len): (u32, u32)| {
+            Box::new(async move {
+                let request: DownloadFileRequest = read_guest_json(&mut caller, ptr, len)?;
+                let result = download_file(caller.data(), request)
+                    .await
+                    .map_err(|error| error.to_string());
+                write_guest_json(&mut caller, &result).await
+            })
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Returns the path that the module asked to read, if it's in its work directory or
+/// in the worktree that it's allowed to read. Paths are compared once their symlinks
+/// are resolved, so that a symlink can't point outside of those directories.
+fn readable_path(state: &WasmState, path: &Path) -> Result<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        state.work_dir.join(path)
+    };
+    if path
+        .components()
+        .any(|component| component == Component::ParentDir)
+    {
+        bail!("{path:?} must not contain `..`");
+    }
+    let path = std::fs::canonicalize(&path).with_context(|| format!("{path:?} doesn't exist"))?;
+    let is_in = |dir: &Path| std::fs::canonicalize(dir).map_or(false, |dir| path.starts_with(dir));
+    let in_worktree = state.manifest.permissions.read_worktree
+        && state.worktree_root.as_deref().map_or(false, is_in);
+    if is_in(&state.work_dir) || in_worktree {
+        Ok(path)
+    } else {
+        bail!("extension {} may not read {path:?}", state.manifest.id)
+    }
+}
+
+async fn http_get(
+    manifest: &ExtensionManifest,
+    http_client: &dyn HttpClient,
+    url: &str,
+) -> Result<Vec<u8>> {
+    // Redirects are followed here rather than by the client, so that the host of each
+    // of them is checked.
+    let mut url = url::Url::parse(url)?;
+    for _ in 0..=MAX_REDIRECTS {
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("extension {} may not fetch {url}", manifest.id);
+        }
+        let host = url.host_str().ok_or_else(|| anyhow!("{url} has no host"))?;
+        if !manifest.permissions.allows_host(host) {
+            bail!("extension {} may not connect to {host}", manifest.id);
+        }
+
+        let mut response = http_client
+            .get(url.as_str(), Default::default(), false)
+            .await?;
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get("location")
+                .and_then(|location| location.to_str().ok())
+                .ok_or_else(|| anyhow!("redirect from {url} has no location"))?;
+            url = url.join(location)?;
+            continue;
+        }
+        if !response.status().is_success() {
+            bail!("request to {url} failed with status {}", response.status());
+        }
+        let mut body = Vec::new();
+        response.body_mut().read_to_end(&mut body).await?;
+        return Ok(body);
+    }
+    bail!("request to {url} was redirected too many times")
+}
+
+async fn download_file(state: &WasmState, request: DownloadFileRequest) -> Result<()> {
+    if request.path.is_absolute()
+        || request
+            .path
+            .components()
+            .any(|component| component == Component::ParentDir)
+    {
+        bail!(
+            "{:?} must be a path in the extension's work directory",
+            request.path
+        );
+    }
+    let path = state.work_dir.join(&request.path);
+
+    audit_log::record(
+        extension_event(
+            state,
+            AuditEventKind::Download,
+            format!("Download of {}", request.url),
+        )
+        .details(format!("into {}", path.display())),
+    );
+    let body = download_verified_with(&request.url, request.sha256.as_deref(), || {
+        http_get(&state.manifest, state.http_client.as_ref(), &request.url)
+    })
+    .await?;
+    let contents = if request.gzip {
+        let mut decompressed = Vec::new();
+        GzipDecoder::new(BufReader::new(body.as_slice()))
+            .read_to_end(&mut decompressed)
+            .await?;
+        decompressed
+    } else {
+        body
+    };
+
+    if let Some(parent) = path.parent() {
+        smol::fs::create_dir_all(parent).await?;
+    }
+    smol::fs::write(&path, contents).await?;
+    #[cfg(unix)]
+    if request.executable {
+        use std::os::unix::fs::PermissionsExt as _;
+        smol::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+    Ok(())
+}
+
+/// Returns an audit event for an action that the extension of `state` took.
+fn extension_event(state: &WasmState, kind: AuditEventKind, summary: String) -> AuditEvent {
+    let event = AuditEvent::new(kind, format!("extension {}", state.manifest.id), summary);
+    match &state.worktree_root {
+        Some(worktree_root) => event.project_root(worktree_root),
+        None => event,
+    }
+}
+
+fn pack(ptr: u32, len: u32) -> u64 {
+    (ptr as u64) << 32 | len as u64
+}
+
+fn guest_memory(caller: &mut Caller<'_, WasmState>) -> Result<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| anyhow!("extension exports no memory"))
+}
+
+fn read_guest_bytes(store: impl AsContext, memory: Memory, packed: u64) -> Result<Vec<u8>> {
+    let ptr = (packed >> 32) as usize;
+    let len = packed as u32 as usize;
+    memory
+        .data(&store)
+        .get(ptr..ptr + len)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| anyhow!("extension returned a buffer outside of its memory"))
+}
+
+fn read_guest_json<T: DeserializeOwned>(
+    caller: &mut Caller<'_, WasmState>,
+    ptr: u32,
+    len: u32,
+) -> Result<T> {
+    let memory = guest_memory(caller)?;
+    let bytes = read_guest_bytes(&*caller, memory, pack(ptr, len))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Copies the value into a buffer that the module allocates, returning the buffer's
+/// packed pointer and length.
+async fn write_guest_json<T: Serialize>(
+    caller: &mut Caller<'_, WasmState>,
+    value: &T,
+) -> Result<u64> {
+    let bytes = serde_json::to_vec(value)?;
+    let alloc = caller
+        .get_export("alloc")
+        .and_then(Extern::into_func)
+        .ok_or_else(|| anyhow!("extension exports no alloc function"))?
+        .typed::<u32, u32>(&*caller)?;
+    let ptr = alloc
+        .call_async(caller.as_context_mut(), bytes.len() as u32)
+        .await?;
+    let memory = guest_memory(caller)?;
+    memory.write(caller.as_context_mut(), ptr as usize, &bytes)?;
+    Ok(pack(ptr, bytes.len() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extension_manifest::ExtensionPermissions;
+    use util::http::{FakeHttpClient, Response};
+
+    /// A module that reads files through the `read_file` host function, and that has
+    /// functions which never return, or which grow its memory. The latter return whether
+    /// `memory.grow` succeeded, as one of the JSON results at the start of the memory.
+    const TEST_MODULE: &str = r#"
+        (module
+            (import "zed" "read_file" (func $read_file (param i32 i32) (result i64)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "{\"Ok\":true}")
+            (data (i32.const 16) "{\"Ok\":false}")
+            (global $next (mut i32) (i32.const 1024))
+            (func (export "alloc") (param $len i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $next))
+                (global.set $next (i32.add (global.get $next) (local.get $len)))
+                (local.get $ptr))
+            (func (export "read") (param i32 i32) (result i64)
+                (call $read_file (local.get 0) (local.get 1)))
+            (func (export "spin") (param i32 i32) (result i64)
+                (loop $forever (br $forever))
+                (i64.const 0))
+            (func $grow (param $pages i32) (result i64)
+                (if (result i64) (i32.eq (memory.grow (local.get $pages)) (i32.const -1))
+                    (then (i64.const 0x100000000c))
+                    (else (i64.const 0xb))))
+            (func (export "grow_within_limit") (param i32 i32) (result i64)
+                (call $grow (i32.const 1)))
+            (func (export "grow_beyond_limit") (param i32 i32) (result i64)
+                (call $grow (i32.const 4096))))
+    "#;
+
+    fn manifest(network: Vec<String>) -> Arc<ExtensionManifest> {
+        Arc::new(ExtensionManifest {
+            id: "test".into(),
+            name: "Test".into(),
+            version: "0.1.0".into(),
+            description: None,
+            authors: Vec::new(),
+            lib: Some("test.wasm".into()),
+            language_servers: Default::default(),
+            permissions: ExtensionPermissions {
+                network,
+                ..Default::default()
+            },
+        })
+    }
+
+    #[test]
+    fn test_call_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let extension_dir = dir.path().join("extension");
+        let work_dir = dir.path().join("work");
+        std::fs::create_dir_all(&extension_dir).unwrap();
+        std::fs::create_dir_all(work_dir.join("test")).unwrap();
+        std::fs::write(extension_dir.join("test.wasm"), TEST_MODULE).unwrap();
+        std::fs::write(work_dir.join("test").join("greeting.txt"), "hello").unwrap();
+
+        let host = WasmHost::with_limits(
+            FakeHttpClient::with_404_response(),
+            work_dir,
+            WasmLimits {
+                max_memory_bytes: 1024 * 1024,
+                max_call_duration: Duration::from_millis(500),
+            },
+        )
+        .unwrap();
+        let extension = LazyWasmExtension::new(host, manifest(Vec::new()), extension_dir);
+        smol::block_on(async {
+            let module = extension.get().await.unwrap();
+            let contents: Option<String> =
+                module.call("read", None, &"greeting.txt").await.unwrap();
+            assert_eq!(contents.as_deref(), Some("hello"));
+            let error = module
+                .call::<_, String>("read", None, &"/etc/passwd")
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("may not read"), "{error}");
+            assert!(module
+                .call::<_, String>("missing", None, &())
+                .await
+                .unwrap()
+                .is_none());
+
+            // Growing the memory beyond the limit fails, without affecting the module.
+            assert_eq!(
+                module
+                    .call::<_, bool>("grow_within_limit", None, &())
+                    .await
+                    .unwrap(),
+                Some(true)
+            );
+            assert_eq!(
+                module
+                    .call::<_, bool>("grow_beyond_limit", None, &())
+                    .await
+                    .unwrap(),
+                Some(false)
+            );
+            let contents: Option<String> =
+                module.call("read", None, &"greeting.txt").await.unwrap();
+            assert_eq!(contents.as_deref(), Some("hello"));
+
+            // A call that never returns is interrupted, and the module is instantiated
+            // again for the next call.
+            let module = extension.get().await.unwrap();
+            let error = module.call::<_, ()>("spin", None, &()).await.unwrap_err();
+            assert!(format!("{error:#}").contains("took too long"), "{error:#}");
+            let module = extension.get().await.unwrap();
+            let contents: Option<String> =
+                module.call("read", None, &"greeting.txt").await.unwrap();
+            assert_eq!(contents.as_deref(), Some("hello"));
+        });
+    }
+
+    #[test]
+    fn test_http_get_checks_redirects() {
+        let http_client = FakeHttpClient::create(|request| async move {
+            let response = match request.uri().to_string().as_str() {
+                "https://github.com/elsewhere" => Response::builder()
+                    .status(302)
+                    .header("location", "https://example.com/payload"),
+                "https://github.com/release" => Response::builder()
+                    .status(302)
+                    .header("location", "https://objects.github.com/asset"),
+                "https://objects.github.com/asset" => Response::builder().status(200),
+                _ => Response::builder().status(404),
+            };
+            Ok(response.body("asset".into()).unwrap())
+        });
+        let manifest = manifest(vec!["github.com".into()]);
+
+        smol::block_on(async {
+            assert_eq!(
+                http_get(&manifest, &http_client, "https://github.com/release")
+                    .await
+                    .unwrap(),
+                b"asset"
+            );
+            let error = http_get(&manifest, &http_client, "https://github.com/elsewhere")
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("example.com"), "{error}");
+            assert!(http_get(&manifest, &http_client, "file:///etc/passwd")
+                .await
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_readable_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        let work_dir = dir.join("work").join("gleam");
+        let worktree_root = dir.join("projects").join("app");
+        let other_worktree_root = dir.join("projects").join("other");
+        std::fs::create_dir_all(work_dir.join("bin")).unwrap();
+        std::fs::create_dir_all(&worktree_root).unwrap();
+        std::fs::create_dir_all(&other_worktree_root).unwrap();
+        std::fs::write(work_dir.join("bin").join("gleam"), "").unwrap();
+        std::fs::write(worktree_root.join("gleam.toml"), "").unwrap();
+        std::fs::write(other_worktree_root.join("gleam.toml"), "").unwrap();
+
+        let state = |read_worktree| WasmState {
+            manifest: Arc::new(ExtensionManifest {
+                id: "gleam".into(),
+                name: "Gleam".into(),
+                version: "0.1.0".into(),
+                description: None,
+                authors: Vec::new(),
+                lib: None,
+                language_servers: Default::default(),
+                permissions: ExtensionPermissions {
+                    read_worktree,
+                    network: vec!["github.com".into()],
+                    commands: Vec::new(),
+                },
+            }),
+            work_dir: work_dir.clone(),
+            worktree_root: Some(worktree_root.clone()),
+            http_client: util::http::FakeHttpClient::with_404_response(),
+            limits: StoreLimits::default(),
+            remaining_call_epochs: 0,
+        };
+
+        let state_without_worktree = state(false);
+        assert_eq!(
+            readable_path(&state_without_worktree, Path::new("bin/gleam")).unwrap(),
+            work_dir.join("bin").join("gleam")
+        );
+        assert!(readable_path(&state_without_worktree, &worktree_root.join("gleam.toml")).is_err());
+        assert!(readable_path(&state_without_worktree, Path::new("../other/secret")).is_err());
+        assert!(readable_path(&state_without_worktree, Path::new("/etc/passwd")).is_err());
+
+        let state_with_worktree = state(true);
+        assert!(readable_path(&state_with_worktree, &worktree_root.join("gleam.toml")).is_ok());
+        assert!(readable_path(
+            &state_with_worktree,
+            &other_worktree_root.join("gleam.toml")
+        )
+        .is_err());
+
+        // Symlinks are resolved before checking where a path leads.
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&other_worktree_root, work_dir.join("escape")).unwrap();
+            std::os::unix::fs::symlink(&work_dir, dir.join("work-link")).unwrap();
+            assert!(readable_path(&state_with_worktree, Path::new("escape/gleam.toml")).is_err());
+            assert_eq!(
+                readable_path(
+                    &state_with_worktree,
+                    &dir.join("work-link").join("bin").join("gleam")
+                )
+                .unwrap(),
+                work_dir.join("bin").join("gleam")
+            );
+        }
+
+        let permissions = &state_with_worktree.manifest.permissions;
+        assert!(permissions.allows_host("github.com"));
+        assert!(permissions.allows_host("api.GitHub.com"));
+        assert!(!permissions.allows_host("notgithub.com"));
+    }
+}
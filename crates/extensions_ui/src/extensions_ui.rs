@@ -0,0 +1,244 @@
+use extension::{ExtensionStore, InstalledExtension};
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
+    Model, ParentElement, PathPromptOptions, PromptLevel, Render, Styled, Subscription, View,
+    ViewContext, VisualContext, WindowContext,
+};
+use std::sync::Arc;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    notifications::NotifyTaskExt,
+    Workspace, WorkspaceId,
+};
+
+actions!(zed, [Extensions]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _cx| {
+        workspace.register_action(|workspace, _: &Extensions, cx| {
+            let extensions_page = ExtensionsPage::new(cx);
+            workspace.add_item(Box::new(extensions_page), cx)
+        });
+    })
+    .detach();
+}
+
+/// Lists the installed extensions, with what each of them provides and may access.
+pub struct ExtensionsPage {
+    extension_store: Model<ExtensionStore>,
+    focus_handle: FocusHandle,
+    _extension_store_subscription: Subscription,
+}
+
+impl ExtensionsPage {
+    pub fn new(cx: &mut ViewContext<Workspace>) -> View<Self> {
+        cx.new_view(|cx| {
+            let extension_store = ExtensionStore::global(cx);
+            Self {
+                _extension_store_subscription: cx.observe(&extension_store, |_, _, cx| cx.notify()),
+                extension_store,
+                focus_handle: cx.focus_handle(),
+            }
+        })
+    }
+
+    fn install_dev_extension(&mut self, cx: &mut ViewContext<Self>) {
+        let extension_store = self.extension_store.clone();
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        let task = cx.spawn(|this, mut cx| async move {
+            let Some(mut paths) = paths.await.log_err().flatten() else {
+                return Ok(());
+            };
+            let Some(path) = paths.pop() else {
+                return Ok(());
+            };
+
+            // Extensions only get the access that the user has agreed to.
+            let manifest = extension_store
+                .update(&mut cx, |store, cx| {
+                    store.dev_extension_manifest(path.clone(), cx)
+                })?
+                .await?;
+            let permissions = manifest.permissions.descriptions();
+            if !permissions.is_empty() {
+                let answer = this.update(&mut cx, |_, cx| {
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        &format!("Install {}?", manifest.name),
+                        Some(&permissions.join("\n")),
+                        &["Install", "Cancel"],
+                    )
+                })?;
+                if answer.await != Ok(0) {
+                    return Ok(());
+                }
+            }
+
+            extension_store
+                .update(&mut cx, |store, cx| store.install_dev_extension(path, cx))?
+                .await
+        });
+        task.detach_and_notify_err(cx);
+    }
+
+    fn uninstall_extension(&mut self, dir_name: Arc<str>, cx: &mut ViewContext<Self>) {
+        self.extension_store
+            .update(cx, |store, cx| store.uninstall_extension(dir_name, cx))
+            .detach_and_notify_err(cx);
+    }
+
+    fn render_extension(
+        &self,
+        extension: InstalledExtension,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let manifest = extension.manifest.as_ref();
+        let name = manifest.map_or(extension.dir_name.to_string(), |manifest| {
+            manifest.name.clone()
+        });
+
+        let mut details = Vec::new();
+        if let Some(manifest) = manifest {
+            if !manifest.language_servers.is_empty() {
+                let language_servers = manifest
+                    .language_servers
+                    .keys()
+                    .map(AsRef::as_ref)
+                    .collect::<Vec<_>>();
+                details.push(format!("Language servers: {}", language_servers.join(", ")));
+            }
+            details.extend(manifest.permissions.descriptions());
+        }
+
+        v_flex()
+            .p_3()
+            .gap_1()
+            .bg(cx.theme().colors().elevated_surface_background)
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .rounded_md()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(name))
+                            .children(manifest.map(|manifest| {
+                                Label::new(format!("v{}", manifest.version)).color(Color::Muted)
+                            })),
+                    )
+                    .child(
+                        Button::new(
+                            SharedString::from(format!("uninstall-{}", extension.dir_name)),
+                            "Uninstall",
+                        )
+                        .on_click({
+                            let dir_name = extension.dir_name.clone();
+                            cx.listener(move |this, _, cx| {
+                                this.uninstall_extension(dir_name.clone(), cx)
+                            })
+                        }),
+                    ),
+            )
+            .children(
+                manifest
+                    .and_then(|manifest| manifest.description.clone())
+                    .map(|description| Label::new(description).size(LabelSize::Small)),
+            )
+            .children(details.into_iter().map(|detail| {
+                Label::new(detail)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+    }
+}
+
+impl Render for ExtensionsPage {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let extensions = self.extension_store.read(cx).installed_extensions();
+
+        v_flex()
+            .id("extensions-page")
+            .full()
+            .p_4()
+            .gap_4()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .track_focus(&self.focus_handle)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Headline::new("Installed Extensions").size(HeadlineSize::XLarge))
+                    .child(
+                        Button::new("install-dev-extension", "Install Dev Extension")
+                            .style(ButtonStyle::Filled)
+                            .on_click(cx.listener(|this, _, cx| this.install_dev_extension(cx))),
+                    ),
+            )
+            .map(|page| {
+                if extensions.is_empty() {
+                    page.child(Label::new("No extensions are installed.").color(Color::Muted))
+                } else {
+                    page.children(
+                        extensions
+                            .into_iter()
+                            .map(|extension| self.render_extension(extension, cx)),
+                    )
+                }
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for ExtensionsPage {}
+
+impl FocusableView for ExtensionsPage {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ExtensionsPage {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new("Extensions")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("extensions page")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| Self {
+            extension_store: self.extension_store.clone(),
+            focus_handle: cx.focus_handle(),
+            _extension_store_subscription:
+                cx.observe(&self.extension_store, |_, _, cx| cx.notify()),
+        }))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        f(*event)
+    }
+}
@@ -1,13 +1,20 @@
+use collections::HashMap;
 use gpui::{AppContext, Global, Subscription, ViewContext};
 
 #[derive(Default)]
 struct FeatureFlags {
     flags: Vec<String>,
     staff: bool,
+    /// The flags that were turned on or off locally, which take precedence over the
+    /// ones provided by the server.
+    overrides: HashMap<String, bool>,
 }
 
 impl FeatureFlags {
     fn has_flag(&self, flag: &str) -> bool {
+        if let Some(enabled) = self.overrides.get(flag) {
+            return *enabled;
+        }
         self.staff || self.flags.iter().find(|f| f.as_str() == flag).is_some()
     }
 }
@@ -42,6 +49,7 @@ where
 pub trait FeatureFlagAppExt {
     fn update_flags(&mut self, staff: bool, flags: Vec<String>);
     fn set_staff(&mut self, staff: bool);
+    fn override_flags(&mut self, overrides: HashMap<String, bool>);
     fn has_flag<T: FeatureFlag>(&self) -> bool;
     fn is_staff(&self) -> bool;
 }
@@ -58,6 +66,17 @@ impl FeatureFlagAppExt for AppContext {
         feature_flags.staff = staff;
     }
 
+    fn override_flags(&mut self, overrides: HashMap<String, bool>) {
+        let is_unchanged = self
+            .try_global::<FeatureFlags>()
+            .map_or(false, |flags| flags.overrides == overrides);
+        if is_unchanged {
+            return;
+        }
+        let feature_flags = self.default_global::<FeatureFlags>();
+        feature_flags.overrides = overrides;
+    }
+
     fn has_flag<T: FeatureFlag>(&self) -> bool {
         self.try_global::<FeatureFlags>()
             .map(|flags| flags.has_flag(T::NAME))
@@ -74,6 +74,10 @@ impl FileFinder {
             })
             .map(|(history_path, abs_path)| FoundPath::new(history_path, abs_path))
             .collect::<Vec<_>>();
+        let closed_paths = workspace
+            .recently_closed_paths(cx)
+            .into_iter()
+            .collect::<HashSet<_>>();
 
         let project = workspace.project().clone();
         let weak_workspace = cx.view().downgrade();
@@ -84,6 +88,7 @@ impl FileFinder {
                 project,
                 currently_opened_path,
                 history_items,
+                closed_paths,
                 cx,
             );
 
@@ -125,6 +130,7 @@ pub struct FileFinderDelegate {
     selected_index: usize,
     cancel_flag: Arc<AtomicBool>,
     history_items: Vec<FoundPath>,
+    closed_paths: HashSet<ProjectPath>,
 }
 
 /// Use a custom ordering for file finder: the regular one
@@ -163,18 +169,21 @@ impl PartialOrd for ProjectPanelOrdMatch {
 #[derive(Debug, Default)]
 struct Matches {
     history: Vec<(FoundPath, Option<ProjectPanelOrdMatch>)>,
+    /// History items that were closed and can be reopened with their previous state.
+    closed: Vec<(FoundPath, Option<ProjectPanelOrdMatch>)>,
     search: Vec<ProjectPanelOrdMatch>,
 }
 
 #[derive(Debug)]
 enum Match<'a> {
     History(&'a FoundPath, Option<&'a ProjectPanelOrdMatch>),
+    Closed(&'a FoundPath, Option<&'a ProjectPanelOrdMatch>),
     Search(&'a ProjectPanelOrdMatch),
 }
 
 impl Matches {
     fn len(&self) -> usize {
-        self.history.len() + self.search.len()
+        self.history.len() + self.closed.len() + self.search.len()
     }
 
     fn get(&self, index: usize) -> Option<Match<'_>> {
@@ -182,9 +191,13 @@ impl Matches {
             self.history
                 .get(index)
                 .map(|(path, path_match)| Match::History(path, path_match.as_ref()))
+        } else if index < self.history.len() + self.closed.len() {
+            self.closed
+                .get(index - self.history.len())
+                .map(|(path, path_match)| Match::Closed(path, path_match.as_ref()))
         } else {
             self.search
-                .get(index - self.history.len())
+                .get(index - self.history.len() - self.closed.len())
                 .map(Match::Search)
         }
     }
@@ -192,6 +205,7 @@ impl Matches {
     fn push_new_matches(
         &mut self,
         history_items: &Vec<FoundPath>,
+        closed_paths: &HashSet<ProjectPath>,
         currently_opened: Option<&FoundPath>,
         query: &PathLikeWithPosition<FileSearchQuery>,
         new_search_matches: impl Iterator<Item = ProjectPanelOrdMatch>,
@@ -206,6 +220,7 @@ impl Matches {
             currently_opened,
             Some(&matching_history_paths),
             history_items,
+            closed_paths,
         );
         if extend_old_matches {
             self.search
@@ -221,9 +236,10 @@ impl Matches {
         currently_opened: Option<&'a FoundPath>,
         query_matches: Option<&'a HashMap<Arc<Path>, ProjectPanelOrdMatch>>,
         history_items: impl IntoIterator<Item = &'a FoundPath> + 'a,
+        closed_paths: &HashSet<ProjectPath>,
     ) {
         let mut processed_paths = HashSet::default();
-        self.history = history_items
+        (self.history, self.closed) = history_items
             .into_iter()
             .chain(currently_opened)
             .filter(|&path| processed_paths.insert(path))
@@ -249,7 +265,7 @@ impl Matches {
                 },
             )
             .map(|(_, paths)| paths)
-            .collect();
+            .partition(|(history_item, _)| !closed_paths.contains(&history_item.project));
     }
 }
 
@@ -363,6 +379,7 @@ impl FileFinderDelegate {
         project: Model<Project>,
         currently_opened_path: Option<FoundPath>,
         history_items: Vec<FoundPath>,
+        closed_paths: HashSet<ProjectPath>,
         cx: &mut ViewContext<FileFinder>,
     ) -> Self {
         cx.observe(&project, |file_finder, _, cx| {
@@ -386,6 +403,7 @@ impl FileFinderDelegate {
             selected_index: 0,
             cancel_flag: Arc::new(AtomicBool::new(false)),
             history_items,
+            closed_paths,
         }
     }
 
@@ -464,6 +482,7 @@ impl FileFinderDelegate {
                         .map(|query| query.path_like.path_query());
             self.matches.push_new_matches(
                 &self.history_items,
+                &self.closed_paths,
                 self.currently_opened_path.as_ref(),
                 &query,
                 matches.into_iter(),
@@ -483,7 +502,8 @@ impl FileFinderDelegate {
         ix: usize,
     ) -> (String, Vec<usize>, String, Vec<usize>) {
         let (file_name, file_name_positions, full_path, full_path_positions) = match path_match {
-            Match::History(found_path, found_path_match) => {
+            Match::History(found_path, found_path_match)
+            | Match::Closed(found_path, found_path_match) => {
                 let worktree_id = found_path.project.worktree_id;
                 let project_relative_path = &found_path.project.path;
                 let has_worktree = self
@@ -682,11 +702,15 @@ impl PickerDelegate for FileFinderDelegate {
 
     fn separators_after_indices(&self) -> Vec<usize> {
         let history_items = self.matches.history.len();
-        if history_items == 0 || self.matches.search.is_empty() {
-            Vec::new()
-        } else {
-            vec![history_items - 1]
+        let closed_items = self.matches.closed.len();
+        let mut separators = Vec::new();
+        if history_items > 0 && closed_items + self.matches.search.len() > 0 {
+            separators.push(history_items - 1);
         }
+        if closed_items > 0 && !self.matches.search.is_empty() {
+            separators.push(history_items + closed_items - 1);
+        }
+        separators
     }
 
     fn update_matches(
@@ -699,10 +723,7 @@ impl PickerDelegate for FileFinderDelegate {
         if raw_query.is_empty() {
             let project = self.project.read(cx);
             self.latest_search_id = post_inc(&mut self.search_count);
-            self.matches = Matches {
-                history: Vec::new(),
-                search: Vec::new(),
-            };
+            self.matches = Matches::default();
             self.matches.set_new_history(
                 self.currently_opened_path.as_ref(),
                 None,
@@ -712,6 +733,7 @@ impl PickerDelegate for FileFinderDelegate {
                         .is_some()
                         || (project.is_local() && history_item.absolute.is_some())
                 }),
+                &self.closed_paths,
             );
 
             self.selected_index = self.calculate_selected_index();
@@ -750,7 +772,17 @@ impl PickerDelegate for FileFinderDelegate {
                         }
                     };
                     match m {
-                        Match::History(history_match, _) => {
+                        Match::Closed(closed_match, _)
+                            if !secondary
+                                && workspace
+                                    .project()
+                                    .read(cx)
+                                    .worktree_for_id(closed_match.project.worktree_id, cx)
+                                    .is_some() =>
+                        {
+                            workspace.reopen_closed_path(closed_match.project.clone(), cx)
+                        }
+                        Match::History(history_match, _) | Match::Closed(history_match, _) => {
                             let worktree_id = history_match.project.worktree_id;
                             if workspace
                                 .project()
@@ -862,6 +894,10 @@ impl PickerDelegate for FileFinderDelegate {
             .get(ix)
             .expect("Invalid matches state: no element for index {ix}");
 
+        let first_closed_item = match path_match {
+            Match::Closed(..) => ix == self.matches.history.len(),
+            _ => false,
+        };
         let (file_name, file_name_positions, full_path, full_path_positions) =
             self.labels_for_match(path_match, cx, ix);
 
@@ -870,6 +906,13 @@ impl PickerDelegate for FileFinderDelegate {
                 .spacing(ListItemSpacing::Sparse)
                 .inset(true)
                 .selected(selected)
+                .when(first_closed_item, |item| {
+                    item.end_slot(
+                        Label::new("Recently closed")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
                 .child(
                     h_flex()
                         .gap_2()
@@ -947,8 +947,9 @@ async fn test_search_preserves_history_items(cx: &mut gpui::TestAppContext) {
         .await;
     finder.update(cx, |finder, _| {
             let delegate = &finder.delegate;
-            assert_eq!(delegate.matches.history.len(), 1, "Only one history item contains {first_query}, it should be present and others should be filtered out");
-            let history_match = delegate.matches.history.first().unwrap();
+            assert!(delegate.matches.history.is_empty(), "All history items were closed and should be shown as such");
+            assert_eq!(delegate.matches.closed.len(), 1, "Only one closed item contains {first_query}, it should be present and others should be filtered out");
+            let history_match = delegate.matches.closed.first().unwrap();
             assert!(history_match.1.is_some(), "Should have path matches for history items after querying");
             assert_eq!(history_match.0, FoundPath::new(
                 ProjectPath {
@@ -971,7 +972,7 @@ async fn test_search_preserves_history_items(cx: &mut gpui::TestAppContext) {
     finder.update(cx, |finder, _| {
         let delegate = &finder.delegate;
         assert!(
-            delegate.matches.history.is_empty(),
+            delegate.matches.history.is_empty() && delegate.matches.closed.is_empty(),
             "No history entries should match {second_query}"
         );
         assert!(
@@ -992,8 +993,8 @@ async fn test_search_preserves_history_items(cx: &mut gpui::TestAppContext) {
         .await;
     finder.update(cx, |finder, _| {
             let delegate = &finder.delegate;
-            assert_eq!(delegate.matches.history.len(), 1, "Only one history item contains {first_query_again}, it should be present and others should be filtered out, even after non-matching query");
-            let history_match = delegate.matches.history.first().unwrap();
+            assert_eq!(delegate.matches.closed.len(), 1, "Only one closed item contains {first_query_again}, it should be present and others should be filtered out, even after non-matching query");
+            let history_match = delegate.matches.closed.first().unwrap();
             assert!(history_match.1.is_some(), "Should have path matches for history items after querying");
             assert_eq!(history_match.0, FoundPath::new(
                 ProjectPath {
@@ -1048,8 +1049,9 @@ async fn test_search_sorts_history_items(cx: &mut gpui::TestAppContext) {
         .await;
     finder.update(cx, |finder, _| {
         let search_matches = collect_search_matches(finder);
+        assert!(search_matches.history.is_empty());
         assert_eq!(
-            search_matches.history,
+            search_matches.closed,
             vec![PathBuf::from("test/1_qw"), PathBuf::from("test/6_qwqwqw"),],
         );
         assert_eq!(
@@ -1267,7 +1269,7 @@ async fn test_history_items_vs_very_good_external_match(cx: &mut gpui::TestAppCo
     finder.update(cx, |finder, _| {
             let delegate = &finder.delegate;
             assert!(
-                delegate.matches.history.is_empty(),
+                delegate.matches.history.is_empty() && delegate.matches.closed.is_empty(),
                 "History items should not math query {query}, they should be matched by name only"
             );
 
@@ -1322,7 +1324,7 @@ async fn test_nonexistent_history_items_not_shown(cx: &mut gpui::TestAppContext)
     picker.update(cx, |finder, _| {
             let history_entries = finder.delegate
                 .matches
-                .history
+                .closed
                 .iter()
                 .map(|(_, path_match)| path_match.as_ref().expect("should have a path match").0.path.to_path_buf())
                 .collect::<Vec<_>>();
@@ -1337,6 +1339,64 @@ async fn test_nonexistent_history_items_not_shown(cx: &mut gpui::TestAppContext)
         });
 }
 
+#[gpui::test]
+async fn test_reopen_recently_closed_item(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/src",
+            json!({
+                "test": {
+                    "first.rs": "// First\n// Rust\n// file",
+                    "second.rs": "// Second Rust file",
+                }
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/src".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+    open_queried_buffer("fir", 1, "first.rs", &workspace, cx).await;
+    let editor = cx.read(|cx| workspace.read(cx).active_item_as::<Editor>(cx).unwrap());
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 3)..Point::new(2, 3)])
+        })
+    });
+    cx.dispatch_action(workspace::CloseActiveItem { save_intent: None });
+    open_queried_buffer("sec", 1, "second.rs", &workspace, cx).await;
+
+    let picker = open_file_picker(&workspace, cx);
+    picker.update(cx, |finder, _| {
+        let search_matches = collect_search_matches(finder);
+        assert_eq!(
+            search_matches.history,
+            vec![PathBuf::from("/src/test/second.rs")]
+        );
+        assert_eq!(
+            search_matches.closed,
+            vec![PathBuf::from("/src/test/first.rs")]
+        );
+        assert_match_selection(finder, 1, "first.rs");
+    });
+
+    cx.dispatch_action(Confirm);
+    cx.run_until_parked();
+    cx.read(|cx| {
+        let editor = workspace.read(cx).active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(editor.read(cx).title(cx), "first.rs");
+        assert_eq!(
+            editor.read(cx).selections.newest::<Point>(cx).head(),
+            Point::new(2, 3),
+            "Reopening a closed item should restore its selections"
+        );
+    });
+}
+
 async fn open_close_queried_buffer(
     input: &str,
     expected_matches: usize,
@@ -1459,6 +1519,7 @@ fn active_file_picker(
 #[derive(Debug)]
 struct SearchEntries {
     history: Vec<PathBuf>,
+    closed: Vec<PathBuf>,
     search: Vec<PathBuf>,
 }
 
@@ -1470,6 +1531,11 @@ impl SearchEntries {
             "Should have no history matches, but got: {:?}",
             self.history
         );
+        assert!(
+            self.closed.is_empty(),
+            "Should have no closed item matches, but got: {:?}",
+            self.closed
+        );
         self.search
     }
 }
@@ -1477,24 +1543,8 @@ impl SearchEntries {
 fn collect_search_matches(picker: &Picker<FileFinderDelegate>) -> SearchEntries {
     let matches = &picker.delegate.matches;
     SearchEntries {
-        history: matches
-            .history
-            .iter()
-            .map(|(history_path, path_match)| {
-                path_match
-                    .as_ref()
-                    .map(|path_match| {
-                        Path::new(path_match.0.path_prefix.as_ref()).join(&path_match.0.path)
-                    })
-                    .unwrap_or_else(|| {
-                        history_path
-                            .absolute
-                            .as_deref()
-                            .unwrap_or_else(|| &history_path.project.path)
-                            .to_path_buf()
-                    })
-            })
-            .collect(),
+        history: history_entry_paths(&matches.history),
+        closed: history_entry_paths(&matches.closed),
         search: matches
             .search
             .iter()
@@ -1503,6 +1553,26 @@ fn collect_search_matches(picker: &Picker<FileFinderDelegate>) -> SearchEntries
     }
 }
 
+fn history_entry_paths(history: &[(FoundPath, Option<ProjectPanelOrdMatch>)]) -> Vec<PathBuf> {
+    history
+        .iter()
+        .map(|(history_path, path_match)| {
+            path_match
+                .as_ref()
+                .map(|path_match| {
+                    Path::new(path_match.0.path_prefix.as_ref()).join(&path_match.0.path)
+                })
+                .unwrap_or_else(|| {
+                    history_path
+                        .absolute
+                        .as_deref()
+                        .unwrap_or_else(|| &history_path.project.path)
+                        .to_path_buf()
+                })
+        })
+        .collect()
+}
+
 #[track_caller]
 fn assert_match_selection(
     finder: &Picker<FileFinderDelegate>,
@@ -1529,7 +1599,9 @@ fn assert_match_at_position(
         .get(match_index)
         .unwrap_or_else(|| panic!("Finder has no match for index {match_index}"));
     let match_file_name = match match_item {
-        Match::History(found_path, _) => found_path.absolute.as_deref().unwrap().file_name(),
+        Match::History(found_path, _) | Match::Closed(found_path, _) => {
+            found_path.absolute.as_deref().unwrap().file_name()
+        }
         Match::Search(path_match) => path_match.0.path.file_name(),
     }
     .unwrap()
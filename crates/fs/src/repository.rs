@@ -21,6 +21,83 @@ pub struct Branch {
     pub unix_timestamp: Option<i64>,
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitDetails {
+    pub sha: String,
+    pub parent_shas: Vec<String>,
+    pub author_name: String,
+    pub author_email: String,
+    /// Timestamp of the commit, normalized to Unix Epoch format.
+    pub commit_timestamp: i64,
+    pub message: String,
+    /// Unified diff of the commit against its first parent.
+    pub diff: String,
+}
+
+impl CommitDetails {
+    /// Returns the first line of the commit message.
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    pub fn short_sha(&self) -> &str {
+        short_sha(&self.sha)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CommitAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+impl std::fmt::Display for CommitAuthor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// A page of a file's history, as returned by [`GitRepository::file_history`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileHistoryPage {
+    /// The commits of the page that changed the file, newest first.
+    pub commits: Vec<CommitDetails>,
+    /// Whether there are older commits left to examine.
+    pub has_more: bool,
+}
+
+/// The commits that last changed the rows of a file at HEAD, as returned by
+/// [`GitRepository::blame_at_head`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileBlame {
+    pub path: RepoPath,
+    pub head_sha: String,
+    /// The contents of the file at HEAD, which are empty if the file isn't in HEAD.
+    pub head_contents: String,
+    /// The SHA of the commit that last changed each row of the file at HEAD.
+    pub row_shas: Vec<String>,
+    /// The commits that the rows are blamed on, by SHA, without their diffs.
+    pub commits: HashMap<String, CommitDetails>,
+}
+
+impl FileBlame {
+    /// Returns the row at HEAD of each row of the file's current contents, which may not
+    /// be saved yet. Rows that changed since HEAD have none.
+    pub fn head_rows(&self, contents: &str) -> Result<Vec<Option<u32>>> {
+        rows_in_original(self.head_contents.as_bytes(), contents.as_bytes())
+    }
+
+    /// Returns the commit that last changed the given row of the file at HEAD.
+    pub fn commit_for_head_row(&self, row: u32) -> Option<&CommitDetails> {
+        self.commits.get(self.row_shas.get(row as usize)?)
+    }
+}
+
+/// Abbreviates a commit SHA to the length git uses by default.
+pub fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
 pub trait GitRepository: Send {
     fn reload_index(&self);
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
@@ -53,6 +130,34 @@ pub trait GitRepository: Send {
     fn branches(&self) -> Result<Vec<Branch>>;
     fn change_branch(&self, _: &str) -> Result<()>;
     fn create_branch(&self, _: &str) -> Result<()>;
+
+    /// Returns a function that blames the file at HEAD. Blaming a large file takes a
+    /// while, so the function doesn't borrow the repository, and can run without holding
+    /// its lock.
+    fn blame_at_head(&self, path: &RepoPath) -> Box<dyn FnOnce() -> Result<FileBlame> + Send>;
+
+    /// Loads the message, metadata and diff of the commit with the given SHA.
+    fn show_commit(&self, sha: &str) -> Result<CommitDetails>;
+
+    /// Like [`GitRepository::show_commit`], but only includes the changes to the given file
+    /// in the diff.
+    fn show_commit_for_path(&self, sha: &str, path: &RepoPath) -> Result<CommitDetails>;
+
+    /// Examines up to `limit` of the commits reachable from HEAD, newest first, after
+    /// skipping the `skip` newest ones, and returns those that changed the given file.
+    /// The returned commits don't include their diffs.
+    fn file_history(&self, path: &RepoPath, skip: usize, limit: usize) -> Result<FileHistoryPage>;
+
+    /// Returns the contents of the file as of the given commit, or `None` if the file
+    /// didn't exist in that commit.
+    fn file_at_revision(&self, sha: &str, path: &RepoPath) -> Result<Option<String>>;
+
+    /// Returns the authors of the given number of most recent commits reachable from HEAD,
+    /// other than the configured user, ordered by how many of those commits they authored.
+    fn recent_authors(&self, commit_limit: usize) -> Result<Vec<CommitAuthor>>;
+
+    /// Returns the changes that are staged for the next commit, as a patch.
+    fn staged_diff(&self) -> Result<String>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -209,6 +314,201 @@ impl GitRepository for LibGitRepository {
 
         Ok(())
     }
+
+    fn blame_at_head(&self, path: &RepoPath) -> Box<dyn FnOnce() -> Result<FileBlame> + Send> {
+        let git_dir = self.path().to_path_buf();
+        let path = path.clone();
+        Box::new(move || {
+            check_path_to_repo_path_errors(&path)?;
+            let repo = LibGitRepository::open(git_dir)?;
+            blame_at_head(&repo, path)
+        })
+    }
+
+    fn show_commit(&self, sha: &str) -> Result<CommitDetails> {
+        show_commit(self, sha, None)
+    }
+
+    fn show_commit_for_path(&self, sha: &str, path: &RepoPath) -> Result<CommitDetails> {
+        check_path_to_repo_path_errors(path)?;
+        show_commit(self, sha, Some(path))
+    }
+
+    fn file_history(&self, path: &RepoPath, skip: usize, limit: usize) -> Result<FileHistoryPage> {
+        check_path_to_repo_path_errors(path)?;
+
+        let mut revwalk = self.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        let mut revwalk = revwalk.skip(skip);
+
+        let blob_id = |tree: git2::Tree| tree.get_path(path).ok().map(|entry| entry.id());
+        let mut history = Vec::new();
+        for oid in revwalk.by_ref().take(limit) {
+            let commit = self.find_commit(oid?)?;
+            let blob = blob_id(commit.tree()?);
+            let mut parent_blobs = Vec::new();
+            for parent in commit.parents() {
+                parent_blobs.push(blob_id(parent.tree()?));
+            }
+            // Like `git log`, only list merges that differ from every parent.
+            let changed = if parent_blobs.is_empty() {
+                blob.is_some()
+            } else {
+                parent_blobs.iter().all(|parent_blob| *parent_blob != blob)
+            };
+            if changed {
+                history.push(commit_details(&commit, String::new()));
+            }
+        }
+        Ok(FileHistoryPage {
+            commits: history,
+            has_more: revwalk.next().is_some(),
+        })
+    }
+
+    fn file_at_revision(&self, sha: &str, path: &RepoPath) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(path)?;
+
+        let tree = self.revparse_single(sha)?.peel_to_commit()?.tree()?;
+        let Ok(entry) = tree.get_path(path) else {
+            return Ok(None);
+        };
+        let content = self.find_blob(entry.id())?.content().to_owned();
+        Ok(Some(String::from_utf8(content)?))
+    }
+
+    fn recent_authors(&self, commit_limit: usize) -> Result<Vec<CommitAuthor>> {
+        let user_email = self.config()?.get_string("user.email").ok();
+
+        let mut revwalk = self.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        let mut authors = Vec::new();
+        for oid in revwalk.take(commit_limit) {
+            let commit = self.find_commit(oid?)?;
+            let details = commit_details(&commit, String::new());
+            if user_email.as_ref() != Some(&details.author_email) {
+                authors.push(CommitAuthor {
+                    name: details.author_name,
+                    email: details.author_email,
+                });
+            }
+        }
+        Ok(authors_by_frequency(authors))
+    }
+
+    fn staged_diff(&self) -> Result<String> {
+        // A repository without commits has no HEAD, and everything in its index is new.
+        let head_tree = self.head().ok().and_then(|head| head.peel_to_tree().ok());
+        let diff = self.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+        let mut patch = Vec::new();
+        diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin() as u8);
+            }
+            patch.extend_from_slice(line.content());
+            true
+        })?;
+        Ok(String::from_utf8_lossy(&patch).into_owned())
+    }
+}
+
+fn authors_by_frequency(authors: impl IntoIterator<Item = CommitAuthor>) -> Vec<CommitAuthor> {
+    let mut counts = HashMap::<CommitAuthor, usize>::default();
+    for author in authors {
+        *counts.entry(author).or_default() += 1;
+    }
+    let mut authors = counts.into_iter().collect::<Vec<_>>();
+    authors.sort_by(|(a, a_count), (b, b_count)| {
+        b_count.cmp(a_count).then_with(|| a.name.cmp(&b.name))
+    });
+    authors.into_iter().map(|(author, _)| author).collect()
+}
+
+fn show_commit(
+    repo: &LibGitRepository,
+    sha: &str,
+    path: Option<&RepoPath>,
+) -> Result<CommitDetails> {
+    let commit = repo.revparse_single(sha)?.peel_to_commit()?;
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+
+    let mut options = git2::DiffOptions::new();
+    if let Some(path) = path {
+        options.pathspec(&path.0).disable_pathspec_match(true);
+    }
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?;
+    let mut diff_text = String::new();
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            diff_text.push(line.origin());
+        }
+        diff_text.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+
+    Ok(commit_details(&commit, diff_text))
+}
+
+fn blame_at_head(repo: &LibGitRepository, path: RepoPath) -> Result<FileBlame> {
+    let head = repo.head()?.peel_to_commit()?;
+    let mut blame = FileBlame {
+        path,
+        head_sha: head.id().to_string(),
+        head_contents: String::new(),
+        row_shas: Vec::new(),
+        commits: HashMap::default(),
+    };
+
+    // Files that aren't in the HEAD commit have no committed rows.
+    let head_entry = match head.tree()?.get_path(&blame.path) {
+        Ok(entry) => entry,
+        Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(blame),
+        Err(error) => return Err(error.into()),
+    };
+    let head_blob = head_entry.to_object(repo)?.peel_to_blob()?;
+    blame.head_contents = String::from_utf8_lossy(head_blob.content()).into_owned();
+
+    let mut options = git2::BlameOptions::new();
+    options.newest_commit(head.id());
+    for hunk in repo.blame_file(&blame.path, Some(&mut options))?.iter() {
+        let oid = hunk.final_commit_id();
+        if oid.is_zero() {
+            continue;
+        }
+        let sha = oid.to_string();
+        if !blame.commits.contains_key(&sha) {
+            let commit = repo.find_commit(oid)?;
+            blame
+                .commits
+                .insert(sha.clone(), commit_details(&commit, String::new()));
+        }
+        // libgit2 line numbers are one-based.
+        let start_row = hunk.final_start_line().saturating_sub(1);
+        blame.row_shas.resize(start_row, String::new());
+        blame
+            .row_shas
+            .extend(std::iter::repeat(sha).take(hunk.lines_in_hunk()));
+    }
+    Ok(blame)
+}
+
+fn commit_details(commit: &git2::Commit, diff: String) -> CommitDetails {
+    let author = commit.author();
+    CommitDetails {
+        sha: commit.id().to_string(),
+        parent_shas: commit.parent_ids().map(|oid| oid.to_string()).collect(),
+        author_name: String::from_utf8_lossy(author.name_bytes()).into_owned(),
+        author_email: String::from_utf8_lossy(author.email_bytes()).into_owned(),
+        commit_timestamp: commit.time().seconds(),
+        message: String::from_utf8_lossy(commit.message_bytes()).into_owned(),
+        diff,
+    }
 }
 
 fn matches_index(repo: &LibGitRepository, path: &RepoPath, mtime: SystemTime) -> bool {
@@ -253,6 +553,18 @@ pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    pub head_sha: Option<String>,
+    /// The SHA of the commit that last changed each row of the file at HEAD, keyed by file.
+    pub blames: HashMap<RepoPath, Vec<String>>,
+    /// The contents of files at HEAD, which the rows of blames are mapped from.
+    pub head_contents: HashMap<RepoPath, String>,
+    pub commits: HashMap<String, CommitDetails>,
+    /// The SHAs of the commits that changed each file, newest first. When paging
+    /// through a file's history, only these commits count as examined.
+    pub file_histories: HashMap<RepoPath, Vec<String>>,
+    /// The contents of files at each commit, keyed by commit SHA.
+    pub revisions: HashMap<String, HashMap<RepoPath, String>>,
+    pub staged_diff: String,
 }
 
 impl FakeGitRepository {
@@ -279,7 +591,8 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn head_sha(&self) -> Option<String> {
-        None
+        let state = self.state.lock();
+        state.head_sha.clone()
     }
 
     fn staged_statuses(&self, path_prefix: &Path) -> TreeMap<RepoPath, GitFileStatus> {
@@ -317,6 +630,149 @@ impl GitRepository for FakeGitRepository {
         state.branch_name = Some(name.to_owned());
         Ok(())
     }
+
+    fn blame_at_head(&self, path: &RepoPath) -> Box<dyn FnOnce() -> Result<FileBlame> + Send> {
+        let state = self.state.clone();
+        let path = path.clone();
+        Box::new(move || {
+            let state = state.lock();
+            let row_shas = state.blames.get(&path).cloned().unwrap_or_default();
+            let commits = row_shas
+                .iter()
+                .map(|sha| {
+                    let commit = state
+                        .commits
+                        .get(sha)
+                        .cloned()
+                        .unwrap_or_else(|| CommitDetails {
+                            sha: sha.clone(),
+                            ..Default::default()
+                        });
+                    (
+                        sha.clone(),
+                        CommitDetails {
+                            diff: String::new(),
+                            ..commit
+                        },
+                    )
+                })
+                .collect();
+            Ok(FileBlame {
+                head_sha: state.head_sha.clone().unwrap_or_default(),
+                head_contents: state.head_contents.get(&path).cloned().unwrap_or_default(),
+                row_shas,
+                commits,
+                path,
+            })
+        })
+    }
+
+    fn show_commit(&self, sha: &str) -> Result<CommitDetails> {
+        let state = self.state.lock();
+        state
+            .commits
+            .get(sha)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("commit {sha} not found"))
+    }
+
+    fn show_commit_for_path(&self, sha: &str, _path: &RepoPath) -> Result<CommitDetails> {
+        self.show_commit(sha)
+    }
+
+    fn file_history(&self, path: &RepoPath, skip: usize, limit: usize) -> Result<FileHistoryPage> {
+        let state = self.state.lock();
+        let Some(shas) = state.file_histories.get(path) else {
+            return Ok(FileHistoryPage::default());
+        };
+        let commits = shas
+            .iter()
+            .skip(skip)
+            .take(limit)
+            .map(|sha| {
+                state
+                    .commits
+                    .get(sha)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("commit {sha} not found"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(FileHistoryPage {
+            commits,
+            has_more: skip + limit < shas.len(),
+        })
+    }
+
+    fn file_at_revision(&self, sha: &str, path: &RepoPath) -> Result<Option<String>> {
+        let state = self.state.lock();
+        Ok(state
+            .revisions
+            .get(sha)
+            .and_then(|files| files.get(path))
+            .cloned())
+    }
+
+    fn recent_authors(&self, commit_limit: usize) -> Result<Vec<CommitAuthor>> {
+        let state = self.state.lock();
+        let mut commits = state.commits.values().collect::<Vec<_>>();
+        commits.sort_by_key(|commit| std::cmp::Reverse(commit.commit_timestamp));
+        Ok(authors_by_frequency(
+            commits
+                .into_iter()
+                .take(commit_limit)
+                .map(|commit| CommitAuthor {
+                    name: commit.author_name.clone(),
+                    email: commit.author_email.clone(),
+                }),
+        ))
+    }
+
+    fn staged_diff(&self) -> Result<String> {
+        Ok(self.state.lock().staged_diff.clone())
+    }
+}
+
+/// Returns the zero-based row of `original` that each row of `changed` is unchanged from,
+/// or `None` for the rows that were added or edited. `changed` is expected to have LF line
+/// endings, as buffer contents do, so the line endings of `original` are normalized to match.
+fn rows_in_original(original: &[u8], changed: &[u8]) -> Result<Vec<Option<u32>>> {
+    let original = String::from_utf8_lossy(original).replace("\r\n", "\n");
+    let mut options = git2::DiffOptions::new();
+    options.context_lines(0);
+    let patch =
+        git2::Patch::from_buffers(original.as_bytes(), None, changed, None, Some(&mut options))?;
+
+    // Hunk line numbers are one-based, and the rows between hunks are offset by the lines
+    // that the hunks before them removed or added.
+    let line_count = changed.iter().filter(|byte| **byte == b'\n').count() as i64 + 1;
+    let mut rows = Vec::with_capacity(line_count as usize);
+    let mut line = 1;
+    let mut offset = 0;
+    for hunk_ix in 0..patch.num_hunks() {
+        let (hunk, _) = patch.hunk(hunk_ix)?;
+        let new_start = hunk.new_start() as i64;
+        let new_lines = hunk.new_lines() as i64;
+        // A hunk that only removes lines starts at the line before the removed ones.
+        let unchanged_end = if new_lines > 0 {
+            new_start
+        } else {
+            new_start + 1
+        };
+        while line < unchanged_end {
+            rows.push(Some((line + offset - 1) as u32));
+            line += 1;
+        }
+        for _ in 0..new_lines {
+            rows.push(None);
+            line += 1;
+        }
+        offset += hunk.old_lines() as i64 - new_lines;
+    }
+    while line <= line_count {
+        rows.push(Some((line + offset - 1) as u32));
+        line += 1;
+    }
+    Ok(rows)
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -435,3 +891,93 @@ impl<'a> MapSeekTarget<RepoPath> for RepoPathDescendants<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_in_original() {
+        let original = "a\nb\nc\nd\ne\n";
+        let row = |changed: &str, row: usize| {
+            rows_in_original(original.as_bytes(), changed.as_bytes()).unwrap()[row]
+        };
+
+        assert_eq!(row(original, 3), Some(3));
+
+        // Rows after inserted lines move down, and the inserted lines have no original.
+        let inserted = "a\nnew\nnewer\nb\nc\nd\ne\n";
+        assert_eq!(row(inserted, 0), Some(0));
+        assert_eq!(row(inserted, 1), None);
+        assert_eq!(row(inserted, 2), None);
+        assert_eq!(row(inserted, 3), Some(1));
+        assert_eq!(row(inserted, 6), Some(4));
+
+        // Rows after removed lines move up.
+        let removed = "a\nd\ne\n";
+        assert_eq!(row(removed, 0), Some(0));
+        assert_eq!(row(removed, 1), Some(3));
+        assert_eq!(row(removed, 2), Some(4));
+
+        // Edited lines have no original.
+        let edited = "a\nb\nC\nd\ne\n";
+        assert_eq!(row(edited, 2), None);
+        assert_eq!(row(edited, 3), Some(3));
+
+        // Files with CRLF line endings are compared with the LF-normalized buffer text.
+        let crlf = "a\r\nb\r\nc\r\n";
+        assert_eq!(
+            rows_in_original(crlf.as_bytes(), b"a\nb\nc\n").unwrap(),
+            [Some(0), Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            rows_in_original(crlf.as_bytes(), b"a\nB\nc\n").unwrap(),
+            [Some(0), None, Some(2), Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_blame_at_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = LibGitRepository::init(dir.path()).unwrap();
+        let commit = |message: &str, contents: &str| {
+            std::fs::write(dir.path().join("file.txt"), contents).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("Ada", "ada@example.com").unwrap();
+            let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+            let parents = parent.iter().collect::<Vec<_>>();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+            .to_string()
+        };
+        let first = commit("First", "a\nb\nc\n");
+        let second = commit("Second", "a\nB\nc\n");
+        // Uncommitted changes aren't blamed.
+        std::fs::write(dir.path().join("file.txt"), "x\n").unwrap();
+
+        let blame = repo.blame_at_head(&RepoPath::from(Path::new("file.txt")))().unwrap();
+        assert_eq!(blame.head_sha, second);
+        assert_eq!(blame.head_contents, "a\nB\nc\n");
+        assert_eq!(blame.row_shas, [first.clone(), second.clone(), first]);
+        assert_eq!(blame.commit_for_head_row(1).unwrap().summary(), "Second");
+        assert_eq!(blame.commit_for_head_row(3), None);
+
+        let head_rows = blame.head_rows("new\na\nB\nc\n").unwrap();
+        assert_eq!(head_rows, [None, Some(0), Some(1), Some(2), Some(3)]);
+
+        // Files that aren't in HEAD have no blamed rows.
+        let blame = repo.blame_at_head(&RepoPath::from(Path::new("new.txt")))().unwrap();
+        assert!(blame.row_shas.is_empty());
+        assert_eq!(blame.head_rows("a\n").unwrap()[0], None);
+    }
+}
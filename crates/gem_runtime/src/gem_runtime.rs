@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Context, Result};
+use futures::AsyncReadExt;
+use serde::Deserialize;
+use smol::process::Command;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{
+    archive,
+    audit_log::{self, AuditEvent, AuditEventKind},
+    http::HttpClient,
+};
+
+#[derive(Debug, Deserialize)]
+struct RubyGemsVersion {
+    version: String,
+}
+
+/// Installs gems with the `gem` command of the user's Ruby installation, for the
+/// language servers that are distributed as gems.
+#[async_trait::async_trait]
+pub trait GemRuntime: Send + Sync {
+    async fn gem_latest_version(&self, name: &str) -> Result<String>;
+
+    /// Installs a gem and its dependencies into a directory of their own. Their
+    /// executables are put into its `bin` directory.
+    async fn gem_install(&self, name: &str, version: &str, directory: &Path) -> Result<()>;
+}
+
+/// The path of an executable of a gem installed into the directory with
+/// [`GemRuntime::gem_install`].
+pub fn gem_executable_path(directory: &Path, executable: &str) -> PathBuf {
+    directory.join("bin").join(executable)
+}
+
+pub struct RealGemRuntime {
+    http: Arc<dyn HttpClient>,
+}
+
+impl RealGemRuntime {
+    pub fn new(http: Arc<dyn HttpClient>) -> Arc<dyn GemRuntime> {
+        Arc::new(RealGemRuntime { http })
+    }
+}
+
+#[async_trait::async_trait]
+impl GemRuntime for RealGemRuntime {
+    async fn gem_latest_version(&self, name: &str) -> Result<String> {
+        let url = format!("https://rubygems.org/api/v1/versions/{name}/latest.json");
+        let mut response = self
+            .http
+            .get(&url, Default::default(), true)
+            .await
+            .with_context(|| format!("error fetching the latest version of the gem {name}"))?;
+        let mut body = Vec::new();
+        response.body_mut().read_to_end(&mut body).await?;
+        let latest = serde_json::from_slice::<RubyGemsVersion>(&body)?;
+        Ok(latest.version)
+    }
+
+    async fn gem_install(&self, name: &str, version: &str, directory: &Path) -> Result<()> {
+        archive::check_unverified_install(&format!("the gem {name} {version}")).await?;
+        audit_log::record(
+            AuditEvent::new(
+                AuditEventKind::Command,
+                "gem",
+                format!("gem install {name} --version {version}"),
+            )
+            .details(format!(
+                "gem install {name} --version {version} (into {})",
+                directory.display()
+            )),
+        );
+
+        let output = Command::new("gem")
+            .args(["install", name, "--version", version, "--no-document"])
+            .arg("--install-dir")
+            .arg(directory)
+            .arg("--bindir")
+            .arg(directory.join("bin"))
+            .output()
+            .await
+            .context("failed to run `gem install`, is Ruby installed?")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "failed to install the gem {name} {version}:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct FakeGemRuntime;
+
+impl FakeGemRuntime {
+    pub fn new() -> Arc<dyn GemRuntime> {
+        Arc::new(Self)
+    }
+}
+
+#[async_trait::async_trait]
+impl GemRuntime for FakeGemRuntime {
+    async fn gem_latest_version(&self, name: &str) -> Result<String> {
+        unreachable!("Should not query gem '{name}' for latest version")
+    }
+
+    async fn gem_install(&self, name: &str, version: &str, _: &Path) -> Result<()> {
+        unreachable!("Should not install gem {name} {version}")
+    }
+}
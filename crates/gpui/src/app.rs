@@ -16,12 +16,13 @@ use time::UtcOffset;
 
 use crate::WindowAppearance;
 use crate::{
-    current_platform, image_cache::ImageCache, init_app_menus, Action, ActionRegistry, Any,
-    AnyView, AnyWindowHandle, AppMetadata, AssetSource, BackgroundExecutor, ClipboardItem, Context,
-    DispatchPhase, Entity, EventEmitter, ForegroundExecutor, Global, KeyBinding, Keymap, Keystroke,
-    LayoutId, Menu, PathPromptOptions, Pixels, Platform, PlatformDisplay, Point, Render,
-    SharedString, SubscriberSet, Subscription, SvgRenderer, Task, TextStyle, TextStyleRefinement,
-    TextSystem, View, ViewContext, Window, WindowContext, WindowHandle, WindowId,
+    current_platform, headless_platform, image_cache::ImageCache, init_app_menus, Action,
+    ActionRegistry, Any, AnyView, AnyWindowHandle, AppMetadata, AssetSource, BackgroundExecutor,
+    ClipboardItem, Context, DispatchPhase, Entity, EventEmitter, ForegroundExecutor, Global,
+    KeyBinding, Keymap, Keystroke, LayoutId, Menu, PathPromptOptions, Pixels, Platform,
+    PlatformDisplay, Point, Render, SharedString, SubscriberSet, Subscription, SvgRenderer, Task,
+    TextStyle, TextStyleRefinement, TextSystem, View, ViewContext, Window, WindowContext,
+    WindowHandle, WindowId,
 };
 use anyhow::{anyhow, Result};
 use collections::{FxHashMap, FxHashSet, VecDeque};
@@ -118,6 +119,16 @@ impl App {
         ))
     }
 
+    /// Builds an app that can't open windows and never connects to a display, such as
+    /// for running on a server.
+    pub fn headless() -> Self {
+        Self(AppContext::new(
+            headless_platform(),
+            Arc::new(()),
+            http::client(),
+        ))
+    }
+
     /// Assign
     pub fn with_assets(self, asset_source: impl AssetSource) -> Self {
         let mut context_lock = self.0.borrow_mut();
@@ -597,6 +608,11 @@ impl AppContext {
         self.platform.reveal_path(path)
     }
 
+    /// Shows a notification through the operating system's notification center.
+    pub fn show_notification(&self, title: &str, body: &str) {
+        self.platform.show_notification(title, body)
+    }
+
     /// Returns whether the user has configured scrollbars to auto-hide at the platform level.
     pub fn should_auto_hide_scrollbars(&self) -> bool {
         self.platform.should_auto_hide_scrollbars()
@@ -1132,6 +1148,13 @@ impl AppContext {
         self.platform.set_menus(menus, &self.keymap.borrow());
     }
 
+    /// Shows the given menu in the system's menu bar, titled with the menu's name, even
+    /// when the application has no windows. Passing `None` removes it.
+    pub fn set_menu_bar_extra(&mut self, menu: Option<Menu>) {
+        self.platform
+            .set_menu_bar_extra(menu, &self.keymap.borrow());
+    }
+
     /// Dispatch an action to the currently active window or global action handler
     /// See [action::Action] for more information on how actions work
     pub fn dispatch_action(&mut self, action: &dyn Action) {
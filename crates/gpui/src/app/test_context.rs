@@ -237,6 +237,15 @@ impl TestAppContext {
         self.test_platform.has_pending_prompt()
     }
 
+    /// Makes the platform keep the credentials that are written, like a keychain, rather
+    /// than forgetting them.
+    pub fn enable_keychain(&self) {
+        self.test_platform
+            .keychain
+            .borrow_mut()
+            .get_or_insert_with(Default::default);
+    }
+
     /// All the urls that have been opened with cx.open_url() during this test.
     pub fn opened_url(&self) -> Option<String> {
         self.test_platform.opened_url.borrow().clone()
@@ -2240,7 +2240,7 @@ where
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Debug)]
 struct ScrollHandleState {
     offset: Rc<RefCell<Point<Pixels>>>,
     bounds: Bounds<Pixels>,
@@ -2252,7 +2252,7 @@ struct ScrollHandleState {
 /// A handle to the scrollable aspects of an element.
 /// Used for accessing scroll state, like the current scroll offset,
 /// and for mutating the scroll state, like scrolling to a specific child.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct ScrollHandle(Rc<RefCell<ScrollHandleState>>);
 
 impl Default for ScrollHandle {
@@ -2272,6 +2272,12 @@ impl ScrollHandle {
         *self.0.borrow().offset.borrow()
     }
 
+    /// Set the scroll offset. It's clamped to the scrollable area the next time the
+    /// element is painted.
+    pub fn set_offset(&self, offset: Point<Pixels>) {
+        *self.0.borrow().offset.borrow_mut() = offset;
+    }
+
     /// Get the top child that's scrolled into view.
     pub fn top_item(&self) -> usize {
         let state = self.0.borrow();
@@ -2,6 +2,7 @@
 #![cfg_attr(target_os = "linux", allow(dead_code))]
 
 mod app_menu;
+mod headless;
 mod keystroke;
 #[cfg(target_os = "linux")]
 mod linux;
@@ -37,6 +38,7 @@ use std::{
 use uuid::Uuid;
 
 pub use app_menu::*;
+pub(crate) use headless::*;
 pub use keystroke::*;
 #[cfg(target_os = "linux")]
 pub(crate) use linux::*;
@@ -56,6 +58,11 @@ pub(crate) fn current_platform() -> Rc<dyn Platform> {
     Rc::new(LinuxPlatform::new())
 }
 
+/// A platform that never connects to a display, for running without any windows.
+pub(crate) fn headless_platform() -> Rc<dyn Platform> {
+    Rc::new(HeadlessPlatform::new())
+}
+
 pub(crate) trait Platform: 'static {
     fn background_executor(&self) -> BackgroundExecutor;
     fn foreground_executor(&self) -> ForegroundExecutor;
@@ -89,6 +96,7 @@ pub(crate) trait Platform: 'static {
     ) -> oneshot::Receiver<Option<Vec<PathBuf>>>;
     fn prompt_for_new_path(&self, directory: &Path) -> oneshot::Receiver<Option<PathBuf>>;
     fn reveal_path(&self, path: &Path);
+    fn show_notification(&self, title: &str, body: &str);
 
     fn on_become_active(&self, callback: Box<dyn FnMut()>);
     fn on_resign_active(&self, callback: Box<dyn FnMut()>);
@@ -97,6 +105,7 @@ pub(crate) trait Platform: 'static {
     fn on_event(&self, callback: Box<dyn FnMut(PlatformInput) -> bool>);
 
     fn set_menus(&self, menus: Vec<Menu>, keymap: &Keymap);
+    fn set_menu_bar_extra(&self, menu: Option<Menu>, keymap: &Keymap);
     fn on_app_menu_action(&self, callback: Box<dyn FnMut(&dyn Action)>);
     fn on_will_open_app_menu(&self, callback: Box<dyn FnMut()>);
     fn on_validate_app_menu_command(&self, callback: Box<dyn FnMut(&dyn Action) -> bool>);
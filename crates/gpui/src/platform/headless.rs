@@ -0,0 +1,13 @@
+mod dispatcher;
+mod display;
+mod platform;
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod text_system;
+mod window;
+
+pub(crate) use dispatcher::*;
+pub(crate) use display::*;
+pub(crate) use platform::*;
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub(crate) use text_system::*;
+pub(crate) use window::*;
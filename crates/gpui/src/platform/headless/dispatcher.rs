@@ -0,0 +1,114 @@
+use crate::{PlatformDispatcher, TaskLabel};
+use async_task::Runnable;
+use parking::{Parker, Unparker};
+use parking_lot::Mutex;
+use std::{
+    panic, thread,
+    time::{Duration, Instant},
+};
+
+/// Runs tasks on threads of its own, and main thread tasks in the headless platform's
+/// run loop, without relying on any windowing system to wake the main thread.
+pub(crate) struct HeadlessDispatcher {
+    parker: Mutex<Parker>,
+    main_sender: flume::Sender<Runnable>,
+    background_sender: flume::Sender<Runnable>,
+    timer_sender: flume::Sender<(Instant, Runnable)>,
+    _background_threads: Vec<thread::JoinHandle<()>>,
+    _timer_thread: thread::JoinHandle<()>,
+    main_thread_id: thread::ThreadId,
+}
+
+impl HeadlessDispatcher {
+    pub fn new(main_sender: flume::Sender<Runnable>) -> Self {
+        let (background_sender, background_receiver) = flume::unbounded::<Runnable>();
+        let background_threads = (0..num_cpus::get())
+            .map(|_| {
+                let background_receiver = background_receiver.clone();
+                thread::spawn(move || {
+                    for runnable in background_receiver {
+                        let _ignore_panic = panic::catch_unwind(|| runnable.run());
+                    }
+                })
+            })
+            .collect();
+
+        let (timer_sender, timer_receiver) = flume::unbounded::<(Instant, Runnable)>();
+        let timer_thread = thread::spawn({
+            let background_sender = background_sender.clone();
+            move || {
+                // Sorted by deadline, latest first.
+                let mut timers = Vec::<(Instant, Runnable)>::new();
+                loop {
+                    let timer = match timers.last() {
+                        Some((deadline, _)) => timer_receiver.recv_deadline(*deadline),
+                        None => timer_receiver
+                            .recv()
+                            .map_err(|_| flume::RecvTimeoutError::Disconnected),
+                    };
+                    match timer {
+                        Ok(timer) => {
+                            timers.push(timer);
+                            timers.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+                        }
+                        Err(flume::RecvTimeoutError::Timeout) => {}
+                        Err(flume::RecvTimeoutError::Disconnected) => return,
+                    }
+
+                    let now = Instant::now();
+                    while timers
+                        .last()
+                        .map_or(false, |(deadline, _)| *deadline <= now)
+                    {
+                        let (_, runnable) = timers.pop().unwrap();
+                        if background_sender.send(runnable).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            parker: Mutex::new(Parker::new()),
+            main_sender,
+            background_sender,
+            timer_sender,
+            _background_threads: background_threads,
+            _timer_thread: timer_thread,
+            main_thread_id: thread::current().id(),
+        }
+    }
+}
+
+impl PlatformDispatcher for HeadlessDispatcher {
+    fn is_main_thread(&self) -> bool {
+        thread::current().id() == self.main_thread_id
+    }
+
+    fn dispatch(&self, runnable: Runnable, _: Option<TaskLabel>) {
+        self.background_sender.send(runnable).ok();
+    }
+
+    fn dispatch_on_main_thread(&self, runnable: Runnable) {
+        self.main_sender.send(runnable).ok();
+    }
+
+    fn dispatch_after(&self, duration: Duration, runnable: Runnable) {
+        self.timer_sender
+            .send((Instant::now() + duration, runnable))
+            .ok();
+    }
+
+    fn tick(&self, _background_only: bool) -> bool {
+        false
+    }
+
+    fn park(&self) {
+        self.parker.lock().park()
+    }
+
+    fn unparker(&self) -> Unparker {
+        self.parker.lock().unparker()
+    }
+}
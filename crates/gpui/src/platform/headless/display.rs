@@ -0,0 +1,36 @@
+use crate::{Bounds, DisplayId, GlobalPixels, PlatformDisplay, Point};
+use anyhow::Result;
+use uuid::Uuid;
+
+/// The display that a headless app's windows are placed on, which doesn't exist.
+#[derive(Debug)]
+pub(crate) struct HeadlessDisplay {
+    bounds: Bounds<GlobalPixels>,
+}
+
+impl HeadlessDisplay {
+    pub(crate) const ID: DisplayId = DisplayId(0);
+
+    pub(crate) fn new() -> Self {
+        Self {
+            bounds: Bounds::from_corners(
+                Point::default(),
+                Point::new(GlobalPixels(1920.), GlobalPixels(1080.)),
+            ),
+        }
+    }
+}
+
+impl PlatformDisplay for HeadlessDisplay {
+    fn id(&self) -> DisplayId {
+        Self::ID
+    }
+
+    fn uuid(&self) -> Result<Uuid> {
+        Ok(Uuid::nil())
+    }
+
+    fn bounds(&self) -> Bounds<GlobalPixels> {
+        self.bounds
+    }
+}
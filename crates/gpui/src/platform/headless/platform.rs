@@ -0,0 +1,256 @@
+use crate::{
+    Action, AnyWindowHandle, BackgroundExecutor, ClipboardItem, CursorStyle, DisplayId,
+    ForegroundExecutor, HeadlessDispatcher, HeadlessDisplay, HeadlessWindow, Keymap, Menu,
+    PathPromptOptions, Platform, PlatformDisplay, PlatformInput, PlatformTextSystem,
+    PlatformWindow, SemanticVersion, Task, WindowAppearance, WindowOptions,
+};
+use anyhow::{anyhow, Result};
+use async_task::Runnable;
+use collections::HashMap;
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+use std::{
+    env,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+use time::UtcOffset;
+
+/// A platform that never connects to a display, for running Zed as a server. Windows are
+/// opened offscreen, and whatever it can't do, such as opening URLs or prompting, is logged.
+pub(crate) struct HeadlessPlatform {
+    background_executor: BackgroundExecutor,
+    foreground_executor: ForegroundExecutor,
+    main_receiver: flume::Receiver<Runnable>,
+    text_system: Arc<dyn PlatformTextSystem>,
+    display: Rc<HeadlessDisplay>,
+    state: Mutex<HeadlessPlatformState>,
+}
+
+struct HeadlessPlatformState {
+    quit_requested: bool,
+    quit: Option<Box<dyn FnMut()>>,
+    clipboard: Option<ClipboardItem>,
+    /// Credentials only last as long as the process, as there may be no keychain.
+    credentials: HashMap<String, (String, Vec<u8>)>,
+}
+
+impl HeadlessPlatform {
+    pub(crate) fn new() -> Self {
+        let (main_sender, main_receiver) = flume::unbounded::<Runnable>();
+        let dispatcher = Arc::new(HeadlessDispatcher::new(main_sender));
+
+        #[cfg(target_os = "linux")]
+        let text_system = Arc::new(crate::LinuxTextSystem::new());
+        #[cfg(target_os = "macos")]
+        let text_system = Arc::new(crate::MacTextSystem::new());
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        let text_system = Arc::new(crate::HeadlessTextSystem);
+
+        Self {
+            background_executor: BackgroundExecutor::new(dispatcher.clone()),
+            foreground_executor: ForegroundExecutor::new(dispatcher),
+            main_receiver,
+            text_system,
+            display: Rc::new(HeadlessDisplay::new()),
+            state: Mutex::new(HeadlessPlatformState {
+                quit_requested: false,
+                quit: None,
+                clipboard: None,
+                credentials: HashMap::default(),
+            }),
+        }
+    }
+}
+
+impl Platform for HeadlessPlatform {
+    fn background_executor(&self) -> BackgroundExecutor {
+        self.background_executor.clone()
+    }
+
+    fn foreground_executor(&self) -> ForegroundExecutor {
+        self.foreground_executor.clone()
+    }
+
+    fn text_system(&self) -> Arc<dyn PlatformTextSystem> {
+        self.text_system.clone()
+    }
+
+    fn run(&self, on_finish_launching: Box<dyn FnOnce()>) {
+        on_finish_launching();
+        // Quitting is requested from the main thread, so it's seen after the task that
+        // requested it has run.
+        while !self.state.lock().quit_requested {
+            let Ok(runnable) = self.main_receiver.recv() else {
+                break;
+            };
+            runnable.run();
+        }
+
+        let quit = self.state.lock().quit.take();
+        if let Some(mut quit) = quit {
+            quit();
+        }
+    }
+
+    fn quit(&self) {
+        self.state.lock().quit_requested = true;
+    }
+
+    fn restart(&self) {}
+
+    fn activate(&self, _ignoring_other_apps: bool) {}
+
+    fn hide(&self) {}
+
+    fn hide_other_apps(&self) {}
+
+    fn unhide_other_apps(&self) {}
+
+    fn displays(&self) -> Vec<Rc<dyn PlatformDisplay>> {
+        vec![self.display.clone()]
+    }
+
+    fn display(&self, id: DisplayId) -> Option<Rc<dyn PlatformDisplay>> {
+        (id == HeadlessDisplay::ID).then(|| self.display.clone() as Rc<dyn PlatformDisplay>)
+    }
+
+    fn active_window(&self) -> Option<AnyWindowHandle> {
+        None
+    }
+
+    fn open_window(
+        &self,
+        handle: AnyWindowHandle,
+        options: WindowOptions,
+    ) -> Box<dyn PlatformWindow> {
+        Box::new(HeadlessWindow::new(handle, options, self.display.clone()))
+    }
+
+    fn window_appearance(&self) -> WindowAppearance {
+        WindowAppearance::Light
+    }
+
+    fn open_url(&self, url: &str) {
+        // Signing in opens a URL, which has to be opened elsewhere.
+        eprintln!("Open this URL in a browser to continue: {url}");
+    }
+
+    fn on_open_urls(&self, _callback: Box<dyn FnMut(Vec<String>)>) {}
+
+    fn prompt_for_paths(
+        &self,
+        _options: PathPromptOptions,
+    ) -> oneshot::Receiver<Option<Vec<PathBuf>>> {
+        let (done_tx, done_rx) = oneshot::channel();
+        done_tx.send(None).ok();
+        done_rx
+    }
+
+    fn prompt_for_new_path(&self, _directory: &Path) -> oneshot::Receiver<Option<PathBuf>> {
+        let (done_tx, done_rx) = oneshot::channel();
+        done_tx.send(None).ok();
+        done_rx
+    }
+
+    fn reveal_path(&self, _path: &Path) {}
+
+    fn show_notification(&self, title: &str, body: &str) {
+        log::info!("{title}: {body}");
+    }
+
+    fn on_become_active(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_resign_active(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_quit(&self, callback: Box<dyn FnMut()>) {
+        self.state.lock().quit = Some(callback);
+    }
+
+    fn on_reopen(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_event(&self, _callback: Box<dyn FnMut(PlatformInput) -> bool>) {}
+
+    fn set_menus(&self, _menus: Vec<Menu>, _keymap: &Keymap) {}
+
+    fn set_menu_bar_extra(&self, _menu: Option<Menu>, _keymap: &Keymap) {}
+
+    fn on_app_menu_action(&self, _callback: Box<dyn FnMut(&dyn Action)>) {}
+
+    fn on_will_open_app_menu(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_validate_app_menu_command(&self, _callback: Box<dyn FnMut(&dyn Action) -> bool>) {}
+
+    fn os_name(&self) -> &'static str {
+        if cfg!(target_os = "macos") {
+            "macOS"
+        } else {
+            "Linux"
+        }
+    }
+
+    fn os_version(&self) -> Result<SemanticVersion> {
+        Err(anyhow!("the OS version isn't known to a headless app"))
+    }
+
+    fn app_version(&self) -> Result<SemanticVersion> {
+        Err(anyhow!("the app version isn't known to a headless app"))
+    }
+
+    fn app_path(&self) -> Result<PathBuf> {
+        Ok(env::current_exe()?)
+    }
+
+    fn local_timezone(&self) -> UtcOffset {
+        UtcOffset::UTC
+    }
+
+    fn double_click_interval(&self) -> Duration {
+        Duration::from_millis(400)
+    }
+
+    fn path_for_auxiliary_executable(&self, name: &str) -> Result<PathBuf> {
+        let path = env::current_exe()?
+            .parent()
+            .ok_or_else(|| anyhow!("executable has no parent directory"))?
+            .join(name);
+        if path.exists() {
+            Ok(path)
+        } else {
+            Err(anyhow!("auxiliary executable not found: {:?}", path))
+        }
+    }
+
+    fn set_cursor_style(&self, _style: CursorStyle) {}
+
+    fn should_auto_hide_scrollbars(&self) -> bool {
+        false
+    }
+
+    fn write_to_clipboard(&self, item: ClipboardItem) {
+        self.state.lock().clipboard = Some(item);
+    }
+
+    fn read_from_clipboard(&self) -> Option<ClipboardItem> {
+        self.state.lock().clipboard.clone()
+    }
+
+    fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
+        self.state
+            .lock()
+            .credentials
+            .insert(url.to_string(), (username.to_string(), password.to_vec()));
+        Task::ready(Ok(()))
+    }
+
+    fn read_credentials(&self, url: &str) -> Task<Result<Option<(String, Vec<u8>)>>> {
+        Task::ready(Ok(self.state.lock().credentials.get(url).cloned()))
+    }
+
+    fn delete_credentials(&self, url: &str) -> Task<Result<()>> {
+        self.state.lock().credentials.remove(url);
+        Task::ready(Ok(()))
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    px, Bounds, DevicePixels, Font, FontId, FontMetrics, FontRun, GlyphId, LineLayout, Pixels,
+    PlatformTextSystem, Point, RenderGlyphParams, ShapedGlyph, ShapedRun, Size,
+};
+use anyhow::Result;
+use std::borrow::Cow;
+
+const UNITS_PER_EM: u32 = 1000;
+/// Every character is laid out as if the font were monospaced, half an em wide.
+const ADVANCE: f32 = 500.;
+
+/// A text system for headless apps on targets without a native one. There are no fonts, so
+/// text is laid out with made-up, monospaced metrics, and nothing is rasterized.
+pub(crate) struct HeadlessTextSystem;
+
+impl PlatformTextSystem for HeadlessTextSystem {
+    fn add_fonts(&self, _fonts: Vec<Cow<'static, [u8]>>) -> Result<()> {
+        Ok(())
+    }
+
+    fn all_font_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn all_font_families(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn font_id(&self, _descriptor: &Font) -> Result<FontId> {
+        Ok(FontId(0))
+    }
+
+    fn font_metrics(&self, _font_id: FontId) -> FontMetrics {
+        FontMetrics {
+            units_per_em: UNITS_PER_EM,
+            ascent: 800.,
+            descent: -200.,
+            line_gap: 0.,
+            underline_position: -100.,
+            underline_thickness: 50.,
+            cap_height: 700.,
+            x_height: 500.,
+            bounding_box: Bounds {
+                origin: Point::new(0., -200.),
+                size: Size {
+                    width: ADVANCE,
+                    height: UNITS_PER_EM as f32,
+                },
+            },
+        }
+    }
+
+    fn typographic_bounds(&self, font_id: FontId, _glyph_id: GlyphId) -> Result<Bounds<f32>> {
+        Ok(self.font_metrics(font_id).bounding_box)
+    }
+
+    fn advance(&self, _font_id: FontId, _glyph_id: GlyphId) -> Result<Size<f32>> {
+        Ok(Size {
+            width: ADVANCE,
+            height: 0.,
+        })
+    }
+
+    fn glyph_for_char(&self, _font_id: FontId, ch: char) -> Option<GlyphId> {
+        Some(GlyphId(ch as u32))
+    }
+
+    fn glyph_raster_bounds(&self, _params: &RenderGlyphParams) -> Result<Bounds<DevicePixels>> {
+        Ok(Bounds::default())
+    }
+
+    fn rasterize_glyph(
+        &self,
+        _params: &RenderGlyphParams,
+        raster_bounds: Bounds<DevicePixels>,
+    ) -> Result<(Size<DevicePixels>, Vec<u8>)> {
+        Ok((raster_bounds.size, Vec::new()))
+    }
+
+    fn layout_line(&self, text: &str, font_size: Pixels, runs: &[FontRun]) -> LineLayout {
+        let advance = font_size * (ADVANCE / UNITS_PER_EM as f32);
+        let mut chars = text.char_indices().peekable();
+        let mut x = px(0.);
+        let mut run_end = 0;
+        let mut shaped_runs = Vec::with_capacity(runs.len());
+        for run in runs {
+            run_end += run.len;
+            let mut glyphs = smallvec::SmallVec::new();
+            while let Some((index, ch)) = chars.next_if(|(index, _)| *index < run_end) {
+                glyphs.push(ShapedGlyph {
+                    id: GlyphId(ch as u32),
+                    position: Point::new(x, px(0.)),
+                    index,
+                    is_emoji: false,
+                });
+                x += advance;
+            }
+            shaped_runs.push(ShapedRun {
+                font_id: run.font_id,
+                glyphs,
+            });
+        }
+        LineLayout {
+            font_size,
+            width: x,
+            ascent: font_size * 0.8,
+            descent: font_size * 0.2,
+            runs: shaped_runs,
+            len: text.len(),
+        }
+    }
+
+    fn wrap_line(
+        &self,
+        text: &str,
+        _font_id: FontId,
+        font_size: Pixels,
+        width: Pixels,
+    ) -> Vec<usize> {
+        let advance = font_size * (ADVANCE / UNITS_PER_EM as f32);
+        let chars_per_line = ((width / advance) as usize).max(1);
+        text.char_indices()
+            .map(|(index, _)| index)
+            .skip(chars_per_line)
+            .step_by(chars_per_line)
+            .collect()
+    }
+}
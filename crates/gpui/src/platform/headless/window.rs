@@ -0,0 +1,206 @@
+use crate::{
+    px, AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTextureKind, AtlasTile, Bounds,
+    DevicePixels, Modifiers, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
+    PlatformInputHandler, PlatformWindow, Point, PromptLevel, Scene, Size, TileId,
+    WindowAppearance, WindowBounds, WindowOptions,
+};
+use collections::HashMap;
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+use raw_window_handle as rwh;
+use std::{any::Any, borrow::Cow, rc::Rc, sync::Arc};
+
+/// A window that's never shown, so that code that opens windows, such as restoring a
+/// workspace or showing settings, keeps working in a headless app. Its contents are laid out
+/// but never drawn, and it receives no input.
+pub(crate) struct HeadlessWindow {
+    bounds: WindowBounds,
+    display: Rc<dyn PlatformDisplay>,
+    input_handler: Option<PlatformInputHandler>,
+    sprite_atlas: Arc<HeadlessAtlas>,
+}
+
+impl HeadlessWindow {
+    pub(crate) fn new(
+        handle: AnyWindowHandle,
+        options: WindowOptions,
+        display: Rc<dyn PlatformDisplay>,
+    ) -> Self {
+        log::info!("opening window {:?} offscreen", handle.window_id());
+        let bounds = match options.bounds {
+            WindowBounds::Fixed(bounds) => WindowBounds::Fixed(bounds),
+            WindowBounds::Maximized | WindowBounds::Fullscreen => {
+                WindowBounds::Fixed(display.bounds())
+            }
+        };
+        Self {
+            bounds,
+            display,
+            input_handler: None,
+            sprite_atlas: Arc::new(HeadlessAtlas::default()),
+        }
+    }
+}
+
+impl rwh::HasWindowHandle for HeadlessWindow {
+    fn window_handle(&self) -> Result<rwh::WindowHandle<'_>, rwh::HandleError> {
+        Err(rwh::HandleError::Unavailable)
+    }
+}
+
+impl rwh::HasDisplayHandle for HeadlessWindow {
+    fn display_handle(&self) -> Result<rwh::DisplayHandle<'_>, rwh::HandleError> {
+        Err(rwh::HandleError::Unavailable)
+    }
+}
+
+impl PlatformWindow for HeadlessWindow {
+    fn bounds(&self) -> WindowBounds {
+        self.bounds
+    }
+
+    fn content_size(&self) -> Size<Pixels> {
+        let WindowBounds::Fixed(bounds) = self.bounds else {
+            unreachable!("headless windows always have fixed bounds")
+        };
+        Size {
+            width: px(bounds.size.width.0),
+            height: px(bounds.size.height.0),
+        }
+    }
+
+    fn scale_factor(&self) -> f32 {
+        1.0
+    }
+
+    fn titlebar_height(&self) -> Pixels {
+        px(0.)
+    }
+
+    fn appearance(&self) -> WindowAppearance {
+        WindowAppearance::Light
+    }
+
+    fn display(&self) -> Rc<dyn PlatformDisplay> {
+        self.display.clone()
+    }
+
+    fn mouse_position(&self) -> Point<Pixels> {
+        Point::default()
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        Modifiers::default()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_input_handler(&mut self, input_handler: PlatformInputHandler) {
+        self.input_handler = Some(input_handler);
+    }
+
+    fn take_input_handler(&mut self) -> Option<PlatformInputHandler> {
+        self.input_handler.take()
+    }
+
+    /// Logs the prompt and leaves it unanswered, as nobody can answer it. Picking an answer
+    /// instead could, for example, discard unsaved changes.
+    fn prompt(
+        &self,
+        _level: PromptLevel,
+        msg: &str,
+        detail: Option<&str>,
+        _answers: &[&str],
+    ) -> oneshot::Receiver<usize> {
+        log::warn!(
+            "can't prompt in a headless app: {msg} {}",
+            detail.unwrap_or("")
+        );
+        let (_, done_rx) = oneshot::channel();
+        done_rx
+    }
+
+    fn activate(&self) {}
+
+    fn set_title(&mut self, _title: &str) {}
+
+    fn set_edited(&mut self, _edited: bool) {}
+
+    fn show_character_palette(&self) {}
+
+    fn minimize(&self) {}
+
+    fn zoom(&self) {}
+
+    fn toggle_full_screen(&self) {}
+
+    fn on_request_frame(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_input(&self, _callback: Box<dyn FnMut(PlatformInput) -> bool>) {}
+
+    fn on_active_status_change(&self, _callback: Box<dyn FnMut(bool)>) {}
+
+    fn on_resize(&self, _callback: Box<dyn FnMut(Size<Pixels>, f32)>) {}
+
+    fn on_fullscreen(&self, _callback: Box<dyn FnMut(bool)>) {}
+
+    fn on_moved(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_should_close(&self, _callback: Box<dyn FnMut() -> bool>) {}
+
+    fn on_close(&self, _callback: Box<dyn FnOnce()>) {}
+
+    fn on_appearance_changed(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn is_topmost_for_position(&self, _position: Point<Pixels>) -> bool {
+        true
+    }
+
+    fn draw(&self, _scene: &Scene) {}
+
+    fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas> {
+        self.sprite_atlas.clone()
+    }
+}
+
+/// An atlas that hands out tiles without storing their contents, as nothing is drawn.
+#[derive(Default)]
+struct HeadlessAtlas(Mutex<HeadlessAtlasState>);
+
+#[derive(Default)]
+struct HeadlessAtlasState {
+    next_id: u32,
+    tiles: HashMap<AtlasKey, AtlasTile>,
+}
+
+impl PlatformAtlas for HeadlessAtlas {
+    fn get_or_insert_with<'a>(
+        &self,
+        key: &AtlasKey,
+        build: &mut dyn FnMut() -> anyhow::Result<(Size<DevicePixels>, Cow<'a, [u8]>)>,
+    ) -> anyhow::Result<AtlasTile> {
+        if let Some(tile) = self.0.lock().tiles.get(key) {
+            return Ok(tile.clone());
+        }
+
+        let (size, _) = build()?;
+        let mut state = self.0.lock();
+        state.next_id += 1;
+        let tile = AtlasTile {
+            texture_id: AtlasTextureId {
+                index: 0,
+                kind: AtlasTextureKind::Path,
+            },
+            tile_id: TileId(state.next_id),
+            padding: 0,
+            bounds: Bounds {
+                origin: Point::default(),
+                size,
+            },
+        };
+        state.tiles.insert(key.clone(), tile.clone());
+        Ok(tile)
+    }
+}
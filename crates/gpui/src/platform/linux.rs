@@ -3,14 +3,18 @@ mod blade_belt;
 mod blade_renderer;
 mod dispatcher;
 mod display;
+mod keyboard;
 mod platform;
+mod status_item;
 mod text_system;
 mod window;
 
 pub(crate) use blade_atlas::*;
 pub(crate) use dispatcher::*;
 pub(crate) use display::*;
+pub(crate) use keyboard::*;
 pub(crate) use platform::*;
+pub(crate) use status_item::*;
 pub(crate) use text_system::*;
 pub(crate) use window::*;
 
@@ -0,0 +1,152 @@
+use crate::{Keystroke, Modifiers};
+use xcb::x;
+
+const NO_SYMBOL: x::Keysym = 0;
+
+/// The keysyms that each keycode produces, as reported by the X server.
+pub(crate) struct KeyboardMapping {
+    min_keycode: x::Keycode,
+    keysyms_per_keycode: usize,
+    keysyms: Vec<x::Keysym>,
+}
+
+impl KeyboardMapping {
+    pub(crate) fn query(xcb_connection: &xcb::Connection) -> xcb::Result<Self> {
+        let setup = xcb_connection.get_setup();
+        let min_keycode = setup.min_keycode();
+        let cookie = xcb_connection.send_request(&x::GetKeyboardMapping {
+            first_keycode: min_keycode,
+            count: setup.max_keycode() - min_keycode + 1,
+        });
+        let reply = xcb_connection.wait_for_reply(cookie)?;
+        Ok(Self {
+            min_keycode,
+            keysyms_per_keycode: reply.keysyms_per_keycode() as usize,
+            keysyms: reply.keysyms().to_vec(),
+        })
+    }
+
+    /// Returns the keystroke for a key event's keycode and modifier state.
+    pub(crate) fn keystroke(&self, keycode: x::Keycode, state: x::KeyButMask) -> Keystroke {
+        let modifiers = modifiers_from_state(state);
+        let unshifted = self.keysym(keycode, 0);
+        let shifted = match self.keysym(keycode, 1) {
+            NO_SYMBOL => unshifted,
+            keysym => keysym,
+        };
+        let key = key_name(unshifted)
+            .or_else(|| keysym_char(unshifted).map(|char| char.to_lowercase().to_string()))
+            .unwrap_or_default();
+        // Characters are typed unless a modifier turns the key into a shortcut.
+        let ime_key = if modifiers.control || modifiers.command {
+            None
+        } else {
+            let keysym = if modifiers.shift { shifted } else { unshifted };
+            keysym_char(keysym).map(|char| char.to_string())
+        };
+        Keystroke {
+            modifiers,
+            key,
+            ime_key,
+        }
+    }
+
+    fn keysym(&self, keycode: x::Keycode, column: usize) -> x::Keysym {
+        if column >= self.keysyms_per_keycode || keycode < self.min_keycode {
+            return NO_SYMBOL;
+        }
+        let ix = (keycode - self.min_keycode) as usize * self.keysyms_per_keycode + column;
+        self.keysyms.get(ix).copied().unwrap_or(NO_SYMBOL)
+    }
+}
+
+pub(crate) fn modifiers_from_state(state: x::KeyButMask) -> Modifiers {
+    Modifiers {
+        control: state.contains(x::KeyButMask::CONTROL),
+        alt: state.contains(x::KeyButMask::MOD1),
+        shift: state.contains(x::KeyButMask::SHIFT),
+        command: state.contains(x::KeyButMask::MOD4),
+        function: false,
+    }
+}
+
+/// Returns the name that keybindings use for a key that doesn't type a character.
+fn key_name(keysym: x::Keysym) -> Option<String> {
+    let name = match keysym {
+        0x0020 => "space",
+        0xff08 => "backspace",
+        0xff09 | 0xfe20 => "tab",
+        0xff0d | 0xff8d => "enter",
+        0xff1b => "escape",
+        0xff50 => "home",
+        0xff51 => "left",
+        0xff52 => "up",
+        0xff53 => "right",
+        0xff54 => "down",
+        0xff55 => "pageup",
+        0xff56 => "pagedown",
+        0xff57 => "end",
+        0xffff => "delete",
+        0xffbe..=0xffc9 => return Some(format!("f{}", keysym - 0xffbe + 1)),
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Returns the character that a keysym types, if any.
+fn keysym_char(keysym: x::Keysym) -> Option<char> {
+    match keysym {
+        // Latin-1 keysyms are equal to their code points.
+        0x0020..=0x007e | 0x00a0..=0x00ff => char::from_u32(keysym),
+        // Other characters are encoded by adding 0x01000000 to their code points.
+        0x0100_0100..=0x0110_ffff => char::from_u32(keysym - 0x0100_0000),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystrokes() {
+        let mapping = KeyboardMapping {
+            min_keycode: 10,
+            keysyms_per_keycode: 2,
+            keysyms: [
+                [0x0061, 0x0041],      // a, A
+                [0x0031, 0x0021],      // 1, !
+                [0xff0d, 0xff0d],      // Return
+                [0x0100_20ac, 0x0000], // €
+            ]
+            .concat(),
+        };
+
+        let keystroke = mapping.keystroke(10, x::KeyButMask::empty());
+        assert_eq!(keystroke.key, "a");
+        assert_eq!(keystroke.ime_key.as_deref(), Some("a"));
+
+        let keystroke = mapping.keystroke(10, x::KeyButMask::SHIFT);
+        assert_eq!(keystroke.key, "a");
+        assert!(keystroke.modifiers.shift);
+        assert_eq!(keystroke.ime_key.as_deref(), Some("A"));
+
+        let keystroke = mapping.keystroke(11, x::KeyButMask::SHIFT);
+        assert_eq!(keystroke.key, "1");
+        assert_eq!(keystroke.ime_key.as_deref(), Some("!"));
+
+        let keystroke = mapping.keystroke(10, x::KeyButMask::CONTROL);
+        assert!(keystroke.modifiers.control);
+        assert_eq!(keystroke.ime_key, None);
+
+        let keystroke = mapping.keystroke(12, x::KeyButMask::empty());
+        assert_eq!(keystroke.key, "enter");
+        assert_eq!(keystroke.ime_key, None);
+
+        let keystroke = mapping.keystroke(13, x::KeyButMask::SHIFT);
+        assert_eq!(keystroke.key, "€");
+        assert_eq!(keystroke.ime_key.as_deref(), Some("€"));
+
+        assert_eq!(mapping.keystroke(42, x::KeyButMask::empty()).key, "");
+    }
+}
@@ -1,19 +1,24 @@
 #![allow(unused)]
 
 use crate::{
-    Action, AnyWindowHandle, BackgroundExecutor, Bounds, ClipboardItem, CursorStyle, DisplayId,
-    ForegroundExecutor, Keymap, LinuxDispatcher, LinuxDisplay, LinuxTextSystem, LinuxWindow,
-    LinuxWindowState, Menu, PathPromptOptions, Platform, PlatformDisplay, PlatformInput,
-    PlatformTextSystem, PlatformWindow, Point, Result, SemanticVersion, Size, Task, WindowOptions,
+    modifiers_from_state, Action, AnyWindowHandle, BackgroundExecutor, Bounds, ClipboardItem,
+    CursorStyle, DisplayId, ForegroundExecutor, KeyUpEvent, KeyboardMapping, Keymap,
+    LinuxDispatcher, LinuxDisplay, LinuxStatusItem, LinuxTextSystem, LinuxWindow, LinuxWindowState,
+    Menu, PathPromptOptions, Platform, PlatformDisplay, PlatformInput, PlatformTextSystem,
+    PlatformWindow, Point, Result, SemanticVersion, Size, Task, WindowOptions,
 };
 
+use anyhow::anyhow;
 use async_task::Runnable;
 use collections::{HashMap, HashSet};
+use copypasta::{ClipboardContext, ClipboardProvider};
 use futures::channel::oneshot;
 use parking_lot::Mutex;
 
 use std::{
+    env,
     path::{Path, PathBuf},
+    process::Command,
     rc::Rc,
     sync::Arc,
     time::Duration,
@@ -26,9 +31,13 @@ xcb::atoms_struct! {
     pub(crate) struct XcbAtoms {
         pub wm_protocols    => b"WM_PROTOCOLS",
         pub wm_del_window   => b"WM_DELETE_WINDOW",
-        wm_state        => b"_NET_WM_STATE",
-        wm_state_maxv   => b"_NET_WM_STATE_MAXIMIZED_VERT",
-        wm_state_maxh   => b"_NET_WM_STATE_MAXIMIZED_HORZ",
+        pub wm_change_state => b"WM_CHANGE_STATE",
+        pub wm_state        => b"_NET_WM_STATE",
+        pub wm_state_maxv   => b"_NET_WM_STATE_MAXIMIZED_VERT",
+        pub wm_state_maxh   => b"_NET_WM_STATE_MAXIMIZED_HORZ",
+        pub wm_state_fullscreen => b"_NET_WM_STATE_FULLSCREEN",
+        pub system_tray_opcode => b"_NET_SYSTEM_TRAY_OPCODE",
+        pub xembed_info     => b"_XEMBED_INFO",
     }
 }
 
@@ -60,6 +69,14 @@ pub(crate) struct LinuxPlatform {
 pub(crate) struct LinuxPlatformState {
     quit_requested: bool,
     windows: HashMap<x::Window, Arc<LinuxWindowState>>,
+    /// The window with the input focus, if it's one of ours.
+    active_window: Option<x::Window>,
+    /// `None` if the clipboard couldn't be connected to, in which case copying and pasting
+    /// do nothing.
+    clipboard: Option<ClipboardContext>,
+    /// The menu bar extra, `None` if none was set or no system tray is running.
+    status_item: Option<LinuxStatusItem>,
+    keyboard: KeyboardMapping,
 }
 
 impl Default for LinuxPlatform {
@@ -70,8 +87,19 @@ impl Default for LinuxPlatform {
 
 impl LinuxPlatform {
     pub(crate) fn new() -> Self {
-        let (xcb_connection, x_root_index) = xcb::Connection::connect(None).unwrap();
+        let (xcb_connection, x_root_index) =
+            xcb::Connection::connect(None).unwrap_or_else(|error| {
+                // Wayland compositors can only be used through XWayland for now.
+                if env::var_os("WAYLAND_DISPLAY").is_some() {
+                    panic!(
+                        "Wayland isn't supported yet, and XWayland couldn't be connected to \
+                        ({error}). Make sure XWayland is running and DISPLAY is set."
+                    );
+                }
+                panic!("failed to connect to the X server: {error}");
+            });
         let atoms = XcbAtoms::intern_all(&xcb_connection).unwrap();
+        let keyboard = KeyboardMapping::query(&xcb_connection).unwrap();
 
         let xcb_connection = Arc::new(xcb_connection);
         let (main_sender, main_receiver) = flume::unbounded::<Runnable>();
@@ -81,6 +109,10 @@ impl LinuxPlatform {
             x_root_index,
         ));
 
+        let clipboard = ClipboardContext::new()
+            .map_err(|error| log::error!("failed to connect to the clipboard: {}", error))
+            .ok();
+
         Self {
             xcb_connection,
             x_root_index,
@@ -93,9 +125,44 @@ impl LinuxPlatform {
             state: Mutex::new(LinuxPlatformState {
                 quit_requested: false,
                 windows: HashMap::default(),
+                active_window: None,
+                clipboard,
+                status_item: None,
+                keyboard,
             }),
         }
     }
+
+    fn handle_key_press(
+        &self,
+        x_window: x::Window,
+        keycode: x::Keycode,
+        state: x::KeyButMask,
+        is_held: bool,
+    ) {
+        let window = self.state.lock().windows.get(&x_window).cloned();
+        if let Some(window) = window {
+            let keystroke = self.state.lock().keyboard.keystroke(keycode, state);
+            window.handle_key_down(keystroke, is_held);
+        }
+    }
+
+    /// Opens a path or URL with the user's preferred application, waiting for `xdg-open`
+    /// on the background executor so that it doesn't linger as a zombie process.
+    fn open_with_system(&self, path: &std::ffi::OsStr) {
+        let path = path.to_owned();
+        self.background_executor
+            .spawn(async move {
+                match Command::new("xdg-open").arg(&path).status() {
+                    Ok(status) if !status.success() => {
+                        log::error!("xdg-open failed to open {:?}: {}", path, status)
+                    }
+                    Ok(_) => {}
+                    Err(error) => log::error!("failed to run xdg-open: {}", error),
+                }
+            })
+            .detach();
+    }
 }
 
 impl Platform for LinuxPlatform {
@@ -116,26 +183,146 @@ impl Platform for LinuxPlatform {
         //Note: here and below, don't keep the lock() open when calling
         // into window functions as they may invoke callbacks that need
         // to immediately access the platform (self).
+        let mut pending_event = None;
         while !self.state.lock().quit_requested {
-            let event = self.xcb_connection.wait_for_event().unwrap();
+            let event = match pending_event.take() {
+                Some(event) => event,
+                None => self.xcb_connection.wait_for_event().unwrap(),
+            };
             match event {
                 xcb::Event::X(x::Event::ClientMessage(ev)) => {
                     if let x::ClientMessageData::Data32([atom, ..]) = ev.data() {
                         if atom == self.atoms.wm_del_window.resource_id() {
                             // window "x" button clicked by user, we gracefully exit
-                            let window = self.state.lock().windows.remove(&ev.window()).unwrap();
-                            window.destroy();
+                            let window = self.state.lock().windows.remove(&ev.window());
+                            if let Some(window) = window {
+                                window.destroy();
+                            }
+                            // Keep running in the system tray when the menu bar extra is shown.
                             let mut state = self.state.lock();
-                            state.quit_requested |= state.windows.is_empty();
+                            state.quit_requested |=
+                                state.windows.is_empty() && state.status_item.is_none();
                         }
                     }
                 }
                 xcb::Event::X(x::Event::Expose(ev)) => {
                     let window = {
                         let state = self.state.lock();
-                        Arc::clone(&state.windows[&ev.window()])
+                        match &state.status_item {
+                            Some(status_item) if status_item.owns(ev.window()) => {
+                                status_item.expose(ev.window());
+                                None
+                            }
+                            // Windows that were closed may still be exposed while they're torn down.
+                            _ => state.windows.get(&ev.window()).cloned(),
+                        }
+                    };
+                    if let Some(window) = window {
+                        window.expose();
+                    }
+                }
+                xcb::Event::X(x::Event::KeyPress(ev)) => {
+                    self.handle_key_press(ev.event(), ev.detail(), ev.state(), false);
+                }
+                xcb::Event::X(x::Event::KeyRelease(ev)) => {
+                    // X repeats a held key by sending a release and a press at the same time.
+                    match self.xcb_connection.poll_for_queued_event() {
+                        Ok(Some(xcb::Event::X(x::Event::KeyPress(next))))
+                            if next.detail() == ev.detail() && next.time() == ev.time() =>
+                        {
+                            self.handle_key_press(next.event(), next.detail(), next.state(), true);
+                        }
+                        next => {
+                            pending_event = next.ok().flatten();
+                            let window = self.state.lock().windows.get(&ev.event()).cloned();
+                            if let Some(window) = window {
+                                let keystroke = self
+                                    .state
+                                    .lock()
+                                    .keyboard
+                                    .keystroke(ev.detail(), ev.state());
+                                window.set_modifiers(keystroke.modifiers);
+                                window.handle_input(PlatformInput::KeyUp(KeyUpEvent { keystroke }));
+                            }
+                        }
+                    }
+                }
+                xcb::Event::X(x::Event::MappingNotify(ev)) => {
+                    if ev.request() == x::Mapping::Keyboard {
+                        match KeyboardMapping::query(&self.xcb_connection) {
+                            Ok(keyboard) => self.state.lock().keyboard = keyboard,
+                            Err(error) => {
+                                log::error!("failed to query the keyboard mapping: {}", error)
+                            }
+                        }
+                    }
+                }
+                xcb::Event::X(x::Event::MotionNotify(ev)) => {
+                    let window = self.state.lock().windows.get(&ev.event()).cloned();
+                    if let Some(window) = window {
+                        window.set_pointer_state(
+                            Point::new(ev.event_x(), ev.event_y()),
+                            modifiers_from_state(ev.state()),
+                        );
+                    }
+                }
+                xcb::Event::X(x::Event::ButtonRelease(ev)) => {
+                    let window = self.state.lock().windows.get(&ev.event()).cloned();
+                    if let Some(window) = window {
+                        window.set_pointer_state(
+                            Point::new(ev.event_x(), ev.event_y()),
+                            modifiers_from_state(ev.state()),
+                        );
+                    }
+                }
+                xcb::Event::X(x::Event::ButtonPress(ev)) => {
+                    let window = self.state.lock().windows.get(&ev.event()).cloned();
+                    if let Some(window) = window {
+                        window.set_pointer_state(
+                            Point::new(ev.event_x(), ev.event_y()),
+                            modifiers_from_state(ev.state()),
+                        );
+                    }
+                    let action = self
+                        .state
+                        .lock()
+                        .status_item
+                        .as_mut()
+                        .and_then(|status_item| status_item.handle_button_press(&ev));
+                    if let Some(action) = action {
+                        let callback = self.callbacks.lock().app_menu_action.take();
+                        if let Some(mut callback) = callback {
+                            callback(&*action);
+                            self.callbacks.lock().app_menu_action = Some(callback);
+                        }
+                    }
+                }
+                // Focus moves temporarily while the keyboard is grabbed, e.g. by the
+                // window manager's window switcher, which doesn't change the active window.
+                xcb::Event::X(x::Event::FocusIn(ev)) if ev.mode() != x::NotifyMode::Grab => {
+                    let window = {
+                        let mut state = self.state.lock();
+                        let window = state.windows.get(&ev.event()).cloned();
+                        if window.is_some() {
+                            state.active_window = Some(ev.event());
+                        }
+                        window
+                    };
+                    if let Some(window) = window {
+                        window.set_active(true);
+                    }
+                }
+                xcb::Event::X(x::Event::FocusOut(ev)) if ev.mode() != x::NotifyMode::Grab => {
+                    let window = {
+                        let mut state = self.state.lock();
+                        if state.active_window == Some(ev.event()) {
+                            state.active_window = None;
+                        }
+                        state.windows.get(&ev.event()).cloned()
                     };
-                    window.expose();
+                    if let Some(window) = window {
+                        window.set_active(false);
+                    }
                 }
                 xcb::Event::X(x::Event::ConfigureNotify(ev)) => {
                     let bounds = Bounds {
@@ -148,11 +335,10 @@ impl Platform for LinuxPlatform {
                             height: ev.height().into(),
                         },
                     };
-                    let window = {
-                        let state = self.state.lock();
-                        Arc::clone(&state.windows[&ev.window()])
-                    };
-                    window.configure(bounds)
+                    let window = self.state.lock().windows.get(&ev.window()).cloned();
+                    if let Some(window) = window {
+                        window.configure(bounds)
+                    }
                 }
                 _ => {}
             }
@@ -205,9 +391,10 @@ impl Platform for LinuxPlatform {
         )))
     }
 
-    //todo!(linux)
     fn active_window(&self) -> Option<AnyWindowHandle> {
-        None
+        let state = self.state.lock();
+        let window = state.windows.get(&state.active_window?)?;
+        Some(window.handle)
     }
 
     fn open_window(
@@ -218,6 +405,7 @@ impl Platform for LinuxPlatform {
         let x_window = self.xcb_connection.generate_id();
 
         let window_ptr = Arc::new(LinuxWindowState::new(
+            handle,
             options,
             &self.xcb_connection,
             self.x_root_index,
@@ -233,7 +421,7 @@ impl Platform for LinuxPlatform {
     }
 
     fn open_url(&self, url: &str) {
-        unimplemented!()
+        self.open_with_system(url.as_ref());
     }
 
     fn on_open_urls(&self, callback: Box<dyn FnMut(Vec<String>)>) {
@@ -244,15 +432,60 @@ impl Platform for LinuxPlatform {
         &self,
         options: PathPromptOptions,
     ) -> oneshot::Receiver<Option<Vec<PathBuf>>> {
-        unimplemented!()
+        let (done_tx, done_rx) = oneshot::channel();
+        self.background_executor
+            .spawn(async move {
+                let mut command = Command::new("zenity");
+                command.args(["--file-selection", "--separator=\n"]);
+                if options.directories {
+                    command.arg("--directory");
+                }
+                if options.multiple {
+                    command.arg("--multiple");
+                }
+                let paths = run_file_dialog(command)
+                    .map(|output| output.lines().map(PathBuf::from).collect::<Vec<_>>());
+                let _ = done_tx.send(paths);
+            })
+            .detach();
+        done_rx
     }
 
     fn prompt_for_new_path(&self, directory: &Path) -> oneshot::Receiver<Option<PathBuf>> {
-        unimplemented!()
+        let (done_tx, done_rx) = oneshot::channel();
+        let mut filename = directory.as_os_str().to_owned();
+        filename.push("/");
+        self.background_executor
+            .spawn(async move {
+                let mut command = Command::new("zenity");
+                command
+                    .args(["--file-selection", "--save", "--confirm-overwrite"])
+                    .arg("--filename")
+                    .arg(filename);
+                let path = run_file_dialog(command).map(|output| PathBuf::from(output.trim_end()));
+                let _ = done_tx.send(path);
+            })
+            .detach();
+        done_rx
     }
 
     fn reveal_path(&self, path: &Path) {
-        unimplemented!()
+        // There's no portable way to select a file in the file manager, so open its
+        // directory instead.
+        if path.is_dir() {
+            self.open_with_system(path.as_os_str());
+        } else if let Some(parent) = path.parent() {
+            self.open_with_system(parent.as_os_str());
+        }
+    }
+
+    fn show_notification(&self, title: &str, body: &str) {
+        if let Err(error) = Command::new("notify-send")
+            .args(["--app-name", "Zed", title, body])
+            .spawn()
+        {
+            log::error!("failed to run notify-send: {}", error);
+        }
     }
 
     fn on_become_active(&self, callback: Box<dyn FnMut()>) {
@@ -291,8 +524,9 @@ impl Platform for LinuxPlatform {
         "Linux"
     }
 
+    // GTK's default double click time.
     fn double_click_interval(&self) -> Duration {
-        Duration::default()
+        Duration::from_millis(400)
     }
 
     fn os_version(&self) -> Result<SemanticVersion> {
@@ -312,18 +546,42 @@ impl Platform for LinuxPlatform {
     }
 
     fn app_path(&self) -> Result<PathBuf> {
-        unimplemented!()
+        Ok(env::current_exe()?)
     }
 
     //todo!(linux)
     fn set_menus(&self, menus: Vec<Menu>, keymap: &Keymap) {}
 
+    fn set_menu_bar_extra(&self, menu: Option<Menu>, keymap: &Keymap) {
+        let mut state = self.state.lock();
+        let Some(menu) = menu else {
+            state.status_item = None;
+            return;
+        };
+        if state.status_item.is_none() {
+            state.status_item =
+                LinuxStatusItem::new(&self.xcb_connection, self.x_root_index, &self.atoms);
+        }
+        if let Some(status_item) = state.status_item.as_mut() {
+            status_item.set_menu(menu);
+        }
+    }
+
     fn local_timezone(&self) -> UtcOffset {
         UtcOffset::UTC
     }
 
     fn path_for_auxiliary_executable(&self, name: &str) -> Result<PathBuf> {
-        unimplemented!()
+        let current_exe = env::current_exe()?;
+        let path = current_exe
+            .parent()
+            .ok_or_else(|| anyhow!("executable has no parent directory"))?
+            .join(name);
+        if path.exists() {
+            Ok(path)
+        } else {
+            Err(anyhow!("auxiliary executable not found: {:?}", path))
+        }
     }
 
     //todo!(linux)
@@ -334,12 +592,20 @@ impl Platform for LinuxPlatform {
         false
     }
 
-    //todo!(linux)
-    fn write_to_clipboard(&self, item: ClipboardItem) {}
+    //todo!(linux): preserve the item's metadata
+    fn write_to_clipboard(&self, item: ClipboardItem) {
+        let mut state = self.state.lock();
+        let Some(clipboard) = state.clipboard.as_mut() else {
+            return;
+        };
+        if let Err(error) = clipboard.set_contents(item.text) {
+            log::error!("failed to write to the clipboard: {}", error);
+        }
+    }
 
-    //todo!(linux)
     fn read_from_clipboard(&self) -> Option<ClipboardItem> {
-        None
+        let text = self.state.lock().clipboard.as_mut()?.get_contents().ok()?;
+        Some(ClipboardItem::new(text))
     }
 
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
@@ -355,10 +621,37 @@ impl Platform for LinuxPlatform {
     }
 
     fn window_appearance(&self) -> crate::WindowAppearance {
+        system_appearance()
+    }
+}
+
+/// Returns the appearance that the `GTK_THEME` environment variable selects, such as
+/// `Adwaita:dark`, which is how some desktops set a dark mode.
+pub(crate) fn system_appearance() -> crate::WindowAppearance {
+    let is_dark = env::var("GTK_THEME").map_or(false, |theme| {
+        let theme = theme.to_ascii_lowercase();
+        theme.ends_with(":dark") || theme.ends_with("-dark")
+    });
+    if is_dark {
+        crate::WindowAppearance::Dark
+    } else {
         crate::WindowAppearance::Light
     }
 }
 
+/// Runs a file dialog command, returning its output or `None` if the user cancelled it.
+fn run_file_dialog(mut command: Command) -> Option<String> {
+    let output = command
+        .output()
+        .map_err(|error| log::error!("failed to open a file dialog: {}", error))
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let output = String::from_utf8(output.stdout).ok()?;
+    (!output.trim().is_empty()).then_some(output)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ClipboardItem;
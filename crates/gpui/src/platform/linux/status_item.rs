@@ -0,0 +1,412 @@
+use crate::{Action, Menu, MenuItem, XcbAtoms};
+use std::sync::Arc;
+use xcb::{x, Xid as _};
+
+/// The opcode of the system tray protocol's request to dock a window as a tray icon.
+const SYSTEM_TRAY_REQUEST_DOCK: u32 = 0;
+/// The `_XEMBED_INFO` flag asking the embedder to map the embedded window.
+const XEMBED_MAPPED: u32 = 1;
+const PADDING: i16 = 4;
+const MIN_MENU_WIDTH: u16 = 120;
+
+struct StatusMenuEntry {
+    /// Empty for separators.
+    label: Vec<u8>,
+    /// `None` for separators and the names of submenus.
+    action: Option<Box<dyn Action>>,
+}
+
+/// An icon in the system tray, docked with the freedesktop system tray protocol, that
+/// shows its menu in a popup window when clicked. The popup is drawn with the core X
+/// font, and the items of submenus are listed, indented, under the submenu's name.
+pub(crate) struct LinuxStatusItem {
+    xcb_connection: Arc<xcb::Connection>,
+    screen_width: u16,
+    screen_height: u16,
+    tray_window: x::Window,
+    menu_window: x::Window,
+    font: x::Font,
+    gc: x::Gcontext,
+    char_width: u16,
+    ascent: i16,
+    line_height: u16,
+    title: Vec<u8>,
+    entries: Vec<StatusMenuEntry>,
+    menu_size: (u16, u16),
+    menu_open: bool,
+}
+
+impl LinuxStatusItem {
+    /// Docks a new tray icon, or returns `None` if no system tray is running.
+    pub(crate) fn new(
+        xcb_connection: &Arc<xcb::Connection>,
+        x_screen_index: i32,
+        atoms: &XcbAtoms,
+    ) -> Option<Self> {
+        let screen = xcb_connection
+            .get_setup()
+            .roots()
+            .nth(x_screen_index as usize)?;
+
+        let selection = xcb_connection
+            .wait_for_reply(xcb_connection.send_request(&x::InternAtom {
+                only_if_exists: false,
+                name: format!("_NET_SYSTEM_TRAY_S{}", x_screen_index).as_bytes(),
+            }))
+            .ok()?
+            .atom();
+        let tray_manager = xcb_connection
+            .wait_for_reply(xcb_connection.send_request(&x::GetSelectionOwner { selection }))
+            .ok()?
+            .owner();
+        if tray_manager.is_none() {
+            log::info!("no system tray is running, not showing the status item");
+            return None;
+        }
+
+        let tray_window = xcb_connection.generate_id();
+        xcb_connection.send_request(&x::CreateWindow {
+            depth: x::COPY_FROM_PARENT as u8,
+            wid: tray_window,
+            parent: screen.root(),
+            x: 0,
+            y: 0,
+            width: 24,
+            height: 24,
+            border_width: 0,
+            class: x::WindowClass::InputOutput,
+            visual: screen.root_visual(),
+            value_list: &[
+                x::Cw::BackPixel(screen.white_pixel()),
+                x::Cw::EventMask(x::EventMask::EXPOSURE | x::EventMask::BUTTON_PRESS),
+            ],
+        });
+        xcb_connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: tray_window,
+            property: atoms.xembed_info,
+            r#type: atoms.xembed_info,
+            data: &[0u32, XEMBED_MAPPED],
+        });
+
+        let menu_window = xcb_connection.generate_id();
+        xcb_connection.send_request(&x::CreateWindow {
+            depth: x::COPY_FROM_PARENT as u8,
+            wid: menu_window,
+            parent: screen.root(),
+            x: 0,
+            y: 0,
+            width: MIN_MENU_WIDTH,
+            height: 1,
+            border_width: 1,
+            class: x::WindowClass::InputOutput,
+            visual: screen.root_visual(),
+            value_list: &[
+                x::Cw::BackPixel(screen.white_pixel()),
+                x::Cw::BorderPixel(screen.black_pixel()),
+                x::Cw::OverrideRedirect(true),
+                x::Cw::EventMask(x::EventMask::EXPOSURE | x::EventMask::BUTTON_PRESS),
+            ],
+        });
+
+        let font = xcb_connection.generate_id();
+        xcb_connection.send_request(&x::OpenFont {
+            fid: font,
+            name: b"fixed",
+        });
+        let gc = xcb_connection.generate_id();
+        xcb_connection.send_request(&x::CreateGc {
+            cid: gc,
+            drawable: x::Drawable::Window(menu_window),
+            value_list: &[
+                x::Gc::Foreground(screen.black_pixel()),
+                x::Gc::Background(screen.white_pixel()),
+                x::Gc::Font(font),
+            ],
+        });
+        let font_info = xcb_connection
+            .wait_for_reply(xcb_connection.send_request(&x::QueryFont {
+                font: x::Fontable::Font(font),
+            }))
+            .ok()?;
+
+        xcb_connection.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(tray_manager),
+            event_mask: x::EventMask::NO_EVENT,
+            event: &x::ClientMessageEvent::new(
+                tray_window,
+                atoms.system_tray_opcode,
+                x::ClientMessageData::Data32([
+                    x::CURRENT_TIME,
+                    SYSTEM_TRAY_REQUEST_DOCK,
+                    tray_window.resource_id(),
+                    0,
+                    0,
+                ]),
+            ),
+        });
+        xcb_connection.flush().ok()?;
+
+        Some(Self {
+            xcb_connection: Arc::clone(xcb_connection),
+            screen_width: screen.width_in_pixels(),
+            screen_height: screen.height_in_pixels(),
+            tray_window,
+            menu_window,
+            font,
+            gc,
+            char_width: font_info.max_bounds().character_width.max(1) as u16,
+            ascent: font_info.font_ascent(),
+            line_height: (font_info.font_ascent() + font_info.font_descent() + 2 * PADDING) as u16,
+            title: Vec::new(),
+            entries: Vec::new(),
+            menu_size: (MIN_MENU_WIDTH, 1),
+            menu_open: false,
+        })
+    }
+
+    pub(crate) fn set_menu(&mut self, menu: Menu) {
+        self.title = latin1(menu.name);
+        self.entries.clear();
+        push_entries(menu.items, 0, &mut self.entries);
+
+        let longest_label = self
+            .entries
+            .iter()
+            .map(|entry| entry.label.len())
+            .max()
+            .unwrap_or(0);
+        self.menu_size = (
+            (longest_label as u16 * self.char_width + 4 * PADDING as u16).max(MIN_MENU_WIDTH),
+            self.entries.len() as u16 * self.line_height + 2 * PADDING as u16,
+        );
+
+        self.xcb_connection.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: self.tray_window,
+            property: x::ATOM_WM_NAME,
+            r#type: x::ATOM_STRING,
+            data: self.title.as_slice(),
+        });
+        self.draw_tray();
+        if self.menu_open {
+            self.xcb_connection.send_request(&x::ConfigureWindow {
+                window: self.menu_window,
+                value_list: &[
+                    x::ConfigWindow::Width(self.menu_size.0 as u32),
+                    x::ConfigWindow::Height(self.menu_size.1 as u32),
+                ],
+            });
+            self.draw_menu();
+        }
+        self.xcb_connection.flush().ok();
+    }
+
+    pub(crate) fn owns(&self, window: x::Window) -> bool {
+        window == self.tray_window || window == self.menu_window
+    }
+
+    pub(crate) fn expose(&self, window: x::Window) {
+        if window == self.tray_window {
+            self.draw_tray();
+        } else if window == self.menu_window {
+            self.draw_menu();
+        }
+        self.xcb_connection.flush().ok();
+    }
+
+    /// Opens or closes the menu on clicks, returning the action of the clicked item.
+    pub(crate) fn handle_button_press(
+        &mut self,
+        event: &x::ButtonPressEvent,
+    ) -> Option<Box<dyn Action>> {
+        let mut action = None;
+        if event.event() == self.tray_window && !self.menu_open {
+            self.open_menu(event.root_x(), event.root_y());
+        } else if event.event() == self.tray_window || event.event() == self.menu_window {
+            // While the menu is open the pointer is grabbed, so clicks outside of it are
+            // reported to it too, with coordinates outside of its bounds.
+            if event.event() == self.menu_window {
+                action = self
+                    .entry_at(event.event_x(), event.event_y())
+                    .and_then(|entry| entry.action.as_ref())
+                    .map(|action| action.boxed_clone());
+            }
+            self.close_menu();
+        }
+        self.xcb_connection.flush().ok();
+        action
+    }
+
+    fn open_menu(&mut self, pointer_x: i16, pointer_y: i16) {
+        let (width, height) = self.menu_size;
+        // Trays are usually at an edge of the screen, so open towards its middle.
+        let x = (pointer_x as i32).min(self.screen_width as i32 - width as i32 - 2);
+        let y = if pointer_y as i32 + height as i32 > self.screen_height as i32 {
+            pointer_y as i32 - height as i32 - 2
+        } else {
+            pointer_y as i32
+        };
+        self.xcb_connection.send_request(&x::ConfigureWindow {
+            window: self.menu_window,
+            value_list: &[
+                x::ConfigWindow::X(x.max(0)),
+                x::ConfigWindow::Y(y.max(0)),
+                x::ConfigWindow::Width(width as u32),
+                x::ConfigWindow::Height(height as u32),
+                x::ConfigWindow::StackMode(x::StackMode::Above),
+            ],
+        });
+        self.xcb_connection.send_request(&x::MapWindow {
+            window: self.menu_window,
+        });
+        self.xcb_connection.send_request(&x::GrabPointer {
+            owner_events: true,
+            grab_window: self.menu_window,
+            event_mask: x::EventMask::BUTTON_PRESS,
+            pointer_mode: x::GrabMode::Async,
+            keyboard_mode: x::GrabMode::Async,
+            confine_to: x::Window::none(),
+            cursor: x::Cursor::none(),
+            time: x::CURRENT_TIME,
+        });
+        self.menu_open = true;
+    }
+
+    fn close_menu(&mut self) {
+        self.xcb_connection.send_request(&x::UngrabPointer {
+            time: x::CURRENT_TIME,
+        });
+        self.xcb_connection.send_request(&x::UnmapWindow {
+            window: self.menu_window,
+        });
+        self.menu_open = false;
+    }
+
+    fn entry_at(&self, x: i16, y: i16) -> Option<&StatusMenuEntry> {
+        if x < 0 || x >= self.menu_size.0 as i16 || y < PADDING {
+            return None;
+        }
+        let index = (y - PADDING) as usize / self.line_height as usize;
+        self.entries.get(index)
+    }
+
+    fn draw_tray(&self) {
+        let Ok(geometry) = self
+            .xcb_connection
+            .wait_for_reply(self.xcb_connection.send_request(&x::GetGeometry {
+                drawable: x::Drawable::Window(self.tray_window),
+            }))
+        else {
+            return;
+        };
+        // There's no icon to show, so show the first letter of the title instead.
+        let Some(initial) = self.title.first() else {
+            return;
+        };
+        self.xcb_connection.send_request(&x::ImageText8 {
+            drawable: x::Drawable::Window(self.tray_window),
+            gc: self.gc,
+            x: (geometry.width() as i16 - self.char_width as i16) / 2,
+            y: (geometry.height() as i16 + self.ascent) / 2,
+            string: std::slice::from_ref(initial),
+        });
+    }
+
+    fn draw_menu(&self) {
+        self.xcb_connection.send_request(&x::ClearArea {
+            exposures: false,
+            window: self.menu_window,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        });
+        let drawable = x::Drawable::Window(self.menu_window);
+        for (index, entry) in self.entries.iter().enumerate() {
+            let top = PADDING + index as i16 * self.line_height as i16;
+            if entry.label.is_empty() {
+                let y = top + self.line_height as i16 / 2;
+                self.xcb_connection.send_request(&x::PolySegment {
+                    drawable,
+                    gc: self.gc,
+                    segments: &[x::Segment {
+                        x1: PADDING,
+                        y1: y,
+                        x2: self.menu_size.0 as i16 - PADDING,
+                        y2: y,
+                    }],
+                });
+            } else {
+                self.xcb_connection.send_request(&x::ImageText8 {
+                    drawable,
+                    gc: self.gc,
+                    x: 2 * PADDING,
+                    y: top + PADDING + self.ascent,
+                    string: &entry.label,
+                });
+            }
+        }
+    }
+}
+
+impl Drop for LinuxStatusItem {
+    fn drop(&mut self) {
+        if self.menu_open {
+            self.close_menu();
+        }
+        self.xcb_connection.send_request(&x::FreeGc { gc: self.gc });
+        self.xcb_connection
+            .send_request(&x::CloseFont { font: self.font });
+        self.xcb_connection.send_request(&x::DestroyWindow {
+            window: self.menu_window,
+        });
+        self.xcb_connection.send_request(&x::DestroyWindow {
+            window: self.tray_window,
+        });
+        self.xcb_connection.flush().ok();
+    }
+}
+
+fn push_entries(items: Vec<MenuItem>, indent: usize, entries: &mut Vec<StatusMenuEntry>) {
+    for item in items {
+        match item {
+            MenuItem::Separator => entries.push(StatusMenuEntry {
+                label: Vec::new(),
+                action: None,
+            }),
+            MenuItem::Submenu(menu) => {
+                entries.push(StatusMenuEntry {
+                    label: latin1(&format!("{:indent$}{}", "", menu.name)),
+                    action: None,
+                });
+                push_entries(menu.items, indent + 2, entries);
+            }
+            MenuItem::Action { name, action, .. } => entries.push(StatusMenuEntry {
+                label: latin1(&format!("{:indent$}{}", "", name)),
+                action: Some(action),
+            }),
+        }
+    }
+}
+
+/// Encodes text for the core X font, which only covers Latin-1.
+fn latin1(text: &str) -> Vec<u8> {
+    text.replace('…', "...")
+        .chars()
+        .take(u8::MAX as usize)
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin1() {
+        assert_eq!(latin1("Open…"), b"Open...");
+        assert_eq!(latin1("café 🦀"), b"caf\xe9 ?");
+    }
+}
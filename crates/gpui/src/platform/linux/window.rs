@@ -1,12 +1,14 @@
 //todo!(linux): remove
 #![allow(unused)]
 
-use super::BladeRenderer;
+use super::{system_appearance, BladeRenderer};
 use crate::{
-    Bounds, GlobalPixels, LinuxDisplay, Pixels, PlatformDisplay, PlatformInputHandler,
-    PlatformWindow, Point, Size, WindowAppearance, WindowBounds, WindowOptions, XcbAtoms,
+    px, AnyWindowHandle, Bounds, GlobalPixels, KeyDownEvent, Keystroke, LinuxDisplay, Modifiers,
+    Pixels, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point,
+    PromptLevel, Size, WindowAppearance, WindowBounds, WindowOptions, XcbAtoms,
 };
 use blade_graphics as gpu;
+use futures::channel::oneshot;
 use parking_lot::Mutex;
 use raw_window_handle as rwh;
 use std::{
@@ -36,8 +38,29 @@ struct LinuxWindowInner {
     bounds: Bounds<i32>,
     scale_factor: f32,
     renderer: BladeRenderer,
+    /// Whether the window was made fullscreen with [`PlatformWindow::toggle_full_screen`].
+    fullscreen: bool,
+    /// The pointer's position in device pixels, as of the last pointer event.
+    mouse_position: Point<i16>,
+    /// The modifier keys that were held, as of the last key or pointer event.
+    modifiers: Modifiers,
 }
 
+/// The atoms of the messages that ask the window manager to change a window's state.
+#[derive(Clone, Copy)]
+struct WindowStateAtoms {
+    wm_change_state: x::Atom,
+    wm_state: x::Atom,
+    wm_state_maxv: x::Atom,
+    wm_state_maxh: x::Atom,
+    wm_state_fullscreen: x::Atom,
+}
+
+/// The `_NET_WM_STATE` action that toggles a state.
+const NET_WM_STATE_TOGGLE: u32 = 2;
+/// The `WM_CHANGE_STATE` state that minimizes a window.
+const ICONIC_STATE: u32 = 3;
+
 impl LinuxWindowInner {
     fn content_size(&self) -> Size<Pixels> {
         let size = self.renderer.viewport_size();
@@ -68,12 +91,16 @@ struct RawWindow {
 }
 
 pub(crate) struct LinuxWindowState {
+    pub(crate) handle: AnyWindowHandle,
     xcb_connection: Arc<xcb::Connection>,
     display: Rc<dyn PlatformDisplay>,
     raw: RawWindow,
     x_window: x::Window,
+    x_root: x::Window,
+    state_atoms: WindowStateAtoms,
     callbacks: Mutex<Callbacks>,
     inner: Mutex<LinuxWindowInner>,
+    input_handler: Mutex<Option<PlatformInputHandler>>,
 }
 
 #[derive(Clone)]
@@ -118,6 +145,7 @@ impl rwh::HasDisplayHandle for LinuxWindow {
 
 impl LinuxWindowState {
     pub fn new(
+        handle: AnyWindowHandle,
         options: WindowOptions,
         xcb_connection: &Arc<xcb::Connection>,
         x_main_screen_index: i32,
@@ -136,7 +164,14 @@ impl LinuxWindowState {
         let xcb_values = [
             x::Cw::BackPixel(screen.white_pixel()),
             x::Cw::EventMask(
-                x::EventMask::EXPOSURE | x::EventMask::STRUCTURE_NOTIFY | x::EventMask::KEY_PRESS,
+                x::EventMask::EXPOSURE
+                    | x::EventMask::STRUCTURE_NOTIFY
+                    | x::EventMask::KEY_PRESS
+                    | x::EventMask::KEY_RELEASE
+                    | x::EventMask::BUTTON_PRESS
+                    | x::EventMask::BUTTON_RELEASE
+                    | x::EventMask::POINTER_MOTION
+                    | x::EventMask::FOCUS_CHANGE,
             ),
         ];
 
@@ -215,16 +250,85 @@ impl LinuxWindowState {
         );
 
         Self {
+            handle,
             xcb_connection: Arc::clone(xcb_connection),
             display: Rc::new(LinuxDisplay::new(xcb_connection, x_screen_index)),
             raw,
             x_window,
+            x_root: screen.root(),
+            state_atoms: WindowStateAtoms {
+                wm_change_state: atoms.wm_change_state,
+                wm_state: atoms.wm_state,
+                wm_state_maxv: atoms.wm_state_maxv,
+                wm_state_maxh: atoms.wm_state_maxh,
+                wm_state_fullscreen: atoms.wm_state_fullscreen,
+            },
             callbacks: Mutex::new(Callbacks::default()),
             inner: Mutex::new(LinuxWindowInner {
                 bounds,
                 scale_factor: 1.0,
                 renderer: BladeRenderer::new(gpu, gpu_extent),
+                fullscreen: false,
+                mouse_position: Point::default(),
+                modifiers: Modifiers::default(),
             }),
+            input_handler: Mutex::new(None),
+        }
+    }
+
+    /// Sends a message about this window to the window manager, which is how EWMH and
+    /// ICCCM have clients ask for their windows to be minimized, maximized and so on.
+    fn send_to_window_manager(&self, message_type: x::Atom, data: [u32; 5]) {
+        self.xcb_connection.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(self.x_root),
+            event_mask: x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT,
+            event: &x::ClientMessageEvent::new(
+                self.x_window,
+                message_type,
+                x::ClientMessageData::Data32(data),
+            ),
+        });
+        self.xcb_connection.flush().ok();
+    }
+
+    pub fn set_pointer_state(&self, position: Point<i16>, modifiers: Modifiers) {
+        let mut inner = self.inner.lock();
+        inner.mouse_position = position;
+        inner.modifiers = modifiers;
+    }
+
+    pub fn set_modifiers(&self, modifiers: Modifiers) {
+        self.inner.lock().modifiers = modifiers;
+    }
+
+    /// Passes an input event to the window, returning whether it was handled.
+    pub fn handle_input(&self, input: PlatformInput) -> bool {
+        // The callback is called without holding the lock, so that it can call back into
+        // the window.
+        let Some(mut callback) = self.callbacks.lock().input.take() else {
+            return false;
+        };
+        let handled = callback(input);
+        self.callbacks.lock().input.get_or_insert(callback);
+        handled
+    }
+
+    /// Passes a key press to the window, typing its character into the focused input
+    /// if no keybinding handled it.
+    pub fn handle_key_down(&self, keystroke: Keystroke, is_held: bool) {
+        self.set_modifiers(keystroke.modifiers);
+        let ime_key = keystroke.ime_key.clone();
+        if self.handle_input(PlatformInput::KeyDown(KeyDownEvent { keystroke, is_held })) {
+            return;
+        }
+        let Some(text) = ime_key else {
+            return;
+        };
+        let input_handler = self.input_handler.lock().take();
+        if let Some(mut input_handler) = input_handler {
+            input_handler.replace_text_in_range(None, &text);
+            self.input_handler.lock().get_or_insert(input_handler);
         }
     }
 
@@ -249,6 +353,12 @@ impl LinuxWindowState {
         }
     }
 
+    pub fn set_active(&self, active: bool) {
+        if let Some(ref mut fun) = self.callbacks.lock().active_status_change {
+            fun(active);
+        }
+    }
+
     pub fn configure(&self, bounds: Bounds<i32>) {
         let mut resize_args = None;
         let do_move;
@@ -290,55 +400,76 @@ impl PlatformWindow for LinuxWindow {
         self.0.inner.lock().scale_factor
     }
 
-    //todo!(linux)
+    // The window manager draws the title bar outside of the window's content.
     fn titlebar_height(&self) -> Pixels {
-        unimplemented!()
+        px(0.)
     }
 
-    //todo!(linux)
     fn appearance(&self) -> WindowAppearance {
-        WindowAppearance::Light
+        system_appearance()
     }
 
     fn display(&self) -> Rc<dyn PlatformDisplay> {
         Rc::clone(&self.0.display)
     }
 
-    //todo!(linux)
     fn mouse_position(&self) -> Point<Pixels> {
-        Point::default()
+        let inner = self.0.inner.lock();
+        Point {
+            x: px(inner.mouse_position.x as f32 / inner.scale_factor),
+            y: px(inner.mouse_position.y as f32 / inner.scale_factor),
+        }
     }
 
-    //todo!(linux)
-    fn modifiers(&self) -> crate::Modifiers {
-        crate::Modifiers::default()
+    fn modifiers(&self) -> Modifiers {
+        self.0.inner.lock().modifiers
     }
 
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
 
-    //todo!(linux)
-    fn set_input_handler(&mut self, input_handler: PlatformInputHandler) {}
+    fn set_input_handler(&mut self, input_handler: PlatformInputHandler) {
+        *self.0.input_handler.lock() = Some(input_handler);
+    }
 
-    //todo!(linux)
     fn take_input_handler(&mut self) -> Option<PlatformInputHandler> {
-        None
+        self.0.input_handler.lock().take()
     }
 
-    //todo!(linux)
     fn prompt(
         &self,
-        _level: crate::PromptLevel,
-        _msg: &str,
-        _detail: Option<&str>,
-        _answers: &[&str],
-    ) -> futures::channel::oneshot::Receiver<usize> {
-        unimplemented!()
+        level: PromptLevel,
+        msg: &str,
+        detail: Option<&str>,
+        answers: &[&str],
+    ) -> oneshot::Receiver<usize> {
+        let (done_tx, done_rx) = oneshot::channel();
+        let command = prompt_command(level, msg, detail, answers);
+        let answers = answers
+            .iter()
+            .map(|answer| answer.to_string())
+            .collect::<Vec<_>>();
+        std::thread::spawn(move || {
+            if let Some(answer) = run_prompt(command, &answers) {
+                let _ = done_tx.send(answer);
+            }
+        });
+        done_rx
     }
 
-    //todo!(linux)
-    fn activate(&self) {}
+    fn activate(&self) {
+        self.0.xcb_connection.send_request(&x::ConfigureWindow {
+            window: self.0.x_window,
+            value_list: &[x::ConfigWindow::StackMode(x::StackMode::Above)],
+        });
+        self.0.xcb_connection.send_request(&x::SetInputFocus {
+            revert_to: x::InputFocus::Parent,
+            focus: self.0.x_window,
+            time: x::CURRENT_TIME,
+        });
+        self.0.xcb_connection.flush().ok();
+    }
 
     fn set_title(&mut self, title: &str) {
         self.0.xcb_connection.send_request(&x::ChangeProperty {
@@ -363,19 +494,46 @@ impl PlatformWindow for LinuxWindow {
         unimplemented!()
     }
 
-    //todo!(linux)
     fn minimize(&self) {
-        unimplemented!()
+        let atoms = self.0.state_atoms;
+        self.0
+            .send_to_window_manager(atoms.wm_change_state, [ICONIC_STATE, 0, 0, 0, 0]);
     }
 
-    //todo!(linux)
     fn zoom(&self) {
-        unimplemented!()
+        let atoms = self.0.state_atoms;
+        self.0.send_to_window_manager(
+            atoms.wm_state,
+            [
+                NET_WM_STATE_TOGGLE,
+                atoms.wm_state_maxv.resource_id(),
+                atoms.wm_state_maxh.resource_id(),
+                0,
+                0,
+            ],
+        );
     }
 
-    //todo!(linux)
     fn toggle_full_screen(&self) {
-        unimplemented!()
+        let atoms = self.0.state_atoms;
+        self.0.send_to_window_manager(
+            atoms.wm_state,
+            [
+                NET_WM_STATE_TOGGLE,
+                atoms.wm_state_fullscreen.resource_id(),
+                0,
+                0,
+                0,
+            ],
+        );
+        let fullscreen = {
+            let mut inner = self.0.inner.lock();
+            inner.fullscreen = !inner.fullscreen;
+            inner.fullscreen
+        };
+        if let Some(ref mut fun) = self.0.callbacks.lock().fullscreen {
+            fun(fullscreen);
+        }
     }
 
     fn on_request_frame(&self, callback: Box<dyn FnMut()>) {
@@ -429,3 +587,63 @@ impl PlatformWindow for LinuxWindow {
         inner.renderer.atlas().clone()
     }
 }
+
+/// Returns the zenity command that shows a prompt. The first answer is the default
+/// button, and the last one, when there are several, is the one chosen by closing
+/// the dialog.
+fn prompt_command(
+    level: PromptLevel,
+    msg: &str,
+    detail: Option<&str>,
+    answers: &[&str],
+) -> std::process::Command {
+    let mut command = std::process::Command::new("zenity");
+    let mut text = msg.to_string();
+    if let Some(detail) = detail {
+        text.push_str("\n\n");
+        text.push_str(detail);
+    }
+    match answers {
+        [] | [_] => {
+            command.arg(match level {
+                PromptLevel::Info => "--info",
+                PromptLevel::Warning => "--warning",
+                PromptLevel::Critical => "--error",
+            });
+            if let Some(answer) = answers.first() {
+                command.arg(format!("--ok-label={answer}"));
+            }
+        }
+        [first, middle @ .., last] => {
+            command.args([
+                "--question".to_string(),
+                format!("--ok-label={first}"),
+                format!("--cancel-label={last}"),
+            ]);
+            for answer in middle {
+                command.arg(format!("--extra-button={answer}"));
+            }
+        }
+    }
+    command.args(["--no-markup", "--text"]).arg(text);
+    command
+}
+
+/// Runs a prompt, returning the index of the chosen answer.
+fn run_prompt(mut command: std::process::Command, answers: &[String]) -> Option<usize> {
+    let output = command
+        .output()
+        .map_err(|error| log::error!("failed to show a prompt: {}", error))
+        .ok()?;
+    if output.status.success() {
+        return Some(0);
+    }
+    // zenity prints the label of an extra button when it's clicked, and nothing when
+    // the dialog is cancelled.
+    let label = String::from_utf8_lossy(&output.stdout);
+    let label = label.trim_end();
+    answers
+        .iter()
+        .position(|answer| !label.is_empty() && answer == label)
+        .or_else(|| answers.len().checked_sub(1))
+}
@@ -56,6 +56,9 @@ use time::UtcOffset;
 const NSUTF8StringEncoding: NSUInteger = 4;
 
 const MAC_PLATFORM_IVAR: &str = "platform";
+/// The tags of the menu bar extra's items start here, so that they don't collide with
+/// the tags of the application menu's items.
+const MENU_BAR_EXTRA_TAG_OFFSET: NSInteger = 1 << 20;
 static mut APP_CLASS: *const Class = ptr::null();
 static mut APP_DELEGATE_CLASS: *const Class = ptr::null();
 
@@ -158,10 +161,23 @@ pub(crate) struct MacPlatformState {
     validate_menu_command: Option<Box<dyn FnMut(&dyn Action) -> bool>>,
     will_open_menu: Option<Box<dyn FnMut()>>,
     menu_actions: Vec<Box<dyn Action>>,
+    menu_bar_extra_actions: Vec<Box<dyn Action>>,
+    status_item: id,
     open_urls: Option<Box<dyn FnMut(Vec<String>)>>,
     finish_launching: Option<Box<dyn FnOnce()>>,
 }
 
+impl MacPlatformState {
+    fn menu_action(&self, tag: NSInteger) -> Option<&Box<dyn Action>> {
+        if tag >= MENU_BAR_EXTRA_TAG_OFFSET {
+            self.menu_bar_extra_actions
+                .get((tag - MENU_BAR_EXTRA_TAG_OFFSET) as usize)
+        } else {
+            self.menu_actions.get(tag as usize)
+        }
+    }
+}
+
 impl Default for MacPlatform {
     fn default() -> Self {
         Self::new()
@@ -188,6 +204,8 @@ impl MacPlatform {
             validate_menu_command: None,
             will_open_menu: None,
             menu_actions: Default::default(),
+            menu_bar_extra_actions: Default::default(),
+            status_item: nil,
             open_urls: None,
             finish_launching: None,
         }))
@@ -225,6 +243,7 @@ impl MacPlatform {
                     item_config,
                     delegate,
                     actions,
+                    0,
                     keymap,
                 ));
             }
@@ -242,10 +261,13 @@ impl MacPlatform {
         application_menu
     }
 
+    /// Creates a menu item whose action is kept in `actions`. Its tag is the action's
+    /// index plus `tag_offset`, which tells the menus whose actions are kept apart.
     unsafe fn create_menu_item(
         item: MenuItem,
         delegate: id,
         actions: &mut Vec<Box<dyn Action>>,
+        tag_offset: NSInteger,
         keymap: &Keymap,
     ) -> id {
         match item {
@@ -338,7 +360,7 @@ impl MacPlatform {
                         .autorelease();
                 }
 
-                let tag = actions.len() as NSInteger;
+                let tag = tag_offset + actions.len() as NSInteger;
                 let _: () = msg_send![item, setTag: tag];
                 actions.push(action);
                 item
@@ -348,7 +370,9 @@ impl MacPlatform {
                 let submenu = NSMenu::new(nil).autorelease();
                 submenu.setDelegate_(delegate);
                 for item in items {
-                    submenu.addItem_(Self::create_menu_item(item, delegate, actions, keymap));
+                    submenu.addItem_(Self::create_menu_item(
+                        item, delegate, actions, tag_offset, keymap,
+                    ));
                 }
                 item.setSubmenu_(submenu);
                 item.setTitle_(ns_string(name));
@@ -622,6 +646,20 @@ impl Platform for MacPlatform {
         }
     }
 
+    fn show_notification(&self, title: &str, body: &str) {
+        unsafe {
+            let center: id = msg_send![
+                class!(NSUserNotificationCenter),
+                defaultUserNotificationCenter
+            ];
+            let notification: id = msg_send![class!(NSUserNotification), new];
+            let _: () = msg_send![notification, setTitle: ns_string(title)];
+            let _: () = msg_send![notification, setInformativeText: ns_string(body)];
+            let _: () = msg_send![center, deliverNotification: notification];
+            let _: () = msg_send![notification, release];
+        }
+    }
+
     fn on_become_active(&self, callback: Box<dyn FnMut()>) {
         self.0.lock().become_active = Some(callback);
     }
@@ -712,6 +750,51 @@ impl Platform for MacPlatform {
         }
     }
 
+    fn set_menu_bar_extra(&self, menu: Option<Menu>, keymap: &Keymap) {
+        unsafe {
+            let app: id = msg_send![APP_CLASS, sharedApplication];
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            let mut state = self.0.lock();
+            let state = &mut *state;
+
+            // The actions of the previous menu are only referenced by its items, which
+            // are replaced.
+            state.menu_bar_extra_actions.clear();
+            let Some(menu) = menu else {
+                if state.status_item != nil {
+                    let _: () = msg_send![status_bar, removeStatusItem: state.status_item];
+                    let _: () = msg_send![state.status_item, release];
+                    state.status_item = nil;
+                }
+                return;
+            };
+
+            if state.status_item == nil {
+                // NSVariableStatusItemLength
+                let status_item: id = msg_send![status_bar, statusItemWithLength: -1.0f64];
+                let _: () = msg_send![status_item, retain];
+                state.status_item = status_item;
+            }
+
+            let delegate = app.delegate();
+            let ns_menu = NSMenu::new(nil).autorelease();
+            ns_menu.setDelegate_(delegate);
+            for item_config in menu.items {
+                ns_menu.addItem_(Self::create_menu_item(
+                    item_config,
+                    delegate,
+                    &mut state.menu_bar_extra_actions,
+                    MENU_BAR_EXTRA_TAG_OFFSET,
+                    keymap,
+                ));
+            }
+
+            let button: id = msg_send![state.status_item, button];
+            let _: () = msg_send![button, setTitle: ns_string(menu.name)];
+            let _: () = msg_send![state.status_item, setMenu: ns_menu];
+        }
+    }
+
     fn local_timezone(&self) -> UtcOffset {
         unsafe {
             let local_timezone: id = msg_send![class!(NSTimeZone), localTimeZone];
@@ -1089,8 +1172,7 @@ extern "C" fn handle_menu_item(this: &mut Object, _: Sel, item: id) {
         let mut lock = platform.0.lock();
         if let Some(mut callback) = lock.menu_command.take() {
             let tag: NSInteger = msg_send![item, tag];
-            let index = tag as usize;
-            if let Some(action) = lock.menu_actions.get(index) {
+            if let Some(action) = lock.menu_action(tag) {
                 let action = action.boxed_clone();
                 drop(lock);
                 callback(&*action);
@@ -1107,8 +1189,7 @@ extern "C" fn validate_menu_item(this: &mut Object, _: Sel, item: id) -> bool {
         let mut lock = platform.0.lock();
         if let Some(mut callback) = lock.validate_menu_command.take() {
             let tag: NSInteger = msg_send![item, tag];
-            let index = tag as usize;
-            if let Some(action) = lock.menu_actions.get(index) {
+            if let Some(action) = lock.menu_action(tag) {
                 let action = action.boxed_clone();
                 drop(lock);
                 result = callback(action.as_ref());
@@ -4,7 +4,7 @@ use crate::{
     WindowAppearance, WindowOptions,
 };
 use anyhow::{anyhow, Result};
-use collections::VecDeque;
+use collections::{HashMap, VecDeque};
 use futures::channel::oneshot;
 use parking_lot::Mutex;
 use std::{
@@ -26,6 +26,8 @@ pub(crate) struct TestPlatform {
     current_clipboard_item: Mutex<Option<ClipboardItem>>,
     pub(crate) prompts: RefCell<TestPrompts>,
     pub opened_url: RefCell<Option<String>>,
+    /// The credentials in the simulated keychain, when it's enabled.
+    pub(crate) keychain: RefCell<Option<HashMap<String, (String, Vec<u8>)>>>,
     weak: Weak<Self>,
 }
 
@@ -47,6 +49,7 @@ impl TestPlatform {
             current_clipboard_item: Mutex::new(None),
             weak: weak.clone(),
             opened_url: Default::default(),
+            keychain: Default::default(),
         })
     }
 
@@ -217,6 +220,8 @@ impl Platform for TestPlatform {
         unimplemented!()
     }
 
+    fn show_notification(&self, _title: &str, _body: &str) {}
+
     fn on_become_active(&self, _callback: Box<dyn FnMut()>) {}
 
     fn on_resign_active(&self, _callback: Box<dyn FnMut()>) {}
@@ -233,6 +238,8 @@ impl Platform for TestPlatform {
 
     fn set_menus(&self, _menus: Vec<crate::Menu>, _keymap: &Keymap) {}
 
+    fn set_menu_bar_extra(&self, _menu: Option<crate::Menu>, _keymap: &Keymap) {}
+
     fn on_app_menu_action(&self, _callback: Box<dyn FnMut(&dyn crate::Action)>) {}
 
     fn on_will_open_app_menu(&self, _callback: Box<dyn FnMut()>) {}
@@ -279,15 +286,26 @@ impl Platform for TestPlatform {
         self.current_clipboard_item.lock().clone()
     }
 
-    fn write_credentials(&self, _url: &str, _username: &str, _password: &[u8]) -> Task<Result<()>> {
+    fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
+        if let Some(keychain) = self.keychain.borrow_mut().as_mut() {
+            keychain.insert(url.to_string(), (username.to_string(), password.to_vec()));
+        }
         Task::ready(Ok(()))
     }
 
-    fn read_credentials(&self, _url: &str) -> Task<Result<Option<(String, Vec<u8>)>>> {
-        Task::ready(Ok(None))
+    fn read_credentials(&self, url: &str) -> Task<Result<Option<(String, Vec<u8>)>>> {
+        let credentials = self
+            .keychain
+            .borrow()
+            .as_ref()
+            .and_then(|keychain| keychain.get(url).cloned());
+        Task::ready(Ok(credentials))
     }
 
-    fn delete_credentials(&self, _url: &str) -> Task<Result<()>> {
+    fn delete_credentials(&self, url: &str) -> Task<Result<()>> {
+        if let Some(keychain) = self.keychain.borrow_mut().as_mut() {
+            keychain.remove(url);
+        }
         Task::ready(Ok(()))
     }
 
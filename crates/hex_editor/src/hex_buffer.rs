@@ -0,0 +1,292 @@
+use anyhow::{anyhow, Result};
+use collections::{BTreeMap, HashMap, HashSet};
+use std::{
+    fs::{File, OpenOptions},
+    ops::Range,
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The number of bytes that are read from disk at once.
+pub const PAGE_SIZE: u64 = 64 * 1024;
+
+/// The number of pages that are kept in memory, so that scrolling through a large file
+/// doesn't load all of it.
+const MAX_CACHED_PAGES: usize = 64;
+
+/// The bytes of a file, which are read from disk a page at a time as they're shown.
+/// Edits overwrite bytes in place, and are kept in memory until they're saved.
+pub struct HexBuffer {
+    path: PathBuf,
+    len: u64,
+    pages: HashMap<u64, Arc<[u8]>>,
+    loading_pages: HashSet<u64>,
+    /// Incremented when the file changes on disk, so that pages that were being read
+    /// before it changed are discarded.
+    version: usize,
+    edits: BTreeMap<u64, u8>,
+}
+
+impl HexBuffer {
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let len = std::fs::metadata(&path)?.len();
+        Ok(Self {
+            path,
+            len,
+            pages: HashMap::default(),
+            loading_pages: HashSet::default(),
+            version: 0,
+            edits: BTreeMap::default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    pub fn edits(&self) -> &BTreeMap<u64, u8> {
+        &self.edits
+    }
+
+    /// Returns the byte at the given offset, or `None` if its page hasn't been loaded.
+    pub fn byte(&self, offset: u64) -> Option<u8> {
+        if let Some(byte) = self.edits.get(&offset) {
+            return Some(*byte);
+        }
+        let page = self.pages.get(&(offset / PAGE_SIZE))?;
+        page.get((offset % PAGE_SIZE) as usize).copied()
+    }
+
+    pub fn is_edited(&self, offset: u64) -> bool {
+        self.edits.contains_key(&offset)
+    }
+
+    pub fn set_byte(&mut self, offset: u64, byte: u8) {
+        if offset >= self.len {
+            return;
+        }
+        let page = self.pages.get(&(offset / PAGE_SIZE));
+        let original = page.and_then(|page| page.get((offset % PAGE_SIZE) as usize));
+        if original == Some(&byte) {
+            self.edits.remove(&offset);
+        } else {
+            self.edits.insert(offset, byte);
+        }
+    }
+
+    /// Returns the pages covering the given range that need to be read from disk, and
+    /// marks them as loading.
+    pub fn pages_to_load(&mut self, range: Range<u64>) -> Vec<u64> {
+        let range = range.start.min(self.len)..range.end.min(self.len);
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let mut pages = Vec::new();
+        for page in range.start / PAGE_SIZE..=(range.end - 1) / PAGE_SIZE {
+            if !self.pages.contains_key(&page) && self.loading_pages.insert(page) {
+                pages.push(page);
+            }
+        }
+        pages
+    }
+
+    pub fn insert_page(&mut self, page: u64, version: usize, bytes: Arc<[u8]>) {
+        if version != self.version {
+            return;
+        }
+        self.loading_pages.remove(&page);
+        if self.pages.len() >= MAX_CACHED_PAGES {
+            if let Some(farthest_page) = self
+                .pages
+                .keys()
+                .copied()
+                .max_by_key(|cached_page| cached_page.abs_diff(page))
+            {
+                self.pages.remove(&farthest_page);
+            }
+        }
+        self.pages.insert(page, bytes);
+    }
+
+    /// Reads the length of the file again after it changed on disk, and forgets the
+    /// pages that were read before. Unsaved edits are kept, unless they're past the new
+    /// end of the file.
+    pub fn reload(&mut self) -> Result<()> {
+        let len = std::fs::metadata(&self.path)?.len();
+        self.len = len;
+        self.edits.retain(|offset, _| *offset < len);
+        self.pages.clear();
+        self.loading_pages.clear();
+        self.version += 1;
+        Ok(())
+    }
+
+    /// Forgets the edits that were written to disk, and the cached pages that they were
+    /// written to. Edits made while saving are kept.
+    pub fn did_save(&mut self, saved_edits: &BTreeMap<u64, u8>) {
+        self.edits
+            .retain(|offset, byte| saved_edits.get(offset) != Some(byte));
+        self.pages.clear();
+        self.loading_pages.clear();
+        self.version += 1;
+    }
+}
+
+pub fn read_page(path: &Path, page: u64) -> Result<Arc<[u8]>> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = page * PAGE_SIZE;
+    let mut bytes = vec![0; PAGE_SIZE.min(len.saturating_sub(start)) as usize];
+    file.read_exact_at(&mut bytes, start)?;
+    Ok(bytes.into())
+}
+
+/// Writes the edited bytes in place, without rewriting the rest of the file.
+pub fn write_edits(path: &Path, edits: &BTreeMap<u64, u8>) -> Result<()> {
+    let file = OpenOptions::new().write(true).open(path)?;
+    for (offset, byte) in edits {
+        file.write_all_at(&[*byte], *offset)?;
+    }
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Parses a query for a sequence of bytes, written in hexadecimal with optional
+/// whitespace between the bytes, such as `7f 45 4c 46` or `0x7f454c46`.
+pub fn parse_hex_query(query: &str) -> Result<Vec<u8>> {
+    let digits = query
+        .split_whitespace()
+        .map(|word| word.strip_prefix("0x").unwrap_or(word))
+        .collect::<String>();
+    if digits.is_empty() {
+        return Err(anyhow!("query is empty"));
+    }
+    if digits.len() % 2 != 0 {
+        return Err(anyhow!("query has an odd number of hex digits"));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|ix| {
+            u8::from_str_radix(&digits[ix..ix + 2], 16)
+                .map_err(|_| anyhow!("invalid hex byte {:?}", &digits[ix..ix + 2]))
+        })
+        .collect()
+}
+
+/// Finds the first occurrence of `pattern` at or after `start`, wrapping around to the
+/// start of the file. The file is read a page at a time, with the given edits applied.
+pub fn find(
+    path: &Path,
+    edits: &BTreeMap<u64, u8>,
+    pattern: &[u8],
+    start: u64,
+) -> Result<Option<u64>> {
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = start.min(len);
+    Ok(
+        find_in_range(&file, edits, pattern, start..len)?.or(find_in_range(
+            &file,
+            edits,
+            pattern,
+            0..start,
+        )?),
+    )
+}
+
+fn find_in_range(
+    file: &File,
+    edits: &BTreeMap<u64, u8>,
+    pattern: &[u8],
+    range: Range<u64>,
+) -> Result<Option<u64>> {
+    let overlap = pattern.len() as u64 - 1;
+    let len = file.metadata()?.len();
+    let mut chunk_start = range.start;
+    let mut chunk = Vec::new();
+    while chunk_start < range.end {
+        // Read past the end of the range, so that matches starting in it are found.
+        let chunk_end = (chunk_start + PAGE_SIZE + overlap).min(len);
+        chunk.resize((chunk_end - chunk_start) as usize, 0);
+        file.read_exact_at(&mut chunk, chunk_start)?;
+        for (offset, byte) in edits.range(chunk_start..chunk_end) {
+            chunk[(offset - chunk_start) as usize] = *byte;
+        }
+        if let Some(ix) = chunk
+            .windows(pattern.len())
+            .position(|window| window == pattern)
+        {
+            let offset = chunk_start + ix as u64;
+            return Ok((offset < range.end).then_some(offset));
+        }
+        chunk_start += PAGE_SIZE;
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_query() {
+        assert_eq!(parse_hex_query("7f 45 4C 46").unwrap(), b"\x7fELF");
+        assert_eq!(parse_hex_query("0x7f454c46").unwrap(), b"\x7fELF");
+        assert!(parse_hex_query("7f4").is_err());
+        assert!(parse_hex_query("zz").is_err());
+        assert!(parse_hex_query(" ").is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.bin");
+        let mut contents = vec![0; PAGE_SIZE as usize * 2];
+        // A match that spans two pages.
+        contents[PAGE_SIZE as usize - 1..PAGE_SIZE as usize + 2].copy_from_slice(b"abc");
+        contents[10..13].copy_from_slice(b"abc");
+        std::fs::write(&path, &contents).unwrap();
+
+        let edits = BTreeMap::default();
+        assert_eq!(find(&path, &edits, b"abc", 0).unwrap(), Some(10));
+        assert_eq!(
+            find(&path, &edits, b"abc", 11).unwrap(),
+            Some(PAGE_SIZE - 1)
+        );
+        assert_eq!(find(&path, &edits, b"abc", PAGE_SIZE).unwrap(), Some(10));
+        assert_eq!(find(&path, &edits, b"xyz", 0).unwrap(), None);
+
+        // Unsaved edits are searched.
+        let edits = BTreeMap::from_iter([(20, b'x'), (21, b'y'), (22, b'z')]);
+        assert_eq!(find(&path, &edits, b"xyz", 0).unwrap(), Some(20));
+
+        write_edits(&path, &edits).unwrap();
+        let mut buffer = HexBuffer::open(path.clone()).unwrap();
+        assert_eq!(buffer.pages_to_load(0..PAGE_SIZE + 1), [0, 1]);
+        assert_eq!(buffer.pages_to_load(0..1), []);
+        buffer.insert_page(0, buffer.version(), read_page(&path, 0).unwrap());
+        assert_eq!(buffer.byte(20), Some(b'x'));
+        assert_eq!(buffer.byte(PAGE_SIZE), None);
+
+        buffer.set_byte(20, b'a');
+        assert!(buffer.is_dirty());
+        buffer.set_byte(20, b'x');
+        assert!(!buffer.is_dirty());
+    }
+}
@@ -0,0 +1,732 @@
+mod hex_buffer;
+
+use anyhow::{anyhow, Result};
+use editor::Editor;
+use futures::StreamExt;
+use gpui::{
+    actions, uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    KeyDownEvent, Model, MouseButton, MouseDownEvent, PathPromptOptions, Render, Task,
+    UniformListScrollHandle, View, ViewContext,
+};
+use hex_buffer::{find, parse_hex_query, read_page, write_edits, HexBuffer};
+use project::{Fs, Project, ProjectPath};
+use settings::Settings;
+use std::{ops::Range, path::PathBuf, sync::Arc, time::Duration};
+use theme::ThemeSettings;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    SaveIntent, Workspace,
+};
+
+actions!(
+    hex_editor,
+    [
+        OpenFile,
+        ReopenWithHexEditor,
+        ReopenWithTextEditor,
+        MoveLeft,
+        MoveRight,
+        MoveUp,
+        MoveDown,
+        PageUp,
+        PageDown,
+        ToggleColumn,
+        Find,
+        SelectNextMatch,
+    ]
+);
+
+const BYTES_PER_ROW: u64 = 16;
+const ROWS_PER_PAGE: u64 = 32;
+const FILE_WATCH_LATENCY: Duration = Duration::from_millis(100);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(HexEditor::register).detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Text,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QueryKind {
+    Hex,
+    Text,
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    Saved,
+}
+
+/// Shows the bytes of a file in hexadecimal, alongside their ASCII characters, and
+/// overwrites them in place as hex digits or characters are typed. The file is read
+/// as it's scrolled through, so that large files open immediately.
+pub struct HexEditor {
+    focus_handle: FocusHandle,
+    project_path: ProjectPath,
+    buffer: HexBuffer,
+    cursor: u64,
+    column: Column,
+    /// Whether the high nibble of the byte under the cursor was typed, and the low one is
+    /// typed next.
+    low_nibble: bool,
+    scroll_handle: UniformListScrollHandle,
+    query_editor: View<Editor>,
+    query_kind: QueryKind,
+    current_match: Option<Range<u64>>,
+    search_status: Option<SharedString>,
+    pending_search: Option<Task<()>>,
+    _watch_file: Task<()>,
+}
+
+impl HexEditor {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::open_file);
+        workspace.register_action(Self::reopen_with_hex_editor);
+        workspace.register_action(Self::reopen_with_text_editor);
+    }
+
+    /// Opens a file that's picked from the system's file picker. Binary files can't be
+    /// opened as text, so this is how they're opened without a text editor first.
+    fn open_file(workspace: &mut Workspace, _: &OpenFile, cx: &mut ViewContext<Workspace>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        let project = workspace.project().clone();
+        cx.spawn(|workspace, mut cx| async move {
+            let Some(abs_path) = paths.await?.and_then(|mut paths| paths.pop()) else {
+                return Ok(());
+            };
+            let (worktree, relative_path) = project
+                .update(&mut cx, |project, cx| {
+                    project.find_or_create_local_worktree(&abs_path, false, cx)
+                })?
+                .await?;
+            let project_path = ProjectPath {
+                worktree_id: worktree.read_with(&cx, |worktree, _| worktree.id())?,
+                path: relative_path.into(),
+            };
+            workspace.update(&mut cx, |workspace, cx| {
+                Self::add_to_active_pane(workspace, project_path, Ok(abs_path), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Adds a hex editor for the file to the active pane, reading the file's bytes
+    /// directly rather than loading it as text.
+    fn add_to_active_pane(
+        workspace: &mut Workspace,
+        project_path: ProjectPath,
+        abs_path: Result<PathBuf>,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Option<View<Self>> {
+        let buffer = match abs_path.and_then(HexBuffer::open) {
+            Ok(buffer) => buffer,
+            Err(error) => {
+                workspace.show_error(&error, cx);
+                return None;
+            }
+        };
+        let fs = workspace.project().read(cx).fs().clone();
+        let hex_editor = cx.new_view(|cx| HexEditor::new(project_path, buffer, fs, cx));
+        workspace.active_pane().update(cx, |pane, cx| {
+            pane.add_item(Box::new(hex_editor.clone()), true, true, None, cx);
+        });
+        Some(hex_editor)
+    }
+
+    fn reopen_with_hex_editor(
+        workspace: &mut Workspace,
+        _: &ReopenWithHexEditor,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let pane = workspace.active_pane().clone();
+        let Some(item) = pane.read(cx).active_item() else {
+            return;
+        };
+        if item.downcast::<HexEditor>().is_some() {
+            return;
+        }
+        let Some(project_path) = item.project_path(cx) else {
+            return;
+        };
+        let abs_path = workspace
+            .project()
+            .read(cx)
+            .absolute_path(&project_path, cx)
+            .ok_or_else(|| anyhow!("{:?} isn't a local file", project_path.path));
+        if Self::add_to_active_pane(workspace, project_path, abs_path, cx).is_some() {
+            pane.update(cx, |pane, cx| {
+                pane.close_item_by_id(item.item_id(), SaveIntent::Close, cx)
+                    .detach_and_log_err(cx);
+            });
+        }
+    }
+
+    fn reopen_with_text_editor(
+        workspace: &mut Workspace,
+        _: &ReopenWithTextEditor,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let pane = workspace.active_pane().clone();
+        let Some(hex_editor) = pane
+            .read(cx)
+            .active_item()
+            .and_then(|item| item.downcast::<HexEditor>())
+        else {
+            return;
+        };
+        let project_path = hex_editor.read(cx).project_path.clone();
+        let open_editor = workspace.open_path(project_path, Some(pane.downgrade()), true, cx);
+        cx.spawn(|_, mut cx| async move {
+            open_editor.await?;
+            pane.update(&mut cx, |pane, cx| {
+                pane.close_item_by_id(hex_editor.item_id(), SaveIntent::Close, cx)
+            })?
+            .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(
+        project_path: ProjectPath,
+        buffer: HexBuffer,
+        fs: Arc<dyn Fs>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let query_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Search bytes…", cx);
+            editor
+        });
+        let path = buffer.path().to_path_buf();
+        let watch_file = cx.spawn(|this, mut cx| async move {
+            let mut events = fs.watch(&path, FILE_WATCH_LATENCY).await;
+            while events.next().await.is_some() {
+                if this.update(&mut cx, |this, cx| this.reload(cx)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            focus_handle: cx.focus_handle(),
+            project_path,
+            buffer,
+            cursor: 0,
+            column: Column::Hex,
+            low_nibble: false,
+            scroll_handle: UniformListScrollHandle::new(),
+            query_editor,
+            query_kind: QueryKind::Hex,
+            current_match: None,
+            search_status: None,
+            pending_search: None,
+            _watch_file: watch_file,
+        }
+    }
+
+    /// Shows the file as it is on disk after it changed, keeping the unsaved edits.
+    fn reload(&mut self, cx: &mut ViewContext<Self>) {
+        if self.buffer.reload().log_err().is_none() {
+            return;
+        }
+        self.cursor = self.cursor.min(self.buffer.len().saturating_sub(1));
+        self.low_nibble = false;
+        cx.notify();
+    }
+
+    fn move_cursor_to(&mut self, offset: u64, cx: &mut ViewContext<Self>) {
+        self.cursor = offset.min(self.buffer.len().saturating_sub(1));
+        self.low_nibble = false;
+        self.scroll_handle
+            .scroll_to_item((self.cursor / BYTES_PER_ROW) as usize);
+        cx.notify();
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, cx: &mut ViewContext<Self>) {
+        self.move_cursor_to(self.cursor.saturating_sub(1), cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, cx: &mut ViewContext<Self>) {
+        self.move_cursor_to(self.cursor + 1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, cx: &mut ViewContext<Self>) {
+        self.move_cursor_to(self.cursor.saturating_sub(BYTES_PER_ROW), cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, cx: &mut ViewContext<Self>) {
+        if self.cursor + BYTES_PER_ROW < self.buffer.len() {
+            self.move_cursor_to(self.cursor + BYTES_PER_ROW, cx);
+        }
+    }
+
+    fn page_up(&mut self, _: &PageUp, cx: &mut ViewContext<Self>) {
+        self.move_cursor_to(
+            self.cursor.saturating_sub(BYTES_PER_ROW * ROWS_PER_PAGE),
+            cx,
+        );
+    }
+
+    fn page_down(&mut self, _: &PageDown, cx: &mut ViewContext<Self>) {
+        self.move_cursor_to(self.cursor + BYTES_PER_ROW * ROWS_PER_PAGE, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, cx: &mut ViewContext<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Text,
+            Column::Text => Column::Hex,
+        };
+        self.low_nibble = false;
+        cx.notify();
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        if !self.focus_handle.is_focused(cx) {
+            return;
+        }
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.control || keystroke.modifiers.alt || keystroke.modifiers.command {
+            return;
+        }
+        let input = keystroke.ime_key.as_ref().unwrap_or(&keystroke.key);
+        let mut chars = input.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+        let Some(byte) = self.buffer.byte(self.cursor) else {
+            return;
+        };
+
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = c.to_digit(16) else {
+                    return;
+                };
+                let digit = digit as u8;
+                if self.low_nibble {
+                    self.buffer.set_byte(self.cursor, byte & 0xf0 | digit);
+                    self.move_cursor_to(self.cursor + 1, cx);
+                } else {
+                    self.buffer.set_byte(self.cursor, digit << 4 | byte & 0x0f);
+                    self.low_nibble = true;
+                }
+            }
+            Column::Text => {
+                if !c.is_ascii() || c.is_ascii_control() {
+                    return;
+                }
+                self.buffer.set_byte(self.cursor, c as u8);
+                self.move_cursor_to(self.cursor + 1, cx);
+            }
+        }
+        cx.stop_propagation();
+        cx.emit(HexEditorEvent::Edited);
+        cx.notify();
+    }
+
+    fn find(&mut self, _: &Find, cx: &mut ViewContext<Self>) {
+        self.query_editor.update(cx, |editor, cx| {
+            editor.select_all(&editor::actions::SelectAll, cx);
+        });
+        cx.focus_view(&self.query_editor);
+    }
+
+    fn set_query_kind(&mut self, query_kind: QueryKind, cx: &mut ViewContext<Self>) {
+        self.query_kind = query_kind;
+        self.current_match = None;
+        self.search_status = None;
+        cx.notify();
+    }
+
+    /// Searches for the query from the cursor, or from the byte after the current match,
+    /// wrapping around to the start of the file.
+    fn select_next_match(&mut self, _: &SelectNextMatch, cx: &mut ViewContext<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let pattern = match self.query_kind {
+            QueryKind::Hex => parse_hex_query(&query),
+            QueryKind::Text if query.is_empty() => Err(anyhow!("query is empty")),
+            QueryKind::Text => Ok(query.into_bytes()),
+        };
+        let pattern = match pattern {
+            Ok(pattern) => pattern,
+            Err(error) => {
+                self.search_status = Some(error.to_string().into());
+                cx.notify();
+                return;
+            }
+        };
+
+        let start = match &self.current_match {
+            Some(current_match) if current_match.start == self.cursor => self.cursor + 1,
+            _ => self.cursor,
+        };
+        let path = self.buffer.path().to_path_buf();
+        let edits = self.buffer.edits().clone();
+        let search = cx.background_executor().spawn(async move {
+            let offset = find(&path, &edits, &pattern, start)?;
+            anyhow::Ok(offset.map(|offset| offset..offset + pattern.len() as u64))
+        });
+        self.pending_search = Some(cx.spawn(|this, mut cx| async move {
+            let result = search.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_search = None;
+                match result {
+                    Ok(Some(range)) => {
+                        this.move_cursor_to(range.start, cx);
+                        this.current_match = Some(range);
+                        this.search_status = None;
+                    }
+                    Ok(None) => {
+                        this.current_match = None;
+                        this.search_status = Some("No matches".into());
+                    }
+                    Err(error) => {
+                        this.current_match = None;
+                        this.search_status = Some(error.to_string().into());
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        }));
+    }
+
+    /// Reads the pages of the file that contain the given rows, if they aren't loaded yet.
+    fn load_rows(&mut self, rows: Range<usize>, cx: &mut ViewContext<Self>) {
+        let range = rows.start as u64 * BYTES_PER_ROW..rows.end as u64 * BYTES_PER_ROW;
+        let version = self.buffer.version();
+        for page in self.buffer.pages_to_load(range) {
+            let path = self.buffer.path().to_path_buf();
+            let read = cx
+                .background_executor()
+                .spawn(async move { read_page(&path, page) });
+            cx.spawn(|this, mut cx| async move {
+                let bytes = read.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.buffer.insert_page(page, version, bytes);
+                    cx.notify();
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn render_row(&self, row: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let start = row as u64 * BYTES_PER_ROW;
+        let offset_digits = format!("{:x}", self.buffer.len()).len().max(8);
+        h_flex()
+            .gap_4()
+            .child(
+                div()
+                    .text_color(Color::Muted.color(cx))
+                    .child(format!("{start:0offset_digits$x}")),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .children((start..start + BYTES_PER_ROW).map(|offset| {
+                        let text = if offset >= self.buffer.len() {
+                            "  ".to_string()
+                        } else if let Some(byte) = self.buffer.byte(offset) {
+                            format!("{byte:02x}")
+                        } else {
+                            "··".to_string()
+                        };
+                        self.render_cell(offset, Column::Hex, text, cx)
+                    })),
+            )
+            .child(
+                h_flex().children((start..start + BYTES_PER_ROW).map(|offset| {
+                    let text = match self.buffer.byte(offset) {
+                        _ if offset >= self.buffer.len() => ' ',
+                        Some(byte) if byte.is_ascii_graphic() || byte == b' ' => byte as char,
+                        _ => '.',
+                    };
+                    self.render_cell(offset, Column::Text, text.to_string(), cx)
+                })),
+            )
+            .into_any_element()
+    }
+
+    fn render_cell(
+        &self,
+        offset: u64,
+        column: Column,
+        text: String,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let is_match = self
+            .current_match
+            .as_ref()
+            .map_or(false, |current_match| current_match.contains(&offset));
+        let background = if offset == self.cursor && column == self.column {
+            Some(colors.element_selected)
+        } else if offset == self.cursor {
+            Some(colors.element_hover)
+        } else if is_match {
+            Some(colors.search_match_background)
+        } else {
+            None
+        };
+        let text_color = if self.buffer.is_edited(offset) {
+            Color::Modified.color(cx)
+        } else {
+            colors.editor_foreground
+        };
+
+        div()
+            .when_some(background, |this, background| this.bg(background))
+            .text_color(text_color)
+            .child(text)
+            .when(offset < self.buffer.len(), |this| {
+                this.on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _: &MouseDownEvent, cx| {
+                        this.column = column;
+                        this.move_cursor_to(offset, cx);
+                        cx.focus(&this.focus_handle);
+                    }),
+                )
+            })
+    }
+
+    fn render_search_bar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let query_kind_button = |id: &'static str, label: &'static str, query_kind| {
+            Button::new(id, label)
+                .selected(self.query_kind == query_kind)
+                .on_click(cx.listener(move |this, _, cx| this.set_query_kind(query_kind, cx)))
+        };
+        h_flex()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(div().flex_1().child(self.query_editor.clone()))
+            .child(query_kind_button("hex-query", "Hex", QueryKind::Hex))
+            .child(query_kind_button("text-query", "Text", QueryKind::Text))
+            .children(
+                self.search_status
+                    .clone()
+                    .map(|status| Label::new(status).color(Color::Muted)),
+            )
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let row_count = self.buffer.len().div_ceil(BYTES_PER_ROW).max(1) as usize;
+        v_flex()
+            .key_context("HexEditor")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::find))
+            .on_action(cx.listener(Self::select_next_match))
+            .child(self.render_search_bar(cx))
+            .child(
+                div()
+                    .id("hex-editor")
+                    .track_focus(&self.focus_handle)
+                    .on_key_down(cx.listener(Self::key_down))
+                    .flex_1()
+                    .p_2()
+                    .font(ThemeSettings::get_global(cx).buffer_font.family.clone())
+                    .child(
+                        uniform_list(cx.view().clone(), "rows", row_count, {
+                            |this, rows, cx| {
+                                this.load_rows(rows.clone(), cx);
+                                rows.map(|row| this.render_row(row, cx)).collect()
+                            }
+                        })
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    ),
+            )
+    }
+}
+
+impl FocusableView for HexEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        let file_name = self
+            .buffer
+            .path()
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        h_flex()
+            .gap_2()
+            .child(Label::new(file_name).color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            }))
+            .child(Label::new("Hex").color(Color::Muted))
+            .into_any()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.buffer.path().to_string_lossy().into_owned().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("hex editor")
+    }
+
+    fn is_dirty(&self, _: &AppContext) -> bool {
+        self.buffer.is_dirty()
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(&mut self, _: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let path = self.buffer.path().to_path_buf();
+        let edits = self.buffer.edits().clone();
+        let write = cx.background_executor().spawn({
+            let edits = edits.clone();
+            async move { write_edits(&path, &edits) }
+        });
+        cx.spawn(|this, mut cx| async move {
+            write.await?;
+            this.update(&mut cx, |this, cx| {
+                this.buffer.did_save(&edits);
+                cx.emit(HexEditorEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::Saved => f(ItemEvent::UpdateTab),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Keystroke, TestAppContext, VisualTestContext};
+    use project::{FakeFs, WorktreeId};
+    use settings::SettingsStore;
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_edit_save_and_reload(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.executor().allow_parking();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.bin");
+        std::fs::write(&path, b"\x00\x01\x02\x03").unwrap();
+
+        // The file watcher is driven by a fake file system, while the bytes are read from disk.
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(&path, String::new()).await;
+        let project = Project::test(fs.clone(), [], cx).await;
+        let (hex_editor, cx) = cx.add_window_view(|cx| {
+            let project_path = ProjectPath {
+                worktree_id: WorktreeId::from_usize(0),
+                path: Path::new("file.bin").into(),
+            };
+            let buffer = HexBuffer::open(path.clone()).unwrap();
+            HexEditor::new(project_path, buffer, project.read(cx).fs().clone(), cx)
+        });
+        hex_editor.update(cx, |hex_editor, cx| {
+            cx.focus_self();
+            hex_editor.load_rows(0..1, cx);
+        });
+        cx.run_until_parked();
+
+        type_keys(&hex_editor, "ab", cx);
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.toggle_column(&ToggleColumn, cx);
+        });
+        type_keys(&hex_editor, "Z", cx);
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.cursor, 2);
+            assert!(hex_editor.is_dirty(cx));
+        });
+
+        hex_editor
+            .update(cx, |hex_editor, cx| hex_editor.save(project.clone(), cx))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"\xabZ\x02\x03");
+        hex_editor.update(cx, |hex_editor, cx| assert!(!hex_editor.is_dirty(cx)));
+
+        // An unsaved edit past the new end of the file is dropped when the file shrinks.
+        type_keys(&hex_editor, "Y", cx);
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.move_cursor_to(3, cx);
+        });
+        type_keys(&hex_editor, "X", cx);
+        std::fs::write(&path, b"\x10\x11\x12").unwrap();
+        fs.insert_file(&path, String::new()).await;
+        cx.run_until_parked();
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.load_rows(0..1, cx);
+        });
+        cx.run_until_parked();
+        hex_editor.update(cx, |hex_editor, _| {
+            assert_eq!(hex_editor.buffer.len(), 3);
+            assert_eq!(hex_editor.cursor, 2);
+            assert_eq!(hex_editor.buffer.byte(0), Some(0x10));
+            assert_eq!(hex_editor.buffer.byte(1), Some(0x11));
+            assert_eq!(hex_editor.buffer.byte(2), Some(b'Y'));
+            assert_eq!(hex_editor.buffer.byte(3), None);
+        });
+    }
+
+    fn type_keys(hex_editor: &View<HexEditor>, keys: &str, cx: &mut VisualTestContext) {
+        for key in keys.chars() {
+            let event = KeyDownEvent {
+                keystroke: Keystroke::parse(&key.to_string()).unwrap(),
+                is_held: false,
+            };
+            hex_editor.update(cx, |hex_editor, cx| hex_editor.key_down(&event, cx));
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+}
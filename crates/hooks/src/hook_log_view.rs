@@ -0,0 +1,93 @@
+use crate::{Event, Hooks};
+use editor::{actions::MoveToEnd, Editor, EditorEvent};
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, IntoElement, Model, Render,
+    Subscription, View, ViewContext, VisualContext, WindowContext,
+};
+use ui::{prelude::*, Label};
+use workspace::item::{Item, ItemEvent};
+
+/// Shows the output of the commands run by a project's hooks.
+pub struct HookLogView {
+    editor: View<Editor>,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HookLogView {
+    pub fn new(hooks: Model<Hooks>, cx: &mut ViewContext<Self>) -> Self {
+        let log = hooks.read(cx).log().iter().cloned().collect::<Vec<_>>();
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_text(log.join("\n"), cx);
+            editor.move_to_end(&MoveToEnd, cx);
+            editor.set_read_only(true);
+            editor.set_show_copilot_suggestions(false);
+            editor
+        });
+
+        let focus_handle = cx.focus_handle();
+        let subscriptions = vec![
+            cx.subscribe(&hooks, |this, _, event: &Event, cx| match event {
+                Event::NewLogLine(line) => this.editor.update(cx, |editor, cx| {
+                    editor.set_read_only(false);
+                    let end = editor.buffer().read(cx).len(cx);
+                    let text = if end == 0 {
+                        line.clone()
+                    } else {
+                        format!("\n{line}")
+                    };
+                    editor.edit([(end..end, text)], cx);
+                    editor.set_read_only(true);
+                }),
+            }),
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+            cx.on_focus(&focus_handle, |this, cx| cx.focus_view(&this.editor)),
+        ];
+
+        Self {
+            editor,
+            focus_handle,
+            _subscriptions: subscriptions,
+        }
+    }
+}
+
+impl Render for HookLogView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        self.editor
+            .update(cx, |editor, cx| editor.render(cx).into_any_element())
+    }
+}
+
+impl FocusableView for HookLogView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<EditorEvent> for HookLogView {}
+
+impl Item for HookLogView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext<'_>) -> AnyElement {
+        Label::new("Hook Logs")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
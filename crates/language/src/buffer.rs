@@ -13,7 +13,7 @@ use crate::{
         SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatches,
         SyntaxSnapshot, ToTreeSitterPoint,
     },
-    CodeLabel, LanguageScope, Outline,
+    CodeLabel, DocumentedItem, LanguageScope, Outline,
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
@@ -202,6 +202,8 @@ pub struct Diagnostic {
     pub is_disk_based: bool,
     /// Whether this diagnostic marks unnecessary code.
     pub is_unnecessary: bool,
+    /// Whether this diagnostic marks deprecated code.
+    pub is_deprecated: bool,
 }
 
 /// TODO - move this into the `project` crate and make it private.
@@ -258,8 +260,9 @@ pub struct Completion {
     pub new_text: String,
     /// A label for this completion that is shown in the menu.
     pub label: CodeLabel,
-    /// The id of the language server that produced this completion.
-    pub server_id: LanguageServerId,
+    /// The id of the language server that produced this completion, or `None` if it
+    /// was produced by the editor itself.
+    pub server_id: Option<LanguageServerId>,
     /// The documentation for this completion.
     pub documentation: Option<Documentation>,
     /// The raw completion provided by the language server.
@@ -277,6 +280,27 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+/// A code lens provided by a language server, such as a reference count
+/// or a "Run test" command displayed above a symbol.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    /// The id of the language server that produced this code lens.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer that this code lens applies to.
+    pub range: Range<Anchor>,
+    /// The raw code lens provided by the language server.
+    pub lsp_lens: lsp::CodeLens,
+}
+
+/// An import statement, as matched by the imports query of a buffer's language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Import {
+    /// The range of the whole statement.
+    pub range: Range<usize>,
+    /// The path that the statement imports, such as `std::fmt` or `"./utils"`.
+    pub path: String,
+}
+
 /// An operation used to synchronize this buffer with its other replicas.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Operation {
@@ -475,6 +499,7 @@ pub struct BufferChunks<'a> {
     information_depth: usize,
     hint_depth: usize,
     unnecessary_depth: usize,
+    deprecated_depth: usize,
     highlights: Option<BufferChunkHighlights<'a>>,
 }
 
@@ -493,6 +518,8 @@ pub struct Chunk<'a> {
     pub diagnostic_severity: Option<DiagnosticSeverity>,
     /// Whether this chunk of text is marked as unnecessary.
     pub is_unnecessary: bool,
+    /// Whether this chunk of text is marked as deprecated.
+    pub is_deprecated: bool,
     /// Whether this chunk of text was originally a tab character.
     pub is_tab: bool,
 }
@@ -510,6 +537,7 @@ pub(crate) struct DiagnosticEndpoint {
     is_start: bool,
     severity: DiagnosticSeverity,
     is_unnecessary: bool,
+    is_deprecated: bool,
 }
 
 /// A class of characters, used for characterizing a run of text.
@@ -2015,6 +2043,24 @@ impl Buffer {
         }
     }
 
+    /// Reverts a specific transaction in the buffer's undo history with a new transaction,
+    /// keeping the changes made after it.
+    pub fn revert_transaction(
+        &mut self,
+        transaction_id: TransactionId,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+        if let Some(operation) = self.text.revert_transaction(transaction_id) {
+            self.send_operation(Operation::Buffer(operation), cx);
+            self.did_edit(&old_version, was_dirty, cx);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Manually undoes all changes after a given transaction in the buffer's undo history.
     pub fn undo_to_transaction(
         &mut self,
@@ -2069,6 +2115,27 @@ impl Buffer {
         redone
     }
 
+    /// Undoes and redoes changes until the given transaction is the last one applied,
+    /// switching branches of the undo tree if needed. `None` undoes every change.
+    pub fn travel_to_transaction(
+        &mut self,
+        transaction_id: Option<TransactionId>,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        let operations = self.text.travel_to_transaction(transaction_id);
+        let changed = !operations.is_empty();
+        for operation in operations {
+            self.send_operation(Operation::Buffer(operation), cx);
+        }
+        if changed {
+            self.did_edit(&old_version, was_dirty, cx)
+        }
+        changed
+    }
+
     /// Override current completion triggers with the user-provided completion triggers.
     pub fn set_completion_triggers(&mut self, triggers: Vec<String>, cx: &mut ModelContext<Self>) {
         self.completion_triggers = triggers.clone();
@@ -2103,6 +2170,34 @@ impl Buffer {
         self.edit(edits, autoindent_mode, cx);
     }
 
+    /// Asserts that auto-indenting every line of `expected_text` reproduces its
+    /// indentation, so that a language's indents query can be tested against code
+    /// that is indented the way it's usually written. Blank lines are ignored.
+    pub fn assert_autoindents(&mut self, expected_text: &str, cx: &mut ModelContext<Self>) {
+        let unindented_text = expected_text
+            .split('\n')
+            .map(str::trim_start)
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.edit(
+            [(0..self.len(), unindented_text)],
+            Some(AutoindentMode::EachLine),
+            cx,
+        );
+
+        let without_blank_lines = |text: &str| {
+            text.split('\n')
+                .map(|line| if line.trim().is_empty() { "" } else { line })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert_eq!(
+            without_blank_lines(&self.text()),
+            without_blank_lines(expected_text),
+            "auto-indented text doesn't match"
+        );
+    }
+
     pub fn set_group_interval(&mut self, group_interval: Duration) {
         self.text.set_group_interval(group_interval);
     }
@@ -2236,12 +2331,17 @@ impl BufferSnapshot {
         while let Some(mat) = matches.peek() {
             let mut start: Option<Point> = None;
             let mut end: Option<Point> = None;
+            let mut hanging_end: Option<Point> = None;
 
             let config = &indent_configs[mat.grammar_index];
+            let is_hanging = config.hanging_pattern_ixs.contains(&mat.pattern_index);
             for capture in mat.captures {
                 if capture.index == config.indent_capture_ix {
                     start.get_or_insert(Point::from_ts_point(capture.node.start_position()));
                     end.get_or_insert(Point::from_ts_point(capture.node.end_position()));
+                    if is_hanging {
+                        hanging_end = next_token_start(capture.node);
+                    }
                 } else if Some(capture.index) == config.start_capture_ix {
                     start = Some(Point::from_ts_point(capture.node.end_position()));
                 } else if Some(capture.index) == config.end_capture_ix {
@@ -2252,9 +2352,16 @@ impl BufferSnapshot {
             }
 
             matches.advance();
-            if let Some((start, end)) = start.zip(end) {
+            if let Some((start, mut end)) = start.zip(end) {
                 if start.row == end.row {
-                    continue;
+                    // A hanging match, such as a `case` label whose body hasn't been written
+                    // yet, indents the lines between it and the next token.
+                    match hanging_end {
+                        Some(hanging_end) if hanging_end.row > start.row => {
+                            end = Point::new(hanging_end.row, 0);
+                        }
+                        _ => continue,
+                    }
                 }
 
                 let range = start..end;
@@ -2451,12 +2558,14 @@ impl BufferSnapshot {
                     is_start: true,
                     severity: entry.diagnostic.severity,
                     is_unnecessary: entry.diagnostic.is_unnecessary,
+                    is_deprecated: entry.diagnostic.is_deprecated,
                 });
                 diagnostic_endpoints.push(DiagnosticEndpoint {
                     offset: entry.range.end,
                     is_start: false,
                     severity: entry.diagnostic.severity,
                     is_unnecessary: entry.diagnostic.is_unnecessary,
+                    is_deprecated: entry.diagnostic.is_deprecated,
                 });
             }
             diagnostic_endpoints
@@ -2886,6 +2995,65 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the parameters of the item that starts on the given row, and whether it
+    /// returns a value, if the item is matched by the documentation query.
+    pub fn documented_item_at_row(&self, row: u32) -> Option<DocumentedItem> {
+        let start = Point::new(row, 0).to_offset(self);
+        let mut matches = self
+            .syntax
+            .matches(start..self.len(), &self.text, |grammar| {
+                grammar.documentation_config.as_ref().map(|c| &c.query)
+            });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|g| g.documentation_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        // An item can be matched by several patterns, such as one for each parameter.
+        let mut item_range = None;
+        let mut param_ranges = Vec::new();
+        let mut returns = false;
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            let item_node = mat
+                .captures
+                .iter()
+                .find(|capture| capture.index == config.item_capture_ix)
+                .map(|capture| capture.node);
+            if let Some(item_node) = item_node {
+                let item_row = item_node.start_position().row as u32;
+                if item_row > row {
+                    break;
+                }
+                if item_row == row
+                    && *item_range.get_or_insert_with(|| item_node.byte_range())
+                        == item_node.byte_range()
+                {
+                    for capture in mat.captures {
+                        if Some(capture.index) == config.param_capture_ix {
+                            param_ranges.push(capture.node.byte_range());
+                        } else if Some(capture.index) == config.returns_capture_ix {
+                            returns = true;
+                        }
+                    }
+                }
+            }
+            matches.advance();
+        }
+        item_range?;
+
+        param_ranges.sort_unstable_by_key(|range| range.start);
+        param_ranges.dedup();
+        Some(DocumentedItem {
+            params: param_ranges
+                .into_iter()
+                .map(|range| self.text_for_range(range).collect())
+                .collect(),
+            returns,
+        })
+    }
+
     /// Returns anchor ranges for any matches of the redaction query.
     /// The buffer can be associated with multiple languages, and the redaction query associated with each
     /// will be run on the relevant section of the buffer.
@@ -2923,6 +3091,44 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the import statements matched by the imports query of each of the
+    /// buffer's languages, in the order they appear in the buffer.
+    pub fn imports(&self) -> Vec<Import> {
+        let mut syntax_matches = self.syntax.matches(0..self.len(), self, |grammar| {
+            grammar.imports_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.imports_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut imports = Vec::new();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                let mut range = None;
+                let mut path = None;
+                for capture in mat.captures {
+                    if capture.index == config.import_capture_ix {
+                        range = Some(capture.node.byte_range());
+                    } else if capture.index == config.path_capture_ix {
+                        path = Some(capture.node.byte_range());
+                    }
+                }
+                if let Some((range, path)) = range.zip(path) {
+                    imports.push(Import {
+                        range,
+                        path: self.text_for_range(path).collect(),
+                    });
+                }
+            }
+            syntax_matches.advance();
+        }
+        imports.sort_by_key(|import| import.range.start);
+        imports
+    }
+
     /// Returns selections for remote peers intersecting the given range.
     #[allow(clippy::type_complexity)]
     pub fn remote_selections_in_range(
@@ -3139,6 +3345,16 @@ fn indent_size_for_text(text: impl Iterator<Item = char>) -> IndentSize {
     result
 }
 
+/// The start of the first token that follows the given node.
+fn next_token_start(mut node: tree_sitter::Node) -> Option<Point> {
+    loop {
+        if let Some(sibling) = node.next_sibling() {
+            return Some(Point::from_ts_point(sibling.start_position()));
+        }
+        node = node.parent()?;
+    }
+}
+
 impl Clone for BufferSnapshot {
     fn clone(&self) -> Self {
         Self {
@@ -3197,6 +3413,7 @@ impl<'a> BufferChunks<'a> {
             information_depth: 0,
             hint_depth: 0,
             unnecessary_depth: 0,
+            deprecated_depth: 0,
             highlights,
         }
     }
@@ -3251,6 +3468,14 @@ impl<'a> BufferChunks<'a> {
                 self.unnecessary_depth -= 1;
             }
         }
+
+        if endpoint.is_deprecated {
+            if endpoint.is_start {
+                self.deprecated_depth += 1;
+            } else {
+                self.deprecated_depth -= 1;
+            }
+        }
     }
 
     fn current_diagnostic_severity(&self) -> Option<DiagnosticSeverity> {
@@ -3270,6 +3495,10 @@ impl<'a> BufferChunks<'a> {
     fn current_code_is_unnecessary(&self) -> bool {
         self.unnecessary_depth > 0
     }
+
+    fn current_code_is_deprecated(&self) -> bool {
+        self.deprecated_depth > 0
+    }
 }
 
 impl<'a> Iterator for BufferChunks<'a> {
@@ -3342,6 +3571,7 @@ impl<'a> Iterator for BufferChunks<'a> {
                 syntax_highlight_id: highlight_id,
                 diagnostic_severity: self.current_diagnostic_severity(),
                 is_unnecessary: self.current_code_is_unnecessary(),
+                is_deprecated: self.current_code_is_deprecated(),
                 ..Default::default()
             })
         } else {
@@ -3380,6 +3610,7 @@ impl Default for Diagnostic {
             is_primary: false,
             is_disk_based: false,
             is_unnecessary: false,
+            is_deprecated: false,
         }
     }
 }
@@ -3451,6 +3682,16 @@ impl Completion {
     pub fn is_snippet(&self) -> bool {
         self.lsp_completion.insert_text_format == Some(lsp::InsertTextFormat::SNIPPET)
     }
+
+    /// Whether this completion is marked as deprecated.
+    pub fn is_deprecated(&self) -> bool {
+        #[allow(deprecated)]
+        let deprecated = self.lsp_completion.deprecated == Some(true);
+        deprecated
+            || self.lsp_completion.tags.as_ref().map_or(false, |tags| {
+                tags.contains(&lsp::CompletionItemTag::DEPRECATED)
+            })
+    }
 }
 
 pub(crate) fn contiguous_ranges(
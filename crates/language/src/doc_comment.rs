@@ -0,0 +1,147 @@
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// How doc comments are written in a language.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DocCommentConfig {
+    /// The text that expands into a doc comment skeleton when it is typed on the
+    /// line above an item matched by the `documentation` query.
+    pub trigger: Arc<str>,
+    pub style: DocCommentStyle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocCommentStyle {
+    /// A `/** */` block with `@param` and `@returns` tags.
+    JsDoc,
+    /// `///` lines with `# Arguments` and `# Returns` sections.
+    Rustdoc,
+}
+
+/// The parts of an item's signature that are documented by a doc comment skeleton.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DocumentedItem {
+    pub params: Vec<String>,
+    pub returns: bool,
+}
+
+impl DocCommentConfig {
+    /// Returns a snippet documenting the given item, with a tabstop for its description
+    /// and for each of its tags. Every line after the first is prefixed with `indent`.
+    pub fn skeleton(&self, item: &DocumentedItem, indent: &str) -> Option<String> {
+        let mut tabstops = 1..;
+        let mut lines = Vec::new();
+        match self.style {
+            DocCommentStyle::JsDoc => {
+                lines.push("/**".to_string());
+                lines.push(format!(" * ${}", tabstops.next()?));
+                for param in &item.params {
+                    lines.push(format!(" * @param {} ${}", escape(param), tabstops.next()?));
+                }
+                if item.returns {
+                    lines.push(format!(" * @returns ${}", tabstops.next()?));
+                }
+                lines.push(" */".to_string());
+            }
+            DocCommentStyle::Rustdoc => {
+                // A one-line doc comment doesn't need a skeleton.
+                if item.params.is_empty() && !item.returns {
+                    return None;
+                }
+                lines.push(format!("/// ${}", tabstops.next()?));
+                if !item.params.is_empty() {
+                    lines.extend(["///", "/// # Arguments", "///"].map(String::from));
+                    for param in &item.params {
+                        lines.push(format!("/// * `{}` - ${}", escape(param), tabstops.next()?));
+                    }
+                }
+                if item.returns {
+                    lines.extend(["///", "/// # Returns", "///"].map(String::from));
+                    lines.push(format!("/// ${}", tabstops.next()?));
+                }
+            }
+        }
+        Some(lines.join(&format!("\n{indent}")))
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_comment_skeleton() {
+        let item = DocumentedItem {
+            params: vec!["a".into(), "$b".into()],
+            returns: true,
+        };
+
+        let js_doc = DocCommentConfig {
+            trigger: "/**".into(),
+            style: DocCommentStyle::JsDoc,
+        };
+        assert_eq!(
+            js_doc.skeleton(&item, "  ").as_deref(),
+            Some(
+                [
+                    "/**",
+                    "   * $1",
+                    "   * @param a $2",
+                    "   * @param \\$b $3",
+                    "   * @returns $4",
+                    "   */",
+                ]
+                .join("\n")
+                .as_str()
+            )
+        );
+        assert_eq!(
+            js_doc.skeleton(&DocumentedItem::default(), "").as_deref(),
+            Some("/**\n * $1\n */")
+        );
+
+        let rustdoc = DocCommentConfig {
+            trigger: "///".into(),
+            style: DocCommentStyle::Rustdoc,
+        };
+        assert_eq!(
+            rustdoc
+                .skeleton(
+                    &DocumentedItem {
+                        params: vec!["path".into()],
+                        returns: true,
+                    },
+                    ""
+                )
+                .as_deref(),
+            Some(
+                [
+                    "/// $1",
+                    "///",
+                    "/// # Arguments",
+                    "///",
+                    "/// * `path` - $2",
+                    "///",
+                    "/// # Returns",
+                    "///",
+                    "/// $3",
+                ]
+                .join("\n")
+                .as_str()
+            )
+        );
+        assert_eq!(rustdoc.skeleton(&DocumentedItem::default(), ""), None);
+    }
+}
@@ -8,6 +8,7 @@
 //! Notably we do *not* assign a single language to a single file; in real world a single file can consist of multiple programming languages - HTML is a good example of that - and `language` crate tends to reflect that status quo in it's API.
 mod buffer;
 mod diagnostic_set;
+mod doc_comment;
 mod highlight_map;
 mod language_registry;
 pub mod language_settings;
@@ -33,6 +34,7 @@ use serde_json::Value;
 use std::{
     any::Any,
     cell::RefCell,
+    ffi::OsString,
     fmt::Debug,
     hash::Hash,
     mem,
@@ -46,12 +48,13 @@ use std::{
 };
 use syntax_map::SyntaxSnapshot;
 use theme::SyntaxTheme;
-use tree_sitter::{self, wasmtime, Query, WasmStore};
+use tree_sitter::{self, wasmtime, Query, QueryCursor, WasmStore};
 use util::http::HttpClient;
 
 pub use buffer::Operation;
 pub use buffer::*;
 pub use diagnostic_set::DiagnosticEntry;
+pub use doc_comment::{DocCommentConfig, DocCommentStyle, DocumentedItem};
 pub use language_registry::{
     LanguageQueries, LanguageRegistry, LanguageServerBinaryStatus, PendingLanguageServer,
     QUERY_FILENAME_PREFIXES,
@@ -183,6 +186,10 @@ impl CachedLspAdapter {
         self.adapter.can_be_reinstalled()
     }
 
+    pub async fn check_server_binary(&self, binary: &LanguageServerBinary) -> Result<()> {
+        self.adapter.check_server_binary(binary).await
+    }
+
     pub async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
@@ -190,10 +197,33 @@ impl CachedLspAdapter {
         self.adapter.installation_test_binary(container_dir).await
     }
 
+    pub async fn root_specific_arguments(&self, root_path: &Path) -> Vec<OsString> {
+        self.adapter.root_specific_arguments(root_path).await
+    }
+
+    pub async fn root_specific_initialization_options(&self, root_path: &Path) -> Option<Value> {
+        self.adapter
+            .root_specific_initialization_options(root_path)
+            .await
+    }
+
     pub fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
         self.adapter.code_action_kinds()
     }
 
+    pub fn code_action_requires_destination(&self, action: &lsp::CodeAction) -> bool {
+        self.adapter.code_action_requires_destination(action)
+    }
+
+    pub fn code_action_destination(&self, action: &lsp::CodeAction) -> Option<PathBuf> {
+        self.adapter.code_action_destination(action)
+    }
+
+    pub fn set_code_action_destination(&self, action: &mut lsp::CodeAction, destination: &Path) {
+        self.adapter
+            .set_code_action_destination(action, destination)
+    }
+
     pub fn workspace_configuration(&self, workspace_root: &Path, cx: &mut AppContext) -> Value {
         self.adapter.workspace_configuration(workspace_root, cx)
     }
@@ -235,6 +265,11 @@ impl CachedLspAdapter {
 pub trait LspAdapterDelegate: Send + Sync {
     fn show_notification(&self, message: &str, cx: &mut AppContext);
     fn http_client(&self) -> Arc<dyn HttpClient>;
+
+    /// The root of the worktree that the language server is started for, if any.
+    fn worktree_root_path(&self) -> Option<&Path> {
+        None
+    }
 }
 
 #[async_trait]
@@ -292,6 +327,40 @@ pub trait LspAdapter: 'static + Send + Sync {
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary>;
 
+    /// Checks that a downloaded or cached binary of the server is complete before it's
+    /// started, such as by running it with `--version` or checking that the files it
+    /// needs exist. A binary that fails the check is reinstalled.
+    async fn check_server_binary(&self, binary: &LanguageServerBinary) -> Result<()> {
+        // Commands such as `env` or `julia` are looked up on the `PATH` when the server
+        // is started, so that's where they're checked.
+        if is_command_name(&binary.path) {
+            return which::which(&binary.path)
+                .map(drop)
+                .with_context(|| format!("server binary {:?} isn't on the PATH", binary.path));
+        }
+        let metadata = smol::fs::metadata(&binary.path)
+            .await
+            .with_context(|| format!("missing server binary {:?}", binary.path))?;
+        if metadata.is_file() {
+            Ok(())
+        } else {
+            Err(anyhow!("server binary {:?} is not a file", binary.path))
+        }
+    }
+
+    /// Returns the arguments to pass to the server in addition to the binary's own, when
+    /// starting it for the given worktree root. The binary is shared between all worktrees,
+    /// so arguments that depend on the project's layout belong here.
+    async fn root_specific_arguments(&self, _root_path: &Path) -> Vec<OsString> {
+        Vec::new()
+    }
+
+    /// Returns the initialization options that depend on the worktree root the server is
+    /// started for, which are merged into the adapter's own initialization options.
+    async fn root_specific_initialization_options(&self, _root_path: &Path) -> Option<Value> {
+        None
+    }
+
     fn process_diagnostics(&self, _: &mut lsp::PublishDiagnosticsParams) {}
 
     /// A callback called for each [`lsp::CompletionItem`] obtained from LSP server.
@@ -336,6 +405,20 @@ pub trait LspAdapter: 'static + Send + Sync {
         ])
     }
 
+    /// Returns whether applying the given code action requires the user to choose a
+    /// destination file, such as when moving a symbol to another file.
+    fn code_action_requires_destination(&self, _: &lsp::CodeAction) -> bool {
+        false
+    }
+
+    /// Returns the destination file that was chosen for the given code action.
+    fn code_action_destination(&self, _: &lsp::CodeAction) -> Option<PathBuf> {
+        None
+    }
+
+    /// Passes the destination file chosen by the user to the given code action.
+    fn set_code_action_destination(&self, _: &mut lsp::CodeAction, _: &Path) {}
+
     fn disk_based_diagnostic_sources(&self) -> Vec<String> {
         Default::default()
     }
@@ -344,6 +427,41 @@ pub trait LspAdapter: 'static + Send + Sync {
         None
     }
 
+    /// Returns the method of a custom notification that the language server reports its
+    /// status with, which is shown like the progress of its work.
+    fn status_notification(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the message to show for a status notification, or `None` when the server
+    /// has no status to show anymore.
+    fn status_message(&self, _params: Value) -> Option<String> {
+        None
+    }
+
+    /// Returns the commands that make the server check every file of the projects that
+    /// contain the given open files again, for when files change outside of Zed, such as
+    /// after switching branches. Servers that check a whole project per command should
+    /// return one command for each project, rather than one for each file.
+    async fn refresh_diagnostics_commands(&self, _open_paths: Vec<PathBuf>) -> Vec<lsp::Command> {
+        Vec::new()
+    }
+
+    /// Returns `true` if a single instance of the server can work in several worktrees,
+    /// which it's told about through the `workspaceFolders` of the LSP. The server is then
+    /// started once per project rather than once per worktree, with the root-specific
+    /// arguments and options of the worktree that it's started for first.
+    fn supports_workspace_folders(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the given entry of the server's container directory holds a
+    /// version of the server. All but the most recent versions are removed after the
+    /// server is updated.
+    fn is_server_version(&self, _entry: &Path) -> bool {
+        false
+    }
+
     fn language_ids(&self) -> HashMap<String, String> {
         Default::default()
     }
@@ -403,6 +521,14 @@ pub struct LanguageConfig {
     /// Starting and closing characters of a block comment.
     #[serde(default)]
     pub block_comment: Option<(Arc<str>, Arc<str>)>,
+    /// The prefix of the lines within a block comment, such as `* `, which continues
+    /// the comment when a newline is inserted within it.
+    #[serde(default)]
+    pub block_comment_line_prefix: Option<Arc<str>>,
+    /// How doc comment skeletons are inserted above the items matched by the
+    /// `documentation` query.
+    #[serde(default)]
+    pub doc_comment: Option<DocCommentConfig>,
     /// A list of language servers that are allowed to run on subranges of a given language.
     #[serde(default)]
     pub scope_opt_in_language_servers: Vec<String>,
@@ -491,6 +617,8 @@ impl Default for LanguageConfig {
             autoclose_before: Default::default(),
             line_comments: Default::default(),
             block_comment: Default::default(),
+            block_comment_line_prefix: None,
+            doc_comment: None,
             scope_opt_in_language_servers: Default::default(),
             overrides: Default::default(),
             word_characters: Default::default(),
@@ -500,6 +628,14 @@ impl Default for LanguageConfig {
     }
 }
 
+/// Whether the path of a server binary is the name of a command, rather than the path
+/// to a file.
+fn is_command_name(path: &Path) -> bool {
+    let mut components = path.components();
+    matches!(components.next(), Some(std::path::Component::Normal(_)))
+        && components.next().is_none()
+}
+
 fn auto_indent_using_last_non_empty_line_default() -> bool {
     true
 }
@@ -533,6 +669,8 @@ pub struct FakeLspAdapter {
     pub disk_based_diagnostics_progress_token: Option<String>,
     pub disk_based_diagnostics_sources: Vec<String>,
     pub prettier_plugins: Vec<&'static str>,
+    pub supports_workspace_folders: bool,
+    pub refresh_diagnostics_command: Option<&'static str>,
 }
 
 /// Configuration of handling bracket pairs for a given language.
@@ -610,9 +748,11 @@ pub struct Grammar {
     pub(crate) highlights_query: Option<Query>,
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
+    pub(crate) imports_config: Option<ImportsConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
+    pub(crate) documentation_config: Option<DocumentationConfig>,
     pub(crate) injection_config: Option<InjectionConfig>,
     pub(crate) override_config: Option<OverrideConfig>,
     pub(crate) highlight_map: Mutex<HighlightMap>,
@@ -624,6 +764,9 @@ struct IndentConfig {
     start_capture_ix: Option<u32>,
     end_capture_ix: Option<u32>,
     outdent_capture_ix: Option<u32>,
+    /// The patterns that set the `hanging` property, whose indentation is applied to
+    /// the lines that follow them even when they end on the line where they start.
+    hanging_pattern_ixs: Vec<usize>,
 }
 
 pub struct OutlineConfig {
@@ -644,6 +787,13 @@ pub struct EmbeddingConfig {
     pub keep_capture_ix: Option<u32>,
 }
 
+struct DocumentationConfig {
+    query: Query,
+    item_capture_ix: u32,
+    param_capture_ix: Option<u32>,
+    returns_capture_ix: Option<u32>,
+}
+
 struct InjectionConfig {
     query: Query,
     content_capture_ix: u32,
@@ -656,6 +806,12 @@ struct RedactionConfig {
     pub redaction_capture_ix: u32,
 }
 
+struct ImportsConfig {
+    pub query: Query,
+    pub import_capture_ix: u32,
+    pub path_capture_ix: u32,
+}
+
 struct OverrideConfig {
     query: Query,
     values: HashMap<u32, (String, LanguageConfigOverride)>,
@@ -684,10 +840,12 @@ impl Language {
                     brackets_config: None,
                     outline_config: None,
                     embedding_config: None,
+                    documentation_config: None,
                     indents_config: None,
                     injection_config: None,
                     override_config: None,
                     redactions_config: None,
+                    imports_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -734,6 +892,11 @@ impl Language {
                 .with_embedding_query(query.as_ref())
                 .context("Error loading embedding query")?;
         }
+        if let Some(query) = queries.documentation {
+            self = self
+                .with_documentation_query(query.as_ref())
+                .context("Error loading documentation query")?;
+        }
         if let Some(query) = queries.injections {
             self = self
                 .with_injection_query(query.as_ref())
@@ -749,6 +912,11 @@ impl Language {
                 .with_redaction_query(query.as_ref())
                 .context("Error loading redaction query")?;
         }
+        if let Some(query) = queries.imports {
+            self = self
+                .with_imports_query(query.as_ref())
+                .context("Error loading imports query")?;
+        }
         Ok(self)
     }
 
@@ -817,6 +985,31 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_documentation_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut item_capture_ix = None;
+        let mut param_capture_ix = None;
+        let mut returns_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("item", &mut item_capture_ix),
+                ("param", &mut param_capture_ix),
+                ("returns", &mut returns_capture_ix),
+            ],
+        );
+        if let Some(item_capture_ix) = item_capture_ix {
+            grammar.documentation_config = Some(DocumentationConfig {
+                query,
+                item_capture_ix,
+                param_capture_ix,
+                returns_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
     pub fn with_brackets_query(mut self, source: &str) -> Result<Self> {
         let grammar = self.grammar_mut();
         let query = Query::new(&grammar.ts_language, source)?;
@@ -856,12 +1049,21 @@ impl Language {
             ],
         );
         if let Some(indent_capture_ix) = indent_capture_ix {
+            let hanging_pattern_ixs = (0..query.pattern_count())
+                .filter(|ix| {
+                    query
+                        .property_settings(*ix)
+                        .iter()
+                        .any(|setting| setting.key.as_ref() == "hanging")
+                })
+                .collect();
             grammar.indents_config = Some(IndentConfig {
                 query,
                 indent_capture_ix,
                 start_capture_ix,
                 end_capture_ix,
                 outdent_capture_ix,
+                hanging_pattern_ixs,
             });
         }
         Ok(self)
@@ -995,6 +1197,30 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_imports_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut import_capture_ix = None;
+        let mut path_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("import", &mut import_capture_ix),
+                ("path", &mut path_capture_ix),
+            ],
+        );
+
+        if let Some((import_capture_ix, path_capture_ix)) = import_capture_ix.zip(path_capture_ix) {
+            grammar.imports_config = Some(ImportsConfig {
+                query,
+                import_capture_ix,
+                path_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> &mut Grammar {
         Arc::get_mut(self.grammar.as_mut().unwrap()).unwrap()
     }
@@ -1117,6 +1343,29 @@ impl Language {
         self.grammar.as_ref()
     }
 
+    /// Returns the paths of the import statements in the given text, as matched by
+    /// the language's imports query, without creating a buffer for it.
+    pub fn import_paths(&self, text: &str) -> Vec<String> {
+        let Some(grammar) = self.grammar() else {
+            return Vec::new();
+        };
+        let Some(config) = grammar.imports_config.as_ref() else {
+            return Vec::new();
+        };
+        let tree = grammar.parse_text(&Rope::from(text), None);
+        let mut cursor = QueryCursor::new();
+        cursor
+            .matches(&config.query, tree.root_node(), text.as_bytes())
+            .filter_map(|mat| {
+                let capture = mat
+                    .captures
+                    .iter()
+                    .find(|capture| capture.index == config.path_capture_ix)?;
+                Some(text[capture.node.byte_range()].to_string())
+            })
+            .collect()
+    }
+
     pub fn default_scope(self: &Arc<Self>) -> LanguageScope {
         LanguageScope {
             language: self.clone(),
@@ -1151,6 +1400,14 @@ impl LanguageScope {
         .map(|e| (&e.0, &e.1))
     }
 
+    pub fn block_comment_line_prefix(&self) -> Option<&Arc<str>> {
+        self.language.config.block_comment_line_prefix.as_ref()
+    }
+
+    pub fn doc_comment(&self) -> Option<&DocCommentConfig> {
+        self.language.config.doc_comment.as_ref()
+    }
+
     /// Returns a list of language-specific word characters.
     ///
     /// By default, Zed treats alphanumeric characters (and '_') as word characters for
@@ -1205,6 +1462,15 @@ impl LanguageScope {
         }
     }
 
+    /// The name of the `overrides` query capture that this scope is within, such as
+    /// `comment` or `string`.
+    pub fn override_name(&self) -> Option<&str> {
+        let id = self.override_id?;
+        let grammar = self.language.grammar.as_ref()?;
+        let override_config = grammar.override_config.as_ref()?;
+        override_config.values.get(&id).map(|e| e.0.as_str())
+    }
+
     fn config_override(&self) -> Option<&LanguageConfigOverride> {
         let id = self.override_id?;
         let grammar = self.language.grammar.as_ref()?;
@@ -1240,6 +1506,11 @@ impl Grammar {
         self.id
     }
 
+    /// Whether the indentation of each line can be derived from the syntax tree.
+    pub fn has_indents_query(&self) -> bool {
+        self.indents_config.is_some()
+    }
+
     fn parse_text(&self, text: &Rope, old_tree: Option<Tree>) -> Tree {
         PARSER.with(|parser| {
             let mut parser = parser.borrow_mut();
@@ -1326,6 +1597,8 @@ impl Default for FakeLspAdapter {
             initialization_options: None,
             disk_based_diagnostics_sources: Vec::new(),
             prettier_plugins: Vec::new(),
+            supports_workspace_folders: false,
+            refresh_diagnostics_command: None,
         }
     }
 }
@@ -1386,6 +1659,24 @@ impl LspAdapter for Arc<FakeLspAdapter> {
     fn prettier_plugins(&self) -> &[&'static str] {
         &self.prettier_plugins
     }
+
+    fn supports_workspace_folders(&self) -> bool {
+        self.supports_workspace_folders
+    }
+
+    async fn refresh_diagnostics_commands(&self, open_paths: Vec<PathBuf>) -> Vec<lsp::Command> {
+        let Some(command) = self.refresh_diagnostics_command else {
+            return Vec::new();
+        };
+        open_paths
+            .into_iter()
+            .map(|path| lsp::Command {
+                title: command.into(),
+                command: command.into(),
+                arguments: Some(vec![serde_json::json!(path)]),
+            })
+            .collect()
+    }
 }
 
 fn get_capture_indices(query: &Query, captures: &mut [(&str, &mut Option<u32>)]) {
@@ -1527,4 +1818,45 @@ mod tests {
         // Loading an unknown language returns an error.
         assert!(languages.language_for_name("Unknown").await.is_err());
     }
+
+    #[gpui::test]
+    async fn test_custom_lsp_adapters(cx: &mut TestAppContext) {
+        let mut languages = LanguageRegistry::test();
+        languages.set_executor(cx.executor());
+        let languages = Arc::new(languages);
+        languages.register_native_grammars([("rust", tree_sitter_rust::language())]);
+        languages.register_test_language(LanguageConfig {
+            name: "Rust".into(),
+            grammar: Some("rust".into()),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let adapter_names = |language: Arc<Language>| {
+            language
+                .lsp_adapters()
+                .iter()
+                .map(|adapter| adapter.name.0.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let rust = languages.language_for_name("Rust").await.unwrap();
+        assert!(adapter_names(rust).is_empty());
+
+        // Setting the servers reloads the language with them.
+        let custom_adapter: Arc<dyn LspAdapter> = Arc::new(FakeLspAdapter {
+            name: "custom-language-server",
+            ..Default::default()
+        });
+        languages
+            .set_custom_lsp_adapters(HashMap::from_iter([("Rust".into(), vec![custom_adapter])]));
+        let rust = languages.language_for_name("Rust").await.unwrap();
+        assert_eq!(adapter_names(rust), ["custom-language-server"]);
+
+        languages.set_custom_lsp_adapters(HashMap::default());
+        let rust = languages.language_for_name("Rust").await.unwrap();
+        assert!(adapter_names(rust).is_empty());
+    }
 }
@@ -3,11 +3,11 @@ use crate::{
     LspAdapterDelegate, PARSER, PLAIN_TEXT,
 };
 use anyhow::{anyhow, Context as _, Result};
-use collections::{hash_map, HashMap};
+use collections::{hash_map, HashMap, HashSet};
 use futures::{
     channel::{mpsc, oneshot},
-    future::Shared,
-    FutureExt as _, TryFutureExt as _,
+    future::{AbortHandle, AbortRegistration, Abortable, Aborted, BoxFuture, Shared},
+    AsyncRead, FutureExt as _, StreamExt as _, TryFutureExt as _,
 };
 use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, Task};
 use lsp::{LanguageServerBinary, LanguageServerId};
@@ -16,15 +16,24 @@ use postage::watch;
 use std::{
     borrow::Cow,
     ffi::OsStr,
+    io, iter,
     ops::Not,
     path::{Path, PathBuf},
+    pin::Pin,
     sync::Arc,
+    task::Poll,
 };
 use sum_tree::Bias;
 use text::{Point, Rope};
 use theme::Theme;
 use unicase::UniCase;
-use util::{paths::PathExt, post_inc, ResultExt, TryFutureExt as _, UnwrapFuture};
+use util::{
+    audit_log::{self, AuditEvent, AuditEventKind},
+    fs::remove_stale_matching,
+    http::{self, AsyncBody, HttpClient, Request, Response},
+    paths::PathExt,
+    post_inc, ResultExt, TryFutureExt as _, UnwrapFuture,
+};
 
 pub struct LanguageRegistry {
     state: RwLock<LanguageRegistryState>,
@@ -34,6 +43,8 @@ pub struct LanguageRegistry {
     lsp_binary_paths: Mutex<
         HashMap<LanguageServerName, Shared<Task<Result<LanguageServerBinary, Arc<anyhow::Error>>>>>,
     >,
+    /// The downloads of language servers that are in progress, by the servers' names.
+    lsp_binary_downloads: Mutex<HashMap<LanguageServerName, AbortHandle>>,
     executor: Option<BackgroundExecutor>,
     lsp_binary_status_tx: LspBinaryStatusSender,
 }
@@ -42,6 +53,8 @@ struct LanguageRegistryState {
     next_language_server_id: usize,
     languages: Vec<Arc<Language>>,
     available_languages: Vec<AvailableLanguage>,
+    /// The language servers configured in the user's settings, by language name.
+    custom_lsp_adapters: HashMap<Arc<str>, Vec<Arc<dyn LspAdapter>>>,
     grammars: HashMap<Arc<str>, AvailableGrammar>,
     next_available_language_id: AvailableLanguageId,
     loading_languages: HashMap<AvailableLanguageId, Vec<oneshot::Sender<Result<Arc<Language>>>>>,
@@ -54,10 +67,18 @@ struct LanguageRegistryState {
 #[derive(Clone)]
 pub enum LanguageServerBinaryStatus {
     CheckingForUpdate,
-    Downloading,
+    /// The server is being downloaded and installed. Only the bytes that the adapter
+    /// downloads through its delegate's HTTP client are counted.
+    Downloading {
+        downloaded_bytes: u64,
+        total_bytes: Option<u64>,
+    },
     Downloaded,
     Cached,
-    Failed { error: String },
+    Failed {
+        error: String,
+    },
+    Cancelled,
 }
 
 pub struct PendingLanguageServer {
@@ -95,9 +116,11 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("outline", |q| &mut q.outline),
     ("indents", |q| &mut q.indents),
     ("embedding", |q| &mut q.embedding),
+    ("documentation", |q| &mut q.documentation),
     ("injections", |q| &mut q.injections),
     ("overrides", |q| &mut q.overrides),
     ("redactions", |q| &mut q.redactions),
+    ("imports", |q| &mut q.imports),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -108,9 +131,11 @@ pub struct LanguageQueries {
     pub indents: Option<Cow<'static, str>>,
     pub outline: Option<Cow<'static, str>>,
     pub embedding: Option<Cow<'static, str>>,
+    pub documentation: Option<Cow<'static, str>>,
     pub injections: Option<Cow<'static, str>>,
     pub overrides: Option<Cow<'static, str>>,
     pub redactions: Option<Cow<'static, str>>,
+    pub imports: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
@@ -125,6 +150,7 @@ impl LanguageRegistry {
                 next_language_server_id: 0,
                 languages: vec![PLAIN_TEXT.clone()],
                 available_languages: Default::default(),
+                custom_lsp_adapters: Default::default(),
                 grammars: Default::default(),
                 next_available_language_id: 0,
                 loading_languages: Default::default(),
@@ -136,6 +162,7 @@ impl LanguageRegistry {
             language_server_download_dir: None,
             login_shell_env_loaded: login_shell_env_loaded.shared(),
             lsp_binary_paths: Default::default(),
+            lsp_binary_downloads: Default::default(),
             executor: None,
             lsp_binary_status_tx: Default::default(),
         }
@@ -204,6 +231,34 @@ impl LanguageRegistry {
         });
     }
 
+    /// Sets the language servers that the user configured for each language, which are
+    /// started in addition to the language's own, and reloads the languages whose servers
+    /// were set before or are set now.
+    pub fn set_custom_lsp_adapters(
+        &self,
+        lsp_adapters: HashMap<Arc<str>, Vec<Arc<dyn LspAdapter>>>,
+    ) {
+        let previous_lsp_adapters = {
+            let state = &mut *self.state.write();
+            let languages_to_reload = state
+                .custom_lsp_adapters
+                .keys()
+                .chain(lsp_adapters.keys())
+                .cloned()
+                .collect::<Vec<_>>();
+            let previous_lsp_adapters =
+                std::mem::replace(&mut state.custom_lsp_adapters, lsp_adapters);
+            state.reload_languages(&languages_to_reload, &[]);
+            previous_lsp_adapters
+        };
+
+        // The previous servers' commands may have changed since their binaries were cached.
+        let mut lsp_binary_paths = self.lsp_binary_paths.lock();
+        for adapter in previous_lsp_adapters.values().flatten() {
+            lsp_binary_paths.remove(&adapter.name());
+        }
+    }
+
     /// Adds grammars to the registry. Language configurations reference a grammar by name. The
     /// grammar controls how the source code is parsed.
     pub fn register_native_grammars(
@@ -341,6 +396,11 @@ impl LanguageRegistry {
                 .rfind(|l| !l.loaded && callback(&l.name, &l.matcher))
                 .cloned()
             {
+                let custom_lsp_adapters = state
+                    .custom_lsp_adapters
+                    .get(&language.name)
+                    .cloned()
+                    .unwrap_or_default();
                 match state.loading_languages.entry(language.id) {
                     hash_map::Entry::Occupied(mut entry) => entry.get_mut().push(tx),
                     hash_map::Entry::Vacant(entry) => {
@@ -358,8 +418,13 @@ impl LanguageRegistry {
                                         None
                                     };
 
+                                    let lsp_adapters = language
+                                        .lsp_adapters
+                                        .into_iter()
+                                        .chain(custom_lsp_adapters)
+                                        .collect();
                                     Language::new(config, grammar)
-                                        .with_lsp_adapters(language.lsp_adapters)
+                                        .with_lsp_adapters(lsp_adapters)
                                         .await
                                         .with_queries(queries)
                                 }
@@ -552,30 +617,64 @@ impl LanguageRegistry {
                         let adapter = adapter.clone();
                         let language = language.clone();
                         let delegate = delegate.clone();
+                        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+                        this.lsp_binary_downloads
+                            .lock()
+                            .insert(adapter.name.clone(), abort_handle);
+                        let this = this.clone();
                         cx.spawn(|cx| {
-                            get_binary(
-                                adapter,
-                                language,
-                                delegate,
-                                container_dir,
-                                lsp_binary_statuses,
-                                cx,
-                            )
+                            async move {
+                                let binary = get_binary_unless_cancelled(
+                                    adapter.clone(),
+                                    language,
+                                    delegate,
+                                    container_dir,
+                                    lsp_binary_statuses,
+                                    abort_registration,
+                                    cx,
+                                )
+                                .await;
+                                this.lsp_binary_downloads.lock().remove(&adapter.name);
+                                // Failed downloads are attempted again when the server is
+                                // started next.
+                                if binary.is_err() {
+                                    this.lsp_binary_paths.lock().remove(&adapter.name);
+                                }
+                                binary
+                            }
                             .map_err(Arc::new)
                         })
                         .shared()
                     })
                     .clone();
 
-                let binary = match entry.await {
+                let mut binary = match entry.await {
                     Ok(binary) => binary,
                     Err(err) => anyhow::bail!("{err}"),
                 };
+                binary
+                    .arguments
+                    .extend(adapter.root_specific_arguments(&root_path).await);
 
                 if let Some(task) = adapter.will_start_server(&delegate, &mut cx) {
                     task.await?;
                 }
 
+                audit_log::record(
+                    AuditEvent::new(
+                        AuditEventKind::Command,
+                        "language servers",
+                        format!("Started {}", adapter.name.0),
+                    )
+                    .project_root(root_path.as_ref())
+                    .details(
+                        std::iter::once(binary.path.as_os_str())
+                            .chain(binary.arguments.iter().map(|argument| argument.as_os_str()))
+                            .map(|part| part.to_string_lossy())
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    ),
+                );
                 lsp::LanguageServer::new(
                     stderr_capture,
                     server_id,
@@ -594,6 +693,24 @@ impl LanguageRegistry {
         })
     }
 
+    /// Cancels the downloads of the given language's servers that are in progress. The
+    /// servers are downloaded again the next time that they're started.
+    pub fn cancel_language_server_downloads(&self, language: &Language) {
+        let downloads = self.lsp_binary_downloads.lock();
+        for adapter in language.lsp_adapters() {
+            if let Some(download) = downloads.get(&adapter.name) {
+                download.abort();
+            }
+        }
+    }
+
+    /// Forgets the binary that was found for the given server, so that it's looked up
+    /// again the next time that the server is started, such as after a setting that
+    /// the binary depends on has changed.
+    pub fn forget_server_binary(&self, name: &LanguageServerName) {
+        self.lsp_binary_paths.lock().remove(name);
+    }
+
     pub fn language_server_binary_statuses(
         &self,
     ) -> mpsc::UnboundedReceiver<(Arc<Language>, LanguageServerBinaryStatus)> {
@@ -727,6 +844,55 @@ impl LspBinaryStatusSender {
     }
 }
 
+/// Gets the binary of a server, unless its download is cancelled. Whatever a cancelled
+/// download had written to the container directory is incomplete, so it's removed rather
+/// than being taken for an installed version later.
+async fn get_binary_unless_cancelled(
+    adapter: Arc<CachedLspAdapter>,
+    language: Arc<Language>,
+    delegate: Arc<dyn LspAdapterDelegate>,
+    container_dir: Arc<Path>,
+    statuses: LspBinaryStatusSender,
+    abort_registration: AbortRegistration,
+    cx: AsyncAppContext,
+) -> Result<LanguageServerBinary> {
+    let entries_before = container_entries(&container_dir).await;
+    let binary = Abortable::new(
+        get_binary(
+            adapter.clone(),
+            language.clone(),
+            delegate,
+            container_dir.clone(),
+            statuses.clone(),
+            cx,
+        ),
+        abort_registration,
+    )
+    .await;
+    match binary {
+        Ok(binary) => binary,
+        Err(Aborted) => {
+            for entry in container_entries(&container_dir).await {
+                if !entries_before.contains(&entry) {
+                    remove_entry(&entry).await;
+                }
+            }
+            statuses.send(language, LanguageServerBinaryStatus::Cancelled);
+            Err(anyhow!("download of {} was cancelled", adapter.name.0))
+        }
+    }
+}
+
+async fn container_entries(container_dir: &Path) -> HashSet<PathBuf> {
+    let mut entries = HashSet::default();
+    if let Ok(mut dir) = smol::fs::read_dir(container_dir).await {
+        while let Some(Ok(entry)) = dir.next().await {
+            entries.insert(entry.path());
+        }
+    }
+    entries
+}
+
 async fn get_binary(
     adapter: Arc<CachedLspAdapter>,
     language: Arc<Language>,
@@ -745,39 +911,137 @@ async fn get_binary(
         task.await?;
     }
 
-    let binary = fetch_latest_binary(
+    let mut binary = fetch_latest_binary(
         adapter.clone(),
         language.clone(),
-        delegate.as_ref(),
+        delegate.clone(),
         &container_dir,
         statuses.clone(),
     )
     .await;
 
+    // An installation that failed halfway can leave a broken binary behind, which the
+    // adapter would otherwise keep using, so that version is installed again. Only the
+    // files that Zed installed are removed, and only those of the broken version.
+    let check_error = match binary.as_ref() {
+        Ok(binary) => check_binary(&adapter, binary)
+            .await
+            .err()
+            .map(|error| (installed_entry(binary, &container_dir), error)),
+        Err(_) => None,
+    };
+    if let Some((entry, error)) = check_error {
+        let Some(entry) = entry else {
+            log::warn!(
+                "binary of {} may be broken, but it isn't installed by Zed: {error:#}",
+                adapter.name.0
+            );
+            return binary;
+        };
+        log::warn!(
+            "reinstalling {}, whose binary is broken: {error:#}",
+            adapter.name.0
+        );
+        remove_entry(&entry).await;
+        binary = fetch_latest_binary(
+            adapter.clone(),
+            language.clone(),
+            delegate.clone(),
+            &container_dir,
+            statuses.clone(),
+        )
+        .await;
+        if let Ok(reinstalled_binary) = binary.as_ref() {
+            if let Err(error) = check_binary(&adapter, reinstalled_binary).await {
+                binary = Err(error.context("reinstalled server binary is broken"));
+            }
+        }
+    }
+
+    if let Ok(binary) = binary.as_ref() {
+        let path_in_use = installed_path(binary, &container_dir).unwrap_or(&*container_dir);
+        remove_stale_matching(
+            &container_dir,
+            SERVER_VERSIONS_TO_KEEP,
+            path_in_use,
+            |entry| adapter.adapter.is_server_version(entry),
+        )
+        .await;
+    }
+
     if let Err(error) = binary.as_ref() {
         if let Some(binary) = adapter
             .cached_server_binary(container_dir.to_path_buf(), delegate.as_ref())
             .await
         {
-            statuses.send(language.clone(), LanguageServerBinaryStatus::Cached);
-            return Ok(binary);
-        } else {
-            statuses.send(
-                language.clone(),
-                LanguageServerBinaryStatus::Failed {
-                    error: format!("{:?}", error),
-                },
-            );
+            match check_binary(&adapter, &binary).await {
+                Ok(()) => {
+                    statuses.send(language.clone(), LanguageServerBinaryStatus::Cached);
+                    return Ok(binary);
+                }
+                Err(check_error) => {
+                    log::error!(
+                        "cached binary of {} is broken: {check_error:#}",
+                        adapter.name.0
+                    );
+                }
+            }
         }
+        statuses.send(
+            language.clone(),
+            LanguageServerBinaryStatus::Failed {
+                error: format!("{:?}", error),
+            },
+        );
     }
 
     binary
 }
 
+/// Returns the file of the binary that Zed installed into the container directory, if
+/// any. Servers that run on Node are passed their script as an argument.
+fn installed_path<'a>(binary: &'a LanguageServerBinary, container_dir: &Path) -> Option<&'a Path> {
+    iter::once(binary.path.as_os_str())
+        .chain(binary.arguments.iter().map(|argument| argument.as_os_str()))
+        .map(Path::new)
+        .find(|path| path.starts_with(container_dir) && *path != container_dir)
+}
+
+/// Returns the entry of the container directory that holds the installed binary, which
+/// is usually the directory of its version.
+fn installed_entry(binary: &LanguageServerBinary, container_dir: &Path) -> Option<PathBuf> {
+    let path = installed_path(binary, container_dir)?;
+    let entry = path.strip_prefix(container_dir).ok()?.components().next()?;
+    Some(container_dir.join(entry))
+}
+
+async fn remove_entry(path: &Path) {
+    let result = if path.is_dir() {
+        smol::fs::remove_dir_all(path).await
+    } else {
+        smol::fs::remove_file(path).await
+    };
+    if let Err(error) = result {
+        if error.kind() != io::ErrorKind::NotFound {
+            log::error!("failed to remove {path:?}: {error}");
+        }
+    }
+}
+
+/// Checks the binary of a server that Zed installs. Servers that are installed by the
+/// user aren't checked, since Zed can't fix them.
+async fn check_binary(adapter: &CachedLspAdapter, binary: &LanguageServerBinary) -> Result<()> {
+    if adapter.can_be_reinstalled() {
+        adapter.check_server_binary(binary).await
+    } else {
+        Ok(())
+    }
+}
+
 async fn fetch_latest_binary(
     adapter: Arc<CachedLspAdapter>,
     language: Arc<Language>,
-    delegate: &dyn LspAdapterDelegate,
+    delegate: Arc<dyn LspAdapterDelegate>,
     container_dir: &Path,
     lsp_binary_statuses_tx: LspBinaryStatusSender,
 ) -> Result<LanguageServerBinary> {
@@ -787,13 +1051,407 @@ async fn fetch_latest_binary(
         LanguageServerBinaryStatus::CheckingForUpdate,
     );
 
-    let version_info = adapter.fetch_latest_server_version(delegate).await?;
-    lsp_binary_statuses_tx.send(language.clone(), LanguageServerBinaryStatus::Downloading);
+    let version_info = adapter
+        .fetch_latest_server_version(delegate.as_ref())
+        .await?;
+    let progress = Arc::new(DownloadProgress {
+        language: language.clone(),
+        statuses: lsp_binary_statuses_tx.clone(),
+        state: Default::default(),
+    });
+    progress.report();
+    let delegate = DownloadProgressDelegate {
+        http_client: Arc::new(DownloadProgressHttpClient {
+            client: delegate.http_client(),
+            progress,
+        }),
+        delegate,
+    };
+    audit_log::record(
+        AuditEvent::new(
+            AuditEventKind::Download,
+            "language servers",
+            format!("Latest version of {}", adapter.name.0),
+        )
+        .details(format!("into {}", container_dir.display())),
+    );
 
     let binary = adapter
-        .fetch_server_binary(version_info, container_dir.to_path_buf(), delegate)
+        .fetch_server_binary(version_info, container_dir.to_path_buf(), &delegate)
         .await?;
     lsp_binary_statuses_tx.send(language.clone(), LanguageServerBinaryStatus::Downloaded);
 
     Ok(binary)
 }
+
+/// How many versions of each language server are kept, so that the previous version
+/// can still be used if the latest one is removed or broken.
+const SERVER_VERSIONS_TO_KEEP: usize = 2;
+
+/// How many more bytes must be downloaded before the progress of a download is
+/// reported again, so that the status bar isn't re-rendered for every chunk.
+const DOWNLOAD_PROGRESS_INTERVAL: u64 = 256 * 1024;
+
+/// Reports the progress of a language server's download, across all of the requests
+/// that its adapter makes.
+struct DownloadProgress {
+    language: Arc<Language>,
+    statuses: LspBinaryStatusSender,
+    state: Mutex<DownloadProgressState>,
+}
+
+#[derive(Default)]
+struct DownloadProgressState {
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    reported_bytes: u64,
+}
+
+impl DownloadProgress {
+    fn add_total_bytes(&self, bytes: u64) {
+        *self.state.lock().total_bytes.get_or_insert(0) += bytes;
+        self.report();
+    }
+
+    fn add_downloaded_bytes(&self, bytes: u64) {
+        let mut state = self.state.lock();
+        state.downloaded_bytes += bytes;
+        if state.downloaded_bytes - state.reported_bytes >= DOWNLOAD_PROGRESS_INTERVAL {
+            drop(state);
+            self.report();
+        }
+    }
+
+    fn report(&self) {
+        let mut state = self.state.lock();
+        state.reported_bytes = state.downloaded_bytes;
+        let status = LanguageServerBinaryStatus::Downloading {
+            downloaded_bytes: state.downloaded_bytes,
+            total_bytes: state.total_bytes,
+        };
+        drop(state);
+        self.statuses.send(self.language.clone(), status);
+    }
+}
+
+/// A response body that counts the bytes that are read from it.
+struct DownloadProgressReader {
+    body: AsyncBody,
+    progress: Arc<DownloadProgress>,
+}
+
+impl AsyncRead for DownloadProgressReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.body).poll_read(cx, buf);
+        if let Poll::Ready(Ok(bytes)) = &poll {
+            self.progress.add_downloaded_bytes(*bytes as u64);
+        }
+        poll
+    }
+}
+
+struct DownloadProgressHttpClient {
+    client: Arc<dyn HttpClient>,
+    progress: Arc<DownloadProgress>,
+}
+
+impl HttpClient for DownloadProgressHttpClient {
+    fn send(
+        &self,
+        request: Request<AsyncBody>,
+    ) -> BoxFuture<Result<Response<AsyncBody>, http::Error>> {
+        let response = self.client.send(request);
+        let progress = self.progress.clone();
+        async move {
+            let response = response.await?;
+            if let Some(content_length) = response
+                .headers()
+                .get("content-length")
+                .and_then(|length| length.to_str().ok()?.parse().ok())
+            {
+                progress.add_total_bytes(content_length);
+            }
+            Ok(response
+                .map(|body| AsyncBody::from_reader(DownloadProgressReader { body, progress })))
+        }
+        .boxed()
+    }
+}
+
+/// The delegate that an adapter downloads its server with, whose HTTP client reports
+/// the download's progress.
+struct DownloadProgressDelegate {
+    delegate: Arc<dyn LspAdapterDelegate>,
+    http_client: Arc<dyn HttpClient>,
+}
+
+impl LspAdapterDelegate for DownloadProgressDelegate {
+    fn show_notification(&self, message: &str, cx: &mut AppContext) {
+        self.delegate.show_notification(message, cx)
+    }
+
+    fn http_client(&self) -> Arc<dyn HttpClient> {
+        self.http_client.clone()
+    }
+
+    fn worktree_root_path(&self) -> Option<&Path> {
+        self.delegate.worktree_root_path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use gpui::TestAppContext;
+    use std::{
+        any::Any,
+        sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    };
+    use util::http::FakeHttpClient;
+
+    #[gpui::test]
+    async fn test_get_binary_on_path(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let container_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(container_dir.path().join("other")).unwrap();
+
+        // A command that's found on the PATH is used as it is.
+        let adapter = TestAdapter::new(|_, _| Ok(binary("sh")), None);
+        let result = get_test_binary(&adapter, container_dir.path(), cx).await;
+        assert_eq!(result.unwrap().path, PathBuf::from("sh"));
+        assert_eq!(adapter.install_count(), 1);
+        assert!(container_dir.path().join("other").exists());
+
+        // A command that isn't on the PATH isn't Zed's to fix, so nothing is removed.
+        let adapter = TestAdapter::new(|_, _| Ok(binary("zed-missing-server")), None);
+        get_test_binary(&adapter, container_dir.path(), cx)
+            .await
+            .unwrap();
+        assert_eq!(adapter.install_count(), 1);
+        assert!(container_dir.path().join("other").exists());
+    }
+
+    #[gpui::test]
+    async fn test_get_binary_reinstalls_broken_version(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let container_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(container_dir.path().join("server-1")).unwrap();
+        std::fs::write(container_dir.path().join("server-1/server"), "").unwrap();
+
+        // The first installation leaves a directory where the binary should be.
+        let adapter = TestAdapter::new(
+            |container_dir, attempt| {
+                let version_dir = container_dir.join("server-2");
+                if attempt == 0 {
+                    std::fs::create_dir_all(version_dir.join("server"))?;
+                } else {
+                    // Only the broken version was removed.
+                    assert!(!version_dir.exists());
+                    std::fs::create_dir_all(&version_dir)?;
+                    std::fs::write(version_dir.join("server"), "")?;
+                }
+                Ok(binary(version_dir.join("server")))
+            },
+            None,
+        );
+        let result = get_test_binary(&adapter, container_dir.path(), cx).await;
+        assert_eq!(
+            result.unwrap().path,
+            container_dir.path().join("server-2/server")
+        );
+        assert_eq!(adapter.install_count(), 2);
+        assert!(container_dir.path().join("server-1/server").is_file());
+    }
+
+    #[gpui::test]
+    async fn test_get_binary_falls_back_to_cached_binary(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let container_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(container_dir.path().join("server-1")).unwrap();
+        std::fs::write(container_dir.path().join("server-1/server"), "").unwrap();
+
+        let adapter = TestAdapter::new(
+            |_, _| Err(anyhow!("offline")),
+            Some(container_dir.path().join("server-1/server")),
+        );
+        let result = get_test_binary(&adapter, container_dir.path(), cx).await;
+        assert_eq!(
+            result.unwrap().path,
+            container_dir.path().join("server-1/server")
+        );
+
+        // A broken cached binary isn't used.
+        let adapter = TestAdapter::new(
+            |_, _| Err(anyhow!("offline")),
+            Some(container_dir.path().join("server-2/server")),
+        );
+        get_test_binary(&adapter, container_dir.path(), cx)
+            .await
+            .unwrap_err();
+        assert!(container_dir.path().join("server-1/server").is_file());
+    }
+
+    #[gpui::test]
+    async fn test_cancelled_download_removes_partial_files(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let container_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(container_dir.path().join("server-1")).unwrap();
+
+        let (adapter, installed) = TestAdapter::hanging(|container_dir, _| {
+            std::fs::create_dir(container_dir.join("server-2"))?;
+            std::fs::write(container_dir.join("server-2/server"), "partial")?;
+            Ok(binary(container_dir.join("server-2/server")))
+        });
+        let statuses = LspBinaryStatusSender::default();
+        let mut status_events = statuses.subscribe();
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let adapter = CachedLspAdapter::new(Arc::new(adapter)).await;
+        let container_path: Arc<Path> = container_dir.path().into();
+        let task = cx.spawn(|cx| {
+            get_binary_unless_cancelled(
+                adapter,
+                Arc::new(Language::new(LanguageConfig::default(), None)),
+                Arc::new(TestDelegate),
+                container_path,
+                statuses,
+                abort_registration,
+                cx,
+            )
+        });
+
+        installed.await.unwrap();
+        assert!(container_dir.path().join("server-2/server").exists());
+        abort_handle.abort();
+        task.await.unwrap_err();
+
+        // Only the files of the cancelled download are removed.
+        assert!(!container_dir.path().join("server-2").exists());
+        assert!(container_dir.path().join("server-1").exists());
+
+        let mut cancelled = false;
+        while let Ok(Some((_, status))) = status_events.try_next() {
+            cancelled |= matches!(status, LanguageServerBinaryStatus::Cancelled);
+        }
+        assert!(cancelled);
+    }
+
+    fn binary(path: impl Into<PathBuf>) -> LanguageServerBinary {
+        LanguageServerBinary {
+            path: path.into(),
+            arguments: Vec::new(),
+        }
+    }
+
+    async fn get_test_binary(
+        adapter: &Arc<TestAdapter>,
+        container_dir: &Path,
+        cx: &mut TestAppContext,
+    ) -> Result<LanguageServerBinary> {
+        let adapter = CachedLspAdapter::new(Arc::new(adapter.clone())).await;
+        get_binary(
+            adapter,
+            Arc::new(Language::new(LanguageConfig::default(), None)),
+            Arc::new(TestDelegate),
+            container_dir.into(),
+            LspBinaryStatusSender::default(),
+            cx.to_async(),
+        )
+        .await
+    }
+
+    type Install = fn(&Path, usize) -> Result<LanguageServerBinary>;
+
+    /// An adapter that installs its server by calling a function, and that uses the
+    /// default check of server binaries.
+    struct TestAdapter {
+        install: Install,
+        installs: AtomicUsize,
+        cached_binary: Option<PathBuf>,
+        /// When set, installations never finish, and this is notified once they've
+        /// written their files.
+        hang: Mutex<Option<oneshot::Sender<()>>>,
+    }
+
+    impl TestAdapter {
+        fn new(install: Install, cached_binary: Option<PathBuf>) -> Arc<Self> {
+            Arc::new(Self {
+                install,
+                installs: AtomicUsize::new(0),
+                cached_binary,
+                hang: Mutex::new(None),
+            })
+        }
+
+        fn hanging(install: Install) -> (Arc<Self>, oneshot::Receiver<()>) {
+            let (tx, rx) = oneshot::channel();
+            let adapter = Self::new(install, None);
+            *adapter.hang.lock() = Some(tx);
+            (adapter, rx)
+        }
+
+        fn install_count(&self) -> usize {
+            self.installs.load(SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl LspAdapter for Arc<TestAdapter> {
+        fn name(&self) -> LanguageServerName {
+            LanguageServerName("test-server".into())
+        }
+
+        fn short_name(&self) -> &'static str {
+            "test"
+        }
+
+        async fn fetch_latest_server_version(
+            &self,
+            _: &dyn LspAdapterDelegate,
+        ) -> Result<Box<dyn 'static + Send + Any>> {
+            Ok(Box::new(()))
+        }
+
+        async fn fetch_server_binary(
+            &self,
+            _: Box<dyn 'static + Send + Any>,
+            container_dir: PathBuf,
+            _: &dyn LspAdapterDelegate,
+        ) -> Result<LanguageServerBinary> {
+            let attempt = self.installs.fetch_add(1, SeqCst);
+            let binary = (self.install)(&container_dir, attempt);
+            let hang = self.hang.lock().take();
+            if let Some(hang) = hang {
+                hang.send(()).ok();
+                futures::future::pending::<()>().await;
+            }
+            binary
+        }
+
+        async fn cached_server_binary(
+            &self,
+            _: PathBuf,
+            _: &dyn LspAdapterDelegate,
+        ) -> Option<LanguageServerBinary> {
+            self.cached_binary.clone().map(binary)
+        }
+
+        async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+            None
+        }
+    }
+
+    struct TestDelegate;
+
+    impl LspAdapterDelegate for TestDelegate {
+        fn show_notification(&self, _: &str, _: &mut AppContext) {}
+
+        fn http_client(&self) -> Arc<dyn HttpClient> {
+            FakeHttpClient::with_404_response()
+        }
+    }
+}
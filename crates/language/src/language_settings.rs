@@ -93,6 +93,15 @@ pub struct LanguageSettings {
     pub inlay_hints: InlayHintSettings,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
+    /// Whether to insert the closing tag when typing the `>` of an opening tag.
+    pub auto_close_tags: bool,
+    /// Whether to edit the ranges linked to the one being edited, such as the name of
+    /// an element's closing tag when editing its opening tag.
+    pub linked_edits: bool,
+    /// How imports are grouped when they're sorted without a language server.
+    pub import_groups: ImportGroups,
+    /// Which parts of a file are folded when it's opened.
+    pub auto_fold: AutoFoldSettings,
 }
 
 /// The settings for [GitHub Copilot](https://github.com/features/copilot).
@@ -215,6 +224,56 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub use_autoclose: Option<bool>,
+    /// Whether to insert the closing tag when typing the `>` of an opening tag,
+    /// such as `</div>` after `<div>`.
+    ///
+    /// Default: false
+    pub auto_close_tags: Option<bool>,
+    /// Whether to edit the ranges that the language server links to the one being
+    /// edited, such as the name of an element's closing tag when editing the name
+    /// of its opening tag.
+    ///
+    /// Default: true
+    pub linked_edits: Option<bool>,
+    /// How imports are grouped when they're sorted without a language server.
+    /// The imports from the standard library come first, followed by the
+    /// imports of external packages and then by the project's own imports.
+    #[serde(default)]
+    pub import_groups: Option<ImportGroups>,
+    /// Which parts of a file are folded when it's opened, using the folding ranges
+    /// of the language server when it has them.
+    #[serde(default)]
+    pub auto_fold: Option<AutoFoldSettings>,
+}
+
+/// The prefixes of the paths that decide which group an import is sorted into.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ImportGroups {
+    /// The paths of the standard library, such as `std` or `node:`.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub std: Vec<String>,
+    /// The paths of the project's own modules, such as `crate` or `./`.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub internal: Vec<String>,
+}
+
+/// The parts of a file that are folded when it's opened.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct AutoFoldSettings {
+    /// Whether to fold the blocks of imports at the top of the file.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub imports: bool,
+    /// Whether to fold the regions between `#region` and `#endregion` comments.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub regions: bool,
 }
 
 /// The contents of the GitHub Copilot settings.
@@ -548,6 +607,8 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
     merge(&mut settings.hard_tabs, src.hard_tabs);
     merge(&mut settings.soft_wrap, src.soft_wrap);
     merge(&mut settings.use_autoclose, src.use_autoclose);
+    merge(&mut settings.auto_close_tags, src.auto_close_tags);
+    merge(&mut settings.linked_edits, src.linked_edits);
     merge(&mut settings.show_wrap_guides, src.show_wrap_guides);
     merge(&mut settings.wrap_guides, src.wrap_guides.clone());
 
@@ -580,6 +641,8 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(&mut settings.import_groups, src.import_groups.clone());
+    merge(&mut settings.auto_fold, src.auto_fold);
     fn merge<T>(target: &mut T, value: Option<T>) {
         if let Some(value) = value {
             *target = value;
@@ -1,8 +1,8 @@
 //! Handles conversions of `language` items to and from the [`rpc`] protocol.
 
 use crate::{
-    diagnostic_set::DiagnosticEntry, CodeAction, CodeLabel, Completion, CursorShape, Diagnostic,
-    Language,
+    diagnostic_set::DiagnosticEntry, CodeAction, CodeLabel, CodeLens, Completion, CursorShape,
+    Diagnostic, Language,
 };
 use anyhow::{anyhow, Result};
 use clock::ReplicaId;
@@ -227,6 +227,7 @@ pub fn serialize_diagnostics<'a>(
             code: entry.diagnostic.code.clone(),
             is_disk_based: entry.diagnostic.is_disk_based,
             is_unnecessary: entry.diagnostic.is_unnecessary,
+            is_deprecated: entry.diagnostic.is_deprecated,
         })
         .collect()
 }
@@ -412,6 +413,7 @@ pub fn deserialize_diagnostics(
                     is_primary: diagnostic.is_primary,
                     is_disk_based: diagnostic.is_disk_based,
                     is_unnecessary: diagnostic.is_unnecessary,
+                    is_deprecated: diagnostic.is_deprecated,
                 },
             })
         })
@@ -478,7 +480,7 @@ pub fn serialize_completion(completion: &Completion) -> proto::Completion {
         old_start: Some(serialize_anchor(&completion.old_range.start)),
         old_end: Some(serialize_anchor(&completion.old_range.end)),
         new_text: completion.new_text.clone(),
-        server_id: completion.server_id.0 as u64,
+        server_id: completion.server_id.map(|server_id| server_id.0 as u64),
         lsp_completion: serde_json::to_vec(&completion.lsp_completion).unwrap(),
     }
 }
@@ -513,7 +515,9 @@ pub async fn deserialize_completion(
             )
         }),
         documentation: None,
-        server_id: LanguageServerId(completion.server_id as usize),
+        server_id: completion
+            .server_id
+            .map(|server_id| LanguageServerId(server_id as usize)),
         lsp_completion,
     })
 }
@@ -546,6 +550,34 @@ pub fn deserialize_code_action(action: proto::CodeAction) -> Result<CodeAction>
     })
 }
 
+/// Serializes a [`CodeLens`] to be sent over RPC.
+pub fn serialize_code_lens(lens: &CodeLens) -> proto::CodeLens {
+    proto::CodeLens {
+        server_id: lens.server_id.0 as u64,
+        start: Some(serialize_anchor(&lens.range.start)),
+        end: Some(serialize_anchor(&lens.range.end)),
+        lsp_lens: serde_json::to_vec(&lens.lsp_lens).unwrap(),
+    }
+}
+
+/// Deserializes a [`CodeLens`] from the RPC representation.
+pub fn deserialize_code_lens(lens: proto::CodeLens) -> Result<CodeLens> {
+    let start = lens
+        .start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("invalid start"))?;
+    let end = lens
+        .end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("invalid end"))?;
+    let lsp_lens = serde_json::from_slice(&lens.lsp_lens)?;
+    Ok(CodeLens {
+        server_id: LanguageServerId(lens.server_id as usize),
+        range: start..end,
+        lsp_lens,
+    })
+}
+
 /// Serializes a [`Transaction`] to be sent over RPC.
 pub fn serialize_transaction(transaction: &Transaction) -> proto::Transaction {
     proto::Transaction {
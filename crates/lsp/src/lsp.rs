@@ -16,6 +16,7 @@ use smol::{
     process::{self, Child},
 };
 use std::{
+    collections::{BTreeSet, VecDeque},
     ffi::OsString,
     fmt,
     future::Future,
@@ -28,12 +29,18 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use std::{path::Path, process::Stdio};
+use std::{
+    path::Path,
+    process::{ExitStatus, Stdio},
+};
 use util::{ResultExt, TryFutureExt};
 
 const JSON_RPC_VERSION: &str = "2.0";
 const CONTENT_LEN_HEADER: &str = "Content-Length: ";
 const LSP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
+/// How many of the last lines that a server wrote to its stderr are kept, to report
+/// why it exited.
+const STDERR_TAIL_LEN: usize = 100;
 
 type NotificationHandler = Box<dyn Send + FnMut(Option<usize>, &str, AsyncAppContext)>;
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
@@ -70,8 +77,15 @@ pub struct LanguageServer {
     #[allow(clippy::type_complexity)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
     output_done_rx: Mutex<Option<barrier::Receiver>>,
+    /// Fires once the server's process exits, `None` if it isn't backed by a process.
+    exit_rx: Option<barrier::Receiver>,
+    /// Dropped to simulate a fake server's process exiting.
+    #[cfg(any(test, feature = "test-support"))]
+    simulated_exit_tx: Arc<Mutex<Option<barrier::Sender>>>,
+    stderr_tail: Arc<Mutex<VecDeque<String>>>,
     root_path: PathBuf,
-    _server: Option<Mutex<Child>>,
+    workspace_folders: Mutex<BTreeSet<Url>>,
+    server: Option<Mutex<Child>>,
 }
 
 /// Identifies a running language server.
@@ -232,6 +246,8 @@ impl LanguageServer {
     {
         let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
         let (output_done_tx, output_done_rx) = barrier::channel();
+        let (input_done_tx, input_done_rx) = barrier::channel();
+        let stderr_tail = Arc::new(Mutex::new(VecDeque::new()));
         let notification_handlers =
             Arc::new(Mutex::new(HashMap::<_, NotificationHandler>::default()));
         let response_handlers =
@@ -259,11 +275,15 @@ impl LanguageServer {
             .map(|stderr| {
                 let io_handlers = io_handlers.clone();
                 let stderr_captures = stderr_capture.clone();
-                cx.spawn(|_| Self::handle_stderr(stderr, io_handlers, stderr_captures).log_err())
+                let stderr_tail = stderr_tail.clone();
+                cx.spawn(|_| {
+                    Self::handle_stderr(stderr, io_handlers, stderr_captures, stderr_tail).log_err()
+                })
             })
             .unwrap_or_else(|| Task::Ready(Some(None)));
         let input_task = cx.spawn(|_| async move {
             let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+            drop(input_done_tx);
             stdout.or(stderr)
         });
         let output_task = cx.background_executor().spawn({
@@ -290,8 +310,13 @@ impl LanguageServer {
             executor: cx.background_executor().clone(),
             io_tasks: Mutex::new(Some((input_task, output_task))),
             output_done_rx: Mutex::new(Some(output_done_rx)),
+            exit_rx: server.is_some().then_some(input_done_rx),
+            #[cfg(any(test, feature = "test-support"))]
+            simulated_exit_tx: Default::default(),
+            stderr_tail,
             root_path: root_path.to_path_buf(),
-            _server: server.map(|server| Mutex::new(server)),
+            workspace_folders: Mutex::new(Url::from_file_path(root_path).into_iter().collect()),
+            server: server.map(|server| Mutex::new(server)),
         }
     }
 
@@ -420,6 +445,7 @@ impl LanguageServer {
         stderr: Stderr,
         io_handlers: Arc<Mutex<HashMap<usize, IoHandler>>>,
         stderr_capture: Arc<Mutex<Option<String>>>,
+        stderr_tail: Arc<Mutex<VecDeque<String>>>,
     ) -> anyhow::Result<()>
     where
         Stderr: AsyncRead + Unpin + Send + 'static,
@@ -444,6 +470,12 @@ impl LanguageServer {
                 if let Some(stderr) = stderr_capture.lock().as_mut() {
                     stderr.push_str(message);
                 }
+
+                let mut stderr_tail = stderr_tail.lock();
+                if stderr_tail.len() == STDERR_TAIL_LEN {
+                    stderr_tail.pop_front();
+                }
+                stderr_tail.push_back(message.to_string());
             }
 
             // Don't starve the main thread when receiving lots of messages at once.
@@ -501,7 +533,7 @@ impl LanguageServer {
         let params = InitializeParams {
             process_id: None,
             root_path: None,
-            root_uri: Some(root_uri.clone()),
+            root_uri: Some(root_uri),
             initialization_options: options,
             capabilities: ClientCapabilities {
                 workspace: Some(WorkspaceClientCapabilities {
@@ -516,6 +548,9 @@ impl LanguageServer {
                     workspace_folders: Some(true),
                     symbol: Some(WorkspaceSymbolClientCapabilities {
                         resolve_support: None,
+                        tag_support: Some(TagSupport {
+                            value_set: vec![SymbolTag::DEPRECATED],
+                        }),
                         ..WorkspaceSymbolClientCapabilities::default()
                     }),
                     inlay_hint: Some(InlayHintWorkspaceClientCapabilities {
@@ -559,6 +594,11 @@ impl LanguageServer {
                     completion: Some(CompletionClientCapabilities {
                         completion_item: Some(CompletionItemCapability {
                             snippet_support: Some(true),
+                            commit_characters_support: Some(true),
+                            deprecated_support: Some(true),
+                            tag_support: Some(TagSupport {
+                                value_set: vec![CompletionItemTag::DEPRECATED],
+                            }),
                             resolve_support: Some(CompletionItemCapabilityResolveSupport {
                                 properties: vec![
                                     "documentation".to_string(),
@@ -585,6 +625,20 @@ impl LanguageServer {
                         content_format: Some(vec![MarkupKind::Markdown]),
                         dynamic_registration: None,
                     }),
+                    signature_help: Some(SignatureHelpClientCapabilities {
+                        signature_information: Some(SignatureInformationSettings {
+                            documentation_format: Some(vec![
+                                MarkupKind::Markdown,
+                                MarkupKind::PlainText,
+                            ]),
+                            parameter_information: Some(ParameterInformationSettings {
+                                label_offset_support: Some(true),
+                            }),
+                            active_parameter_support: Some(true),
+                        }),
+                        context_support: Some(true),
+                        dynamic_registration: None,
+                    }),
                     inlay_hint: Some(InlayHintClientCapabilities {
                         resolve_support: Some(InlayHintResolveClientCapabilities {
                             properties: vec![
@@ -599,11 +653,31 @@ impl LanguageServer {
                     }),
                     publish_diagnostics: Some(PublishDiagnosticsClientCapabilities {
                         related_information: Some(true),
+                        tag_support: Some(TagSupport {
+                            value_set: vec![DiagnosticTag::UNNECESSARY, DiagnosticTag::DEPRECATED],
+                        }),
                         ..Default::default()
                     }),
                     formatting: Some(DynamicRegistrationClientCapabilities {
                         dynamic_registration: None,
                     }),
+                    code_lens: Some(CodeLensClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    document_link: Some(DocumentLinkClientCapabilities {
+                        dynamic_registration: None,
+                        tooltip_support: Some(false),
+                    }),
+                    color_provider: Some(DocumentColorClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    linked_editing_range: Some(LinkedEditingRangeClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    folding_range: Some(FoldingRangeClientCapabilities {
+                        dynamic_registration: None,
+                        ..Default::default()
+                    }),
                     on_type_formatting: Some(DynamicRegistrationClientCapabilities {
                         dynamic_registration: None,
                     }),
@@ -615,6 +689,12 @@ impl LanguageServer {
                 }),
                 experimental: Some(json!({
                     "serverStatusNotification": true,
+                    "commands": {
+                        "commands": [
+                            "rust-analyzer.runSingle",
+                            "rust-analyzer.showReferences",
+                        ],
+                    },
                 })),
                 window: Some(WindowClientCapabilities {
                     work_done_progress: Some(true),
@@ -623,10 +703,14 @@ impl LanguageServer {
                 general: None,
             },
             trace: None,
-            workspace_folders: Some(vec![WorkspaceFolder {
-                uri: root_uri,
-                name: Default::default(),
-            }]),
+            workspace_folders: Some(
+                self.workspace_folders
+                    .lock()
+                    .iter()
+                    .cloned()
+                    .map(workspace_folder)
+                    .collect(),
+            ),
             client_info: Some(ClientInfo {
                 name: release_channel::ReleaseChannel::global(cx)
                     .display_name()
@@ -648,6 +732,25 @@ impl LanguageServer {
         })
     }
 
+    /// Returns a future that resolves once the server's process exits, or `None` if the
+    /// server isn't backed by a process.
+    pub fn exited(&self) -> Option<impl 'static + Send + Future<Output = ()>> {
+        let mut exit_rx = self.exit_rx.clone()?;
+        Some(async move {
+            exit_rx.recv().await;
+        })
+    }
+
+    /// Returns the exit status of the server's process, if it has exited.
+    pub fn exit_status(&self) -> Option<ExitStatus> {
+        self.server.as_ref()?.lock().try_status().ok().flatten()
+    }
+
+    /// Returns the last lines that the server wrote to its stderr.
+    pub fn stderr_tail(&self) -> String {
+        self.stderr_tail.lock().iter().map(String::as_str).collect()
+    }
+
     /// Sends a shutdown request to the language server process and prepares the [`LanguageServer`] to be dropped.
     pub fn shutdown(&self) -> Option<impl 'static + Send + Future<Output = Option<()>>> {
         if let Some(tasks) = self.io_tasks.lock().take() {
@@ -738,8 +841,10 @@ impl LanguageServer {
         self.notification_handlers.lock().contains_key(T::METHOD)
     }
 
+    /// Registers a handler for a notification that isn't part of the LSP specification,
+    /// such as an extension of a particular language server.
     #[must_use]
-    fn on_custom_notification<Params, F>(&self, method: &'static str, mut f: F) -> Subscription
+    pub fn on_custom_notification<Params, F>(&self, method: &'static str, mut f: F) -> Subscription
     where
         F: 'static + FnMut(Params, AsyncAppContext) + Send,
         Params: DeserializeOwned,
@@ -861,6 +966,56 @@ impl LanguageServer {
         &self.root_path
     }
 
+    /// Get the folders that the language server is working in, which include its root path.
+    pub fn workspace_folders(&self) -> Vec<Url> {
+        self.workspace_folders.lock().iter().cloned().collect()
+    }
+
+    /// Adds a folder for the language server to work in. Folders that are added before the
+    /// server is initialized are sent with the `Initialize` request, and afterwards through
+    /// a `workspace/didChangeWorkspaceFolders` notification.
+    ///
+    /// [LSP Specification](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#workspace_didChangeWorkspaceFolders)
+    pub fn add_workspace_folder(&self, uri: Url) {
+        if self.workspace_folders.lock().insert(uri.clone()) {
+            self.notify_workspace_folders_changed(vec![workspace_folder(uri)], Vec::new());
+        }
+    }
+
+    /// Removes a folder that the language server is working in.
+    pub fn remove_workspace_folder(&self, uri: Url) {
+        if self.workspace_folders.lock().remove(&uri) {
+            self.notify_workspace_folders_changed(Vec::new(), vec![workspace_folder(uri)]);
+        }
+    }
+
+    fn notify_workspace_folders_changed(
+        &self,
+        added: Vec<WorkspaceFolder>,
+        removed: Vec<WorkspaceFolder>,
+    ) {
+        // Servers only accept the notification when they've registered for it, which they
+        // can't have done before being initialized.
+        let supports_changes = self
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.workspace_folders.as_ref())
+            .and_then(|folders| folders.change_notifications.as_ref())
+            .map_or(false, |change_notifications| match change_notifications {
+                OneOf::Left(supported) => *supported,
+                OneOf::Right(_) => true,
+            });
+        if supports_changes {
+            self.notify::<notification::DidChangeWorkspaceFolders>(
+                DidChangeWorkspaceFoldersParams {
+                    event: WorkspaceFoldersChangeEvent { added, removed },
+                },
+            )
+            .log_err();
+        }
+    }
+
     /// Sends a RPC request to the language server.
     ///
     /// [LSP Specification](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#requestMessage)
@@ -985,6 +1140,15 @@ impl LanguageServer {
     }
 }
 
+fn workspace_folder(uri: Url) -> WorkspaceFolder {
+    let name = uri
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default()
+        .to_string();
+    WorkspaceFolder { uri, name }
+}
+
 impl Drop for LanguageServer {
     fn drop(&mut self) {
         if let Some(shutdown) = self.shutdown() {
@@ -1047,6 +1211,7 @@ impl Drop for Subscription {
 pub struct FakeLanguageServer {
     pub server: Arc<LanguageServer>,
     notifications_rx: channel::Receiver<(String, String)>,
+    simulated_exit_tx: Arc<Mutex<Option<barrier::Sender>>>,
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -1060,8 +1225,9 @@ impl FakeLanguageServer {
         let (stdin_writer, stdin_reader) = async_pipe::pipe();
         let (stdout_writer, stdout_reader) = async_pipe::pipe();
         let (notifications_tx, notifications_rx) = channel::unbounded();
+        let (exit_tx, exit_rx) = barrier::channel();
 
-        let server = LanguageServer::new_internal(
+        let mut server = LanguageServer::new_internal(
             LanguageServerId(0),
             stdin_writer,
             stdout_reader,
@@ -1073,6 +1239,10 @@ impl FakeLanguageServer {
             cx.clone(),
             |_| {},
         );
+        // The server keeps the sender too, so that it's only dropped by `simulate_exit`
+        // and not when the fake is.
+        server.exit_rx = Some(exit_rx);
+        *server.simulated_exit_tx.lock() = Some(exit_tx);
         let fake = FakeLanguageServer {
             server: Arc::new(LanguageServer::new_internal(
                 LanguageServerId(0),
@@ -1097,6 +1267,7 @@ impl FakeLanguageServer {
                 },
             )),
             notifications_rx,
+            simulated_exit_tx: server.simulated_exit_tx.clone(),
         };
         fake.handle_request::<request::Initialize, _, _>({
             let capabilities = capabilities;
@@ -1245,6 +1416,11 @@ impl FakeLanguageServer {
         });
     }
 
+    /// Simulate that the server's process exited on its own, as if it crashed.
+    pub fn simulate_exit(&self) {
+        self.simulated_exit_tx.lock().take();
+    }
+
     /// Simulate that the server has completed work and notifies about that with the specified token.
     pub fn end_progress(&self, token: impl Into<String>) {
         self.notify::<notification::Progress>(ProgressParams {
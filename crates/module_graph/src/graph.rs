@@ -0,0 +1,319 @@
+use collections::HashMap;
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+/// The extensions that imports without one are resolved with, in order of preference.
+pub const MODULE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    /// The absolute path of the module's file, which its imports are resolved against.
+    pub abs_path: PathBuf,
+    /// The path of the module's file, relative to its worktree.
+    pub path: Arc<Path>,
+}
+
+/// The modules of a project and the modules that each of them imports.
+#[derive(Default)]
+pub struct ModuleGraph {
+    pub modules: Vec<Module>,
+    /// The indices of the modules that each module imports.
+    pub imports: Vec<Vec<usize>>,
+}
+
+impl ModuleGraph {
+    /// Builds the graph from the import paths of each module. Only relative imports are
+    /// resolved, so imports of packages are left out.
+    pub fn new(modules: Vec<(Module, Vec<String>)>) -> Self {
+        let ids_by_path = modules
+            .iter()
+            .enumerate()
+            .map(|(ix, (module, _))| (module.abs_path.clone(), ix))
+            .collect::<HashMap<_, _>>();
+
+        let mut graph = Self::default();
+        for (module, import_paths) in modules {
+            let mut imports = import_paths
+                .iter()
+                .filter_map(|import_path| {
+                    resolve_import(&module.abs_path, import_path, &ids_by_path)
+                })
+                .collect::<Vec<_>>();
+            imports.sort_unstable();
+            imports.dedup();
+            graph.modules.push(module);
+            graph.imports.push(imports);
+        }
+        graph
+    }
+
+    /// Returns the subgraph of the modules in the given directory of their worktree.
+    pub fn in_directory(&self, directory: &Path) -> Self {
+        let mut new_ids = HashMap::default();
+        let mut graph = Self::default();
+        for (ix, module) in self.modules.iter().enumerate() {
+            if module.path.starts_with(directory) {
+                new_ids.insert(ix, graph.modules.len());
+                graph.modules.push(module.clone());
+            }
+        }
+        for (ix, imports) in self.imports.iter().enumerate() {
+            if new_ids.contains_key(&ix) {
+                graph.imports.push(
+                    imports
+                        .iter()
+                        .filter_map(|import| new_ids.get(import).copied())
+                        .collect(),
+                );
+            }
+        }
+        graph
+    }
+
+    pub fn import_count(&self) -> usize {
+        self.imports.iter().map(Vec::len).sum()
+    }
+
+    /// Returns the groups of modules that import each other, directly or through
+    /// other modules in the group.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        self.strongly_connected_components()
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.imports[component[0]].contains(&component[0])
+            })
+            .collect()
+    }
+
+    /// Returns the layer of each module, which is one more than the highest layer of
+    /// the modules it imports. Modules that import each other share a layer.
+    pub fn layers(&self) -> Vec<usize> {
+        let components = self.strongly_connected_components();
+        let mut component_ids = vec![0; self.modules.len()];
+        for (component_id, component) in components.iter().enumerate() {
+            for &module in component {
+                component_ids[module] = component_id;
+            }
+        }
+
+        // Components are ordered after the components they import.
+        let mut component_layers = vec![0; components.len()];
+        for (component_id, component) in components.iter().enumerate() {
+            component_layers[component_id] = component
+                .iter()
+                .flat_map(|&module| &self.imports[module])
+                .map(|&import| component_ids[import])
+                .filter(|&import_component_id| import_component_id != component_id)
+                .map(|import_component_id| component_layers[import_component_id] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        component_ids
+            .into_iter()
+            .map(|component_id| component_layers[component_id])
+            .collect()
+    }
+
+    /// Returns the strongly connected components of the graph using Tarjan's algorithm,
+    /// in reverse topological order.
+    fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let count = self.modules.len();
+        let mut indices = vec![usize::MAX; count];
+        let mut low_links = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..count {
+            if indices[root] != usize::MAX {
+                continue;
+            }
+
+            // Walk the graph with an explicit stack of (module, next import) pairs, so
+            // that long import chains don't overflow the call stack.
+            let mut walk = vec![(root, 0)];
+            while let Some((module, import_ix)) = walk.pop() {
+                if import_ix == 0 {
+                    indices[module] = next_index;
+                    low_links[module] = next_index;
+                    next_index += 1;
+                    stack.push(module);
+                    on_stack[module] = true;
+                }
+
+                if let Some(&import) = self.imports[module].get(import_ix) {
+                    walk.push((module, import_ix + 1));
+                    if indices[import] == usize::MAX {
+                        walk.push((import, 0));
+                    } else if on_stack[import] {
+                        low_links[module] = low_links[module].min(indices[import]);
+                    }
+                    continue;
+                }
+
+                if low_links[module] == indices[module] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == module {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+                if let Some(&(importer, _)) = walk.last() {
+                    low_links[importer] = low_links[importer].min(low_links[module]);
+                }
+            }
+        }
+        components
+    }
+}
+
+/// Resolves a relative import to a module, trying the extensions and index files
+/// that bundlers and TypeScript resolve imports without an extension to.
+fn resolve_import(
+    importer: &Path,
+    import_path: &str,
+    ids_by_path: &HashMap<PathBuf, usize>,
+) -> Option<usize> {
+    let import_path = import_path.trim_matches(|c| c == '"' || c == '\'' || c == '`');
+    if !import_path.starts_with("./") && !import_path.starts_with("../") {
+        return None;
+    }
+    let path = normalize_path(&importer.parent()?.join(import_path));
+
+    // TypeScript resolves `./module.js` to `./module.ts`.
+    let without_extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| MODULE_EXTENSIONS.contains(extension))
+        .map(|_| path.with_extension(""));
+
+    std::iter::once(path.clone())
+        .chain(
+            MODULE_EXTENSIONS
+                .iter()
+                .map(|extension| append_extension(&path, extension)),
+        )
+        .chain(without_extension.into_iter().flat_map(|path| {
+            MODULE_EXTENSIONS
+                .iter()
+                .map(move |extension| path.with_extension(extension))
+        }))
+        .chain(
+            MODULE_EXTENSIONS
+                .iter()
+                .map(|extension| path.join("index").with_extension(extension)),
+        )
+        .find_map(|candidate| ids_by_path.get(&candidate).copied())
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    path.into()
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(modules: &[(&str, &[&str])]) -> ModuleGraph {
+        ModuleGraph::new(
+            modules
+                .iter()
+                .map(|(path, imports)| {
+                    (
+                        Module {
+                            abs_path: Path::new("/app").join(path),
+                            path: Path::new(path).into(),
+                        },
+                        imports.iter().map(|import| format!("'{import}'")).collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_resolve_imports() {
+        let graph = graph(&[
+            (
+                "src/app.tsx",
+                &[
+                    "./components",
+                    "./utils/format.js",
+                    "../config.json",
+                    "react",
+                    "./missing",
+                ],
+            ),
+            ("src/components/index.ts", &["./button", "../app"]),
+            ("src/components/button.tsx", &["../utils/format"]),
+            ("src/utils/format.ts", &[]),
+        ]);
+        assert_eq!(graph.imports, [vec![1, 3], vec![0, 2], vec![3], vec![]]);
+        assert_eq!(graph.import_count(), 5);
+    }
+
+    #[test]
+    fn test_cycles_and_layers() {
+        let graph = graph(&[
+            ("a.ts", &["./b", "./d"]),
+            ("b.ts", &["./c"]),
+            ("c.ts", &["./a", "./d"]),
+            ("d.ts", &[]),
+            ("e.ts", &["./e", "./a"]),
+        ]);
+        let mut cycles = graph
+            .cycles()
+            .into_iter()
+            .map(|mut cycle| {
+                cycle.sort();
+                cycle
+            })
+            .collect::<Vec<_>>();
+        cycles.sort();
+        assert_eq!(cycles, [vec![0, 1, 2], vec![4]]);
+        assert_eq!(graph.layers(), [1, 1, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_in_directory() {
+        let graph = graph(&[
+            ("src/ui/button.ts", &["../lib/color"]),
+            ("src/lib/color.ts", &[]),
+            ("src/ui/menu.ts", &["./button"]),
+        ]);
+        let ui = graph.in_directory(Path::new("src/ui"));
+        assert_eq!(
+            ui.modules
+                .iter()
+                .map(|module| module.path.as_ref())
+                .collect::<Vec<_>>(),
+            [Path::new("src/ui/button.ts"), Path::new("src/ui/menu.ts")]
+        );
+        assert_eq!(ui.imports, [vec![], vec![0]]);
+    }
+}
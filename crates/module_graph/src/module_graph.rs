@@ -0,0 +1,419 @@
+mod graph;
+
+use editor::{Editor, EditorEvent};
+use gpui::{
+    actions, canvas, point, px, size, AnyElement, AppContext, ClickEvent, ElementContext,
+    EventEmitter, FocusHandle, FocusableView, Hsla, Model, Path as PathShape, Pixels, Point,
+    Render, Size, Subscription, Task, View, ViewContext, VisualContext, WeakView,
+};
+use graph::{Module, ModuleGraph, MODULE_EXTENSIONS};
+use project::Project;
+use std::path::Path;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{item::Item, Workspace};
+
+actions!(module_graph, [OpenModuleGraph, Rescan]);
+
+const NODE_WIDTH: Pixels = px(240.);
+const NODE_HEIGHT: Pixels = px(24.);
+const ROW_GAP: Pixels = px(8.);
+const COLUMN_GAP: Pixels = px(96.);
+const PADDING: Pixels = px(16.);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenModuleGraph, cx| {
+            let project = workspace.project().clone();
+            let workspace_handle = workspace.weak_handle();
+            let view = cx.new_view(|cx| ModuleGraphView::new(project, workspace_handle, cx));
+            workspace.add_item(Box::new(view), cx);
+        });
+    })
+    .detach();
+}
+
+/// Shows the JavaScript and TypeScript modules of a project as a graph, where each
+/// module is placed to the left of the modules it imports and the imports that form
+/// cycles are highlighted.
+pub struct ModuleGraphView {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    filter_editor: View<Editor>,
+    graph: ModuleGraph,
+    layout: GraphLayout,
+    selected_module: Option<usize>,
+    scanning: bool,
+    scan_task: Task<()>,
+    _subscription: Subscription,
+}
+
+/// The modules in the filtered directory, and where they're placed in the view.
+#[derive(Default)]
+struct GraphLayout {
+    graph: ModuleGraph,
+    positions: Vec<Point<Pixels>>,
+    /// The cycle that each module is part of, if any.
+    cycle_ids: Vec<Option<usize>>,
+    cycle_count: usize,
+    size: Size<Pixels>,
+}
+
+impl GraphLayout {
+    fn new(graph: ModuleGraph) -> Self {
+        let mut cycle_ids = vec![None; graph.modules.len()];
+        let cycles = graph.cycles();
+        for (cycle_id, cycle) in cycles.iter().enumerate() {
+            for &module in cycle {
+                cycle_ids[module] = Some(cycle_id);
+            }
+        }
+
+        // Importers are placed in the columns to the left of the modules they import.
+        let layers = graph.layers();
+        let max_layer = layers.iter().copied().max().unwrap_or(0);
+        let mut columns = vec![Vec::new(); max_layer + 1];
+        for (module, layer) in layers.into_iter().enumerate() {
+            columns[max_layer - layer].push(module);
+        }
+
+        let mut positions = vec![Point::default(); graph.modules.len()];
+        let mut row_count = 0;
+        for (column_ix, column) in columns.iter_mut().enumerate() {
+            column.sort_by_key(|&module| &graph.modules[module].path);
+            for (row_ix, &module) in column.iter().enumerate() {
+                positions[module] = point(
+                    PADDING + (NODE_WIDTH + COLUMN_GAP) * column_ix as f32,
+                    PADDING + (NODE_HEIGHT + ROW_GAP) * row_ix as f32,
+                );
+            }
+            row_count = row_count.max(column.len());
+        }
+
+        let size = size(
+            PADDING * 2. + (NODE_WIDTH + COLUMN_GAP) * columns.len() as f32 - COLUMN_GAP,
+            PADDING * 2. + (NODE_HEIGHT + ROW_GAP) * row_count as f32 - ROW_GAP,
+        );
+        Self {
+            graph,
+            positions,
+            cycle_ids,
+            cycle_count: cycles.len(),
+            size,
+        }
+    }
+
+    fn is_cyclic_import(&self, importer: usize, import: usize) -> bool {
+        self.cycle_ids[importer].is_some() && self.cycle_ids[importer] == self.cycle_ids[import]
+    }
+}
+
+impl ModuleGraphView {
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter by directory, such as src/components", cx);
+            editor
+        });
+        let subscription = cx.subscribe(&filter_editor, |this, _, event, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.update_layout(cx);
+            }
+        });
+
+        let mut this = Self {
+            project,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            filter_editor,
+            graph: ModuleGraph::default(),
+            layout: GraphLayout::default(),
+            selected_module: None,
+            scanning: false,
+            scan_task: Task::ready(()),
+            _subscription: subscription,
+        };
+        this.scan(cx);
+        this
+    }
+
+    /// Reads and parses the modules of the project's worktrees in the background, and
+    /// replaces the graph once they're all parsed.
+    fn scan(&mut self, cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        if !project.is_local() {
+            return;
+        }
+        let fs = project.fs().clone();
+        let languages = project.languages().clone();
+        let modules = project
+            .visible_worktrees(cx)
+            .flat_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let root = worktree.abs_path();
+                worktree
+                    .files(false, 0)
+                    .filter(|entry| {
+                        entry
+                            .path
+                            .extension()
+                            .and_then(|extension| extension.to_str())
+                            .map_or(false, |extension| MODULE_EXTENSIONS.contains(&extension))
+                    })
+                    .map(|entry| Module {
+                        abs_path: root.join(&entry.path),
+                        path: entry.path.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        self.scanning = true;
+        cx.notify();
+        self.scan_task = cx.spawn(|this, mut cx| async move {
+            let mut sources = Vec::new();
+            for module in modules {
+                let Some(text) = fs.load(&module.abs_path).await.log_err() else {
+                    continue;
+                };
+                let Some(language) = languages
+                    .language_for_file(&module.abs_path, None)
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+                sources.push((module, text, language));
+            }
+
+            let graph = cx
+                .background_executor()
+                .spawn(async move {
+                    ModuleGraph::new(
+                        sources
+                            .into_iter()
+                            .map(|(module, text, language)| {
+                                let import_paths = language.import_paths(&text);
+                                (module, import_paths)
+                            })
+                            .collect(),
+                    )
+                })
+                .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.graph = graph;
+                this.scanning = false;
+                this.update_layout(cx);
+            })
+            .log_err();
+        });
+    }
+
+    fn rescan(&mut self, _: &Rescan, cx: &mut ViewContext<Self>) {
+        self.scan(cx);
+    }
+
+    fn update_layout(&mut self, cx: &mut ViewContext<Self>) {
+        let directory = self.filter_editor.read(cx).text(cx);
+        let directory = Path::new(directory.trim().trim_matches('/'));
+        self.layout = GraphLayout::new(self.graph.in_directory(directory));
+        self.selected_module = None;
+        cx.notify();
+    }
+
+    fn click_module(&mut self, module: usize, event: &ClickEvent, cx: &mut ViewContext<Self>) {
+        if event.up.click_count > 1 {
+            let abs_path = self.layout.graph.modules[module].abs_path.clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace
+                        .open_abs_path(abs_path, true, cx)
+                        .detach_and_log_err(cx);
+                })
+                .log_err();
+        } else {
+            self.selected_module = Some(module).filter(|&ix| self.selected_module != Some(ix));
+            cx.notify();
+        }
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let graph = &self.layout.graph;
+        let summary = if !self.project.read(cx).is_local() {
+            "The module graph is only available for local projects".to_string()
+        } else if self.scanning {
+            "Parsing modules…".to_string()
+        } else {
+            format!(
+                "{} modules, {} imports, {} cycles",
+                graph.modules.len(),
+                graph.import_count(),
+                self.layout.cycle_count
+            )
+        };
+        h_flex()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(div().flex_1().child(self.filter_editor.clone()))
+            .child(Label::new(summary).color(Color::Muted))
+            .child(
+                IconButton::new("rescan", IconName::Update)
+                    .tooltip(|cx| Tooltip::for_action("Rescan", &Rescan, cx))
+                    .on_click(cx.listener(|this, _, cx| this.scan(cx))),
+            )
+    }
+
+    fn render_graph(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let layout = &self.layout;
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+
+        let mut edges = Vec::new();
+        for (importer, imports) in layout.graph.imports.iter().enumerate() {
+            for &import in imports {
+                let is_selected = self
+                    .selected_module
+                    .map_or(false, |selected| selected == importer || selected == import);
+                let color = if layout.is_cyclic_import(importer, import) {
+                    status.error
+                } else if is_selected {
+                    colors.text_accent
+                } else {
+                    colors.border
+                };
+                let start = layout.positions[importer] + point(NODE_WIDTH, NODE_HEIGHT / 2.);
+                let end = layout.positions[import] + point(px(0.), NODE_HEIGHT / 2.);
+                edges.push((start, end, color, is_selected));
+            }
+        }
+        // Paint the selected module's imports above the others.
+        edges.sort_by_key(|(_, _, _, is_selected)| *is_selected);
+
+        let nodes = layout
+            .graph
+            .modules
+            .iter()
+            .enumerate()
+            .map(|(ix, module)| {
+                let position = layout.positions[ix];
+                let file_name = module
+                    .path
+                    .file_name()
+                    .map(|file_name| file_name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let path: SharedString = module.path.to_string_lossy().into_owned().into();
+                let border_color = if layout.cycle_ids[ix].is_some() {
+                    status.error
+                } else if self.selected_module == Some(ix) {
+                    colors.text_accent
+                } else {
+                    colors.border
+                };
+                div()
+                    .id(ix)
+                    .absolute()
+                    .left(position.x)
+                    .top(position.y)
+                    .w(NODE_WIDTH)
+                    .h(NODE_HEIGHT)
+                    .px_2()
+                    .flex()
+                    .items_center()
+                    .overflow_hidden()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(border_color)
+                    .bg(if self.selected_module == Some(ix) {
+                        colors.element_selected
+                    } else {
+                        colors.elevated_surface_background
+                    })
+                    .hover(|style| style.bg(colors.element_hover))
+                    .child(Label::new(file_name).size(LabelSize::Small))
+                    .tooltip(move |cx| Tooltip::text(path.clone(), cx))
+                    .on_click(cx.listener(move |this, event, cx| this.click_module(ix, event, cx)))
+            })
+            .collect::<Vec<_>>();
+
+        div().id("module-graph").flex_1().overflow_scroll().child(
+            div()
+                .relative()
+                .w(layout.size.width)
+                .h(layout.size.height)
+                .child(
+                    canvas(move |bounds, cx| {
+                        for (start, end, color, _) in edges {
+                            paint_line(bounds.origin + start, bounds.origin + end, color, cx);
+                        }
+                    })
+                    .absolute()
+                    .size_full(),
+                )
+                .children(nodes),
+        )
+    }
+}
+
+/// Paints a line between two points as a thin quadrilateral.
+fn paint_line(start: Point<Pixels>, end: Point<Pixels>, color: Hsla, cx: &mut ElementContext) {
+    let dx = f32::from(end.x - start.x);
+    let dy = f32::from(end.y - start.y);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0. {
+        return;
+    }
+    let offset = point(px(-dy / length * 0.5), px(dx / length * 0.5));
+    let mut path = PathShape::new(start + offset);
+    path.line_to(end + offset);
+    path.line_to(end - offset);
+    path.line_to(start - offset);
+    cx.paint_path(path, color);
+}
+
+impl Render for ModuleGraphView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ModuleGraph")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::rescan))
+            .child(self.render_header(cx))
+            .child(self.render_graph(cx))
+    }
+}
+
+impl FocusableView for ModuleGraphView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for ModuleGraphView {}
+
+impl Item for ModuleGraphView {
+    type Event = ();
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new("Module Graph")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("module graph")
+    }
+}
@@ -1,15 +1,17 @@
 use anyhow::{anyhow, bail, Context, Result};
-use async_compression::futures::bufread::GzipDecoder;
-use async_tar::Archive;
 use serde::Deserialize;
-use smol::{fs, io::BufReader, lock::Mutex, process::Command};
+use smol::{fs, lock::Mutex, process::Command};
 use std::process::{Output, Stdio};
 use std::{
     env::consts,
     path::{Path, PathBuf},
     sync::Arc,
 };
-use util::http::HttpClient;
+use util::{
+    archive::{self, ArchiveType},
+    audit_log::{self, AuditEvent, AuditEventKind},
+    http::HttpClient,
+};
 
 const VERSION: &str = "v18.15.0";
 
@@ -45,19 +47,24 @@ pub trait NodeRuntime: Send + Sync {
 
 pub struct RealNodeRuntime {
     http: Arc<dyn HttpClient>,
-    installation_lock: Mutex<()>,
+    /// The installation that was verified, so that npm commands that run concurrently,
+    /// such as the installs of several language servers, don't wait on each other.
+    installation_path: Mutex<Option<PathBuf>>,
 }
 
 impl RealNodeRuntime {
     pub fn new(http: Arc<dyn HttpClient>) -> Arc<dyn NodeRuntime> {
         Arc::new(RealNodeRuntime {
             http,
-            installation_lock: Mutex::new(()),
+            installation_path: Mutex::new(None),
         })
     }
 
     async fn install_if_needed(&self) -> Result<PathBuf> {
-        let _lock = self.installation_lock.lock().await;
+        let mut installation_path = self.installation_path.lock().await;
+        if let Some(installation_path) = installation_path.as_ref() {
+            return Ok(installation_path.clone());
+        }
         log::info!("Node runtime install_if_needed");
 
         let arch = match consts::ARCH {
@@ -79,6 +86,7 @@ impl RealNodeRuntime {
             .stdin(Stdio::null())
             .stdout(Stdio::null())
             .stderr(Stdio::null())
+            .kill_on_drop(true)
             .args(["--cache".into(), node_dir.join("cache")])
             .args(["--userconfig".into(), node_dir.join("blank_user_npmrc")])
             .args(["--globalconfig".into(), node_dir.join("blank_global_npmrc")])
@@ -94,15 +102,19 @@ impl RealNodeRuntime {
 
             let file_name = format!("node-{VERSION}-darwin-{arch}.tar.gz");
             let url = format!("https://nodejs.org/dist/{VERSION}/{file_name}");
-            let mut response = self
-                .http
-                .get(&url, Default::default(), true)
-                .await
-                .context("error downloading Node binary tarball")?;
-
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
-            let archive = Archive::new(decompressed_bytes);
-            archive.unpack(&node_containing_dir).await?;
+            audit_log::record(
+                AuditEvent::new(AuditEventKind::Download, "node", format!("Node {VERSION}"))
+                    .details(url.clone()),
+            );
+            archive::download_archive(
+                &*self.http,
+                &url,
+                ArchiveType::TarGz,
+                None,
+                &node_containing_dir,
+            )
+            .await
+            .context("error downloading Node binary tarball")?;
         }
 
         // Note: Not in the `if !valid {}` so we can populate these for existing installations
@@ -110,6 +122,7 @@ impl RealNodeRuntime {
         _ = fs::write(node_dir.join("blank_user_npmrc"), []).await;
         _ = fs::write(node_dir.join("blank_global_npmrc"), []).await;
 
+        *installation_path = Some(node_dir.clone());
         anyhow::Ok(node_dir)
     }
 }
@@ -142,10 +155,12 @@ impl NodeRuntime for RealNodeRuntime {
             let npm_file = installation_path.join("bin/npm");
 
             if smol::fs::metadata(&node_binary).await.is_err() {
+                self.installation_path.lock().await.take();
                 return Err(anyhow!("missing node binary file"));
             }
 
             if smol::fs::metadata(&npm_file).await.is_err() {
+                self.installation_path.lock().await.take();
                 return Err(anyhow!("missing npm file"));
             }
 
@@ -163,6 +178,8 @@ impl NodeRuntime for RealNodeRuntime {
                 installation_path.join("blank_global_npmrc"),
             ]);
             command.args(args);
+            // npm is stopped when the install that it's running for is cancelled.
+            command.kill_on_drop(true);
 
             if let Some(directory) = directory {
                 command.current_dir(directory);
@@ -229,6 +246,8 @@ impl NodeRuntime for RealNodeRuntime {
             .into_iter()
             .map(|(name, version)| format!("{name}@{version}"))
             .collect();
+        archive::check_unverified_install(&format!("the npm packages {}", packages.join(", ")))
+            .await?;
 
         let mut arguments: Vec<_> = packages.iter().map(|p| p.as_str()).collect();
         arguments.extend_from_slice(&[
@@ -240,6 +259,18 @@ impl NodeRuntime for RealNodeRuntime {
             "5000",
         ]);
 
+        audit_log::record(
+            AuditEvent::new(
+                AuditEventKind::Command,
+                "npm",
+                format!("npm install {}", packages.join(" ")),
+            )
+            .details(format!(
+                "npm install {} (in {})",
+                arguments.join(" "),
+                directory.display()
+            )),
+        );
         self.run_npm_subcommand(Some(directory), "install", &arguments)
             .await?;
         Ok(())
@@ -1,3 +1,4 @@
+use collections::HashSet;
 use editor::{
     display_map::ToDisplayPoint, scroll::Autoscroll, Anchor, AnchorRangeExt, DisplayPoint, Editor,
     EditorMode, ToPoint,
@@ -5,17 +6,21 @@ use editor::{
 use fuzzy::StringMatch;
 use gpui::{
     actions, div, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
-    FontStyle, FontWeight, HighlightStyle, ParentElement, Point, Render, Styled, StyledText, Task,
-    TextStyle, View, ViewContext, VisualContext, WeakView, WhiteSpace, WindowContext,
+    FontStyle, FontWeight, HighlightStyle, Model, ParentElement, Point, Render, StrikethroughStyle,
+    Styled, StyledText, Task, TextStyle, View, ViewContext, VisualContext, WeakView, WhiteSpace,
+    WindowContext,
 };
-use language::Outline;
+use language::{Buffer, Outline};
 use ordered_float::OrderedFloat;
 use picker::{Picker, PickerDelegate};
+use project::{DocumentSymbol, Project};
 use settings::Settings;
 use std::{
     cmp::{self, Reverse},
+    ops::Range,
     sync::Arc,
 };
+use text::ToOffset as _;
 
 use theme::{color_alpha, ActiveTheme, ThemeSettings};
 use ui::{prelude::*, ListItem, ListItemSpacing};
@@ -38,13 +43,15 @@ pub fn toggle(editor: View<Editor>, _: &Toggle, cx: &mut WindowContext) {
 
     if let Some((workspace, outline)) = editor.read(cx).workspace().zip(outline) {
         workspace.update(cx, |workspace, cx| {
-            workspace.toggle_modal(cx, |cx| OutlineView::new(outline, editor, cx));
+            let project = workspace.project().clone();
+            workspace.toggle_modal(cx, |cx| OutlineView::new(outline, editor, project, cx));
         })
     }
 }
 
 pub struct OutlineView {
     picker: View<Picker<OutlineViewDelegate>>,
+    _deprecated_symbols: Option<Task<Option<()>>>,
 }
 
 impl FocusableView for OutlineView {
@@ -83,11 +90,34 @@ impl OutlineView {
     fn new(
         outline: Outline<Anchor>,
         editor: View<Editor>,
+        project: Model<Project>,
         cx: &mut ViewContext<Self>,
     ) -> OutlineView {
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton();
         let delegate = OutlineViewDelegate::new(cx.view().downgrade(), outline, editor, cx);
         let picker = cx.new_view(|cx| Picker::new(delegate, cx).max_height(vh(0.75, cx)));
-        OutlineView { picker }
+
+        // The outline comes from the syntax tree, which doesn't know which items are
+        // deprecated, so ask the language server for the buffer's symbols.
+        let deprecated_symbols = buffer.map(|buffer| {
+            let symbols = project.update(cx, |project, cx| project.document_symbols(&buffer, cx));
+            cx.spawn(|outline_view, mut cx| async move {
+                let symbols = symbols.await.log_err()?;
+                outline_view
+                    .update(&mut cx, |outline_view, cx| {
+                        outline_view.picker.update(cx, |picker, cx| {
+                            picker.delegate.mark_deprecated_items(&buffer, &symbols, cx);
+                            cx.notify();
+                        })
+                    })
+                    .ok()
+            })
+        });
+
+        OutlineView {
+            picker,
+            _deprecated_symbols: deprecated_symbols,
+        }
     }
 }
 
@@ -95,6 +125,7 @@ struct OutlineViewDelegate {
     outline_view: WeakView<OutlineView>,
     active_editor: View<Editor>,
     outline: Outline<Anchor>,
+    deprecated_items: HashSet<usize>,
     selected_match_index: usize,
     prev_scroll_position: Option<Point<f32>>,
     matches: Vec<StringMatch>,
@@ -116,9 +147,44 @@ impl OutlineViewDelegate {
             prev_scroll_position: Some(editor.update(cx, |editor, cx| editor.scroll_position(cx))),
             active_editor: editor,
             outline,
+            deprecated_items: HashSet::default(),
         }
     }
 
+    fn mark_deprecated_items(
+        &mut self,
+        buffer: &Model<Buffer>,
+        symbols: &[DocumentSymbol],
+        cx: &AppContext,
+    ) {
+        let buffer = buffer.read(cx);
+        let deprecated_symbols = symbols
+            .iter()
+            .filter(|symbol| symbol.is_deprecated)
+            .map(|symbol| {
+                (
+                    symbol.name_range.start.to_offset(buffer),
+                    symbol.name.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let snapshot = self.active_editor.read(cx).buffer().read(cx).snapshot(cx);
+        let items = self
+            .outline
+            .items
+            .iter()
+            .map(|item| {
+                let names = item
+                    .name_ranges
+                    .iter()
+                    .map(|range| &item.text[range.clone()])
+                    .collect::<Vec<_>>();
+                (item.range.to_offset(&snapshot), names)
+            })
+            .collect::<Vec<_>>();
+        self.deprecated_items = deprecated_outline_items(&items, &deprecated_symbols);
+    }
+
     fn restore_active_editor(&mut self, cx: &mut WindowContext) {
         self.active_editor.update(cx, |editor, cx| {
             editor.highlight_rows(None);
@@ -292,8 +358,22 @@ impl PickerDelegate for OutlineViewDelegate {
         let mat = &self.matches[ix];
         let outline_item = &self.outline.items[mat.candidate_id];
 
+        let deprecated_highlight = self.deprecated_items.contains(&mat.candidate_id).then(|| {
+            (
+                0..outline_item.text.len(),
+                HighlightStyle {
+                    strikethrough: Some(StrikethroughStyle {
+                        thickness: 1.0.into(),
+                        color: None,
+                    }),
+                    ..Default::default()
+                },
+            )
+        });
         let highlights = gpui::combine_highlights(
-            mat.ranges().map(|range| (range, highlight_style)),
+            mat.ranges()
+                .map(|range| (range, highlight_style))
+                .chain(deprecated_highlight),
             outline_item.highlight_ranges.iter().cloned(),
         );
 
@@ -314,3 +394,57 @@ impl PickerDelegate for OutlineViewDelegate {
         )
     }
 }
+
+/// Returns the indices of the outline items that declare one of the deprecated
+/// symbols, given as the offset and text of their names. Each symbol marks the
+/// innermost item that contains it and has a matching name.
+fn deprecated_outline_items(
+    items: &[(Range<usize>, Vec<&str>)],
+    deprecated_symbols: &[(usize, &str)],
+) -> HashSet<usize> {
+    deprecated_symbols
+        .iter()
+        .filter_map(|(offset, name)| {
+            items
+                .iter()
+                .enumerate()
+                .filter(|(_, (range, names))| range.contains(offset) && names.contains(name))
+                .min_by_key(|(_, (range, _))| range.len())
+                .map(|(ix, _)| ix)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deprecated_outline_items() {
+        // struct A;
+        // impl A {
+        //     fn A() {}
+        //     fn old() {}
+        // }
+        let items = [
+            (0..9, vec!["A"]),
+            (10..50, vec!["A"]),
+            (23..32, vec!["A"]),
+            (37..48, vec!["old"]),
+        ];
+
+        assert_eq!(
+            deprecated_outline_items(&items, &[(40, "old")]),
+            HashSet::from_iter([3])
+        );
+        assert_eq!(
+            deprecated_outline_items(&items, &[(26, "A")]),
+            HashSet::from_iter([2])
+        );
+        assert_eq!(
+            deprecated_outline_items(&items, &[(7, "A"), (40, "new")]),
+            HashSet::from_iter([0])
+        );
+        assert!(deprecated_outline_items(&items, &[]).is_empty());
+    }
+}
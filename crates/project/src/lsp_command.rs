@@ -1,7 +1,8 @@
 use crate::{
-    DocumentHighlight, Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel,
+    symbol_is_deprecated, DocumentColor, DocumentHighlight, DocumentLink, DocumentLinkTarget,
+    DocumentSymbol, FoldingRange, Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel,
     InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink,
-    MarkupContent, Project, ProjectTransaction, ResolveState,
+    MarkupContent, Project, ProjectTransaction, ResolveState, SignatureHelp, SignatureInformation,
 };
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
@@ -13,8 +14,8 @@ use language::{
     point_from_lsp, point_to_lsp, prepare_completion_documentation,
     proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
     range_from_lsp, range_to_lsp, Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind,
-    CodeAction, Completion, OffsetRangeExt, PointUtf16, ToOffset, ToPointUtf16, Transaction,
-    Unclipped,
+    CodeAction, CodeLens, Completion, OffsetRangeExt, Point, PointUtf16, ToOffset, ToPoint,
+    ToPointUtf16, Transaction, Unclipped,
 };
 use lsp::{
     CompletionListItemDefaultsEditRange, DocumentHighlightKind, LanguageServer, LanguageServerId,
@@ -113,10 +114,18 @@ pub(crate) struct GetDocumentHighlights {
     pub position: PointUtf16,
 }
 
+pub(crate) struct GetDocumentLink {
+    pub position: PointUtf16,
+}
+
 pub(crate) struct GetHover {
     pub position: PointUtf16,
 }
 
+pub(crate) struct GetSignatureHelp {
+    pub position: PointUtf16,
+}
+
 pub(crate) struct GetCompletions {
     pub position: PointUtf16,
 }
@@ -125,6 +134,18 @@ pub(crate) struct GetCodeActions {
     pub range: Range<Anchor>,
 }
 
+pub(crate) struct GetCodeLens;
+
+pub(crate) struct GetDocumentColors;
+
+pub(crate) struct GetDocumentSymbols;
+
+pub(crate) struct GetFoldingRanges;
+
+pub(crate) struct GetLinkedEditingRanges {
+    pub position: PointUtf16,
+}
+
 pub(crate) struct OnTypeFormatting {
     pub position: PointUtf16,
     pub trigger: String,
@@ -1104,6 +1125,249 @@ impl LspCommand for GetDocumentHighlights {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLink {
+    type Response = Option<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLink;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.document_link_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::DocumentLinkParams {
+        lsp::DocumentLinkParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: lsp::Url::from_file_path(path).unwrap(),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_links: Option<Vec<lsp::DocumentLink>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Option<DocumentLink>> {
+        let position = self.position;
+        let Some(mut lsp_link) = lsp_links.unwrap_or_default().into_iter().find(|link| {
+            point_from_lsp(link.range.start).0 <= position
+                && position <= point_from_lsp(link.range.end).0
+        }) else {
+            return Ok(None);
+        };
+
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        // Servers may defer computing the target until the link is resolved.
+        if lsp_link.target.is_none() {
+            let can_resolve = language_server
+                .capabilities()
+                .document_link_provider
+                .as_ref()
+                .and_then(|options| options.resolve_provider)
+                .unwrap_or(false);
+            if can_resolve {
+                lsp_link = language_server
+                    .request::<lsp::request::DocumentLinkResolve>(lsp_link)
+                    .await?;
+            }
+        }
+        let Some(mut target_uri) = lsp_link.target else {
+            return Ok(None);
+        };
+
+        let range = buffer.update(&mut cx, |buffer, _| {
+            let start = buffer.clip_point_utf16(point_from_lsp(lsp_link.range.start), Bias::Left);
+            let end = buffer.clip_point_utf16(point_from_lsp(lsp_link.range.end), Bias::Left);
+            buffer.anchor_after(start)..buffer.anchor_before(end)
+        })?;
+
+        if target_uri.scheme() != "file" {
+            return Ok(Some(DocumentLink {
+                range,
+                target: DocumentLinkTarget::Url(target_uri.to_string()),
+            }));
+        }
+
+        // File links may point at a position with a `#L<line>,<column>` fragment.
+        let target_position = target_uri
+            .fragment()
+            .and_then(parse_document_link_fragment)
+            .unwrap_or_default();
+        target_uri.set_fragment(None);
+        let target_buffer = project
+            .update(&mut cx, |project, cx| {
+                project.open_local_buffer_via_lsp(
+                    target_uri,
+                    language_server.server_id(),
+                    lsp_adapter.name.clone(),
+                    cx,
+                )
+            })?
+            .await?;
+        let target_anchor = target_buffer.update(&mut cx, |target_buffer, _| {
+            let target_position = target_buffer.clip_point_utf16(target_position, Bias::Left);
+            target_buffer.anchor_after(target_position)
+        })?;
+
+        Ok(Some(DocumentLink {
+            range,
+            target: DocumentLinkTarget::Location(Location {
+                buffer: target_buffer,
+                range: target_anchor..target_anchor,
+            }),
+        }))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentLink {
+        proto::GetDocumentLink {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentLink,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Option<DocumentLink>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::GetDocumentLinkResponse {
+        let link = response.map(|link| {
+            let target = match link.target {
+                DocumentLinkTarget::Url(url) => proto::document_link::Target::Url(url),
+                DocumentLinkTarget::Location(location) => {
+                    let buffer_id = project
+                        .create_buffer_for_peer(&location.buffer, peer_id, cx)
+                        .into();
+                    proto::document_link::Target::Location(proto::Location {
+                        buffer_id,
+                        start: Some(serialize_anchor(&location.range.start)),
+                        end: Some(serialize_anchor(&location.range.end)),
+                    })
+                }
+            };
+            proto::DocumentLink {
+                start: Some(serialize_anchor(&link.range.start)),
+                end: Some(serialize_anchor(&link.range.end)),
+                target: Some(target),
+            }
+        });
+        proto::GetDocumentLinkResponse { link }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinkResponse,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Option<DocumentLink>> {
+        let Some(link) = message.link else {
+            return Ok(None);
+        };
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("missing link start"))?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("missing link end"))?;
+        buffer
+            .update(&mut cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+            .await?;
+
+        let target = match link.target.ok_or_else(|| anyhow!("missing link target"))? {
+            proto::document_link::Target::Url(url) => DocumentLinkTarget::Url(url),
+            proto::document_link::Target::Location(location) => {
+                let buffer_id = BufferId::new(location.buffer_id)?;
+                let target_buffer = project
+                    .update(&mut cx, |this, cx| {
+                        this.wait_for_remote_buffer(buffer_id, cx)
+                    })?
+                    .await?;
+                let target_start = location
+                    .start
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("missing target start"))?;
+                let target_end = location
+                    .end
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("missing target end"))?;
+                target_buffer
+                    .update(&mut cx, |buffer, _| {
+                        buffer.wait_for_anchors([target_start, target_end])
+                    })?
+                    .await?;
+                DocumentLinkTarget::Location(Location {
+                    buffer: target_buffer,
+                    range: target_start..target_end,
+                })
+            }
+        };
+
+        Ok(Some(DocumentLink {
+            range: start..end,
+            target,
+        }))
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentLink) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+/// Parses the `L<line>` or `L<line>,<column>` fragment of a file link into a zero-based position.
+fn parse_document_link_fragment(fragment: &str) -> Option<Unclipped<PointUtf16>> {
+    let fragment = fragment.strip_prefix('L').unwrap_or(fragment);
+    let (row, column) = match fragment.split_once(',') {
+        Some((row, column)) => (row, Some(column)),
+        None => (fragment, None),
+    };
+    let row = row.parse::<u32>().ok()?.saturating_sub(1);
+    let column = match column {
+        Some(column) => column.parse::<u32>().ok()?.saturating_sub(1),
+        None => 0,
+    };
+    Some(Unclipped(PointUtf16::new(row, column)))
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetHover {
     type Response = Option<Hover>;
@@ -1321,10 +1585,14 @@ impl LspCommand for GetHover {
 }
 
 #[async_trait(?Send)]
-impl LspCommand for GetCompletions {
-    type Response = Vec<Completion>;
-    type LspRequest = lsp::request::Completion;
-    type ProtoRequest = proto::GetCompletions;
+impl LspCommand for GetSignatureHelp {
+    type Response = Option<SignatureHelp>;
+    type LspRequest = lsp::request::SignatureHelpRequest;
+    type ProtoRequest = proto::GetSignatureHelp;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.signature_help_provider.is_some()
+    }
 
     fn to_lsp(
         &self,
@@ -1332,51 +1600,307 @@ impl LspCommand for GetCompletions {
         _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &AppContext,
-    ) -> lsp::CompletionParams {
-        lsp::CompletionParams {
-            text_document_position: lsp::TextDocumentPositionParams::new(
-                lsp::TextDocumentIdentifier::new(lsp::Url::from_file_path(path).unwrap()),
-                point_to_lsp(self.position),
-            ),
-            context: Default::default(),
+    ) -> lsp::SignatureHelpParams {
+        lsp::SignatureHelpParams {
+            context: None,
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
             work_done_progress_params: Default::default(),
-            partial_result_params: Default::default(),
         }
     }
 
     async fn response_from_lsp(
         self,
-        completions: Option<lsp::CompletionResponse>,
-        project: Model<Project>,
+        message: Option<lsp::SignatureHelp>,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: LanguageServerId,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        let Some(help) = message.filter(|help| !help.signatures.is_empty()) else {
+            return Ok(None);
+        };
+
+        // Like the spec says, an active signature that is out of range defaults to the
+        // first one.
+        let active_signature = help
+            .active_signature
+            .map(|ix| ix as usize)
+            .filter(|ix| *ix < help.signatures.len())
+            .unwrap_or(0);
+        let signatures = help
+            .signatures
+            .into_iter()
+            .map(|signature| signature_information_from_lsp(signature, help.active_parameter))
+            .collect::<Vec<_>>();
+        Ok(Some(SignatureHelp {
+            active_signature,
+            signatures,
+        }))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSignatureHelp {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version),
+        }
+    }
+
+    async fn from_proto(
+        message: Self::ProtoRequest,
+        _: Model<Project>,
         buffer: Model<Buffer>,
-        server_id: LanguageServerId,
         mut cx: AsyncAppContext,
-    ) -> Result<Vec<Completion>> {
-        let mut response_list = None;
-        let completions = if let Some(completions) = completions {
-            match completions {
-                lsp::CompletionResponse::Array(completions) => completions,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
 
-                lsp::CompletionResponse::List(mut list) => {
-                    let items = std::mem::take(&mut list.items);
-                    response_list = Some(list);
-                    items
-                }
-            }
-        } else {
-            Default::default()
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut Project,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetSignatureHelpResponse {
+        let Some(help) = response else {
+            return proto::GetSignatureHelpResponse::default();
         };
+        proto::GetSignatureHelpResponse {
+            signatures: help
+                .signatures
+                .into_iter()
+                .map(|signature| proto::SignatureInformation {
+                    label: signature.label,
+                    documentation: signature.documentation,
+                    parameters: signature
+                        .parameters
+                        .into_iter()
+                        .map(|range| proto::Range {
+                            start: range.start as u64,
+                            end: range.end as u64,
+                        })
+                        .collect(),
+                    active_parameter: signature.active_parameter.map(|ix| ix as u64),
+                })
+                .collect(),
+            active_signature: help.active_signature as u64,
+        }
+    }
 
-        let completions = buffer.update(&mut cx, |buffer, cx| {
-            let language_registry = project.read(cx).languages().clone();
-            let language = buffer.language().cloned();
-            let snapshot = buffer.snapshot();
-            let clipped_position = buffer.clip_point_utf16(Unclipped(self.position), Bias::Left);
+    async fn response_from_proto(
+        self,
+        message: proto::GetSignatureHelpResponse,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        if message.signatures.is_empty() {
+            return Ok(None);
+        }
+
+        let signatures = message
+            .signatures
+            .into_iter()
+            .map(|signature| SignatureInformation {
+                label: signature.label,
+                documentation: signature.documentation,
+                parameters: signature
+                    .parameters
+                    .into_iter()
+                    .map(|range| range.start as usize..range.end as usize)
+                    .collect(),
+                active_parameter: signature.active_parameter.map(|ix| ix as usize),
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(SignatureHelp {
+            active_signature: (message.active_signature as usize).min(signatures.len() - 1),
+            signatures,
+        }))
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+fn signature_information_from_lsp(
+    signature: lsp::SignatureInformation,
+    active_parameter: Option<u32>,
+) -> SignatureInformation {
+    let label = signature.label;
+    let active_parameter = signature
+        .active_parameter
+        .or(active_parameter)
+        .map(|ix| ix as usize);
+
+    // Parameters given by name are searched for in order, after the opening parenthesis,
+    // so that they don't match the name of the callable itself.
+    let mut search_start = label.find('(').map_or(0, |ix| ix + 1);
+    let mut parameters = Vec::new();
+    let mut active_parameter_ix = None;
+    for (ix, parameter) in signature
+        .parameters
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+    {
+        let range = match parameter.label {
+            lsp::ParameterLabel::Simple(name) => label
+                .get(search_start..)
+                .and_then(|rest| rest.find(&name))
+                .map(|start| search_start + start..search_start + start + name.len()),
+            lsp::ParameterLabel::LabelOffsets([start, end]) => {
+                byte_offset_for_utf16_offset(&label, start)
+                    .zip(byte_offset_for_utf16_offset(&label, end))
+                    .map(|(start, end)| start..end)
+            }
+        };
+        // Parameters that can't be found in the label are skipped, so the active
+        // parameter's index is remapped to account for them.
+        let Some(range) = range else {
+            continue;
+        };
+        if active_parameter == Some(ix) {
+            active_parameter_ix = Some(parameters.len());
+        }
+        search_start = range.end;
+        parameters.push(range);
+    }
+    let documentation = signature
+        .documentation
+        .map(|documentation| match documentation {
+            lsp::Documentation::String(text) => text,
+            lsp::Documentation::MarkupContent(content) => content.value,
+        });
+
+    SignatureInformation {
+        label,
+        documentation,
+        parameters,
+        active_parameter: active_parameter_ix,
+    }
+}
+
+fn byte_offset_for_utf16_offset(text: &str, utf16_offset: u32) -> Option<usize> {
+    let mut utf16_len = 0;
+    for (ix, ch) in text.char_indices() {
+        if utf16_len >= utf16_offset as usize {
+            return Some(ix);
+        }
+        utf16_len += ch.len_utf16();
+    }
+    (utf16_len == utf16_offset as usize).then_some(text.len())
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetCompletions {
+    type Response = Vec<Completion>;
+    type LspRequest = lsp::request::Completion;
+    type ProtoRequest = proto::GetCompletions;
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CompletionParams {
+        lsp::CompletionParams {
+            text_document_position: lsp::TextDocumentPositionParams::new(
+                lsp::TextDocumentIdentifier::new(lsp::Url::from_file_path(path).unwrap()),
+                point_to_lsp(self.position),
+            ),
+            context: Default::default(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        completions: Option<lsp::CompletionResponse>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Completion>> {
+        let mut response_list = None;
+        let completions = if let Some(completions) = completions {
+            match completions {
+                lsp::CompletionResponse::Array(completions) => completions,
+
+                lsp::CompletionResponse::List(mut list) => {
+                    let items = std::mem::take(&mut list.items);
+                    response_list = Some(list);
+                    items
+                }
+            }
+        } else {
+            Default::default()
+        };
+
+        let completions = buffer.update(&mut cx, |buffer, cx| {
+            let language_registry = project.read(cx).languages().clone();
+            let language = buffer.language().cloned();
+            let snapshot = buffer.snapshot();
+            let clipped_position = buffer.clip_point_utf16(Unclipped(self.position), Bias::Left);
+            // The server's commit characters apply to the items that have none of their own.
+            let all_commit_characters = project
+                .read(cx)
+                .language_server_for_id(server_id)
+                .and_then(|server| {
+                    server
+                        .capabilities()
+                        .completion_provider
+                        .as_ref()?
+                        .all_commit_characters
+                        .clone()
+                });
 
             let mut range_for_token = None;
             completions
                 .into_iter()
                 .filter_map(move |mut lsp_completion| {
+                    // Servers may omit the properties shared by all items and rely on the
+                    // list's defaults instead. The `data` in particular has to be sent back
+                    // when the item is resolved.
+                    if let Some(defaults) = response_list
+                        .as_ref()
+                        .and_then(|list| list.item_defaults.as_ref())
+                    {
+                        if lsp_completion.data.is_none() {
+                            lsp_completion.data = defaults.data.clone();
+                        }
+                        if lsp_completion.commit_characters.is_none() {
+                            lsp_completion.commit_characters = defaults.commit_characters.clone();
+                        }
+                        if lsp_completion.insert_text_mode.is_none() {
+                            lsp_completion.insert_text_mode = defaults.insert_text_mode;
+                        }
+                    }
+                    if lsp_completion.commit_characters.is_none() {
+                        lsp_completion.commit_characters = all_commit_characters.clone();
+                    }
+
                     let (old_range, mut new_text) = match lsp_completion.text_edit.as_ref() {
                         // If the language server provides a range to overwrite, then
                         // check that the range is valid.
@@ -1485,7 +2009,7 @@ impl LspCommand for GetCompletions {
                                 )
                             }),
                             documentation,
-                            server_id,
+                            server_id: Some(server_id),
                             lsp_completion,
                         }
                     })
@@ -1707,6 +2231,755 @@ impl LspCommand for GetCodeActions {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetCodeLens {
+    type Response = Vec<CodeLens>;
+    type LspRequest = lsp::request::CodeLensRequest;
+    type ProtoRequest = proto::GetCodeLens;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.code_lens_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CodeLensParams {
+        lsp::CodeLensParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lenses: Option<Vec<lsp::CodeLens>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        let language_server = project
+            .update(&mut cx, |project, _| {
+                project.language_server_for_id(server_id)
+            })?
+            .ok_or_else(|| anyhow!("no language server found for id {server_id}"))?;
+        let can_resolve = language_server
+            .capabilities()
+            .code_lens_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false);
+
+        // Servers may defer computing the command (e.g. the reference count)
+        // until the lens is resolved, so resolve all such lenses at once.
+        let lsp_lenses = future::join_all(lenses.unwrap_or_default().into_iter().map(|lsp_lens| {
+            let language_server = language_server.clone();
+            async move {
+                if lsp_lens.command.is_some() || !can_resolve {
+                    return lsp_lens;
+                }
+                match language_server
+                    .request::<lsp::request::CodeLensResolve>(lsp_lens.clone())
+                    .await
+                {
+                    Ok(resolved) => resolved,
+                    Err(error) => {
+                        log::warn!("failed to resolve code lens: {error:#}");
+                        lsp_lens
+                    }
+                }
+            }
+        }))
+        .await;
+
+        let mut code_lenses = Vec::new();
+        for lsp_lens in lsp_lenses {
+            if lsp_lens.command.is_none() {
+                continue;
+            }
+
+            let range = buffer.update(&mut cx, |buffer, _| {
+                let start =
+                    buffer.clip_point_utf16(point_from_lsp(lsp_lens.range.start), Bias::Left);
+                let end = buffer.clip_point_utf16(point_from_lsp(lsp_lens.range.end), Bias::Left);
+                buffer.anchor_after(start)..buffer.anchor_before(end)
+            })?;
+            code_lenses.push(CodeLens {
+                server_id,
+                range,
+                lsp_lens,
+            });
+        }
+        Ok(code_lenses)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeLens {
+        proto::GetCodeLens {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCodeLens,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        code_lenses: Vec<CodeLens>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetCodeLensResponse {
+        proto::GetCodeLensResponse {
+            lenses: code_lenses
+                .iter()
+                .map(language::proto::serialize_code_lens)
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCodeLensResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .lenses
+            .into_iter()
+            .map(language::proto::deserialize_code_lens)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCodeLens) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentColors {
+    type Response = Vec<DocumentColor>;
+    type LspRequest = lsp::request::DocumentColor;
+    type ProtoRequest = proto::GetDocumentColors;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.color_provider {
+            Some(lsp::ColorProviderCapability::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::DocumentColorParams {
+        lsp::DocumentColorParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        colors: Vec<lsp::ColorInformation>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentColor>> {
+        buffer.update(&mut cx, |buffer, _| {
+            colors
+                .into_iter()
+                .map(|info| {
+                    let start =
+                        buffer.clip_point_utf16(point_from_lsp(info.range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(info.range.end), Bias::Left);
+                    DocumentColor {
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        color: info.color,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentColors {
+        proto::GetDocumentColors {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentColors,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        colors: Vec<DocumentColor>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetDocumentColorsResponse {
+        proto::GetDocumentColorsResponse {
+            colors: colors
+                .into_iter()
+                .map(|color| proto::DocumentColor {
+                    start: Some(serialize_anchor(&color.range.start)),
+                    end: Some(serialize_anchor(&color.range.end)),
+                    red: color.color.red,
+                    green: color.color.green,
+                    blue: color.color.blue,
+                    alpha: color.color.alpha,
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentColorsResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentColor>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .colors
+            .into_iter()
+            .map(|color| {
+                let start = color
+                    .start
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid start"))?;
+                let end = color
+                    .end
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid end"))?;
+                Ok(DocumentColor {
+                    range: start..end,
+                    color: lsp::Color {
+                        red: color.red,
+                        green: color.green,
+                        blue: color.blue,
+                        alpha: color.alpha,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentColors) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentSymbols {
+    type Response = Vec<DocumentSymbol>;
+    type LspRequest = lsp::request::DocumentSymbolRequest;
+    type ProtoRequest = proto::GetDocumentSymbols;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.document_symbol_provider {
+            Some(OneOf::Left(enabled)) => *enabled,
+            Some(OneOf::Right(_)) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::DocumentSymbolParams {
+        lsp::DocumentSymbolParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        response: Option<lsp::DocumentSymbolResponse>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentSymbol>> {
+        #[allow(deprecated)]
+        let symbols = match response {
+            Some(lsp::DocumentSymbolResponse::Flat(symbols)) => symbols
+                .into_iter()
+                .map(|symbol| {
+                    let is_deprecated =
+                        symbol_is_deprecated(symbol.tags.as_deref(), symbol.deprecated);
+                    (symbol.name, symbol.location.range, is_deprecated)
+                })
+                .collect(),
+            Some(lsp::DocumentSymbolResponse::Nested(symbols)) => {
+                let mut flattened = Vec::new();
+                let mut stack = symbols;
+                stack.reverse();
+                while let Some(symbol) = stack.pop() {
+                    let is_deprecated =
+                        symbol_is_deprecated(symbol.tags.as_deref(), symbol.deprecated);
+                    flattened.push((symbol.name, symbol.selection_range, is_deprecated));
+                    if let Some(mut children) = symbol.children {
+                        children.reverse();
+                        stack.extend(children);
+                    }
+                }
+                flattened
+            }
+            None => Vec::new(),
+        };
+
+        buffer.update(&mut cx, |buffer, _| {
+            symbols
+                .into_iter()
+                .map(|(name, range, is_deprecated)| {
+                    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    DocumentSymbol {
+                        name,
+                        name_range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        is_deprecated,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentSymbols {
+        proto::GetDocumentSymbols {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentSymbols,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        symbols: Vec<DocumentSymbol>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetDocumentSymbolsResponse {
+        proto::GetDocumentSymbolsResponse {
+            symbols: symbols
+                .into_iter()
+                .map(|symbol| proto::DocumentSymbol {
+                    name: symbol.name,
+                    start: Some(serialize_anchor(&symbol.name_range.start)),
+                    end: Some(serialize_anchor(&symbol.name_range.end)),
+                    is_deprecated: symbol.is_deprecated,
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentSymbolsResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentSymbol>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .symbols
+            .into_iter()
+            .map(|symbol| {
+                let start = symbol
+                    .start
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid start"))?;
+                let end = symbol
+                    .end
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid end"))?;
+                Ok(DocumentSymbol {
+                    name: symbol.name,
+                    name_range: start..end,
+                    is_deprecated: symbol.is_deprecated,
+                })
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentSymbols) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetFoldingRanges {
+    type Response = Vec<FoldingRange>;
+    type LspRequest = lsp::request::FoldingRangeRequest;
+    type ProtoRequest = proto::GetFoldingRanges;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.folding_range_provider {
+            Some(lsp::FoldingRangeProviderCapability::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::FoldingRangeParams {
+        lsp::FoldingRangeParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        ranges: Option<Vec<lsp::FoldingRange>>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<FoldingRange>> {
+        buffer.update(&mut cx, |buffer, _| {
+            let max_row = buffer.max_point().row;
+            // Folding ranges are made of lines, and keep their first line visible unless
+            // the server says where in it they start.
+            let point = |row: u32, column: Option<u32>| {
+                let row = row.min(max_row);
+                match column {
+                    Some(column) => buffer
+                        .clip_point_utf16(Unclipped(PointUtf16::new(row, column)), Bias::Left)
+                        .to_point(buffer),
+                    None => Point::new(row, buffer.line_len(row)),
+                }
+            };
+            ranges
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|range| range.start_line < range.end_line)
+                .map(|range| {
+                    let start = point(range.start_line, range.start_character);
+                    let end = point(range.end_line, range.end_character);
+                    FoldingRange {
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        kind: range.kind,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetFoldingRanges {
+        proto::GetFoldingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetFoldingRanges,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        ranges: Vec<FoldingRange>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetFoldingRangesResponse {
+        proto::GetFoldingRangesResponse {
+            ranges: ranges
+                .into_iter()
+                .map(|range| proto::FoldingRange {
+                    start: Some(serialize_anchor(&range.range.start)),
+                    end: Some(serialize_anchor(&range.range.end)),
+                    kind: range.kind.map(|kind| match kind {
+                        lsp::FoldingRangeKind::Comment => "comment".to_string(),
+                        lsp::FoldingRangeKind::Imports => "imports".to_string(),
+                        lsp::FoldingRangeKind::Region => "region".to_string(),
+                    }),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetFoldingRangesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<FoldingRange>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .ranges
+            .into_iter()
+            .map(|range| {
+                let start = range
+                    .start
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid start"))?;
+                let end = range
+                    .end
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid end"))?;
+                Ok(FoldingRange {
+                    range: start..end,
+                    kind: range.kind.and_then(|kind| match kind.as_str() {
+                        "comment" => Some(lsp::FoldingRangeKind::Comment),
+                        "imports" => Some(lsp::FoldingRangeKind::Imports),
+                        "region" => Some(lsp::FoldingRangeKind::Region),
+                        _ => None,
+                    }),
+                })
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetFoldingRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetLinkedEditingRanges {
+    type Response = Vec<Range<Anchor>>;
+    type LspRequest = lsp::request::LinkedEditingRange;
+    type ProtoRequest = proto::GetLinkedEditingRanges;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        match &capabilities.linked_editing_range_provider {
+            Some(lsp::LinkedEditingRangeServerCapabilities::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::LinkedEditingRangeParams {
+        lsp::LinkedEditingRangeParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        linked_ranges: Option<lsp::LinkedEditingRanges>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<Anchor>>> {
+        let Some(linked_ranges) = linked_ranges else {
+            return Ok(Vec::new());
+        };
+        buffer.update(&mut cx, |buffer, _| {
+            linked_ranges
+                .ranges
+                .into_iter()
+                .map(|range| {
+                    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    // The ranges grow when text is typed at either of their ends.
+                    buffer.anchor_before(start)..buffer.anchor_after(end)
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetLinkedEditingRanges {
+        proto::GetLinkedEditingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetLinkedEditingRanges,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        ranges: Vec<Range<Anchor>>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetLinkedEditingRangesResponse {
+        proto::GetLinkedEditingRangesResponse {
+            ranges: ranges
+                .into_iter()
+                .map(|range| proto::LinkedEditingRange {
+                    start: Some(serialize_anchor(&range.start)),
+                    end: Some(serialize_anchor(&range.end)),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetLinkedEditingRangesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<Anchor>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .ranges
+            .into_iter()
+            .map(|range| {
+                let start = range
+                    .start
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid start"))?;
+                let end = range
+                    .end
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid end"))?;
+                Ok(start..end)
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetLinkedEditingRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for OnTypeFormatting {
     type Response = Option<Transaction>;
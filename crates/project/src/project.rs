@@ -19,6 +19,7 @@ use clock::ReplicaId;
 use collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use copilot::Copilot;
 use debounced_delay::DebouncedDelay;
+use fs::repository::{GitRepository, RepoPath};
 use futures::{
     channel::mpsc::{self, UnboundedReceiver},
     future::{try_join_all, Shared},
@@ -33,13 +34,13 @@ use gpui::{
 use itertools::Itertools;
 use language::{
     language_settings::{language_settings, FormatOnSave, Formatter, InlayHintKind},
-    markdown, point_to_lsp,
+    markdown, point_from_lsp, point_to_lsp,
     proto::{
         deserialize_anchor, deserialize_fingerprint, deserialize_line_ending, deserialize_version,
         serialize_anchor, serialize_version, split_operations,
     },
     range_from_lsp, range_to_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability,
-    CodeAction, CodeLabel, Completion, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff,
+    CodeAction, CodeLabel, CodeLens, Completion, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff,
     Documentation, Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName,
     LocalFile, LspAdapterDelegate, OffsetRangeExt, Operation, Patch, PendingLanguageServer,
     PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
@@ -93,6 +94,15 @@ pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use worktree::*;
 
 const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
+/// How long a command that refreshes diagnostics may stay unanswered before it counts as sent.
+const REFRESH_DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many times in a row a language server that exits unexpectedly is restarted.
+const MAX_SERVER_RESTART_ATTEMPT_COUNT: u32 = 5;
+/// How long Zed waits before restarting a language server that exited unexpectedly for
+/// the first time, which doubles with each further attempt.
+const SERVER_RESTART_DELAY: Duration = Duration::from_secs(1);
+/// How long a restarted language server must run for its restart attempts to be reset.
+const SERVER_RESTART_RESET_INTERVAL: Duration = Duration::from_secs(5 * 60);
 
 pub trait Item {
     fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId>;
@@ -109,6 +119,8 @@ pub struct Project {
     language_servers: HashMap<LanguageServerId, LanguageServerState>,
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
+    /// The restarts of language servers that exited unexpectedly, by the servers' names.
+    language_server_restarts: HashMap<LanguageServerName, LanguageServerRestarts>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     client: Arc<client::Client>,
     next_entry_id: Arc<AtomicUsize>,
@@ -133,6 +145,7 @@ pub struct Project {
         HashMap<Arc<Path>, Shared<Task<Result<Model<Worktree>, Arc<anyhow::Error>>>>>,
     opened_buffers: HashMap<BufferId, OpenBuffer>,
     local_buffer_ids_by_path: HashMap<ProjectPath, BufferId>,
+    local_buffer_ids_by_canonical_path: HashMap<PathBuf, BufferId>,
     local_buffer_ids_by_entry_id: HashMap<ProjectEntryId, BufferId>,
     /// A mapping from a buffer ID to None means that we've started waiting for an ID but haven't finished loading it.
     /// Used for re-issuing buffer requests when peers temporarily disconnect
@@ -246,6 +259,42 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    /// A buffer of a local file was saved to disk.
+    BufferSaved(ProjectPath),
+    /// A language server exited without being stopped. The message tells whether it's
+    /// restarted, and the report contains the last lines it wrote to its stderr.
+    LanguageServerCrashed {
+        language_server_id: LanguageServerId,
+        message: String,
+        report: String,
+    },
+}
+
+struct LanguageServerRestarts {
+    attempt_count: u32,
+    last_restarted_at: Instant,
+}
+
+impl LanguageServerRestarts {
+    fn new(now: Instant) -> Self {
+        Self {
+            attempt_count: 0,
+            last_restarted_at: now,
+        }
+    }
+
+    /// Records another restart at `now`, returning how long to wait before starting the
+    /// server again, or `None` if it has been restarted too many times in a row.
+    fn next_restart_delay(&mut self, now: Instant) -> Option<Duration> {
+        if now.saturating_duration_since(self.last_restarted_at) > SERVER_RESTART_RESET_INTERVAL {
+            self.attempt_count = 0;
+        }
+        let delay = (self.attempt_count < MAX_SERVER_RESTART_ATTEMPT_COUNT)
+            .then(|| SERVER_RESTART_DELAY * 2u32.pow(self.attempt_count));
+        self.attempt_count += 1;
+        self.last_restarted_at = now;
+        delay
+    }
 }
 
 pub enum LanguageServerState {
@@ -358,6 +407,43 @@ pub struct LocationLink {
     pub target: Location,
 }
 
+#[derive(Debug, Clone)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    pub target: DocumentLinkTarget,
+}
+
+#[derive(Debug, Clone)]
+pub enum DocumentLinkTarget {
+    /// A link to a resource outside of the project, such as a web page.
+    Url(String),
+    /// A link to a file, opened in a buffer.
+    Location(Location),
+}
+
+/// A color that a language server found in a buffer, such as a CSS color value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentColor {
+    pub range: Range<language::Anchor>,
+    pub color: lsp::Color,
+}
+
+/// A symbol that a language server reported in a buffer, such as a function or a type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    /// The range of the symbol's name.
+    pub name_range: Range<language::Anchor>,
+    pub is_deprecated: bool,
+}
+
+/// A range that a language server reported as foldable, such as a block of imports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoldingRange {
+    pub range: Range<language::Anchor>,
+    pub kind: Option<lsp::FoldingRangeKind>,
+}
+
 #[derive(Debug)]
 pub struct DocumentHighlight {
     pub range: Range<language::Anchor>,
@@ -374,6 +460,7 @@ pub struct Symbol {
     pub kind: lsp::SymbolKind,
     pub range: Range<Unclipped<PointUtf16>>,
     pub signature: [u8; 32],
+    pub is_deprecated: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -402,6 +489,22 @@ impl Hover {
     }
 }
 
+/// The signatures of the call surrounding a position, as reported by a language server.
+#[derive(Debug, Clone)]
+pub struct SignatureHelp {
+    pub signatures: Vec<SignatureInformation>,
+    pub active_signature: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureInformation {
+    pub label: String,
+    pub documentation: Option<String>,
+    /// The byte ranges of the signature's parameters within its label.
+    pub parameters: Vec<Range<usize>>,
+    pub active_parameter: Option<usize>,
+}
+
 #[derive(Default)]
 pub struct ProjectTransaction(pub HashMap<Model<Buffer>, language::Transaction>);
 
@@ -475,6 +578,7 @@ pub enum FormatTrigger {
 struct ProjectLspAdapterDelegate {
     project: Model<Project>,
     http_client: Arc<dyn HttpClient>,
+    worktree_root_path: Arc<Path>,
 }
 
 // Currently, formatting operations are represented differently depending on
@@ -557,8 +661,15 @@ impl Project {
         client.add_model_request_handler(Self::handle_synchronize_buffers);
         client.add_model_request_handler(Self::handle_format_buffers);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentColors>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentLink>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetLinkedEditingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSignatureHelp>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
@@ -600,6 +711,7 @@ impl Project {
                 loading_buffers_by_path: Default::default(),
                 loading_local_worktrees: Default::default(),
                 local_buffer_ids_by_path: Default::default(),
+                local_buffer_ids_by_canonical_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
                 buffer_snapshots: Default::default(),
                 join_project_response_message_id: 0,
@@ -624,6 +736,7 @@ impl Project {
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
                 language_server_statuses: Default::default(),
+                language_server_restarts: Default::default(),
                 last_workspace_edits_by_language_server: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffers_needing_diff: Default::default(),
@@ -685,6 +798,7 @@ impl Project {
                 incomplete_remote_buffers: Default::default(),
                 loading_local_worktrees: Default::default(),
                 local_buffer_ids_by_path: Default::default(),
+                local_buffer_ids_by_canonical_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
                 active_entry: None,
                 collaborators: Default::default(),
@@ -889,20 +1003,17 @@ impl Project {
                 if !language_settings(Some(language), file.as_ref(), cx).enable_language_server {
                     language_servers_to_stop.push((*worktree_id, started_lsp_name.clone()));
                 } else if let Some(worktree) = worktree {
+                    // Changes to the server's `settings` are sent to it without a restart,
+                    // see `maintain_workspace_config`.
                     let server_name = &adapter.name.0;
-                    match (
-                        current_lsp_settings.get(server_name),
-                        new_lsp_settings.get(server_name),
-                    ) {
-                        (None, None) => {}
-                        (Some(_), None) | (None, Some(_)) => {
-                            language_servers_to_restart.push((worktree, Arc::clone(language)));
-                        }
-                        (Some(current_lsp_settings), Some(new_lsp_settings)) => {
-                            if current_lsp_settings != new_lsp_settings {
-                                language_servers_to_restart.push((worktree, Arc::clone(language)));
-                            }
-                        }
+                    let current_options = current_lsp_settings
+                        .get(server_name)
+                        .and_then(|settings| settings.initialization_options.as_ref());
+                    let new_options = new_lsp_settings
+                        .get(server_name)
+                        .and_then(|settings| settings.initialization_options.as_ref());
+                    if current_options != new_options {
+                        language_servers_to_restart.push((worktree, Arc::clone(language)));
                     }
                 }
             }
@@ -1067,6 +1178,22 @@ impl Project {
             .find(|worktree| worktree.read(cx).id() == id)
     }
 
+    /// Returns the local git repository containing the given path, along with that
+    /// path relative to the repository's working directory.
+    pub fn git_repository_for_path(
+        &self,
+        project_path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Option<(Arc<Mutex<dyn GitRepository>>, RepoPath)> {
+        let worktree = self.worktree_for_id(project_path.worktree_id, cx)?;
+        let worktree = worktree.read(cx).as_local()?;
+        let (work_directory, repo) =
+            worktree.repository_and_work_directory_for_path(&project_path.path)?;
+        let repo = worktree.get_local_repo(&repo)?;
+        let relative_path = project_path.path.strip_prefix(&work_directory).ok()?;
+        Some((repo.repo_ptr.clone(), RepoPath::from(relative_path)))
+    }
+
     pub fn worktree_for_entry(
         &self,
         entry_id: ProjectEntryId,
@@ -1772,17 +1899,79 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Model<Buffer>>> {
         let buffer_id = self.next_buffer_id.next();
-        let load_buffer = worktree.update(cx, |worktree, cx| {
-            let worktree = worktree.as_local_mut().unwrap();
-            worktree.load_buffer(buffer_id, path, cx)
-        });
+        let abs_path = worktree.read(cx).as_local().unwrap().absolutize(path);
+        let fs = self.fs.clone();
+        let worktree = worktree.clone();
+        let path = path.clone();
         cx.spawn(move |this, mut cx| async move {
+            // A file can be reached through several paths, such as through symlinked
+            // directories. Only open it once, so that saving it through one path doesn't
+            // overwrite the edits made through another.
+            let canonical_path = match abs_path {
+                Ok(abs_path) => fs.canonicalize(&abs_path).await.ok(),
+                Err(_) => None,
+            };
+            if let Some(canonical_path) = &canonical_path {
+                let existing_buffer = this.update(&mut cx, |this, cx| {
+                    this.open_buffer_for_canonical_path(canonical_path, cx)
+                })?;
+                if let Some((existing_buffer, existing_abs_path)) = existing_buffer {
+                    // The existing buffer's file may have been renamed or deleted since.
+                    if fs.canonicalize(&existing_abs_path).await.ok().as_ref()
+                        == Some(canonical_path)
+                    {
+                        this.update(&mut cx, |_, cx| {
+                            cx.emit(Event::Notification(format!(
+                                "{} is the same file as {}, which is already open",
+                                path.display(),
+                                existing_abs_path.display()
+                            )))
+                        })?;
+                        return Ok(existing_buffer);
+                    }
+                }
+            }
+
+            let load_buffer = worktree.update(&mut cx, |worktree, cx| {
+                let worktree = worktree.as_local_mut().unwrap();
+                worktree.load_buffer(buffer_id, &path, cx)
+            })?;
             let buffer = load_buffer.await?;
-            this.update(&mut cx, |this, cx| this.register_buffer(&buffer, cx))??;
+            this.update(&mut cx, |this, cx| {
+                this.register_buffer(&buffer, cx)?;
+                if let Some(canonical_path) = canonical_path {
+                    this.local_buffer_ids_by_canonical_path
+                        .insert(canonical_path, buffer.read(cx).remote_id());
+                }
+                anyhow::Ok(())
+            })??;
             Ok(buffer)
         })
     }
 
+    /// Returns the open buffer that was loaded from the given canonical path, along with
+    /// the path of its file.
+    fn open_buffer_for_canonical_path(
+        &mut self,
+        canonical_path: &Path,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<(Model<Buffer>, PathBuf)> {
+        let buffer_id = *self
+            .local_buffer_ids_by_canonical_path
+            .get(canonical_path)?;
+        let Some(buffer) = self
+            .opened_buffers
+            .get(&buffer_id)
+            .and_then(|buffer| buffer.upgrade())
+        else {
+            self.local_buffer_ids_by_canonical_path
+                .remove(canonical_path);
+            return None;
+        };
+        let abs_path = File::from_dyn(buffer.read(cx).file())?.abs_path(cx);
+        Some((buffer, abs_path))
+    }
+
     fn open_remote_buffer_internal(
         &mut self,
         path: &Arc<Path>,
@@ -2403,6 +2592,11 @@ impl Project {
                 let file = File::from_dyn(buffer.read(cx).file())?;
                 let worktree_id = file.worktree_id(cx);
                 let abs_path = file.as_local()?.abs_path(cx);
+                let project_path = ProjectPath {
+                    worktree_id,
+                    path: file.path.clone(),
+                };
+                cx.emit(Event::BufferSaved(project_path));
                 let text_document = lsp::TextDocumentIdentifier {
                     uri: lsp::Url::from_file_path(abs_path).unwrap(),
                 };
@@ -2673,8 +2867,9 @@ impl Project {
                 })?;
 
                 for (adapter, server) in servers {
-                    let settings =
-                        cx.update(|cx| adapter.workspace_configuration(server.root_path(), cx))?;
+                    let settings = cx.update(|cx| {
+                        lsp_workspace_configuration(&adapter, server.root_path(), cx)
+                    })?;
 
                     server
                         .notify::<lsp::notification::DidChangeConfiguration>(
@@ -2689,6 +2884,32 @@ impl Project {
         })
     }
 
+    /// Sends the running servers with the given name their workspace configuration again,
+    /// for configurations that depend on more than the settings.
+    pub fn refresh_workspace_configuration(
+        &mut self,
+        server_name: &LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) {
+        for state in self.language_servers.values() {
+            let LanguageServerState::Running {
+                adapter, server, ..
+            } = state
+            else {
+                continue;
+            };
+            if &adapter.name != server_name {
+                continue;
+            }
+            let settings = lsp_workspace_configuration(adapter, server.root_path(), cx);
+            server
+                .notify::<lsp::notification::DidChangeConfiguration>(
+                    lsp::DidChangeConfigurationParams { settings },
+                )
+                .ok();
+        }
+    }
+
     fn detect_language_for_buffer(
         &mut self,
         buffer_handle: &Model<Buffer>,
@@ -2769,18 +2990,34 @@ impl Project {
         let worktree = worktree.read(cx);
         let worktree_id = worktree.id();
         let worktree_path = worktree.abs_path();
+        let is_visible = worktree.is_visible();
         let key = (worktree_id, adapter.name.clone());
         if self.language_server_ids.contains_key(&key) {
             return;
         }
 
+        // Servers that support workspace folders are shared by the project's worktrees.
+        if adapter.adapter.supports_workspace_folders() && is_visible {
+            if let Some(server_id) = self.shared_language_server_id(&adapter.name, cx) {
+                self.language_server_ids.insert(key, server_id);
+                if let Some(LanguageServerState::Running { server, .. }) =
+                    self.language_servers.get(&server_id)
+                {
+                    if let Ok(uri) = lsp::Url::from_file_path(&worktree_path) {
+                        server.add_workspace_folder(uri);
+                    }
+                }
+                return;
+            }
+        }
+
         let stderr_capture = Arc::new(Mutex::new(Some(String::new())));
         let pending_server = match self.languages.create_pending_language_server(
             stderr_capture.clone(),
             language.clone(),
             adapter.clone(),
             Arc::clone(&worktree_path),
-            ProjectLspAdapterDelegate::new(self, cx),
+            ProjectLspAdapterDelegate::new(self, Arc::clone(&worktree_path), cx),
             cx,
         ) {
             Some(pending_server) => pending_server,
@@ -2858,6 +3095,40 @@ impl Project {
         self.language_server_ids.insert(key, server_id);
     }
 
+    /// Returns the server with the given name that's started for one of the project's
+    /// visible worktrees, if any.
+    fn shared_language_server_id(
+        &self,
+        adapter_name: &LanguageServerName,
+        cx: &AppContext,
+    ) -> Option<LanguageServerId> {
+        self.language_server_ids
+            .iter()
+            .find_map(|((worktree_id, name), server_id)| {
+                if name != adapter_name {
+                    return None;
+                }
+                let worktree = self.worktree_for_id(*worktree_id, cx)?;
+                let worktree = worktree.read(cx);
+                (worktree.is_local() && worktree.is_visible()).then_some(*server_id)
+            })
+    }
+
+    /// Returns the roots of the worktrees that a language server works in.
+    fn language_server_worktree_roots(
+        &self,
+        server_id: LanguageServerId,
+        cx: &AppContext,
+    ) -> Vec<Arc<Path>> {
+        self.language_server_ids
+            .iter()
+            .filter(|(_, id)| **id == server_id)
+            .filter_map(|((worktree_id, _), _)| {
+                Some(self.worktree_for_id(*worktree_id, cx)?.read(cx).abs_path())
+            })
+            .collect()
+    }
+
     fn reinstall_language_server(
         &mut self,
         language: Arc<Language>,
@@ -2966,9 +3237,18 @@ impl Project {
         cx: &mut AsyncAppContext,
     ) -> Result<Arc<LanguageServer>> {
         let workspace_config =
-            cx.update(|cx| adapter.workspace_configuration(worktree_path, cx))?;
+            cx.update(|cx| lsp_workspace_configuration(&adapter, worktree_path, cx))?;
         let language_server = pending_server.task.await?;
 
+        let worktree_roots = this.update(cx, |this, cx| {
+            this.language_server_worktree_roots(server_id, cx)
+        })?;
+        for root in worktree_roots {
+            if let Ok(uri) = lsp::Url::from_file_path(&root) {
+                language_server.add_workspace_folder(uri);
+            }
+        }
+
         language_server
             .on_notification::<lsp::notification::PublishDiagnostics, _>({
                 let adapter = adapter.clone();
@@ -3000,13 +3280,18 @@ impl Project {
                     let adapter = adapter.clone();
                     let worktree_path = worktree_path.clone();
                     async move {
-                        let workspace_config =
-                            cx.update(|cx| adapter.workspace_configuration(&worktree_path, cx))?;
+                        let workspace_config = cx.update(|cx| {
+                            lsp_workspace_configuration(&adapter, &worktree_path, cx)
+                        })?;
                         Ok(params
                             .items
                             .into_iter()
                             .map(|item| {
-                                if let Some(section) = &item.section {
+                                // Some servers, such as ESLint, ask for an empty section
+                                // in order to receive their whole configuration.
+                                if let Some(section) =
+                                    item.section.as_ref().filter(|section| !section.is_empty())
+                                {
                                     workspace_config
                                         .get(section)
                                         .cloned()
@@ -3104,6 +3389,24 @@ impl Project {
             })
             .detach();
 
+        if let Some(method) = adapter.adapter.status_notification() {
+            language_server
+                .on_custom_notification(method, {
+                    let adapter = adapter.clone();
+                    let this = this.clone();
+                    move |params, mut cx| {
+                        if let Some(this) = this.upgrade() {
+                            let message = adapter.adapter.status_message(params);
+                            this.update(&mut cx, |this, cx| {
+                                this.on_lsp_status(server_id, method.to_string(), message, cx);
+                            })
+                            .ok();
+                        }
+                    }
+                })
+                .detach();
+        }
+
         let disk_based_diagnostics_progress_token =
             adapter.disk_based_diagnostics_progress_token.clone();
 
@@ -3123,6 +3426,17 @@ impl Project {
             })
             .detach();
         let mut initialization_options = adapter.adapter.initialization_options();
+        if let Some(root_options) = adapter
+            .root_specific_initialization_options(worktree_path)
+            .await
+        {
+            match &mut initialization_options {
+                Some(initialization_options) => {
+                    merge_json_value_into(root_options, initialization_options)
+                }
+                None => initialization_options = Some(root_options),
+            }
+        }
         match (&mut initialization_options, override_options) {
             (Some(initialization_options), Some(override_options)) => {
                 merge_json_value_into(override_options, initialization_options);
@@ -3190,6 +3504,24 @@ impl Project {
 
         cx.emit(Event::LanguageServerAdded(server_id));
 
+        if let Some(exited) = language_server.exited() {
+            cx.spawn(|this, mut cx| async move {
+                exited.await;
+                this.update(&mut cx, |this, cx| {
+                    this.handle_language_server_exit(server_id, cx)
+                })
+                .ok();
+            })
+            .detach();
+        }
+
+        // Worktrees can be added to a shared server while it's starting.
+        for root in self.language_server_worktree_roots(server_id, cx) {
+            if let Ok(uri) = lsp::Url::from_file_path(&root) {
+                language_server.add_workspace_folder(uri);
+            }
+        }
+
         if let Some(project_id) = self.remote_id() {
             self.client.send(proto::StartLanguageServer {
                 project_id,
@@ -3200,7 +3532,7 @@ impl Project {
             })?;
         }
 
-        // Tell the language server about every open buffer in the worktree that matches the language.
+        // Tell the language server about every open buffer in its worktrees that matches the language.
         for buffer in self.opened_buffers.values() {
             if let Some(buffer_handle) = buffer.upgrade() {
                 let buffer = buffer_handle.read(cx);
@@ -3213,7 +3545,8 @@ impl Project {
                     None => continue,
                 };
 
-                if file.worktree.read(cx).id() != key.0
+                let worktree_key = (file.worktree.read(cx).id(), key.1.clone());
+                if self.language_server_ids.get(&worktree_key) != Some(&server_id)
                     || !language.lsp_adapters().iter().any(|a| a.name == key.1)
                 {
                     continue;
@@ -3371,6 +3704,28 @@ impl Project {
         None
     }
 
+    /// Restarts the given language's servers in every worktree that they were started
+    /// for, such as to retry their downloads.
+    pub fn restart_language_servers_for_language(
+        &mut self,
+        language: Arc<Language>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let worktrees = self
+            .worktrees()
+            .filter(|worktree| {
+                let worktree_id = worktree.read(cx).id();
+                language.lsp_adapters().iter().any(|adapter| {
+                    self.language_server_ids
+                        .contains_key(&(worktree_id, adapter.name.clone()))
+                })
+            })
+            .collect::<Vec<_>>();
+        for worktree in worktrees {
+            self.restart_language_servers(worktree, language.clone(), cx);
+        }
+    }
+
     fn restart_language_servers(
         &mut self,
         worktree: Model<Worktree>,
@@ -3383,6 +3738,8 @@ impl Project {
             .lsp_adapters()
             .iter()
             .map(|adapter| {
+                // Servers that were given up on after crashing can be restarted by hand.
+                self.language_server_restarts.remove(&adapter.name);
                 let stop_task = self.stop_language_server(worktree_id, adapter.name.clone(), cx);
                 (stop_task, adapter.name.clone())
             })
@@ -3422,6 +3779,80 @@ impl Project {
         .detach();
     }
 
+    /// Restarts a language server that exited while it was running, with a delay that grows
+    /// with each consecutive restart, until it's been restarted too many times.
+    fn handle_language_server_exit(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        // Servers that are stopped on purpose are removed before they exit.
+        let Some(LanguageServerState::Running {
+            language,
+            adapter,
+            server,
+            ..
+        }) = self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let language = language.clone();
+        let server_name = adapter.name.clone();
+        let exit_status = server.exit_status();
+        let stderr_tail = server.stderr_tail();
+        let Some(worktree_id) = self
+            .language_server_ids
+            .iter()
+            .find_map(|((worktree_id, _), id)| (*id == server_id).then_some(*worktree_id))
+        else {
+            return;
+        };
+
+        let now = Instant::now();
+        let restart_delay = self
+            .language_server_restarts
+            .entry(server_name.clone())
+            .or_insert_with(|| LanguageServerRestarts::new(now))
+            .next_restart_delay(now);
+
+        let mut message = format!("Language server {} exited unexpectedly", server_name.0);
+        if let Some(exit_status) = exit_status {
+            message.push_str(&format!(" ({exit_status})"));
+        }
+        match restart_delay {
+            Some(delay) => {
+                message.push_str(&format!(". Restarting it in {}s.", delay.as_secs()));
+            }
+            None => message.push_str(&format!(
+                ". It won't be restarted again after {MAX_SERVER_RESTART_ATTEMPT_COUNT} attempts."
+            )),
+        }
+        log::error!("{message}\nserver stderr: {stderr_tail}");
+        cx.emit(Event::LanguageServerCrashed {
+            language_server_id: server_id,
+            message,
+            report: stderr_tail,
+        });
+
+        let stop = self.stop_language_server(worktree_id, server_name, cx);
+        cx.spawn(move |this, mut cx| async move {
+            let affected_worktrees = stop.await;
+            let Some(delay) = restart_delay else {
+                return;
+            };
+            cx.background_executor().timer(delay).await;
+            this.update(&mut cx, |this, cx| {
+                for worktree_id in affected_worktrees {
+                    if let Some(worktree) = this.worktree_for_id(worktree_id, cx) {
+                        this.start_language_servers(&worktree, language.clone(), cx);
+                    }
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     fn check_errored_server(
         language: Arc<Language>,
         adapter: Arc<CachedLspAdapter>,
@@ -3525,6 +3956,11 @@ impl Project {
 
         match progress {
             lsp::WorkDoneProgress::Begin(report) => {
+                // Servers often only describe their work in the title, such as
+                // "Initializing JS/TS language features".
+                let message = report
+                    .message
+                    .or_else(|| (!report.title.is_empty()).then_some(report.title));
                 if is_disk_based_diagnostics_progress {
                     language_server_status.has_pending_diagnostic_updates = true;
                     self.disk_based_diagnostics_started(language_server_id, cx);
@@ -3539,7 +3975,7 @@ impl Project {
                         language_server_id,
                         token.clone(),
                         LanguageServerProgress {
-                            message: report.message.clone(),
+                            message: message.clone(),
                             percentage: report.percentage.map(|p| p as usize),
                             last_update_at: Instant::now(),
                         },
@@ -3551,7 +3987,7 @@ impl Project {
                             message: proto::update_language_server::Variant::WorkStart(
                                 proto::LspWorkStart {
                                     token,
-                                    message: report.message,
+                                    message,
                                     percentage: report.percentage.map(|p| p as u32),
                                 },
                             ),
@@ -3615,6 +4051,58 @@ impl Project {
         }
     }
 
+    /// Shows the status that a language server reports through a custom notification
+    /// as work in progress, until the server has no status to show.
+    fn on_lsp_status(
+        &mut self,
+        language_server_id: LanguageServerId,
+        token: String,
+        message: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(language_server_status) = self.language_server_statuses.get(&language_server_id)
+        else {
+            return;
+        };
+        let is_showing = language_server_status.pending_work.contains_key(&token);
+
+        let message = match message {
+            Some(message) => {
+                let progress = LanguageServerProgress {
+                    message: Some(message.clone()),
+                    percentage: None,
+                    last_update_at: Instant::now(),
+                };
+                if is_showing {
+                    self.on_lsp_work_progress(language_server_id, token.clone(), progress, cx);
+                    proto::update_language_server::Variant::WorkProgress(proto::LspWorkProgress {
+                        token,
+                        message: Some(message),
+                        percentage: None,
+                    })
+                } else {
+                    self.on_lsp_work_start(language_server_id, token.clone(), progress, cx);
+                    proto::update_language_server::Variant::WorkStart(proto::LspWorkStart {
+                        token,
+                        message: Some(message),
+                        percentage: None,
+                    })
+                }
+            }
+            None if is_showing => {
+                self.on_lsp_work_end(language_server_id, token.clone(), cx);
+                proto::update_language_server::Variant::WorkEnd(proto::LspWorkEnd { token })
+            }
+            None => return,
+        };
+        self.buffer_ordered_messages_tx
+            .unbounded_send(BufferOrderedMessage::LanguageServerUpdate {
+                language_server_id,
+                message,
+            })
+            .ok();
+    }
+
     fn on_lsp_work_start(
         &mut self,
         language_server_id: LanguageServerId,
@@ -3818,11 +4306,14 @@ impl Project {
             let is_unnecessary = diagnostic.tags.as_ref().map_or(false, |tags| {
                 tags.iter().any(|tag| *tag == DiagnosticTag::UNNECESSARY)
             });
+            let is_deprecated = diagnostic.tags.as_ref().map_or(false, |tags| {
+                tags.iter().any(|tag| *tag == DiagnosticTag::DEPRECATED)
+            });
 
             if is_supporting {
                 supporting_diagnostics.insert(
                     (source, code.clone(), range),
-                    (diagnostic.severity, is_unnecessary),
+                    (diagnostic.severity, is_unnecessary, is_deprecated),
                 );
             } else {
                 let group_id = post_inc(&mut self.next_diagnostic_group_id);
@@ -3844,6 +4335,7 @@ impl Project {
                         is_primary: true,
                         is_disk_based,
                         is_unnecessary,
+                        is_deprecated,
                     },
                 });
                 if let Some(infos) = &diagnostic.related_information {
@@ -3861,6 +4353,7 @@ impl Project {
                                     is_primary: false,
                                     is_disk_based,
                                     is_unnecessary: false,
+                                    is_deprecated: false,
                                 },
                             });
                         }
@@ -3873,15 +4366,14 @@ impl Project {
             let diagnostic = &mut entry.diagnostic;
             if !diagnostic.is_primary {
                 let source = *sources_by_group_id.get(&diagnostic.group_id).unwrap();
-                if let Some(&(severity, is_unnecessary)) = supporting_diagnostics.get(&(
-                    source,
-                    diagnostic.code.clone(),
-                    entry.range.clone(),
-                )) {
+                if let Some(&(severity, is_unnecessary, is_deprecated)) = supporting_diagnostics
+                    .get(&(source, diagnostic.code.clone(), entry.range.clone()))
+                {
                     if let Some(severity) = severity {
                         diagnostic.severity = severity;
                     }
                     diagnostic.is_unnecessary = is_unnecessary;
+                    diagnostic.is_deprecated = is_deprecated;
                 }
             }
         }
@@ -4509,6 +5001,22 @@ impl Project {
         self.document_highlights_impl(buffer, position, cx)
     }
 
+    /// Returns the link reported by the language server at the given position, if any.
+    pub fn document_link<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<DocumentLink>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentLink { position },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut ModelContext<Self>) -> Task<Result<Vec<Symbol>>> {
         if self.is_local() {
             let mut requests = Vec::new();
@@ -4545,7 +5053,9 @@ impl Project {
                             let lsp_symbols = response.flatten().map(|symbol_response| match symbol_response {
                                 lsp::WorkspaceSymbolResponse::Flat(flat_responses) => {
                                     flat_responses.into_iter().map(|lsp_symbol| {
-                                        (lsp_symbol.name, lsp_symbol.kind, lsp_symbol.location)
+                                        #[allow(deprecated)]
+                                        let is_deprecated = symbol_is_deprecated(lsp_symbol.tags.as_deref(), lsp_symbol.deprecated);
+                                        (lsp_symbol.name, lsp_symbol.kind, lsp_symbol.location, is_deprecated)
                                     }).collect::<Vec<_>>()
                                 }
                                 lsp::WorkspaceSymbolResponse::Nested(nested_responses) => {
@@ -4557,7 +5067,8 @@ impl Project {
                                                 return None
                                             }
                                         };
-                                        Some((lsp_symbol.name, lsp_symbol.kind, location))
+                                        let is_deprecated = symbol_is_deprecated(lsp_symbol.tags.as_deref(), None);
+                                        Some((lsp_symbol.name, lsp_symbol.kind, location, is_deprecated))
                                     }).collect::<Vec<_>>()
                                 }
                             }).unwrap_or_default();
@@ -4591,7 +5102,7 @@ impl Project {
                     ) in responses
                     {
                         symbols.extend(lsp_symbols.into_iter().filter_map(
-                            |(symbol_name, symbol_kind, symbol_location)| {
+                            |(symbol_name, symbol_kind, symbol_location, is_deprecated)| {
                                 let abs_path = symbol_location.uri.to_file_path().ok()?;
                                 let mut worktree_id = source_worktree_id;
                                 let path;
@@ -4631,6 +5142,7 @@ impl Project {
                                         name: symbol_name,
                                         range: range_from_lsp(symbol_location.range),
                                         signature,
+                                        is_deprecated,
                                     }
                                 })
                             },
@@ -4752,6 +5264,46 @@ impl Project {
         self.hover_impl(buffer, position, cx)
     }
 
+    /// Returns the signatures of the call surrounding the given position, if any.
+    pub fn signature_help<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SignatureHelp>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSignatureHelp { position },
+            cx,
+        )
+    }
+
+    /// Returns the characters that should trigger a signature help request when typed in the
+    /// given buffer.
+    pub fn signature_help_trigger_characters(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Vec<String> {
+        if !self.is_local() {
+            // The host's language servers aren't available to guests, so fall back to the
+            // characters that start or continue an argument list in most languages.
+            return vec!["(".to_string(), ",".to_string()];
+        }
+        self.primary_language_server_for_buffer(buffer.read(cx), cx)
+            .and_then(|(_, server)| {
+                server
+                    .capabilities()
+                    .signature_help_provider
+                    .as_ref()?
+                    .trigger_characters
+                    .clone()
+            })
+            .unwrap_or_default()
+    }
+
     #[inline(never)]
     fn completions_impl(
         &self,
@@ -4840,8 +5392,10 @@ impl Project {
                         continue;
                     }
 
+                    let Some(server_id) = completion.server_id else {
+                        continue;
+                    };
                     did_resolve = true;
-                    let server_id = completion.server_id;
                     let completion = completion.lsp_completion.clone();
                     drop(completions_guard);
 
@@ -4864,7 +5418,9 @@ impl Project {
                         continue;
                     }
 
-                    let server_id = completion.server_id;
+                    let Some(server_id) = completion.server_id else {
+                        continue;
+                    };
                     let completion = completion.lsp_completion.clone();
                     drop(completions_guard);
 
@@ -4990,7 +5546,9 @@ impl Project {
         let buffer_id = buffer.remote_id();
 
         if self.is_local() {
-            let server_id = completion.server_id;
+            let Some(server_id) = completion.server_id else {
+                return Task::ready(Ok(Default::default()));
+            };
             let lang_server = match self.language_server_for_buffer(buffer, server_id, cx) {
                 Some((_, server)) => server.clone(),
                 _ => return Task::ready(Ok(Default::default())),
@@ -5003,14 +5561,21 @@ impl Project {
                     .as_ref()
                     .and_then(|options| options.resolve_provider)
                     .unwrap_or(false);
-                let additional_text_edits = if can_resolve {
-                    lang_server
-                        .request::<lsp::request::ResolveCompletionItem>(completion.lsp_completion)
-                        .await?
-                        .additional_text_edits
-                } else {
-                    completion.lsp_completion.additional_text_edits
-                };
+                // Servers may compute the additional edits (such as auto-imports) lazily, so
+                // resolve the item first and fall back to the edits it was sent with.
+                let mut additional_text_edits = None;
+                if can_resolve {
+                    additional_text_edits = lang_server
+                        .request::<lsp::request::ResolveCompletionItem>(
+                            completion.lsp_completion.clone(),
+                        )
+                        .await
+                        .context("resolving completion")
+                        .log_err()
+                        .and_then(|resolved| resolved.additional_text_edits);
+                }
+                let additional_text_edits = additional_text_edits
+                    .or_else(|| completion.lsp_completion.additional_text_edits.clone());
                 if let Some(edits) = additional_text_edits {
                     let edits = this
                         .update(&mut cx, |this, cx| {
@@ -5145,7 +5710,17 @@ impl Project {
                     action.lsp_action = lang_server
                         .request::<lsp::request::CodeActionResolveRequest>(action.lsp_action)
                         .await?;
+                } else if action.lsp_action.edit.is_none()
+                    && Self::can_resolve_code_actions(&lang_server)
+                {
+                    // The server defers computing the action's edits until it is chosen.
+                    let title = action.lsp_action.title.clone();
+                    action.lsp_action = lang_server
+                        .request::<lsp::request::CodeActionResolveRequest>(action.lsp_action)
+                        .await
+                        .with_context(|| format!("failed to resolve code action {title:?}"))?;
                 } else {
+                    let destination = lsp_adapter.code_action_destination(&action.lsp_action);
                     let actions = this
                         .update(&mut cx, |this, cx| {
                             this.code_actions(&buffer_handle, action.range, cx)
@@ -5156,6 +5731,10 @@ impl Project {
                         .find(|a| a.lsp_action.title == action.lsp_action.title)
                         .ok_or_else(|| anyhow!("code action is outdated"))?
                         .lsp_action;
+                    if let Some(destination) = destination {
+                        lsp_adapter
+                            .set_code_action_destination(&mut action.lsp_action, &destination);
+                    }
                 }
 
                 if let Some(edit) = action.lsp_action.edit {
@@ -5198,7 +5777,10 @@ impl Project {
                     })?);
                 }
 
-                Ok(ProjectTransaction::default())
+                Err(anyhow!(
+                    "code action {:?} has neither edits nor a command",
+                    action.lsp_action.title
+                ))
             })
         } else if let Some(project_id) = self.remote_id() {
             let client = self.client.clone();
@@ -5223,6 +5805,305 @@ impl Project {
         }
     }
 
+    /// Returns whether the user has to choose a destination file before the given code
+    /// action is applied, which is then passed to [`Self::set_code_action_destination`].
+    pub fn code_action_requires_destination(
+        &self,
+        buffer: &Model<Buffer>,
+        action: &CodeAction,
+        cx: &AppContext,
+    ) -> bool {
+        self.language_server_for_buffer(buffer.read(cx), action.server_id, cx)
+            .map_or(false, |(adapter, _)| {
+                adapter.code_action_requires_destination(&action.lsp_action)
+            })
+    }
+
+    pub fn set_code_action_destination(
+        &self,
+        buffer: &Model<Buffer>,
+        action: &mut CodeAction,
+        destination: &Path,
+        cx: &AppContext,
+    ) {
+        if let Some((adapter, _)) =
+            self.language_server_for_buffer(buffer.read(cx), action.server_id, cx)
+        {
+            adapter.set_code_action_destination(&mut action.lsp_action, destination);
+        }
+    }
+
+    fn can_resolve_code_actions(server: &LanguageServer) -> bool {
+        matches!(
+            server.capabilities().code_action_provider,
+            Some(lsp::CodeActionProviderCapability::Options(
+                lsp::CodeActionOptions {
+                    resolve_provider: Some(true),
+                    ..
+                }
+            ))
+        )
+    }
+
+    pub fn code_lens(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
+    pub fn document_colors(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentColor>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentColors,
+            cx,
+        )
+    }
+
+    /// Returns the symbols of the buffer, with nested symbols flattened.
+    pub fn document_symbols(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentSymbol>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentSymbols,
+            cx,
+        )
+    }
+
+    pub fn folding_ranges(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<FoldingRange>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetFoldingRanges,
+            cx,
+        )
+    }
+
+    /// Returns the ranges that are edited along with the one at the given position, such
+    /// as the names of an HTML element's opening and closing tags.
+    pub fn linked_editing_ranges<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Range<language::Anchor>>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetLinkedEditingRanges { position },
+            cx,
+        )
+    }
+
+    /// Asks the language server that produced the given code lens to execute its command,
+    /// returning any edits that the server applied while doing so.
+    pub fn execute_code_lens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(command) = lens.lsp_lens.command else {
+            return Task::ready(Ok(Default::default()));
+        };
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "code lens commands can only be executed by the project host"
+            )));
+        }
+        let buffer = buffer_handle.read(cx);
+        let Some((_, lang_server)) = self.language_server_for_buffer(buffer, lens.server_id, cx)
+        else {
+            return Task::ready(Ok(Default::default()));
+        };
+        let lang_server = lang_server.clone();
+        let supports_command = lang_server
+            .capabilities()
+            .execute_command_provider
+            .as_ref()
+            .map_or(false, |provider| {
+                provider.commands.contains(&command.command)
+            });
+        if !supports_command {
+            return Task::ready(Err(anyhow!(
+                "language server does not support the command {:?}",
+                command.command
+            )));
+        }
+
+        cx.spawn(move |this, mut cx| async move {
+            this.update(&mut cx, |this, _| {
+                this.last_workspace_edits_by_language_server
+                    .remove(&lang_server.server_id());
+            })?;
+
+            lang_server
+                .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                    command: command.command,
+                    arguments: command.arguments.unwrap_or_default(),
+                    ..Default::default()
+                })
+                .await?;
+
+            Ok(this.update(&mut cx, |this, _| {
+                this.last_workspace_edits_by_language_server
+                    .remove(&lang_server.server_id())
+                    .unwrap_or_default()
+            })?)
+        })
+    }
+
+    /// Asks the language servers of the open buffers to check every file of their projects
+    /// again, for the servers whose adapters know how to.
+    ///
+    /// Some servers, like tsserver, publish the refreshed diagnostics without ever answering
+    /// the command, so a command that's still pending after [`REFRESH_DIAGNOSTICS_TIMEOUT`]
+    /// counts as sent.
+    pub fn refresh_diagnostics(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "diagnostics can only be refreshed by the project host"
+            )));
+        }
+
+        let mut open_paths_by_server = HashMap::<
+            LanguageServerId,
+            (Arc<CachedLspAdapter>, Arc<LanguageServer>, Vec<PathBuf>),
+        >::default();
+        for (buffer_id, server_snapshots) in &self.buffer_snapshots {
+            let Some(buffer) = self
+                .opened_buffers
+                .get(buffer_id)
+                .and_then(OpenBuffer::upgrade)
+            else {
+                continue;
+            };
+            let Some(path) = File::from_dyn(buffer.read(cx).file()).map(|file| file.abs_path(cx))
+            else {
+                continue;
+            };
+            for server_id in server_snapshots.keys() {
+                let Some(LanguageServerState::Running {
+                    adapter, server, ..
+                }) = self.language_servers.get(server_id)
+                else {
+                    continue;
+                };
+                open_paths_by_server
+                    .entry(*server_id)
+                    .or_insert_with(|| (adapter.clone(), server.clone(), Vec::new()))
+                    .2
+                    .push(path.clone());
+            }
+        }
+
+        cx.spawn(|_, cx| async move {
+            let mut requests = Vec::new();
+            for (adapter, server, mut open_paths) in open_paths_by_server.into_values() {
+                open_paths.sort();
+                open_paths.dedup();
+                let supported_commands = server
+                    .capabilities()
+                    .execute_command_provider
+                    .as_ref()
+                    .map(|provider| provider.commands.clone())
+                    .unwrap_or_default();
+                for command in adapter
+                    .adapter
+                    .refresh_diagnostics_commands(open_paths)
+                    .await
+                {
+                    if !supported_commands.contains(&command.command) {
+                        log::warn!(
+                            "{} doesn't support the {} command, not refreshing its diagnostics",
+                            server.name(),
+                            command.command
+                        );
+                        continue;
+                    }
+                    let request =
+                        server.request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                            command: command.command,
+                            arguments: command.arguments.unwrap_or_default(),
+                            ..Default::default()
+                        });
+                    let timeout = cx.background_executor().timer(REFRESH_DIAGNOSTICS_TIMEOUT);
+                    requests.push(async move {
+                        futures::select! {
+                            response = request.fuse() => response.map(drop),
+                            _ = timeout.fuse() => Ok(()),
+                        }
+                    });
+                }
+            }
+            try_join_all(requests).await?;
+            Ok(())
+        })
+    }
+
+    /// Opens the buffers for locations reported by one of the given buffer's language servers
+    /// outside of a regular request, e.g. in the arguments of a code lens command.
+    pub fn open_lsp_locations(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        server_id: LanguageServerId,
+        locations: Vec<lsp::Location>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Location>>> {
+        let buffer = buffer_handle.read(cx);
+        let Some((adapter, _)) = self.language_server_for_buffer(buffer, server_id, cx) else {
+            return Task::ready(Err(anyhow!("no language server found for buffer")));
+        };
+        let adapter_name = adapter.name.clone();
+        let buffers = locations
+            .iter()
+            .map(|location| {
+                self.open_local_buffer_via_lsp(
+                    location.uri.clone(),
+                    server_id,
+                    adapter_name.clone(),
+                    cx,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |_, mut cx| async move {
+            let buffers = try_join_all(buffers).await?;
+            let mut result = Vec::new();
+            for (buffer, location) in buffers.into_iter().zip(locations) {
+                let range = buffer.update(&mut cx, |buffer, _| {
+                    let start =
+                        buffer.clip_point_utf16(point_from_lsp(location.range.start), Bias::Left);
+                    let end =
+                        buffer.clip_point_utf16(point_from_lsp(location.range.end), Bias::Left);
+                    buffer.anchor_after(start)..buffer.anchor_before(end)
+                })?;
+                result.push(Location { buffer, range });
+            }
+            Ok(result)
+        })
+    }
+
     fn apply_on_type_formatting(
         &self,
         buffer: Model<Buffer>,
@@ -5368,7 +6249,9 @@ impl Project {
                     if let Some(parent_path) = abs_path.parent() {
                         fs.create_dir(parent_path).await?;
                     }
-                    if abs_path.ends_with("/") {
+                    // `Path::ends_with` compares components, so check the URI for a
+                    // trailing slash, which denotes a directory.
+                    if op.uri.path().ends_with('/') {
                         fs.create_dir(&abs_path).await?;
                     } else {
                         fs.create_file(
@@ -5411,6 +6294,7 @@ impl Project {
                         .uri
                         .to_file_path()
                         .map_err(|_| anyhow!("can't convert URI to path"))?;
+                    let is_dir = op.uri.path().ends_with('/');
                     let options = op
                         .options
                         .map(|options| fs::RemoveOptions {
@@ -5418,7 +6302,7 @@ impl Project {
                             ignore_if_not_exists: options.ignore_if_not_exists.unwrap_or(false),
                         })
                         .unwrap_or_default();
-                    if abs_path.ends_with("/") {
+                    if is_dir {
                         fs.remove_dir(&abs_path, options).await?;
                     } else {
                         fs.remove_file(&abs_path, options).await?;
@@ -5471,8 +6355,24 @@ impl Project {
 
                         transaction
                     })?;
-                    if let Some(transaction) = transaction {
-                        project_transaction.0.insert(buffer_to_edit, transaction);
+                    let Some(transaction) = transaction else {
+                        continue;
+                    };
+                    // Group all of the edits made to a buffer, so that they are undone at once.
+                    match project_transaction.0.entry(buffer_to_edit.clone()) {
+                        hash_map::Entry::Occupied(mut entry) => {
+                            let existing_transaction = entry.get_mut();
+                            if push_to_history {
+                                buffer_to_edit.update(cx, |buffer, _| {
+                                    buffer
+                                        .merge_transactions(transaction.id, existing_transaction.id)
+                                })?;
+                            }
+                            existing_transaction.edit_ids.extend(transaction.edit_ids);
+                        }
+                        hash_map::Entry::Vacant(entry) => {
+                            entry.insert(transaction);
+                        }
                     }
                 }
             }
@@ -6412,10 +7312,24 @@ impl Project {
                 servers_to_preserve.insert(server_id);
             }
         }
-        servers_to_remove.retain(|server_id, _| !servers_to_preserve.contains(server_id));
+        let removed_worktree_root = self
+            .worktree_for_id(id_to_remove, cx)
+            .map(|worktree| worktree.read(cx).abs_path());
         for (server_id_to_remove, server_name) in servers_to_remove {
             self.language_server_ids
                 .remove(&(id_to_remove, server_name));
+            if servers_to_preserve.contains(&server_id_to_remove) {
+                // The server keeps working in the other worktrees that it's shared by.
+                if let (Some(LanguageServerState::Running { server, .. }), Some(root)) = (
+                    self.language_servers.get(&server_id_to_remove),
+                    removed_worktree_root.as_ref(),
+                ) {
+                    if let Ok(uri) = lsp::Url::from_file_path(root) {
+                        server.remove_workspace_folder(uri);
+                    }
+                }
+                continue;
+            }
             self.language_server_statuses.remove(&server_id_to_remove);
             self.last_workspace_edits_by_language_server
                 .remove(&server_id_to_remove);
@@ -8530,6 +9444,7 @@ impl Project {
                     .signature
                     .try_into()
                     .map_err(|_| anyhow!("invalid signature"))?,
+                is_deprecated: serialized_symbol.is_deprecated,
             })
         }
     }
@@ -8980,10 +9895,15 @@ impl<P: AsRef<Path>> From<(WorktreeId, P)> for ProjectPath {
 }
 
 impl ProjectLspAdapterDelegate {
-    fn new(project: &Project, cx: &ModelContext<Project>) -> Arc<Self> {
+    fn new(
+        project: &Project,
+        worktree_root_path: Arc<Path>,
+        cx: &ModelContext<Project>,
+    ) -> Arc<Self> {
         Arc::new(Self {
             project: cx.handle(),
             http_client: project.client.http_client(),
+            worktree_root_path,
         })
     }
 }
@@ -8997,6 +9917,10 @@ impl LspAdapterDelegate for ProjectLspAdapterDelegate {
     fn http_client(&self) -> Arc<dyn HttpClient> {
         self.http_client.clone()
     }
+
+    fn worktree_root_path(&self) -> Option<&Path> {
+        Some(&self.worktree_root_path)
+    }
 }
 
 fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
@@ -9016,9 +9940,18 @@ fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
             column: symbol.range.end.0.column,
         }),
         signature: symbol.signature.to_vec(),
+        is_deprecated: symbol.is_deprecated,
     }
 }
 
+pub(crate) fn symbol_is_deprecated(
+    tags: Option<&[lsp::SymbolTag]>,
+    deprecated: Option<bool>,
+) -> bool {
+    deprecated == Some(true)
+        || tags.map_or(false, |tags| tags.contains(&lsp::SymbolTag::DEPRECATED))
+}
+
 fn relativize_path(base: &Path, path: &Path) -> PathBuf {
     let mut path_components = path.components();
     let mut base_components = base.components();
@@ -9104,3 +10037,20 @@ fn include_text(server: &lsp::LanguageServer) -> bool {
         })
         .unwrap_or(false)
 }
+
+/// The adapter's workspace configuration, with the user's `lsp.<server>.settings` merged into it.
+fn lsp_workspace_configuration(
+    adapter: &CachedLspAdapter,
+    workspace_root: &Path,
+    cx: &mut AppContext,
+) -> serde_json::Value {
+    let mut configuration = adapter.workspace_configuration(workspace_root, cx);
+    let user_settings = ProjectSettings::get_global(cx)
+        .lsp
+        .get(&adapter.name.0)
+        .and_then(|settings| settings.settings.clone());
+    if let Some(user_settings) = user_settings {
+        merge_json_value_into(user_settings, &mut configuration);
+    }
+    configuration
+}
@@ -11,6 +11,7 @@ pub struct ProjectSettings {
     ///
     /// The following settings can be overridden for specific language servers:
     /// - initialization_options
+    /// - settings
     /// To override settings for a language, add an entry for that language server's
     /// name to the lsp value.
     /// Default: null
@@ -63,7 +64,12 @@ pub enum GitGutterSetting {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct LspSettings {
+    /// Options merged into the ones Zed sends when initializing the server.
+    /// Changing them restarts the server.
     pub initialization_options: Option<serde_json::Value>,
+    /// Settings merged into the server's workspace configuration, which is sent on
+    /// `workspace/didChangeConfiguration` and in response to `workspace/configuration`.
+    pub settings: Option<serde_json::Value>,
 }
 
 impl Settings for ProjectSettings {
@@ -11,7 +11,11 @@ use lsp::Url;
 use parking_lot::Mutex;
 use pretty_assertions::assert_eq;
 use serde_json::json;
-use std::{os, task::Poll};
+use std::{
+    os,
+    sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    task::Poll,
+};
 use unindent::Unindent as _;
 use util::{assert_set_eq, paths::PathMatcher, test::temp_tree};
 
@@ -85,6 +89,53 @@ async fn test_symlinks(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_opening_file_through_symlink(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let dir = temp_tree(json!({
+        "root": {
+            "fennel": {
+                "grape": "grape",
+            },
+            "apple": "apple",
+        }
+    }));
+    os::unix::fs::symlink(
+        &dir.path().join("root/fennel"),
+        &dir.path().join("root/finnochio"),
+    )
+    .unwrap();
+
+    let project = Project::test(Arc::new(RealFs), [dir.path().join("root").as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "fennel/grape"), cx)
+        })
+        .await
+        .unwrap();
+    let symlinked_buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "finnochio/grape"), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(buffer.entity_id(), symlinked_buffer.entity_id());
+
+    let other_buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "apple"), cx)
+        })
+        .await
+        .unwrap();
+    assert_ne!(buffer.entity_id(), other_buffer.entity_id());
+}
+
 #[gpui::test]
 async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -971,6 +1022,112 @@ async fn test_omitted_diagnostics(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_language_server_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-language-server",
+            ..Default::default()
+        }))
+        .await;
+
+    let set_server_settings = |settings: serde_json::Value, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<ProjectSettings>(cx, |project_settings| {
+                    project_settings.lsp.insert(
+                        "the-language-server".into(),
+                        LspSettings {
+                            initialization_options: None,
+                            settings: Some(settings),
+                        },
+                    );
+                });
+            })
+        });
+    };
+    set_server_settings(json!({ "check": { "command": "clippy" } }), cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn a() {}" })).await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidChangeConfiguration>()
+            .await
+            .settings,
+        json!({ "check": { "command": "clippy" } })
+    );
+    let configuration = fake_server
+        .server
+        .request::<lsp::request::WorkspaceConfiguration>(lsp::ConfigurationParams {
+            items: vec![
+                lsp::ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("check".into()),
+                },
+                lsp::ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("".into()),
+                },
+            ],
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        configuration,
+        vec![
+            json!({ "command": "clippy" }),
+            json!({ "check": { "command": "clippy" } })
+        ]
+    );
+
+    // Changing the settings notifies the server instead of restarting it.
+    set_server_settings(json!({ "check": { "command": "check" } }), cx);
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidChangeConfiguration>()
+            .await
+            .settings,
+        json!({ "check": { "command": "check" } })
+    );
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+
+    // A configuration can be sent again without the settings changing.
+    project.update(cx, |project, cx| {
+        project
+            .refresh_workspace_configuration(&LanguageServerName("the-language-server".into()), cx)
+    });
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidChangeConfiguration>()
+            .await
+            .settings,
+        json!({ "check": { "command": "check" } })
+    );
+}
+
 #[gpui::test]
 async fn test_disk_based_diagnostics_progress(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1107,6 +1264,153 @@ async fn test_disk_based_diagnostics_progress(cx: &mut gpui::TestAppContext) {
     assert_eq!(futures::poll!(events.next()), Poll::Pending);
 }
 
+#[gpui::test]
+async fn test_refreshing_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                execute_command_provider: Some(lsp::ExecuteCommandOptions {
+                    commands: vec!["the/refresh".into()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            refresh_diagnostics_command: Some("the/refresh"),
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() { A }",
+            "b.rs": "const y: i32 = 1",
+            "c.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let _buffers = future::try_join_all(
+        ["/dir/b.rs", "/dir/a.rs"]
+            .map(|path| project.update(cx, |project, cx| project.open_local_buffer(path, cx))),
+    )
+    .await
+    .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    // The adapter is asked for the commands of every open file at once.
+    let refreshed_paths = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::ExecuteCommand, _, _>({
+        let refreshed_paths = refreshed_paths.clone();
+        move |params, _| {
+            assert_eq!(params.command, "the/refresh");
+            refreshed_paths.lock().extend(params.arguments);
+            async move { Ok(None) }
+        }
+    });
+    project
+        .update(cx, |project, cx| project.refresh_diagnostics(cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        refreshed_paths.lock().drain(..).collect::<Vec<_>>(),
+        [json!("/dir/a.rs"), json!("/dir/b.rs")]
+    );
+
+    // Commands that the server never answers count as sent after a while.
+    fake_server.handle_request::<lsp::request::ExecuteCommand, _, _>(|_, _| future::pending());
+    let refresh = project.update(cx, |project, cx| project.refresh_diagnostics(cx));
+    cx.executor().run_until_parked();
+    cx.executor().advance_clock(REFRESH_DIAGNOSTICS_TIMEOUT);
+    refresh.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_progress_without_message(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter::default()))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    for (token, title, message) in [
+        ("titled", "Initializing JS/TS language features", None),
+        ("described", "Indexing", Some("3/10 files")),
+    ] {
+        fake_server
+            .request::<lsp::request::WorkDoneProgressCreate>(lsp::WorkDoneProgressCreateParams {
+                token: lsp::NumberOrString::String(token.into()),
+            })
+            .await
+            .unwrap();
+        fake_server.notify::<lsp::notification::Progress>(lsp::ProgressParams {
+            token: lsp::NumberOrString::String(token.into()),
+            value: lsp::ProgressParamsValue::WorkDone(lsp::WorkDoneProgress::Begin(
+                lsp::WorkDoneProgressBegin {
+                    title: title.into(),
+                    message: message.map(Into::into),
+                    ..Default::default()
+                },
+            )),
+        });
+    }
+    cx.executor().run_until_parked();
+
+    // The title stands in for the message of progress that has none.
+    project.update(cx, |project, _| {
+        let status = project.language_server_statuses().next().unwrap();
+        assert_eq!(
+            status
+                .pending_work
+                .iter()
+                .map(|(token, progress)| (token.as_str(), progress.message.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                ("described", Some("3/10 files")),
+                ("titled", Some("Initializing JS/TS language features")),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_restarting_server_with_diagnostics_running(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1192,8 +1496,43 @@ async fn test_restarting_server_with_diagnostics_running(cx: &mut gpui::TestAppC
     });
 }
 
+#[test]
+fn test_language_server_restart_delay() {
+    let start = Instant::now();
+    let mut restarts = LanguageServerRestarts::new(start);
+
+    // The delay doubles with each restart in a row, until the server is given up on.
+    let delays = (0..6)
+        .map(|_| restarts.next_restart_delay(start))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        delays,
+        [
+            Some(Duration::from_secs(1)),
+            Some(Duration::from_secs(2)),
+            Some(Duration::from_secs(4)),
+            Some(Duration::from_secs(8)),
+            Some(Duration::from_secs(16)),
+            None,
+        ]
+    );
+    let gave_up_at = start + Duration::from_secs(60);
+    assert_eq!(restarts.next_restart_delay(gave_up_at), None);
+
+    // Once the server has run for long enough, the delay starts over.
+    let later = gave_up_at + SERVER_RESTART_RESET_INTERVAL + Duration::from_secs(1);
+    assert_eq!(
+        restarts.next_restart_delay(later),
+        Some(SERVER_RESTART_DELAY)
+    );
+    assert_eq!(
+        restarts.next_restart_delay(later),
+        Some(SERVER_RESTART_DELAY * 2)
+    );
+}
+
 #[gpui::test]
-async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAppContext) {
+async fn test_restarting_crashed_server(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let mut language = Language::new(
@@ -1207,51 +1546,131 @@ async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAp
         None,
     );
     let mut fake_servers = language
-        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
-            ..Default::default()
-        }))
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter::default()))
         .await;
 
     let fs = FakeFs::new(cx.executor());
-    fs.insert_tree("/dir", json!({ "a.rs": "x" })).await;
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
 
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
     project.update(cx, |project, _| project.languages.add(Arc::new(language)));
-
-    let buffer = project
+    let _buffer = project
         .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
 
-    // Publish diagnostics
-    let fake_server = fake_servers.next().await.unwrap();
-    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
-        uri: Url::from_file_path("/dir/a.rs").unwrap(),
-        version: None,
-        diagnostics: vec![lsp::Diagnostic {
-            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
-            severity: Some(lsp::DiagnosticSeverity::ERROR),
-            message: "the message".to_string(),
-            ..Default::default()
-        }],
-    });
+    let mut fake_server = fake_servers.next().await.unwrap();
+    let mut events = cx.events(&project);
+    let mut crash_messages = move || {
+        let mut messages = Vec::new();
+        while let Ok(Some(event)) = events.try_next() {
+            if let Event::LanguageServerCrashed { message, .. } = event {
+                messages.push(message);
+            }
+        }
+        messages
+    };
 
-    cx.executor().run_until_parked();
-    buffer.update(cx, |buffer, _| {
+    // The server is started again once the delay has passed, which grows with each crash.
+    for attempt in 0..MAX_SERVER_RESTART_ATTEMPT_COUNT {
+        let delay = SERVER_RESTART_DELAY * 2u32.pow(attempt);
+        fake_server.simulate_exit();
+        cx.executor().run_until_parked();
         assert_eq!(
-            buffer
-                .snapshot()
-                .diagnostics_in_range::<_, usize>(0..1, false)
-                .map(|entry| entry.diagnostic.message.clone())
-                .collect::<Vec<_>>(),
-            ["the message".to_string()]
+            crash_messages(),
+            [format!(
+                "Language server the-fake-language-server exited unexpectedly. Restarting it in {}s.",
+                delay.as_secs()
+            )]
         );
-    });
-    project.update(cx, |project, cx| {
-        assert_eq!(
-            project.diagnostic_summary(false, cx),
-            DiagnosticSummary {
-                error_count: 1,
+
+        cx.executor()
+            .advance_clock(delay - Duration::from_millis(1));
+        cx.executor().run_until_parked();
+        assert!(fake_servers.try_next().is_err());
+
+        cx.executor().advance_clock(Duration::from_millis(1));
+        cx.executor().run_until_parked();
+        fake_server = fake_servers.next().await.unwrap();
+    }
+
+    // After too many crashes in a row, the server is given up on.
+    fake_server.simulate_exit();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        crash_messages(),
+        [format!(
+            "Language server the-fake-language-server exited unexpectedly. It won't be restarted again after {MAX_SERVER_RESTART_ATTEMPT_COUNT} attempts."
+        )]
+    );
+    cx.executor().advance_clock(Duration::from_secs(60));
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+    project.update(cx, |project, _| {
+        assert_eq!(project.language_servers.len(), 0);
+    });
+}
+
+#[gpui::test]
+async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "x" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    // Publish diagnostics
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path("/dir/a.rs").unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: "the message".to_string(),
+            ..Default::default()
+        }],
+    });
+
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..1, false)
+                .map(|entry| entry.diagnostic.message.clone())
+                .collect::<Vec<_>>(),
+            ["the message".to_string()]
+        );
+    });
+    project.update(cx, |project, cx| {
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
                 warning_count: 0,
             }
         );
@@ -1471,6 +1890,139 @@ async fn test_toggling_enable_language_server(cx: &mut gpui::TestAppContext) {
         .await;
 }
 
+#[gpui::test]
+async fn test_sharing_language_server_between_worktrees(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: Some(lsp::WorkspaceFoldersServerCapabilities {
+                        supported: Some(true),
+                        change_notifications: Some(lsp::OneOf::Left(true)),
+                    }),
+                    file_operations: None,
+                }),
+                ..Default::default()
+            },
+            supports_workspace_folders: true,
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a": { "one.rs": "" },
+            "b": { "two.rs": "" },
+            "c": { "three.rs": "" },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir/a".as_ref(), "/dir/b".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer_one = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    let _buffer_two = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/b/two.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    // A single server is started, which works in both worktrees.
+    let mut fake_server = fake_servers.next().await.unwrap();
+    let mut opened_uris = Vec::new();
+    for _ in 0..2 {
+        let params = fake_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await;
+        opened_uris.push(params.text_document.uri.to_string());
+    }
+    opened_uris.sort();
+    assert_eq!(
+        opened_uris,
+        ["file:///dir/a/one.rs", "file:///dir/b/two.rs"]
+    );
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+    project.update(cx, |project, cx| {
+        let (_, server) = project
+            .language_servers_for_buffer(buffer_one.read(cx), cx)
+            .next()
+            .unwrap();
+        assert_eq!(
+            server
+                .workspace_folders()
+                .iter()
+                .map(|uri| uri.as_str())
+                .collect::<Vec<_>>(),
+            ["file:///dir/a", "file:///dir/b"]
+        );
+    });
+
+    // Removing a worktree removes its folder from the server, which keeps running.
+    let worktree_b_id = project.update(cx, |project, cx| {
+        project.worktrees().collect::<Vec<_>>()[1].read(cx).id()
+    });
+    project.update(cx, |project, cx| project.remove_worktree(worktree_b_id, cx));
+    let params = fake_server
+        .receive_notification::<lsp::notification::DidChangeWorkspaceFolders>()
+        .await;
+    assert!(params.event.added.is_empty());
+    assert_eq!(params.event.removed[0].uri.as_str(), "file:///dir/b");
+    assert_eq!(params.event.removed[0].name, "b");
+
+    // Adding a worktree adds its folder to the same server.
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_local_worktree("/dir/c", true, cx)
+        })
+        .await
+        .unwrap();
+    let _buffer_three = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/c/three.rs", cx)
+        })
+        .await
+        .unwrap();
+    let params = fake_server
+        .receive_notification::<lsp::notification::DidChangeWorkspaceFolders>()
+        .await;
+    assert_eq!(params.event.added[0].uri.as_str(), "file:///dir/c");
+    assert!(params.event.removed.is_empty());
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/c/three.rs"
+    );
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+}
+
 #[gpui::test(iterations = 3)]
 async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2379,6 +2931,301 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_signature_help_indices(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                signature_help_provider: Some(Default::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "f(1, 2, 3)" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let signature = |parameters: &[&str], active_parameter| lsp::SignatureInformation {
+        label: "fn f(a: u8, c: u8)".into(),
+        documentation: None,
+        parameters: Some(
+            parameters
+                .iter()
+                .map(|name| lsp::ParameterInformation {
+                    label: lsp::ParameterLabel::Simple(name.to_string()),
+                    documentation: None,
+                })
+                .collect(),
+        ),
+        active_parameter,
+    };
+    let signatures = vec![
+        // The `b` parameter isn't part of the label, so it isn't highlighted, and
+        // the active parameter still refers to `c`.
+        signature(&["a", "b", "c"], Some(2)),
+        signature(&["a", "b", "c"], Some(1)),
+        signature(&["a", "c"], Some(5)),
+    ];
+    fake_server.handle_request::<lsp::request::SignatureHelpRequest, _, _>(move |_, _| {
+        let signatures = signatures.clone();
+        async move {
+            Ok(Some(lsp::SignatureHelp {
+                signatures,
+                active_signature: Some(7),
+                active_parameter: None,
+            }))
+        }
+    });
+
+    let help = project
+        .update(cx, |project, cx| project.signature_help(&buffer, 8, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(help.active_signature, 0);
+    assert_eq!(
+        help.signatures
+            .iter()
+            .map(|signature| (signature.parameters.clone(), signature.active_parameter))
+            .collect::<Vec<_>>(),
+        [
+            (vec![5..6, 12..13], Some(1)),
+            (vec![5..6, 12..13], None),
+            (vec![5..6, 12..13], None),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_resolving_code_lenses_concurrently(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({ "a.rs": "fn a() {}\nfn b() {}\nfn c() {}\n" }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let lens = |row, command: Option<&str>| lsp::CodeLens {
+        range: lsp::Range::new(lsp::Position::new(row, 3), lsp::Position::new(row, 4)),
+        command: command.map(|title| lsp::Command {
+            title: title.into(),
+            command: "the/command".into(),
+            arguments: None,
+        }),
+        data: None,
+    };
+    let lenses = vec![lens(0, None), lens(1, Some("resolved")), lens(2, None)];
+    fake_server.handle_request::<lsp::request::CodeLensRequest, _, _>(move |_, _| {
+        let lenses = lenses.clone();
+        async move { Ok(Some(lenses)) }
+    });
+
+    // Each resolve request waits a while for the other one to start, and reports
+    // how many had started by then.
+    let started = Arc::new(AtomicUsize::new(0));
+    fake_server.handle_request::<lsp::request::CodeLensResolve, _, _>(move |mut lens, _| {
+        let started = started.clone();
+        async move {
+            started.fetch_add(1, SeqCst);
+            for _ in 0..100 {
+                if started.load(SeqCst) == 2 {
+                    break;
+                }
+                smol::future::yield_now().await;
+            }
+            lens.command = Some(lsp::Command {
+                title: format!("{} started", started.load(SeqCst)),
+                command: "the/command".into(),
+                arguments: None,
+            });
+            Ok(lens)
+        }
+    });
+
+    let lenses = project
+        .update(cx, |project, cx| project.code_lens(&buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        lenses
+            .iter()
+            .map(|lens| lens.lsp_lens.command.as_ref().unwrap().title.as_str())
+            .collect::<Vec<_>>(),
+        ["2 started", "resolved", "2 started"]
+    );
+}
+
+#[gpui::test]
+async fn test_document_symbols(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_symbol_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "struct A;\nimpl A {\n    fn old() {}\n    fn new() {}\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::DocumentSymbolRequest, _, _>(|_, _| async move {
+        #[allow(deprecated)]
+        fn symbol(
+            name: &str,
+            row: u32,
+            column: u32,
+            tags: Option<Vec<lsp::SymbolTag>>,
+            deprecated: Option<bool>,
+            children: Option<Vec<lsp::DocumentSymbol>>,
+        ) -> lsp::DocumentSymbol {
+            let end = lsp::Position::new(row, column + name.len() as u32);
+            lsp::DocumentSymbol {
+                name: name.to_string(),
+                detail: None,
+                kind: lsp::SymbolKind::FUNCTION,
+                tags,
+                deprecated,
+                range: lsp::Range::new(lsp::Position::new(row, 0), end),
+                selection_range: lsp::Range::new(lsp::Position::new(row, column), end),
+                children,
+            }
+        }
+
+        Ok(Some(lsp::DocumentSymbolResponse::Nested(vec![
+            symbol("A", 0, 7, None, Some(true), None),
+            symbol(
+                "impl A",
+                1,
+                0,
+                None,
+                None,
+                Some(vec![
+                    symbol(
+                        "old",
+                        2,
+                        7,
+                        Some(vec![lsp::SymbolTag::DEPRECATED]),
+                        None,
+                        None,
+                    ),
+                    symbol("new", 3, 7, None, Some(false), None),
+                ]),
+            ),
+        ])))
+    });
+
+    let symbols = project
+        .update(cx, |project, cx| project.document_symbols(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            symbols
+                .iter()
+                .map(|symbol| (
+                    symbol.name.as_str(),
+                    symbol.name_range.start.to_point(buffer),
+                    symbol.is_deprecated
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("A", Point::new(0, 7), true),
+                ("impl A", Point::new(1, 0), false),
+                ("old", Point::new(2, 7), true),
+                ("new", Point::new(3, 7), false),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2669,6 +3516,265 @@ async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_apply_code_actions_with_resolved_edits(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["ts".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Options(
+                    lsp::CodeActionOptions {
+                        resolve_provider: Some(true),
+                        ..Default::default()
+                    },
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    // Language server returns code actions whose edits are only computed when resolved.
+    let actions = project.update(cx, |project, cx| project.code_actions(&buffer, 0..0, cx));
+    fake_server
+        .handle_request::<lsp::request::CodeActionRequest, _, _>(|_, _| async move {
+            Ok(Some(vec![
+                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                    title: "Move to a new file".into(),
+                    data: Some(json!({ "id": 1 })),
+                    ..Default::default()
+                }),
+                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                    title: "Extract to function".into(),
+                    data: Some(json!({ "id": 2 })),
+                    ..Default::default()
+                }),
+            ]))
+        })
+        .next()
+        .await;
+    let actions = actions.await.unwrap();
+
+    fake_server.handle_request::<lsp::request::CodeActionResolveRequest, _, _>(
+        |mut action, _| async move {
+            if action.data != Some(json!({ "id": 1 })) {
+                return Err(anyhow!("the selection can't be extracted"));
+            }
+            action.edit = Some(lsp::WorkspaceEdit {
+                changes: Some(
+                    [(
+                        lsp::Url::from_file_path("/dir/a.ts").unwrap(),
+                        vec![lsp::TextEdit {
+                            range: lsp::Range::new(
+                                lsp::Position::new(0, 0),
+                                lsp::Position::new(0, 0),
+                            ),
+                            new_text: "X".into(),
+                        }],
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            });
+            Ok(action)
+        },
+    );
+
+    let transaction = project
+        .update(cx, |project, cx| {
+            project.apply_code_action(buffer.clone(), actions[0].clone(), true, cx)
+        })
+        .await
+        .unwrap();
+    assert!(transaction.0.contains_key(&buffer));
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "Xa"));
+
+    // Errors from resolving the code action are reported rather than ignored.
+    let error = project
+        .update(cx, |project, cx| {
+            project.apply_code_action(buffer.clone(), actions[1].clone(), true, cx)
+        })
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Extract to function"));
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "Xa"));
+}
+
+#[gpui::test]
+async fn test_apply_code_actions_with_resource_operations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["ts".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_language_servers = language.set_fake_lsp_adapter(Default::default()).await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "one two",
+            "old.ts": "",
+            "stale": {
+                "c.ts": "",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    // Moving a declaration to a new file creates the file, and edits both files.
+    let edit_a = |range: lsp::Range, new_text: &str| {
+        lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+            text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                uri: lsp::Url::from_file_path("/dir/a.ts").unwrap(),
+                version: None,
+            },
+            edits: vec![lsp::OneOf::Left(lsp::TextEdit {
+                range,
+                new_text: new_text.into(),
+            })],
+        })
+    };
+    let operations = vec![
+        lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(lsp::CreateFile {
+            uri: lsp::Url::from_file_path("/dir/b.ts").unwrap(),
+            options: None,
+            annotation_id: None,
+        })),
+        lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+            text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                uri: lsp::Url::from_file_path("/dir/b.ts").unwrap(),
+                version: None,
+            },
+            edits: vec![lsp::OneOf::Left(lsp::TextEdit {
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                new_text: "two".into(),
+            })],
+        }),
+        edit_a(
+            lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+            "",
+        ),
+        edit_a(
+            lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+            "import { two } from './b';\n",
+        ),
+        lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(lsp::RenameFile {
+            old_uri: lsp::Url::from_file_path("/dir/old.ts").unwrap(),
+            new_uri: lsp::Url::from_file_path("/dir/new.ts").unwrap(),
+            options: None,
+            annotation_id: None,
+        })),
+        lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(lsp::DeleteFile {
+            uri: lsp::Url::parse("file:///dir/stale/").unwrap(),
+            options: Some(lsp::DeleteFileOptions {
+                recursive: Some(true),
+                ignore_if_not_exists: None,
+                annotation_id: None,
+            }),
+        })),
+    ];
+
+    let actions = project.update(cx, |project, cx| project.code_actions(&buffer, 0..0, cx));
+    fake_server
+        .handle_request::<lsp::request::CodeActionRequest, _, _>(move |_, _| {
+            let operations = operations.clone();
+            async move {
+                Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                    lsp::CodeAction {
+                        title: "Move to a new file".into(),
+                        edit: Some(lsp::WorkspaceEdit {
+                            document_changes: Some(lsp::DocumentChanges::Operations(operations)),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                )]))
+            }
+        })
+        .next()
+        .await;
+    let action = actions.await.unwrap()[0].clone();
+
+    let transaction = project
+        .update(cx, |project, cx| {
+            project.apply_code_action(buffer.clone(), action, true, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(transaction.0.len(), 2);
+    assert!(fs.is_file(Path::new("/dir/new.ts")).await);
+    assert!(!fs.is_file(Path::new("/dir/old.ts")).await);
+    assert!(fs
+        .metadata(Path::new("/dir/stale"))
+        .await
+        .unwrap()
+        .is_none());
+
+    let new_buffer = transaction
+        .0
+        .keys()
+        .find(|transaction_buffer| **transaction_buffer != buffer)
+        .unwrap()
+        .clone();
+    new_buffer.update(cx, |new_buffer, _| assert_eq!(new_buffer.text(), "two"));
+
+    // Both of the edits to a buffer are undone together.
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "import { two } from './b';\none");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "one two");
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_save_file(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4,7 +4,7 @@ use settings::Settings;
 use std::path::{Path, PathBuf};
 use terminal::{
     terminal_settings::{self, TerminalSettings, VenvSettingsContent},
-    Terminal, TerminalBuilder,
+    SpawnTask, Terminal, TerminalBuilder,
 };
 
 // #[cfg(target_os = "macos")]
@@ -18,6 +18,7 @@ impl Project {
     pub fn create_terminal(
         &mut self,
         working_directory: Option<PathBuf>,
+        task: Option<SpawnTask>,
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
@@ -28,10 +29,13 @@ impl Project {
         } else {
             let settings = TerminalSettings::get_global(cx);
             let python_settings = settings.detect_venv.clone();
+            // Activating a virtual environment types into the shell, which a task doesn't run.
+            let activate_venv = task.is_none();
             let shell = settings.shell.clone();
 
             let terminal = TerminalBuilder::new(
                 working_directory.clone(),
+                task,
                 shell.clone(),
                 settings.env.clone(),
                 Some(settings.blinking.clone()),
@@ -59,7 +63,9 @@ impl Project {
                 })
                 .detach();
 
-                if let Some(python_settings) = &python_settings.as_option() {
+                if let Some(python_settings) =
+                    &python_settings.as_option().filter(|_| activate_venv)
+                {
                     let activate_command = Project::get_activate_command(python_settings);
                     let activate_script_path =
                         self.find_activate_script_path(python_settings, working_directory);
@@ -1,5 +1,6 @@
 pub mod file_associations;
 mod project_panel_settings;
+mod symbol_rename;
 use client::{ErrorCode, ErrorExt};
 use settings::Settings;
 
@@ -8,7 +9,7 @@ use editor::{actions::Cancel, scroll::Autoscroll, Editor};
 use file_associations::FileAssociations;
 
 use anyhow::{anyhow, Result};
-use collections::{hash_map, HashMap};
+use collections::{hash_map, HashMap, HashSet};
 use gpui::{
     actions, div, overlay, px, uniform_list, Action, AppContext, AssetSource, AsyncWindowContext,
     ClipboardItem, DismissEvent, Div, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
@@ -19,11 +20,18 @@ use gpui::{
 use menu::{Confirm, SelectNext, SelectPrev};
 use project::{
     repository::GitFileStatus, Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath,
-    Worktree, WorktreeId,
+    ProjectTransaction, Worktree, WorktreeId,
 };
 use project_panel_settings::{ProjectPanelDockPosition, ProjectPanelSettings};
 use serde::{Deserialize, Serialize};
-use std::{cmp::Ordering, ffi::OsStr, ops::Range, path::Path, sync::Arc};
+use std::{
+    cmp::Ordering,
+    ffi::OsStr,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use symbol_rename::SymbolRename;
 use theme::ThemeSettings;
 use ui::{prelude::*, v_flex, ContextMenu, Icon, KeyBinding, Label, ListItem};
 use unicase::UniCase;
@@ -54,6 +62,7 @@ pub struct ProjectPanel {
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
     pending_serialization: Task<Option<()>>,
+    last_symbol_rename: Option<SymbolRename>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -98,6 +107,7 @@ pub struct EntryDetails {
     is_cut: bool,
     git_status: Option<GitFileStatus>,
     is_dotenv: bool,
+    is_symlink: bool,
 }
 
 actions!(
@@ -112,11 +122,13 @@ actions!(
         CopyPath,
         CopyRelativePath,
         RevealInFinder,
+        RevealSymlinkTarget,
         OpenInTerminal,
         Cut,
         Paste,
         Delete,
         Rename,
+        UndoRename,
         Open,
         ToggleFocus,
         NewSearchInDirectory,
@@ -240,6 +252,7 @@ impl ProjectPanel {
                 workspace: workspace.weak_handle(),
                 width: None,
                 pending_serialization: Task::ready(None),
+                last_symbol_rename: None,
             };
             this.update_visible_entries(None, cx);
 
@@ -391,9 +404,11 @@ impl ProjectPanel {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             let is_root = Some(entry) == worktree.root_entry();
             let is_dir = entry.is_dir();
+            let is_symlink = entry.is_symlink;
             let worktree_id = worktree.id();
             let is_local = project.is_local();
             let is_read_only = project.is_read_only();
+            let can_undo_rename = self.last_symbol_rename.is_some();
 
             let context_menu = ContextMenu::build(cx, |menu, cx| {
                 menu.context(self.focus_handle.clone()).when_else(
@@ -437,12 +452,18 @@ impl ProjectPanel {
                         .action("Copy Relative Path", Box::new(CopyRelativePath))
                         .separator()
                         .action("Reveal in Finder", Box::new(RevealInFinder))
+                        .when(is_local && is_symlink, |menu| {
+                            menu.action("Reveal Symlink Target", Box::new(RevealSymlinkTarget))
+                        })
                         .when(is_dir, |menu| {
                             menu.action("Open in Terminal", Box::new(OpenInTerminal))
                                 .action("Search Inside", Box::new(NewSearchInDirectory))
                         })
                         .separator()
                         .action("Rename", Box::new(Rename))
+                        .when(can_undo_rename, |menu| {
+                            menu.action("Undo Rename", Box::new(UndoRename))
+                        })
                         .when(!is_root, |menu| menu.action("Delete", Box::new(Delete)))
                     },
                 )
@@ -627,9 +648,25 @@ impl ProjectPanel {
             }
 
             edited_entry_id = entry.id;
-            edit_task = self.project.update(cx, |project, cx| {
-                project.rename_entry(entry.id, new_path.as_path(), cx)
-            });
+            let renamed_symbol = entry
+                .is_file()
+                .then(|| symbol_rename::renamed_stems(&entry.path, &new_path))
+                .flatten();
+            edit_task = if let Some((old_name, new_name)) = renamed_symbol {
+                Self::rename_entry_and_symbol(
+                    self.project.clone(),
+                    worktree_id,
+                    &entry,
+                    new_path,
+                    old_name,
+                    new_name,
+                    cx,
+                )
+            } else {
+                self.project.update(cx, |project, cx| {
+                    project.rename_entry(entry.id, new_path.as_path(), cx)
+                })
+            };
         };
 
         edit_state.processing_filename = Some(filename);
@@ -662,6 +699,132 @@ impl ProjectPanel {
         }))
     }
 
+    /// Renames a file whose stem is the name of one of its top-level symbols, such as
+    /// `FooBar.tsx`, offering to rename the symbol and its usages along with it.
+    fn rename_entry_and_symbol(
+        project: Model<Project>,
+        worktree_id: WorktreeId,
+        entry: &Entry,
+        new_path: PathBuf,
+        old_name: String,
+        new_name: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Option<Entry>>> {
+        let entry_id = entry.id;
+        let old_path = entry.path.clone();
+        let open_buffer = project.update(cx, |project, cx| {
+            project.open_buffer((worktree_id, old_path.clone()), cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let mut transaction = None;
+            if let Some(buffer) = open_buffer.await.log_err() {
+                let offset = project.update(&mut cx, |project, cx| {
+                    let has_language_server = project.is_local()
+                        && project
+                            .language_servers_for_buffer(buffer.read(cx), cx)
+                            .next()
+                            .is_some();
+                    has_language_server
+                        .then(|| {
+                            symbol_rename::symbol_offset(&buffer.read(cx).snapshot(), &old_name)
+                        })
+                        .flatten()
+                })?;
+                if let Some(offset) = offset {
+                    let answer = this.update(&mut cx, |_, cx| {
+                        cx.prompt(
+                            PromptLevel::Info,
+                            &format!("Also rename `{old_name}` to `{new_name}`?"),
+                            Some("Its usages are renamed across the project."),
+                            &["Rename Symbol", "Only Rename File"],
+                        )
+                    })?;
+                    if answer.await == Ok(0) {
+                        let project_transaction = project
+                            .update(&mut cx, |project, cx| {
+                                project.perform_rename(buffer, offset, new_name, true, cx)
+                            })?
+                            .await?;
+                        let buffers = project_transaction.0.keys().cloned().collect();
+                        project
+                            .update(&mut cx, |project, cx| project.save_buffers(buffers, cx))?
+                            .await?;
+                        transaction = Some(project_transaction);
+                    }
+                }
+            }
+
+            let new_entry = project
+                .update(&mut cx, |project, cx| {
+                    project.rename_entry(entry_id, new_path.as_path(), cx)
+                })?
+                .await;
+            // The symbol is only renamed along with the file, so it's renamed back when
+            // the file can't be.
+            let new_entry = match new_entry {
+                Ok(new_entry) => new_entry,
+                Err(error) => {
+                    if let Some(transaction) = transaction {
+                        Self::undo_symbol_rename(&project, transaction, &mut cx)
+                            .await
+                            .log_err();
+                    }
+                    return Err(error);
+                }
+            };
+            if let (Some(new_entry), Some(transaction)) = (&new_entry, transaction) {
+                this.update(&mut cx, |this, _| {
+                    this.last_symbol_rename = Some(SymbolRename {
+                        entry_id: new_entry.id,
+                        old_path,
+                        transaction,
+                    });
+                })?;
+            }
+            Ok(new_entry)
+        })
+    }
+
+    /// Undoes the last rename of a file along with its symbol, reverting the file's
+    /// name and the edits that renamed the symbol.
+    fn undo_rename(&mut self, _: &UndoRename, cx: &mut ViewContext<Self>) {
+        let Some(rename) = self.last_symbol_rename.take() else {
+            return;
+        };
+        let project = self.project.clone();
+        cx.spawn(|_, mut cx| async move {
+            Self::undo_symbol_rename(&project, rename.transaction, &mut cx).await?;
+            project
+                .update(&mut cx, |project, cx| {
+                    project.rename_entry(rename.entry_id, rename.old_path, cx)
+                })?
+                .await?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Undoes the edits that renamed a symbol, and saves the buffers they were made in.
+    async fn undo_symbol_rename(
+        project: &Model<Project>,
+        transaction: ProjectTransaction,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let buffers = project.update(cx, |_, cx| {
+            transaction
+                .0
+                .into_iter()
+                .map(|(buffer, transaction)| {
+                    buffer.update(cx, |buffer, cx| buffer.undo_transaction(transaction.id, cx));
+                    buffer
+                })
+                .collect::<HashSet<_>>()
+        })?;
+        project
+            .update(cx, |project, cx| project.save_buffers(buffers, cx))?
+            .await
+    }
+
     fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
         self.edit_state = None;
         self.update_visible_entries(None, cx);
@@ -975,6 +1138,44 @@ impl ProjectPanel {
         }
     }
 
+    /// Selects the entry a symlink points to, or reveals it in Finder when it's outside
+    /// of the project.
+    fn reveal_symlink_target(&mut self, _: &RevealSymlinkTarget, cx: &mut ViewContext<Self>) {
+        let Some((worktree, entry)) = self.selected_entry(cx) else {
+            return;
+        };
+        if !entry.is_symlink {
+            return;
+        }
+        let abs_path = worktree.abs_path().join(&entry.path);
+        let fs = self.project.read(cx).fs().clone();
+        cx.spawn(|this, mut cx| async move {
+            let target_path = fs.canonicalize(&abs_path).await?;
+            this.update(&mut cx, |this, cx| {
+                let project = this.project.clone();
+                let target_entry = project
+                    .read(cx)
+                    .find_local_worktree(&target_path, cx)
+                    .and_then(|(worktree, relative_path)| {
+                        let worktree = worktree.read(cx);
+                        let entry = worktree.entry_for_path(&relative_path)?;
+                        Some((worktree.id(), entry.id))
+                    });
+                match target_entry {
+                    Some((worktree_id, entry_id)) => {
+                        this.selection = Some(Selection {
+                            worktree_id,
+                            entry_id,
+                        });
+                        this.reveal_entry(project, entry_id, false, cx);
+                    }
+                    None => cx.reveal_path(&target_path),
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn open_in_terminal(&mut self, _: &OpenInTerminal, cx: &mut ViewContext<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             let path = worktree.abs_path().join(&entry.path);
@@ -1317,6 +1518,7 @@ impl ProjectPanel {
                             .map_or(false, |e| e.is_cut() && e.entry_id() == entry.id),
                         git_status: status,
                         is_dotenv: entry.is_private,
+                        is_symlink: entry.is_symlink,
                     };
 
                     if let Some(edit_state) = &self.edit_state {
@@ -1380,6 +1582,7 @@ impl ProjectPanel {
         let file_name = details.filename.clone();
         let icon = details.icon.clone();
         let depth = details.depth;
+        let is_symlink = details.is_symlink;
         div()
             .id(entry_id.to_proto() as usize)
             .on_drag(entry_id, move |entry_id, cx| {
@@ -1413,6 +1616,13 @@ impl ProjectPanel {
                         }
                         .ml_1(),
                     )
+                    .when(is_symlink, |item| {
+                        item.end_slot(
+                            Icon::new(IconName::Link)
+                                .size(IconSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                    })
                     .on_click(cx.listener(move |this, event: &gpui::ClickEvent, cx| {
                         if event.down.button == MouseButton::Right {
                             return;
@@ -1507,6 +1717,7 @@ impl Render for ProjectPanel {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
                         .on_action(cx.listener(Self::rename))
+                        .on_action(cx.listener(Self::undo_rename))
                         .on_action(cx.listener(Self::delete))
                         .on_action(cx.listener(Self::cut))
                         .on_action(cx.listener(Self::copy))
@@ -1514,6 +1725,7 @@ impl Render for ProjectPanel {
                 })
                 .when(project.is_local(), |el| {
                     el.on_action(cx.listener(Self::reveal_in_finder))
+                        .on_action(cx.listener(Self::reveal_symlink_target))
                         .on_action(cx.listener(Self::open_in_terminal))
                 })
                 .on_mouse_down(
@@ -1694,7 +1906,9 @@ impl ClipboardEntry {
 mod tests {
     use super::*;
     use collections::HashSet;
+    use futures::StreamExt;
     use gpui::{TestAppContext, View, VisualTestContext, WindowHandle};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
     use pretty_assertions::assert_eq;
     use project::{project_settings::ProjectSettings, FakeFs};
     use serde_json::json;
@@ -3441,6 +3655,127 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_rename_file_and_symbol(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let mut language = Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_outline_query("(struct_item name: (_) @name) @item")
+        .unwrap();
+        let mut fake_servers = language
+            .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    rename_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }))
+            .await;
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "FooBar.rs": "struct FooBar;",
+                "main.rs": "fn main() { FooBar; }",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        project.update(cx, |project, _| project.languages().add(Arc::new(language)));
+        let _buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/FooBar.rs", cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        let mut rename_requests =
+            fake_server.handle_request::<lsp::request::Rename, _, _>(|params, _| async move {
+                assert_eq!(params.new_name, "BazQux");
+                let edit = |path: &str, column: u32| {
+                    (
+                        lsp::Url::from_file_path(path).unwrap(),
+                        vec![lsp::TextEdit::new(
+                            lsp::Range::new(
+                                lsp::Position::new(0, column),
+                                lsp::Position::new(0, column + 6),
+                            ),
+                            "BazQux".to_string(),
+                        )],
+                    )
+                };
+                Ok(Some(lsp::WorkspaceEdit {
+                    changes: Some(
+                        [edit("/root/FooBar.rs", 7), edit("/root/main.rs", 12)]
+                            .into_iter()
+                            .collect(),
+                    ),
+                    ..Default::default()
+                }))
+            });
+
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        select_path(&panel, "root/FooBar.rs", cx);
+        let confirm = panel.update(cx, |panel, cx| {
+            panel.rename(&Rename, cx);
+            panel
+                .filename_editor
+                .update(cx, |editor, cx| editor.set_text("BazQux.rs", cx));
+            panel.confirm_edit(cx).unwrap()
+        });
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(0);
+        rename_requests.next().await.unwrap();
+        confirm.await.unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v root", "      BazQux.rs  <== selected", "      main.rs"]
+        );
+        assert_eq!(
+            fs.load("/root/BazQux.rs".as_ref()).await.unwrap(),
+            "struct BazQux;"
+        );
+        assert_eq!(
+            fs.load("/root/main.rs".as_ref()).await.unwrap(),
+            "fn main() { BazQux; }"
+        );
+
+        // The file and the symbol are renamed back together.
+        panel.update(cx, |panel, cx| panel.undo_rename(&UndoRename, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v root", "      FooBar.rs  <== selected", "      main.rs"]
+        );
+        assert_eq!(
+            fs.load("/root/FooBar.rs".as_ref()).await.unwrap(),
+            "struct FooBar;"
+        );
+        assert_eq!(
+            fs.load("/root/main.rs".as_ref()).await.unwrap(),
+            "fn main() { FooBar; }"
+        );
+    }
+
     fn toggle_expand_dir(
         panel: &View<ProjectPanel>,
         path: impl AsRef<Path>,
@@ -0,0 +1,98 @@
+use language::{BufferSnapshot, OffsetRangeExt};
+use project::{ProjectEntryId, ProjectTransaction};
+use std::{path::Path, sync::Arc};
+
+/// A file rename that also renamed the symbol the file is named after, which is
+/// undone as a whole.
+pub(crate) struct SymbolRename {
+    pub entry_id: ProjectEntryId,
+    pub old_path: Arc<Path>,
+    pub transaction: ProjectTransaction,
+}
+
+/// Returns the old and new names of the symbol to rename along with a file, when
+/// the rename only changes the file's stem, such as `FooBar.tsx` to `BazQux.tsx`.
+pub(crate) fn renamed_stems(old_path: &Path, new_path: &Path) -> Option<(String, String)> {
+    if old_path.parent() != new_path.parent() || old_path.extension() != new_path.extension() {
+        return None;
+    }
+    let old_stem = old_path.file_stem()?.to_str()?;
+    let new_stem = new_path.file_stem()?.to_str()?;
+    if old_stem == new_stem || !is_identifier(old_stem) || !is_identifier(new_stem) {
+        return None;
+    }
+    Some((old_stem.to_string(), new_stem.to_string()))
+}
+
+/// Returns the offset of the name of the top-level symbol called `name`.
+pub(crate) fn symbol_offset(buffer: &BufferSnapshot, name: &str) -> Option<usize> {
+    let outline = buffer.outline(None)?;
+    outline.items.iter().find_map(|item| {
+        let item_name = item
+            .name_ranges
+            .iter()
+            .map(|range| &item.text[range.clone()])
+            .collect::<String>();
+        if item.depth != 0 || item_name != name {
+            return None;
+        }
+        let range = item.range.to_offset(buffer);
+        let text = buffer.text_for_range(range.clone()).collect::<String>();
+        Some(range.start + find_word(&text, name)?)
+    })
+}
+
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    text.match_indices(word).map(|(ix, _)| ix).find(|&ix| {
+        let before = text[..ix].chars().next_back();
+        let after = text[ix + word.len()..].chars().next();
+        !before.map_or(false, is_identifier_char) && !after.map_or(false, is_identifier_char)
+    })
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(is_identifier_char)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renamed_stems() {
+        assert_eq!(
+            renamed_stems(Path::new("src/FooBar.tsx"), Path::new("src/BazQux.tsx")),
+            Some(("FooBar".into(), "BazQux".into()))
+        );
+        assert_eq!(
+            renamed_stems(Path::new("src/FooBar.tsx"), Path::new("src/FooBar.ts")),
+            None
+        );
+        assert_eq!(
+            renamed_stems(Path::new("src/FooBar.tsx"), Path::new("src/foo-bar.tsx")),
+            None
+        );
+        assert_eq!(
+            renamed_stems(Path::new("src/FooBar.tsx"), Path::new("lib/BazQux.tsx")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_word() {
+        assert_eq!(
+            find_word("export class FooBarProps extends FooBar", "FooBar"),
+            Some(33)
+        );
+        assert_eq!(find_word("export class FooBar {}", "FooBar"), Some(13));
+        assert_eq!(find_word("const $FooBar = 1", "FooBar"), None);
+    }
+}
@@ -1,8 +1,8 @@
 use editor::{scroll::Autoscroll, styled_runs_for_code_label, Bias, Editor};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
-    actions, rems, AppContext, DismissEvent, FontWeight, Model, ParentElement, StyledText, Task,
-    View, ViewContext, WeakView,
+    actions, rems, AppContext, DismissEvent, FontWeight, HighlightStyle, Model, ParentElement,
+    StrikethroughStyle, StyledText, Task, View, ViewContext, WeakView,
 };
 use ordered_float::OrderedFloat;
 use picker::{Picker, PickerDelegate};
@@ -223,11 +223,24 @@ impl PickerDelegate for ProjectSymbolsDelegate {
         let label = symbol.label.text.clone();
         let path = path.to_string().clone();
 
+        let deprecated_highlight = symbol.is_deprecated.then(|| {
+            (
+                0..label.len(),
+                HighlightStyle {
+                    strikethrough: Some(StrikethroughStyle {
+                        thickness: 1.0.into(),
+                        color: None,
+                    }),
+                    ..Default::default()
+                },
+            )
+        });
         let highlights = gpui::combine_highlights(
             string_match
                 .positions
                 .iter()
-                .map(|pos| (*pos..pos + 1, FontWeight::BOLD.into())),
+                .map(|pos| (*pos..pos + 1, FontWeight::BOLD.into()))
+                .chain(deprecated_highlight),
             syntax_runs.map(|(range, mut highlight)| {
                 // Ignore font weight for syntax highlighting, as we'll use it
                 // for fuzzy matches.
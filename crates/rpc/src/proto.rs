@@ -174,14 +174,26 @@ messages!(
     (GetChannelMessagesResponse, Background),
     (GetCodeActions, Background),
     (GetCodeActionsResponse, Background),
+    (GetCodeLens, Background),
+    (GetCodeLensResponse, Background),
     (GetCompletions, Background),
     (GetCompletionsResponse, Background),
     (GetDefinition, Background),
     (GetDefinitionResponse, Background),
+    (GetDocumentColors, Background),
+    (GetDocumentColorsResponse, Background),
+    (GetDocumentSymbols, Background),
+    (GetDocumentSymbolsResponse, Background),
     (GetDocumentHighlights, Background),
     (GetDocumentHighlightsResponse, Background),
+    (GetDocumentLink, Background),
+    (GetDocumentLinkResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
+    (GetLinkedEditingRanges, Background),
+    (GetLinkedEditingRangesResponse, Background),
     (GetNotifications, Foreground),
     (GetNotificationsResponse, Foreground),
     (GetPrivateUserInfo, Foreground),
@@ -190,6 +202,8 @@ messages!(
     (GetProjectSymbolsResponse, Background),
     (GetReferences, Background),
     (GetReferencesResponse, Background),
+    (GetSignatureHelp, Background),
+    (GetSignatureHelpResponse, Background),
     (GetTypeDefinition, Background),
     (GetTypeDefinitionResponse, Background),
     (GetUsers, Foreground),
@@ -310,14 +324,21 @@ request_messages!(
     (GetChannelMessages, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
+    (GetCodeLens, GetCodeLensResponse),
     (GetCompletions, GetCompletionsResponse),
     (GetDefinition, GetDefinitionResponse),
+    (GetDocumentColors, GetDocumentColorsResponse),
+    (GetDocumentSymbols, GetDocumentSymbolsResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
+    (GetDocumentLink, GetDocumentLinkResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
     (GetHover, GetHoverResponse),
+    (GetLinkedEditingRanges, GetLinkedEditingRangesResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetPrivateUserInfo, GetPrivateUserInfoResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
+    (GetSignatureHelp, GetSignatureHelpResponse),
     (GetTypeDefinition, GetTypeDefinitionResponse),
     (GetUsers, UsersResponse),
     (IncomingCall, Ack),
@@ -386,12 +407,19 @@ entity_messages!(
     ExpandProjectEntry,
     FormatBuffers,
     GetCodeActions,
+    GetCodeLens,
     GetCompletions,
     GetDefinition,
+    GetDocumentColors,
+    GetDocumentSymbols,
     GetDocumentHighlights,
+    GetDocumentLink,
+    GetFoldingRanges,
     GetHover,
+    GetLinkedEditingRanges,
     GetProjectSymbols,
     GetReferences,
+    GetSignatureHelp,
     GetTypeDefinition,
     InlayHints,
     JoinProject,
@@ -0,0 +1,488 @@
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use futures::{future::Shared, FutureExt};
+use gpui::{AppContext, BackgroundExecutor, Global, PromptLevel, Task};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use util::ResultExt;
+
+const SECRETS_KEY: &str = "secrets";
+const SECRET_PERMISSIONS_KEY: &str = "secret_permissions";
+
+/// The features that keep secrets in the platform keychain.
+///
+/// A feature can only access the keychain entries that it stored itself, so that a
+/// secret can't be read on behalf of another feature, and so that the user can see
+/// which feature each entry belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SecretScope {
+    ZedAccount,
+    OpenAi,
+    GitHub,
+    Database,
+}
+
+impl SecretScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SecretScope::ZedAccount => "Zed Account",
+            SecretScope::OpenAi => "OpenAI",
+            SecretScope::GitHub => "GitHub",
+            SecretScope::Database => "Databases",
+        }
+    }
+}
+
+/// A secret stored in the keychain. The secret itself is only ever kept in the keychain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretEntry {
+    pub scope: SecretScope,
+    pub url: String,
+    pub username: String,
+}
+
+/// The keychain can't list the entries that Zed has stored, so they are recorded in the
+/// database alongside the feature that stored them.
+struct Secrets {
+    entries: Vec<SecretEntry>,
+    /// Whether the user allowed each feature to use the keychain, which is asked the
+    /// first time the feature accesses it.
+    permissions: HashMap<SecretScope, bool>,
+    permission_prompts: HashMap<SecretScope, Shared<Task<Option<bool>>>>,
+    loaded: Shared<Task<()>>,
+    executor: BackgroundExecutor,
+}
+
+impl Global for Secrets {}
+
+/// Loads the recorded entries and permissions from the database in the background.
+pub fn init(cx: &mut AppContext) {
+    let load = cx.background_executor().spawn(async move {
+        let entries: Vec<SecretEntry> = read_kvp_json(SECRETS_KEY).unwrap_or_default();
+        let permissions: HashMap<SecretScope, bool> =
+            read_kvp_json(SECRET_PERMISSIONS_KEY).unwrap_or_default();
+        (entries, permissions)
+    });
+    let loaded = cx
+        .spawn(|cx| async move {
+            let (entries, permissions) = load.await;
+            cx.update(|cx| {
+                let secrets = cx.global_mut::<Secrets>();
+                secrets.entries = entries;
+                secrets.permissions = permissions;
+            })
+            .log_err();
+        })
+        .shared();
+    cx.set_global(Secrets {
+        entries: Vec::new(),
+        permissions: HashMap::default(),
+        permission_prompts: HashMap::default(),
+        loaded,
+        executor: cx.background_executor().clone(),
+    });
+}
+
+fn read_kvp_json<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let value = KEY_VALUE_STORE.read_kvp(key).log_err()??;
+    serde_json::from_str(&value).log_err()
+}
+
+impl Secrets {
+    fn global_mut(cx: &mut AppContext) -> &mut Self {
+        if !cx.has_global::<Self>() {
+            init(cx);
+        }
+        cx.global_mut::<Self>()
+    }
+
+    fn check_access(&self, scope: SecretScope, url: &str) -> Result<()> {
+        match self.entries.iter().find(|entry| entry.url == url) {
+            Some(entry) if entry.scope != scope => Err(anyhow!(
+                "{} can't access the secret stored for {url} by {}",
+                scope.label(),
+                entry.scope.label()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the entry, replacing the one with the same url, and persists the entries.
+    fn insert(&mut self, entry: SecretEntry) -> Task<Result<()>> {
+        self.entries.retain(|existing| existing.url != entry.url);
+        self.entries.push(entry);
+        self.persist(SECRETS_KEY, &self.entries)
+    }
+
+    fn remove(&mut self, url: &str) -> Task<Result<()>> {
+        self.entries.retain(|entry| entry.url != url);
+        self.persist(SECRETS_KEY, &self.entries)
+    }
+
+    fn persist(&self, key: &str, value: &impl serde::Serialize) -> Task<Result<()>> {
+        let value = match serde_json::to_string(value) {
+            Ok(value) => value,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        let key = key.to_string();
+        self.executor
+            .spawn(async move { KEY_VALUE_STORE.write_kvp(key, value).await })
+    }
+}
+
+/// The secrets that are stored in the keychain, in the order in which they were stored.
+///
+/// No entries are returned until they've been loaded, see [`secrets_loaded`].
+pub fn secret_entries(cx: &mut AppContext) -> Vec<SecretEntry> {
+    Secrets::global_mut(cx).entries.clone()
+}
+
+/// Resolves once the recorded entries have been loaded from the database.
+pub fn secrets_loaded(cx: &mut AppContext) -> Shared<Task<()>> {
+    Secrets::global_mut(cx).loaded.clone()
+}
+
+/// Checks that the feature can access the secret stored for the url, asking the user
+/// whether it may use the keychain the first time it does.
+fn authorize(scope: SecretScope, url: &str, cx: &mut AppContext) -> Task<Result<()>> {
+    let loaded = secrets_loaded(cx);
+    let url = url.to_string();
+    cx.spawn(|cx| async move {
+        loaded.await;
+        let permission = cx.update(|cx| {
+            Secrets::global_mut(cx).check_access(scope, &url)?;
+            anyhow::Ok(request_permission(scope, cx))
+        })??;
+        match permission.await {
+            Some(true) => Ok(()),
+            Some(false) => Err(anyhow!(
+                "{} isn't allowed to use the keychain",
+                scope.label()
+            )),
+            None => Err(anyhow!(
+                "couldn't ask whether {} may use the keychain",
+                scope.label()
+            )),
+        }
+    })
+}
+
+/// Resolves to whether the user allowed the feature to use the keychain, prompting
+/// them unless they already answered. Resolves to `None` if there's no window to
+/// prompt in, in which case they're asked again on the next access.
+fn request_permission(scope: SecretScope, cx: &mut AppContext) -> Shared<Task<Option<bool>>> {
+    let secrets = cx.global::<Secrets>();
+    if let Some(granted) = secrets.permissions.get(&scope) {
+        return Task::ready(Some(*granted)).shared();
+    }
+    // Accesses made while the user is being asked share the answer.
+    if let Some(prompt) = secrets.permission_prompts.get(&scope) {
+        return prompt.clone();
+    }
+
+    let Some(answer) = cx
+        .active_window()
+        .or_else(|| cx.windows().into_iter().next())
+        .and_then(|window| {
+            window
+                .update(cx, |_, cx| {
+                    cx.prompt(
+                        PromptLevel::Info,
+                        &format!("Allow {} to use the keychain?", scope.label()),
+                        Some("Your answer is remembered."),
+                        &["Allow", "Deny"],
+                    )
+                })
+                .log_err()
+        })
+    else {
+        return Task::ready(None).shared();
+    };
+    let prompt = cx
+        .spawn(|cx| async move {
+            let answer = answer.await.ok();
+            cx.update(|cx| {
+                let secrets = cx.global_mut::<Secrets>();
+                secrets.permission_prompts.remove(&scope);
+                let granted = answer? == 0;
+                secrets.permissions.insert(scope, granted);
+                secrets
+                    .persist(SECRET_PERMISSIONS_KEY, &secrets.permissions)
+                    .detach_and_log_err(cx);
+                Some(granted)
+            })
+            .ok()
+            .flatten()
+        })
+        .shared();
+    cx.global_mut::<Secrets>()
+        .permission_prompts
+        .insert(scope, prompt.clone());
+    prompt
+}
+
+/// Reads the secret that the feature stored for the url.
+///
+/// A secret that isn't recorded yet, such as one stored before the secrets service
+/// existed, is recorded as belonging to the first feature that reads it, so that no
+/// other feature can access it afterwards.
+pub fn read_secret(
+    scope: SecretScope,
+    url: &str,
+    cx: &mut AppContext,
+) -> Task<Result<Option<(String, Vec<u8>)>>> {
+    let authorize = authorize(scope, url, cx);
+    let url = url.to_string();
+    cx.spawn(|cx| async move {
+        authorize.await?;
+        let credentials = cx.update(|cx| cx.read_credentials(&url))?.await?;
+        if let Some((username, _)) = credentials.as_ref() {
+            let insert = cx.update(|cx| {
+                let secrets = Secrets::global_mut(cx);
+                // Another feature may have claimed the secret while it was being read.
+                secrets.check_access(scope, &url)?;
+                let is_recorded = secrets.entries.iter().any(|entry| entry.url == url);
+                anyhow::Ok((!is_recorded).then(|| {
+                    secrets.insert(SecretEntry {
+                        scope,
+                        url,
+                        username: username.clone(),
+                    })
+                }))
+            })??;
+            if let Some(insert) = insert {
+                insert.await?;
+            }
+        }
+        Ok(credentials)
+    })
+}
+
+pub fn write_secret(
+    scope: SecretScope,
+    url: &str,
+    username: &str,
+    secret: &[u8],
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let authorize = authorize(scope, url, cx);
+    let entry = SecretEntry {
+        scope,
+        url: url.to_string(),
+        username: username.to_string(),
+    };
+    let secret = secret.to_vec();
+    cx.spawn(|cx| async move {
+        authorize.await?;
+        cx.update(|cx| cx.write_credentials(&entry.url, &entry.username, &secret))?
+            .await?;
+        // The entry is only recorded once the keychain has the secret.
+        cx.update(|cx| Secrets::global_mut(cx).insert(entry))?.await
+    })
+}
+
+pub fn delete_secret(scope: SecretScope, url: &str, cx: &mut AppContext) -> Task<Result<()>> {
+    let authorize = authorize(scope, url, cx);
+    let url = url.to_string();
+    cx.spawn(|cx| async move {
+        authorize.await?;
+        cx.update(|cx| remove_secret(&url, cx))?.await
+    })
+}
+
+/// Revokes a secret at the user's request, whichever feature stored it.
+///
+/// Only the recorded entries can be revoked, so this can't be used to delete arbitrary
+/// keychain items.
+pub fn revoke_secret(entry: &SecretEntry, cx: &mut AppContext) -> Task<Result<()>> {
+    let loaded = secrets_loaded(cx);
+    let entry = entry.clone();
+    cx.spawn(|cx| async move {
+        loaded.await;
+        cx.update(|cx| {
+            if !Secrets::global_mut(cx).entries.contains(&entry) {
+                return Task::ready(Err(anyhow!("no secret is stored for {}", entry.url)));
+            }
+            remove_secret(&entry.url, cx)
+        })?
+        .await
+    })
+}
+
+fn remove_secret(url: &str, cx: &mut AppContext) -> Task<Result<()>> {
+    let delete_credentials = cx.delete_credentials(url);
+    let url = url.to_string();
+    cx.spawn(|cx| async move {
+        delete_credentials.await?;
+        cx.update(|cx| Secrets::global_mut(cx).remove(&url))?.await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Starts from no recorded entries, rather than from the ones that other tests
+    /// persisted in the shared database, with the given features allowed to use the
+    /// keychain.
+    fn init_test(allowed_scopes: &[SecretScope], cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| {
+            cx.set_global(Secrets {
+                entries: Vec::new(),
+                permissions: allowed_scopes.iter().map(|scope| (*scope, true)).collect(),
+                permission_prompts: HashMap::default(),
+                loaded: Task::ready(()).shared(),
+                executor: cx.background_executor().clone(),
+            })
+        });
+    }
+
+    const ALL_SCOPES: [SecretScope; 4] = [
+        SecretScope::ZedAccount,
+        SecretScope::OpenAi,
+        SecretScope::GitHub,
+        SecretScope::Database,
+    ];
+
+    #[gpui::test]
+    async fn test_secrets_are_scoped_to_features(cx: &mut gpui::TestAppContext) {
+        init_test(&ALL_SCOPES, cx);
+        let url = "https://api.openai.com/v1";
+        cx.update(|cx| write_secret(SecretScope::OpenAi, url, "Bearer", b"sk-123", cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            cx.update(|cx| secret_entries(cx)),
+            [SecretEntry {
+                scope: SecretScope::OpenAi,
+                url: url.into(),
+                username: "Bearer".into(),
+            }]
+        );
+
+        cx.update(|cx| read_secret(SecretScope::OpenAi, url, cx))
+            .await
+            .unwrap();
+        cx.update(|cx| read_secret(SecretScope::ZedAccount, url, cx))
+            .await
+            .unwrap_err();
+        cx.update(|cx| write_secret(SecretScope::ZedAccount, url, "1", b"token", cx))
+            .await
+            .unwrap_err();
+        cx.update(|cx| delete_secret(SecretScope::ZedAccount, url, cx))
+            .await
+            .unwrap_err();
+
+        cx.update(|cx| delete_secret(SecretScope::OpenAi, url, cx))
+            .await
+            .unwrap();
+        assert!(cx.update(|cx| secret_entries(cx)).is_empty());
+        cx.update(|cx| read_secret(SecretScope::ZedAccount, url, cx))
+            .await
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_revoking_secrets(cx: &mut gpui::TestAppContext) {
+        init_test(&ALL_SCOPES, cx);
+        let url = "https://api.github.com";
+        let entry = SecretEntry {
+            scope: SecretScope::GitHub,
+            url: url.into(),
+            username: "token".into(),
+        };
+        cx.update(|cx| revoke_secret(&entry, cx)).await.unwrap_err();
+
+        cx.update(|cx| write_secret(SecretScope::GitHub, url, "token", b"ghp_123", cx))
+            .await
+            .unwrap();
+        let other_scope = SecretEntry {
+            scope: SecretScope::OpenAi,
+            ..entry.clone()
+        };
+        cx.update(|cx| revoke_secret(&other_scope, cx))
+            .await
+            .unwrap_err();
+        assert!(cx.update(|cx| secret_entries(cx)).contains(&entry));
+
+        cx.update(|cx| revoke_secret(&entry, cx)).await.unwrap();
+        assert!(!cx.update(|cx| secret_entries(cx)).contains(&entry));
+    }
+
+    #[gpui::test]
+    async fn test_reading_unrecorded_secrets(cx: &mut gpui::TestAppContext) {
+        init_test(&ALL_SCOPES, cx);
+        cx.enable_keychain();
+
+        // A secret stored before the secrets service existed isn't recorded.
+        let url = "https://zed.dev";
+        cx.update(|cx| cx.write_credentials(url, "1", b"token"))
+            .await
+            .unwrap();
+        assert!(cx.update(|cx| secret_entries(cx)).is_empty());
+
+        // The first feature that reads it claims it.
+        assert_eq!(
+            cx.update(|cx| read_secret(SecretScope::ZedAccount, url, cx))
+                .await
+                .unwrap(),
+            Some(("1".into(), b"token".to_vec()))
+        );
+        assert_eq!(
+            cx.update(|cx| secret_entries(cx)),
+            [SecretEntry {
+                scope: SecretScope::ZedAccount,
+                url: url.into(),
+                username: "1".into(),
+            }]
+        );
+        cx.update(|cx| read_secret(SecretScope::OpenAi, url, cx))
+            .await
+            .unwrap_err();
+
+        // Reading a secret that isn't in the keychain claims nothing.
+        let url = "https://api.openai.com/v1";
+        assert_eq!(
+            cx.update(|cx| read_secret(SecretScope::OpenAi, url, cx))
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(cx.update(|cx| secret_entries(cx)).len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_asking_for_keychain_access(cx: &mut gpui::TestAppContext) {
+        init_test(&[], cx);
+        let url = "https://api.openai.com/v1";
+
+        // There's no window to ask in.
+        cx.update(|cx| read_secret(SecretScope::OpenAi, url, cx))
+            .await
+            .unwrap_err();
+        assert!(!cx.has_pending_prompt());
+
+        // Accesses made while the user is asked share the answer.
+        cx.add_empty_window();
+        let read = cx.update(|cx| read_secret(SecretScope::OpenAi, url, cx));
+        let write = cx.update(|cx| write_secret(SecretScope::OpenAi, url, "Bearer", b"sk-123", cx));
+        cx.run_until_parked();
+        cx.simulate_prompt_answer(0);
+        read.await.unwrap();
+        write.await.unwrap();
+        assert!(!cx.has_pending_prompt());
+
+        // Denying access is remembered too.
+        let url = "https://api.github.com";
+        let read = cx.update(|cx| read_secret(SecretScope::GitHub, url, cx));
+        cx.run_until_parked();
+        cx.simulate_prompt_answer(1);
+        read.await.unwrap_err();
+        cx.update(|cx| write_secret(SecretScope::GitHub, url, "token", b"ghp_123", cx))
+            .await
+            .unwrap_err();
+        assert!(!cx.has_pending_prompt());
+        assert_eq!(cx.update(|cx| secret_entries(cx)).len(), 1);
+    }
+}
@@ -0,0 +1,357 @@
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, PromptLevel,
+    Render, Task, View, ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use secrets::{
+    read_secret, revoke_secret, secret_entries, secrets_loaded, write_secret, SecretEntry,
+    SecretScope,
+};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::{
+    github::{set_github_token, GITHUB_API_URL},
+    ResultExt,
+};
+use workspace::{ModalView, Workspace};
+
+actions!(secrets, [Manage, SetGitHubToken]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(SecretsManager::register).detach();
+    cx.observe_new_views(GitHubTokenPrompt::register).detach();
+    load_github_token(cx);
+}
+
+/// Authenticates GitHub API requests, such as the release lookups made when installing
+/// language servers, with the token stored in the keychain.
+fn load_github_token(cx: &mut AppContext) {
+    let loaded = secrets_loaded(cx);
+    cx.spawn(|cx| async move {
+        loaded.await;
+        // Avoid touching the keychain, which can prompt for access, unless a token was stored.
+        let Some(token) = cx.update(|cx| {
+            secret_entries(cx)
+                .iter()
+                .any(|entry| entry.scope == SecretScope::GitHub)
+                .then(|| read_secret(SecretScope::GitHub, GITHUB_API_URL, cx))
+        })?
+        else {
+            return Ok(());
+        };
+        if let Some((_, token)) = token.await? {
+            set_github_token(Some(String::from_utf8(token)?));
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Lists the secrets that Zed keeps in the keychain, and which feature stored each of
+/// them. Confirming revokes the selected secret, and a secondary confirmation revokes
+/// every secret stored by the same feature.
+pub struct SecretsManager {
+    picker: View<Picker<SecretsManagerDelegate>>,
+}
+
+impl SecretsManager {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Manage, cx| {
+            workspace.toggle_modal(cx, Self::new);
+        });
+    }
+
+    fn new(cx: &mut ViewContext<Self>) -> Self {
+        let delegate = SecretsManagerDelegate {
+            secrets_manager: cx.view().downgrade(),
+            entries: secret_entries(cx),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for SecretsManager {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for SecretsManager {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SecretsManager {}
+impl ModalView for SecretsManager {}
+
+pub struct SecretsManagerDelegate {
+    secrets_manager: WeakView<SecretsManager>,
+    entries: Vec<SecretEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+fn entry_label(entry: &SecretEntry) -> String {
+    format!("{}: {}", entry.scope.label(), entry.url)
+}
+
+impl PickerDelegate for SecretsManagerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Search secrets stored in the keychain...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| StringMatchCandidate::new(ix, entry_label(entry)))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(selected_entry) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.entries.get(mat.candidate_id))
+            .cloned()
+        else {
+            return;
+        };
+
+        let (entries_to_revoke, message) = if secondary {
+            let scope = selected_entry.scope;
+            (
+                self.entries
+                    .iter()
+                    .filter(|entry| entry.scope == scope)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                format!("Revoke all secrets stored by {}?", scope.label()),
+            )
+        } else {
+            let message = format!(
+                "Revoke the {} secret for {}?",
+                selected_entry.scope.label(),
+                selected_entry.url
+            );
+            (vec![selected_entry], message)
+        };
+
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            &message,
+            Some("The feature will ask for new credentials the next time it needs them."),
+            &["Revoke", "Cancel"],
+        );
+        cx.spawn(|this, mut cx| async move {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+            for entry in entries_to_revoke {
+                if entry.scope == SecretScope::GitHub {
+                    set_github_token(None);
+                }
+                cx.update(|cx| revoke_secret(&entry, cx))?.await?;
+            }
+            this.update(&mut cx, |this, cx| {
+                this.delegate.entries = secret_entries(cx);
+                this.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.secrets_manager
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(entry.username.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+}
+
+/// Prompts for a GitHub personal access token, which raises the rate limit of the GitHub
+/// API requests that Zed makes. The token doesn't need any scopes.
+pub struct GitHubTokenPrompt {
+    picker: View<Picker<GitHubTokenPromptDelegate>>,
+}
+
+impl GitHubTokenPrompt {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &SetGitHubToken, cx| {
+            workspace.toggle_modal(cx, Self::new);
+        });
+    }
+
+    fn new(cx: &mut ViewContext<Self>) -> Self {
+        let delegate = GitHubTokenPromptDelegate {
+            prompt: cx.view().downgrade(),
+            token: String::new(),
+        };
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for GitHubTokenPrompt {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for GitHubTokenPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for GitHubTokenPrompt {}
+impl ModalView for GitHubTokenPrompt {}
+
+pub struct GitHubTokenPromptDelegate {
+    prompt: WeakView<GitHubTokenPrompt>,
+    token: String,
+}
+
+impl PickerDelegate for GitHubTokenPromptDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Paste a GitHub personal access token...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        1
+    }
+
+    fn selected_index(&self) -> usize {
+        0
+    }
+
+    fn set_selected_index(&mut self, _: usize, _: &mut ViewContext<Picker<Self>>) {}
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        self.token = query.trim().to_string();
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if self.token.is_empty() {
+            return;
+        }
+        let token = self.token.clone();
+        let write = write_secret(
+            SecretScope::GitHub,
+            GITHUB_API_URL,
+            "token",
+            token.as_bytes(),
+            cx,
+        );
+        set_github_token(Some(token));
+        write.detach_and_log_err(cx);
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.prompt
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let label = if self.token.is_empty() {
+            "Paste a token to authenticate GitHub API requests"
+        } else {
+            "Store the token in the keychain"
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(Label::new(label)),
+        )
+    }
+}
@@ -277,6 +277,21 @@ impl Display for TerminalError {
     }
 }
 
+/// A command that a terminal runs in place of the user's shell, such as a test that a
+/// language server offers to run.
+#[derive(Clone, Debug)]
+pub struct SpawnTask {
+    /// The title of the terminal.
+    pub label: String,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+struct TaskState {
+    label: String,
+    finished: bool,
+}
+
 pub struct TerminalBuilder {
     terminal: Terminal,
     events_rx: UnboundedReceiver<AlacTermEvent>,
@@ -285,12 +300,22 @@ pub struct TerminalBuilder {
 impl TerminalBuilder {
     pub fn new(
         working_directory: Option<PathBuf>,
+        task: Option<SpawnTask>,
         shell: Shell,
         env: HashMap<String, String>,
         blink_settings: Option<TerminalBlink>,
         alternate_scroll: AlternateScroll,
         window: AnyWindowHandle,
     ) -> Result<TerminalBuilder> {
+        // The program of a task is run directly, so its arguments are passed as they are
+        // rather than being quoted for whichever shell the user has.
+        let shell = match &task {
+            Some(task) => Shell::WithArguments {
+                program: task.command.clone(),
+                args: task.args.clone(),
+            },
+            None => shell,
+        };
         let pty_options = {
             let alac_shell = match shell.clone() {
                 Shell::System => None,
@@ -402,6 +427,10 @@ impl TerminalBuilder {
             hovered_word: false,
             url_regex,
             word_regex,
+            task: task.map(|task| TaskState {
+                label: task.label,
+                finished: false,
+            }),
         };
 
         Ok(TerminalBuilder {
@@ -558,6 +587,7 @@ pub struct Terminal {
     hovered_word: bool,
     url_regex: RegexSearch,
     word_regex: RegexSearch,
+    task: Option<TaskState>,
 }
 
 impl Terminal {
@@ -589,7 +619,14 @@ impl Terminal {
             AlacTermEvent::Bell => {
                 cx.emit(Event::Bell);
             }
-            AlacTermEvent::Exit => cx.emit(Event::CloseTerminal),
+            AlacTermEvent::Exit => match &mut self.task {
+                // A task's output stays visible after it exits.
+                Some(task) => {
+                    task.finished = true;
+                    cx.emit(Event::TitleChanged);
+                }
+                None => cx.emit(Event::CloseTerminal),
+            },
             AlacTermEvent::MouseCursorDirty => {
                 //NOOP, Handled in render
             }
@@ -635,6 +672,13 @@ impl Terminal {
         }
     }
 
+    /// Returns whether a process other than the shell is running in the foreground, such
+    /// as a build or a server, which closing the terminal would kill.
+    pub fn has_running_process(&self) -> bool {
+        let pid = unsafe { libc::tcgetpgrp(self.shell_fd as i32) };
+        pid > 0 && pid as u32 != self.shell_pid
+    }
+
     fn get_cwd(&self) -> Option<PathBuf> {
         self.foreground_process_info
             .as_ref()
@@ -1307,6 +1351,19 @@ impl Terminal {
     }
 
     pub fn title(&self, truncate: bool) -> String {
+        if let Some(task) = &self.task {
+            let label = if truncate {
+                truncate_and_trailoff(&task.label, 25)
+            } else {
+                task.label.clone()
+            };
+            return if task.finished {
+                format!("{label} (finished)")
+            } else {
+                label
+            };
+        }
+
         self.foreground_process_info
             .as_ref()
             .map(|fpi| {
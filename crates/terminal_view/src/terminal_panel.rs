@@ -12,7 +12,10 @@ use project::{Fs, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use terminal::terminal_settings::{TerminalDockPosition, TerminalSettings};
+use terminal::{
+    terminal_settings::{TerminalDockPosition, TerminalSettings},
+    SpawnTask,
+};
 use ui::{h_flex, ButtonCommon, Clickable, IconButton, IconSize, Selectable, Tooltip};
 use util::{ResultExt, TryFutureExt};
 use workspace::{
@@ -34,6 +37,7 @@ pub fn init(cx: &mut AppContext) {
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(TerminalPanel::new_terminal);
             workspace.register_action(TerminalPanel::open_terminal);
+            workspace.register_action(TerminalPanel::spawn_in_terminal);
             workspace.register_action(|workspace, _: &ToggleFocus, cx| {
                 workspace.toggle_panel_focus::<TerminalPanel>(cx);
             });
@@ -75,7 +79,7 @@ impl TerminalPanel {
                             .icon_size(IconSize::Small)
                             .on_click(move |_, cx| {
                                 terminal_panel
-                                    .update(cx, |panel, cx| panel.add_terminal(None, cx))
+                                    .update(cx, |panel, cx| panel.add_terminal(None, None, cx))
                                     .log_err();
                             })
                             .tooltip(|cx| Tooltip::text("New Terminal", cx)),
@@ -266,7 +270,27 @@ impl TerminalPanel {
         };
 
         this.update(cx, |this, cx| {
-            this.add_terminal(Some(action.working_directory.clone()), cx)
+            this.add_terminal(Some(action.working_directory.clone()), None, cx)
+        })
+    }
+
+    /// Create a new Terminal and run the given command in it
+    fn spawn_in_terminal(
+        workspace: &mut Workspace,
+        action: &workspace::SpawnInTerminal,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(this) = workspace.focus_panel::<Self>(cx) else {
+            return;
+        };
+
+        let task = SpawnTask {
+            label: action.label.clone(),
+            command: action.command.clone(),
+            args: action.args.clone(),
+        };
+        this.update(cx, |this, cx| {
+            this.add_terminal(action.cwd.clone(), Some(task), cx)
         })
     }
 
@@ -280,10 +304,15 @@ impl TerminalPanel {
             return;
         };
 
-        this.update(cx, |this, cx| this.add_terminal(None, cx))
+        this.update(cx, |this, cx| this.add_terminal(None, None, cx))
     }
 
-    fn add_terminal(&mut self, working_directory: Option<PathBuf>, cx: &mut ViewContext<Self>) {
+    fn add_terminal(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        task: Option<SpawnTask>,
+        cx: &mut ViewContext<Self>,
+    ) {
         let workspace = self.workspace.clone();
         cx.spawn(|this, mut cx| async move {
             let pane = this.update(&mut cx, |this, _| this.pane.clone())?;
@@ -299,7 +328,7 @@ impl TerminalPanel {
                 let window = cx.window_handle();
                 if let Some(terminal) = workspace.project().update(cx, |project, cx| {
                     project
-                        .create_terminal(working_directory, window, cx)
+                        .create_terminal(working_directory, task, window, cx)
                         .log_err()
                 }) {
                     let terminal = Box::new(cx.new_view(|cx| {
@@ -451,7 +480,7 @@ impl Panel for TerminalPanel {
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
         if active && self.pane.read(cx).items_len() == 0 {
-            self.add_terminal(None, cx)
+            self.add_terminal(None, None, cx)
         }
     }
 
@@ -479,6 +508,10 @@ impl Panel for TerminalPanel {
     fn toggle_action(&self) -> Box<dyn gpui::Action> {
         Box::new(ToggleFocus)
     }
+
+    fn pane(&self) -> Option<View<Pane>> {
+        Some(self.pane.clone())
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -524,6 +524,101 @@ fn test_undo_redo() {
     assert_eq!(buffer.text(), "1234");
 }
 
+#[test]
+fn test_undo_branches() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+    let transaction_ids = |entries: &[HistoryEntry]| {
+        entries
+            .iter()
+            .map(|entry| entry.transaction_id())
+            .collect::<Vec<_>>()
+    };
+
+    buffer.edit([(0..0, "a")]);
+    buffer.edit([(1..1, "b")]);
+    buffer.edit([(2..2, "c")]);
+    let ids = transaction_ids(buffer.undo_stack());
+    let [a, b, c] = ids[..] else {
+        panic!("expected three transactions");
+    };
+
+    // Editing after undoing keeps the undone transactions in a branch.
+    buffer.undo();
+    buffer.undo();
+    buffer.edit([(1..1, "d")]);
+    assert_eq!(buffer.text(), "ad");
+    let d = buffer.undo_stack().last().unwrap().transaction_id();
+    assert!(buffer.redo_stack().is_empty());
+    assert_eq!(buffer.undo_branches().len(), 1);
+    assert_eq!(buffer.undo_branches()[0].parent, Some(a));
+    assert_eq!(transaction_ids(&buffer.undo_branches()[0].entries), [b, c]);
+
+    buffer.travel_to_transaction(Some(b));
+    assert_eq!(buffer.text(), "ab");
+    assert_eq!(transaction_ids(buffer.undo_stack()), [a, b]);
+    assert_eq!(transaction_ids(buffer.redo_stack()), [c]);
+    assert_eq!(buffer.undo_branches().len(), 1);
+    assert_eq!(transaction_ids(&buffer.undo_branches()[0].entries), [d]);
+
+    buffer.travel_to_transaction(Some(d));
+    assert_eq!(buffer.text(), "ad");
+    assert!(buffer.redo_stack().is_empty());
+    assert_eq!(buffer.undo_branches().len(), 1);
+    assert_eq!(transaction_ids(&buffer.undo_branches()[0].entries), [b, c]);
+
+    // An earlier transaction can be undone without the later ones.
+    buffer.undo_transaction(a);
+    assert_eq!(buffer.text(), "d");
+
+    buffer.travel_to_transaction(None);
+    assert_eq!(buffer.text(), "");
+}
+
+#[test]
+fn test_revert_transaction() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+
+    buffer.edit([(0..0, "a")]);
+    buffer.edit([(1..1, "b")]);
+    buffer.edit([(2..2, "c")]);
+    let a = buffer.undo_stack()[0].transaction_id();
+
+    // Reverting an earlier transaction keeps it in the history, and the revert can be undone.
+    buffer.revert_transaction(a).unwrap();
+    assert_eq!(buffer.text(), "bc");
+    assert_eq!(buffer.undo_stack().len(), 4);
+    assert_eq!(buffer.undo_stack()[0].transaction_id(), a);
+    assert!(buffer.redo_stack().is_empty());
+
+    buffer.undo();
+    assert_eq!(buffer.text(), "abc");
+    buffer.redo();
+    assert_eq!(buffer.text(), "bc");
+
+    buffer.travel_to_transaction(Some(a));
+    assert_eq!(buffer.text(), "a");
+}
+
+#[test]
+fn test_undo_branches_are_capped() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+
+    for ix in 0..MAX_UNDO_BRANCHES + 10 {
+        let len = buffer.len();
+        buffer.edit([(len..len, "a")]);
+        buffer.undo();
+        let len = buffer.len();
+        buffer.edit([(len..len, "b")]);
+        assert_eq!(
+            buffer.undo_branches().len(),
+            (ix + 1).min(MAX_UNDO_BRANCHES)
+        );
+    }
+}
+
 #[test]
 fn test_history() {
     let mut now = Instant::now();
@@ -50,6 +50,10 @@ lazy_static! {
 
 pub type TransactionId = clock::Lamport;
 
+/// The most branches of undone edits kept in the undo tree, after which the oldest
+/// ones are dropped.
+const MAX_UNDO_BRANCHES: usize = 1024;
+
 pub struct Buffer {
     snapshot: BufferSnapshot,
     history: History,
@@ -123,6 +127,30 @@ impl HistoryEntry {
     pub fn transaction_id(&self) -> TransactionId {
         self.transaction.id
     }
+
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
+
+    pub fn last_edit_at(&self) -> Instant {
+        self.last_edit_at
+    }
+}
+
+/// Transactions that were undone and then replaced by new edits. They're kept as a
+/// branch of the undo tree rather than discarded with the redo stack, so that they
+/// can be restored.
+pub struct UndoBranch {
+    /// The last transaction before the branch, or `None` if it starts at the base text.
+    pub parent: Option<TransactionId>,
+    /// The undone transactions, oldest first.
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl UndoBranch {
+    pub fn first_transaction_id(&self) -> TransactionId {
+        self.entries[0].transaction.id
+    }
 }
 
 struct History {
@@ -131,6 +159,7 @@ struct History {
     insertion_slices: HashMap<clock::Lamport, Vec<InsertionSlice>>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    branches: Vec<UndoBranch>,
     transaction_depth: usize,
     group_interval: Duration,
 }
@@ -149,6 +178,7 @@ impl History {
             insertion_slices: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            branches: Vec::new(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -202,7 +232,15 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                if !self.redo_stack.is_empty() {
+                    // Edits made after undoing aren't grouped with the edits before
+                    // them, which the undone ones branch off of.
+                    let parent = self.undo_stack.len().checked_sub(2).map(|ix| {
+                        self.undo_stack[ix].suppress_grouping = true;
+                        self.undo_stack[ix].transaction.id
+                    });
+                    self.branch_off_redo_stack(parent);
+                }
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -272,13 +310,50 @@ impl History {
 
     fn push_transaction(&mut self, transaction: Transaction, now: Instant) {
         assert_eq!(self.transaction_depth, 0);
+        let parent = self.undo_stack.last().map(|entry| entry.transaction.id);
+        self.branch_off_redo_stack(parent);
         self.undo_stack.push(HistoryEntry {
             transaction,
             first_edit_at: now,
             last_edit_at: now,
             suppress_grouping: false,
         });
-        self.redo_stack.clear();
+    }
+
+    /// Moves the undone transactions into a branch of the undo tree, because new edits
+    /// are replacing them after the given transaction.
+    fn branch_off_redo_stack(&mut self, parent: Option<TransactionId>) {
+        if !self.redo_stack.is_empty() {
+            let entries = self.redo_stack.drain(..).rev().collect();
+            self.branches.push(UndoBranch { parent, entries });
+            while self.branches.len() > MAX_UNDO_BRANCHES {
+                self.remove_branch(0);
+            }
+        }
+    }
+
+    /// Removes a branch of the undo tree, along with the branches that start in it,
+    /// since they can't be switched to without it.
+    fn remove_branch(&mut self, branch_ix: usize) {
+        let branch = self.branches.remove(branch_ix);
+        for entry in branch.entries {
+            while let Some(child_ix) = self
+                .branches
+                .iter()
+                .position(|branch| branch.parent == Some(entry.transaction.id))
+            {
+                self.remove_branch(child_ix);
+            }
+        }
+    }
+
+    fn branch_containing(&self, transaction_id: TransactionId) -> Option<&UndoBranch> {
+        self.branches.iter().find(|branch| {
+            branch
+                .entries
+                .iter()
+                .any(|entry| entry.transaction.id == transaction_id)
+        })
     }
 
     fn push_undo(&mut self, op_id: clock::Lamport) {
@@ -1162,6 +1237,21 @@ impl Buffer {
         self.history.redo_stack.last()
     }
 
+    /// The applied transactions, oldest first.
+    pub fn undo_stack(&self) -> &[HistoryEntry] {
+        &self.history.undo_stack
+    }
+
+    /// The undone transactions that can be redone, with the next one to redo last.
+    pub fn redo_stack(&self) -> &[HistoryEntry] {
+        &self.history.redo_stack
+    }
+
+    /// The branches of the undo tree besides the undo and redo stacks.
+    pub fn undo_branches(&self) -> &[UndoBranch] {
+        &self.history.branches
+    }
+
     pub fn start_transaction(&mut self) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now())
     }
@@ -1221,6 +1311,31 @@ impl Buffer {
         self.undo_or_redo(transaction).log_err()
     }
 
+    /// Reverts the edits of a transaction on the undo stack, keeping the ones made
+    /// after it. The revert is pushed as a transaction of its own, so it can be undone.
+    pub fn revert_transaction(&mut self, transaction_id: TransactionId) -> Option<Operation> {
+        let transaction = self
+            .history
+            .undo_stack
+            .iter()
+            .rfind(|entry| entry.transaction.id == transaction_id)?
+            .transaction
+            .clone();
+        let start = self.version();
+        let edit_ids = transaction.edit_ids.clone();
+        let operation = self.undo_or_redo(transaction).log_err()?;
+        let id = self.lamport_clock.tick();
+        self.push_transaction(
+            Transaction {
+                id,
+                edit_ids,
+                start,
+            },
+            Instant::now(),
+        );
+        Some(operation)
+    }
+
     pub fn undo_to_transaction(&mut self, transaction_id: TransactionId) -> Vec<Operation> {
         let transactions = self
             .history
@@ -1268,6 +1383,142 @@ impl Buffer {
             .collect()
     }
 
+    /// Undoes and redoes transactions until the given one is the last one applied,
+    /// switching to the branch of the undo tree that contains it if needed. `None`
+    /// undoes every transaction.
+    pub fn travel_to_transaction(
+        &mut self,
+        transaction_id: Option<TransactionId>,
+    ) -> Vec<Operation> {
+        let mut operations = Vec::new();
+        if let Some(transaction_id) = transaction_id {
+            if let Some(branch) = self.history.branch_containing(transaction_id) {
+                let first_transaction_id = branch.first_transaction_id();
+                operations.extend(self.switch_to_undo_branch(first_transaction_id));
+            }
+            if self
+                .history
+                .redo_stack
+                .iter()
+                .any(|entry| entry.transaction.id == transaction_id)
+            {
+                operations.extend(self.redo_to_transaction(transaction_id));
+                return operations;
+            }
+        }
+
+        let next_ix = match transaction_id {
+            Some(transaction_id) => {
+                let Some(ix) = self
+                    .history
+                    .undo_stack
+                    .iter()
+                    .position(|entry| entry.transaction.id == transaction_id)
+                else {
+                    return operations;
+                };
+                ix + 1
+            }
+            None => 0,
+        };
+        if let Some(next) = self.history.undo_stack.get(next_ix) {
+            let next_transaction_id = next.transaction.id;
+            operations.extend(self.undo_to_transaction(next_transaction_id));
+        }
+        operations
+    }
+
+    /// Undoes the transactions after the start of the given branch of the undo tree,
+    /// and redoes the branch's transactions instead. The undone transactions become a
+    /// branch of their own, so that no edits are lost.
+    pub fn switch_to_undo_branch(&mut self, first_transaction_id: TransactionId) -> Vec<Operation> {
+        // A branch can start in another branch, which has to be switched to first.
+        let mut path = vec![first_transaction_id];
+        let mut parent = self
+            .history
+            .branches
+            .iter()
+            .find(|branch| branch.first_transaction_id() == first_transaction_id)
+            .and_then(|branch| branch.parent);
+        while let Some(parent_branch) =
+            parent.and_then(|parent| self.history.branch_containing(parent))
+        {
+            path.push(parent_branch.first_transaction_id());
+            parent = parent_branch.parent;
+        }
+
+        let mut operations = Vec::new();
+        for first_transaction_id in path.into_iter().rev() {
+            operations.extend(self.switch_to_adjacent_undo_branch(first_transaction_id));
+        }
+        operations
+    }
+
+    /// Switches to a branch that starts in the undo or redo stack.
+    fn switch_to_adjacent_undo_branch(
+        &mut self,
+        first_transaction_id: TransactionId,
+    ) -> Vec<Operation> {
+        let Some(branch_ix) = self
+            .history
+            .branches
+            .iter()
+            .position(|branch| branch.first_transaction_id() == first_transaction_id)
+        else {
+            return Vec::new();
+        };
+        let parent = self.history.branches[branch_ix].parent;
+
+        let mut operations = Vec::new();
+        if let Some(parent) = parent {
+            if self
+                .history
+                .redo_stack
+                .iter()
+                .any(|entry| entry.transaction.id == parent)
+            {
+                operations.extend(self.redo_to_transaction(parent));
+            }
+        }
+        let start_ix = match parent {
+            Some(parent) => {
+                let Some(ix) = self
+                    .history
+                    .undo_stack
+                    .iter()
+                    .position(|entry| entry.transaction.id == parent)
+                else {
+                    return operations;
+                };
+                ix + 1
+            }
+            None => 0,
+        };
+
+        let mut replaced_entries = self
+            .history
+            .undo_stack
+            .drain(start_ix..)
+            .collect::<Vec<_>>();
+        for entry in replaced_entries.iter().rev() {
+            operations.push(self.undo_or_redo(entry.transaction.clone()).unwrap());
+        }
+        replaced_entries.extend(self.history.redo_stack.drain(..).rev());
+
+        let branch = self.history.branches.remove(branch_ix);
+        if !replaced_entries.is_empty() {
+            self.history.branches.push(UndoBranch {
+                parent,
+                entries: replaced_entries,
+            });
+        }
+        for entry in branch.entries {
+            operations.push(self.undo_or_redo(entry.transaction.clone()).unwrap());
+            self.history.undo_stack.push(entry);
+        }
+        operations
+    }
+
     fn undo_or_redo(&mut self, transaction: Transaction) -> Result<Operation> {
         let mut counts = HashMap::default();
         for edit_id in transaction.edit_ids {
@@ -31,6 +31,15 @@ pub struct ThemeSettings {
     pub theme_selection: Option<ThemeSelection>,
     pub active_theme: Arc<Theme>,
     pub theme_overrides: Option<ThemeStyleContent>,
+    pub presentation_mode: PresentationModeSettings,
+}
+
+/// The sizes that replace the configured ones while presentation mode is on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PresentationModeSettings {
+    pub buffer_font_size: Pixels,
+    pub ui_font_size: Pixels,
+    pub buffer_line_height: BufferLineHeight,
 }
 
 impl ThemeSettings {
@@ -96,6 +105,12 @@ pub(crate) struct AdjustedBufferFontSize(Pixels);
 
 impl Global for AdjustedBufferFontSize {}
 
+/// Present while presentation mode is on, with the sizes of the project that it was
+/// turned on for.
+pub(crate) struct PresentationMode(PresentationModeSettings);
+
+impl Global for PresentationMode {}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum ThemeSelection {
@@ -168,6 +183,30 @@ pub struct ThemeSettingsContent {
     /// These values will override the ones on the current theme specified in `theme`.
     #[serde(rename = "experimental.theme_overrides", default)]
     pub theme_overrides: Option<ThemeStyleContent>,
+
+    /// The sizes to use in presentation mode, such as when screen sharing. A project can
+    /// set its own in its local settings.
+    #[serde(default)]
+    pub presentation_mode: Option<PresentationModeContent>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PresentationModeContent {
+    /// The font size for text in the editor and terminal.
+    ///
+    /// Default: 24
+    #[serde(default)]
+    pub buffer_font_size: Option<f32>,
+    /// The font size for text in the UI.
+    ///
+    /// Default: 20
+    #[serde(default)]
+    pub ui_font_size: Option<f32>,
+    /// The line height of text in the editor.
+    ///
+    /// Default: comfortable
+    #[serde(default)]
+    pub buffer_line_height: Option<BufferLineHeight>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema, Default)]
@@ -192,10 +231,28 @@ impl BufferLineHeight {
 impl ThemeSettings {
     pub fn buffer_font_size(&self, cx: &AppContext) -> Pixels {
         cx.try_global::<AdjustedBufferFontSize>()
-            .map_or(self.buffer_font_size, |size| size.0)
+            .map_or(self.unadjusted_buffer_font_size(cx), |size| size.0)
             .max(MIN_FONT_SIZE)
     }
 
+    /// Returns the buffer font size before it's increased or decreased with the font size
+    /// actions, which is the one of presentation mode while it's on.
+    fn unadjusted_buffer_font_size(&self, cx: &AppContext) -> Pixels {
+        cx.try_global::<PresentationMode>()
+            .map_or(self.buffer_font_size, |mode| mode.0.buffer_font_size)
+    }
+
+    pub fn ui_font_size(&self, cx: &AppContext) -> Pixels {
+        cx.try_global::<PresentationMode>()
+            .map_or(self.ui_font_size, |mode| mode.0.ui_font_size)
+            .max(MIN_FONT_SIZE)
+    }
+
+    pub fn buffer_line_height(&self, cx: &AppContext) -> BufferLineHeight {
+        cx.try_global::<PresentationMode>()
+            .map_or(self.buffer_line_height, |mode| mode.0.buffer_line_height)
+    }
+
     pub fn line_height(&self) -> f32 {
         f32::max(self.buffer_line_height.value(), MIN_LINE_HEIGHT)
     }
@@ -256,7 +313,7 @@ pub fn observe_buffer_font_size_adjustment<V: 'static>(
 
 pub fn adjusted_font_size(size: Pixels, cx: &mut AppContext) -> Pixels {
     if let Some(AdjustedBufferFontSize(adjusted_size)) = cx.try_global::<AdjustedBufferFontSize>() {
-        let buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
+        let buffer_font_size = ThemeSettings::get_global(cx).unadjusted_buffer_font_size(cx);
         let delta = *adjusted_size - buffer_font_size;
         size + delta
     } else {
@@ -266,7 +323,7 @@ pub fn adjusted_font_size(size: Pixels, cx: &mut AppContext) -> Pixels {
 }
 
 pub fn adjust_font_size(cx: &mut AppContext, f: fn(&mut Pixels)) {
-    let buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
+    let buffer_font_size = ThemeSettings::get_global(cx).unadjusted_buffer_font_size(cx);
     let mut adjusted_size = cx
         .try_global::<AdjustedBufferFontSize>()
         .map_or(buffer_font_size, |adjusted_size| adjusted_size.0);
@@ -284,6 +341,24 @@ pub fn reset_font_size(cx: &mut AppContext) {
     }
 }
 
+pub fn is_presentation_mode(cx: &AppContext) -> bool {
+    cx.has_global::<PresentationMode>()
+}
+
+/// Turns presentation mode on with the given sizes, or off if it's on, restoring the
+/// configured sizes. Font size adjustments are discarded either way.
+pub fn toggle_presentation_mode(settings: PresentationModeSettings, cx: &mut AppContext) {
+    if cx.has_global::<AdjustedBufferFontSize>() {
+        cx.remove_global::<AdjustedBufferFontSize>();
+    }
+    if cx.has_global::<PresentationMode>() {
+        cx.remove_global::<PresentationMode>();
+    } else {
+        cx.set_global(PresentationMode(settings));
+    }
+    cx.refresh();
+}
+
 impl settings::Settings for ThemeSettings {
     const KEY: Option<&'static str> = None;
 
@@ -319,6 +394,14 @@ impl settings::Settings for ThemeSettings {
                 .or(themes.get(&one_dark().name))
                 .unwrap(),
             theme_overrides: None,
+            presentation_mode: {
+                let defaults = defaults.presentation_mode.unwrap();
+                PresentationModeSettings {
+                    buffer_font_size: defaults.buffer_font_size.unwrap().into(),
+                    ui_font_size: defaults.ui_font_size.unwrap().into(),
+                    buffer_line_height: defaults.buffer_line_height.unwrap(),
+                }
+            },
         };
 
         for value in user_values.into_iter().copied().cloned() {
@@ -355,6 +438,22 @@ impl settings::Settings for ThemeSettings {
                 value.buffer_font_size.map(Into::into),
             );
             merge(&mut this.buffer_line_height, value.buffer_line_height);
+
+            if let Some(value) = value.presentation_mode {
+                let presentation_mode = &mut this.presentation_mode;
+                merge(
+                    &mut presentation_mode.buffer_font_size,
+                    value.buffer_font_size.map(Into::into),
+                );
+                merge(
+                    &mut presentation_mode.ui_font_size,
+                    value.ui_font_size.map(Into::into),
+                );
+                merge(
+                    &mut presentation_mode.buffer_line_height,
+                    value.buffer_line_height,
+                );
+            }
         }
 
         Ok(this)
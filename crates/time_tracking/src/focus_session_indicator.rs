@@ -0,0 +1,164 @@
+use editor::{Editor, EditorEvent};
+use gpui::{div, Model, Render, Subscription, Task, View, ViewContext, WeakView};
+use settings::{Settings, SettingsStore};
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+use ui::{prelude::*, Button, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView, Toast, Workspace};
+
+use crate::{Event, FocusPhase, Heartbeat, TimeTracker, TimeTrackingSettings, ToggleFocusSession};
+
+const FOCUS_SESSION_TOAST_ID: usize = 0x4f0c5e55;
+
+/// Shows the time left in the focus session in the status bar, and records the
+/// heartbeats of the workspace's active editor.
+pub struct FocusSessionIndicator {
+    tracker: Model<TimeTracker>,
+    workspace: WeakView<Workspace>,
+    tick_task: Option<Task<()>>,
+    _active_editor_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FocusSessionIndicator {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let tracker = TimeTracker::global(cx);
+        let _subscriptions = vec![
+            cx.observe(&tracker, |this, _, cx| this.update_tick_task(cx)),
+            cx.subscribe(&tracker, Self::handle_tracker_event),
+            cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        ];
+        let mut this = Self {
+            tracker,
+            workspace: workspace.weak_handle(),
+            tick_task: None,
+            _active_editor_subscription: None,
+            _subscriptions,
+        };
+        this.update_tick_task(cx);
+        this
+    }
+
+    /// Re-renders the indicator every second while a focus session runs, to count
+    /// down its time.
+    fn update_tick_task(&mut self, cx: &mut ViewContext<Self>) {
+        if self.tracker.read(cx).focus_session().is_none() {
+            self.tick_task = None;
+        } else if self.tick_task.is_none() {
+            self.tick_task = Some(cx.spawn(|this, mut cx| async move {
+                loop {
+                    cx.background_executor().timer(Duration::from_secs(1)).await;
+                    if this.update(&mut cx, |_, cx| cx.notify()).is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+        cx.notify();
+    }
+
+    fn handle_tracker_event(
+        &mut self,
+        _: Model<TimeTracker>,
+        event: &Event,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Event::FocusPhaseEnded(phase) = event;
+        let message = match phase {
+            FocusPhase::Focus => "Focus session finished. Time for a break!",
+            FocusPhase::Break => "Break finished.",
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(Toast::new(FOCUS_SESSION_TOAST_ID, message), cx)
+            })
+            .ok();
+    }
+
+    fn record_heartbeat(
+        &mut self,
+        editor: View<Editor>,
+        is_write: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let Some(file) = buffer.file() else {
+            return;
+        };
+        // The full path of a file starts with the name of its worktree.
+        let full_path = file.full_path(cx);
+        let Some(project) = full_path.components().next() else {
+            return;
+        };
+        let heartbeat = Heartbeat {
+            project: project.as_os_str().to_string_lossy().into_owned(),
+            path: file
+                .as_local()
+                .map_or_else(|| full_path.clone(), |file| file.abs_path(cx)),
+            language: buffer
+                .language()
+                .map(|language| language.name().to_string()),
+            time: OffsetDateTime::now_utc(),
+            is_write,
+        };
+        self.tracker
+            .update(cx, |tracker, cx| tracker.record_heartbeat(heartbeat, cx));
+    }
+}
+
+impl Render for FocusSessionIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        if !TimeTrackingSettings::get_global(cx).enabled {
+            return div().into_any_element();
+        }
+
+        let (label, tooltip) = match self.tracker.read(cx).focus_session() {
+            Some(session) => {
+                let remaining = session
+                    .ends_at
+                    .saturating_duration_since(Instant::now())
+                    .as_secs();
+                let phase = match session.phase {
+                    FocusPhase::Focus => "Focus",
+                    FocusPhase::Break => "Break",
+                };
+                (
+                    format!("{phase} {}:{:02}", remaining / 60, remaining % 60),
+                    "Stop Focus Session",
+                )
+            }
+            None => ("Focus".to_string(), "Start Focus Session"),
+        };
+        Button::new("focus-session", label)
+            .label_size(LabelSize::Small)
+            .on_click(|_, cx| cx.dispatch_action(Box::new(ToggleFocusSession)))
+            .tooltip(move |cx| Tooltip::text(tooltip, cx))
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for FocusSessionIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self._active_editor_subscription = Some(cx.subscribe(
+                &editor,
+                |this, editor, event: &EditorEvent, cx| match event {
+                    EditorEvent::Edited => this.record_heartbeat(editor, false, cx),
+                    EditorEvent::Saved => this.record_heartbeat(editor, true, cx),
+                    _ => {}
+                },
+            ));
+            // Switching to a file counts as activity in it.
+            self.record_heartbeat(editor, false, cx);
+        } else {
+            self._active_editor_subscription = None;
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, fmt::Write, path::PathBuf};
+use time::{Duration, OffsetDateTime};
+
+/// A sign of activity in a file, sent when it's edited or saved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heartbeat {
+    /// The name of the worktree that the file is in.
+    pub project: String,
+    pub path: PathBuf,
+    pub language: Option<String>,
+    pub time: OffsetDateTime,
+    /// Whether the file was saved.
+    pub is_write: bool,
+}
+
+/// A period of continuous activity in a project.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeSpan {
+    pub project: String,
+    pub language: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub end: OffsetDateTime,
+}
+
+impl TimeSpan {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// The time spent in each project, built from heartbeats.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeLog {
+    pub spans: Vec<TimeSpan>,
+}
+
+impl TimeLog {
+    /// Adds a heartbeat to the log. The time since the previous heartbeat counts toward
+    /// the previous heartbeat's project, unless it's longer than the idle timeout, in
+    /// which case the time in between isn't counted.
+    pub fn record(&mut self, heartbeat: &Heartbeat, idle_timeout: Duration) {
+        if let Some(span) = self.spans.last_mut() {
+            let idle_time = heartbeat.time - span.end;
+            if idle_time.is_negative() || idle_time > idle_timeout {
+                // Start a new span below.
+            } else if span.project == heartbeat.project && span.language == heartbeat.language {
+                span.end = heartbeat.time;
+                return;
+            } else {
+                span.end = heartbeat.time;
+            }
+        }
+
+        self.spans.push(TimeSpan {
+            project: heartbeat.project.clone(),
+            language: heartbeat.language.clone(),
+            start: heartbeat.time,
+            end: heartbeat.time,
+        });
+    }
+
+    /// Returns the time spent in each project since the given time, longest first.
+    pub fn project_totals(&self, since: OffsetDateTime) -> Vec<(&str, Duration)> {
+        let mut totals = Vec::<(&str, Duration)>::new();
+        for span in &self.spans {
+            if span.end <= since {
+                continue;
+            }
+            let duration = span.end - span.start.max(since);
+            match totals
+                .iter_mut()
+                .find(|(project, _)| *project == span.project)
+            {
+                Some((_, total)) => *total += duration,
+                None => totals.push((&span.project, duration)),
+            }
+        }
+        totals.retain(|(_, duration)| duration.is_positive());
+        totals.sort_by_key(|(project, duration)| (Reverse(*duration), *project));
+        totals
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("project,language,start,end,seconds\n");
+        for span in &self.spans {
+            let format = time::format_description::well_known::Rfc3339;
+            writeln!(
+                csv,
+                "{},{},{},{},{}",
+                csv_field(&span.project),
+                csv_field(span.language.as_deref().unwrap_or_default()),
+                span.start.format(&format).unwrap_or_default(),
+                span.end.format(&format).unwrap_or_default(),
+                span.duration().whole_seconds(),
+            )
+            .unwrap();
+        }
+        csv
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Formats a duration as hours and minutes, such as `1h 05m`.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.whole_minutes();
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(project: &str, language: &str, minute: i64) -> Heartbeat {
+        Heartbeat {
+            project: project.to_string(),
+            path: PathBuf::from("/a"),
+            language: Some(language.to_string()),
+            time: OffsetDateTime::UNIX_EPOCH + Duration::minutes(minute),
+            is_write: false,
+        }
+    }
+
+    #[test]
+    fn test_record_heartbeats() {
+        let mut log = TimeLog::default();
+        let idle_timeout = Duration::minutes(15);
+        for heartbeat in [
+            heartbeat("zed", "Rust", 0),
+            heartbeat("zed", "Rust", 10),
+            heartbeat("zed", "TOML", 12),
+            heartbeat("blog", "Markdown", 20),
+            // Idle for longer than the timeout.
+            heartbeat("blog", "Markdown", 60),
+            heartbeat("blog", "Markdown", 65),
+        ] {
+            log.record(&heartbeat, idle_timeout);
+        }
+
+        assert_eq!(
+            log.spans
+                .iter()
+                .map(|span| (span.project.as_str(), span.duration().whole_minutes()))
+                .collect::<Vec<_>>(),
+            [("zed", 12), ("zed", 8), ("blog", 0), ("blog", 5)]
+        );
+        assert_eq!(
+            log.project_totals(OffsetDateTime::UNIX_EPOCH),
+            [
+                ("zed", Duration::minutes(20)),
+                ("blog", Duration::minutes(5))
+            ]
+        );
+        assert_eq!(
+            log.project_totals(OffsetDateTime::UNIX_EPOCH + Duration::minutes(15)),
+            [
+                ("blog", Duration::minutes(5)),
+                ("zed", Duration::minutes(5))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut log = TimeLog::default();
+        log.record(&heartbeat("acme, inc", "Rust", 0), Duration::minutes(15));
+        log.record(&heartbeat("acme, inc", "Rust", 2), Duration::minutes(15));
+        assert_eq!(
+            log.to_csv(),
+            "project,language,start,end,seconds\n\
+             \"acme, inc\",Rust,1970-01-01T00:00:00Z,1970-01-01T00:02:00Z,120\n"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::seconds(59)), "0m");
+        assert_eq!(format_duration(Duration::minutes(42)), "42m");
+        assert_eq!(format_duration(Duration::minutes(65)), "1h 05m");
+    }
+}
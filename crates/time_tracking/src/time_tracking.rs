@@ -0,0 +1,557 @@
+mod focus_session_indicator;
+mod time_log;
+
+use anyhow::{anyhow, Context as _, Result};
+use editor::{Editor, MultiBuffer};
+use fs::{Fs, RenameOptions};
+use futures::Future;
+use gpui::{
+    actions, AppContext, Context, EventEmitter, Global, Model, ModelContext, Subscription, Task,
+    ViewContext, VisualContext,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+use time::{Duration, OffsetDateTime, UtcOffset};
+use util::{
+    audit_log::{self, AuditEvent, AuditEventKind},
+    http::HttpClient,
+    paths, ResultExt,
+};
+use workspace::Workspace;
+
+pub use focus_session_indicator::FocusSessionIndicator;
+pub use time_log::{format_duration, Heartbeat, TimeLog, TimeSpan};
+
+actions!(
+    time_tracking,
+    [ToggleFocusSession, ShowTimeSummary, ExportTimeLog]
+);
+
+/// The shortest interval between the heartbeats sent for the same file, unless it's
+/// saved, like WakaTime's editor plugins.
+const HEARTBEAT_INTERVAL: Duration = Duration::minutes(2);
+const SAVE_DEBOUNCE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TimeTrackingSettings {
+    pub enabled: bool,
+    pub idle_timeout_minutes: u32,
+    pub focus_minutes: u32,
+    pub break_minutes: u32,
+    pub heartbeat_url: Option<String>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct TimeTrackingSettingsContent {
+    /// Whether to record the time spent in each project and show the focus session
+    /// timer in the status bar. The log is only stored on this machine.
+    ///
+    /// Default: false
+    enabled: Option<bool>,
+    /// How long to go without editing or saving a file before the time stops counting.
+    ///
+    /// Default: 15
+    idle_timeout_minutes: Option<u32>,
+    /// The length of a focus session.
+    ///
+    /// Default: 25
+    focus_minutes: Option<u32>,
+    /// The length of the break after a focus session.
+    ///
+    /// Default: 5
+    break_minutes: Option<u32>,
+    /// The URL that heartbeats are posted to as JSON, for external time trackers.
+    /// Heartbeats have the same fields as WakaTime's: `entity`, `type`, `time`,
+    /// `project`, `language` and `is_write`.
+    ///
+    /// Default: null
+    heartbeat_url: Option<String>,
+}
+
+impl Settings for TimeTrackingSettings {
+    const KEY: Option<&'static str> = Some("time_tracking");
+
+    type FileContent = TimeTrackingSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+struct GlobalTimeTracker(Model<TimeTracker>);
+
+impl Global for GlobalTimeTracker {}
+
+pub fn init(fs: Arc<dyn Fs>, http_client: Arc<dyn HttpClient>, cx: &mut AppContext) {
+    TimeTrackingSettings::register(cx);
+
+    let tracker = cx.new_model(|cx| TimeTracker::new(fs, http_client, paths::TIME_LOG.clone(), cx));
+    cx.set_global(GlobalTimeTracker(tracker));
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|_, _: &ToggleFocusSession, cx| {
+                TimeTracker::global(cx).update(cx, |tracker, cx| tracker.toggle_focus_session(cx));
+            })
+            .register_action(show_time_summary)
+            .register_action(export_time_log);
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusPhase {
+    Focus,
+    Break,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FocusSession {
+    pub phase: FocusPhase,
+    pub ends_at: Instant,
+}
+
+pub enum Event {
+    FocusPhaseEnded(FocusPhase),
+}
+
+/// Records the heartbeats of every window into the time log, and runs the focus
+/// sessions, which are shared between windows.
+pub struct TimeTracker {
+    log: TimeLog,
+    log_path: PathBuf,
+    /// Whether the log that was saved before has been loaded, and so can be saved over.
+    is_loaded: bool,
+    fs: Arc<dyn Fs>,
+    http_client: Arc<dyn HttpClient>,
+    last_sent_heartbeat: Option<Heartbeat>,
+    focus_session: Option<FocusSession>,
+    focus_timer: Option<Task<()>>,
+    save_task: Option<Task<()>>,
+    _quit_subscription: Subscription,
+}
+
+impl EventEmitter<Event> for TimeTracker {}
+
+impl TimeTracker {
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalTimeTracker>().0.clone()
+    }
+
+    fn new(
+        fs: Arc<dyn Fs>,
+        http_client: Arc<dyn HttpClient>,
+        log_path: PathBuf,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let load_fs = fs.clone();
+        let load_path = log_path.clone();
+        cx.spawn(|this, mut cx| async move {
+            let loaded_log = load_time_log(load_fs.as_ref(), &load_path).await?;
+            this.update(&mut cx, |this, cx| {
+                let recorded_spans = std::mem::replace(&mut this.log.spans, loaded_log.spans);
+                this.log.spans.extend(recorded_spans);
+                this.is_loaded = true;
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+
+        Self {
+            log: TimeLog::default(),
+            log_path,
+            is_loaded: false,
+            fs,
+            http_client,
+            last_sent_heartbeat: None,
+            focus_session: None,
+            focus_timer: None,
+            save_task: None,
+            _quit_subscription: cx.on_app_quit(Self::save),
+        }
+    }
+
+    pub fn log(&self) -> &TimeLog {
+        &self.log
+    }
+
+    pub fn focus_session(&self) -> Option<FocusSession> {
+        self.focus_session
+    }
+
+    pub fn record_heartbeat(&mut self, heartbeat: Heartbeat, cx: &mut ModelContext<Self>) {
+        let settings = TimeTrackingSettings::get_global(cx);
+        if !settings.enabled {
+            return;
+        }
+        let idle_timeout = Duration::minutes(settings.idle_timeout_minutes.into());
+        self.log.record(&heartbeat, idle_timeout);
+        if let Some(url) = settings.heartbeat_url.clone() {
+            self.send_heartbeat(url, heartbeat, cx);
+        }
+
+        self.save_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(SAVE_DEBOUNCE_TIMEOUT).await;
+            if let Ok(save) = this.update(&mut cx, |this, cx| this.save(cx)) {
+                save.await;
+            }
+        }));
+        cx.notify();
+    }
+
+    fn send_heartbeat(&mut self, url: String, heartbeat: Heartbeat, cx: &mut ModelContext<Self>) {
+        let is_throttled = self.last_sent_heartbeat.as_ref().map_or(false, |last| {
+            !heartbeat.is_write
+                && last.path == heartbeat.path
+                && heartbeat.time - last.time < HEARTBEAT_INTERVAL
+        });
+        if is_throttled {
+            return;
+        }
+
+        let body = serde_json::json!({
+            "entity": heartbeat.path,
+            "type": "file",
+            "time": heartbeat.time.unix_timestamp(),
+            "project": heartbeat.project,
+            "language": heartbeat.language,
+            "is_write": heartbeat.is_write,
+            "plugin": "zed",
+        })
+        .to_string();
+        audit_log::record(
+            AuditEvent::new(
+                AuditEventKind::Request,
+                "time tracking",
+                format!("Heartbeat to {url}"),
+            )
+            .content(&body),
+        );
+        self.last_sent_heartbeat = Some(heartbeat);
+        let http_client = self.http_client.clone();
+        cx.background_executor()
+            .spawn(async move {
+                let response = http_client.post_json(&url, body.into()).await?;
+                if !response.status().is_success() {
+                    return Err(anyhow!(
+                        "heartbeat endpoint responded with status {}",
+                        response.status()
+                    ));
+                }
+                Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn save(&mut self, _: &mut ModelContext<Self>) -> impl Future<Output = ()> {
+        let fs = self.fs.clone();
+        let path = self.log_path.clone();
+        let is_loaded = self.is_loaded;
+        let json = serde_json::to_string(&self.log);
+        async move {
+            // Saving before the log is loaded would replace the time recorded before.
+            if !is_loaded {
+                log::error!("not saving the time log, as {path:?} wasn't loaded");
+                return;
+            }
+            if let Some(json) = json.log_err() {
+                fs.atomic_write(path, json).await.log_err();
+            }
+        }
+    }
+
+    /// Starts a focus session, or stops the current one, including its break.
+    pub fn toggle_focus_session(&mut self, cx: &mut ModelContext<Self>) {
+        if self.focus_session.take().is_some() {
+            self.focus_timer = None;
+        } else {
+            self.start_focus_phase(FocusPhase::Focus, cx);
+        }
+        cx.notify();
+    }
+
+    fn start_focus_phase(&mut self, phase: FocusPhase, cx: &mut ModelContext<Self>) {
+        let settings = TimeTrackingSettings::get_global(cx);
+        let minutes = match phase {
+            FocusPhase::Focus => settings.focus_minutes,
+            FocusPhase::Break => settings.break_minutes,
+        };
+        let duration = std::time::Duration::from_secs(u64::from(minutes) * 60);
+        self.focus_session = Some(FocusSession {
+            phase,
+            ends_at: Instant::now() + duration,
+        });
+        self.focus_timer = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(duration).await;
+            this.update(&mut cx, |this, cx| {
+                cx.emit(Event::FocusPhaseEnded(phase));
+                match phase {
+                    FocusPhase::Focus => this.start_focus_phase(FocusPhase::Break, cx),
+                    FocusPhase::Break => this.focus_session = None,
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+}
+
+/// Loads the time log at the given path. A log that can't be parsed is moved aside, so
+/// that it isn't saved over, and an error is returned if that fails.
+async fn load_time_log(fs: &dyn Fs, path: &Path) -> Result<TimeLog> {
+    if fs.metadata(path).await?.is_none() {
+        return Ok(TimeLog::default());
+    }
+    let text = fs.load(path).await?;
+    match serde_json::from_str(&text) {
+        Ok(log) => Ok(log),
+        Err(error) => {
+            let invalid_path = path.with_extension("json.invalid");
+            fs.rename(
+                path,
+                &invalid_path,
+                RenameOptions {
+                    overwrite: true,
+                    ignore_if_exists: false,
+                },
+            )
+            .await
+            .with_context(|| format!("failed to move the invalid time log {path:?} aside"))?;
+            log::error!("moved the invalid time log to {invalid_path:?}: {error}");
+            Ok(TimeLog::default())
+        }
+    }
+}
+
+fn show_time_summary(
+    workspace: &mut Workspace,
+    _: &ShowTimeSummary,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let summary = time_summary(
+        TimeTracker::global(cx).read(cx).log(),
+        OffsetDateTime::now_utc(),
+        UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+    );
+    let markdown = workspace
+        .app_state()
+        .languages
+        .language_for_name("Markdown");
+    cx.spawn(|workspace, mut cx| async move {
+        let markdown = markdown.await.log_err();
+        workspace.update(&mut cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let Some(buffer) = project
+                .update(cx, |project, cx| {
+                    project.create_buffer(&summary, markdown, cx)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            let buffer = cx.new_model(|cx| {
+                MultiBuffer::singleton(buffer, cx).with_title("Time Summary".into())
+            });
+            let editor =
+                cx.new_view(|cx| Editor::for_multibuffer(buffer, Some(project.clone()), cx));
+            workspace.add_item(Box::new(editor), cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Summarizes the time spent in each project today, in the last seven days and in
+/// total, as Markdown tables.
+fn time_summary(log: &TimeLog, now: OffsetDateTime, local_offset: UtcOffset) -> String {
+    let today = now
+        .to_offset(local_offset)
+        .replace_time(time::Time::MIDNIGHT);
+    let periods = [
+        ("Today", today),
+        ("Last 7 Days", today - Duration::days(6)),
+        ("All Time", OffsetDateTime::UNIX_EPOCH),
+    ];
+
+    let mut summary = String::from("# Time Summary\n");
+    for (title, since) in periods {
+        write!(summary, "\n## {title}\n\n").unwrap();
+        let totals = log.project_totals(since);
+        if totals.is_empty() {
+            summary.push_str("No time recorded.\n");
+            continue;
+        }
+        summary.push_str("| Project | Time |\n| --- | --- |\n");
+        for (project, duration) in totals {
+            writeln!(summary, "| {project} | {} |", format_duration(duration)).unwrap();
+        }
+    }
+    summary
+}
+
+fn export_time_log(workspace: &mut Workspace, _: &ExportTimeLog, cx: &mut ViewContext<Workspace>) {
+    let csv = TimeTracker::global(cx).read(cx).log().to_csv();
+    let fs = workspace.app_state().fs.clone();
+    let path = cx.prompt_for_new_path(&paths::HOME);
+    cx.spawn(|_, _| async move {
+        let Some(path) = path.await? else {
+            return Ok(());
+        };
+        fs.atomic_write(path, csv).await
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use time::{Date, Month};
+    use util::http::FakeHttpClient;
+
+    fn datetime(day: u8, hour: u8, minute: u8) -> OffsetDateTime {
+        Date::from_calendar_date(2024, Month::January, day)
+            .unwrap()
+            .with_hms(hour, minute, 0)
+            .unwrap()
+            .assume_utc()
+    }
+
+    #[test]
+    fn test_time_summary() {
+        let mut log = TimeLog::default();
+        for (project, time) in [
+            ("blog", datetime(1, 10, 0)),
+            ("blog", datetime(1, 10, 30)),
+            ("zed", datetime(8, 9, 0)),
+            ("zed", datetime(8, 10, 5)),
+        ] {
+            let heartbeat = Heartbeat {
+                project: project.to_string(),
+                path: PathBuf::from("/a"),
+                language: None,
+                time,
+                is_write: false,
+            };
+            log.record(&heartbeat, Duration::hours(2));
+        }
+
+        assert_eq!(
+            time_summary(&log, datetime(8, 12, 0), UtcOffset::UTC),
+            "# Time Summary\n\
+             \n## Today\n\n\
+             | Project | Time |\n| --- | --- |\n\
+             | zed | 1h 05m |\n\
+             \n## Last 7 Days\n\n\
+             | Project | Time |\n| --- | --- |\n\
+             | zed | 1h 05m |\n\
+             \n## All Time\n\n\
+             | Project | Time |\n| --- | --- |\n\
+             | zed | 1h 05m |\n\
+             | blog | 30m |\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_load_and_save_time_log(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let log = TimeLog {
+            spans: vec![TimeSpan {
+                project: "zed".into(),
+                language: Some("Rust".into()),
+                start: datetime(1, 10, 0),
+                end: datetime(1, 10, 30),
+            }],
+        };
+        fs.insert_file(
+            "/support/time_log.json",
+            serde_json::to_string(&log).unwrap(),
+        )
+        .await;
+
+        let tracker = cx.new_model(|cx| {
+            TimeTracker::new(
+                fs.clone(),
+                FakeHttpClient::with_404_response(),
+                PathBuf::from("/support/time_log.json"),
+                cx,
+            )
+        });
+        cx.executor().run_until_parked();
+        tracker.read_with(cx, |tracker, _| assert_eq!(tracker.log(), &log));
+
+        fs.remove_file(Path::new("/support/time_log.json"), Default::default())
+            .await
+            .unwrap();
+        tracker.update(cx, |tracker, cx| tracker.save(cx)).await;
+        let saved_log = fs.load(Path::new("/support/time_log.json")).await.unwrap();
+        assert_eq!(serde_json::from_str::<TimeLog>(&saved_log).unwrap(), log);
+    }
+
+    #[gpui::test]
+    async fn test_invalid_time_log(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file("/support/time_log.json", "{\"spans\": [".into())
+            .await;
+
+        let tracker = cx.new_model(|cx| {
+            TimeTracker::new(
+                fs.clone(),
+                FakeHttpClient::with_404_response(),
+                PathBuf::from("/support/time_log.json"),
+                cx,
+            )
+        });
+        cx.executor().run_until_parked();
+
+        // The invalid log is kept, and a new one is started.
+        assert_eq!(
+            fs.load(Path::new("/support/time_log.json.invalid"))
+                .await
+                .unwrap(),
+            "{\"spans\": ["
+        );
+        tracker.update(cx, |tracker, cx| tracker.save(cx)).await;
+        let saved_log = fs.load(Path::new("/support/time_log.json")).await.unwrap();
+        assert_eq!(
+            serde_json::from_str::<TimeLog>(&saved_log).unwrap(),
+            TimeLog::default()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_time_log_is_not_saved_before_loading(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file("/support/time_log.json", "{\"spans\": []}".into())
+            .await;
+
+        let tracker = cx.new_model(|cx| {
+            TimeTracker::new(
+                fs.clone(),
+                FakeHttpClient::with_404_response(),
+                PathBuf::from("/support/time_log.json"),
+                cx,
+            )
+        });
+        let save = tracker.update(cx, |tracker, cx| tracker.save(cx));
+        save.await;
+        assert_eq!(
+            fs.load(Path::new("/support/time_log.json")).await.unwrap(),
+            "{\"spans\": []}"
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use editor::Editor;
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use language::{Buffer, Point};
+use picker::{Picker, PickerDelegate};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use text::{HistoryEntry, TransactionId};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(undo_tree, [Toggle]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(UndoTree::register).detach();
+}
+
+/// Lists the edits of the active buffer as a tree, whose branches are the edits that
+/// were undone and then replaced by others.
+pub struct UndoTree {
+    picker: View<Picker<UndoTreeDelegate>>,
+}
+
+impl UndoTree {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(move |workspace, _: &Toggle, cx| {
+            Self::toggle(workspace, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> Option<()> {
+        let buffer = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()?;
+        workspace.toggle_modal(cx, move |cx| UndoTree::new(buffer, cx));
+        Some(())
+    }
+
+    fn new(buffer: Model<Buffer>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = UndoTreeDelegate::new(cx.view().downgrade(), buffer, cx);
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for UndoTree {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for UndoTree {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for UndoTree {}
+impl ModalView for UndoTree {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EntryState {
+    Applied,
+    /// The last applied transaction, which is the current state of the buffer.
+    Current,
+    Undone,
+}
+
+struct UndoTreeEntry {
+    /// The transaction to travel to, or `None` for the text before any edit.
+    transaction_id: Option<TransactionId>,
+    depth: usize,
+    state: EntryState,
+    label: String,
+}
+
+pub struct UndoTreeDelegate {
+    undo_tree: WeakView<UndoTree>,
+    buffer: Model<Buffer>,
+    entries: Vec<UndoTreeEntry>,
+    selected_index: usize,
+}
+
+impl UndoTreeDelegate {
+    fn new(undo_tree: WeakView<UndoTree>, buffer: Model<Buffer>, cx: &AppContext) -> Self {
+        let entries = undo_tree_entries(buffer.read(cx), Instant::now());
+        let selected_index = entries
+            .iter()
+            .position(|entry| entry.state == EntryState::Current)
+            .unwrap_or(0);
+        Self {
+            undo_tree,
+            buffer,
+            entries,
+            selected_index,
+        }
+    }
+}
+
+impl PickerDelegate for UndoTreeDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Travel to an edit, or revert it with secondary confirm...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Travels to the selected edit, or reverts only that edit on secondary confirm,
+    /// keeping the ones made after it.
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(entry) = self.entries.get(self.selected_index) {
+            let transaction_id = entry.transaction_id;
+            if secondary {
+                if let (Some(transaction_id), EntryState::Applied | EntryState::Current) =
+                    (transaction_id, entry.state)
+                {
+                    self.buffer.update(cx, |buffer, cx| {
+                        buffer.revert_transaction(transaction_id, cx);
+                    });
+                }
+            } else {
+                self.buffer.update(cx, |buffer, cx| {
+                    buffer.travel_to_transaction(transaction_id, cx);
+                });
+            }
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.undo_tree
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, _: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        Task::ready(())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.entries[ix];
+        let (icon, color) = match entry.state {
+            EntryState::Applied => (IconName::Check, Color::Default),
+            EntryState::Current => (IconName::ArrowRight, Color::Accent),
+            EntryState::Undone => (IconName::Dash, Color::Muted),
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .indent_level(entry.depth)
+                .start_slot(Icon::new(icon).size(IconSize::Small).color(color))
+                .child(Label::new(entry.label.clone()).color(color)),
+        )
+    }
+}
+
+/// Lists the text before any edit, then the applied and undone transactions in the
+/// order they were made, each followed by the branches that start after it.
+fn undo_tree_entries(buffer: &Buffer, now: Instant) -> Vec<UndoTreeEntry> {
+    let undo_stack = buffer.undo_stack();
+    let mut entries = vec![UndoTreeEntry {
+        transaction_id: None,
+        depth: 0,
+        state: if undo_stack.is_empty() {
+            EntryState::Current
+        } else {
+            EntryState::Applied
+        },
+        label: "Original text".into(),
+    }];
+    push_branches(&mut entries, buffer, None, 1, now);
+
+    for (ix, history_entry) in undo_stack.iter().enumerate() {
+        let state = if ix + 1 == undo_stack.len() {
+            EntryState::Current
+        } else {
+            EntryState::Applied
+        };
+        push_entry(&mut entries, buffer, history_entry, 0, state, now);
+    }
+    for history_entry in buffer.redo_stack().iter().rev() {
+        push_entry(
+            &mut entries,
+            buffer,
+            history_entry,
+            0,
+            EntryState::Undone,
+            now,
+        );
+    }
+    entries
+}
+
+fn push_entry(
+    entries: &mut Vec<UndoTreeEntry>,
+    buffer: &Buffer,
+    history_entry: &HistoryEntry,
+    depth: usize,
+    state: EntryState,
+    now: Instant,
+) {
+    entries.push(UndoTreeEntry {
+        transaction_id: Some(history_entry.transaction_id()),
+        depth,
+        state,
+        label: entry_label(buffer, history_entry, now),
+    });
+    push_branches(
+        entries,
+        buffer,
+        Some(history_entry.transaction_id()),
+        depth + 1,
+        now,
+    );
+}
+
+fn push_branches(
+    entries: &mut Vec<UndoTreeEntry>,
+    buffer: &Buffer,
+    parent: Option<TransactionId>,
+    depth: usize,
+    now: Instant,
+) {
+    for branch in buffer
+        .undo_branches()
+        .iter()
+        .filter(|branch| branch.parent == parent)
+    {
+        for history_entry in &branch.entries {
+            push_entry(
+                entries,
+                buffer,
+                history_entry,
+                depth,
+                EntryState::Undone,
+                now,
+            );
+        }
+    }
+}
+
+/// Describes a transaction by the lines it edited and how long ago it was made.
+fn entry_label(buffer: &Buffer, history_entry: &HistoryEntry, now: Instant) -> String {
+    let mut rows = buffer
+        .edited_ranges_for_transaction::<Point>(history_entry.transaction())
+        .map(|range| range.start.row + 1);
+    let location = match (rows.next(), rows.count()) {
+        (Some(row), 0) => format!("Line {row}"),
+        (Some(row), more) => format!("Line {row} and {more} more"),
+        (None, _) => "Edit".to_string(),
+    };
+    format!(
+        "{location} · {}",
+        time_ago(now.saturating_duration_since(history_entry.last_edit_at()))
+    )
+}
+
+fn time_ago(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        _ => format!("{} h ago", seconds / 3600),
+    }
+}
@@ -0,0 +1,830 @@
+//! Downloading and unpacking the archives that language servers are released as.
+
+use crate::{
+    http::{AsyncBody, HttpClient, Response},
+    paths, ResultExt,
+};
+use anyhow::{anyhow, Context, Result};
+use async_compression::futures::bufread::{GzipDecoder, XzDecoder};
+use collections::HashMap;
+use futures::{io::BufReader, AsyncReadExt, AsyncWriteExt, Future, StreamExt};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// How many times a download whose checksum doesn't match is attempted, since the
+/// mismatch can come from a download that was cut short.
+const DOWNLOAD_ATTEMPTS: usize = 2;
+
+/// The checksums in `~/.config/zed/checksums.json`, which take precedence over the
+/// ones that GitHub reports, so that an organization can pin the binaries it trusts:
+///
+/// ```json
+/// {
+///   "require_checksums": true,
+///   "checksums": {
+///     "https://github.com/clangd/clangd/releases/download/17.0.3/clangd-mac-17.0.3.zip": "<sha256>"
+///   }
+/// }
+/// ```
+#[derive(Default, Deserialize)]
+struct PinnedChecksums {
+    /// Whether downloads without a known checksum are refused.
+    #[serde(default)]
+    require_checksums: bool,
+    /// The SHA-256 checksums of downloads, by their URLs.
+    #[serde(default)]
+    checksums: HashMap<String, String>,
+}
+
+impl PinnedChecksums {
+    /// Loads the pinned checksums, if there are any. A file that can't be read or parsed
+    /// is an error rather than no pins, so that a typo can't turn `require_checksums` off.
+    async fn load() -> Result<Self> {
+        let path = paths::PINNED_CHECKSUMS.as_path();
+        match smol::fs::read_to_string(path).await {
+            Ok(content) => Self::parse(&content).with_context(|| format!("{path:?}")),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("failed to read {path:?}")),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        serde_json::from_str(content).context("invalid pinned checksums")
+    }
+
+    /// Returns the checksum that a download must match, if any.
+    fn expected_sha256(&self, url: &str, reported_sha256: Option<&str>) -> Result<Option<String>> {
+        match self
+            .checksums
+            .get(url)
+            .map(String::as_str)
+            .or(reported_sha256)
+        {
+            Some(sha256) => Ok(Some(sha256.to_string())),
+            None if self.require_checksums => Err(anyhow!(
+                "refusing to download {url}, which has no known checksum"
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns an error when installs that can't be checked against a checksum, such
+    /// as ones done by package managers, aren't allowed.
+    fn check_unverified_install(&self, description: &str) -> Result<()> {
+        if self.require_checksums {
+            Err(anyhow!(
+                "refusing to install {description}, which can't be verified against a checksum"
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns an error when `require_checksums` is set, for installs whose contents can't
+/// be verified, such as the packages that npm or `gem` install. Also returns an error
+/// when the pinned checksums can't be loaded.
+pub async fn check_unverified_install(description: &str) -> Result<()> {
+    PinnedChecksums::load()
+        .await?
+        .check_unverified_install(description)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveType {
+    TarGz,
+    TarXz,
+    Zip,
+}
+
+impl ArchiveType {
+    /// Returns the type of the archive with the given file name or URL, by its suffix.
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let file_name = file_name.to_ascii_lowercase();
+        if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if file_name.ends_with(".tar.xz") || file_name.ends_with(".txz") {
+            Some(Self::TarXz)
+        } else if file_name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Downloads an archive and unpacks it into `destination`, after verifying it like
+/// [`download_verified`] does. The archive is downloaded to a temporary file rather than
+/// held in memory.
+pub async fn download_archive(
+    http_client: &dyn HttpClient,
+    url: &str,
+    archive_type: ArchiveType,
+    sha256: Option<&str>,
+    destination: &Path,
+) -> Result<()> {
+    let archive_path = download_verified_to_file(http_client, url, sha256, destination).await?;
+    let result = extract_archive(&archive_path, archive_type, destination).await;
+    smol::fs::remove_file(&archive_path).await.log_err();
+    result
+}
+
+/// Downloads an archive and unpacks it into the new directory `destination` like
+/// [`extract_archive_atomically`] does, after verifying it like [`download_archive`] does.
+pub async fn download_archive_atomically(
+    http_client: &dyn HttpClient,
+    url: &str,
+    archive_type: ArchiveType,
+    sha256: Option<&str>,
+    destination: &Path,
+) -> Result<()> {
+    let archive_path = download_verified_to_file(http_client, url, sha256, destination).await?;
+    let result = extract_archive_atomically(&archive_path, archive_type, destination).await;
+    smol::fs::remove_file(&archive_path).await.log_err();
+    result
+}
+
+/// Downloads an archive that contains a single directory, and moves that directory to
+/// `destination` like [`download_archive_atomically`] does, for releases that are
+/// archived together with their version's directory.
+pub async fn download_archive_directory_atomically(
+    http_client: &dyn HttpClient,
+    url: &str,
+    archive_type: ArchiveType,
+    sha256: Option<&str>,
+    destination: &Path,
+) -> Result<()> {
+    let archive_path = download_verified_to_file(http_client, url, sha256, destination).await?;
+    let result =
+        extract_archive_directory_atomically(&archive_path, archive_type, destination).await;
+    smol::fs::remove_file(&archive_path).await.log_err();
+    result
+}
+
+/// Downloads an executable that's released on its own rather than in an archive, and
+/// moves it to `destination` once it's complete and executable, after verifying it
+/// like [`download_archive`] does.
+pub async fn download_binary_atomically(
+    http_client: &dyn HttpClient,
+    url: &str,
+    sha256: Option<&str>,
+    destination: &Path,
+) -> Result<()> {
+    let download_path = download_verified_to_file(http_client, url, sha256, destination).await?;
+    let result = install_binary(&download_path, destination).await;
+    if result.is_err() {
+        smol::fs::remove_file(&download_path).await.log_err();
+    }
+    result
+}
+
+/// Downloads a file and checks that its SHA-256 checksum matches the pinned one for its
+/// URL, or else `sha256`, when either is known. A download that doesn't match is retried,
+/// and then moved to the quarantine directory for inspection rather than used. Nothing
+/// is downloaded when the pinned checksums can't be loaded.
+pub async fn download_verified(
+    http_client: &dyn HttpClient,
+    url: &str,
+    sha256: Option<&str>,
+) -> Result<Vec<u8>> {
+    download_verified_with(url, sha256, || download(http_client, url)).await
+}
+
+/// Verifies a download like [`download_verified`] does, but fetches it with `download`,
+/// for callers that make the request themselves, such as to check where it redirects.
+pub async fn download_verified_with<F, Fut>(
+    url: &str,
+    sha256: Option<&str>,
+    download: F,
+) -> Result<Vec<u8>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<u8>>>,
+{
+    let expected_sha256 = PinnedChecksums::load()
+        .await?
+        .expected_sha256(url, sha256)?;
+    download_verified_into_quarantine(
+        url,
+        expected_sha256.as_deref(),
+        &paths::QUARANTINE_DIR,
+        download,
+    )
+    .await
+}
+
+/// Downloads a file next to `destination` and verifies it like [`download_verified`]
+/// does, returning the path of the download.
+async fn download_verified_to_file(
+    http_client: &dyn HttpClient,
+    url: &str,
+    sha256: Option<&str>,
+    destination: &Path,
+) -> Result<PathBuf> {
+    let expected_sha256 = PinnedChecksums::load()
+        .await?
+        .expected_sha256(url, sha256)?;
+    let download_path = temp_path(destination, "download")?;
+    let result = download_file_into_quarantine(
+        url,
+        expected_sha256.as_deref(),
+        &paths::QUARANTINE_DIR,
+        &download_path,
+        || download_to_file(http_client, url, &download_path),
+    )
+    .await;
+    if result.is_err() && smol::fs::metadata(&download_path).await.is_ok() {
+        smol::fs::remove_file(&download_path).await.log_err();
+    }
+    result.map(|_| download_path)
+}
+
+async fn download_verified_into_quarantine<F, Fut>(
+    url: &str,
+    expected_sha256: Option<&str>,
+    quarantine_dir: &Path,
+    mut download: F,
+) -> Result<Vec<u8>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<u8>>>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let bytes = download().await?;
+        let Some(expected_sha256) = expected_sha256 else {
+            return Ok(bytes);
+        };
+        let Err(error) = verify_sha256(&bytes, expected_sha256) else {
+            return Ok(bytes);
+        };
+        if attempt < DOWNLOAD_ATTEMPTS {
+            log::warn!("retrying download of {url}: {error}");
+            continue;
+        }
+        let quarantine_path = quarantine(url, &bytes, quarantine_dir).await;
+        return Err(error).with_context(|| match quarantine_path {
+            Some(path) => format!("download of {url} was quarantined at {path:?}"),
+            None => format!("download of {url} is invalid"),
+        });
+    }
+}
+
+/// Like [`download_verified_into_quarantine`], for a `download` that writes the file to
+/// `path` and returns its checksum.
+async fn download_file_into_quarantine<F, Fut>(
+    url: &str,
+    expected_sha256: Option<&str>,
+    quarantine_dir: &Path,
+    path: &Path,
+    mut download: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let sha256 = download().await?;
+        let Some(expected_sha256) = expected_sha256 else {
+            return Ok(());
+        };
+        let Err(error) = check_sha256(&sha256, expected_sha256) else {
+            return Ok(());
+        };
+        if attempt < DOWNLOAD_ATTEMPTS {
+            log::warn!("retrying download of {url}: {error}");
+            continue;
+        }
+        let quarantine_path = quarantine_file(url, path, quarantine_dir).await;
+        return Err(error).with_context(|| match quarantine_path {
+            Some(path) => format!("download of {url} was quarantined at {path:?}"),
+            None => format!("download of {url} is invalid"),
+        });
+    }
+}
+
+async fn get(http_client: &dyn HttpClient, url: &str) -> Result<Response<AsyncBody>> {
+    let response = http_client
+        .get(url, Default::default(), true)
+        .await
+        .context("error downloading archive")?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "download failed with status {}",
+            response.status().to_string()
+        ));
+    }
+    Ok(response)
+}
+
+async fn download(http_client: &dyn HttpClient, url: &str) -> Result<Vec<u8>> {
+    let mut response = get(http_client, url).await?;
+    let mut bytes = Vec::new();
+    response.body_mut().read_to_end(&mut bytes).await?;
+    Ok(bytes)
+}
+
+/// Streams a download into the file at `path`, returning its SHA-256 checksum.
+async fn download_to_file(http_client: &dyn HttpClient, url: &str, path: &Path) -> Result<String> {
+    let mut response = get(http_client, url).await?;
+    let mut file = smol::fs::File::create(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let len = response.body_mut().read(&mut buffer).await?;
+        if len == 0 {
+            break;
+        }
+        hasher.update(&buffer[..len]);
+        file.write_all(&buffer[..len]).await?;
+    }
+    file.flush().await?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn quarantine_file_name(url: &str) -> &str {
+    url.rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("download")
+}
+
+async fn quarantine(url: &str, bytes: &[u8], quarantine_dir: &Path) -> Option<PathBuf> {
+    let path = quarantine_dir.join(quarantine_file_name(url));
+    smol::fs::create_dir_all(quarantine_dir).await.log_err()?;
+    smol::fs::write(&path, bytes).await.log_err()?;
+    Some(path)
+}
+
+async fn quarantine_file(url: &str, path: &Path, quarantine_dir: &Path) -> Option<PathBuf> {
+    let quarantine_path = quarantine_dir.join(quarantine_file_name(url));
+    smol::fs::create_dir_all(quarantine_dir).await.log_err()?;
+    // The quarantine directory may be on another file system than the download.
+    if smol::fs::rename(path, &quarantine_path).await.is_err() {
+        smol::fs::copy(path, &quarantine_path).await.log_err()?;
+        smol::fs::remove_file(path).await.log_err();
+    }
+    Some(quarantine_path)
+}
+
+/// Unpacks the archive at `archive_path` into `destination`, which is created when it
+/// doesn't exist. Entries that would end up outside of `destination` are rejected.
+pub async fn extract_archive(
+    archive_path: &Path,
+    archive_type: ArchiveType,
+    destination: &Path,
+) -> Result<()> {
+    smol::fs::create_dir_all(destination).await?;
+    match archive_type {
+        ArchiveType::TarGz => {
+            let file = smol::fs::File::open(archive_path).await?;
+            let decompressed = GzipDecoder::new(BufReader::new(file));
+            async_tar::Archive::new(decompressed)
+                .unpack(destination)
+                .await?;
+        }
+        ArchiveType::TarXz => {
+            let file = smol::fs::File::open(archive_path).await?;
+            let decompressed = XzDecoder::new(BufReader::new(file));
+            async_tar::Archive::new(decompressed)
+                .unpack(destination)
+                .await?;
+        }
+        ArchiveType::Zip => {
+            let archive_path = archive_path.to_path_buf();
+            let destination = destination.to_path_buf();
+            smol::unblock(move || {
+                let file = std::fs::File::open(&archive_path)?;
+                let mut archive = zip::ZipArchive::new(std::io::BufReader::new(file))?;
+                // Sets the permissions of the entries on Unix, so that binaries stay executable.
+                archive.extract(&destination)?;
+                anyhow::Ok(())
+            })
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Unpacks an archive next to `destination` and then moves it into place, so that an
+/// install that's interrupted never leaves behind a `destination` that looks complete.
+/// A `destination` left by an earlier interrupted install is replaced.
+pub async fn extract_archive_atomically(
+    archive_path: &Path,
+    archive_type: ArchiveType,
+    destination: &Path,
+) -> Result<()> {
+    extract_atomically(archive_path, archive_type, destination, false).await
+}
+
+/// Unpacks an archive that contains a single directory like [`extract_archive_atomically`]
+/// does, moving that directory rather than the archive's root to `destination`.
+pub async fn extract_archive_directory_atomically(
+    archive_path: &Path,
+    archive_type: ArchiveType,
+    destination: &Path,
+) -> Result<()> {
+    extract_atomically(archive_path, archive_type, destination, true).await
+}
+
+async fn extract_atomically(
+    archive_path: &Path,
+    archive_type: ArchiveType,
+    destination: &Path,
+    single_directory: bool,
+) -> Result<()> {
+    let temp_dir = temp_path(destination, "partial")?;
+    if smol::fs::metadata(&temp_dir).await.is_ok() {
+        smol::fs::remove_dir_all(&temp_dir).await?;
+    }
+    let result = async {
+        extract_archive(archive_path, archive_type, &temp_dir).await?;
+        let source = if single_directory {
+            single_directory_in(&temp_dir).await?
+        } else {
+            temp_dir.clone()
+        };
+        if smol::fs::metadata(destination).await.is_ok() {
+            smol::fs::remove_dir_all(destination).await?;
+        }
+        smol::fs::rename(&source, destination)
+            .await
+            .with_context(|| format!("failed to move {source:?} to {destination:?}"))
+    }
+    .await;
+    if smol::fs::metadata(&temp_dir).await.is_ok() {
+        smol::fs::remove_dir_all(&temp_dir).await.log_err();
+    }
+    result
+}
+
+async fn single_directory_in(dir: &Path) -> Result<PathBuf> {
+    let mut entries = smol::fs::read_dir(dir).await?;
+    let mut directory = None;
+    while let Some(entry) = entries.next().await {
+        let entry = entry?;
+        if directory.is_some() || !entry.file_type().await?.is_dir() {
+            return Err(anyhow!(
+                "expected the archive to contain a single directory"
+            ));
+        }
+        directory = Some(entry.path());
+    }
+    directory.context("the archive is empty")
+}
+
+/// Makes a downloaded executable executable and then moves it into place, so that an
+/// interrupted install never leaves behind a truncated or unusable `destination`.
+async fn install_binary(download_path: &Path, destination: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        smol::fs::set_permissions(download_path, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+    smol::fs::rename(download_path, destination)
+        .await
+        .with_context(|| format!("failed to move {download_path:?} to {destination:?}"))
+}
+
+/// Returns a temporary path next to `destination`, which is written to before it's
+/// complete. It starts with a `.`, so that it isn't taken for a version of a language
+/// server.
+fn temp_path(destination: &Path, suffix: &str) -> Result<PathBuf> {
+    let file_name = destination
+        .file_name()
+        .with_context(|| format!("invalid destination {destination:?}"))?;
+    Ok(destination.with_file_name(format!(".{}.{suffix}", file_name.to_string_lossy())))
+}
+
+/// Checks that the SHA-256 checksum of `bytes` is the given hex string.
+pub fn verify_sha256(bytes: &[u8], expected: &str) -> Result<()> {
+    check_sha256(&format!("{:x}", Sha256::digest(bytes)), expected)
+}
+
+fn check_sha256(actual: &str, expected: &str) -> Result<()> {
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "SHA-256 checksum mismatch: expected {expected}, got {actual}"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{AsyncBody, Error, Request, Response};
+    use futures::future::BoxFuture;
+    use parking_lot::Mutex;
+    use std::io::Write as _;
+
+    /// Responds to each request with the next of the given bodies.
+    struct FakeDownloads(Mutex<Vec<&'static str>>);
+
+    impl HttpClient for FakeDownloads {
+        fn send(&self, _: Request<AsyncBody>) -> BoxFuture<Result<Response<AsyncBody>, Error>> {
+            let body = self.0.lock().remove(0);
+            Box::pin(async move { Ok(Response::new(AsyncBody::from(body))) })
+        }
+    }
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    fn zip_archive(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (path, contents) in entries {
+            writer
+                .start_file(
+                    *path,
+                    zip::write::FileOptions::default().unix_permissions(0o755),
+                )
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn write_zip_archive(dir: &Path, entries: &[(&str, &str)]) -> PathBuf {
+        let path = dir.join("archive.zip");
+        std::fs::write(&path, zip_archive(entries)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_archive_type_from_file_name() {
+        assert_eq!(
+            ArchiveType::from_file_name("clangd-mac-17.0.3.zip"),
+            Some(ArchiveType::Zip)
+        );
+        assert_eq!(
+            ArchiveType::from_file_name("https://example.com/server-linux-x64.tar.xz"),
+            Some(ArchiveType::TarXz)
+        );
+        assert_eq!(
+            ArchiveType::from_file_name("Server.TGZ"),
+            Some(ArchiveType::TarGz)
+        );
+        assert_eq!(ArchiveType::from_file_name("server.gz"), None);
+    }
+
+    #[test]
+    fn test_expected_sha256() {
+        let url = "https://example.com/server.zip";
+        let mut pinned = PinnedChecksums::default();
+        assert_eq!(pinned.expected_sha256(url, None).unwrap(), None);
+        assert_eq!(
+            pinned.expected_sha256(url, Some("reported")).unwrap(),
+            Some("reported".into())
+        );
+
+        pinned.checksums.insert(url.into(), "pinned".into());
+        assert_eq!(
+            pinned.expected_sha256(url, Some("reported")).unwrap(),
+            Some("pinned".into())
+        );
+
+        pinned.require_checksums = true;
+        assert!(pinned
+            .expected_sha256("https://example.com/other.zip", None)
+            .is_err());
+        assert_eq!(
+            pinned
+                .expected_sha256("https://example.com/other.zip", Some("reported"))
+                .unwrap(),
+            Some("reported".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_pinned_checksums() {
+        let pinned = PinnedChecksums::parse(
+            r#"{"require_checksums": true, "checksums": {"https://example.com/a.zip": "abc"}}"#,
+        )
+        .unwrap();
+        assert!(pinned.require_checksums);
+        assert_eq!(pinned.checksums["https://example.com/a.zip"], "abc");
+
+        assert!(!PinnedChecksums::parse("{}").unwrap().require_checksums);
+        assert!(PinnedChecksums::parse(r#"{"require_checksums": tru"#).is_err());
+        assert!(PinnedChecksums::parse(r#"{"require_checksums": "yes"}"#).is_err());
+    }
+
+    #[test]
+    fn test_check_unverified_install() {
+        let mut pinned = PinnedChecksums::default();
+        assert!(pinned.check_unverified_install("npm packages").is_ok());
+        pinned.require_checksums = true;
+        assert!(pinned.check_unverified_install("npm packages").is_err());
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        assert!(verify_sha256(b"hello", HELLO_SHA256).is_ok());
+        assert!(verify_sha256(b"hello", &HELLO_SHA256.to_uppercase()).is_ok());
+        assert!(verify_sha256(b"hello!", HELLO_SHA256).is_err());
+    }
+
+    #[test]
+    fn test_download_verified_retries_and_quarantines() {
+        let quarantine_dir = tempfile::tempdir().unwrap();
+        let url = "https://example.com/releases/server.zip";
+        let download = |bodies: Vec<&'static str>, sha256: Option<&str>| {
+            let http_client = FakeDownloads(Mutex::new(bodies));
+            smol::block_on(download_verified_into_quarantine(
+                url,
+                sha256,
+                quarantine_dir.path(),
+                || super::download(&http_client, url),
+            ))
+        };
+
+        // Downloads without a checksum aren't checked.
+        assert_eq!(download(vec!["hel"], None).unwrap(), b"hel");
+
+        // A download that was cut short is retried.
+        assert_eq!(
+            download(vec!["hel", "hello"], Some(HELLO_SHA256)).unwrap(),
+            b"hello"
+        );
+        assert!(!quarantine_dir.path().join("server.zip").exists());
+
+        // A download that keeps mismatching is quarantined rather than returned.
+        let error = download(vec!["hel", "help"], Some(HELLO_SHA256)).unwrap_err();
+        assert!(error.to_string().contains("quarantined"), "{error}");
+        assert_eq!(
+            std::fs::read_to_string(quarantine_dir.path().join("server.zip")).unwrap(),
+            "help"
+        );
+    }
+
+    #[test]
+    fn test_download_verified_to_file_retries_and_quarantines() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine_dir = tempfile::tempdir().unwrap();
+        let url = "https://example.com/releases/server.zip";
+        let path = dir.path().join(".server.download");
+        let download = |bodies: Vec<&'static str>, sha256: Option<&str>| {
+            let http_client = FakeDownloads(Mutex::new(bodies));
+            smol::block_on(download_file_into_quarantine(
+                url,
+                sha256,
+                quarantine_dir.path(),
+                &path,
+                || download_to_file(&http_client, url, &path),
+            ))
+        };
+
+        download(vec!["hel"], None).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hel");
+
+        download(vec!["hel", "hello"], Some(HELLO_SHA256)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello");
+        assert!(!quarantine_dir.path().join("server.zip").exists());
+
+        let error = download(vec!["hel", "help"], Some(HELLO_SHA256)).unwrap_err();
+        assert!(error.to_string().contains("quarantined"), "{error}");
+        assert!(!path.exists());
+        assert_eq!(
+            std::fs::read_to_string(quarantine_dir.path().join("server.zip")).unwrap(),
+            "help"
+        );
+    }
+
+    #[test]
+    fn test_extract_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive = write_zip_archive(
+            archive_dir.path(),
+            &[
+                ("server/bin/server", "#!/bin/sh"),
+                ("server/README.md", "# Server"),
+            ],
+        );
+        smol::block_on(extract_archive(&archive, ArchiveType::Zip, dir.path())).unwrap();
+
+        let binary_path = dir.path().join("server/bin/server");
+        assert_eq!(std::fs::read_to_string(&binary_path).unwrap(), "#!/bin/sh");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("server/README.md")).unwrap(),
+            "# Server"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+            let mode = std::fs::metadata(&binary_path)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+
+        // Entries can't be written outside of the destination.
+        let archive = write_zip_archive(archive_dir.path(), &[("../escaped", "")]);
+        smol::block_on(extract_archive(&archive, ArchiveType::Zip, dir.path())).unwrap_err();
+        assert!(!dir.path().parent().unwrap().join("escaped").exists());
+    }
+
+    #[test]
+    fn test_install_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("server-1.0");
+        let download_path = dir.path().join(".server-1.0.download");
+        std::fs::write(&download_path, "#!/bin/sh").unwrap();
+
+        smol::block_on(install_binary(&download_path, &destination)).unwrap();
+        assert_eq!(std::fs::read_to_string(&destination).unwrap(), "#!/bin/sh");
+        assert!(!download_path.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+            let mode = std::fs::metadata(&destination)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+
+    #[test]
+    fn test_extract_archive_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("server_1.0");
+        let file_names = || {
+            let mut names = std::fs::read_dir(dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        // A failed install leaves nothing behind.
+        let archive = write_zip_archive(
+            archive_dir.path(),
+            &[("bin/server", "#!/bin/sh"), ("../escaped", "")],
+        );
+        smol::block_on(extract_archive_atomically(
+            &archive,
+            ArchiveType::Zip,
+            &destination,
+        ))
+        .unwrap_err();
+        assert_eq!(file_names(), Vec::<String>::new());
+
+        // The partial directory of an interrupted install is replaced.
+        std::fs::create_dir_all(destination.join("lib")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".server_1.0.partial")).unwrap();
+        let archive = write_zip_archive(archive_dir.path(), &[("bin/server", "#!/bin/sh")]);
+        smol::block_on(extract_archive_atomically(
+            &archive,
+            ArchiveType::Zip,
+            &destination,
+        ))
+        .unwrap();
+        assert_eq!(file_names(), ["server_1.0"]);
+        assert!(destination.join("bin/server").exists());
+        assert!(!destination.join("lib").exists());
+    }
+
+    #[test]
+    fn test_extract_archive_directory_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("clangd_17.0.3");
+
+        let archive = write_zip_archive(
+            archive_dir.path(),
+            &[("clangd_17.0.3/bin/clangd", "#!/bin/sh")],
+        );
+        smol::block_on(extract_archive_directory_atomically(
+            &archive,
+            ArchiveType::Zip,
+            &destination,
+        ))
+        .unwrap();
+        assert!(destination.join("bin/clangd").exists());
+        assert!(!dir.path().join(".clangd_17.0.3.partial").exists());
+
+        // Archives with anything next to the directory are rejected.
+        let archive = write_zip_archive(
+            archive_dir.path(),
+            &[("clangd_18.1.0/bin/clangd", "#!/bin/sh"), ("README.md", "")],
+        );
+        let destination = dir.path().join("clangd_18.1.0");
+        smol::block_on(extract_archive_directory_atomically(
+            &archive,
+            ArchiveType::Zip,
+            &destination,
+        ))
+        .unwrap_err();
+        assert!(!destination.exists());
+        assert!(!dir.path().join(".clangd_18.1.0.partial").exists());
+    }
+}
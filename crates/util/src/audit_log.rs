@@ -0,0 +1,296 @@
+//! A record of what Zed runs and sends on its own, such as the npm installs of
+//! language servers, the commands of project hooks and the requests sent to AI
+//! providers, so that it can be reviewed in environments that require it.
+//!
+//! The data that's sent is only recorded by its size and SHA-256 hash, unless
+//! recording it in full was opted into with [`set_record_content`].
+
+use anyhow::Result;
+use futures::channel::mpsc;
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::paths;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// A process was started.
+    Command,
+    /// A file was downloaded.
+    Download,
+    /// Data was sent to a service.
+    Request,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// The number of seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub kind: AuditEventKind,
+    /// What acted, such as `npm` or `hooks`.
+    pub source: String,
+    /// The root of the project that the action was taken for, or `None` when it was
+    /// taken for Zed as a whole.
+    pub project_root: Option<PathBuf>,
+    pub summary: String,
+    /// The full command line, or the data that was sent or its size and hash.
+    pub details: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(
+        kind: AuditEventKind,
+        source: impl Into<String>,
+        summary: impl Into<String>,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            kind,
+            source: source.into(),
+            project_root: None,
+            summary: summary.into(),
+            details: None,
+        }
+    }
+
+    pub fn project_root(mut self, project_root: impl Into<PathBuf>) -> Self {
+        self.project_root = Some(project_root.into());
+        self
+    }
+
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    /// Sets the details to the data that was sent, or to its size and hash when
+    /// recording it in full wasn't opted into.
+    pub fn content(self, content: &str) -> Self {
+        let details = content_details(content, RECORD_CONTENT.load(Ordering::Relaxed));
+        self.details(details)
+    }
+}
+
+/// The size above which the audit log is moved to [`paths::OLD_AUDIT_LOG`], replacing
+/// the one that was moved there before.
+#[cfg(not(any(test, feature = "test-support")))]
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+static RECORD_CONTENT: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether the data sent to services is recorded in full, rather than by its size
+/// and hash.
+pub fn set_record_content(record_content: bool) {
+    RECORD_CONTENT.store(record_content, Ordering::Relaxed);
+}
+
+fn content_details(content: &str, record_content: bool) -> String {
+    if record_content {
+        content.to_string()
+    } else {
+        format!(
+            "{} bytes, sha256 {:x}",
+            content.len(),
+            Sha256::digest(content.as_bytes())
+        )
+    }
+}
+
+lazy_static! {
+    static ref SUBSCRIBERS: Mutex<Vec<mpsc::UnboundedSender<AuditEvent>>> = Default::default();
+    static ref WRITER: Mutex<Option<std::sync::mpsc::Sender<AuditEvent>>> =
+        Mutex::new(spawn_writer());
+}
+
+/// Appends the event to the audit log, and sends it to the subscribers.
+pub fn record(event: AuditEvent) {
+    log::debug!("audit: {} {}", event.source, event.summary);
+    SUBSCRIBERS
+        .lock()
+        .retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+    if let Some(writer) = WRITER.lock().as_ref() {
+        writer.send(event).ok();
+    }
+}
+
+/// Returns the events recorded from now on.
+pub fn subscribe() -> mpsc::UnboundedReceiver<AuditEvent> {
+    let (tx, rx) = mpsc::unbounded();
+    SUBSCRIBERS.lock().push(tx);
+    rx
+}
+
+/// Loads the events that were written to the audit log, including the ones that were
+/// moved aside when it last grew too large, oldest first.
+pub async fn load() -> Result<Vec<AuditEvent>> {
+    let mut events = Vec::new();
+    for path in [&*paths::OLD_AUDIT_LOG, &*paths::AUDIT_LOG] {
+        match smol::fs::read_to_string(path).await {
+            Ok(text) => events.extend(parse(&text)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(events)
+}
+
+/// Parses the lines of the audit log, skipping the ones that were cut off by a crash.
+fn parse(text: &str) -> Vec<AuditEvent> {
+    text.lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// The file that the events are appended to, which is moved aside once it's larger than
+/// the maximum size.
+#[cfg(any(test, not(feature = "test-support")))]
+struct LogFile {
+    path: PathBuf,
+    old_path: PathBuf,
+    max_len: u64,
+    file: Option<(std::fs::File, u64)>,
+}
+
+#[cfg(any(test, not(feature = "test-support")))]
+impl LogFile {
+    fn new(path: &std::path::Path, old_path: &std::path::Path, max_len: u64) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            old_path: old_path.to_path_buf(),
+            max_len,
+            file: None,
+        }
+    }
+
+    fn append(&mut self, event: &AuditEvent) -> Result<()> {
+        use std::io::Write;
+
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+
+        if let Some((_, len)) = &self.file {
+            if *len > 0 && *len + line.len() as u64 > self.max_len {
+                self.file = None;
+                std::fs::rename(&self.path, &self.old_path)?;
+            }
+        }
+        let (file, len) = match &mut self.file {
+            Some(file) => file,
+            file @ None => {
+                if let Some(dir) = self.path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let opened = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                let len = opened.metadata()?.len();
+                file.insert((opened, len))
+            }
+        };
+        file.write_all(line.as_bytes())?;
+        *len += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Writes the events to the audit log on a thread of its own, so that recording them
+/// never blocks.
+#[cfg(not(any(test, feature = "test-support")))]
+fn spawn_writer() -> Option<std::sync::mpsc::Sender<AuditEvent>> {
+    let (tx, rx) = std::sync::mpsc::channel::<AuditEvent>();
+    std::thread::Builder::new()
+        .name("AuditLog".into())
+        .spawn(move || {
+            let mut log_file =
+                LogFile::new(&paths::AUDIT_LOG, &paths::OLD_AUDIT_LOG, MAX_LOG_BYTES);
+            for event in rx {
+                if let Err(error) = log_file.append(&event) {
+                    log::error!("failed to write to the audit log: {error}");
+                }
+            }
+        })
+        .ok()?;
+    Some(tx)
+}
+
+/// Tests never write to the audit log of the machine they run on.
+#[cfg(any(test, feature = "test-support"))]
+fn spawn_writer() -> Option<std::sync::mpsc::Sender<AuditEvent>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skips_truncated_lines() {
+        let event = AuditEvent::new(AuditEventKind::Command, "npm", "npm install prettier@3.0.0")
+            .project_root("/projects/a")
+            .details("in /support/prettier");
+        let line = serde_json::to_string(&event).unwrap();
+        let text = format!("{line}\n{}", &line[..line.len() / 2]);
+        assert_eq!(parse(&text), [event]);
+    }
+
+    #[test]
+    fn test_content_details() {
+        let content = r#"{"messages":[{"role":"user","content":"fn main() {}"}]}"#;
+        assert_eq!(content_details(content, true), content);
+
+        let details = content_details(content, false);
+        assert!(details.starts_with(&format!("{} bytes, sha256 ", content.len())));
+        assert!(!details.contains("fn main"));
+        assert_eq!(details, content_details(content, false));
+        assert_ne!(details, content_details("{}", false));
+    }
+
+    #[test]
+    fn test_log_file_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs/audit_log.jsonl");
+        let old_path = dir.path().join("logs/audit_log.jsonl.old");
+        let event = |summary: &str| AuditEvent {
+            timestamp: 0,
+            ..AuditEvent::new(AuditEventKind::Command, "npm", summary)
+        };
+        let line_len = serde_json::to_string(&event("1")).unwrap().len() as u64 + 1;
+
+        let mut log_file = LogFile::new(&path, &old_path, 2 * line_len);
+        log_file.append(&event("1")).unwrap();
+        log_file.append(&event("2")).unwrap();
+        assert!(!old_path.exists());
+
+        // The log is moved aside before it would grow past its maximum size.
+        log_file.append(&event("3")).unwrap();
+        let read = |path: &std::path::Path| parse(&std::fs::read_to_string(path).unwrap());
+        assert_eq!(read(&old_path), [event("1"), event("2")]);
+        assert_eq!(read(&path), [event("3")]);
+
+        // The size of an existing log counts when it's opened again.
+        let mut log_file = LogFile::new(&path, &old_path, 2 * line_len);
+        log_file.append(&event("4")).unwrap();
+        log_file.append(&event("5")).unwrap();
+        assert_eq!(read(&old_path), [event("3"), event("4")]);
+        assert_eq!(read(&path), [event("5")]);
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let mut events = subscribe();
+        let event = AuditEvent::new(AuditEventKind::Request, "assistant", "completion");
+        record(event.clone());
+        assert_eq!(events.try_next().unwrap(), Some(event));
+    }
+}
@@ -1,5 +1,9 @@
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
+use anyhow::Result;
 use smol::{fs, stream::StreamExt};
 
 use crate::ResultExt;
@@ -26,3 +30,114 @@ where
         }
     }
 }
+
+/// Returns the entries of `dir` that match the given predicate, from the most recently
+/// modified to the least.
+pub async fn matching_by_recency<F>(dir: &Path, predicate: F) -> Vec<PathBuf>
+where
+    F: Fn(&Path) -> bool,
+{
+    let mut matching = Vec::new();
+    if let Some(mut entries) = fs::read_dir(dir).await.log_err() {
+        while let Some(entry) = entries.next().await {
+            if let Some(entry) = entry.log_err() {
+                let entry_path = entry.path();
+                if predicate(entry_path.as_path()) {
+                    let modified = fs::metadata(&entry_path)
+                        .await
+                        .and_then(|metadata| metadata.modified())
+                        .unwrap_or(SystemTime::UNIX_EPOCH);
+                    matching.push((modified, entry_path));
+                }
+            }
+        }
+    }
+    matching.sort_by(|(a, _), (b, _)| b.cmp(a));
+    matching.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Removes all but the `keep` most recently modified files and directories matching the
+/// given predicate, along with any others that `keep_path` is inside of.
+pub async fn remove_stale_matching<F>(dir: &Path, keep: usize, keep_path: &Path, predicate: F)
+where
+    F: Fn(&Path) -> bool,
+{
+    let mut matching = matching_by_recency(dir, predicate).await;
+    let mut keep = keep;
+    if let Some(ix) = matching
+        .iter()
+        .position(|entry| keep_path.starts_with(entry))
+    {
+        matching.remove(ix);
+        keep = keep.saturating_sub(1);
+    }
+    for entry_path in matching.into_iter().skip(keep) {
+        if let Ok(metadata) = fs::metadata(&entry_path).await {
+            if metadata.is_file() {
+                fs::remove_file(&entry_path).await.log_err();
+            } else {
+                fs::remove_dir_all(&entry_path).await.log_err();
+            }
+        }
+    }
+}
+
+/// Returns how many bytes the given file, or the files in the given directory, take up.
+/// Symlinks aren't followed.
+pub async fn disk_usage(path: &Path) -> Result<u64> {
+    let mut usage = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        let metadata = fs::symlink_metadata(&path).await?;
+        if metadata.is_dir() {
+            let mut entries = fs::read_dir(&path).await?;
+            while let Some(entry) = entries.next().await {
+                pending.push(entry?.path());
+            }
+        } else {
+            usage += metadata.len();
+        }
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_remove_stale_matching() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        for (ix, name) in ["server-1", "server-2", "server-3", "server-4"]
+            .into_iter()
+            .enumerate()
+        {
+            let file = std::fs::File::create(dir.path().join(name)).unwrap();
+            file.set_modified(now - Duration::from_secs(60 * (4 - ix as u64)))
+                .unwrap();
+        }
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+
+        // The version in use is kept even when it isn't one of the most recent ones.
+        smol::block_on(remove_stale_matching(
+            dir.path(),
+            2,
+            &dir.path().join("server-1"),
+            |entry| {
+                entry
+                    .file_name()
+                    .map_or(false, |name| name.to_string_lossy().starts_with("server-"))
+            },
+        ));
+        let mut remaining = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(remaining, ["package.json", "server-1", "server-4"]);
+
+        assert_eq!(smol::block_on(disk_usage(dir.path())).unwrap(), 2);
+    }
+}
@@ -1,12 +1,54 @@
-use crate::http::HttpClient;
+use crate::{
+    archive::download_verified,
+    http::{AsyncBody, HttpClient, Request, Response, StatusCode},
+    paths,
+};
 use anyhow::{anyhow, bail, Context, Result};
 use futures::AsyncReadExt;
-use serde::Deserialize;
-use std::sync::Arc;
+use isahc::config::{Configurable, RedirectPolicy};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+lazy_static::lazy_static! {
+    static ref GITHUB_TOKEN: RwLock<Option<String>> = RwLock::new(None);
+}
+
+/// Sets the token that GitHub API requests are authenticated with, which raises their
+/// rate limit from 60 requests an hour per IP address to 5000 an hour per user. When no
+/// token is set, the `GITHUB_TOKEN` environment variable is used, if present.
+pub fn set_github_token(token: Option<String>) {
+    *GITHUB_TOKEN.write() = token;
+}
+
+fn github_token() -> Option<String> {
+    GITHUB_TOKEN.read().clone().or_else(|| {
+        std::env::var("GITHUB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+    })
+}
 
 pub struct GitHubLspBinaryVersion {
     pub name: String,
     pub url: String,
+    /// The SHA-256 checksum of the download, when GitHub reports one.
+    pub sha256: Option<String>,
+}
+
+impl GitHubLspBinaryVersion {
+    pub fn new(name: String, asset: &GithubReleaseAsset) -> Self {
+        Self {
+            name,
+            url: asset.browser_download_url.clone(),
+            sha256: asset.sha256().map(Into::into),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -23,6 +65,25 @@ pub struct GithubRelease {
 pub struct GithubReleaseAsset {
     pub name: String,
     pub browser_download_url: String,
+    /// The asset's digest, such as `sha256:<hex>`, which older releases don't have.
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl GithubReleaseAsset {
+    pub fn sha256(&self) -> Option<&str> {
+        self.digest.as_deref()?.strip_prefix("sha256:")
+    }
+}
+
+/// Downloads a language server release, verified against its checksum as described in
+/// [`download_verified`]. The response's body is the whole download.
+pub async fn download_release(
+    http: &dyn HttpClient,
+    version: &GitHubLspBinaryVersion,
+) -> Result<Response<AsyncBody>> {
+    let bytes = download_verified(http, &version.url, version.sha256.as_deref()).await?;
+    Ok(Response::new(AsyncBody::from(bytes)))
 }
 
 pub async fn latest_github_release(
@@ -31,29 +92,12 @@ pub async fn latest_github_release(
     pre_release: bool,
     http: Arc<dyn HttpClient>,
 ) -> Result<GithubRelease, anyhow::Error> {
-    let mut response = http
-        .get(
-            &format!("https://api.github.com/repos/{repo_name_with_owner}/releases"),
-            Default::default(),
-            true,
-        )
-        .await
-        .context("error fetching latest release")?;
-
-    let mut body = Vec::new();
-    response
-        .body_mut()
-        .read_to_end(&mut body)
-        .await
-        .context("error reading latest release")?;
-
-    if response.status().is_client_error() {
-        let text = String::from_utf8_lossy(body.as_slice());
-        bail!(
-            "status error {}, response: {text:?}",
-            response.status().as_u16()
-        );
-    }
+    let body = fetch_github_api(
+        &format!("{GITHUB_API_URL}/repos/{repo_name_with_owner}/releases"),
+        &*http,
+    )
+    .await
+    .context("error fetching latest release")?;
 
     let releases = match serde_json::from_slice::<Vec<GithubRelease>>(body.as_slice()) {
         Ok(releases) => releases,
@@ -74,3 +118,242 @@ pub async fn latest_github_release(
         .find(|release| release.pre_release == pre_release)
         .ok_or(anyhow!("Failed to find a release"))
 }
+
+pub async fn github_release_with_tag(
+    repo_name_with_owner: &str,
+    tag: &str,
+    http: Arc<dyn HttpClient>,
+) -> Result<GithubRelease, anyhow::Error> {
+    let body = fetch_github_api(
+        &format!("{GITHUB_API_URL}/repos/{repo_name_with_owner}/releases/tags/{tag}"),
+        &*http,
+    )
+    .await
+    .with_context(|| format!("error fetching release {tag}"))?;
+
+    serde_json::from_slice(body.as_slice())
+        .with_context(|| format!("error deserializing release {tag}"))
+}
+
+/// A GitHub API response, kept so that it can be revalidated with its ETag, which
+/// doesn't count against the rate limit, and reused when the rate limit is exceeded.
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    etag: String,
+    body: String,
+}
+
+async fn fetch_github_api(url: &str, http: &dyn HttpClient) -> Result<Vec<u8>> {
+    fetch_with_cache(url, &cache_path(url), http).await
+}
+
+async fn fetch_with_cache(url: &str, cache_path: &Path, http: &dyn HttpClient) -> Result<Vec<u8>> {
+    let cached_response = smol::fs::read(cache_path)
+        .await
+        .ok()
+        .and_then(|cached| serde_json::from_slice::<CachedResponse>(&cached).ok());
+
+    let mut request = Request::get(url)
+        .redirect_policy(RedirectPolicy::Follow)
+        .header("Accept", "application/vnd.github+json");
+    if let Some(token) = github_token() {
+        request = request.header("Authorization", format!("Bearer {token}"));
+    }
+    if let Some(cached_response) = &cached_response {
+        request = request.header("If-None-Match", cached_response.etag.as_str());
+    }
+
+    let mut response = match http.send(request.body(AsyncBody::default())?).await {
+        Ok(response) => response,
+        Err(error) => {
+            if let Some(cached_response) = cached_response {
+                log::warn!("using cached response for {url}: {error}");
+                return Ok(cached_response.body.into_bytes());
+            }
+            return Err(error.into());
+        }
+    };
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED {
+        if let Some(cached_response) = cached_response {
+            return Ok(cached_response.body.into_bytes());
+        }
+    }
+
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+
+    if status.is_client_error() {
+        let is_rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN
+                && response
+                    .headers()
+                    .get("x-ratelimit-remaining")
+                    .map_or(false, |remaining| remaining == "0"));
+        if is_rate_limited {
+            if let Some(cached_response) = cached_response {
+                log::warn!("GitHub API rate limit exceeded, using cached response for {url}");
+                return Ok(cached_response.body.into_bytes());
+            }
+        }
+        let text = String::from_utf8_lossy(body.as_slice());
+        bail!("status error {}, response: {text:?}", status.as_u16());
+    }
+
+    if let Some(etag) = response
+        .headers()
+        .get("etag")
+        .and_then(|etag| etag.to_str().ok())
+    {
+        if let Ok(body) = String::from_utf8(body.clone()) {
+            let cached_response = CachedResponse {
+                etag: etag.to_string(),
+                body,
+            };
+            if let Err(error) = write_cached_response(cache_path, &cached_response).await {
+                log::warn!("failed to cache response for {url}: {error:?}");
+            }
+        }
+    }
+
+    Ok(body)
+}
+
+fn cache_path(url: &str) -> PathBuf {
+    let file_name = url
+        .trim_start_matches(GITHUB_API_URL)
+        .trim_matches('/')
+        .replace('/', "_");
+    paths::GITHUB_CACHE_DIR.join(format!("{file_name}.json"))
+}
+
+async fn write_cached_response(path: &Path, cached_response: &CachedResponse) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        smol::fs::create_dir_all(dir).await?;
+    }
+    smol::fs::write(path, serde_json::to_vec(cached_response)?).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Error;
+    use futures::future::BoxFuture;
+
+    /// Checks each request and responds with a fixed status, headers and body, or
+    /// fails like an unreachable network when no status is given.
+    struct FakeGitHub {
+        check_request: fn(&Request<AsyncBody>),
+        status: Option<u16>,
+        headers: &'static [(&'static str, &'static str)],
+        body: &'static str,
+    }
+
+    impl HttpClient for FakeGitHub {
+        fn send(
+            &self,
+            request: Request<AsyncBody>,
+        ) -> BoxFuture<Result<Response<AsyncBody>, Error>> {
+            (self.check_request)(&request);
+            let response = self.status.map(|status| {
+                let mut response = Response::builder().status(status);
+                for (name, value) in self.headers {
+                    response = response.header(*name, *value);
+                }
+                response.body(AsyncBody::from(self.body)).unwrap()
+            });
+            Box::pin(async move {
+                response.ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::NotConnected, "offline").into()
+                })
+            })
+        }
+    }
+
+    #[test]
+    fn test_cache_path() {
+        assert_eq!(
+            cache_path("https://api.github.com/repos/rust-lang/rust-analyzer/releases"),
+            paths::GITHUB_CACHE_DIR.join("repos_rust-lang_rust-analyzer_releases.json")
+        );
+    }
+
+    #[test]
+    fn test_fetch_with_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join("releases.json");
+        let url = "https://api.github.com/repos/owner/repo/releases";
+        set_github_token(Some("the-token".into()));
+
+        smol::block_on(async {
+            // The first response is authenticated and cached along with its ETag.
+            let http = FakeGitHub {
+                check_request: |request| {
+                    assert_eq!(request.headers()["Authorization"], "Bearer the-token");
+                    assert!(request.headers().get("If-None-Match").is_none());
+                },
+                status: Some(200),
+                headers: &[("ETag", "\"v1\"")],
+                body: "[1]",
+            };
+            let body = fetch_with_cache(url, &cache_path, &http).await.unwrap();
+            assert_eq!(body, b"[1]");
+
+            // Unchanged responses are revalidated with the ETag and read from the cache.
+            let http = FakeGitHub {
+                check_request: |request| {
+                    assert_eq!(request.headers()["If-None-Match"], "\"v1\"");
+                },
+                status: Some(304),
+                headers: &[],
+                body: "",
+            };
+            let body = fetch_with_cache(url, &cache_path, &http).await.unwrap();
+            assert_eq!(body, b"[1]");
+
+            // The cache is reused when the rate limit is exceeded or the network is down.
+            let http = FakeGitHub {
+                check_request: |_| {},
+                status: Some(403),
+                headers: &[("x-ratelimit-remaining", "0")],
+                body: "rate limit exceeded",
+            };
+            let body = fetch_with_cache(url, &cache_path, &http).await.unwrap();
+            assert_eq!(body, b"[1]");
+            let http = FakeGitHub {
+                check_request: |_| {},
+                status: None,
+                headers: &[],
+                body: "",
+            };
+            let body = fetch_with_cache(url, &cache_path, &http).await.unwrap();
+            assert_eq!(body, b"[1]");
+
+            // Other errors aren't hidden by the cache.
+            let http = FakeGitHub {
+                check_request: |_| {},
+                status: Some(404),
+                headers: &[],
+                body: "not found",
+            };
+            assert!(fetch_with_cache(url, &cache_path, &http).await.is_err());
+
+            // Changed responses replace the cached one.
+            let http = FakeGitHub {
+                check_request: |_| {},
+                status: Some(200),
+                headers: &[("ETag", "\"v2\"")],
+                body: "[2]",
+            };
+            let body = fetch_with_cache(url, &cache_path, &http).await.unwrap();
+            assert_eq!(body, b"[2]");
+            let cached: CachedResponse =
+                serde_json::from_slice(&std::fs::read(&cache_path).unwrap()).unwrap();
+            assert_eq!(cached.etag, "\"v2\"");
+            assert_eq!(cached.body, "[2]");
+        });
+
+        set_github_token(None);
+    }
+}
@@ -12,6 +12,7 @@ lazy_static::lazy_static! {
     pub static ref CONVERSATIONS_DIR: PathBuf = CONFIG_DIR.join("conversations");
     pub static ref EMBEDDINGS_DIR: PathBuf = CONFIG_DIR.join("embeddings");
     pub static ref THEMES_DIR: PathBuf = CONFIG_DIR.join("themes");
+    pub static ref USER_LANGUAGES_DIR: PathBuf = CONFIG_DIR.join("languages");
     pub static ref LOGS_DIR: PathBuf = if cfg!(target_os = "macos") {
         HOME.join("Library/Logs/Zed")
     } else {
@@ -24,9 +25,15 @@ lazy_static::lazy_static! {
     };
     pub static ref EXTENSIONS_DIR: PathBuf = SUPPORT_DIR.join("extensions");
     pub static ref LANGUAGES_DIR: PathBuf = SUPPORT_DIR.join("languages");
+    pub static ref QUARANTINE_DIR: PathBuf = SUPPORT_DIR.join("quarantine");
     pub static ref COPILOT_DIR: PathBuf = SUPPORT_DIR.join("copilot");
+    pub static ref GITHUB_CACHE_DIR: PathBuf = SUPPORT_DIR.join("github");
+    pub static ref REMOTE_FILES_DIR: PathBuf = SUPPORT_DIR.join("remote_files");
     pub static ref DEFAULT_PRETTIER_DIR: PathBuf = SUPPORT_DIR.join("prettier");
     pub static ref DB_DIR: PathBuf = SUPPORT_DIR.join("db");
+    pub static ref TIME_LOG: PathBuf = SUPPORT_DIR.join("time_log.json");
+    pub static ref AUDIT_LOG: PathBuf = SUPPORT_DIR.join("audit_log.jsonl");
+    pub static ref OLD_AUDIT_LOG: PathBuf = SUPPORT_DIR.join("audit_log.jsonl.old");
     pub static ref CRASHES_DIR: PathBuf = if cfg!(target_os = "macos") {
         HOME.join("Library/Logs/DiagnosticReports")
     } else {
@@ -39,6 +46,7 @@ lazy_static::lazy_static! {
     };
     pub static ref SETTINGS: PathBuf = CONFIG_DIR.join("settings.json");
     pub static ref KEYMAP: PathBuf = CONFIG_DIR.join("keymap.json");
+    pub static ref PINNED_CHECKSUMS: PathBuf = CONFIG_DIR.join("checksums.json");
     pub static ref LAST_USERNAME: PathBuf = CONFIG_DIR.join("last-username.txt");
     pub static ref LOG: PathBuf = LOGS_DIR.join("Zed.log");
     pub static ref OLD_LOG: PathBuf = LOGS_DIR.join("Zed.log.old");
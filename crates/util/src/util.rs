@@ -1,4 +1,6 @@
 pub mod arc_cow;
+pub mod archive;
+pub mod audit_log;
 pub mod fs;
 pub mod github;
 pub mod http;
@@ -0,0 +1,549 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use editor::{CommitView, Editor, MultiBuffer};
+use fs::repository::{CommitDetails, GitRepository, RepoPath};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, Model, ParentElement, Render, Styled, Subscription, Task,
+    View, ViewContext, VisualContext, WeakView,
+};
+use parking_lot::Mutex;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use ui::{
+    h_flex, v_flex, Color, HighlightedLabel, Label, LabelCommon, LabelSize, ListItem,
+    ListItemSpacing, Selectable,
+};
+use util::ResultExt;
+use workspace::{ModalView, Toast, Workspace};
+
+actions!(file, [ShowHistory]);
+
+/// The number of commits that are examined at a time when loading a file's history.
+/// The repository is unlocked between pages.
+const FILE_HISTORY_PAGE_SIZE: usize = 500;
+
+/// The most commits that are examined when loading a file's history.
+const MAX_FILE_HISTORY_DEPTH: usize = 20_000;
+
+pub(crate) fn init(workspace: &mut Workspace) {
+    workspace.register_action(FileHistory::toggle);
+}
+
+/// A picker listing the commits that changed the active file.
+///
+/// Confirming a commit opens the changes it made to the file, while a secondary
+/// confirmation opens the file's contents at that commit in a read-only buffer.
+pub struct FileHistory {
+    picker: View<Picker<FileHistoryDelegate>>,
+    _load_older_commits: Option<Task<anyhow::Result<()>>>,
+    _subscription: Subscription,
+}
+
+impl FileHistory {
+    fn new(delegate: FileHistoryDelegate, has_more: bool, cx: &mut ViewContext<Self>) -> Self {
+        let load_older_commits = has_more.then(|| {
+            Self::load_older_commits(delegate.repository.clone(), delegate.repo_path.clone(), cx)
+        });
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _load_older_commits: load_older_commits,
+            _subscription,
+        }
+    }
+
+    /// Loads the file's history after the first page, one page at a time, and appends
+    /// it to the listed commits.
+    fn load_older_commits(
+        repository: Arc<Mutex<dyn GitRepository>>,
+        repo_path: RepoPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        cx.spawn(|this, mut cx| async move {
+            let mut skip = FILE_HISTORY_PAGE_SIZE;
+            while skip < MAX_FILE_HISTORY_DEPTH {
+                let page = cx
+                    .background_executor()
+                    .spawn({
+                        let repository = repository.clone();
+                        let repo_path = repo_path.clone();
+                        async move {
+                            repository
+                                .lock()
+                                .file_history(&repo_path, skip, FILE_HISTORY_PAGE_SIZE)
+                        }
+                    })
+                    .await?;
+                skip += FILE_HISTORY_PAGE_SIZE;
+                this.update(&mut cx, |this, cx| {
+                    this.picker.update(cx, |picker, cx| {
+                        picker.delegate.commits.extend(page.commits);
+                        picker.refresh(cx);
+                    })
+                })?;
+                if !page.has_more {
+                    break;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn toggle(workspace: &mut Workspace, _: &ShowHistory, cx: &mut ViewContext<Workspace>) {
+        const FILE_HISTORY_FAILURE_ID: usize = 2049;
+
+        let project = workspace.project().clone();
+        let repository = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+            .and_then(|project_path| project.read(cx).git_repository_for_path(&project_path, cx));
+        let Some((repository, repo_path)) = repository else {
+            workspace.show_toast(
+                Toast::new(
+                    FILE_HISTORY_FAILURE_ID,
+                    "The active file is not tracked in a git repository",
+                ),
+                cx,
+            );
+            return;
+        };
+
+        let history = cx.background_executor().spawn({
+            let repository = repository.clone();
+            let repo_path = repo_path.clone();
+            async move {
+                repository
+                    .lock()
+                    .file_history(&repo_path, 0, FILE_HISTORY_PAGE_SIZE)
+            }
+        });
+        cx.spawn(|workspace, mut cx| async move {
+            let page = history.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let delegate = FileHistoryDelegate {
+                    workspace: cx.view().downgrade(),
+                    project,
+                    repository,
+                    repo_path,
+                    commits: page.commits,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                };
+                workspace.toggle_modal(cx, |cx| FileHistory::new(delegate, page.has_more, cx));
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl ModalView for FileHistory {}
+impl EventEmitter<DismissEvent> for FileHistory {}
+
+impl FocusableView for FileHistory {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for FileHistory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+pub struct FileHistoryDelegate {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    repository: Arc<Mutex<dyn GitRepository>>,
+    repo_path: RepoPath,
+    commits: Vec<CommitDetails>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl FileHistoryDelegate {
+    fn open_revision(&self, commit: &CommitDetails, cx: &mut ViewContext<Picker<Self>>) {
+        let sha = commit.sha.clone();
+        let title = format!(
+            "{} @ {}",
+            self.repo_path.file_name().map_or_else(
+                || self.repo_path.to_string_lossy(),
+                |name| name.to_string_lossy()
+            ),
+            commit.short_sha()
+        );
+        let repo_path = self.repo_path.clone();
+        let contents = cx.background_executor().spawn({
+            let repository = self.repository.clone();
+            let repo_path = repo_path.clone();
+            async move { repository.lock().file_at_revision(&sha, &repo_path) }
+        });
+        let language = self
+            .project
+            .read(cx)
+            .languages()
+            .language_for_file(&repo_path.0, None);
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            let contents = contents
+                .await?
+                .ok_or_else(|| anyhow!("{title} does not exist"))?;
+            let language = language.await.ok();
+            let buffer = project.update(&mut cx, |project, cx| {
+                project.create_buffer(&contents, language, cx)
+            })??;
+            workspace.update(&mut cx, |workspace, cx| {
+                let buffer =
+                    cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                let editor = cx.new_view(|cx| {
+                    let mut editor = Editor::for_multibuffer(buffer, Some(project), cx);
+                    editor.set_read_only(true);
+                    editor
+                });
+                workspace.add_item(Box::new(editor), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for FileHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Search commits...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .commits
+            .iter()
+            .enumerate()
+            .map(|(ix, commit)| {
+                let string = format!("{} {}", commit.short_sha(), commit.summary());
+                StringMatchCandidate {
+                    id: ix,
+                    char_bag: string.chars().collect(),
+                    string,
+                }
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(commit) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.commits.get(hit.candidate_id))
+        else {
+            return;
+        };
+
+        if secondary {
+            self.open_revision(commit, cx);
+        } else {
+            CommitView::open_for_path(
+                commit.sha.clone(),
+                self.repo_path.clone(),
+                self.repository.clone(),
+                self.workspace.clone(),
+                cx,
+            )
+            .detach_and_log_err(cx);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = &self.matches[ix];
+        let commit = &self.commits[hit.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(commit.author_name.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+
+    fn render_header(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        Some(
+            h_flex()
+                .ml_3()
+                .child(
+                    Label::new(format!("History of {}", self.repo_path.display()))
+                        .size(LabelSize::Small),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use menu::{Confirm, SecondaryConfirm};
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::{AppState, Item};
+
+    #[gpui::test]
+    async fn test_file_history(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({
+                    ".git": {},
+                    "main.rs": "fn main() {}\n",
+                }),
+            )
+            .await;
+        app_state
+            .fs
+            .as_fake()
+            .with_git_state(Path::new("/root/.git"), false, |state| {
+                let path = RepoPath::from(Path::new("main.rs"));
+                for sha in ["aaa", "bbb", "ccc"] {
+                    state.commits.insert(sha.into(), commit(sha));
+                }
+                state
+                    .file_histories
+                    .insert(path.clone(), vec!["ccc".into(), "aaa".into()]);
+                state.revisions.insert(
+                    "aaa".into(),
+                    [(path, "fn old() {}\n".to_string())].into_iter().collect(),
+                );
+            });
+
+        // Only the commits that changed the file are listed, newest first.
+        let (workspace, cx) = open_file(&app_state, "main.rs", cx).await;
+        let history = show_history(&workspace, cx).unwrap();
+        assert_eq!(
+            listed_commits(&history, cx),
+            ["ccc Change ccc", "aaa Change aaa"]
+        );
+
+        // Confirming a commit opens the changes it made.
+        cx.simulate_input("aaa");
+        assert_eq!(listed_commits(&history, cx), ["aaa Change aaa"]);
+        cx.dispatch_action(Confirm);
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, cx| workspace
+            .active_modal::<FileHistory>(cx)
+            .is_none()));
+        let commit_view = workspace
+            .update(cx, |workspace, cx| {
+                workspace.active_item_as::<CommitView>(cx)
+            })
+            .unwrap();
+        assert_eq!(
+            commit_view.update(cx, |commit_view, cx| commit_view.tab_tooltip_text(cx)),
+            Some("Change aaa".into())
+        );
+
+        // A secondary confirmation opens the file as of the commit, read-only.
+        show_history(&workspace, cx).unwrap();
+        cx.simulate_input("aaa");
+        cx.dispatch_action(SecondaryConfirm);
+        cx.run_until_parked();
+        let editor = workspace
+            .update(cx, |workspace, cx| workspace.active_item_as::<Editor>(cx))
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            assert_eq!(editor.title(cx), "main.rs @ aaa");
+            assert_eq!(editor.text(cx), "fn old() {}\n");
+            assert!(editor.read_only(cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_file_history_without_commits(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({
+                    "repo": {
+                        ".git": {},
+                        "new.rs": "",
+                    },
+                    "untracked.rs": "",
+                }),
+            )
+            .await;
+
+        // A file that no commit changed yet has an empty history.
+        let (workspace, cx) = open_file(&app_state, "repo/new.rs", cx).await;
+        let history = show_history(&workspace, cx).unwrap();
+        assert!(listed_commits(&history, cx).is_empty());
+        cx.dispatch_action(menu::Cancel);
+
+        // A file outside of any repository has no history to show.
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .worktrees()
+                .next()
+                .unwrap()
+                .read(cx)
+                .id()
+        });
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "untracked.rs"), None, true, cx)
+            })
+            .await
+            .unwrap();
+        assert!(show_history(&workspace, cx).is_none());
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+            state
+        })
+    }
+
+    fn commit(sha: &str) -> CommitDetails {
+        CommitDetails {
+            sha: sha.into(),
+            author_name: "Ada".into(),
+            message: format!("Change {sha}\n\nDetails"),
+            ..Default::default()
+        }
+    }
+
+    async fn open_file<'a>(
+        app_state: &Arc<AppState>,
+        path: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (View<Workspace>, &'a mut VisualTestContext) {
+        let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        cx.run_until_parked();
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, path), None, true, cx)
+            })
+            .await
+            .unwrap();
+        (workspace, cx)
+    }
+
+    fn show_history(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Option<View<Picker<FileHistoryDelegate>>> {
+        cx.dispatch_action(ShowHistory);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<FileHistory>(cx)
+                .map(|history| history.read(cx).picker.clone())
+        })
+    }
+
+    fn listed_commits(
+        history: &View<Picker<FileHistoryDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        cx.run_until_parked();
+        history.update(cx, |history, _| {
+            history
+                .delegate
+                .matches
+                .iter()
+                .map(|hit| hit.string.clone())
+                .collect()
+        })
+    }
+}
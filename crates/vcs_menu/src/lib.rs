@@ -15,6 +15,10 @@ use ui::{
 use util::ResultExt;
 use workspace::{ModalView, Toast, Workspace};
 
+mod file_history;
+
+pub use file_history::{FileHistory, ShowHistory};
+
 actions!(branches, [OpenRecent]);
 
 pub fn init(cx: &mut AppContext) {
@@ -22,6 +26,7 @@ pub fn init(cx: &mut AppContext) {
         workspace.register_action(|workspace, action, cx| {
             BranchList::toggle_modal(workspace, action, cx).log_err();
         });
+        file_history::init(workspace);
     })
     .detach();
 }
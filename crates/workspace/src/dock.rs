@@ -1,6 +1,6 @@
 use crate::persistence::model::DockData;
 use crate::DraggedDock;
-use crate::{status_bar::StatusItemView, Workspace};
+use crate::{status_bar::StatusItemView, Pane, Workspace};
 use gpui::{
     div, px, Action, AnchorCorner, AnyView, AppContext, Axis, ClickEvent, Entity, EntityId,
     EventEmitter, FocusHandle, FocusableView, IntoElement, KeyContext, MouseButton, ParentElement,
@@ -44,6 +44,10 @@ pub trait Panel: FocusableView + EventEmitter<PanelEvent> {
     }
     fn set_zoomed(&mut self, _zoomed: bool, _cx: &mut ViewContext<Self>) {}
     fn set_active(&mut self, _active: bool, _cx: &mut ViewContext<Self>) {}
+    /// Returns the pane that the panel keeps its items in, if it has one.
+    fn pane(&self) -> Option<View<Pane>> {
+        None
+    }
 }
 
 pub trait PanelHandle: Send + Sync {
@@ -62,6 +66,7 @@ pub trait PanelHandle: Send + Sync {
     fn toggle_action(&self, cx: &WindowContext) -> Box<dyn Action>;
     fn icon_label(&self, cx: &WindowContext) -> Option<String>;
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle;
+    fn pane(&self, cx: &AppContext) -> Option<View<Pane>>;
     fn to_any(&self) -> AnyView;
 }
 
@@ -132,6 +137,10 @@ where
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
         self.read(cx).focus_handle(cx).clone()
     }
+
+    fn pane(&self, cx: &AppContext) -> Option<View<Pane>> {
+        self.read(cx).pane()
+    }
 }
 
 impl From<&dyn PanelHandle> for AnyView {
@@ -283,6 +292,14 @@ impl Dock {
             .position(|entry| entry.panel.persistent_name() == ui_name)
     }
 
+    /// Returns the panes of the dock's panels.
+    pub fn panes(&self, cx: &AppContext) -> Vec<View<Pane>> {
+        self.panel_entries
+            .iter()
+            .filter_map(|entry| entry.panel.pane(cx))
+            .collect()
+    }
+
     pub fn active_panel_index(&self) -> usize {
         self.active_panel_index
     }
@@ -143,6 +143,11 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn has_conflict(&self, _: &AppContext) -> bool {
         false
     }
+    /// Returns whether the item runs a process that closing it would kill, which the
+    /// user is warned about before quitting.
+    fn has_running_process(&self, _: &AppContext) -> bool {
+        false
+    }
     fn can_save(&self, _cx: &AppContext) -> bool {
         false
     }
@@ -257,6 +262,7 @@ pub trait ItemHandle: 'static + Send {
     fn to_any(&self) -> AnyView;
     fn is_dirty(&self, cx: &AppContext) -> bool;
     fn has_conflict(&self, cx: &AppContext) -> bool;
+    fn has_running_process(&self, cx: &AppContext) -> bool;
     fn can_save(&self, cx: &AppContext) -> bool;
     fn save(&self, project: Model<Project>, cx: &mut WindowContext) -> Task<Result<()>>;
     fn save_as(
@@ -562,6 +568,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).has_conflict(cx)
     }
 
+    fn has_running_process(&self, cx: &AppContext) -> bool {
+        self.read(cx).has_running_process(cx)
+    }
+
     fn can_save(&self, cx: &AppContext) -> bool {
         self.read(cx).can_save(cx)
     }
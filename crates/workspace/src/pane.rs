@@ -106,6 +106,10 @@ actions!(
         SplitUp,
         SplitRight,
         SplitDown,
+        SplitAndMoveLeft,
+        SplitAndMoveUp,
+        SplitAndMoveRight,
+        SplitAndMoveDown,
     ]
 );
 
@@ -117,6 +121,7 @@ pub enum Event {
     Remove,
     RemoveItem { item_id: EntityId },
     Split(SplitDirection),
+    SplitAndMove(SplitDirection),
     ChangeItemTitle,
     Focus,
     ZoomIn,
@@ -307,6 +312,11 @@ impl Pane {
                                         .action("Split Left", SplitLeft.boxed_clone())
                                         .action("Split Up", SplitUp.boxed_clone())
                                         .action("Split Down", SplitDown.boxed_clone())
+                                        .separator()
+                                        .action("Move Right", SplitAndMoveRight.boxed_clone())
+                                        .action("Move Left", SplitAndMoveLeft.boxed_clone())
+                                        .action("Move Up", SplitAndMoveUp.boxed_clone())
+                                        .action("Move Down", SplitAndMoveDown.boxed_clone())
                                 });
                                 cx.subscribe(&menu, |pane, _, _: &DismissEvent, cx| {
                                     pane.focus(cx);
@@ -1220,10 +1230,16 @@ impl Pane {
         }
     }
 
+    /// Splits the pane, duplicating the active item into the new pane.
     pub fn split(&mut self, direction: SplitDirection, cx: &mut ViewContext<Self>) {
         cx.emit(Event::Split(direction));
     }
 
+    /// Splits the pane, moving the active item into the new pane.
+    pub fn split_and_move(&mut self, direction: SplitDirection, cx: &mut ViewContext<Self>) {
+        cx.emit(Event::SplitAndMove(direction));
+    }
+
     pub fn toolbar(&self) -> &View<Toolbar> {
         &self.toolbar
     }
@@ -1752,6 +1768,18 @@ impl Render for Pane {
                 cx.listener(|pane, _: &SplitRight, cx| pane.split(SplitDirection::Right, cx)),
             )
             .on_action(cx.listener(|pane, _: &SplitDown, cx| pane.split(SplitDirection::Down, cx)))
+            .on_action(cx.listener(|pane, _: &SplitAndMoveLeft, cx| {
+                pane.split_and_move(SplitDirection::Left, cx)
+            }))
+            .on_action(cx.listener(|pane, _: &SplitAndMoveUp, cx| {
+                pane.split_and_move(SplitDirection::Up, cx)
+            }))
+            .on_action(cx.listener(|pane, _: &SplitAndMoveRight, cx| {
+                pane.split_and_move(SplitDirection::Right, cx)
+            }))
+            .on_action(cx.listener(|pane, _: &SplitAndMoveDown, cx| {
+                pane.split_and_move(SplitDirection::Down, cx)
+            }))
             .on_action(cx.listener(|pane, _: &GoBack, cx| pane.navigate_backward(cx)))
             .on_action(cx.listener(|pane, _: &GoForward, cx| pane.navigate_forward(cx)))
             .on_action(cx.listener(Pane::toggle_zoom))
@@ -2062,6 +2090,74 @@ impl NavHistory {
     pub fn path_for_item(&self, item_id: EntityId) -> Option<(ProjectPath, Option<PathBuf>)> {
         self.0.lock().paths_by_item.get(&item_id).cloned()
     }
+
+    /// Calls the given callback with each closed item that can be reopened, from the
+    /// least to the most recently closed one.
+    pub fn for_each_closed_entry(
+        &self,
+        mut f: impl FnMut(&NavigationEntry, (ProjectPath, Option<PathBuf>)),
+    ) {
+        let state = self.0.lock();
+        for entry in &state.closed_stack {
+            if let Some(project_and_abs_path) = state.paths_by_item.get(&entry.item.id()) {
+                f(entry, project_and_abs_path.clone());
+            }
+        }
+    }
+
+    pub fn last_closed_timestamp(&self) -> Option<usize> {
+        self.0
+            .lock()
+            .closed_stack
+            .back()
+            .map(|entry| entry.timestamp)
+    }
+
+    /// Moves the most recently closed entry for the given path to the top of the closed stack,
+    /// so that it's the next one to be reopened. Returns `false` if there is no such entry.
+    pub fn bump_closed_entry(&mut self, project_path: &ProjectPath) -> bool {
+        let state = &mut *self.0.lock();
+        let Some(index) = state.closed_stack.iter().rposition(|entry| {
+            state
+                .paths_by_item
+                .get(&entry.item.id())
+                .map_or(false, |(path, _)| path == project_path)
+        }) else {
+            return false;
+        };
+        if let Some(mut entry) = state.closed_stack.remove(index) {
+            entry.timestamp = state.next_timestamp.fetch_add(1, Ordering::SeqCst);
+            state.closed_stack.push_back(entry);
+        }
+        true
+    }
+
+    /// Takes over the closed items of another history, so that they can still be reopened
+    /// after the pane owning that history is removed.
+    pub fn adopt_closed_entries(&mut self, other: &NavHistory) {
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return;
+        }
+
+        let mut other = other.0.lock();
+        let other = &mut *other;
+        let mut state = self.0.lock();
+        for entry in other.closed_stack.drain(..) {
+            if let Some(project_and_abs_path) = other.paths_by_item.get(&entry.item.id()) {
+                state
+                    .paths_by_item
+                    .insert(entry.item.id(), project_and_abs_path.clone());
+                state.closed_stack.push_back(entry);
+            }
+        }
+        state
+            .closed_stack
+            .make_contiguous()
+            .sort_by_key(|entry| entry.timestamp);
+        while state.closed_stack.len() > MAX_NAVIGATION_HISTORY_LEN {
+            state.closed_stack.pop_front();
+        }
+    }
 }
 
 impl NavHistoryState {
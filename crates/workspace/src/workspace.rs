@@ -166,6 +166,7 @@ impl_actions!(
         OpenTerminal,
         Save,
         SaveAll,
+        SpawnInTerminal,
         SwapPaneInDirection,
     ]
 );
@@ -220,6 +221,15 @@ pub struct OpenTerminal {
     pub working_directory: PathBuf,
 }
 
+/// Runs a command in a new terminal, e.g. a test requested by a language server.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct SpawnInTerminal {
+    pub label: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
 pub type WorkspaceId = i64;
 
 pub fn init_settings(cx: &mut AppContext) {
@@ -1074,12 +1084,87 @@ impl Workspace {
         self.navigate_history(pane, NavigationMode::GoingForward, cx)
     }
 
+    /// Reopens the most recently closed item in the pane it was closed from.
     pub fn reopen_closed_item(&mut self, cx: &mut ViewContext<Workspace>) -> Task<Result<()>> {
-        self.navigate_history(
-            self.active_pane().downgrade(),
-            NavigationMode::ReopeningClosedItem,
-            cx,
-        )
+        let pane = self
+            .panes
+            .iter()
+            .filter_map(|pane| {
+                let timestamp = pane.read(cx).nav_history().last_closed_timestamp()?;
+                Some((pane, timestamp))
+            })
+            .max_by_key(|(_, timestamp)| *timestamp)
+            .map_or(&self.active_pane, |(pane, _)| pane)
+            .downgrade();
+        self.navigate_history(pane, NavigationMode::ReopeningClosedItem, cx)
+    }
+
+    /// Returns the paths of the closed items that can be reopened, most recently closed first.
+    /// Paths that are currently open in any pane are omitted.
+    pub fn recently_closed_paths(&self, cx: &AppContext) -> Vec<ProjectPath> {
+        let open_paths = self
+            .panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).items())
+            .filter_map(|item| item.project_path(cx))
+            .collect::<HashSet<_>>();
+        let mut closed: HashMap<ProjectPath, usize> = HashMap::default();
+        for pane in &self.panes {
+            pane.read(cx)
+                .nav_history()
+                .for_each_closed_entry(|entry, (project_path, _)| {
+                    if !open_paths.contains(&project_path) {
+                        let timestamp = closed.entry(project_path).or_default();
+                        *timestamp = (*timestamp).max(entry.timestamp);
+                    }
+                });
+        }
+
+        closed
+            .into_iter()
+            .sorted_by_key(|(_, timestamp)| cmp::Reverse(*timestamp))
+            .map(|(project_path, _)| project_path)
+            .collect()
+    }
+
+    /// Reopens the most recently closed item with the given path, restoring its previous state.
+    /// Opens the path afresh if no closed item matches it.
+    pub fn reopen_closed_path(
+        &mut self,
+        project_path: ProjectPath,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        let pane = self
+            .panes
+            .iter()
+            .filter_map(|pane| {
+                let mut latest_timestamp = None;
+                pane.read(cx)
+                    .nav_history()
+                    .for_each_closed_entry(|entry, (closed_path, _)| {
+                        if closed_path == project_path {
+                            latest_timestamp = Some(entry.timestamp);
+                        }
+                    });
+                Some((pane.clone(), latest_timestamp?))
+            })
+            .max_by_key(|(_, timestamp)| *timestamp)
+            .map(|(pane, _)| pane);
+        let Some(pane) = pane.filter(|pane| {
+            pane.update(cx, |pane, _| {
+                pane.nav_history_mut().bump_closed_entry(&project_path)
+            })
+        }) else {
+            return self.open_path(project_path, None, true, cx);
+        };
+
+        let reopen =
+            self.navigate_history(pane.downgrade(), NavigationMode::ReopeningClosedItem, cx);
+        cx.spawn(|_, mut cx| async move {
+            reopen.await?;
+            pane.update(&mut cx, |pane, _| pane.active_item())?
+                .context("failed to reopen closed item")
+        })
     }
 
     pub fn client(&self) -> &Client {
@@ -1502,6 +1587,30 @@ impl Workspace {
         self.panes.iter().flat_map(|pane| pane.read(cx).items())
     }
 
+    /// Returns how many items of the workspace, including the ones in its docks, run a
+    /// process that quitting would kill.
+    pub fn running_process_count(&self, cx: &AppContext) -> usize {
+        let dock_panes = [&self.left_dock, &self.bottom_dock, &self.right_dock]
+            .into_iter()
+            .flat_map(|dock| dock.read(cx).panes(cx));
+        self.panes
+            .iter()
+            .cloned()
+            .chain(dock_panes)
+            .flat_map(|pane| pane.read(cx).items())
+            .filter(|item| item.has_running_process(cx))
+            .count()
+    }
+
+    /// Returns how many buffers of the workspace have changes but were never saved.
+    pub fn untitled_buffer_count(&self, cx: &AppContext) -> usize {
+        self.items(cx)
+            .filter(|item| {
+                item.is_singleton(cx) && item.is_dirty(cx) && item.project_path(cx).is_none()
+            })
+            .count()
+    }
+
     pub fn item_of_type<T: Item>(&self, cx: &AppContext) -> Option<View<T>> {
         self.items_of_type(cx).max_by_key(|item| item.item_id())
     }
@@ -2242,6 +2351,9 @@ impl Workspace {
             pane::Event::Split(direction) => {
                 self.split_and_clone(pane, *direction, cx);
             }
+            pane::Event::SplitAndMove(direction) => {
+                self.split_and_move(pane, *direction, cx);
+            }
             pane::Event::Remove => self.remove_pane(pane, cx),
             pane::Event::ActivateItem { local } => {
                 if *local {
@@ -2324,6 +2436,23 @@ impl Workspace {
         maybe_pane_handle
     }
 
+    /// Moves the active item of the given pane into a new pane next to it, removing the
+    /// original pane if it has no items left.
+    pub fn split_and_move(
+        &mut self,
+        pane: View<Pane>,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Pane>> {
+        let item = pane.read(cx).active_item()?;
+        let new_pane = self.split_pane(pane.clone(), direction, cx);
+        self.move_item(pane.clone(), new_pane.clone(), item.item_id(), 0, cx);
+        if pane.read(cx).items_len() == 0 {
+            self.remove_pane(pane, cx);
+        }
+        Some(new_pane)
+    }
+
     pub fn split_pane_with_item(
         &mut self,
         pane_to_split: WeakView<Pane>,
@@ -2405,6 +2534,14 @@ impl Workspace {
     fn remove_pane(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
         if self.center.remove(&pane).unwrap() {
             self.force_remove_pane(&pane, cx);
+            let closed_history = pane.read(cx).nav_history().clone();
+            if let Some(remaining_pane) = self.panes.last() {
+                remaining_pane.update(cx, |remaining_pane, _| {
+                    remaining_pane
+                        .nav_history_mut()
+                        .adopt_closed_entries(&closed_history)
+                });
+            }
             self.unfollow(&pane, cx);
             self.last_leaders_by_pane.remove(&pane.downgrade());
             for removed_item in pane.read(cx).items() {
@@ -3613,7 +3750,7 @@ impl Render for Workspace {
             let theme_settings = ThemeSettings::get_global(cx);
             (
                 theme_settings.ui_font.family.clone(),
-                theme_settings.ui_font_size.clone(),
+                theme_settings.ui_font_size(cx),
             )
         };
 
@@ -4392,8 +4529,96 @@ pub fn join_remote_project(
     })
 }
 
+/// Something besides unsaved changes to files that quitting or restarting would lose,
+/// which unsaved changes are prompted for separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuitWarning {
+    /// Leaving the call that the user is in.
+    ActiveCall,
+    /// Killing the processes that are running in terminals.
+    RunningProcesses(usize),
+    /// Discarding buffers that were never saved.
+    UntitledBuffers(usize),
+}
+
+impl QuitWarning {
+    fn message(&self) -> String {
+        match self {
+            Self::ActiveCall => "You will leave the current call.".into(),
+            Self::RunningProcesses(1) => "A process running in a terminal will be killed.".into(),
+            Self::RunningProcesses(count) => {
+                format!("{count} processes running in terminals will be killed.")
+            }
+            Self::UntitledBuffers(1) => "An untitled buffer has never been saved.".into(),
+            Self::UntitledBuffers(count) => {
+                format!("{count} untitled buffers have never been saved.")
+            }
+        }
+    }
+}
+
+/// Returns what quitting with the given windows open would lose, leaving out what the
+/// `warn_before_quit` setting always allows.
+pub fn quit_warnings(windows: &[WindowHandle<Workspace>], cx: &AppContext) -> Vec<QuitWarning> {
+    let warn_before_quit = WorkspaceSettings::get_global(cx).warn_before_quit;
+    let mut warnings = Vec::new();
+    if warn_before_quit.active_call
+        && ActiveCall::try_global(cx).map_or(false, |call| call.read(cx).room().is_some())
+    {
+        warnings.push(QuitWarning::ActiveCall);
+    }
+
+    let mut running_processes = 0;
+    let mut untitled_buffers = 0;
+    for window in windows {
+        if let Ok(workspace) = window.read(cx) {
+            running_processes += workspace.running_process_count(cx);
+            untitled_buffers += workspace.untitled_buffer_count(cx);
+        }
+    }
+    if warn_before_quit.running_processes && running_processes > 0 {
+        warnings.push(QuitWarning::RunningProcesses(running_processes));
+    }
+    if warn_before_quit.untitled_buffers && untitled_buffers > 0 {
+        warnings.push(QuitWarning::UntitledBuffers(untitled_buffers));
+    }
+    warnings
+}
+
+/// Prompts the user to confirm quitting or restarting in the first of the given windows,
+/// when the `confirm_quit` setting asks for it or when doing so would lose something
+/// they're warned about. The prompt answers 0 when the user confirms.
+pub fn prompt_to_quit(
+    windows: &[WindowHandle<Workspace>],
+    confirm_label: &str,
+    cx: &mut AppContext,
+) -> Option<oneshot::Receiver<usize>> {
+    let window = windows.first()?;
+    let warnings = quit_warnings(windows, cx);
+    if warnings.is_empty() && !WorkspaceSettings::get_global(cx).confirm_quit {
+        return None;
+    }
+
+    let message = format!("Are you sure you want to {}?", confirm_label.to_lowercase());
+    let (level, detail) = if warnings.is_empty() {
+        (PromptLevel::Info, None)
+    } else {
+        let detail = warnings.iter().map(QuitWarning::message).join("\n");
+        (PromptLevel::Warning, Some(detail))
+    };
+    window
+        .update(cx, |_, cx| {
+            cx.prompt(
+                level,
+                &message,
+                detail.as_deref(),
+                &[confirm_label, "Cancel"],
+            )
+        })
+        .ok()
+}
+
 pub fn restart(_: &Restart, cx: &mut AppContext) {
-    let should_confirm = WorkspaceSettings::get_global(cx).confirm_quit;
     let mut workspace_windows = cx
         .windows()
         .into_iter()
@@ -4404,19 +4629,7 @@ pub fn restart(_: &Restart, cx: &mut AppContext) {
     // prompt in the active window before switching to a different window.
     workspace_windows.sort_by_key(|window| window.is_active(cx) == Some(false));
 
-    let mut prompt = None;
-    if let (true, Some(window)) = (should_confirm, workspace_windows.first()) {
-        prompt = window
-            .update(cx, |_, cx| {
-                cx.prompt(
-                    PromptLevel::Info,
-                    "Are you sure you want to restart?",
-                    None,
-                    &["Restart", "Cancel"],
-                )
-            })
-            .ok();
-    }
+    let prompt = prompt_to_quit(&workspace_windows, "Restart", cx);
 
     cx.spawn(|mut cx| async move {
         if let Some(prompt) = prompt {
@@ -4526,6 +4739,7 @@ mod tests {
             test::{TestItem, TestProjectItem},
             ItemEvent,
         },
+        workspace_settings::WarnBeforeQuitContent,
     };
     use fs::FakeFs;
     use gpui::{px, DismissEvent, TestAppContext, VisualTestContext};
@@ -4708,6 +4922,52 @@ mod tests {
         assert!(!task.await.unwrap());
     }
 
+    #[gpui::test]
+    async fn test_quit_warnings(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, None, cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let windows = [window];
+        cx.update(|cx| assert!(quit_warnings(&windows, cx).is_empty()));
+
+        // Only dirty items that were never saved are counted.
+        window
+            .update(cx, |workspace, cx| {
+                let untitled = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
+                let saved = cx.new_view(|cx| {
+                    TestItem::new(cx)
+                        .with_dirty(true)
+                        .with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+                });
+                let clean = cx.new_view(|cx| TestItem::new(cx));
+                workspace.add_item(Box::new(untitled), cx);
+                workspace.add_item(Box::new(saved), cx);
+                workspace.add_item(Box::new(clean), cx);
+            })
+            .unwrap();
+        cx.update(|cx| {
+            assert_eq!(
+                quit_warnings(&windows, cx),
+                [QuitWarning::UntitledBuffers(1)]
+            )
+        });
+
+        // Warnings that are always allowed are left out.
+        cx.update(|cx| {
+            cx.update_global(|settings: &mut SettingsStore, cx| {
+                settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.warn_before_quit = Some(WarnBeforeQuitContent {
+                        untitled_buffers: Some(false),
+                        ..Default::default()
+                    });
+                })
+            });
+            assert!(quit_warnings(&windows, cx).is_empty());
+        });
+    }
+
     #[gpui::test]
     async fn test_close_pane_items(cx: &mut TestAppContext) {
         init_test(cx);
@@ -4924,6 +5184,57 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_split_and_move(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item_1 = cx.new_view(|cx| TestItem::new(cx));
+        let item_2 = cx.new_view(|cx| TestItem::new(cx));
+        let left_pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item(Box::new(item_1.clone()), cx);
+            workspace.add_item(Box::new(item_2.clone()), cx);
+            workspace.active_pane().clone()
+        });
+
+        // Moving the active item keeps the other items in the original pane.
+        let right_pane = workspace
+            .update(cx, |workspace, cx| {
+                workspace.split_and_move(left_pane.clone(), SplitDirection::Right, cx)
+            })
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 2);
+            assert_eq!(
+                right_pane.read(cx).active_item().unwrap().item_id(),
+                item_2.entity_id()
+            );
+            assert_eq!(
+                left_pane.read(cx).active_item().unwrap().item_id(),
+                item_1.entity_id()
+            );
+            assert_eq!(left_pane.read(cx).items_len(), 1);
+        });
+
+        // Moving the last item of a pane removes that pane.
+        let bottom_pane = workspace
+            .update(cx, |workspace, cx| {
+                workspace.split_and_move(left_pane.clone(), SplitDirection::Down, cx)
+            })
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 2);
+            assert!(!workspace.panes().contains(&left_pane));
+            assert_eq!(
+                bottom_pane.read(cx).active_item().unwrap().item_id(),
+                item_1.entity_id()
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_autosave(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -7,7 +7,16 @@ pub struct WorkspaceSettings {
     pub active_pane_magnification: f32,
     pub confirm_quit: bool,
     pub show_call_status_icon: bool,
+    pub show_menu_bar_extra: bool,
     pub autosave: AutosaveSetting,
+    pub warn_before_quit: WarnBeforeQuit,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct WarnBeforeQuit {
+    pub active_call: bool,
+    pub running_processes: bool,
+    pub untitled_buffers: bool,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -26,10 +35,34 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: true
     pub show_call_status_icon: Option<bool>,
+    /// Whether to show a menu in the system menu bar with the call status, recent projects
+    /// and quick actions, which stays available when all windows are closed.
+    ///
+    /// Default: false
+    pub show_menu_bar_extra: Option<bool>,
     /// When to automatically save edited buffers.
     ///
     /// Default: off
     pub autosave: Option<AutosaveSetting>,
+    /// What to warn about before quitting or restarting, when it would be lost. Setting
+    /// a kind of loss to false always allows it.
+    pub warn_before_quit: Option<WarnBeforeQuitContent>,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WarnBeforeQuitContent {
+    /// Whether to warn when quitting would leave the current call.
+    ///
+    /// Default: true
+    pub active_call: Option<bool>,
+    /// Whether to warn when quitting would kill processes running in terminals.
+    ///
+    /// Default: true
+    pub running_processes: Option<bool>,
+    /// Whether to warn when quitting would discard buffers that were never saved.
+    ///
+    /// Default: true
+    pub untitled_buffers: Option<bool>,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
@@ -19,6 +19,7 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                         MenuItem::action("Open Default Key Bindings", super::OpenDefaultKeymap),
                         MenuItem::action("Open Local Settings", super::OpenLocalSettings),
                         MenuItem::action("Select Theme", theme_selector::Toggle),
+                        MenuItem::action("Extensions", extensions_ui::Extensions),
                     ],
                 }),
                 MenuItem::action("Install CLI", install_cli::Install),
@@ -100,6 +101,7 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                 MenuItem::action("Zoom In", super::IncreaseBufferFontSize),
                 MenuItem::action("Zoom Out", super::DecreaseBufferFontSize),
                 MenuItem::action("Reset Zoom", super::ResetBufferFontSize),
+                MenuItem::action("Presentation Mode", super::TogglePresentationMode),
                 MenuItem::separator(),
                 MenuItem::action("Toggle Left Dock", workspace::ToggleLeftDock),
                 MenuItem::action("Toggle Right Dock", workspace::ToggleRightDock),
@@ -0,0 +1,226 @@
+use anyhow::{anyhow, Context as _, Result};
+use call::ActiveCall;
+use gpui::{AppContext, Global, Model, Task};
+use project::Project;
+use std::{path::PathBuf, sync::Arc};
+use util::ResultExt;
+use workspace::AppState;
+
+/// Runs Zed without any windows, hosting a project for collaborators:
+///
+/// ```text
+/// zed --headless --channel <channel-id> <path>
+/// ```
+///
+/// The project is shared in the channel, and whoever joins the channel opens it in their
+/// own Zed, which renders it from the project's replicated state. This allows working on
+/// a machine where Zed's UI can't run, such as a remote server. The app never connects to
+/// a display, and the process exits with an error if the project can't be hosted.
+///
+/// Collaborators need Zed itself to join, either from the channel or with [`JoinArgs`]:
+/// there's no browser client for headless projects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeadlessArgs {
+    pub channel_id: u64,
+    pub path: PathBuf,
+}
+
+impl HeadlessArgs {
+    /// Parses the command line arguments, returning `None` if `--headless` isn't one of them.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let args = args.into_iter().collect::<Vec<_>>();
+        if !args.iter().any(|arg| arg == "--headless") {
+            return Ok(None);
+        }
+
+        let mut channel_id = None;
+        let mut path = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => {}
+                "--channel" => {
+                    let id = args.next().context("--channel requires a channel id")?;
+                    channel_id = Some(
+                        id.parse()
+                            .with_context(|| format!("invalid channel id {id:?}"))?,
+                    );
+                }
+                _ if path.is_none() && !arg.starts_with('-') => path = Some(PathBuf::from(arg)),
+                _ => return Err(anyhow!("unexpected argument {arg:?}")),
+            }
+        }
+        Ok(Some(Self {
+            channel_id: channel_id.context("--headless requires --channel <channel-id>")?,
+            path: path.context("--headless requires the path of a project")?,
+        }))
+    }
+}
+
+/// Runs Zed as a client of a channel, such as one that a headless host shares a project in:
+///
+/// ```text
+/// zed --join <channel-id>
+/// ```
+///
+/// No local projects are restored: the only window is the channel's shared project, which is
+/// rendered from its replicated state while the host does all of the work on its files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JoinArgs {
+    pub channel_id: u64,
+}
+
+impl JoinArgs {
+    /// Parses the command line arguments, returning `None` if `--join` isn't one of them.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let args = args.into_iter().collect::<Vec<_>>();
+        if !args.iter().any(|arg| arg == "--join") {
+            return Ok(None);
+        }
+
+        let mut channel_id = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--join" if channel_id.is_none() => {
+                    let id = args.next().context("--join requires a channel id")?;
+                    channel_id = Some(
+                        id.parse()
+                            .with_context(|| format!("invalid channel id {id:?}"))?,
+                    );
+                }
+                _ => return Err(anyhow!("unexpected argument {arg:?}")),
+            }
+        }
+        Ok(Some(Self {
+            channel_id: channel_id.context("--join requires a channel id")?,
+        }))
+    }
+}
+
+/// The projects that are hosted, which are otherwise only referenced weakly by the room.
+#[derive(Default)]
+struct HostedProjects(Vec<Model<Project>>);
+
+impl Global for HostedProjects {}
+
+pub fn host_project(
+    args: HeadlessArgs,
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let path = match std::fs::canonicalize(&args.path) {
+        Ok(path) => path,
+        Err(error) => {
+            return Task::ready(Err(error).with_context(|| format!("{:?}", args.path)));
+        }
+    };
+    cx.spawn(|mut cx| async move {
+        app_state
+            .client
+            .authenticate_and_connect(true, &cx)
+            .await
+            .context("signing in")?;
+
+        let project = cx.update(|cx| {
+            Project::local(
+                app_state.client.clone(),
+                app_state.node_runtime.clone(),
+                app_state.user_store.clone(),
+                app_state.languages.clone(),
+                app_state.fs.clone(),
+                cx,
+            )
+        })?;
+        project
+            .update(&mut cx, |project, cx| {
+                project.find_or_create_local_worktree(&path, true, cx)
+            })?
+            .await?;
+
+        let active_call = cx.update(|cx| ActiveCall::global(cx))?;
+        active_call
+            .update(&mut cx, |call, cx| call.join_channel(args.channel_id, cx))?
+            .await?
+            .context("joining the channel")?;
+        let project_id = active_call
+            .update(&mut cx, |call, cx| call.share_project(project.clone(), cx))?
+            .await?;
+        cx.update(|cx| {
+            cx.default_global::<HostedProjects>().0.push(project);
+        })?;
+
+        log::info!(
+            "hosting {:?} as project {project_id} in channel {}",
+            path,
+            args.channel_id
+        );
+        Ok(())
+    })
+}
+
+pub fn join_project(
+    args: JoinArgs,
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    cx.spawn(|cx| async move {
+        // Joining is the only thing the client does, so it's worth signing in interactively.
+        // Failing to is reported by `join_channel`, in the window it opens.
+        app_state
+            .client
+            .authenticate_and_connect(true, &cx)
+            .await
+            .log_err();
+        cx.update(|cx| workspace::join_channel(args.channel_id, app_state, None, cx))?
+            .await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Option<HeadlessArgs>> {
+        HeadlessArgs::parse(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn test_parse_headless_args() {
+        assert_eq!(parse("/a/file.rs /b/file.rs").unwrap(), None);
+        assert_eq!(
+            parse("--headless --channel 42 /srv/project").unwrap(),
+            Some(HeadlessArgs {
+                channel_id: 42,
+                path: "/srv/project".into(),
+            })
+        );
+        assert_eq!(
+            parse("/srv/project --channel 7 --headless").unwrap(),
+            Some(HeadlessArgs {
+                channel_id: 7,
+                path: "/srv/project".into(),
+            })
+        );
+        assert!(parse("--headless /srv/project").is_err());
+        assert!(parse("--headless --channel 42").is_err());
+        assert!(parse("--headless --channel one /srv/project").is_err());
+        assert!(parse("--headless --channel 42 /a /b").is_err());
+        assert!(parse("--headless --join 42 /srv/project").is_err());
+    }
+
+    #[test]
+    fn test_parse_join_args() {
+        let parse = |args: &str| JoinArgs::parse(args.split_whitespace().map(str::to_string));
+        assert_eq!(parse("/a/file.rs").unwrap(), None);
+        assert_eq!(
+            parse("--join 42").unwrap(),
+            Some(JoinArgs { channel_id: 42 })
+        );
+        assert!(parse("--join").is_err());
+        assert!(parse("--join one").is_err());
+        assert!(parse("--join 42 /a/file.rs").is_err());
+        assert!(parse("--join 42 --join 7").is_err());
+        assert!(parse("--headless --join 42").is_err());
+    }
+}
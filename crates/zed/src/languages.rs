@@ -1,4 +1,5 @@
 use anyhow::Context;
+use gem_runtime::GemRuntime;
 use gpui::AppContext;
 pub use language::*;
 use node_runtime::NodeRuntime;
@@ -7,36 +8,65 @@ use settings::Settings;
 use std::{str, sync::Arc};
 use util::asset_str;
 
-use self::{deno::DenoSettings, elixir::ElixirSettings};
+use self::{
+    bash::BashSettings, custom::CustomLanguageServerSettings, dart::DartSettings,
+    deno::DenoSettings, elixir::ElixirSettings, java::JavaSettings, julia::JuliaSettings,
+    latex::TexlabSettings, lua::LuaSettings, php::IntelephenseSettings,
+    powershell::PowerShellSettings, r::RSettings, rust::RustAnalyzerSettings, sql::SqlSettings,
+    swift::SwiftSettings, yaml::YamlSettings,
+};
 
+mod astro;
+mod bash;
 mod c;
+mod clojure;
 mod csharp;
 mod css;
+mod custom;
+mod dart;
 mod deno;
+mod dockerfile;
 mod elixir;
 mod elm;
 mod erlang;
 mod gleam;
+mod glsl;
 mod go;
 mod haskell;
 mod html;
+mod java;
 mod json;
+mod julia;
+mod kotlin;
 #[cfg(feature = "plugin_runtime")]
 mod language_plugin;
+mod latex;
 mod lua;
+mod markdown;
+mod mdx;
+mod nix;
 mod nu;
 mod ocaml;
 mod php;
+mod powershell;
+mod prisma;
+mod proto;
 mod purescript;
 mod python;
+mod r;
 mod ruby;
 mod rust;
+mod scala;
+mod sql;
 mod svelte;
+mod swift;
 mod tailwind;
+mod terraform;
 mod toml;
 mod typescript;
 mod uiua;
 mod vue;
+mod wgsl;
 mod yaml;
 mod zig;
 
@@ -47,6 +77,7 @@ mod zig;
 //      Note: github highlights take the last match while zed takes the first
 // 5. Add indents.scm, outline.scm, and brackets.scm to implement indent on newline, outline/breadcrumbs,
 //    and autoclosing brackets respectively
+//      Note: `Buffer::assert_autoindents` tests an indents query against correctly indented code
 // 6. If the language has injections add an injections.scm query file
 
 #[derive(RustEmbed)]
@@ -57,18 +88,40 @@ struct LanguageDir;
 pub fn init(
     languages: Arc<LanguageRegistry>,
     node_runtime: Arc<dyn NodeRuntime>,
+    gem_runtime: Arc<dyn GemRuntime>,
     cx: &mut AppContext,
 ) {
     ElixirSettings::register(cx);
     DenoSettings::register(cx);
+    RustAnalyzerSettings::register(cx);
+    JavaSettings::register(cx);
+    IntelephenseSettings::register(cx);
+    SwiftSettings::register(cx);
+    DartSettings::register(cx);
+    YamlSettings::register(cx);
+    SqlSettings::register(cx);
+    LuaSettings::register(cx);
+    TexlabSettings::register(cx);
+    RSettings::register(cx);
+    JuliaSettings::register(cx);
+    BashSettings::register(cx);
+    PowerShellSettings::register(cx);
+    CustomLanguageServerSettings::register(cx);
+    dart::init_flutter(cx);
+    sql::init(cx);
+    latex::init_texlab_commands(cx);
 
     languages.register_native_grammars([
+        ("astro", tree_sitter_astro::language()),
         ("bash", tree_sitter_bash::language()),
         ("beancount", tree_sitter_beancount::language()),
         ("c", tree_sitter_c::language()),
         ("c_sharp", tree_sitter_c_sharp::language()),
+        ("clojure", tree_sitter_clojure::language()),
         ("cpp", tree_sitter_cpp::language()),
         ("css", tree_sitter_css::language()),
+        ("dart", tree_sitter_dart::language()),
+        ("dockerfile", tree_sitter_dockerfile::language()),
         ("elixir", tree_sitter_elixir::language()),
         ("elm", tree_sitter_elm::language()),
         (
@@ -86,7 +139,11 @@ pub fn init(
         ("hcl", tree_sitter_hcl::language()),
         ("heex", tree_sitter_heex::language()),
         ("html", tree_sitter_html::language()),
+        ("java", tree_sitter_java::language()),
         ("json", tree_sitter_json::language()),
+        ("julia", tree_sitter_julia::language()),
+        ("kotlin", tree_sitter_kotlin::language()),
+        ("latex", tree_sitter_latex::language()),
         ("lua", tree_sitter_lua::language()),
         ("markdown", tree_sitter_markdown::language()),
         ("nix", tree_sitter_nix::language()),
@@ -97,20 +154,27 @@ pub fn init(
             tree_sitter_ocaml::language_ocaml_interface(),
         ),
         ("php", tree_sitter_php::language_php()),
+        ("prisma", tree_sitter_prisma_io::language()),
         ("proto", tree_sitter_proto::language()),
         #[cfg(not(target_os = "linux"))]
         ("purescript", tree_sitter_purescript::language()),
         ("python", tree_sitter_python::language()),
+        ("r", tree_sitter_r::language()),
         ("racket", tree_sitter_racket::language()),
         ("ruby", tree_sitter_ruby::language()),
         ("rust", tree_sitter_rust::language()),
+        ("scala", tree_sitter_scala::language()),
         ("scheme", tree_sitter_scheme::language()),
+        ("scss", tree_sitter_scss::language()),
+        ("sql", tree_sitter_sequel::language()),
         ("svelte", tree_sitter_svelte::language()),
+        ("swift", tree_sitter_swift::language()),
         ("toml", tree_sitter_toml::language()),
         ("tsx", tree_sitter_typescript::language_tsx()),
         ("typescript", tree_sitter_typescript::language_typescript()),
         ("uiua", tree_sitter_uiua::language()),
         ("vue", tree_sitter_vue::language()),
+        ("wgsl", tree_sitter_wgsl::language()),
         ("yaml", tree_sitter_yaml::language()),
         ("zig", tree_sitter_zig::language()),
     ]);
@@ -126,9 +190,13 @@ pub fn init(
         )
     };
 
-    language("bash", vec![]);
+    language(
+        "bash",
+        vec![Arc::new(bash::BashLspAdapter::new(node_runtime.clone()))],
+    );
     language("beancount", vec![]);
     language("c", vec![Arc::new(c::CLspAdapter) as Arc<dyn LspAdapter>]);
+    language("clojure", vec![Arc::new(clojure::ClojureLspAdapter)]);
     language("cpp", vec![Arc::new(c::CLspAdapter)]);
     language("csharp", vec![Arc::new(csharp::OmniSharpAdapter {})]);
     language(
@@ -138,6 +206,26 @@ pub fn init(
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
+    language(
+        "scss",
+        vec![
+            Arc::new(css::CssLspAdapter::new(node_runtime.clone())),
+            Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
+        ],
+    );
+    language(
+        "less",
+        vec![
+            Arc::new(css::CssLspAdapter::new(node_runtime.clone())),
+            Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
+        ],
+    );
+    language(
+        "dockerfile",
+        vec![Arc::new(dockerfile::DockerfileLspAdapter::new(
+            node_runtime.clone(),
+        ))],
+    );
 
     match &ElixirSettings::get(None, cx).lsp {
         elixir::ElixirLspSetting::ElixirLs => language(
@@ -173,6 +261,12 @@ pub fn init(
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
+    language(
+        "java",
+        vec![Arc::new(java::JdtlsLspAdapter::new(
+            JavaSettings::get(None, cx).jdk_path.clone(),
+        ))],
+    );
     language(
         "json",
         vec![Arc::new(json::JsonLspAdapter::new(
@@ -180,7 +274,19 @@ pub fn init(
             languages.clone(),
         ))],
     );
-    language("markdown", vec![]);
+    language("jsonl", vec![]);
+    language(
+        "julia",
+        vec![Arc::new(julia::JuliaLspAdapter::new(
+            JuliaSettings::get(None, cx).executable.clone(),
+        ))],
+    );
+    language("kotlin", vec![Arc::new(kotlin::KotlinLspAdapter)]);
+    language("markdown", vec![Arc::new(markdown::MarksmanAdapter)]);
+    language(
+        "mdx",
+        vec![Arc::new(mdx::MdxLspAdapter::new(node_runtime.clone()))],
+    );
     language(
         "python",
         vec![Arc::new(python::PythonLspAdapter::new(
@@ -188,6 +294,7 @@ pub fn init(
         ))],
     );
     language("rust", vec![Arc::new(rust::RustLspAdapter)]);
+    language("scala", vec![Arc::new(scala::MetalsLspAdapter)]);
     language("toml", vec![Arc::new(toml::TaploLspAdapter)]);
     match &DenoSettings::get(None, cx).enable {
         true => {
@@ -242,21 +349,43 @@ pub fn init(
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
-    language("ruby", vec![Arc::new(ruby::RubyLanguageServer)]);
+    language(
+        "ruby",
+        vec![Arc::new(ruby::RubyLanguageServer::new(gem_runtime.clone()))],
+    );
     language(
         "erb",
         vec![
-            Arc::new(ruby::RubyLanguageServer),
+            Arc::new(ruby::RubyLanguageServer::new(gem_runtime.clone())),
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
+    language(
+        "swift",
+        vec![Arc::new(swift::SourceKitLspAdapter::new(
+            SwiftSettings::get(None, cx).toolchain_path.clone(),
+        ))],
+    );
     language("scheme", vec![]);
+    language(
+        "sql",
+        vec![Arc::new(sql::SqlLspAdapter::new(node_runtime.clone()))],
+    );
     language("racket", vec![]);
+    language("r", vec![Arc::new(r::RLanguageServer::default())]);
     language("lua", vec![Arc::new(lua::LuaLspAdapter)]);
+    language("latex", vec![Arc::new(latex::TexlabAdapter)]);
     language(
         "yaml",
         vec![Arc::new(yaml::YamlLspAdapter::new(node_runtime.clone()))],
     );
+    language(
+        "astro",
+        vec![
+            Arc::new(astro::AstroLspAdapter::new(node_runtime.clone())),
+            Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
+        ],
+    );
     language(
         "svelte",
         vec![
@@ -267,10 +396,23 @@ pub fn init(
     language(
         "php",
         vec![
-            Arc::new(php::IntelephenseLspAdapter::new(node_runtime.clone())),
+            Arc::new(php::IntelephenseLspAdapter::new(
+                node_runtime.clone(),
+                IntelephenseSettings::get(None, cx).license_key.clone(),
+            )),
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
+    language(
+        "powershell",
+        vec![Arc::new(powershell::PowerShellLspAdapter::default())],
+    );
+    language(
+        "prisma",
+        vec![Arc::new(prisma::PrismaLspAdapter::new(
+            node_runtime.clone(),
+        ))],
+    );
 
     // Produces a link error on linux due to duplicated `state_new` symbol
     // todo!(linux): Restore purescript
@@ -285,17 +427,31 @@ pub fn init(
         "elm",
         vec![Arc::new(elm::ElmLspAdapter::new(node_runtime.clone()))],
     );
-    language("glsl", vec![]);
-    language("nix", vec![]);
+    language(
+        "dart",
+        vec![Arc::new(dart::DartLanguageServer::new(
+            DartSettings::get(None, cx).sdk_path.clone(),
+        ))],
+    );
+    language("glsl", vec![Arc::new(glsl::GlslLspAdapter)]);
+    language("wgsl", vec![Arc::new(wgsl::WgslLspAdapter)]);
+    language("nix", vec![Arc::new(nix::NilAdapter)]);
     language("nu", vec![Arc::new(nu::NuLanguageServer {})]);
     language("ocaml", vec![Arc::new(ocaml::OCamlLspAdapter)]);
     language("ocaml-interface", vec![Arc::new(ocaml::OCamlLspAdapter)]);
     language("vue", vec![Arc::new(vue::VueLspAdapter::new(node_runtime))]);
     language("uiua", vec![Arc::new(uiua::UiuaLanguageServer {})]);
-    language("proto", vec![]);
-    language("terraform", vec![]);
-    language("terraform-vars", vec![]);
+    language("proto", vec![Arc::new(proto::BufLspAdapter)]);
+    language("terraform", vec![Arc::new(terraform::TerraformLspAdapter)]);
+    language(
+        "terraform-vars",
+        vec![Arc::new(terraform::TerraformLspAdapter)],
+    );
     language("hcl", vec![]);
+
+    r::init(languages.clone(), cx);
+    powershell::init(languages.clone(), cx);
+    custom::init(languages, cx);
 }
 
 #[cfg(any(test, feature = "test-support"))]
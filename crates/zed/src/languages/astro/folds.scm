@@ -0,0 +1,6 @@
+[
+ (frontmatter)
+ (style_element)
+ (script_element)
+ (element)
+] @fold
@@ -0,0 +1,26 @@
+(tag_name) @tag
+(erroneous_end_tag_name) @keyword
+(doctype) @constant
+(attribute_name) @property
+(comment) @comment
+
+[
+  (attribute_value)
+  (quoted_attribute_value)
+] @string
+
+"=" @operator
+
+[
+  "{"
+  "}"
+] @punctuation.bracket
+
+"---" @punctuation.delimiter
+
+[
+  "<"
+  ">"
+  "</"
+  "/>"
+] @tag.delimiter
@@ -0,0 +1,20 @@
+; The frontmatter is TypeScript, and expressions in the template are TSX.
+(frontmatter
+  (raw_text) @content
+  (#set! "language" "typescript"))
+
+(interpolation
+  (raw_text) @content
+  (#set! "language" "tsx"))
+
+(attribute_interpolation
+  (attribute_js_expr) @content
+  (#set! "language" "tsx"))
+
+(script_element
+  (raw_text) @content
+  (#set! "language" "typescript"))
+
+(style_element
+  (raw_text) @content
+  (#set! "language" "css"))
@@ -0,0 +1,227 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use gpui::AppContext;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use node_runtime::NodeRuntime;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use settings::Settings;
+use smol::fs;
+use std::{
+    any::Any,
+    env,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{async_maybe, ResultExt};
+
+const SERVER_PATH: &'static str = "node_modules/bash-language-server/out/cli.js";
+
+fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
+    vec![server_path.into(), "start".into()]
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BashSettings {
+    pub shellcheck_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct BashSettingsContent {
+    /// The path to the ShellCheck executable whose warnings are shown as diagnostics.
+    /// When null, `shellcheck` on the `PATH` is used, and there are no ShellCheck
+    /// diagnostics when it isn't installed.
+    ///
+    /// Default: null
+    shellcheck_path: Option<PathBuf>,
+}
+
+impl Settings for BashSettings {
+    const KEY: Option<&'static str> = Some("bash");
+
+    type FileContent = BashSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// Returns the path of ShellCheck, or an empty string when it can't be found, which
+/// stops bash-language-server from trying to run it on every change.
+fn shellcheck_path(settings: &BashSettings) -> String {
+    settings
+        .shellcheck_path
+        .clone()
+        .or_else(|| find_in_path("shellcheck"))
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    find_in_paths(name, &env::var_os("PATH")?)
+}
+
+fn find_in_paths(name: &str, paths: impl AsRef<OsStr>) -> Option<PathBuf> {
+    env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+pub struct BashLspAdapter {
+    node: Arc<dyn NodeRuntime>,
+}
+
+impl BashLspAdapter {
+    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
+        BashLspAdapter { node }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for BashLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("bash-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "bash"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(
+            self.node
+                .npm_package_latest_version("bash-language-server")
+                .await?,
+        ) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let server_path = container_dir.join(SERVER_PATH);
+
+        if fs::metadata(&server_path).await.is_err() {
+            self.node
+                .npm_install_packages(
+                    &container_dir,
+                    &[("bash-language-server", version.as_str())],
+                )
+                .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: self.node.binary_path().await?,
+            arguments: server_binary_arguments(&server_path),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node).await
+    }
+
+    fn workspace_configuration(&self, _workspace_root: &Path, cx: &mut AppContext) -> Value {
+        serde_json::json!({
+            "bashIde": {
+                "shellcheckPath": shellcheck_path(BashSettings::get_global(cx)),
+            }
+        })
+    }
+}
+
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    node: &dyn NodeRuntime,
+) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let server_path = cached_server_path(&container_dir)?;
+        Ok(LanguageServerBinary {
+            path: node.binary_path().await?,
+            arguments: server_binary_arguments(&server_path),
+        })
+    })
+    .await
+    .log_err()
+}
+
+/// Returns the path of the server installed by `fetch_server_binary`, which installs
+/// the npm package directly into the container directory rather than a version directory.
+fn cached_server_path(container_dir: &Path) -> Result<PathBuf> {
+    let server_path = container_dir.join(SERVER_PATH);
+    if server_path.is_file() {
+        Ok(server_path)
+    } else {
+        Err(anyhow!(
+            "missing executable in directory {:?}",
+            container_dir
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_server_path() {
+        let container_dir = tempfile::tempdir().unwrap();
+        assert!(cached_server_path(container_dir.path()).is_err());
+
+        let server_path = container_dir.path().join(SERVER_PATH);
+        std::fs::create_dir_all(server_path.parent().unwrap()).unwrap();
+        assert!(cached_server_path(container_dir.path()).is_err());
+
+        std::fs::write(&server_path, "").unwrap();
+        assert_eq!(
+            cached_server_path(container_dir.path()).unwrap(),
+            server_path
+        );
+        assert_eq!(
+            server_binary_arguments(&server_path),
+            vec![OsString::from(server_path), OsString::from("start")]
+        );
+    }
+
+    #[test]
+    fn test_shellcheck_path() {
+        let settings = BashSettings {
+            shellcheck_path: Some("/opt/shellcheck/bin/shellcheck".into()),
+        };
+        assert_eq!(shellcheck_path(&settings), "/opt/shellcheck/bin/shellcheck");
+
+        let bin_dir = tempfile::tempdir().unwrap();
+        assert_eq!(find_in_paths("shellcheck", bin_dir.path()), None);
+        std::fs::write(bin_dir.path().join("shellcheck"), "").unwrap();
+        assert_eq!(
+            find_in_paths("shellcheck", bin_dir.path()),
+            Some(bin_dir.path().join("shellcheck"))
+        );
+    }
+}
@@ -3,15 +3,23 @@ use async_trait::async_trait;
 use futures::StreamExt;
 pub use language::*;
 use lsp::LanguageServerBinary;
-use smol::fs::{self, File};
-use std::{any::Any, path::PathBuf, sync::Arc};
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use util::{
+    archive::{download_archive_directory_atomically, ArchiveType},
     async_maybe,
-    fs::remove_matching,
+    fs::matching_by_recency,
     github::{latest_github_release, GitHubLspBinaryVersion},
     ResultExt,
 };
 
+const COMPILE_COMMANDS: &str = "compile_commands.json";
+
 pub struct CLspAdapter;
 
 #[async_trait]
@@ -36,10 +44,7 @@ impl super::LspAdapter for CLspAdapter {
             .iter()
             .find(|asset| asset.name == asset_name)
             .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url.clone(),
-        };
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -50,36 +55,20 @@ impl super::LspAdapter for CLspAdapter {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let zip_path = container_dir.join(format!("clangd_{}.zip", version.name));
         let version_dir = container_dir.join(format!("clangd_{}", version.name));
         let binary_path = version_dir.join("bin/clangd");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("error downloading release")?;
-            let mut file = File::create(&zip_path).await?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
-
-            let unzip_status = smol::process::Command::new("unzip")
-                .current_dir(&container_dir)
-                .arg(&zip_path)
-                .output()
-                .await?
-                .status;
-            if !unzip_status.success() {
-                Err(anyhow!("failed to unzip clangd archive"))?;
-            }
-
-            remove_matching(&container_dir, |entry| entry != version_dir).await;
+            // The archive contains the version's directory.
+            download_archive_directory_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
         }
 
         Ok(LanguageServerBinary {
@@ -108,6 +97,21 @@ impl super::LspAdapter for CLspAdapter {
             })
     }
 
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_clangd_version(entry)
+    }
+
+    async fn root_specific_arguments(&self, root_path: &Path) -> Vec<OsString> {
+        match find_compile_commands_dir(root_path).await {
+            Some(dir) => {
+                let mut argument = OsString::from("--compile-commands-dir=");
+                argument.push(dir);
+                vec![argument]
+            }
+            None => Vec::new(),
+        }
+    }
+
     async fn label_for_completion(
         &self,
         completion: &lsp::CompletionItem,
@@ -245,17 +249,19 @@ impl super::LspAdapter for CLspAdapter {
     }
 }
 
+fn is_clangd_version(entry: &Path) -> bool {
+    entry
+        .file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with("clangd_"))
+}
+
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last_clangd_dir = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            let entry = entry?;
-            if entry.file_type().await?.is_dir() {
-                last_clangd_dir = Some(entry.path());
-            }
-        }
-        let clangd_dir = last_clangd_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let clangd_dir = matching_by_recency(&container_dir, is_clangd_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
         let clangd_bin = clangd_dir.join("bin/clangd");
         if clangd_bin.exists() {
             Ok(LanguageServerBinary {
@@ -273,6 +279,43 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
     .log_err()
 }
 
+/// clangd looks for the compilation database in the ancestors of each source file and in
+/// their `build` directories. Projects often generate it in other build directories, such as
+/// the ones of CMake presets, so look for it in the root's subdirectories as well.
+async fn find_compile_commands_dir(root_path: &Path) -> Option<PathBuf> {
+    if fs::metadata(root_path.join(COMPILE_COMMANDS)).await.is_ok()
+        || fs::metadata(root_path.join("build").join(COMPILE_COMMANDS))
+            .await
+            .is_ok()
+    {
+        return None;
+    }
+
+    let mut directories = Vec::new();
+    let mut entries = fs::read_dir(root_path).await.ok()?;
+    while let Some(entry) = entries.next().await {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let is_dir = entry
+            .file_type()
+            .await
+            .map_or(false, |file_type| file_type.is_dir());
+        if is_dir && !is_hidden {
+            directories.push(entry.path());
+        }
+    }
+
+    directories.sort();
+    for directory in directories {
+        if fs::metadata(directory.join(COMPILE_COMMANDS)).await.is_ok() {
+            return Some(directory);
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::{Context, TestAppContext};
@@ -281,6 +324,30 @@ mod tests {
     use std::num::NonZeroU32;
     use text::BufferId;
 
+    use super::{find_compile_commands_dir, COMPILE_COMMANDS};
+
+    #[gpui::test]
+    async fn test_find_compile_commands_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let root_path = root.path();
+        std::fs::create_dir_all(root_path.join(".cache")).unwrap();
+        std::fs::create_dir_all(root_path.join("out/release")).unwrap();
+        std::fs::create_dir_all(root_path.join("src")).unwrap();
+        std::fs::write(root_path.join(".cache").join(COMPILE_COMMANDS), "[]").unwrap();
+        assert_eq!(find_compile_commands_dir(root_path).await, None);
+
+        std::fs::write(root_path.join("out").join(COMPILE_COMMANDS), "[]").unwrap();
+        assert_eq!(
+            find_compile_commands_dir(root_path).await,
+            Some(root_path.join("out"))
+        );
+
+        // clangd finds the ones in the root and its `build` directory on its own.
+        std::fs::create_dir_all(root_path.join("build")).unwrap();
+        std::fs::write(root_path.join("build").join(COMPILE_COMMANDS), "[]").unwrap();
+        assert_eq!(find_compile_commands_dir(root_path).await, None);
+    }
+
     #[gpui::test]
     async fn test_c_autoindent(cx: &mut TestAppContext) {
         // cx.executor().set_block_on_ticks(usize::MAX..=usize::MAX);
@@ -0,0 +1,146 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::env::consts::{ARCH, OS};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::archive::{download_archive_atomically, ArchiveType};
+use util::async_maybe;
+use util::fs::matching_by_recency;
+use util::github::latest_github_release;
+use util::{github::GitHubLspBinaryVersion, ResultExt};
+
+const BINARY_NAME: &str = "clojure-lsp";
+
+pub struct ClojureLspAdapter;
+
+#[async_trait]
+impl LspAdapter for ClojureLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("clojure-lsp".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "clojure"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = latest_github_release(
+            "clojure-lsp/clojure-lsp",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let asset_name = asset_name()?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("clojure-lsp_{}", version.name));
+        let binary_path = version_dir.join(BINARY_NAME);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_archive_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_clojure_lsp_version(entry)
+    }
+}
+
+fn asset_name() -> Result<&'static str> {
+    Ok(match (OS, ARCH) {
+        ("macos", "x86_64") => "clojure-lsp-native-macos-amd64.zip",
+        ("macos", "aarch64") => "clojure-lsp-native-macos-aarch64.zip",
+        ("linux", "x86_64") => "clojure-lsp-native-linux-amd64.zip",
+        ("linux", "aarch64") => "clojure-lsp-native-linux-aarch64.zip",
+        (os, arch) => bail!("Running on unsupported platform: {os} {arch}"),
+    })
+}
+
+fn is_clojure_lsp_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("clojure-lsp_")
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_clojure_lsp_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join(BINARY_NAME);
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                path: binary_path,
+                arguments: Vec::new(),
+            })
+        } else {
+            Err(anyhow!("missing executable in directory {:?}", version_dir))
+        }
+    })
+    .await
+    .log_err()
+}
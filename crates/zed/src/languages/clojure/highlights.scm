@@ -0,0 +1,44 @@
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
+
+[(str_lit)
+ (char_lit)] @string
+(regex_lit) @string.regex
+
+(comment) @comment
+(dis_expr) @comment
+
+(num_lit) @number
+[(bool_lit)
+ (nil_lit)] @constant.builtin
+(kwd_lit) @constant
+
+(sym_lit) @variable
+
+["'" "`" "~" "~@" "@" "^" "#'" "#_" "#?" "#?@"] @operator
+
+((sym_lit) @operator
+ (#match? @operator "^(\\+|-|\\*|/|=|==|not=|>|<|>=|<=)$"))
+
+(list_lit
+  .
+  (sym_lit) @function)
+
+(list_lit
+  .
+  (sym_lit) @keyword
+  (#match? @keyword
+   "^(def|defn|defn-|defmacro|defmulti|defmethod|defonce|defprotocol|defrecord|deftype|definterface|defstruct|deftest|ns|fn|fn\\*|let|letfn|loop|recur|if|if-let|if-not|if-some|when|when-let|when-not|when-some|when-first|cond|condp|cond->|cond->>|case|do|doseq|dotimes|doto|for|while|and|or|not|quote|var|throw|try|catch|finally|new|set!|binding|with-open|with-redefs|monitor-enter|monitor-exit|reify|proxy|extend-type|extend-protocol|->|->>|some->|some->>|as->|import|require|use|refer|in-ns)$"))
+
+(list_lit
+  .
+  (sym_lit) @_def
+  .
+  (sym_lit) @function
+  (#match? @_def "^(defn|defn-|defmacro|defmulti|defmethod)$"))
+
+(list_lit
+  .
+  (sym_lit) @_ns
+  .
+  (sym_lit) @namespace
+  (#eq? @_ns "ns"))
@@ -0,0 +1,7 @@
+(list_lit
+    .
+    (sym_lit) @context
+    .
+    (sym_lit) @name
+    (#match? @context "^(ns|def|defn|defn-|defmacro|defmulti|defmethod|defprotocol|defrecord|deftype|definterface|defonce|deftest)$")
+) @item
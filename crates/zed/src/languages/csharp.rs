@@ -1,18 +1,26 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
+use futures::io::BufReader;
 use language::{LanguageServerName, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
 use smol::fs;
-use std::env::consts::ARCH;
+use std::env::consts::{ARCH, OS};
 use std::ffi::OsString;
-use std::{any::Any, path::PathBuf};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
 use util::async_maybe;
-use util::github::latest_github_release;
+use util::fs::matching_by_recency;
+use util::github::{download_release, latest_github_release};
 use util::{github::GitHubLspBinaryVersion, ResultExt};
 
+/// The name of the server's executable in the release archives, which is case-sensitive
+/// on Linux.
+const BINARY_NAME: &str = "OmniSharp";
+
 pub struct OmniSharpAdapter;
 
 #[async_trait]
@@ -29,36 +37,32 @@ impl super::LspAdapter for OmniSharpAdapter {
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let mapped_arch = match ARCH {
-            "aarch64" => Some("arm64"),
-            "x86_64" => Some("x64"),
-            _ => None,
+        let platform = match OS {
+            "macos" => "osx",
+            "linux" => "linux",
+            other => bail!("Running on unsupported os: {other}"),
+        };
+        let arch = match ARCH {
+            "aarch64" => "arm64",
+            "x86_64" => "x64",
+            other => bail!("Running on unsupported architecture: {other}"),
         };
+        let release = latest_github_release(
+            "OmniSharp/omnisharp-roslyn",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let asset_name = format!("omnisharp-{platform}-{arch}-net6.0.tar.gz");
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
 
-        match mapped_arch {
-            None => Ok(Box::new(())),
-            Some(arch) => {
-                let release = latest_github_release(
-                    "OmniSharp/omnisharp-roslyn",
-                    true,
-                    false,
-                    delegate.http_client(),
-                )
-                .await?;
-                let asset_name = format!("omnisharp-osx-{}-net6.0.tar.gz", arch);
-                let asset = release
-                    .assets
-                    .iter()
-                    .find(|asset| asset.name == asset_name)
-                    .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-                let version = GitHubLspBinaryVersion {
-                    name: release.tag_name,
-                    url: asset.browser_download_url.clone(),
-                };
-
-                Ok(Box::new(version) as Box<_>)
-            }
-        }
+        Ok(Box::new(version) as Box<_>)
     }
 
     async fn fetch_server_binary(
@@ -68,17 +72,16 @@ impl super::LspAdapter for OmniSharpAdapter {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let binary_path = container_dir.join("omnisharp");
+        let version_dir = container_dir.join(format!("omnisharp_{}", version.name));
+        let binary_path = version_dir.join(BINARY_NAME);
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
+            let mut response = download_release(&*delegate.http_client(), &version)
                 .await
                 .context("error downloading release")?;
             let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
             let archive = Archive::new(decompressed_bytes);
-            archive.unpack(container_dir).await?;
+            archive.unpack(&version_dir).await?;
         }
 
         fs::set_permissions(
@@ -111,25 +114,30 @@ impl super::LspAdapter for OmniSharpAdapter {
                 binary
             })
     }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_omnisharp_version(entry)
+    }
+}
+
+fn is_omnisharp_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("omnisharp_")
+    })
 }
 
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last_binary_path = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            let entry = entry?;
-            if entry.file_type().await?.is_file()
-                && entry
-                    .file_name()
-                    .to_str()
-                    .map_or(false, |name| name == "omnisharp")
-            {
-                last_binary_path = Some(entry.path());
+        let mut latest_binary_path = None;
+        for version_dir in matching_by_recency(&container_dir, is_omnisharp_version).await {
+            let binary_path = version_dir.join(BINARY_NAME);
+            if fs::metadata(&binary_path).await.is_ok() {
+                latest_binary_path = Some(binary_path);
+                break;
             }
         }
 
-        if let Some(path) = last_binary_path {
+        if let Some(path) = latest_binary_path {
             Ok(LanguageServerBinary {
                 path,
                 arguments: server_binary_arguments(),
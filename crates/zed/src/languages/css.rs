@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use collections::HashMap;
 use futures::StreamExt;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
@@ -96,6 +97,14 @@ impl LspAdapter for CssLspAdapter {
             "provideFormatter": true
         }))
     }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("CSS".to_string(), "css".to_string()),
+            ("SCSS".to_string(), "scss".to_string()),
+            ("Less".to_string(), "less".to_string()),
+        ])
+    }
 }
 
 async fn get_cached_server_binary(
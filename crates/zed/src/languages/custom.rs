@@ -0,0 +1,172 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use collections::{BTreeMap, HashMap, HashSet};
+use gpui::AppContext;
+use language::{
+    LanguageConfig, LanguageMatcher, LanguageQueries, LanguageRegistry, LanguageServerName,
+    LspAdapter, LspAdapterDelegate,
+};
+use lsp::LanguageServerBinary;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{any::Any, path::PathBuf, sync::Arc};
+use util::ResultExt;
+
+/// The language servers that the user configured, by the name of the server.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct CustomLanguageServerSettings(pub BTreeMap<String, CustomLanguageServer>);
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CustomLanguageServer {
+    /// The name of the language whose files the server is started for, such as
+    /// `"Python"`.
+    pub language: String,
+    /// The file extensions of the language, when it's one that Zed doesn't know. It's
+    /// added as a language without syntax highlighting.
+    #[serde(default)]
+    pub path_suffixes: Vec<String>,
+    /// The command that starts the server, which is looked up on the `PATH` unless it's
+    /// a path. `~` and environment variables are expanded.
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl Settings for CustomLanguageServerSettings {
+    const KEY: Option<&'static str> = Some("custom_language_servers");
+
+    type FileContent = Self;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// Registers the language servers from the user's settings, and registers them again
+/// whenever they change.
+pub fn init(languages: Arc<LanguageRegistry>, cx: &mut AppContext) {
+    let mut servers = CustomLanguageServerSettings::get_global(cx).clone();
+    let mut custom_languages = HashSet::default();
+    register_servers(&languages, &servers, &mut custom_languages);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_servers = CustomLanguageServerSettings::get_global(cx);
+        if *new_servers != servers {
+            servers = new_servers.clone();
+            register_servers(&languages, &servers, &mut custom_languages);
+        }
+    })
+    .detach();
+}
+
+fn register_servers(
+    languages: &LanguageRegistry,
+    servers: &CustomLanguageServerSettings,
+    custom_languages: &mut HashSet<Arc<str>>,
+) {
+    let known_languages = languages.language_names();
+    let mut lsp_adapters = HashMap::<Arc<str>, Vec<Arc<dyn LspAdapter>>>::default();
+    for (name, server) in &servers.0 {
+        let language_name: Arc<str> = server.language.as_str().into();
+        // Zed's own languages are never replaced, since that would drop their grammars.
+        let is_custom_language = custom_languages.contains(&language_name)
+            || !known_languages.contains(&server.language);
+        if !server.path_suffixes.is_empty() && is_custom_language {
+            let config = LanguageConfig {
+                name: language_name.clone(),
+                matcher: LanguageMatcher {
+                    path_suffixes: server.path_suffixes.clone(),
+                    first_line_pattern: None,
+                },
+                ..Default::default()
+            };
+            languages.register_language(
+                language_name.clone(),
+                None,
+                config.matcher.clone(),
+                Vec::new(),
+                move || Ok((config.clone(), LanguageQueries::default())),
+            );
+            custom_languages.insert(language_name.clone());
+        }
+
+        lsp_adapters
+            .entry(language_name)
+            .or_default()
+            .push(Arc::new(CustomLspAdapter {
+                name: name.clone(),
+                command: server.command.clone(),
+                args: server.args.clone(),
+            }));
+    }
+    languages.set_custom_lsp_adapters(lsp_adapters);
+}
+
+/// Starts a language server with the command from the user's settings, rather than one
+/// that Zed downloads.
+pub struct CustomLspAdapter {
+    name: String,
+    command: String,
+    args: Vec<String>,
+}
+
+impl CustomLspAdapter {
+    fn binary(&self) -> Result<LanguageServerBinary> {
+        let command = shellexpand::full(&self.command)?;
+        Ok(LanguageServerBinary {
+            path: PathBuf::from(command.as_ref()),
+            arguments: self.args.iter().map(|arg| arg.into()).collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl LspAdapter for CustomLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName(self.name.clone().into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "custom"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        Ok(Box::new(()) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        self.binary()
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        self.binary().log_err()
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        self.binary().log_err()
+    }
+}
@@ -0,0 +1,346 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use futures::{io::BufReader, AsyncBufReadExt, StreamExt};
+use gpui::{actions, AppContext, AsyncAppContext, Global, ViewContext};
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::{LanguageServer, LanguageServerBinary};
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use settings::Settings;
+use smol::process::{Child, ChildStderr, ChildStdout, Command, Stdio};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use workspace::Workspace;
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DartSettings {
+    pub sdk_path: Option<PathBuf>,
+    pub hot_reload_on_save: bool,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct DartSettingsContent {
+    /// The path to the Dart or Flutter SDK whose `dart language-server` is used.
+    /// When null, `dart` on the `PATH` is used.
+    ///
+    /// Default: null
+    sdk_path: Option<PathBuf>,
+    /// Whether to hot reload the running Flutter app when a Dart file is saved.
+    /// The app has to be started with the `dart: run flutter app` action, or
+    /// through a language server that supports hot reloading.
+    ///
+    /// Default: false
+    hot_reload_on_save: Option<bool>,
+}
+
+impl Settings for DartSettings {
+    const KEY: Option<&'static str> = Some("dart");
+
+    type FileContent = DartSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// The Dart analysis server is part of the SDK, so it is never downloaded.
+pub struct DartLanguageServer {
+    sdk_path: Option<PathBuf>,
+}
+
+impl DartLanguageServer {
+    pub fn new(sdk_path: Option<PathBuf>) -> Self {
+        DartLanguageServer { sdk_path }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for DartLanguageServer {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("dart".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "dart"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(()))
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _version: Box<dyn 'static + Send + Any>,
+        _container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        Err(anyhow!("dart must be installed from dart.dev/get-dart"))
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        let path = match &self.sdk_path {
+            Some(sdk_path) => sdk_path.join("bin").join("dart"),
+            None => PathBuf::from("dart"),
+        };
+        Some(LanguageServerBinary {
+            path,
+            arguments: vec!["language-server".into(), "--protocol=lsp".into()],
+        })
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        Some(json!({
+            "onlyAnalyzeProjectsWithOpenFiles": true,
+            "suggestFromUnimportedLibraries": true,
+        }))
+    }
+}
+
+actions!(dart, [RunFlutterApp, StopFlutterApp]);
+
+/// The command that a language server advertises when it can hot reload the Flutter app
+/// of a file, which is passed the file's URI. The Dart analysis server doesn't, but
+/// servers that wrap it together with the Flutter daemon can.
+const HOT_RELOAD_COMMAND: &str = "flutter.hotReload";
+
+/// The `flutter run` processes that Zed started, by the directory of their app's
+/// `pubspec.yaml`. Only these processes are ever signalled.
+#[derive(Default)]
+struct FlutterApps(HashMap<PathBuf, Child>);
+
+impl Global for FlutterApps {}
+
+/// Runs Flutter apps, and hot reloads them when Dart files are saved, if enabled.
+pub(super) fn init_flutter(cx: &mut AppContext) {
+    cx.set_global(FlutterApps::default());
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(run_flutter_app)
+            .register_action(stop_flutter_app);
+    })
+    .detach();
+    cx.observe_new_views(|_: &mut Editor, cx| {
+        cx.subscribe(&cx.view().clone(), |editor, _, event, cx| {
+            if let EditorEvent::Saved = event {
+                hot_reload_flutter_app(editor, cx);
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Starts `flutter run` for the app of the active file, replacing the one that Zed
+/// started before for the same app.
+fn run_flutter_app(workspace: &mut Workspace, _: &RunFlutterApp, cx: &mut ViewContext<Workspace>) {
+    let Some(path) = active_file_path(workspace, cx) else {
+        return;
+    };
+    let flutter = match &DartSettings::get_global(cx).sdk_path {
+        Some(sdk_path) => sdk_path.join("bin").join("flutter"),
+        None => PathBuf::from("flutter"),
+    };
+    cx.spawn(|_, mut cx| async move {
+        let app_dir = flutter_app_dir(&path)
+            .await
+            .with_context(|| format!("no pubspec.yaml found for {path:?}"))?;
+        let mut child = Command::new(&flutter)
+            .arg("run")
+            .current_dir(&app_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to start {flutter:?} run"))?;
+        log_output(child.stdout.take(), child.stderr.take(), &cx);
+        cx.update(|cx| {
+            cx.global_mut::<FlutterApps>().0.insert(app_dir, child);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn stop_flutter_app(
+    workspace: &mut Workspace,
+    _: &StopFlutterApp,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(path) = active_file_path(workspace, cx) else {
+        return;
+    };
+    cx.spawn(|_, mut cx| async move {
+        let Some(app_dir) = flutter_app_dir(&path).await else {
+            return Ok(());
+        };
+        // The process is killed when it's dropped.
+        cx.update(|cx| {
+            cx.global_mut::<FlutterApps>().0.remove(&app_dir);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn active_file_path(workspace: &Workspace, cx: &AppContext) -> Option<PathBuf> {
+    let project_path = workspace.active_item(cx)?.project_path(cx)?;
+    workspace
+        .project()
+        .read(cx)
+        .absolute_path(&project_path, cx)
+}
+
+/// Logs the output of `flutter run`, which has to be read so that the process doesn't
+/// block on writing it.
+fn log_output(stdout: Option<ChildStdout>, stderr: Option<ChildStderr>, cx: &AsyncAppContext) {
+    if let Some(stdout) = stdout {
+        cx.background_executor()
+            .spawn(async move {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Some(Ok(line)) = lines.next().await {
+                    log::info!("flutter run: {line}");
+                }
+            })
+            .detach();
+    }
+    if let Some(stderr) = stderr {
+        cx.background_executor()
+            .spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Some(Ok(line)) = lines.next().await {
+                    log::warn!("flutter run: {line}");
+                }
+            })
+            .detach();
+    }
+}
+
+/// Hot reloads the app of a saved Dart file, through its language server when the server
+/// supports it, or else through the `flutter run` that Zed started for the app.
+fn hot_reload_flutter_app(editor: &Editor, cx: &mut ViewContext<Editor>) {
+    if !DartSettings::get_global(cx).hot_reload_on_save {
+        return;
+    }
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let buffer = buffer.read(cx);
+    if buffer
+        .language()
+        .map_or(true, |language| language.name().as_ref() != "Dart")
+    {
+        return;
+    }
+    let Some(path) = buffer
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx))
+    else {
+        return;
+    };
+
+    let server = editor.workspace().and_then(|workspace| {
+        workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .language_servers_for_buffer(buffer, cx)
+            .map(|(_, server)| server)
+            .find(|server| supports_hot_reload(server))
+            .cloned()
+    });
+    if let Some(server) = server {
+        let Ok(uri) = lsp::Url::from_file_path(&path) else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                server
+                    .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                        command: HOT_RELOAD_COMMAND.into(),
+                        arguments: vec![json!(uri)],
+                        ..Default::default()
+                    })
+                    .await?;
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        return;
+    }
+
+    cx.spawn(|_, mut cx| async move {
+        let Some(app_dir) = flutter_app_dir(&path).await else {
+            return Ok(());
+        };
+        cx.update(|cx| signal_flutter_app(&app_dir, cx))?
+    })
+    .detach_and_log_err(cx);
+}
+
+fn supports_hot_reload(server: &LanguageServer) -> bool {
+    server
+        .capabilities()
+        .execute_command_provider
+        .as_ref()
+        .map_or(false, |provider| {
+            provider
+                .commands
+                .iter()
+                .any(|command| command == HOT_RELOAD_COMMAND)
+        })
+}
+
+/// Returns the directory of the `pubspec.yaml` that the given file belongs to.
+async fn flutter_app_dir(path: &Path) -> Option<PathBuf> {
+    for dir in path.ancestors().skip(1) {
+        if smol::fs::metadata(dir.join("pubspec.yaml")).await.is_ok() {
+            return Some(dir.to_path_buf());
+        }
+    }
+    None
+}
+
+/// Sends `SIGUSR1`, which `flutter run` hot reloads on, to the `flutter run` that Zed
+/// started for the app, if it's still running.
+fn signal_flutter_app(app_dir: &Path, cx: &mut AppContext) -> Result<()> {
+    let apps = &mut cx.global_mut::<FlutterApps>().0;
+    let Some(child) = apps.get_mut(app_dir) else {
+        return Ok(());
+    };
+    if child.try_status()?.is_some() {
+        apps.remove(app_dir);
+        return Ok(());
+    }
+    // The process hasn't been waited for, so its pid can't have been reused.
+    let pid = child.id() as libc::pid_t;
+    if unsafe { libc::kill(pid, libc::SIGUSR1) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("failed to signal flutter run");
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+(identifier) @variable
+
+(type_identifier) @type
+
+(class_definition
+  name: (identifier) @type)
+
+(enum_declaration
+  name: (identifier) @type)
+
+(enum_constant
+  name: (identifier) @constant)
+
+(function_signature
+  name: (identifier) @function)
+
+(getter_signature
+  name: (identifier) @property)
+
+(setter_signature
+  name: (identifier) @property)
+
+(constructor_signature
+  name: (identifier) @constructor)
+
+(annotation) @attribute
+
+((identifier) @constant
+  (#match? @constant "^_?[A-Z][A-Z0-9_]+$"))
+
+[
+  (comment)
+  (documentation_comment)
+] @comment
+
+(string_literal) @string
+(escape_sequence) @string.escape
+
+[
+  (decimal_integer_literal)
+  (decimal_floating_point_literal)
+  (hex_integer_literal)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+(null_literal) @constant.builtin
+
+(this) @variable.special
+(super) @variable.special
+
+[
+  "abstract"
+  "as"
+  "assert"
+  "async"
+  "await"
+  "break"
+  "case"
+  "catch"
+  "class"
+  "const"
+  "continue"
+  "default"
+  "do"
+  "else"
+  "enum"
+  "export"
+  "extends"
+  "extension"
+  "factory"
+  "final"
+  "finally"
+  "for"
+  "get"
+  "if"
+  "implements"
+  "import"
+  "in"
+  "is"
+  "late"
+  "library"
+  "mixin"
+  "new"
+  "on"
+  "part"
+  "required"
+  "return"
+  "set"
+  "static"
+  "switch"
+  "throw"
+  "try"
+  "var"
+  "while"
+  "with"
+  "yield"
+] @keyword
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ","
+  ";"
+  ":"
+] @punctuation.delimiter
@@ -0,0 +1,29 @@
+(class_definition
+  "class" @context
+  name: (_) @name) @item
+
+(mixin_declaration
+  "mixin" @context
+  (identifier) @name) @item
+
+(extension_declaration
+  "extension" @context
+  name: (_) @name) @item
+
+(enum_declaration
+  "enum" @context
+  name: (_) @name) @item
+
+(function_signature
+  name: (_) @name) @item
+
+(getter_signature
+  "get" @context
+  name: (_) @name) @item
+
+(setter_signature
+  "set" @context
+  name: (_) @name) @item
+
+(constructor_signature
+  name: (_) @name) @item
@@ -1,18 +1,24 @@
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::HashMap;
-use futures::StreamExt;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::{CodeActionKind, LanguageServerBinary};
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::json;
 use settings::Settings;
-use smol::{fs, fs::File};
-use std::{any::Any, env::consts, ffi::OsString, path::PathBuf, sync::Arc};
+use smol::fs;
+use std::{
+    any::Any,
+    env::consts,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use util::{
+    archive::{download_archive_atomically, ArchiveType},
     async_maybe,
-    fs::remove_matching,
+    fs::matching_by_recency,
     github::{latest_github_release, GitHubLspBinaryVersion},
     ResultExt,
 };
@@ -78,10 +84,7 @@ impl LspAdapter for DenoLspAdapter {
             .iter()
             .find(|asset| asset.name == asset_name)
             .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url.clone(),
-        };
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -92,38 +95,19 @@ impl LspAdapter for DenoLspAdapter {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let zip_path = container_dir.join(format!("deno_{}.zip", version.name));
         let version_dir = container_dir.join(format!("deno_{}", version.name));
         let binary_path = version_dir.join("deno");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("error downloading release")?;
-            let mut file = File::create(&zip_path).await?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
-
-            let unzip_status = smol::process::Command::new("unzip")
-                .current_dir(&container_dir)
-                .arg(&zip_path)
-                .arg("-d")
-                .arg(&version_dir)
-                .output()
-                .await?
-                .status;
-            if !unzip_status.success() {
-                Err(anyhow!("failed to unzip deno archive"))?;
-            }
-
-            remove_matching(&container_dir, |entry| entry != version_dir).await;
+            download_archive_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
         }
 
         Ok(LanguageServerBinary {
@@ -147,6 +131,10 @@ impl LspAdapter for DenoLspAdapter {
         get_cached_server_binary(container_dir).await
     }
 
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_deno_version(entry)
+    }
+
     fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
         Some(vec![
             CodeActionKind::QUICKFIX,
@@ -200,15 +188,21 @@ impl LspAdapter for DenoLspAdapter {
     }
 }
 
+fn is_deno_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        let name = name.to_string_lossy();
+        name.starts_with("deno_") && !name.ends_with(".zip")
+    })
+}
+
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            last = Some(entry?.path());
-        }
+        let latest = matching_by_recency(&container_dir, is_deno_version)
+            .await
+            .into_iter()
+            .next();
 
-        match last {
+        match latest {
             Some(path) if path.is_dir() => {
                 let binary = path.join("deno");
                 if fs::metadata(&binary).await.is_ok() {
@@ -0,0 +1,41 @@
+[
+  "FROM"
+  "AS"
+  "RUN"
+  "CMD"
+  "LABEL"
+  "EXPOSE"
+  "ENV"
+  "ADD"
+  "COPY"
+  "ENTRYPOINT"
+  "VOLUME"
+  "USER"
+  "WORKDIR"
+  "ARG"
+  "ONBUILD"
+  "STOPSIGNAL"
+  "HEALTHCHECK"
+  "SHELL"
+  "MAINTAINER"
+] @keyword
+
+(comment) @comment
+
+[
+  (double_quoted_string)
+  (single_quoted_string)
+  (json_string)
+] @string
+
+(escape_sequence) @string.escape
+
+(image_name) @type
+(image_tag) @constant
+(image_alias) @label
+
+(param) @attribute
+
+(variable) @variable
+
+(expansion) @embedded
@@ -0,0 +1,2 @@
+((shell_command) @content
+  (#set! "language" "bash"))
@@ -7,20 +7,21 @@ use lsp::{CompletionItemKind, LanguageServerBinary, SymbolKind};
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 use settings::Settings;
-use smol::fs::{self, File};
+use smol::fs;
 use std::{
     any::Any,
     env::consts,
     ops::Deref,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering::SeqCst},
         Arc,
     },
 };
 use util::{
+    archive::{download_archive_atomically, download_binary_atomically, ArchiveType},
     async_maybe,
-    fs::remove_matching,
+    fs::matching_by_recency,
     github::{latest_github_release, GitHubLspBinaryVersion},
     ResultExt,
 };
@@ -120,10 +121,7 @@ impl LspAdapter for ElixirLspAdapter {
             .find(|asset| asset.name == asset_name)
             .ok_or_else(|| anyhow!("no asset found matching {asset_name:?}"))?;
 
-        let version = GitHubLspBinaryVersion {
-            name: release.tag_name.clone(),
-            url: asset.browser_download_url.clone(),
-        };
+        let version = GitHubLspBinaryVersion::new(release.tag_name.clone(), asset);
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -134,42 +132,19 @@ impl LspAdapter for ElixirLspAdapter {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let zip_path = container_dir.join(format!("elixir-ls_{}.zip", version.name));
-        let folder_path = container_dir.join("elixir-ls");
+        let folder_path = container_dir.join(format!("elixir-ls_{}", version.name));
         let binary_path = folder_path.join("language_server.sh");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("error downloading release")?;
-            let mut file = File::create(&zip_path)
-                .await
-                .with_context(|| format!("failed to create file {}", zip_path.display()))?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
-
-            fs::create_dir_all(&folder_path)
-                .await
-                .with_context(|| format!("failed to create directory {}", folder_path.display()))?;
-            let unzip_status = smol::process::Command::new("unzip")
-                .arg(&zip_path)
-                .arg("-d")
-                .arg(&folder_path)
-                .output()
-                .await?
-                .status;
-            if !unzip_status.success() {
-                Err(anyhow!("failed to unzip elixir-ls archive"))?;
-            }
-
-            remove_matching(&container_dir, |entry| entry != folder_path).await;
+            download_archive_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &folder_path,
+            )
+            .await
+            .context("error downloading release")?;
         }
 
         Ok(LanguageServerBinary {
@@ -193,6 +168,10 @@ impl LspAdapter for ElixirLspAdapter {
         get_cached_server_binary_elixir_ls(container_dir).await
     }
 
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_elixir_ls_version(entry)
+    }
+
     async fn label_for_completion(
         &self,
         completion: &lsp::CompletionItem,
@@ -277,19 +256,34 @@ impl LspAdapter for ElixirLspAdapter {
     }
 }
 
+fn is_elixir_ls_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        let name = name.to_string_lossy();
+        name.starts_with("elixir-ls_") && !name.ends_with(".zip")
+    })
+}
+
 async fn get_cached_server_binary_elixir_ls(
     container_dir: PathBuf,
 ) -> Option<LanguageServerBinary> {
-    let server_path = container_dir.join("elixir-ls/language_server.sh");
-    if server_path.exists() {
-        Some(LanguageServerBinary {
-            path: server_path,
-            arguments: vec![],
-        })
-    } else {
-        log::error!("missing executable in directory {:?}", server_path);
-        None
-    }
+    async_maybe!({
+        let folder_path = matching_by_recency(&container_dir, is_elixir_ls_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let server_path = folder_path.join("language_server.sh");
+        if server_path.exists() {
+            Ok(LanguageServerBinary {
+                path: server_path,
+                arguments: vec![],
+            })
+        } else {
+            Err(anyhow!("missing executable in directory {:?}", folder_path))
+        }
+    })
+    .await
+    .log_err()
 }
 
 pub struct NextLspAdapter;
@@ -323,10 +317,7 @@ impl LspAdapter for NextLspAdapter {
             .iter()
             .find(|asset| asset.name == asset_name)
             .with_context(|| format!("no asset found matching {asset_name:?}"))?;
-        let version = GitHubLspBinaryVersion {
-            name: version,
-            url: asset.browser_download_url.clone(),
-        };
+        let version = GitHubLspBinaryVersion::new(version, asset);
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -341,26 +332,14 @@ impl LspAdapter for NextLspAdapter {
         let binary_path = container_dir.join("next-ls");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .map_err(|err| anyhow!("error downloading release: {}", err))?;
-
-            let mut file = smol::fs::File::create(&binary_path).await?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
-
-            fs::set_permissions(
+            download_binary_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                version.sha256.as_deref(),
                 &binary_path,
-                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
             )
-            .await?;
+            .await
+            .context("error downloading release")?;
         }
 
         Ok(LanguageServerBinary {
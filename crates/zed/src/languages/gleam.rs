@@ -11,7 +11,7 @@ use futures::StreamExt;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
 use smol::fs;
-use util::github::{latest_github_release, GitHubLspBinaryVersion};
+use util::github::{download_release, latest_github_release, GitHubLspBinaryVersion};
 use util::{async_maybe, ResultExt};
 
 fn server_binary_arguments() -> Vec<OsString> {
@@ -47,10 +47,10 @@ impl LspAdapter for GleamLspAdapter {
             .iter()
             .find(|asset| asset.name == asset_name)
             .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        Ok(Box::new(GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url.clone(),
-        }))
+        Ok(Box::new(GitHubLspBinaryVersion::new(
+            release.tag_name,
+            asset,
+        )))
     }
 
     async fn fetch_server_binary(
@@ -63,9 +63,7 @@ impl LspAdapter for GleamLspAdapter {
         let binary_path = container_dir.join("gleam");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
+            let mut response = download_release(&*delegate.http_client(), &version)
                 .await
                 .map_err(|err| anyhow!("error downloading release: {}", err))?;
             let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
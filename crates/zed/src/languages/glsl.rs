@@ -0,0 +1,148 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::env::consts::{ARCH, OS};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    archive::{download_archive_atomically, ArchiveType},
+    async_maybe,
+    fs::matching_by_recency,
+    github::{latest_github_release, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+pub struct GlslLspAdapter;
+
+#[async_trait]
+impl LspAdapter for GlslLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("glsl_analyzer".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "glsl"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = latest_github_release(
+            "nolanderc/glsl_analyzer",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let asset_name = asset_name()?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("glsl_analyzer_{}", version.name));
+        let binary_path = binary_path(&version_dir);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_archive_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: vec![],
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_glsl_analyzer_version(entry)
+    }
+}
+
+fn asset_name() -> Result<&'static str> {
+    Ok(match (OS, ARCH) {
+        ("macos", "x86_64") => "x86_64-macos.zip",
+        ("macos", "aarch64") => "aarch64-macos.zip",
+        ("linux", "x86_64") => "x86_64-linux-musl.zip",
+        ("linux", "aarch64") => "aarch64-linux-musl.zip",
+        (os, arch) => bail!("Running on unsupported platform: {os} {arch}"),
+    })
+}
+
+fn binary_path(version_dir: &Path) -> PathBuf {
+    version_dir.join("bin/glsl_analyzer")
+}
+
+fn is_glsl_analyzer_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        let name = name.to_string_lossy();
+        name.starts_with("glsl_analyzer_") && !name.ends_with(".zip")
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_glsl_analyzer_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = binary_path(&version_dir);
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                path: binary_path,
+                arguments: vec![],
+            })
+        } else {
+            Err(anyhow!(
+                "missing glsl_analyzer binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
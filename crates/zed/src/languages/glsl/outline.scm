@@ -0,0 +1,11 @@
+(struct_specifier
+    "struct" @context
+    name: (_) @name) @item
+
+(function_definition
+    type: (_)? @context
+    declarator: (function_declarator
+        declarator: (_) @name
+        parameters: (parameter_list
+            "(" @context
+            ")" @context))) @item
@@ -1,6 +1,5 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use futures::StreamExt;
 use gpui::{AsyncAppContext, Task};
 pub use language::*;
 use lazy_static::lazy_static;
@@ -9,16 +8,23 @@ use regex::Regex;
 use smol::{fs, process};
 use std::{
     any::Any,
-    ffi::{OsStr, OsString},
+    ffi::OsString,
     ops::Range,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str,
     sync::{
         atomic::{AtomicBool, Ordering::SeqCst},
         Arc,
     },
 };
-use util::{async_maybe, fs::remove_matching, github::latest_github_release, ResultExt};
+use util::{
+    archive::check_unverified_install,
+    async_maybe,
+    audit_log::{self, AuditEvent, AuditEventKind},
+    fs::matching_by_recency,
+    github::latest_github_release,
+    ResultExt,
+};
 
 fn server_binary_arguments() -> Vec<OsString> {
     vec!["-mode=stdio".into()]
@@ -98,11 +104,6 @@ impl super::LspAdapter for GoLspAdapter {
             let binary_path = container_dir.join(&format!("gopls_{version}"));
             if let Ok(metadata) = fs::metadata(&binary_path).await {
                 if metadata.is_file() {
-                    remove_matching(&container_dir, |entry| {
-                        entry != binary_path && entry.file_name() != Some(OsStr::new("gobin"))
-                    })
-                    .await;
-
                     return Ok(LanguageServerBinary {
                         path: binary_path.to_path_buf(),
                         arguments: server_binary_arguments(),
@@ -116,8 +117,20 @@ impl super::LspAdapter for GoLspAdapter {
             return Ok(path);
         }
 
+        check_unverified_install("gopls with go install").await?;
         let gobin_dir = container_dir.join("gobin");
         fs::create_dir_all(&gobin_dir).await?;
+        audit_log::record(
+            AuditEvent::new(
+                AuditEventKind::Command,
+                "go",
+                "go install golang.org/x/tools/gopls@latest",
+            )
+            .details(format!(
+                "GO111MODULE=on GOBIN={} go install golang.org/x/tools/gopls@latest",
+                gobin_dir.display()
+            )),
+        );
         let install_output = process::Command::new("go")
             .env("GO111MODULE", "on")
             .env("GOBIN", &gobin_dir)
@@ -169,6 +182,10 @@ impl super::LspAdapter for GoLspAdapter {
             })
     }
 
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_gopls_version(entry)
+    }
+
     async fn label_for_completion(
         &self,
         completion: &lsp::CompletionItem,
@@ -329,23 +346,19 @@ impl super::LspAdapter for GoLspAdapter {
     }
 }
 
+fn is_gopls_version(entry: &Path) -> bool {
+    entry
+        .file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with("gopls_"))
+}
+
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last_binary_path = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            let entry = entry?;
-            if entry.file_type().await?.is_file()
-                && entry
-                    .file_name()
-                    .to_str()
-                    .map_or(false, |name| name.starts_with("gopls_"))
-            {
-                last_binary_path = Some(entry.path());
-            }
-        }
-
-        if let Some(path) = last_binary_path {
+        let latest = matching_by_recency(&container_dir, is_gopls_version)
+            .await
+            .into_iter()
+            .next();
+        if let Some(path) = latest {
             Ok(LanguageServerBinary {
                 path,
                 arguments: server_binary_arguments(),
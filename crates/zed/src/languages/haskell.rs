@@ -1,8 +1,26 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
+use futures::StreamExt;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
-use std::{any::Any, path::PathBuf};
+use smol::{
+    fs::{self, File},
+    process::Command,
+};
+use std::{
+    any::Any,
+    env::consts::{ARCH, OS},
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    audit_log::{self, AuditEvent, AuditEventKind},
+    fs::matching_by_recency,
+    github::{download_release, latest_github_release, GitHubLspBinaryVersion, GithubReleaseAsset},
+    paths, ResultExt,
+};
+
+const WRAPPER_NAME: &str = "haskell-language-server-wrapper";
 
 pub struct HaskellLanguageServer;
 
@@ -18,38 +36,215 @@ impl LspAdapter for HaskellLanguageServer {
 
     async fn fetch_latest_server_version(
         &self,
-        _: &dyn LspAdapterDelegate,
+        delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Any + Send>> {
-        Ok(Box::new(()))
+        let release = latest_github_release(
+            "haskell/haskell-language-server",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let asset = release_asset(&release.assets)?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+        Ok(Box::new(version) as Box<_>)
     }
 
     async fn fetch_server_binary(
         &self,
-        _version: Box<dyn 'static + Send + Any>,
-        _container_dir: PathBuf,
-        _: &dyn LspAdapterDelegate,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
-        Err(anyhow!(
-            "hls (haskell language server) must be installed via ghcup"
-        ))
+        // Prefer an installation on the PATH or managed by ghcup, which has HLS builds for
+        // the GHC versions installed with it.
+        if let Some(binary) = installed_server_binary().await {
+            return Ok(binary);
+        }
+
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("hls_{}", version.name));
+        let binary_path = version_dir.join("bin").join(WRAPPER_NAME);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let archive_path = container_dir.join(format!("hls_{}.tar.xz", version.name));
+            let mut response = download_release(&*delegate.http_client(), &version)
+                .await
+                .context("error downloading release")?;
+            if !response.status().is_success() {
+                bail!("download failed with status {}", response.status());
+            }
+            let mut file = File::create(&archive_path).await?;
+            futures::io::copy(response.body_mut(), &mut file).await?;
+
+            let result = install_bindist(&archive_path, &container_dir, &version_dir).await;
+            fs::remove_file(&archive_path).await.log_err();
+            result?;
+        }
+
+        Ok(server_binary(binary_path))
     }
 
     async fn cached_server_binary(
         &self,
-        _: PathBuf,
+        container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        Some(LanguageServerBinary {
-            path: "haskell-language-server-wrapper".into(),
-            arguments: vec!["lsp".into()],
-        })
+        if let Some(binary) = installed_server_binary().await {
+            return Some(binary);
+        }
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_hls_version(entry)
     }
+}
 
-    fn can_be_reinstalled(&self) -> bool {
-        false
+/// The binary distribution contains a build of HLS for each supported GHC version.
+/// The wrapper starts the one matching the GHC version that the project is built with.
+fn server_binary(wrapper_path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path: wrapper_path,
+        arguments: vec!["--lsp".into()],
     }
+}
+
+fn release_asset(assets: &[GithubReleaseAsset]) -> Result<&GithubReleaseAsset> {
+    let platform = match OS {
+        "macos" => format!("{ARCH}-apple-darwin"),
+        "linux" => format!("{ARCH}-linux-"),
+        other => bail!("Running on unsupported os: {other}"),
+    };
+    let mut candidates = assets
+        .iter()
+        .filter(|asset| asset.name.contains(&platform) && asset.name.ends_with(".tar.xz"))
+        .collect::<Vec<_>>();
+    // There are several Linux builds, of which the Ubuntu one has the fewest dependencies.
+    candidates.sort_by_key(|asset| !asset.name.contains("ubuntu"));
+    candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("no haskell-language-server release found for {platform}"))
+}
+
+/// Installs a binary distribution the way ghcup does, by running its makefile.
+async fn install_bindist(archive_path: &Path, container_dir: &Path, prefix: &Path) -> Result<()> {
+    let extract_dir = container_dir.join("hls_bindist");
+    fs::create_dir_all(&extract_dir).await?;
+    let result = async_maybe!({
+        audit_log::record(
+            AuditEvent::new(
+                AuditEventKind::Command,
+                "haskell-language-server",
+                format!("tar -xJf {}", archive_path.display()),
+            )
+            .details(format!(
+                "tar -xJf {} -C {}",
+                archive_path.display(),
+                extract_dir.display()
+            )),
+        );
+        let output = Command::new("tar")
+            .arg("-xJf")
+            .arg(archive_path)
+            .arg("-C")
+            .arg(&extract_dir)
+            .output()
+            .await?;
+        if !output.status.success() {
+            bail!(
+                "failed to extract haskell-language-server: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
 
-    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
-        None
+        let mut entries = fs::read_dir(&extract_dir).await?;
+        let bindist_dir = entries
+            .next()
+            .await
+            .ok_or_else(|| anyhow!("empty haskell-language-server archive"))??
+            .path();
+        audit_log::record(
+            AuditEvent::new(
+                AuditEventKind::Command,
+                "haskell-language-server",
+                format!("make install PREFIX={}", prefix.display()),
+            )
+            .details(format!(
+                "make install PREFIX={} (in {})",
+                prefix.display(),
+                bindist_dir.display()
+            )),
+        );
+        let output = Command::new("make")
+            .arg("install")
+            .arg(format!("PREFIX={}", prefix.display()))
+            .current_dir(&bindist_dir)
+            .output()
+            .await?;
+        if !output.status.success() {
+            bail!(
+                "failed to install haskell-language-server: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(())
+    })
+    .await;
+    fs::remove_dir_all(&extract_dir).await.log_err();
+    result
+}
+
+/// Returns the wrapper on the `PATH`, or else the one in ghcup's directory, for when Zed
+/// is started from a desktop environment whose `PATH` doesn't include it.
+async fn installed_server_binary() -> Option<LanguageServerBinary> {
+    if let Ok(path) = which::which(WRAPPER_NAME) {
+        return Some(server_binary(path));
     }
+    let path = paths::HOME.join(".ghcup").join("bin").join(WRAPPER_NAME);
+    fs::metadata(&path).await.ok()?;
+    Some(server_binary(path))
+}
+
+/// Whether the entry is an installed version, rather than the directory that a
+/// version is built in.
+fn is_hls_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        let name = name.to_string_lossy();
+        name.starts_with("hls_") && name != "hls_bindist"
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_hls_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("bin").join(WRAPPER_NAME);
+        if binary_path.exists() {
+            Ok(server_binary(binary_path))
+        } else {
+            Err(anyhow!(
+                "missing haskell-language-server binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
 }
@@ -0,0 +1,286 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::{AsyncReadExt, StreamExt};
+use gpui::AppContext;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lazy_static::lazy_static;
+use lsp::LanguageServerBinary;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::Settings;
+use smol::fs;
+use std::{
+    any::Any,
+    env::{self, consts},
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::{
+    archive::{download_archive_atomically, ArchiveType},
+    async_maybe,
+    fs::matching_by_recency,
+    paths, ResultExt,
+};
+
+const SNAPSHOTS_URL: &str = "https://download.eclipse.org/jdtls/snapshots";
+const LAUNCHER_JAR_PREFIX: &str = "org.eclipse.equinox.launcher_";
+
+lazy_static! {
+    /// JDT LS stores its index of a project in a data directory that can't be shared
+    /// between projects, so each worktree root gets its own directory in here.
+    static ref WORKSPACES_DIR: PathBuf = paths::SUPPORT_DIR.join("jdtls-workspaces");
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JavaSettings {
+    pub jdk_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct JavaSettingsContent {
+    /// The path to the JDK used to run JDT LS, which needs Java 17 or newer.
+    /// When null, the JDK at `JAVA_HOME` is used, falling back to `java` on the `PATH`.
+    ///
+    /// Default: null
+    jdk_path: Option<PathBuf>,
+}
+
+impl Settings for JavaSettings {
+    const KEY: Option<&'static str> = Some("java");
+
+    type FileContent = JavaSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+struct JdtlsVersion {
+    name: String,
+    url: String,
+}
+
+pub struct JdtlsLspAdapter {
+    jdk_path: Option<PathBuf>,
+}
+
+impl JdtlsLspAdapter {
+    pub fn new(jdk_path: Option<PathBuf>) -> Self {
+        JdtlsLspAdapter { jdk_path }
+    }
+
+    fn java_path(&self) -> PathBuf {
+        self.jdk_path
+            .clone()
+            .or_else(|| env::var_os("JAVA_HOME").map(PathBuf::from))
+            .map(|jdk_path| jdk_path.join("bin").join("java"))
+            .unwrap_or_else(|| PathBuf::from("java"))
+    }
+
+    async fn server_binary(&self, version_dir: &Path) -> Result<LanguageServerBinary> {
+        let plugins_dir = version_dir.join("plugins");
+        let mut launcher_jar = None;
+        let mut entries = fs::read_dir(&plugins_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let path = entry?.path();
+            let is_launcher = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| {
+                    name.starts_with(LAUNCHER_JAR_PREFIX) && name.ends_with(".jar")
+                });
+            if is_launcher {
+                launcher_jar = Some(path);
+            }
+        }
+        let launcher_jar =
+            launcher_jar.ok_or_else(|| anyhow!("no launcher jar found in {:?}", plugins_dir))?;
+
+        Ok(LanguageServerBinary {
+            path: self.java_path(),
+            arguments: vec![
+                "-Declipse.application=org.eclipse.jdt.ls.core.id1".into(),
+                "-Dosgi.bundles.defaultStartLevel=4".into(),
+                "-Declipse.product=org.eclipse.jdt.ls.core.product".into(),
+                "-Xmx1G".into(),
+                "--add-modules=ALL-SYSTEM".into(),
+                "--add-opens".into(),
+                "java.base/java.util=ALL-UNNAMED".into(),
+                "--add-opens".into(),
+                "java.base/java.lang=ALL-UNNAMED".into(),
+                "-jar".into(),
+                launcher_jar.into(),
+                "-configuration".into(),
+                version_dir.join(configuration_dir_name()).into(),
+            ],
+        })
+    }
+
+    async fn get_cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        async_maybe!({
+            let version_dir = matching_by_recency(&container_dir, is_jdtls_version)
+                .await
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("no cached JDT LS version"))?;
+            self.server_binary(&version_dir).await
+        })
+        .await
+        .log_err()
+    }
+}
+
+#[async_trait]
+impl LspAdapter for JdtlsLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("jdtls".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "jdtls"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let mut response = delegate
+            .http_client()
+            .get(
+                &format!("{SNAPSHOTS_URL}/latest.txt"),
+                Default::default(),
+                true,
+            )
+            .await
+            .context("error fetching the latest JDT LS version")?;
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+
+        let file_name = body.trim();
+        let name = file_name
+            .strip_suffix(".tar.gz")
+            .ok_or_else(|| anyhow!("unexpected JDT LS snapshot {:?}", file_name))?;
+        Ok(Box::new(JdtlsVersion {
+            name: name.to_string(),
+            url: format!("{SNAPSHOTS_URL}/{file_name}"),
+        }) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<JdtlsVersion>().unwrap();
+        let version_dir = container_dir.join(&version.name);
+
+        if fs::metadata(&version_dir).await.is_err() {
+            // The version's directory counts as installed as soon as it exists, so it's
+            // only moved into place once it's fully unpacked.
+            download_archive_atomically(
+                &*delegate.http_client(),
+                &version.url,
+                ArchiveType::TarGz,
+                None,
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
+        }
+
+        self.server_binary(&version_dir).await
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        self.get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        self.get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["-version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_jdtls_version(entry)
+    }
+
+    async fn root_specific_arguments(&self, root_path: &Path) -> Vec<OsString> {
+        vec![
+            "-data".into(),
+            WORKSPACES_DIR.join(workspace_dir_name(root_path)).into(),
+        ]
+    }
+}
+
+fn is_jdtls_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("jdt-language-server-")
+    })
+}
+
+fn configuration_dir_name() -> &'static str {
+    match (consts::OS, consts::ARCH) {
+        ("macos", "aarch64") => "config_mac_arm",
+        ("macos", _) => "config_mac",
+        ("windows", _) => "config_win",
+        (_, "aarch64") => "config_linux_arm",
+        _ => "config_linux",
+    }
+}
+
+/// Names a worktree root's data directory after its path, so that reopening a project
+/// reuses the index JDT LS built for it before.
+fn workspace_dir_name(root_path: &Path) -> String {
+    root_path
+        .to_string_lossy()
+        .trim_start_matches(['/', '\\'])
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::workspace_dir_name;
+    use std::path::Path;
+
+    #[test]
+    fn test_workspace_dir_name() {
+        assert_eq!(
+            workspace_dir_name(Path::new("/Users/me/code/my-app")),
+            "Users_me_code_my-app"
+        );
+        assert_eq!(
+            workspace_dir_name(Path::new("/home/me/spring boot.demo")),
+            "home_me_spring_boot.demo"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+(class_declaration
+    "class" @context
+    name: (identifier) @name) @item
+
+(interface_declaration
+    "interface" @context
+    name: (identifier) @name) @item
+
+(enum_declaration
+    "enum" @context
+    name: (identifier) @name) @item
+
+(record_declaration
+    "record" @context
+    name: (identifier) @name) @item
+
+(annotation_type_declaration
+    "@interface" @context
+    name: (identifier) @name) @item
+
+(enum_constant
+    name: (identifier) @name) @item
+
+(constructor_declaration
+    name: (identifier) @name
+    parameters: (formal_parameters) @context) @item
+
+(method_declaration
+    name: (identifier) @name
+    parameters: (formal_parameters) @context) @item
+
+(field_declaration
+    declarator: (variable_declarator
+        name: (identifier) @name)) @item
@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::HashMap;
 use feature_flags::FeatureFlagAppExt;
@@ -7,6 +7,8 @@ use gpui::AppContext;
 use language::{LanguageRegistry, LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
 use node_runtime::NodeRuntime;
+use parking_lot::Mutex;
+use serde_derive::Deserialize;
 use serde_json::{json, Value};
 use settings::{KeymapFile, SettingsJsonSchemaParams, SettingsStore};
 use smol::fs;
@@ -15,11 +17,16 @@ use std::{
     ffi::OsString,
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
+    time::{Duration, SystemTime},
 };
-use util::{async_maybe, paths, ResultExt};
+use util::{archive, async_maybe, paths, ResultExt};
 
 const SERVER_PATH: &'static str =
     "node_modules/vscode-json-languageserver/bin/vscode-json-languageserver";
+const SCHEMA_CATALOG_URL: &str = "https://www.schemastore.org/api/json/catalog.json";
+const SCHEMA_CATALOG_PATH: &str = "schema-catalog.json";
+/// How long a downloaded schema catalog is used before it's downloaded again.
+const SCHEMA_CATALOG_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 
 fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
     vec![server_path.into(), "--stdio".into()]
@@ -29,6 +36,21 @@ pub struct JsonLspAdapter {
     node: Arc<dyn NodeRuntime>,
     languages: Arc<LanguageRegistry>,
     workspace_config: OnceLock<Value>,
+    schema_catalog: Mutex<Vec<CatalogSchema>>,
+}
+
+/// The schemastore.org catalog, which lists the schemas of well-known JSON files, such
+/// as `package.json` and `tsconfig.json`, along with the globs of the files they apply to.
+#[derive(Deserialize)]
+struct SchemaCatalog {
+    schemas: Vec<CatalogSchema>,
+}
+
+#[derive(Clone, Deserialize)]
+struct CatalogSchema {
+    url: String,
+    #[serde(rename = "fileMatch", default)]
+    file_match: Vec<String>,
 }
 
 impl JsonLspAdapter {
@@ -37,6 +59,37 @@ impl JsonLspAdapter {
             node,
             languages,
             workspace_config: Default::default(),
+            schema_catalog: Default::default(),
+        }
+    }
+
+    /// Downloads the schema catalog when the server starts, unless the copy downloaded
+    /// previously is younger than a day. An older copy is still used when offline.
+    async fn load_schema_catalog(&self, container_dir: &Path, delegate: &dyn LspAdapterDelegate) {
+        let catalog_path = container_dir.join(SCHEMA_CATALOG_PATH);
+        let catalog = match fresh_schema_catalog(&catalog_path, SystemTime::now()).await {
+            Some(catalog) => Some(catalog),
+            None => match download_schema_catalog(delegate).await {
+                Ok(catalog) => {
+                    fs::write(&catalog_path, &catalog).await.log_err();
+                    Some(catalog)
+                }
+                Err(error) => {
+                    log::warn!("failed to download JSON schema catalog: {error:?}");
+                    fs::read(&catalog_path).await.ok()
+                }
+            },
+        };
+        if let Some(catalog) = catalog.and_then(|catalog| {
+            serde_json::from_slice::<SchemaCatalog>(&catalog)
+                .context("invalid JSON schema catalog")
+                .log_err()
+        }) {
+            *self.schema_catalog.lock() = catalog
+                .schemas
+                .into_iter()
+                .filter(|schema| !schema.file_match.is_empty())
+                .collect();
         }
     }
 
@@ -59,6 +112,9 @@ impl JsonLspAdapter {
                 "format": {
                     "enable": true,
                 },
+                "schemaDownload": {
+                    "enable": true,
+                },
                 "schemas": [
                     {
                         "fileMatch": [
@@ -102,7 +158,7 @@ impl LspAdapter for JsonLspAdapter {
         &self,
         version: Box<dyn 'static + Send + Any>,
         container_dir: PathBuf,
-        _: &dyn LspAdapterDelegate,
+        delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<String>().unwrap();
         let server_path = container_dir.join(SERVER_PATH);
@@ -115,6 +171,7 @@ impl LspAdapter for JsonLspAdapter {
                 )
                 .await?;
         }
+        self.load_schema_catalog(&container_dir, delegate).await;
 
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
@@ -125,8 +182,9 @@ impl LspAdapter for JsonLspAdapter {
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
-        _: &dyn LspAdapterDelegate,
+        delegate: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
+        self.load_schema_catalog(&container_dir, delegate).await;
         get_cached_server_binary(container_dir, &*self.node).await
     }
 
@@ -139,14 +197,27 @@ impl LspAdapter for JsonLspAdapter {
 
     fn initialization_options(&self) -> Option<serde_json::Value> {
         Some(json!({
-            "provideFormatter": true
+            "provideFormatter": true,
+            "handledSchemaProtocols": ["file", "http", "https"]
         }))
     }
 
     fn workspace_configuration(&self, _workspace_root: &Path, cx: &mut AppContext) -> Value {
-        self.workspace_config
+        let mut config = self
+            .workspace_config
             .get_or_init(|| Self::get_workspace_config(self.languages.language_names(), cx))
-            .clone()
+            .clone();
+        // The catalog is reloaded whenever the server starts, so it isn't part of the
+        // cached configuration.
+        if let Some(schemas) = config["json"]["schemas"].as_array_mut() {
+            schemas.extend(self.schema_catalog.lock().iter().map(|schema| {
+                json!({
+                    "fileMatch": schema.file_match,
+                    "url": schema.url,
+                })
+            }));
+        }
+        config
     }
 
     fn language_ids(&self) -> HashMap<String, String> {
@@ -186,7 +257,52 @@ async fn get_cached_server_binary(
     .log_err()
 }
 
+/// Downloads the catalog like a release, so that it's refused when `require_checksums`
+/// is set and its checksum isn't pinned.
+async fn download_schema_catalog(delegate: &dyn LspAdapterDelegate) -> Result<Vec<u8>> {
+    archive::download_verified(&*delegate.http_client(), SCHEMA_CATALOG_URL, None).await
+}
+
+/// Reads the previously downloaded catalog, unless it's older than `SCHEMA_CATALOG_MAX_AGE`.
+async fn fresh_schema_catalog(catalog_path: &Path, now: SystemTime) -> Option<Vec<u8>> {
+    let modified = fs::metadata(catalog_path).await.ok()?.modified().ok()?;
+    let age = now.duration_since(modified).unwrap_or_default();
+    if age > SCHEMA_CATALOG_MAX_AGE {
+        return None;
+    }
+    fs::read(catalog_path).await.ok()
+}
+
 fn schema_file_match(path: &Path) -> &Path {
     path.strip_prefix(path.parent().unwrap().parent().unwrap())
         .unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_schema_catalog() {
+        let container_dir = tempfile::tempdir().unwrap();
+        let catalog_path = container_dir.path().join(SCHEMA_CATALOG_PATH);
+        smol::block_on(async {
+            assert_eq!(
+                fresh_schema_catalog(&catalog_path, SystemTime::now()).await,
+                None
+            );
+
+            std::fs::write(&catalog_path, r#"{"schemas": []}"#).unwrap();
+            assert_eq!(
+                fresh_schema_catalog(&catalog_path, SystemTime::now()).await,
+                Some(br#"{"schemas": []}"#.to_vec())
+            );
+
+            let after_max_age = SystemTime::now() + SCHEMA_CATALOG_MAX_AGE * 2;
+            assert_eq!(
+                fresh_schema_catalog(&catalog_path, after_max_age).await,
+                None
+            );
+        });
+    }
+}
@@ -0,0 +1 @@
+(string) @string
\ No newline at end of file
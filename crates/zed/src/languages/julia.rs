@@ -0,0 +1,226 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use gpui::AppContext;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::Settings;
+use smol::{fs, process};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    archive::check_unverified_install,
+    async_maybe,
+    audit_log::{self, AuditEvent, AuditEventKind},
+    fs::matching_by_recency,
+    github::latest_github_release,
+    ResultExt,
+};
+
+/// Runs LanguageServer.jl for the Julia project containing the working directory,
+/// falling back to the default environment, while LanguageServer.jl itself is
+/// loaded from the environment that Zed installed it into.
+const RUN_SERVER_SCRIPT: &str = r#"
+using LanguageServer
+project_path = dirname(something(Base.current_project(pwd()), Base.load_path_expand("@v#.#")))
+depot_path = get(ENV, "JULIA_DEPOT_PATH", "")
+server = LanguageServer.LanguageServerInstance(stdin, stdout, project_path, depot_path)
+server.runlinter = true
+run(server)
+"#;
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JuliaSettings {
+    pub executable: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct JuliaSettingsContent {
+    /// The path to the Julia executable that installs and runs LanguageServer.jl.
+    /// When null, `julia` on the `PATH` is used.
+    ///
+    /// Default: null
+    executable: Option<PathBuf>,
+}
+
+impl Settings for JuliaSettings {
+    const KEY: Option<&'static str> = Some("julia");
+
+    type FileContent = JuliaSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// LanguageServer.jl is a Julia package, so each version is installed with Julia's
+/// package manager into its own environment in the container directory, rather than
+/// into the user's default environment.
+pub struct JuliaLspAdapter {
+    executable: Option<PathBuf>,
+}
+
+impl JuliaLspAdapter {
+    pub fn new(executable: Option<PathBuf>) -> Self {
+        JuliaLspAdapter { executable }
+    }
+
+    fn julia_path(&self) -> PathBuf {
+        self.executable
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("julia"))
+    }
+
+    /// Returns a binary that runs the given Julia code in the environment that
+    /// LanguageServer.jl is installed in.
+    fn julia_binary(&self, environment_dir: &Path, code: &str) -> LanguageServerBinary {
+        LanguageServerBinary {
+            path: self.julia_path(),
+            arguments: vec![
+                "--startup-file=no".into(),
+                "--history-file=no".into(),
+                "--quiet".into(),
+                format!("--project={}", environment_dir.display()).into(),
+                "-e".into(),
+                code.into(),
+            ],
+        }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for JuliaLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("julia-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "julia"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = latest_github_release(
+            "julia-vscode/LanguageServer.jl",
+            false,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        Ok(Box::new(release.tag_name.trim_start_matches('v').to_string()) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let environment_dir = container_dir.join(format!("LanguageServer-{version}"));
+
+        if fs::metadata(environment_dir.join("Manifest.toml"))
+            .await
+            .is_err()
+        {
+            check_unverified_install(&format!("LanguageServer.jl {version} with Pkg")).await?;
+            fs::create_dir_all(&environment_dir).await?;
+            let install = self.julia_binary(
+                &environment_dir,
+                &format!(
+                    r#"using Pkg; Pkg.add(name="LanguageServer", version="{version}"); Pkg.precompile()"#
+                ),
+            );
+            audit_log::record(
+                AuditEvent::new(
+                    AuditEventKind::Command,
+                    "julia",
+                    format!("Pkg.add LanguageServer {version}"),
+                )
+                .details(format!(
+                    "{} {}",
+                    install.path.display(),
+                    install
+                        .arguments
+                        .iter()
+                        .map(|argument| argument.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )),
+            );
+            let output = process::Command::new(&install.path)
+                .args(&install.arguments)
+                .output()
+                .await
+                .context("failed to run julia")?;
+            if !output.status.success() {
+                fs::remove_dir_all(&environment_dir).await.log_err();
+                bail!(
+                    "failed to install LanguageServer.jl: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+        }
+
+        Ok(self.julia_binary(&environment_dir, RUN_SERVER_SCRIPT))
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        let environment_dir = get_cached_environment_dir(container_dir).await?;
+        Some(self.julia_binary(&environment_dir, RUN_SERVER_SCRIPT))
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        let environment_dir = get_cached_environment_dir(container_dir).await?;
+        Some(self.julia_binary(&environment_dir, "using LanguageServer"))
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_language_server_version(entry)
+    }
+}
+
+fn is_language_server_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("LanguageServer-")
+    })
+}
+
+async fn get_cached_environment_dir(container_dir: PathBuf) -> Option<PathBuf> {
+    async_maybe!({
+        let environment_dir = matching_by_recency(&container_dir, is_language_server_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached LanguageServer.jl"))?;
+        if environment_dir.join("Manifest.toml").exists() {
+            Ok(environment_dir)
+        } else {
+            Err(anyhow!(
+                "LanguageServer.jl isn't installed in {:?}",
+                environment_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,107 @@
+(identifier) @variable
+
+(call_expression
+    .
+    (identifier) @function)
+
+(call_expression
+    .
+    (field_expression
+        (identifier) @function .))
+
+(signature
+    (call_expression
+        .
+        (identifier) @function.definition))
+
+(macro_identifier) @attribute
+
+(field_expression
+    (identifier) @property .)
+
+(type_head
+    (identifier) @type)
+
+(parametrized_type_expression
+    .
+    (identifier) @type)
+
+(typed_expression
+    (identifier) @type .)
+
+((identifier) @type
+ (#match? @type "^[A-Z][A-Za-z0-9]*$"))
+
+((identifier) @constant.builtin
+ (#match? @constant.builtin "^(nothing|missing|undef|Inf|NaN|pi|π|ℯ)$"))
+
+[
+  (line_comment)
+  (block_comment)
+] @comment
+
+[
+  (string_literal)
+  (command_literal)
+  (prefixed_string_literal)
+  (character_literal)
+] @string
+
+(escape_sequence) @string.escape
+
+[
+  (integer_literal)
+  (float_literal)
+] @number
+
+(boolean_literal) @boolean
+
+(operator) @operator
+
+[
+  "function"
+  "macro"
+  "module"
+  "baremodule"
+  "struct"
+  "mutable"
+  "abstract"
+  "primitive"
+  "type"
+  "end"
+  "if"
+  "elseif"
+  "else"
+  "for"
+  "while"
+  "begin"
+  "let"
+  "try"
+  "catch"
+  "finally"
+  "do"
+  "quote"
+  "return"
+  "import"
+  "using"
+  "export"
+  "const"
+  "global"
+  "local"
+  (break_statement)
+  (continue_statement)
+] @keyword
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ","
+  ";"
+] @punctuation.delimiter
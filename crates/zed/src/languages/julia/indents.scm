@@ -0,0 +1,20 @@
+[
+    (function_definition)
+    (macro_definition)
+    (module_definition)
+    (struct_definition)
+    (abstract_definition)
+    (if_statement)
+    (for_statement)
+    (while_statement)
+    (let_statement)
+    (try_statement)
+    (do_clause)
+    (compound_statement)
+    (quote_statement)
+] @indent
+
+(_ "end" @end)
+(_ "[" "]" @end) @indent
+(_ "(" ")" @end) @indent
+(_ "{" "}" @end) @indent
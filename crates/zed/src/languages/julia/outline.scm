@@ -0,0 +1,32 @@
+(module_definition
+    ["module" "baremodule"] @context
+    name: (identifier) @name) @item
+
+(function_definition
+    "function" @context
+    (signature
+        (call_expression
+            .
+            [(identifier) (field_expression)] @name))) @item
+
+(macro_definition
+    "macro" @context
+    (signature
+        (call_expression
+            .
+            (identifier) @name))) @item
+
+(struct_definition
+    "struct" @context
+    (type_head
+        [(identifier) @name
+         (parametrized_type_expression . (identifier) @name)
+         (binary_expression . (identifier) @name)])) @item
+
+(abstract_definition
+    "abstract" @context
+    "type" @context
+    (type_head
+        [(identifier) @name
+         (parametrized_type_expression . (identifier) @name)
+         (binary_expression . (identifier) @name)])) @item
@@ -0,0 +1,134 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    archive::{download_archive_atomically, ArchiveType},
+    async_maybe,
+    fs::matching_by_recency,
+    github::{latest_github_release, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+const ASSET_NAME: &str = "server.zip";
+
+pub struct KotlinLspAdapter;
+
+#[async_trait]
+impl LspAdapter for KotlinLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("kotlin-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "kotlin"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = latest_github_release(
+            "fwcd/kotlin-language-server",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == ASSET_NAME)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", ASSET_NAME))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("kotlin-language-server_{}", version.name));
+        let binary_path = version_dir.join("server/bin/kotlin-language-server");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_archive_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: vec![],
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--help".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_kotlin_language_server_version(entry)
+    }
+}
+
+fn is_kotlin_language_server_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy()
+            .starts_with("kotlin-language-server_")
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_kotlin_language_server_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("server/bin/kotlin-language-server");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                path: binary_path,
+                arguments: vec![],
+            })
+        } else {
+            Err(anyhow!(
+                "missing kotlin-language-server binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
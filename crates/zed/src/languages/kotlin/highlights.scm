@@ -0,0 +1,147 @@
+(simple_identifier) @variable
+
+(type_identifier) @type
+
+(class_parameter
+  (simple_identifier) @property)
+
+(parameter
+  (simple_identifier) @variable)
+
+(function_declaration
+  (simple_identifier) @function)
+
+(call_expression
+  (simple_identifier) @function)
+
+(call_expression
+  (navigation_expression
+    (navigation_suffix
+      (simple_identifier) @function)))
+
+(navigation_suffix
+  (simple_identifier) @property)
+
+(enum_entry
+  (simple_identifier) @constant)
+
+((simple_identifier) @constant
+  (#match? @constant "^[A-Z][A-Z0-9_]+$"))
+
+(annotation) @attribute
+
+[
+  (line_comment)
+  (multiline_comment)
+] @comment
+
+(string_literal) @string
+(character_literal) @string
+
+[
+  (integer_literal)
+  (long_literal)
+  (hex_literal)
+  (bin_literal)
+  (unsigned_literal)
+  (real_literal)
+] @number
+
+(boolean_literal) @boolean
+"null" @constant.builtin
+
+(this_expression) @variable.special
+(super_expression) @variable.special
+
+[
+  (class_modifier)
+  (member_modifier)
+  (function_modifier)
+  (property_modifier)
+  (platform_modifier)
+  (variance_modifier)
+  (parameter_modifier)
+  (visibility_modifier)
+  (reification_modifier)
+  (inheritance_modifier)
+] @keyword
+
+[
+  "as"
+  "break"
+  "by"
+  "catch"
+  "class"
+  "companion"
+  "constructor"
+  "continue"
+  "do"
+  "else"
+  "finally"
+  "for"
+  "fun"
+  "if"
+  "import"
+  "in"
+  "init"
+  "interface"
+  "is"
+  "object"
+  "package"
+  "return"
+  "throw"
+  "try"
+  "typealias"
+  "val"
+  "var"
+  "when"
+  "where"
+  "while"
+] @keyword
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ","
+  ";"
+  ":"
+  "::"
+] @punctuation.delimiter
+
+[
+  "="
+  "+="
+  "-="
+  "*="
+  "/="
+  "%="
+  "=="
+  "!="
+  "==="
+  "!=="
+  "<"
+  ">"
+  "<="
+  ">="
+  "&&"
+  "||"
+  "!"
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "++"
+  "--"
+  "->"
+  "?:"
+  ".."
+] @operator
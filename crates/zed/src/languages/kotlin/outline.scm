@@ -0,0 +1,16 @@
+(class_declaration
+  ["class" "interface"] @context
+  (type_identifier) @name) @item
+
+(object_declaration
+  "object" @context
+  (type_identifier) @name) @item
+
+(function_declaration
+  "fun" @context
+  (simple_identifier) @name) @item
+
+(property_declaration
+  (binding_pattern_kind) @context
+  (variable_declaration
+    (simple_identifier) @name)) @item
@@ -0,0 +1,422 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
+use async_trait::async_trait;
+use editor::Editor;
+use futures::io::BufReader;
+use gpui::{actions, AppContext, ViewContext};
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use settings::Settings;
+use smol::fs;
+use std::env::consts::{ARCH, OS};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::async_maybe;
+use util::fs::matching_by_recency;
+use util::github::{download_release, latest_github_release};
+use util::{github::GitHubLspBinaryVersion, ResultExt};
+use workspace::{notifications::NotifyTaskExt, Workspace};
+
+const SERVER_NAME: &str = "texlab";
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TexlabSettings {
+    pub build: TexlabBuildSettings,
+    pub forward_search: TexlabForwardSearchSettings,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TexlabBuildSettings {
+    pub executable: String,
+    pub args: Vec<String>,
+    pub on_save: bool,
+    pub forward_search_after: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TexlabForwardSearchSettings {
+    pub executable: Option<String>,
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct TexlabSettingsContent {
+    /// How documents are compiled.
+    build: Option<TexlabBuildSettingsContent>,
+    /// The PDF viewer that shows the position of the cursor in the compiled document.
+    forward_search: Option<TexlabForwardSearchSettingsContent>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct TexlabBuildSettingsContent {
+    /// The program that compiles documents.
+    ///
+    /// Default: "latexmk"
+    executable: Option<String>,
+    /// The arguments of the build program, where `%f` is replaced with the path of
+    /// the document.
+    ///
+    /// Default: ["-pdf", "-interaction=nonstopmode", "-synctex=1", "%f"]
+    args: Option<Vec<String>>,
+    /// Whether to compile documents when they're saved.
+    ///
+    /// Default: false
+    on_save: Option<bool>,
+    /// Whether to run the forward search after each build.
+    ///
+    /// Default: false
+    forward_search_after: Option<bool>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct TexlabForwardSearchSettingsContent {
+    /// The PDF viewer. Forward search is disabled when it isn't set.
+    ///
+    /// Default: null
+    executable: Option<String>,
+    /// The arguments of the PDF viewer, where `%f` is replaced with the path of the
+    /// document, `%p` with the path of the PDF and `%l` with the line of the cursor.
+    ///
+    /// Default: []
+    args: Option<Vec<String>>,
+}
+
+impl Settings for TexlabSettings {
+    const KEY: Option<&'static str> = Some("texlab");
+
+    type FileContent = TexlabSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+pub struct TexlabAdapter;
+
+#[async_trait]
+impl LspAdapter for TexlabAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName(SERVER_NAME.into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "texlab"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            latest_github_release("latex-lsp/texlab", true, false, delegate.http_client()).await?;
+        let asset_name = asset_name()?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("texlab_{}", version.name));
+        let binary_path = binary_path(&version_dir);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let mut response = download_release(&*delegate.http_client(), &version)
+                .await
+                .context("error downloading release")?;
+            let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+            let archive = Archive::new(decompressed_bytes);
+            archive.unpack(&version_dir).await?;
+
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: vec![],
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_texlab_version(entry)
+    }
+
+    fn workspace_configuration(&self, _workspace_root: &Path, cx: &mut AppContext) -> Value {
+        let settings = TexlabSettings::get_global(cx);
+        json!({
+            "texlab": {
+                "build": {
+                    "executable": settings.build.executable,
+                    "args": settings.build.args,
+                    "onSave": settings.build.on_save,
+                    "forwardSearchAfter": settings.build.forward_search_after,
+                },
+                "forwardSearch": {
+                    "executable": settings.forward_search.executable,
+                    "args": settings.forward_search.args,
+                },
+            }
+        })
+    }
+}
+
+actions!(latex, [Build, ForwardSearch]);
+
+/// texlab's request that compiles the document with the configured build program.
+enum BuildRequest {}
+
+impl lsp::request::Request for BuildRequest {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = TexlabStatus;
+    const METHOD: &'static str = "textDocument/build";
+}
+
+/// texlab's request that shows the position of the cursor in the configured PDF viewer.
+enum ForwardSearchRequest {}
+
+impl lsp::request::Request for ForwardSearchRequest {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = TexlabStatus;
+    const METHOD: &'static str = "textDocument/forwardSearch";
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TexlabStatus {
+    status: u8,
+}
+
+/// Returns the error that texlab reports with the status of a build, if any. A build
+/// that was cancelled by a newer one isn't an error.
+fn build_error(status: u8) -> Option<&'static str> {
+    match status {
+        0 | 3 => None,
+        1 => Some("The document has errors, see the build log"),
+        _ => Some("The build program could not be run, check `texlab.build.executable`"),
+    }
+}
+
+/// Returns the error that texlab reports with the status of a forward search, if any.
+fn forward_search_error(status: u8) -> Option<&'static str> {
+    match status {
+        0 => None,
+        1 => Some("The PDF viewer exited with an error"),
+        2 => Some("The PDF viewer could not be run, check `texlab.forward_search.executable`"),
+        _ => Some("Forward search needs `texlab.forward_search.executable` to be set"),
+    }
+}
+
+/// Registers the commands that build the active LaTeX document and show the position
+/// of its cursor in the PDF viewer.
+pub(super) fn init_texlab_commands(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &Build, cx| {
+                send_texlab_request::<BuildRequest>(workspace, build_error, cx);
+            })
+            .register_action(|workspace, _: &ForwardSearch, cx| {
+                send_texlab_request::<ForwardSearchRequest>(workspace, forward_search_error, cx);
+            });
+    })
+    .detach();
+}
+
+fn send_texlab_request<R>(
+    workspace: &mut Workspace,
+    error: fn(u8) -> Option<&'static str>,
+    cx: &mut ViewContext<Workspace>,
+) where
+    R: lsp::request::Request<Params = lsp::TextDocumentPositionParams, Result = TexlabStatus>,
+{
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let editor = editor.read(cx);
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let position = editor.selections.newest::<text::PointUtf16>(cx).head();
+    let buffer = buffer.read(cx);
+    let Some(path) = buffer
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx))
+    else {
+        return;
+    };
+    let Some(server) = workspace
+        .project()
+        .read(cx)
+        .language_servers_for_buffer(buffer, cx)
+        .find(|(adapter, _)| adapter.name.0.as_ref() == SERVER_NAME)
+        .map(|(_, server)| server.clone())
+    else {
+        return;
+    };
+    let Ok(uri) = lsp::Url::from_file_path(&path) else {
+        return;
+    };
+
+    let request = server.request::<R>(lsp::TextDocumentPositionParams {
+        text_document: lsp::TextDocumentIdentifier::new(uri),
+        position: lsp::Position::new(position.row, position.column),
+    });
+    cx.background_executor()
+        .spawn(async move {
+            let TexlabStatus { status } = request.await?;
+            match error(status) {
+                Some(error) => Err(anyhow!(error)),
+                None => Ok(()),
+            }
+        })
+        .detach_and_notify_err(cx);
+}
+
+fn asset_name() -> Result<String> {
+    let os = match OS {
+        "macos" => "macos",
+        "linux" => "linux",
+        other => bail!("Running on unsupported os: {other}"),
+    };
+    Ok(format!("texlab-{ARCH}-{os}.tar.gz"))
+}
+
+fn binary_path(version_dir: &Path) -> PathBuf {
+    version_dir.join("texlab")
+}
+
+fn is_texlab_version(entry: &Path) -> bool {
+    entry
+        .file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with("texlab_"))
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_texlab_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let path = binary_path(&version_dir);
+        if path.exists() {
+            Ok(LanguageServerBinary {
+                path,
+                arguments: Vec::new(),
+            })
+        } else {
+            Err(anyhow!(
+                "missing texlab binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context, TestAppContext};
+    use language::{Buffer, BufferId, LanguageRegistry};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_texlab_statuses() {
+        assert_eq!(build_error(0), None);
+        assert_eq!(build_error(3), None);
+        assert!(build_error(1).is_some());
+        assert!(build_error(2).is_some());
+
+        assert_eq!(forward_search_error(0), None);
+        assert!(forward_search_error(1).is_some());
+        assert!(forward_search_error(2).is_some());
+        assert!(forward_search_error(3)
+            .unwrap()
+            .contains("texlab.forward_search.executable"));
+    }
+
+    #[test]
+    fn test_is_texlab_version() {
+        assert!(is_texlab_version(Path::new(
+            "/servers/texlab/texlab_v5.12.3"
+        )));
+        assert!(!is_texlab_version(Path::new(
+            "/servers/texlab/.texlab_v5.12.3.partial"
+        )));
+    }
+
+    #[gpui::test]
+    async fn test_math_injection(cx: &mut TestAppContext) {
+        let languages = Arc::new(LanguageRegistry::test());
+        languages
+            .add(crate::languages::language("latex", tree_sitter_latex::language(), None).await);
+        let markdown =
+            crate::languages::language("markdown", tree_sitter_markdown::language(), None).await;
+        languages.add(markdown.clone());
+
+        let text = "Euler:\n\n```math\ne^{i\\pi} + 1 = 0\n```\n";
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text);
+            buffer.set_language_registry(languages);
+            buffer.set_language(Some(markdown), cx);
+            buffer
+        });
+        buffer.update(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            let math_offset = text.find("e^").unwrap();
+            assert_eq!(
+                snapshot.language_at(math_offset).unwrap().name().as_ref(),
+                "LaTeX"
+            );
+            assert_eq!(snapshot.language_at(0).unwrap().name().as_ref(), "Markdown");
+        });
+    }
+}
@@ -0,0 +1,71 @@
+[
+    (line_comment)
+    (block_comment)
+    (comment_environment)
+] @comment
+
+; Math is highlighted as a whole, with the commands inside it highlighted on top.
+[
+    (inline_formula)
+    (displayed_equation)
+    (math_environment)
+] @text.literal
+
+(begin
+    command: _ @keyword
+    name: (curly_group_text (text) @tag))
+
+(end
+    command: _ @keyword
+    name: (curly_group_text (text) @tag))
+
+(part command: _ @keyword text: (curly_group (_) @title))
+(chapter command: _ @keyword text: (curly_group (_) @title))
+(section command: _ @keyword text: (curly_group (_) @title))
+(subsection command: _ @keyword text: (curly_group (_) @title))
+(subsubsection command: _ @keyword text: (curly_group (_) @title))
+(paragraph command: _ @keyword text: (curly_group (_) @title))
+(subparagraph command: _ @keyword text: (curly_group (_) @title))
+
+(package_include
+    command: _ @keyword
+    paths: (curly_group_path_list) @string)
+
+(class_include
+    command: _ @keyword
+    path: (curly_group_path) @string)
+
+(label_definition
+    command: _ @function
+    name: (curly_group_text (_) @label))
+
+(label_reference
+    command: _ @function
+    names: (curly_group_text_list (_) @label))
+
+(citation
+    command: _ @function
+    keys: (curly_group_text_list) @label)
+
+(new_command_definition
+    command: _ @keyword
+    declaration: (curly_group_command_name (_) @function))
+
+(key_value_pair
+    key: (_) @property)
+
+(command_name) @function
+
+"\\item" @punctuation.special
+
+[
+    (brack_group)
+    (brack_group_argc)
+] @variable.special
+
+[
+    "["
+    "]"
+    "{"
+    "}"
+] @punctuation.bracket
@@ -0,0 +1,4 @@
+(_ (begin) @start (end) @end) @indent
+
+(_ "{" "}" @end) @indent
+(_ "[" "]" @end) @indent
@@ -0,0 +1,27 @@
+(part
+    command: _ @context
+    text: (curly_group (_) @name)) @item
+
+(chapter
+    command: _ @context
+    text: (curly_group (_) @name)) @item
+
+(section
+    command: _ @context
+    text: (curly_group (_) @name)) @item
+
+(subsection
+    command: _ @context
+    text: (curly_group (_) @name)) @item
+
+(subsubsection
+    command: _ @context
+    text: (curly_group (_) @name)) @item
+
+(paragraph
+    command: _ @context
+    text: (curly_group (_) @name)) @item
+
+(subparagraph
+    command: _ @context
+    text: (curly_group (_) @name)) @item
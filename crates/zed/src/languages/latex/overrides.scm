@@ -0,0 +1,5 @@
+[
+    (line_comment)
+    (block_comment)
+    (comment_environment)
+] @comment
@@ -0,0 +1,3 @@
+(comment) @comment
+(string_value) @string
+(single_line_comment) @comment
@@ -2,17 +2,85 @@ use anyhow::{anyhow, bail, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
+use futures::io::BufReader;
+use gpui::AppContext;
 use language::{LanguageServerName, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use settings::Settings;
 use smol::fs;
-use std::{any::Any, env::consts, path::PathBuf};
+use std::{
+    any::Any,
+    env::consts,
+    path::{Path, PathBuf},
+};
 use util::{
     async_maybe,
-    github::{latest_github_release, GitHubLspBinaryVersion},
-    ResultExt,
+    github::{download_release, latest_github_release, GitHubLspBinaryVersion},
+    paths, ResultExt,
 };
 
+const BINARY_PATH: &'static str = "bin/lua-language-server";
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LuaSettings {
+    pub runtime_version: Option<String>,
+    pub library: Vec<String>,
+    pub globals: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct LuaSettingsContent {
+    /// The Lua version that code is checked against, such as "Lua 5.4" or "LuaJIT".
+    /// Defaults to lua-language-server's own default.
+    ///
+    /// Default: null
+    runtime_version: Option<String>,
+    /// Directories of Lua code whose definitions are made available to every file,
+    /// such as the runtime of Neovim or of a game engine. Relative paths are resolved
+    /// against the project's root, and `~` against the home directory.
+    ///
+    /// Default: []
+    library: Option<Vec<String>>,
+    /// Global variables that are defined by the host application, such as `vim` or
+    /// `love`, and shouldn't be reported as undefined.
+    ///
+    /// Default: []
+    globals: Option<Vec<String>>,
+}
+
+impl Settings for LuaSettings {
+    const KEY: Option<&'static str> = Some("lua");
+
+    type FileContent = LuaSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+fn library_paths(library: &[String], workspace_root: &Path) -> Vec<String> {
+    library
+        .iter()
+        .map(|path| {
+            let path = match path.strip_prefix("~/") {
+                Some(path) => paths::HOME.join(path),
+                None => workspace_root.join(path),
+            };
+            path.to_string_lossy().into_owned()
+        })
+        .collect()
+}
+
 #[derive(Copy, Clone)]
 pub struct LuaLspAdapter;
 
@@ -30,6 +98,11 @@ impl super::LspAdapter for LuaLspAdapter {
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
+        let os = match consts::OS {
+            "macos" => "darwin",
+            "linux" => "linux",
+            other => bail!("Running on unsupported os: {other}"),
+        };
         let platform = match consts::ARCH {
             "x86_64" => "x64",
             "aarch64" => "arm64",
@@ -43,16 +116,13 @@ impl super::LspAdapter for LuaLspAdapter {
         )
         .await?;
         let version = &release.tag_name;
-        let asset_name = format!("lua-language-server-{version}-darwin-{platform}.tar.gz");
+        let asset_name = format!("lua-language-server-{version}-{os}-{platform}.tar.gz");
         let asset = release
             .assets
             .iter()
             .find(|asset| asset.name == asset_name)
             .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url.clone(),
-        };
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -64,12 +134,10 @@ impl super::LspAdapter for LuaLspAdapter {
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
 
-        let binary_path = container_dir.join("bin/lua-language-server");
+        let binary_path = container_dir.join(BINARY_PATH);
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
+            let mut response = download_release(&*delegate.http_client(), &version)
                 .await
                 .map_err(|err| anyhow!("error downloading release: {}", err))?;
             let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
@@ -107,27 +175,34 @@ impl super::LspAdapter for LuaLspAdapter {
                 binary
             })
     }
+
+    fn workspace_configuration(&self, workspace_root: &Path, cx: &mut AppContext) -> Value {
+        let settings = LuaSettings::get_global(cx);
+        json!({
+            "Lua": {
+                "runtime": {
+                    "version": settings.runtime_version,
+                },
+                "workspace": {
+                    "library": library_paths(&settings.library, workspace_root),
+                    // Don't prompt to configure the workspace for the libraries that are
+                    // detected in it.
+                    "checkThirdParty": false,
+                },
+                "diagnostics": {
+                    "globals": settings.globals,
+                },
+            }
+        })
+    }
 }
 
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last_binary_path = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            let entry = entry?;
-            if entry.file_type().await?.is_file()
-                && entry
-                    .file_name()
-                    .to_str()
-                    .map_or(false, |name| name == "lua-language-server")
-            {
-                last_binary_path = Some(entry.path());
-            }
-        }
-
-        if let Some(path) = last_binary_path {
+        let binary_path = container_dir.join(BINARY_PATH);
+        if binary_path.exists() {
             Ok(LanguageServerBinary {
-                path,
+                path: binary_path,
                 arguments: Vec::new(),
             })
         } else {
@@ -137,3 +212,26 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
     .await
     .log_err()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_library_paths() {
+        let library = [
+            "types".to_string(),
+            "~/.local/share/nvim/runtime/lua".into(),
+        ];
+        assert_eq!(
+            library_paths(&library, Path::new("/code/plugin")),
+            [
+                "/code/plugin/types".to_string(),
+                paths::HOME
+                    .join(".local/share/nvim/runtime/lua")
+                    .to_string_lossy()
+                    .into_owned(),
+            ]
+        );
+    }
+}
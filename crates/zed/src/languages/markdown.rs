@@ -0,0 +1,135 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use smol::fs::{self, File};
+use std::env::consts::{ARCH, OS};
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::async_maybe;
+use util::fs::matching_by_recency;
+use util::github::{download_release, latest_github_release};
+use util::{github::GitHubLspBinaryVersion, ResultExt};
+
+pub struct MarksmanAdapter;
+
+#[async_trait]
+impl LspAdapter for MarksmanAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("marksman".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "marksman"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            latest_github_release("artempyanykh/marksman", true, false, delegate.http_client())
+                .await?;
+        let asset_name = asset_name()?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let binary_path = container_dir.join(format!("marksman-{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let mut response = download_release(&*delegate.http_client(), &version)
+                .await
+                .context("error downloading release")?;
+            // The release assets are executables, rather than archives.
+            let mut file = File::create(&binary_path).await?;
+            futures::io::copy(response.body_mut(), &mut file).await?;
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_marksman_version(entry)
+    }
+}
+
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["server".into()]
+}
+
+fn asset_name() -> Result<&'static str> {
+    Ok(match (OS, ARCH) {
+        // The macOS release is a universal binary.
+        ("macos", _) => "marksman-macos",
+        ("linux", "x86_64") => "marksman-linux-x64",
+        ("linux", "aarch64") => "marksman-linux-arm64",
+        (os, arch) => bail!("Running on unsupported platform: {os} {arch}"),
+    })
+}
+
+fn is_marksman_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("marksman-")
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let latest = matching_by_recency(&container_dir, is_marksman_version)
+            .await
+            .into_iter()
+            .next();
+
+        anyhow::Ok(LanguageServerBinary {
+            path: latest.ok_or_else(|| anyhow!("no cached binary"))?,
+            arguments: server_binary_arguments(),
+        })
+    })
+    .await
+    .log_err()
+}
@@ -1,4 +1,13 @@
 (fenced_code_block
   (info_string
     (language) @language)
-  (code_fence_content) @content)
+  (code_fence_content) @content
+  (#not-eq? @language "math"))
+
+; Math blocks, such as GitHub's ```math fences, are highlighted as LaTeX.
+(fenced_code_block
+  (info_string
+    (language) @_language)
+  (code_fence_content) @content
+  (#eq? @_language "math")
+  (#set! "language" "latex"))
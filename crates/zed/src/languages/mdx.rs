@@ -0,0 +1,168 @@
+use anyhow::{anyhow, ensure, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use node_runtime::NodeRuntime;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{async_maybe, ResultExt};
+
+const SERVER_PATH: &'static str = "node_modules/@mdx-js/language-server/index.js";
+const TYPESCRIPT_PATH: &'static str = "node_modules/typescript/lib";
+
+fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
+    vec![server_path.into(), "--stdio".into()]
+}
+
+pub struct MdxLspVersion {
+    mdx_version: String,
+    ts_version: String,
+}
+
+pub struct MdxLspAdapter {
+    node: Arc<dyn NodeRuntime>,
+    /// The TypeScript installation that the server checks the JSX and ESM with,
+    /// which is only known once the server has been installed.
+    typescript_install_path: Mutex<Option<PathBuf>>,
+}
+
+impl MdxLspAdapter {
+    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
+        MdxLspAdapter {
+            node,
+            typescript_install_path: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for MdxLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("mdx-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "mdx"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(MdxLspVersion {
+            mdx_version: self
+                .node
+                .npm_package_latest_version("@mdx-js/language-server")
+                .await?,
+            ts_version: self.node.npm_package_latest_version("typescript").await?,
+        }) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<MdxLspVersion>().unwrap();
+        let server_path = container_dir.join(SERVER_PATH);
+        let typescript_path = container_dir.join(TYPESCRIPT_PATH);
+
+        if fs::metadata(&server_path).await.is_err()
+            || fs::metadata(&typescript_path).await.is_err()
+        {
+            self.node
+                .npm_install_packages(
+                    &container_dir,
+                    &[
+                        ("@mdx-js/language-server", version.mdx_version.as_str()),
+                        ("typescript", version.ts_version.as_str()),
+                    ],
+                )
+                .await?;
+        }
+        ensure!(
+            fs::metadata(&typescript_path).await.is_ok(),
+            "typescript for MDX package installation failed"
+        );
+
+        *self.typescript_install_path.lock() = Some(typescript_path);
+        Ok(LanguageServerBinary {
+            path: self.node.binary_path().await?,
+            arguments: server_binary_arguments(&server_path),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        let (binary, typescript_path) =
+            get_cached_server_binary(container_dir, &*self.node).await?;
+        *self.typescript_install_path.lock() = Some(typescript_path);
+        Some(binary)
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node)
+            .await
+            .map(|(binary, _)| binary)
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        let typescript_install_path = self.typescript_install_path.lock();
+        Some(json!({
+            "typescript": {
+                "enabled": true,
+                "tsdk": typescript_install_path.as_ref()?,
+            }
+        }))
+    }
+}
+
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    node: &dyn NodeRuntime,
+) -> Option<(LanguageServerBinary, PathBuf)> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir() {
+                last_version_dir = Some(entry.path());
+            }
+        }
+        let last_version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let server_path = last_version_dir.join(SERVER_PATH);
+        let typescript_path = last_version_dir.join(TYPESCRIPT_PATH);
+        if server_path.exists() && typescript_path.exists() {
+            Ok((
+                LanguageServerBinary {
+                    path: node.binary_path().await?,
+                    arguments: server_binary_arguments(&server_path),
+                },
+                typescript_path,
+            ))
+        } else {
+            Err(anyhow!(
+                "missing executable in directory {:?}",
+                last_version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,13 @@
+(fenced_code_block
+  (info_string
+    (language) @language)
+  (code_fence_content) @content)
+
+; MDX parses what Markdown treats as HTML as JSX, and lets ESM statements stand
+; on their own lines.
+((html_block) @content
+  (#set! "language" "tsx"))
+
+((paragraph) @content
+  (#match? @content "^(import|export) ")
+  (#set! "language" "tsx"))
@@ -0,0 +1,5 @@
+(atx_heading
+    .
+    (_) @context
+    .
+    (_) @name ) @item
@@ -1,21 +1,38 @@
-use std::{any::Any, ops::Range, path::PathBuf, sync::Arc};
-
-use anyhow::{anyhow, Result};
+use std::{
+    any::Any,
+    ffi::OsString,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use collections::HashMap;
 use language::{CodeLabel, LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::{CompletionItemKind, LanguageServerBinary, SymbolKind};
 use rope::Rope;
+use smol::process::Command;
+use util::{
+    archive::check_unverified_install,
+    audit_log::{self, AuditEvent, AuditEventKind},
+    paths,
+};
+
+const SERVER_NAME: &str = "ocamllsp";
 
 const OPERATOR_CHAR: [char; 17] = [
     '~', '!', '?', '%', '<', ':', '.', '$', '&', '*', '+', '-', '/', '=', '>', '@', '^',
 ];
 
+/// `ocamllsp` has to be built with the same compiler as the project, so it is taken from
+/// the current opam switch, and installed into it with opam when missing.
 pub struct OCamlLspAdapter;
 
 #[async_trait]
 impl LspAdapter for OCamlLspAdapter {
     fn name(&self) -> LanguageServerName {
-        LanguageServerName("ocamllsp".into())
+        LanguageServerName(SERVER_NAME.into())
     }
 
     fn short_name(&self) -> &'static str {
@@ -33,11 +50,39 @@ impl LspAdapter for OCamlLspAdapter {
         &self,
         _: Box<dyn 'static + Send + Any>,
         _: PathBuf,
-        _: &dyn LspAdapterDelegate,
+        delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
-        Err(anyhow!(
-            "ocamllsp (ocaml-language-server) must be installed manually."
-        ))
+        let project_root = delegate.worktree_root_path();
+        if let Some(path) = opam_server_path(project_root).await {
+            return Ok(server_binary(path));
+        }
+
+        let switch_bin_dir = opam_switch_bin_dir(project_root).await.ok_or_else(|| {
+            anyhow!("ocamllsp (ocaml-language-server) must be installed manually, or with opam")
+        })?;
+        check_unverified_install("ocaml-lsp-server with opam").await?;
+        let mut event = AuditEvent::new(
+            AuditEventKind::Command,
+            "opam",
+            "opam install ocaml-lsp-server",
+        )
+        .details("opam install --yes ocaml-lsp-server");
+        if let Some(project_root) = project_root {
+            event = event.project_root(project_root);
+        }
+        audit_log::record(event);
+        let output = opam_command(project_root)
+            .args(["install", "--yes", "ocaml-lsp-server"])
+            .output()
+            .await
+            .context("failed to run opam")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "failed to install ocaml-lsp-server with opam: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(server_binary(switch_bin_dir.join(SERVER_NAME)))
     }
 
     async fn cached_server_binary(
@@ -45,10 +90,7 @@ impl LspAdapter for OCamlLspAdapter {
         _: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        Some(LanguageServerBinary {
-            path: "ocamllsp".into(),
-            arguments: vec![],
-        })
+        Some(server_binary(SERVER_NAME.into()))
     }
 
     fn can_be_reinstalled(&self) -> bool {
@@ -59,6 +101,27 @@ impl LspAdapter for OCamlLspAdapter {
         None
     }
 
+    /// Dune projects are described to `ocamllsp` by dune itself. Other projects may
+    /// still have a `.merlin` file, which `ocamllsp` only reads when asked to.
+    async fn root_specific_arguments(&self, root_path: &Path) -> Vec<OsString> {
+        let is_dune_project = smol::fs::metadata(root_path.join("dune-project"))
+            .await
+            .is_ok();
+        let has_merlin_file = smol::fs::metadata(root_path.join(".merlin")).await.is_ok();
+        if !is_dune_project && has_merlin_file {
+            vec!["--fallback-read-dot-merlin".into()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("OCaml".into(), "ocaml".into()),
+            ("OCaml Interface".into(), "ocaml.interface".into()),
+        ])
+    }
+
     async fn label_for_completion(
         &self,
         completion: &lsp::CompletionItem,
@@ -315,3 +378,45 @@ impl LspAdapter for OCamlLspAdapter {
         })
     }
 }
+
+fn server_binary(path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path,
+        arguments: vec![],
+    }
+}
+
+/// Runs opam from the project root, so that a local switch in the project's `_opam`
+/// directory is picked over the global one.
+fn opam_command(project_root: Option<&Path>) -> Command {
+    let mut command = Command::new("opam");
+    if let Some(project_root) = project_root {
+        command.current_dir(project_root);
+    }
+    command
+}
+
+/// Finds `ocamllsp` in the current opam switch, or in the default switch when opam
+/// isn't on the `PATH`, which is the case when Zed isn't launched from a terminal.
+async fn opam_server_path(project_root: Option<&Path>) -> Option<PathBuf> {
+    let bin_dir = match opam_switch_bin_dir(project_root).await {
+        Some(bin_dir) => bin_dir,
+        None => paths::HOME.join(".opam").join("default").join("bin"),
+    };
+    let path = bin_dir.join(SERVER_NAME);
+    smol::fs::metadata(&path).await.is_ok().then_some(path)
+}
+
+async fn opam_switch_bin_dir(project_root: Option<&Path>) -> Option<PathBuf> {
+    let output = opam_command(project_root)
+        .args(["var", "bin"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = String::from_utf8(output.stdout).ok()?;
+    let path = path.trim();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
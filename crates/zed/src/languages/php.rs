@@ -2,10 +2,15 @@ use anyhow::{anyhow, Result};
 
 use async_trait::async_trait;
 use collections::HashMap;
+use gpui::AppContext;
 
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
-use lsp::LanguageServerBinary;
+use lsp::{CompletionItemKind, LanguageServerBinary};
 use node_runtime::NodeRuntime;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::json;
+use settings::Settings;
 
 use smol::{fs, stream::StreamExt};
 use std::{
@@ -20,17 +25,48 @@ fn intelephense_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
     vec![server_path.into(), "--stdio".into()]
 }
 
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IntelephenseSettings {
+    pub license_key: Option<String>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct IntelephenseSettingsContent {
+    /// The licence key that unlocks intelephense's premium features.
+    ///
+    /// Default: null
+    license_key: Option<String>,
+}
+
+impl Settings for IntelephenseSettings {
+    const KEY: Option<&'static str> = Some("intelephense");
+
+    type FileContent = IntelephenseSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
 pub struct IntelephenseVersion(String);
 
 pub struct IntelephenseLspAdapter {
     node: Arc<dyn NodeRuntime>,
+    license_key: Option<String>,
 }
 
 impl IntelephenseLspAdapter {
     const SERVER_PATH: &'static str = "node_modules/intelephense/lib/intelephense.js";
 
-    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
-        Self { node }
+    pub fn new(node: Arc<dyn NodeRuntime>, license_key: Option<String>) -> Self {
+        Self { node, license_key }
     }
 }
 
@@ -90,14 +126,33 @@ impl LspAdapter for IntelephenseLspAdapter {
 
     async fn label_for_completion(
         &self,
-        _item: &lsp::CompletionItem,
-        _language: &Arc<language::Language>,
+        item: &lsp::CompletionItem,
+        language: &Arc<language::Language>,
     ) -> Option<language::CodeLabel> {
-        None
+        let label = &item.label;
+        let highlight_name = match item.kind? {
+            CompletionItemKind::METHOD => "function.method",
+            CompletionItemKind::FUNCTION => "function",
+            CompletionItemKind::CLASS
+            | CompletionItemKind::INTERFACE
+            | CompletionItemKind::ENUM => "type",
+            CompletionItemKind::CONSTANT | CompletionItemKind::ENUM_MEMBER => "constant",
+            CompletionItemKind::PROPERTY | CompletionItemKind::FIELD => "property",
+            CompletionItemKind::VARIABLE => "variable",
+            CompletionItemKind::KEYWORD => "keyword",
+            _ => return None,
+        };
+        let highlight_id = language.grammar()?.highlight_id_for_name(highlight_name)?;
+        Some(language::CodeLabel {
+            text: label.clone(),
+            runs: vec![(0..label.len(), highlight_id)],
+            filter_range: 0..label.len(),
+        })
     }
 
     fn initialization_options(&self) -> Option<serde_json::Value> {
-        None
+        let license_key = self.license_key.as_ref()?;
+        Some(json!({ "licenceKey": license_key }))
     }
     fn language_ids(&self) -> HashMap<String, String> {
         HashMap::from_iter([("PHP".into(), "php".into())])
@@ -134,3 +189,55 @@ async fn get_cached_server_binary(
     .await
     .log_err()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::language;
+    use gpui::Hsla;
+    use language::CodeLabel;
+    use node_runtime::FakeNodeRuntime;
+    use theme::SyntaxTheme;
+
+    #[gpui::test]
+    async fn test_php_label_for_completion() {
+        let adapter = Arc::new(IntelephenseLspAdapter::new(FakeNodeRuntime::new(), None));
+        let language = language("php", tree_sitter_php::language_php(), Some(adapter)).await;
+        let theme =
+            SyntaxTheme::new_test([("type", Hsla::default()), ("function", Hsla::default())]);
+        language.set_theme(&theme);
+
+        let grammar = language.grammar().unwrap();
+        let highlight_type = grammar.highlight_id_for_name("type").unwrap();
+        let highlight_function = grammar.highlight_id_for_name("function").unwrap();
+
+        assert_eq!(
+            language
+                .label_for_completion(&lsp::CompletionItem {
+                    kind: Some(lsp::CompletionItemKind::CLASS),
+                    label: "DateTimeImmutable".to_string(),
+                    ..Default::default()
+                })
+                .await,
+            Some(CodeLabel {
+                text: "DateTimeImmutable".to_string(),
+                runs: vec![(0..17, highlight_type)],
+                filter_range: 0..17,
+            })
+        );
+        assert_eq!(
+            language
+                .label_for_completion(&lsp::CompletionItem {
+                    kind: Some(lsp::CompletionItemKind::FUNCTION),
+                    label: "array_map".to_string(),
+                    ..Default::default()
+                })
+                .await,
+            Some(CodeLabel {
+                text: "array_map".to_string(),
+                runs: vec![(0..9, highlight_function)],
+                filter_range: 0..9,
+            })
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use gpui::{AppContext, AsyncAppContext, Task};
+use language::{LanguageRegistry, LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use smol::fs;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{
+    archive::{download_archive_atomically, ArchiveType},
+    async_maybe,
+    fs::matching_by_recency,
+    github::{latest_github_release, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+const SERVER_NAME: &str = "powershell-editor-services";
+const ASSET_NAME: &str = "PowerShellEditorServices.zip";
+/// The script that starts PowerShell Editor Services, relative to the directory
+/// that the release is extracted into, which also holds the modules it bundles.
+const START_SCRIPT_PATH: &str = "PowerShellEditorServices/Start-EditorServices.ps1";
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PowerShellSettings {
+    pub executable: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct PowerShellSettingsContent {
+    /// The path to the PowerShell executable that runs PowerShell Editor Services,
+    /// which needs PowerShell 7.2 or newer, or Windows PowerShell 5.1.
+    /// When null, `pwsh` on the `PATH` is used.
+    ///
+    /// Default: null
+    executable: Option<PathBuf>,
+}
+
+impl Settings for PowerShellSettings {
+    const KEY: Option<&'static str> = Some("powershell");
+
+    type FileContent = PowerShellSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// Looks up the PowerShell executable again when the server is next started, whenever
+/// `executable` changes.
+pub fn init(languages: Arc<LanguageRegistry>, cx: &mut AppContext) {
+    let mut executable = PowerShellSettings::get_global(cx).executable.clone();
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_executable = &PowerShellSettings::get_global(cx).executable;
+        if *new_executable != executable {
+            executable = new_executable.clone();
+            languages.forget_server_binary(&LanguageServerName(SERVER_NAME.into()));
+        }
+    })
+    .detach();
+}
+
+/// PowerShell Editor Services is a PowerShell module, so it's downloaded as a release
+/// of modules and started with a script run by PowerShell.
+#[derive(Default)]
+pub struct PowerShellLspAdapter {
+    /// The `executable` setting when the server's binary was last looked up.
+    executable: Mutex<Option<PathBuf>>,
+}
+
+impl PowerShellLspAdapter {
+    fn executable(&self) -> PathBuf {
+        self.executable
+            .lock()
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("pwsh"))
+    }
+
+    fn server_binary(&self, version_dir: &Path) -> LanguageServerBinary {
+        let command = format!(
+            "& '{}' -BundledModulesPath '{}' -LogPath '{}' -SessionDetailsPath '{}' \
+             -HostName Zed -HostProfileId Zed -HostVersion 1.0.0 \
+             -FeatureFlags @() -AdditionalModules @() -LogLevel Normal -Stdio",
+            quote(&version_dir.join(START_SCRIPT_PATH)),
+            quote(version_dir),
+            quote(&version_dir.join("logs")),
+            quote(&version_dir.join("session.json")),
+        );
+        LanguageServerBinary {
+            path: self.executable(),
+            arguments: vec![
+                "-NoLogo".into(),
+                "-NoProfile".into(),
+                "-NonInteractive".into(),
+                "-Command".into(),
+                command.into(),
+            ],
+        }
+    }
+}
+
+/// Escapes a path for a single-quoted PowerShell string.
+fn quote(path: &Path) -> String {
+    path.to_string_lossy().replace('\'', "''")
+}
+
+#[async_trait]
+impl LspAdapter for PowerShellLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName(SERVER_NAME.into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "powershell"
+    }
+
+    fn will_fetch_server(
+        &self,
+        _: &Arc<dyn LspAdapterDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Option<Task<Result<()>>> {
+        *self.executable.lock() = cx
+            .update(|cx| PowerShellSettings::get_global(cx).executable.clone())
+            .ok()
+            .flatten();
+        None
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = latest_github_release(
+            "PowerShell/PowerShellEditorServices",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == ASSET_NAME)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", ASSET_NAME))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("PowerShellEditorServices_{}", version.name));
+
+        if fs::metadata(version_dir.join(START_SCRIPT_PATH))
+            .await
+            .is_err()
+        {
+            download_archive_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
+        }
+
+        Ok(self.server_binary(&version_dir))
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        let version_dir = get_cached_version_dir(container_dir).await?;
+        Some(self.server_binary(&version_dir))
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_version_dir(container_dir).await?;
+        Some(LanguageServerBinary {
+            path: self.executable(),
+            arguments: vec!["-Version".into()],
+        })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_editor_services_version(entry)
+    }
+}
+
+fn is_editor_services_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        let name = name.to_string_lossy();
+        name.starts_with("PowerShellEditorServices_") && !name.ends_with(".zip")
+    })
+}
+
+async fn get_cached_version_dir(container_dir: PathBuf) -> Option<PathBuf> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_editor_services_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        if version_dir.join(START_SCRIPT_PATH).exists() {
+            Ok(version_dir)
+        } else {
+            Err(anyhow!(
+                "missing PowerShell Editor Services in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
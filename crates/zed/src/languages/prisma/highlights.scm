@@ -0,0 +1,50 @@
+[
+  "datasource"
+  "enum"
+  "generator"
+  "model"
+] @keyword
+
+[
+  (comment)
+  (developer_comment)
+] @comment
+
+(string) @string
+
+(number) @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+(enumeral) @constant
+
+(column_type) @type
+
+(attribute) @attribute
+
+(block_attribute_declaration) @attribute
+
+(call_expression) @function
+
+(variable) @property
+
+(column_declaration
+  (identifier) @property)
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "="
+  "@"
+  "@@"
+] @operator
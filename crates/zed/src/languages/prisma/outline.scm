@@ -0,0 +1,18 @@
+(model_declaration
+    "model" @context
+    (identifier) @name) @item
+
+(enum_declaration
+    "enum" @context
+    (identifier) @name) @item
+
+(datasource_declaration
+    "datasource" @context
+    (identifier) @name) @item
+
+(generator_declaration
+    "generator" @context
+    (identifier) @name) @item
+
+(column_declaration
+    (identifier) @name) @item
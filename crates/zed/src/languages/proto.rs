@@ -0,0 +1,134 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::env::consts::{ARCH, OS};
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::archive::download_binary_atomically;
+use util::async_maybe;
+use util::fs::matching_by_recency;
+use util::github::latest_github_release;
+use util::{github::GitHubLspBinaryVersion, ResultExt};
+
+/// The language server is a subcommand of the `buf` CLI, which also formats `.proto`
+/// files and reports the lint rules configured in `buf.yaml` as diagnostics.
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["beta".into(), "lsp".into()]
+}
+
+pub struct BufLspAdapter;
+
+#[async_trait]
+impl LspAdapter for BufLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("buf".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "buf"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            latest_github_release("bufbuild/buf", true, false, delegate.http_client()).await?;
+        let asset_name = asset_name()?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let binary_path = container_dir.join(format!("buf-{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_binary_atomically(
+                &*delegate.http_client(),
+                &version.url,
+                version.sha256.as_deref(),
+                &binary_path,
+            )
+            .await
+            .context("error downloading release")?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_buf_version(entry)
+    }
+}
+
+fn asset_name() -> Result<&'static str> {
+    Ok(match (OS, ARCH) {
+        ("macos", "x86_64") => "buf-Darwin-x86_64",
+        ("macos", "aarch64") => "buf-Darwin-arm64",
+        ("linux", "x86_64") => "buf-Linux-x86_64",
+        ("linux", "aarch64") => "buf-Linux-aarch64",
+        (os, arch) => bail!("Running on unsupported platform: {os} {arch}"),
+    })
+}
+
+fn is_buf_version(entry: &Path) -> bool {
+    entry
+        .file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with("buf-"))
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let latest = matching_by_recency(&container_dir, is_buf_version)
+            .await
+            .into_iter()
+            .next();
+
+        anyhow::Ok(LanguageServerBinary {
+            path: latest.ok_or_else(|| anyhow!("no cached binary"))?,
+            arguments: server_binary_arguments(),
+        })
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,205 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use gpui::{AppContext, AsyncAppContext, Task};
+use language::{LanguageRegistry, LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const SERVER_NAME: &str = "r_language_server";
+
+/// An R expression that exits with a failure status when the `languageserver`
+/// package isn't installed.
+const CHECK_LANGUAGESERVER_INSTALLED: &str =
+    "if (!requireNamespace(\"languageserver\", quietly = TRUE)) quit(status = 1)";
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RSettings {
+    pub r_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct RSettingsContent {
+    /// The path to the R binary that runs the `languageserver` package.
+    /// When null, `R` on the `PATH` is used.
+    ///
+    /// Default: null
+    r_path: Option<PathBuf>,
+}
+
+impl Settings for RSettings {
+    const KEY: Option<&'static str> = Some("r");
+
+    type FileContent = RSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// Looks up the R binary again when the server is next started, whenever `r_path`
+/// changes.
+pub fn init(languages: Arc<LanguageRegistry>, cx: &mut AppContext) {
+    let mut r_path = RSettings::get_global(cx).r_path.clone();
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_r_path = &RSettings::get_global(cx).r_path;
+        if *new_r_path != r_path {
+            r_path = new_r_path.clone();
+            languages.forget_server_binary(&LanguageServerName(SERVER_NAME.into()));
+        }
+    })
+    .detach();
+}
+
+/// The R language server is an R package, which is installed into the user's R
+/// library rather than downloaded.
+#[derive(Default)]
+pub struct RLanguageServer {
+    /// The `r_path` setting when the server's binary was last looked up.
+    r_path: Mutex<Option<PathBuf>>,
+}
+
+#[async_trait]
+impl LspAdapter for RLanguageServer {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName(SERVER_NAME.into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "r"
+    }
+
+    fn will_fetch_server(
+        &self,
+        _: &Arc<dyn LspAdapterDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Option<Task<Result<()>>> {
+        *self.r_path.lock() = cx
+            .update(|cx| RSettings::get_global(cx).r_path.clone())
+            .ok()
+            .flatten();
+        None
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(()))
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _version: Box<dyn 'static + Send + Any>,
+        _container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let r_binary = r_binary(self.r_path.lock().as_deref());
+        check_languageserver_installed(&r_binary).await?;
+        Ok(server_binary(r_binary))
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        // Nothing is downloaded, so a binary whose package check failed isn't used.
+        None
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+}
+
+fn r_binary(r_path: Option<&Path>) -> PathBuf {
+    r_path.map_or_else(|| PathBuf::from("R"), Path::to_path_buf)
+}
+
+fn server_binary(r_binary: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path: r_binary,
+        arguments: vec![
+            "--slave".into(),
+            "-e".into(),
+            "languageserver::run()".into(),
+        ],
+    }
+}
+
+async fn check_languageserver_installed(r_binary: &Path) -> Result<()> {
+    let output = smol::process::Command::new(r_binary)
+        .args(["--slave", "-e", CHECK_LANGUAGESERVER_INSTALLED])
+        .output()
+        .await
+        .with_context(|| format!("failed to run {r_binary:?}, set `r.r_path` to the R binary"))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "the languageserver R package isn't installed for {r_binary:?}, install it with install.packages(\"languageserver\")"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_r_binary() {
+        assert_eq!(r_binary(None), PathBuf::from("R"));
+        assert_eq!(
+            r_binary(Some(Path::new("/opt/R/4.3.2/bin/R"))),
+            PathBuf::from("/opt/R/4.3.2/bin/R")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_languageserver_installed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin_dir = tempfile::tempdir().unwrap();
+        let fake_r = |name: &str, status: i32| {
+            let path = bin_dir.path().join(name);
+            std::fs::write(&path, format!("#!/bin/sh\nexit {status}\n")).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            path
+        };
+        let with_package = fake_r("R-with-package", 0);
+        let without_package = fake_r("R-without-package", 1);
+
+        smol::block_on(async {
+            assert!(check_languageserver_installed(&with_package).await.is_ok());
+
+            let error = check_languageserver_installed(&without_package)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("isn't installed"), "{error}");
+
+            let error = check_languageserver_installed(&bin_dir.path().join("missing"))
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("failed to run"), "{error}");
+        });
+    }
+}
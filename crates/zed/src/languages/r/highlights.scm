@@ -0,0 +1,107 @@
+(comment) @comment
+
+(string) @string
+(escape_sequence) @string.escape
+
+[
+  (integer)
+  (float)
+  (complex)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+[
+  (null)
+  (na)
+  (nan)
+  (inf)
+] @constant.builtin
+
+(identifier) @variable
+
+(parameter
+  name: (identifier) @parameter)
+
+(argument
+  name: (identifier) @property)
+
+(call
+  function: (identifier) @function)
+
+(call
+  function: (namespace_operator
+    rhs: (identifier) @function))
+
+(namespace_operator
+  lhs: (identifier) @module)
+
+(binary_operator
+  lhs: (identifier) @function.definition
+  operator: ["<-" "<<-" "="]
+  rhs: (function_definition))
+
+(extract_operator
+  rhs: (identifier) @property)
+
+[
+  "function"
+  "if"
+  "else"
+  "for"
+  "in"
+  "while"
+  "repeat"
+  (break)
+  (next)
+] @keyword
+
+((identifier) @keyword
+ (#match? @keyword "^(return|library|require|source|stop|warning|invisible|on\\.exit)$"))
+
+[
+  "?"
+  "="
+  "<-"
+  "<<-"
+  "->"
+  "->>"
+  "~"
+  "|>"
+  "||"
+  "|"
+  "&&"
+  "&"
+  "<"
+  "<="
+  ">"
+  ">="
+  "=="
+  "!="
+  "+"
+  "-"
+  "*"
+  "/"
+  "^"
+  "!"
+  ":"
+  "$"
+  "@"
+  "::"
+  ":::"
+  (special)
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+(comma) @punctuation.delimiter
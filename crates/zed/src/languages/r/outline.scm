@@ -0,0 +1,4 @@
+(binary_operator
+    lhs: [(identifier) (string)] @name
+    operator: ["<-" "<<-" "="]
+    rhs: (function_definition)) @item
@@ -1,10 +1,30 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use gem_runtime::{gem_executable_path, GemRuntime};
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
-use std::{any::Any, path::PathBuf, sync::Arc};
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{async_maybe, fs::matching_by_recency, ResultExt};
 
-pub struct RubyLanguageServer;
+const GEM_NAME: &str = "solargraph";
+
+pub struct RubyLanguageServer {
+    gem_runtime: Arc<dyn GemRuntime>,
+}
+
+impl RubyLanguageServer {
+    pub fn new(gem_runtime: Arc<dyn GemRuntime>) -> Self {
+        RubyLanguageServer { gem_runtime }
+    }
+}
+
+struct GemVersion(String);
 
 #[async_trait]
 impl LspAdapter for RubyLanguageServer {
@@ -20,35 +40,51 @@ impl LspAdapter for RubyLanguageServer {
         &self,
         _: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Any + Send>> {
-        Ok(Box::new(()))
+        let version = self.gem_runtime.gem_latest_version(GEM_NAME).await?;
+        Ok(Box::new(GemVersion(version)) as Box<_>)
     }
 
     async fn fetch_server_binary(
         &self,
-        _version: Box<dyn 'static + Send + Any>,
-        _container_dir: PathBuf,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
-        Err(anyhow!("solargraph must be installed manually"))
+        let version = version.downcast::<GemVersion>().unwrap();
+        let gem_dir = container_dir.join(&version.0);
+
+        if fs::metadata(gem_executable(&gem_dir)).await.is_err() {
+            self.gem_runtime
+                .gem_install(GEM_NAME, &version.0, &gem_dir)
+                .await?;
+        }
+
+        Ok(server_binary(&gem_dir))
     }
 
     async fn cached_server_binary(
         &self,
-        _: PathBuf,
+        container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        Some(LanguageServerBinary {
-            path: "solargraph".into(),
-            arguments: vec!["stdio".into()],
-        })
+        get_cached_server_binary(container_dir).await
     }
 
-    fn can_be_reinstalled(&self) -> bool {
-        false
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments.pop();
+                binary.arguments.push("--version".into());
+                binary
+            })
     }
 
-    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
-        None
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_gem_version(entry)
     }
 
     async fn label_for_completion(
@@ -158,3 +194,49 @@ impl LspAdapter for RubyLanguageServer {
         }
     }
 }
+
+fn gem_executable(gem_dir: &Path) -> PathBuf {
+    gem_executable_path(gem_dir, GEM_NAME)
+}
+
+/// The gem's executable only finds the gem's code if it's on the `GEM_PATH`, so the
+/// server is started through `env`.
+fn server_binary(gem_dir: &Path) -> LanguageServerBinary {
+    let mut gem_home = OsString::from("GEM_HOME=");
+    gem_home.push(gem_dir);
+    let mut gem_path = OsString::from("GEM_PATH=");
+    gem_path.push(gem_dir);
+    LanguageServerBinary {
+        path: "env".into(),
+        arguments: vec![
+            gem_home,
+            gem_path,
+            gem_executable(gem_dir).into(),
+            "stdio".into(),
+        ],
+    }
+}
+
+/// Each version of the gem is installed into a directory named after the version.
+fn is_gem_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy()
+            .starts_with(|c: char| c.is_ascii_digit())
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let gem_dir = matching_by_recency(&container_dir, is_gem_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        if fs::metadata(gem_executable(&gem_dir)).await.is_err() {
+            return Err(anyhow!("missing executable in directory {:?}", gem_dir));
+        }
+        Ok(server_binary(&gem_dir))
+    })
+    .await
+    .log_err()
+}
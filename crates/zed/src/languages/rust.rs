@@ -1,26 +1,97 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
+use futures::io::BufReader;
+use gpui::AppContext;
 pub use language::*;
 use lazy_static::lazy_static;
 use lsp::LanguageServerBinary;
+use project::project_settings::ProjectSettings;
 use regex::Regex;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use settings::Settings;
 use smol::fs::{self, File};
-use std::{any::Any, borrow::Cow, env::consts, path::PathBuf, str, sync::Arc};
+use std::{
+    any::Any,
+    borrow::Cow,
+    env::consts,
+    path::{Path, PathBuf},
+    str,
+    sync::Arc,
+};
 use util::{
     async_maybe,
-    fs::remove_matching,
-    github::{latest_github_release, GitHubLspBinaryVersion},
-    ResultExt,
+    fs::matching_by_recency,
+    github::{download_release, latest_github_release, GitHubLspBinaryVersion},
+    merge_json_value_into, ResultExt,
 };
 
+const SERVER_NAME: &'static str = "rust-analyzer";
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RustAnalyzerSettings {
+    pub check_command: String,
+    pub all_features: bool,
+    pub features: Vec<String>,
+    pub target_dir: Option<String>,
+    pub proc_macros: bool,
+    pub proc_macro_attributes: bool,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct RustAnalyzerSettingsContent {
+    /// The cargo command used to check the project on save, such as `check` or `clippy`.
+    ///
+    /// Default: "check"
+    check_command: Option<String>,
+    /// Whether to activate all of the cargo features of the workspace.
+    ///
+    /// Default: false
+    all_features: Option<bool>,
+    /// The cargo features to activate when `all_features` is disabled.
+    ///
+    /// Default: []
+    features: Option<Vec<String>>,
+    /// The directory that rust-analyzer runs cargo in, to avoid blocking the builds
+    /// started from the terminal. Uses the workspace's target directory when null.
+    ///
+    /// Default: null
+    target_dir: Option<String>,
+    /// Whether to expand procedural macros.
+    ///
+    /// Default: true
+    proc_macros: Option<bool>,
+    /// Whether to expand attribute procedural macros.
+    ///
+    /// Default: true
+    proc_macro_attributes: Option<bool>,
+}
+
+impl Settings for RustAnalyzerSettings {
+    const KEY: Option<&'static str> = Some("rust_analyzer");
+
+    type FileContent = RustAnalyzerSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
 pub struct RustLspAdapter;
 
 #[async_trait]
 impl LspAdapter for RustLspAdapter {
     fn name(&self) -> LanguageServerName {
-        LanguageServerName("rust-analyzer".into())
+        LanguageServerName(SERVER_NAME.into())
     }
 
     fn short_name(&self) -> &'static str {
@@ -44,10 +115,10 @@ impl LspAdapter for RustLspAdapter {
             .iter()
             .find(|asset| asset.name == asset_name)
             .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        Ok(Box::new(GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url.clone(),
-        }))
+        Ok(Box::new(GitHubLspBinaryVersion::new(
+            release.tag_name,
+            asset,
+        )))
     }
 
     async fn fetch_server_binary(
@@ -60,9 +131,7 @@ impl LspAdapter for RustLspAdapter {
         let destination_path = container_dir.join(format!("rust-analyzer-{}", version.name));
 
         if fs::metadata(&destination_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
+            let mut response = download_release(&*delegate.http_client(), &version)
                 .await
                 .map_err(|err| anyhow!("error downloading release: {}", err))?;
             let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
@@ -73,8 +142,6 @@ impl LspAdapter for RustLspAdapter {
                 <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
             )
             .await?;
-
-            remove_matching(&container_dir, |entry| entry != destination_path).await;
         }
 
         Ok(LanguageServerBinary {
@@ -103,6 +170,68 @@ impl LspAdapter for RustLspAdapter {
             })
     }
 
+    fn workspace_configuration(&self, _workspace_root: &Path, cx: &mut AppContext) -> Value {
+        let settings = RustAnalyzerSettings::get_global(cx);
+        let features = if settings.all_features {
+            json!("all")
+        } else {
+            json!(settings.features)
+        };
+        let mut configuration = json!({
+            "check": {
+                "command": settings.check_command,
+            },
+            "cargo": {
+                "features": features,
+                "targetDir": settings.target_dir,
+            },
+            "procMacro": {
+                "enable": settings.proc_macros,
+                "attributes": {
+                    "enable": settings.proc_macro_attributes,
+                },
+            },
+        });
+
+        // rust-analyzer replaces the configuration it was initialized with when it pulls
+        // the workspace configuration, so keep the user's initialization options around.
+        let initialization_options = ProjectSettings::get_global(cx)
+            .lsp
+            .get(SERVER_NAME)
+            .and_then(|settings| settings.initialization_options.clone());
+        if let Some(initialization_options) = initialization_options {
+            merge_json_value_into(initialization_options, &mut configuration);
+        }
+
+        json!({ SERVER_NAME: configuration })
+    }
+
+    fn supports_workspace_folders(&self) -> bool {
+        true
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_rust_analyzer_version(entry)
+    }
+
+    async fn check_server_binary(&self, binary: &LanguageServerBinary) -> Result<()> {
+        // A download that was cut short leaves a truncated binary behind, which can't run.
+        let output = smol::process::Command::new(&binary.path)
+            .arg("--version")
+            .output()
+            .await
+            .with_context(|| format!("failed to run {:?}", binary.path))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "{:?} --version failed: {}",
+                binary.path,
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+
     fn disk_based_diagnostic_sources(&self) -> Vec<String> {
         vec!["rustc".into()]
     }
@@ -276,16 +405,22 @@ impl LspAdapter for RustLspAdapter {
     }
 }
 
+fn is_rust_analyzer_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy()
+            .starts_with(&format!("{SERVER_NAME}-"))
+    })
+}
+
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            last = Some(entry?.path());
-        }
+        let latest = matching_by_recency(&container_dir, is_rust_analyzer_version)
+            .await
+            .into_iter()
+            .next();
 
         anyhow::Ok(LanguageServerBinary {
-            path: last.ok_or_else(|| anyhow!("no cached binary"))?,
+            path: latest.ok_or_else(|| anyhow!("no cached binary"))?,
             arguments: Default::default(),
         })
     })
@@ -498,6 +633,61 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_rust_workspace_configuration(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let test_settings = SettingsStore::test(cx);
+            cx.set_global(test_settings);
+            ProjectSettings::register(cx);
+            RustAnalyzerSettings::register(cx);
+
+            let configuration = RustLspAdapter.workspace_configuration(Path::new("/a"), cx);
+            assert_eq!(
+                configuration[SERVER_NAME],
+                json!({
+                    "check": { "command": "check" },
+                    "cargo": { "features": [], "targetDir": null },
+                    "procMacro": { "enable": true, "attributes": { "enable": true } },
+                })
+            );
+
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<RustAnalyzerSettings>(cx, |settings| {
+                    settings.check_command = Some("clippy".into());
+                    settings.all_features = Some(true);
+                    settings.features = Some(vec!["serde".into()]);
+                });
+            });
+            let configuration = RustLspAdapter.workspace_configuration(Path::new("/a"), cx);
+            assert_eq!(configuration[SERVER_NAME]["check"]["command"], "clippy");
+            assert_eq!(configuration[SERVER_NAME]["cargo"]["features"], "all");
+
+            // Initialization options take precedence over the typed settings.
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_user_settings(
+                        r#"{
+                            "rust_analyzer": { "check_command": "clippy" },
+                            "lsp": {
+                                "rust-analyzer": {
+                                    "initialization_options": {
+                                        "check": { "command": "check", "allTargets": false }
+                                    }
+                                }
+                            }
+                        }"#,
+                        cx,
+                    )
+                    .unwrap();
+            });
+            let configuration = RustLspAdapter.workspace_configuration(Path::new("/a"), cx);
+            assert_eq!(
+                configuration[SERVER_NAME]["check"],
+                json!({ "command": "check", "allTargets": false })
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_rust_autoindent(cx: &mut TestAppContext) {
         // cx.executor().set_block_on_ticks(usize::MAX..=usize::MAX);
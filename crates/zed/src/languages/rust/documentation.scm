@@ -0,0 +1,23 @@
+[
+    (function_item)
+    (function_signature_item)
+] @item
+
+([
+    (function_item
+        parameters: (parameters
+            (parameter
+                pattern: (identifier) @param)))
+    (function_signature_item
+        parameters: (parameters
+            (parameter
+                pattern: (identifier) @param)))
+] @item)
+
+([
+    (function_item
+        return_type: (_) @returns)
+    (function_signature_item
+        return_type: (_) @returns)
+] @item
+    (#not-eq? @returns "()"))
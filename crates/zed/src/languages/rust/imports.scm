@@ -0,0 +1,3 @@
+(source_file
+    (use_declaration
+        argument: (_) @path) @import)
@@ -0,0 +1,275 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_trait::async_trait;
+use futures::io::BufReader;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+use smol::{
+    fs::{self, File},
+    process,
+};
+use std::{
+    any::Any,
+    env::consts::{ARCH, OS},
+    path::{Path, PathBuf},
+};
+use util::{
+    archive::{check_unverified_install, download_verified},
+    async_maybe,
+    audit_log::{self, AuditEvent, AuditEventKind},
+    fs::matching_by_recency,
+    github::latest_github_release,
+    ResultExt,
+};
+
+const COURSIER_BINARY_NAME: &str = "cs";
+const METALS_BINARY_NAME: &str = "metals";
+
+pub struct MetalsLspVersion {
+    metals_version: String,
+    coursier_url: String,
+    coursier_sha256: Option<String>,
+}
+
+/// The parameters of the `metals/status` notification, which Metals reports the
+/// progress of importing and compiling the build with.
+#[derive(Deserialize)]
+struct MetalsStatusParams {
+    text: String,
+    #[serde(default)]
+    hide: bool,
+}
+
+pub struct MetalsLspAdapter;
+
+#[async_trait]
+impl LspAdapter for MetalsLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("metals".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "metals"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let metals_release =
+            latest_github_release("scalameta/metals", false, false, delegate.http_client()).await?;
+        let coursier_release =
+            latest_github_release("coursier/coursier", true, false, delegate.http_client()).await?;
+        let asset_name = coursier_asset_name()?;
+        let asset = coursier_release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+
+        Ok(Box::new(MetalsLspVersion {
+            metals_version: metals_release.tag_name.trim_start_matches('v').to_string(),
+            coursier_url: asset.browser_download_url.clone(),
+            coursier_sha256: asset.sha256().map(Into::into),
+        }) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<MetalsLspVersion>().unwrap();
+        let coursier_path = container_dir.join(COURSIER_BINARY_NAME);
+        let version_dir = container_dir.join(format!("metals-{}", version.metals_version));
+        let binary_path = version_dir.join(METALS_BINARY_NAME);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            // The coursier launcher is verified, but the Metals artifacts it fetches aren't.
+            check_unverified_install(&format!("Metals {} with coursier", version.metals_version))
+                .await?;
+            if fs::metadata(&coursier_path).await.is_err() {
+                let bytes = download_verified(
+                    &*delegate.http_client(),
+                    &version.coursier_url,
+                    version.coursier_sha256.as_deref(),
+                )
+                .await
+                .context("error downloading coursier")?;
+                let decompressed_bytes = GzipDecoder::new(BufReader::new(bytes.as_slice()));
+                let mut file = File::create(&coursier_path).await?;
+                futures::io::copy(decompressed_bytes, &mut file).await?;
+                fs::set_permissions(
+                    &coursier_path,
+                    <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+                )
+                .await?;
+            }
+
+            // Coursier writes a launcher that fetches Metals and its dependencies, and
+            // runs it with the Java on the `PATH`.
+            fs::create_dir_all(&version_dir).await?;
+            audit_log::record(
+                AuditEvent::new(
+                    AuditEventKind::Command,
+                    "coursier",
+                    format!(
+                        "coursier bootstrap org.scalameta:metals_2.13:{}",
+                        version.metals_version
+                    ),
+                )
+                .details(format!(
+                    "{} bootstrap org.scalameta:metals_2.13:{} --output {}",
+                    coursier_path.display(),
+                    version.metals_version,
+                    binary_path.display()
+                )),
+            );
+            let output = process::Command::new(&coursier_path)
+                .arg("bootstrap")
+                .arg(format!(
+                    "org.scalameta:metals_2.13:{}",
+                    version.metals_version
+                ))
+                .args(["--java-opt", "-Xss4m", "--java-opt", "-Xms100m"])
+                .arg("--output")
+                .arg(&binary_path)
+                .arg("--force")
+                .output()
+                .await
+                .context("failed to run coursier")?;
+            if !output.status.success() {
+                fs::remove_dir_all(&version_dir).await.log_err();
+                bail!(
+                    "failed to bootstrap Metals: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_metals_version(entry)
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        Some(json!({
+            // Report the status of build imports and compilation through the
+            // `metals/status` notification.
+            "statusBarProvider": "on",
+            "isHttpEnabled": false,
+        }))
+    }
+
+    fn status_notification(&self) -> Option<&'static str> {
+        Some("metals/status")
+    }
+
+    fn status_message(&self, params: Value) -> Option<String> {
+        let params = serde_json::from_value::<MetalsStatusParams>(params).log_err()?;
+        if params.hide {
+            return None;
+        }
+        let message = strip_icons(&params.text);
+        (!message.is_empty()).then_some(message)
+    }
+}
+
+fn coursier_asset_name() -> Result<&'static str> {
+    Ok(match (OS, ARCH) {
+        ("macos", "x86_64") => "cs-x86_64-apple-darwin.gz",
+        ("macos", "aarch64") => "cs-aarch64-apple-darwin.gz",
+        ("linux", "x86_64") => "cs-x86_64-pc-linux.gz",
+        ("linux", "aarch64") => "cs-aarch64-pc-linux.gz",
+        (os, arch) => bail!("Running on unsupported platform: {os} {arch}"),
+    })
+}
+
+/// Removes the icons that Metals prefixes its status with, such as `$(sync~spin)`.
+fn strip_icons(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("$(") {
+        let Some(end) = rest[start..].find(')') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_metals_version(entry: &Path) -> bool {
+    entry
+        .file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with("metals-"))
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_metals_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join(METALS_BINARY_NAME);
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                path: binary_path,
+                arguments: Vec::new(),
+            })
+        } else {
+            Err(anyhow!("missing executable in directory {:?}", version_dir))
+        }
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_icons() {
+        assert_eq!(
+            strip_icons("$(sync~spin) Importing build"),
+            "Importing build"
+        );
+        assert_eq!(
+            strip_icons("Compiling $(rocket) core (42%)"),
+            "Compiling core (42%)"
+        );
+        assert_eq!(strip_icons("Build imported"), "Build imported");
+        assert_eq!(strip_icons("$(check)"), "");
+    }
+}
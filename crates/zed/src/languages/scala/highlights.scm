@@ -0,0 +1,125 @@
+(identifier) @variable
+
+((identifier) @constant
+ (#match? @constant "^[A-Z][A-Z\\d_]+$"))
+
+(type_identifier) @type
+
+(class_definition
+  name: (identifier) @type)
+
+(object_definition
+  name: (identifier) @type)
+
+(trait_definition
+  name: (identifier) @type)
+
+(function_definition
+  name: (identifier) @function)
+
+(function_declaration
+  name: (identifier) @function)
+
+(call_expression
+  function: (identifier) @function)
+
+(call_expression
+  function: (field_expression
+    field: (identifier) @function.method))
+
+(field_expression
+  field: (identifier) @property)
+
+(parameter
+  name: (identifier) @variable.parameter)
+
+(class_parameter
+  name: (identifier) @property)
+
+(annotation
+  "@" @attribute
+  name: (_) @attribute)
+
+(operator_identifier) @operator
+
+[
+  (comment)
+  (block_comment)
+] @comment
+
+[
+  (string)
+  (interpolated_string_expression)
+] @string
+
+(interpolation
+  "$" @punctuation.special)
+
+(character_literal) @string
+
+[
+  (integer_literal)
+  (floating_point_literal)
+] @number
+
+[
+  (boolean_literal)
+  (null_literal)
+] @constant
+
+[
+  "abstract"
+  "case"
+  "catch"
+  "class"
+  "def"
+  "do"
+  "else"
+  "extends"
+  "final"
+  "finally"
+  "for"
+  "if"
+  "implicit"
+  "import"
+  "lazy"
+  "match"
+  "new"
+  "object"
+  "override"
+  "package"
+  "private"
+  "protected"
+  "return"
+  "sealed"
+  "throw"
+  "trait"
+  "try"
+  "type"
+  "val"
+  "var"
+  "while"
+  "with"
+  "yield"
+] @keyword
+
+[
+  "=>"
+  "<-"
+  "="
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ","
+  ":"
+] @punctuation.delimiter
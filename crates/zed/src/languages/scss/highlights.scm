@@ -0,0 +1,114 @@
+(comment) @comment
+
+[
+  (tag_name)
+  (nesting_selector)
+  (universal_selector)
+] @tag
+
+[
+  "~"
+  ">"
+  "+"
+  "-"
+  "*"
+  "/"
+  "="
+  "^="
+  "|="
+  "~="
+  "$="
+  "*="
+  "and"
+  "or"
+  "not"
+  "only"
+] @operator
+
+(attribute_selector (plain_value) @string)
+
+(attribute_name) @attribute
+(pseudo_element_selector (tag_name) @attribute)
+(pseudo_class_selector (class_name) @attribute)
+
+[
+  (class_name)
+  (id_name)
+  (namespace_name)
+  (property_name)
+  (feature_name)
+] @property
+
+(function_name) @function
+
+(
+  [
+    (property_name)
+    (plain_value)
+  ] @variable.special
+  (#match? @variable.special "^--")
+)
+
+[
+  "@media"
+  "@import"
+  "@charset"
+  "@namespace"
+  "@supports"
+  "@keyframes"
+  (at_keyword)
+  (to)
+  (from)
+  (important)
+]  @keyword
+
+(string_value) @string
+(color_value) @string.special
+
+[
+  (integer_value)
+  (float_value)
+] @number
+
+(unit) @type
+
+[
+  ","
+  ":"
+] @punctuation.delimiter
+
+(single_line_comment) @comment
+
+(variable_name) @variable
+
+(mixin_statement
+  name: (identifier) @function)
+
+(function_statement
+  name: (identifier) @function)
+
+(include_statement
+  (identifier) @function)
+
+(mixin_statement
+  parameters: (parameters (parameter) @parameter))
+
+(function_statement
+  parameters: (parameters (parameter) @parameter))
+
+[
+  "@at-root"
+  "@debug"
+  "@each"
+  "@error"
+  "@extend"
+  "@for"
+  "@forward"
+  "@function"
+  "@include"
+  "@mixin"
+  "@return"
+  "@use"
+  "@warn"
+  "@while"
+] @keyword
@@ -0,0 +1,596 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::{HashMap, HashSet};
+use editor::Editor;
+use futures::StreamExt;
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Global, Render,
+    Task, View, ViewContext, WeakView,
+};
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use node_runtime::NodeRuntime;
+use picker::{Picker, PickerDelegate};
+use schemars::JsonSchema;
+use secrets::{read_secret, secret_entries, secrets_loaded, write_secret, SecretScope};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use settings::{Settings, SettingsStore};
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::{async_maybe, ResultExt};
+use workspace::{ModalView, Workspace};
+
+const SERVER_PATH: &'static str = "node_modules/sql-language-server/dist/bin/cli.js";
+
+fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
+    vec![
+        server_path.into(),
+        "up".into(),
+        "--method".into(),
+        "stdio".into(),
+    ]
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SqlSettings {
+    pub connections: Vec<SqlConnection>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct SqlSettingsContent {
+    /// The databases that sql-language-server reads the schema of, so that completions
+    /// include their tables and columns. The first connection that applies to the
+    /// project is used.
+    ///
+    /// Default: []
+    connections: Option<Vec<SqlConnection>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SqlConnection {
+    pub name: String,
+    /// The database engine, which also determines the SQL dialect.
+    pub dialect: SqlDialect,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    /// The password, stored in plain text. Prefer storing it in the keychain with
+    /// the `sql: set connection password` command, which takes precedence.
+    pub password: Option<String>,
+    pub database: Option<String>,
+    /// The database file of a SQLite connection.
+    pub filename: Option<String>,
+    /// The projects that the connection applies to. Defaults to every project.
+    pub project_paths: Option<Vec<String>>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDialect {
+    Mysql,
+    Postgres,
+    Sqlite3,
+    Bigquery,
+}
+
+impl Settings for SqlSettings {
+    const KEY: Option<&'static str> = Some("sql");
+
+    type FileContent = SqlSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+actions!(sql, [SetConnectionPassword]);
+
+/// The passwords of the connections that were read from the keychain, by their
+/// keychain url. They're sent to sql-language-server with the connections.
+#[derive(Default)]
+struct SqlPasswords {
+    passwords: HashMap<String, String>,
+    loading: HashSet<String>,
+}
+
+impl Global for SqlPasswords {}
+
+/// The keychain url that a connection's password is stored under.
+fn password_url(connection: &SqlConnection) -> String {
+    format!("sql://{}", connection.name)
+}
+
+pub(super) fn init(cx: &mut AppContext) {
+    cx.set_global(SqlPasswords::default());
+    // Which connections have a stored password is only known once the secrets are loaded.
+    let secrets_loaded = secrets_loaded(cx);
+    cx.spawn(|cx| async move {
+        secrets_loaded.await;
+        cx.update(load_passwords).ok();
+    })
+    .detach();
+    cx.observe_global::<SettingsStore>(load_passwords).detach();
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &SetConnectionPassword, cx| {
+            workspace.toggle_modal(cx, SqlPasswordPrompt::new);
+        });
+    })
+    .detach();
+}
+
+/// Reads the passwords of the configured connections that have one in the keychain.
+fn load_passwords(cx: &mut AppContext) {
+    // Avoid touching the keychain, which can prompt for access, unless a password was stored.
+    let stored_urls = secret_entries(cx)
+        .into_iter()
+        .filter(|entry| entry.scope == SecretScope::Database)
+        .map(|entry| entry.url)
+        .collect::<HashSet<_>>();
+    let urls_to_load = SqlSettings::get_global(cx)
+        .connections
+        .iter()
+        .map(password_url)
+        .filter(|url| {
+            let passwords = cx.global::<SqlPasswords>();
+            stored_urls.contains(url)
+                && !passwords.passwords.contains_key(url)
+                && !passwords.loading.contains(url)
+        })
+        .collect::<Vec<_>>();
+
+    for url in urls_to_load {
+        cx.global_mut::<SqlPasswords>().loading.insert(url.clone());
+        let password = read_secret(SecretScope::Database, &url, cx);
+        cx.spawn(|cx| async move {
+            let password = password.await;
+            cx.update(|cx| {
+                let passwords = cx.global_mut::<SqlPasswords>();
+                passwords.loading.remove(&url);
+                if let Some((_, password)) = password? {
+                    passwords
+                        .passwords
+                        .insert(url, String::from_utf8(password)?);
+                    refresh_server_configuration(cx);
+                }
+                anyhow::Ok(())
+            })?
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// Sends sql-language-server its workspace configuration again in every project, which
+/// is how the passwords reach it.
+fn refresh_server_configuration(cx: &mut AppContext) {
+    // Deferred, as the window that this is called from can't be updated until it returns.
+    cx.defer(|cx| {
+        let server_name = LanguageServerName("sql-language-server".into());
+        for window in cx.windows() {
+            let Some(workspace) = window.downcast::<Workspace>() else {
+                continue;
+            };
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.project().update(cx, |project, cx| {
+                        project.refresh_workspace_configuration(&server_name, cx)
+                    })
+                })
+                .ok();
+        }
+    });
+}
+
+/// sql-language-server only applies connections whose `projectPaths` contain the
+/// workspace root, and calls the dialect of a connection its adapter.
+fn server_connections(
+    connections: &[SqlConnection],
+    passwords: &HashMap<String, String>,
+    workspace_root: &Path,
+) -> Vec<Value> {
+    connections
+        .iter()
+        .map(|connection| {
+            let project_paths = connection
+                .project_paths
+                .clone()
+                .unwrap_or_else(|| vec![workspace_root.to_string_lossy().into_owned()]);
+            let password = passwords
+                .get(&password_url(connection))
+                .or(connection.password.as_ref());
+            json!({
+                "name": connection.name,
+                "adapter": connection.dialect,
+                "host": connection.host,
+                "port": connection.port,
+                "user": connection.user,
+                "password": password,
+                "database": connection.database,
+                "filename": connection.filename,
+                "projectPaths": project_paths,
+            })
+        })
+        .collect()
+}
+
+pub struct SqlLspAdapter {
+    node: Arc<dyn NodeRuntime>,
+}
+
+impl SqlLspAdapter {
+    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
+        SqlLspAdapter { node }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for SqlLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("sql-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "sql"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(
+            self.node
+                .npm_package_latest_version("sql-language-server")
+                .await?,
+        ) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let server_path = container_dir.join(SERVER_PATH);
+
+        if fs::metadata(&server_path).await.is_err() {
+            self.node
+                .npm_install_packages(&container_dir, &[("sql-language-server", version.as_str())])
+                .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: self.node.binary_path().await?,
+            arguments: server_binary_arguments(&server_path),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node).await
+    }
+
+    fn workspace_configuration(&self, workspace_root: &Path, cx: &mut AppContext) -> Value {
+        let no_passwords = HashMap::default();
+        let passwords = cx
+            .try_global::<SqlPasswords>()
+            .map_or(&no_passwords, |passwords| &passwords.passwords);
+        let connections = &SqlSettings::get_global(cx).connections;
+        json!({
+            "sqlLanguageServer": {
+                "connections": server_connections(connections, passwords, workspace_root),
+            }
+        })
+    }
+}
+
+/// Prompts for the password of one of the configured connections, and stores it in
+/// the keychain. The connection is chosen first when there's more than one, and the
+/// password is then typed into a masked editor.
+pub struct SqlPasswordPrompt {
+    state: SqlPasswordPromptState,
+}
+
+enum SqlPasswordPromptState {
+    ChoosingConnection(View<Picker<SqlConnectionPickerDelegate>>),
+    EnteringPassword {
+        connection: SqlConnection,
+        password_editor: View<Editor>,
+    },
+}
+
+impl SqlPasswordPrompt {
+    fn new(cx: &mut ViewContext<Self>) -> Self {
+        let connections = SqlSettings::get_global(cx).connections.clone();
+        if let [connection] = connections.as_slice() {
+            return Self {
+                state: Self::password_state(connection.clone(), cx),
+            };
+        }
+
+        let delegate = SqlConnectionPickerDelegate {
+            prompt: cx.view().downgrade(),
+            matches: (0..connections.len()).collect(),
+            connections,
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self {
+            state: SqlPasswordPromptState::ChoosingConnection(picker),
+        }
+    }
+
+    fn password_state(
+        connection: SqlConnection,
+        cx: &mut ViewContext<Self>,
+    ) -> SqlPasswordPromptState {
+        let password_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_masked(true, cx);
+            editor.set_placeholder_text(format!("Password of {}", connection.name), cx);
+            editor
+        });
+        SqlPasswordPromptState::EnteringPassword {
+            connection,
+            password_editor,
+        }
+    }
+
+    fn enter_password(&mut self, connection: SqlConnection, cx: &mut ViewContext<Self>) {
+        self.state = Self::password_state(connection, cx);
+        cx.focus_self();
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let SqlPasswordPromptState::EnteringPassword {
+            connection,
+            password_editor,
+        } = &self.state
+        else {
+            return;
+        };
+        let password = password_editor.read(cx).text(cx);
+        if password.is_empty() {
+            return;
+        }
+
+        let url = password_url(connection);
+        let write = write_secret(
+            SecretScope::Database,
+            &url,
+            connection.user.as_deref().unwrap_or_default(),
+            password.as_bytes(),
+            cx,
+        );
+        cx.spawn(|_, mut cx| async move {
+            write.await?;
+            cx.update(|cx| {
+                cx.global_mut::<SqlPasswords>()
+                    .passwords
+                    .insert(url, password);
+                refresh_server_configuration(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for SqlPasswordPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        match &self.state {
+            SqlPasswordPromptState::ChoosingConnection(picker) => {
+                v_flex().w(rems(34.)).child(picker.clone())
+            }
+            SqlPasswordPromptState::EnteringPassword {
+                password_editor, ..
+            } => v_flex()
+                .key_context("SqlPasswordPrompt")
+                .on_action(cx.listener(Self::confirm))
+                .on_action(cx.listener(Self::cancel))
+                .elevation_2(cx)
+                .w(rems(34.))
+                .px_2()
+                .py_1()
+                .child(password_editor.clone()),
+        }
+    }
+}
+
+impl FocusableView for SqlPasswordPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        match &self.state {
+            SqlPasswordPromptState::ChoosingConnection(picker) => picker.focus_handle(cx),
+            SqlPasswordPromptState::EnteringPassword {
+                password_editor, ..
+            } => password_editor.focus_handle(cx),
+        }
+    }
+}
+
+impl EventEmitter<DismissEvent> for SqlPasswordPrompt {}
+impl ModalView for SqlPasswordPrompt {}
+
+pub struct SqlConnectionPickerDelegate {
+    prompt: WeakView<SqlPasswordPrompt>,
+    connections: Vec<SqlConnection>,
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for SqlConnectionPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Choose the connection to store the password of...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .connections
+            .iter()
+            .enumerate()
+            .filter(|(_, connection)| connection.name.to_lowercase().contains(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(connection) = self
+            .matches
+            .get(self.selected_index)
+            .map(|&ix| self.connections[ix].clone())
+        else {
+            return;
+        };
+        self.prompt
+            .update(cx, |prompt, cx| prompt.enter_password(connection, cx))
+            .log_err();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.prompt
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let connection = &self.connections[*self.matches.get(ix)?];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(Label::new(connection.name.clone()))
+                .end_slot(
+                    Label::new(connection.user.clone().unwrap_or_default())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+}
+
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    node: &dyn NodeRuntime,
+) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir() {
+                last_version_dir = Some(entry.path());
+            }
+        }
+        let last_version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let server_path = last_version_dir.join(SERVER_PATH);
+        if server_path.exists() {
+            Ok(LanguageServerBinary {
+                path: node.binary_path().await?,
+                arguments: server_binary_arguments(&server_path),
+            })
+        } else {
+            Err(anyhow!(
+                "missing executable in directory {:?}",
+                last_version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_connections() {
+        let connections = [
+            SqlConnection {
+                name: "app".into(),
+                dialect: SqlDialect::Postgres,
+                host: Some("localhost".into()),
+                port: Some(5432),
+                user: Some("postgres".into()),
+                password: None,
+                database: Some("app_development".into()),
+                filename: None,
+                project_paths: None,
+            },
+            SqlConnection {
+                name: "cache".into(),
+                dialect: SqlDialect::Sqlite3,
+                host: None,
+                port: None,
+                user: None,
+                password: None,
+                database: None,
+                filename: Some("/tmp/cache.db".into()),
+                project_paths: Some(vec!["/code/cache".into()]),
+            },
+        ];
+        let passwords = HashMap::from_iter([(password_url(&connections[0]), "secret".into())]);
+        let server_connections =
+            server_connections(&connections, &passwords, Path::new("/code/app"));
+        assert_eq!(server_connections[0]["adapter"], "postgres");
+        assert_eq!(server_connections[0]["password"], "secret");
+        assert_eq!(server_connections[1]["password"], Value::Null);
+        assert_eq!(server_connections[0]["projectPaths"], json!(["/code/app"]));
+        assert_eq!(server_connections[1]["adapter"], "sqlite3");
+        assert_eq!(
+            server_connections[1]["projectPaths"],
+            json!(["/code/cache"])
+        );
+    }
+}
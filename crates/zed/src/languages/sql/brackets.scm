@@ -0,0 +1 @@
+("(" @open ")" @close)
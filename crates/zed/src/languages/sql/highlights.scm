@@ -0,0 +1,90 @@
+[
+  (comment)
+  (marginalia)
+] @comment
+
+(literal) @string
+
+(invocation
+  (object_reference
+    name: (identifier) @function))
+
+(object_reference
+  name: (identifier) @type)
+
+(field
+  name: (identifier) @property)
+
+(relation
+  alias: (identifier) @variable)
+
+[
+  (keyword_true)
+  (keyword_false)
+] @boolean
+
+(keyword_null) @constant
+
+[
+  (keyword_select)
+  (keyword_from)
+  (keyword_where)
+  (keyword_insert)
+  (keyword_into)
+  (keyword_values)
+  (keyword_update)
+  (keyword_set)
+  (keyword_delete)
+  (keyword_create)
+  (keyword_alter)
+  (keyword_drop)
+  (keyword_table)
+  (keyword_view)
+  (keyword_index)
+  (keyword_join)
+  (keyword_left)
+  (keyword_right)
+  (keyword_inner)
+  (keyword_outer)
+  (keyword_on)
+  (keyword_as)
+  (keyword_and)
+  (keyword_or)
+  (keyword_not)
+  (keyword_in)
+  (keyword_is)
+  (keyword_like)
+  (keyword_between)
+  (keyword_order)
+  (keyword_group)
+  (keyword_by)
+  (keyword_having)
+  (keyword_limit)
+  (keyword_offset)
+  (keyword_distinct)
+  (keyword_union)
+  (keyword_all)
+  (keyword_case)
+  (keyword_when)
+  (keyword_then)
+  (keyword_else)
+  (keyword_end)
+  (keyword_with)
+  (keyword_primary)
+  (keyword_key)
+  (keyword_references)
+  (keyword_default)
+  (keyword_asc)
+  (keyword_desc)
+] @keyword
+
+[
+  "("
+  ")"
+] @punctuation.bracket
+
+[
+  ","
+  "."
+  ";"
+] @punctuation.delimiter
@@ -0,0 +1,134 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use gpui::AppContext;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::Settings;
+use smol::process::Command;
+use std::{any::Any, path::PathBuf};
+
+const SERVER_NAME: &str = "sourcekit-lsp";
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SwiftSettings {
+    pub toolchain_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct SwiftSettingsContent {
+    /// The path to the Swift toolchain whose `sourcekit-lsp` is used, such as
+    /// `/Library/Developer/Toolchains/swift-latest.xctoolchain`. When null, the
+    /// toolchain selected with `xcode-select` is used on macOS, falling back to
+    /// `sourcekit-lsp` on the `PATH`.
+    ///
+    /// Default: null
+    toolchain_path: Option<PathBuf>,
+}
+
+impl Settings for SwiftSettings {
+    const KEY: Option<&'static str> = Some("swift");
+
+    type FileContent = SwiftSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// `sourcekit-lsp` ships with every Swift toolchain, so it is never downloaded.
+pub struct SourceKitLspAdapter {
+    toolchain_path: Option<PathBuf>,
+}
+
+impl SourceKitLspAdapter {
+    pub fn new(toolchain_path: Option<PathBuf>) -> Self {
+        SourceKitLspAdapter { toolchain_path }
+    }
+
+    async fn server_path(&self) -> Option<PathBuf> {
+        if let Some(toolchain_path) = &self.toolchain_path {
+            let path = toolchain_path.join("usr").join("bin").join(SERVER_NAME);
+            return smol::fs::metadata(&path).await.is_ok().then_some(path);
+        }
+
+        if cfg!(target_os = "macos") {
+            if let Some(path) = xcrun_find(SERVER_NAME).await {
+                return Some(path);
+            }
+        }
+
+        Some(PathBuf::from(SERVER_NAME))
+    }
+}
+
+#[async_trait]
+impl LspAdapter for SourceKitLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName(SERVER_NAME.into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "swift"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(()))
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _version: Box<dyn 'static + Send + Any>,
+        _container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        Err(anyhow!(
+            "sourcekit-lsp must be installed with Xcode or a Swift toolchain"
+        ))
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        Some(LanguageServerBinary {
+            path: self.server_path().await?,
+            arguments: vec![],
+        })
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+}
+
+/// Asks `xcrun` for a tool in the active Xcode toolchain.
+async fn xcrun_find(tool: &str) -> Option<PathBuf> {
+    let output = Command::new("xcrun")
+        .args(["--find", tool])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = String::from_utf8(output.stdout).ok()?;
+    let path = path.trim();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
@@ -0,0 +1,144 @@
+(simple_identifier) @variable
+
+(type_identifier) @type
+
+(function_declaration
+  name: (simple_identifier) @function)
+
+(protocol_function_declaration
+  name: (simple_identifier) @function)
+
+(call_expression
+  (simple_identifier) @function)
+
+(call_expression
+  (navigation_expression
+    suffix: (navigation_suffix
+      suffix: (simple_identifier) @function)))
+
+(navigation_suffix
+  suffix: (simple_identifier) @property)
+
+(parameter
+  external_name: (simple_identifier) @property)
+
+(enum_entry
+  name: (simple_identifier) @constant)
+
+(attribute) @attribute
+
+[
+  (comment)
+  (multiline_comment)
+] @comment
+
+[
+  (line_string_literal)
+  (multi_line_string_literal)
+  (raw_string_literal)
+] @string
+
+(regex_literal) @string.regex
+
+[
+  (integer_literal)
+  (hex_literal)
+  (oct_literal)
+  (bin_literal)
+  (real_literal)
+] @number
+
+(boolean_literal) @boolean
+"nil" @constant.builtin
+
+(self_expression) @variable.special
+(super_expression) @variable.special
+
+[
+  (visibility_modifier)
+  (member_modifier)
+  (function_modifier)
+  (property_modifier)
+  (parameter_modifier)
+  (inheritance_modifier)
+  (mutation_modifier)
+  (ownership_modifier)
+] @keyword
+
+[
+  "actor"
+  "as"
+  "async"
+  "await"
+  "break"
+  "case"
+  "catch"
+  "class"
+  "continue"
+  "default"
+  "defer"
+  "do"
+  "else"
+  "enum"
+  "extension"
+  "fallthrough"
+  "for"
+  "func"
+  "guard"
+  "if"
+  "import"
+  "in"
+  "init"
+  "is"
+  "let"
+  "protocol"
+  "repeat"
+  "return"
+  "struct"
+  "switch"
+  "throw"
+  "throws"
+  "try"
+  "typealias"
+  "var"
+  "where"
+  "while"
+] @keyword
+
+[
+  "="
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "<"
+  ">"
+  "<="
+  ">="
+  "=="
+  "!="
+  "&&"
+  "||"
+  "!"
+  "?"
+  "->"
+  "..."
+  "..<"
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ","
+  ":"
+  ";"
+] @punctuation.delimiter
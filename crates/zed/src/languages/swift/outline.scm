@@ -0,0 +1,31 @@
+(class_declaration
+  declaration_kind: _ @context
+  name: (_) @name) @item
+
+(protocol_declaration
+  "protocol" @context
+  name: (_) @name) @item
+
+(function_declaration
+  "func" @context
+  name: (_) @name) @item
+
+(init_declaration
+  "init" @name) @item
+
+(protocol_function_declaration
+  "func" @context
+  name: (_) @name) @item
+
+(property_declaration
+  (value_binding_pattern) @context
+  name: (pattern
+    bound_identifier: (simple_identifier) @name)) @item
+
+(typealias_declaration
+  "typealias" @context
+  name: (_) @name) @item
+
+(enum_entry
+  "case" @context
+  name: (_) @name) @item
@@ -116,9 +116,12 @@ impl LspAdapter for TailwindLspAdapter {
         HashMap::from_iter([
             ("HTML".to_string(), "html".to_string()),
             ("CSS".to_string(), "css".to_string()),
+            ("SCSS".to_string(), "scss".to_string()),
+            ("Less".to_string(), "less".to_string()),
             ("JavaScript".to_string(), "javascript".to_string()),
             ("TSX".to_string(), "typescriptreact".to_string()),
             ("Svelte".to_string(), "svelte".to_string()),
+            ("Astro".to_string(), "astro".to_string()),
             ("Elixir".to_string(), "phoenix-heex".to_string()),
             ("HEEX".to_string(), "phoenix-heex".to_string()),
             ("ERB".to_string(), "erb".to_string()),
@@ -0,0 +1,201 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use language::{CodeLabel, Language, LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::{CompletionItemKind, LanguageServerBinary};
+use rope::Rope;
+use smol::fs;
+use std::{
+    any::Any,
+    env::consts::{ARCH, OS},
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{
+    archive::{download_archive_atomically, ArchiveType},
+    async_maybe,
+    fs::matching_by_recency,
+    github::{latest_github_release, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+const SERVER_NAME: &str = "terraform-ls";
+
+/// terraform-ls is published on HashiCorp's releases site rather than as GitHub release
+/// assets, so only the version is looked up on GitHub.
+pub struct TerraformLspAdapter;
+
+#[async_trait]
+impl LspAdapter for TerraformLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName(SERVER_NAME.into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "terraform"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = latest_github_release(
+            "hashicorp/terraform-ls",
+            false,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let version = release.tag_name.trim_start_matches('v').to_string();
+        let url = build_download_url(&version)?;
+        Ok(Box::new(GitHubLspBinaryVersion {
+            name: version,
+            url,
+            sha256: None,
+        }) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("terraform-ls_{}", version.name));
+        let binary_path = version_dir.join(SERVER_NAME);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_archive_atomically(
+                delegate.http_client().as_ref(),
+                &version.url,
+                ArchiveType::Zip,
+                version.sha256.as_deref(),
+                &version_dir,
+            )
+            .await
+            .context("error downloading release")?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_terraform_ls_version(entry)
+    }
+
+    /// terraform-ls completes blocks such as `resource` and `provider` as classes, their
+    /// attributes as properties, and block labels such as resource types as fields.
+    async fn label_for_completion(
+        &self,
+        completion: &lsp::CompletionItem,
+        language: &Arc<Language>,
+    ) -> Option<CodeLabel> {
+        let label = completion.label.as_str();
+        let (prefix, suffix) = match completion.kind? {
+            CompletionItemKind::CLASS => ("", " {}"),
+            CompletionItemKind::PROPERTY => ("", " = null"),
+            CompletionItemKind::FIELD => ("resource \"", "\" {}"),
+            _ => return None,
+        };
+        let source = Rope::from(format!("{prefix}{label}{suffix}").as_str());
+        let runs = language.highlight_text(&source, prefix.len()..prefix.len() + label.len());
+
+        // The detail is the provider of a resource type, or the type of an attribute.
+        let mut text = label.to_string();
+        if let Some(detail) = completion.detail.as_deref().filter(|d| !d.is_empty()) {
+            text.push(' ');
+            text.push_str(detail);
+        }
+        Some(CodeLabel {
+            text,
+            runs,
+            filter_range: 0..label.len(),
+        })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("Terraform".into(), "terraform".into()),
+            ("Terraform Vars".into(), "terraform-vars".into()),
+        ])
+    }
+}
+
+fn build_download_url(version: &str) -> Result<String> {
+    let os = match OS {
+        "macos" => "darwin",
+        "linux" => "linux",
+        "windows" => "windows",
+        other => bail!("Running on unsupported os: {other}"),
+    };
+    let arch = match ARCH {
+        "x86" => "386",
+        "x86_64" => "amd64",
+        "arm" => "arm",
+        "aarch64" => "arm64",
+        other => bail!("Running on unsupported architecture: {other}"),
+    };
+    Ok(format!(
+        "https://releases.hashicorp.com/terraform-ls/{version}/terraform-ls_{version}_{os}_{arch}.zip"
+    ))
+}
+
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["serve".into()]
+}
+
+fn is_terraform_ls_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        let name = name.to_string_lossy();
+        name.starts_with("terraform-ls_") && !name.ends_with(".zip")
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let version_dir = matching_by_recency(&container_dir, is_terraform_ls_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join(SERVER_NAME);
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                path: binary_path,
+                arguments: server_binary_arguments(),
+            })
+        } else {
+            Err(anyhow!(
+                "missing terraform-ls binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
@@ -1,15 +1,27 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
+use futures::io::BufReader;
+use gpui::AppContext;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
 use smol::fs::{self, File};
-use std::{any::Any, path::PathBuf};
+use std::{
+    any::Any,
+    env::consts::{ARCH, OS},
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 use util::async_maybe;
-use util::github::latest_github_release;
+use util::fs::matching_by_recency;
+use util::github::{download_release, latest_github_release};
 use util::{github::GitHubLspBinaryVersion, ResultExt};
 
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["lsp".into(), "stdio".into()]
+}
+
 pub struct TaploLspAdapter;
 
 #[async_trait]
@@ -28,7 +40,12 @@ impl LspAdapter for TaploLspAdapter {
     ) -> Result<Box<dyn 'static + Send + Any>> {
         let release =
             latest_github_release("tamasfe/taplo", true, false, delegate.http_client()).await?;
-        let asset_name = format!("taplo-full-darwin-{arch}.gz", arch = std::env::consts::ARCH);
+        let os = match OS {
+            "macos" => "darwin",
+            "linux" => "linux",
+            other => bail!("Running on unsupported os: {other}"),
+        };
+        let asset_name = format!("taplo-full-{os}-{ARCH}.gz");
 
         let asset = release
             .assets
@@ -36,10 +53,10 @@ impl LspAdapter for TaploLspAdapter {
             .find(|asset| asset.name == asset_name)
             .context(format!("no asset found matching {asset_name:?}"))?;
 
-        Ok(Box::new(GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url.clone(),
-        }))
+        Ok(Box::new(GitHubLspBinaryVersion::new(
+            release.tag_name,
+            asset,
+        )))
     }
 
     async fn fetch_server_binary(
@@ -49,14 +66,18 @@ impl LspAdapter for TaploLspAdapter {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let binary_path = container_dir.join("taplo");
+        let binary_path = container_dir.join(format!("taplo_{}", version.name));
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
+            let mut response = download_release(&*delegate.http_client(), &version)
                 .await
                 .context("error downloading release")?;
+            if !response.status().is_success() {
+                Err(anyhow!(
+                    "download failed with status {}",
+                    response.status().to_string()
+                ))?;
+            }
 
             let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
             let mut file = File::create(&binary_path).await?;
@@ -72,7 +93,7 @@ impl LspAdapter for TaploLspAdapter {
 
         Ok(LanguageServerBinary {
             path: binary_path,
-            arguments: vec!["lsp".into(), "stdio".into()],
+            arguments: server_binary_arguments(),
         })
     }
 
@@ -95,19 +116,46 @@ impl LspAdapter for TaploLspAdapter {
                 binary
             })
     }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_taplo_version(entry)
+    }
+
+    /// Taplo reads its settings from the `evenBetterToml` section, named after its VS Code
+    /// extension. Schemas are looked up in the schemastore.org catalog by file name, which
+    /// covers `Cargo.toml`, `pyproject.toml` and others, and the `Cargo.toml` schema is
+    /// associated explicitly so that it's used even when the catalog can't be fetched.
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        json!({
+            "evenBetterToml": {
+                "schema": {
+                    "enabled": true,
+                    "catalogs": ["https://www.schemastore.org/api/json/catalog.json"],
+                    "associations": {
+                        "(^|/)Cargo\\.toml$": "https://json.schemastore.org/cargo.json",
+                    },
+                },
+            }
+        })
+    }
+}
+
+fn is_taplo_version(entry: &Path) -> bool {
+    entry
+        .file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with("taplo_"))
 }
 
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            last = Some(entry?.path());
-        }
+        let latest = matching_by_recency(&container_dir, is_taplo_version)
+            .await
+            .into_iter()
+            .next();
 
         anyhow::Ok(LanguageServerBinary {
-            path: last.context("no cached binary")?,
-            arguments: Default::default(),
+            path: latest.context("no cached binary")?,
+            arguments: server_binary_arguments(),
         })
     })
     .await
@@ -0,0 +1,34 @@
+[
+    (function_declaration)
+    (generator_function_declaration)
+    (function)
+    (arrow_function)
+    (method_definition)
+] @item
+
+(_
+    parameters: (formal_parameters
+        [
+            (required_parameter
+                pattern: (identifier) @param)
+            (optional_parameter
+                pattern: (identifier) @param)
+        ])) @item
+
+(arrow_function
+    parameter: (identifier) @param) @item
+
+(_
+    return_type: (type_annotation (_) @returns)
+    (#not-eq? @returns "void")) @item
+
+([
+    (function_declaration
+        body: (statement_block (return_statement (_))))
+    (function
+        body: (statement_block (return_statement (_))))
+    (arrow_function
+        body: (statement_block (return_statement (_))))
+    (method_definition
+        body: (statement_block (return_statement (_))))
+] @item @returns)
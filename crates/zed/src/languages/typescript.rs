@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
@@ -8,6 +8,7 @@ use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::{CodeActionKind, LanguageServerBinary};
 use node_runtime::NodeRuntime;
 use serde_json::{json, Value};
+use settings::parse_json_with_comments;
 use smol::{fs, io::BufReader, stream::StreamExt};
 use std::{
     any::Any,
@@ -17,8 +18,8 @@ use std::{
 };
 use util::{
     async_maybe,
-    fs::remove_matching,
-    github::{latest_github_release, GitHubLspBinaryVersion},
+    fs::{matching_by_recency, remove_matching},
+    github::{download_release, latest_github_release, GitHubLspBinaryVersion},
     ResultExt,
 };
 
@@ -43,6 +44,31 @@ impl TypeScriptLspAdapter {
     }
 }
 
+const APPLY_REFACTORING_COMMAND: &str = "_typescript.applyRefactoring";
+const MOVE_TO_FILE_ACTION: &str = "Move to file";
+/// Forwards a request to tsserver, with its command and arguments.
+const TSSERVER_REQUEST_COMMAND: &str = "typescript.tsserverRequest";
+
+/// The arguments of tsserver's `getEditsForRefactor` request, which typescript-language-server
+/// passes along when a refactor's command is executed.
+fn refactoring_arguments(action: &lsp::CodeAction) -> Option<&serde_json::Map<String, Value>> {
+    let command = action.command.as_ref()?;
+    if command.command != APPLY_REFACTORING_COMMAND {
+        return None;
+    }
+    command.arguments.as_ref()?.first()?.as_object()
+}
+
+fn refactoring_arguments_mut(
+    action: &mut lsp::CodeAction,
+) -> Option<&mut serde_json::Map<String, Value>> {
+    let command = action.command.as_mut()?;
+    if command.command != APPLY_REFACTORING_COMMAND {
+        return None;
+    }
+    command.arguments.as_mut()?.first_mut()?.as_object_mut()
+}
+
 struct TypeScriptVersions {
     typescript_version: String,
     server_version: String,
@@ -116,15 +142,45 @@ impl LspAdapter for TypeScriptLspAdapter {
         get_cached_ts_server_binary(container_dir, &*self.node).await
     }
 
+    async fn check_server_binary(&self, binary: &LanguageServerBinary) -> Result<()> {
+        check_node_server_script(binary).await
+    }
+
     fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
         Some(vec![
             CodeActionKind::QUICKFIX,
             CodeActionKind::REFACTOR,
             CodeActionKind::REFACTOR_EXTRACT,
+            CodeActionKind::new("refactor.move"),
             CodeActionKind::SOURCE,
         ])
     }
 
+    /// "Move to file" moves the selected declarations to a file chosen by the user, which
+    /// tsserver expects as an interactive argument of the refactor.
+    fn code_action_requires_destination(&self, action: &lsp::CodeAction) -> bool {
+        refactoring_arguments(action).map_or(false, |arguments| {
+            arguments.get("action").and_then(Value::as_str) == Some(MOVE_TO_FILE_ACTION)
+        })
+    }
+
+    fn code_action_destination(&self, action: &lsp::CodeAction) -> Option<PathBuf> {
+        refactoring_arguments(action)?
+            .get("interactiveRefactorArguments")?
+            .get("targetFile")?
+            .as_str()
+            .map(PathBuf::from)
+    }
+
+    fn set_code_action_destination(&self, action: &mut lsp::CodeAction, destination: &Path) {
+        if let Some(arguments) = refactoring_arguments_mut(action) {
+            arguments.insert(
+                "interactiveRefactorArguments".into(),
+                json!({ "targetFile": destination }),
+            );
+        }
+    }
+
     async fn label_for_completion(
         &self,
         item: &lsp::CompletionItem,
@@ -163,6 +219,50 @@ impl LspAdapter for TypeScriptLspAdapter {
         }))
     }
 
+    /// Loads the project's tsserver plugins from its own `node_modules`, since tsserver
+    /// only looks for them next to the TypeScript that Zed installed.
+    async fn root_specific_initialization_options(&self, root_path: &Path) -> Option<Value> {
+        let plugins = workspace_typescript_plugins(root_path).await;
+        if plugins.is_empty() {
+            return None;
+        }
+        Some(json!({
+            "plugins": plugins
+                .iter()
+                .map(|name| json!({ "name": name, "location": root_path }))
+                .collect::<Vec<_>>(),
+        }))
+    }
+
+    /// Sends tsserver's `geterrForProject` request once for each project with open files,
+    /// whose diagnostics the server publishes as they're computed. The request is marked
+    /// as async, so that typescript-language-server answers it once tsserver is done.
+    async fn refresh_diagnostics_commands(&self, open_paths: Vec<PathBuf>) -> Vec<lsp::Command> {
+        let mut project_files = Vec::<(PathBuf, PathBuf)>::new();
+        for path in open_paths {
+            let project_dir = typescript_project_dir(&path).await;
+            if project_files.iter().all(|(dir, _)| *dir != project_dir) {
+                project_files.push((project_dir, path));
+            }
+        }
+        project_files
+            .into_iter()
+            .map(|(_, path)| lsp::Command {
+                title: "Refresh project diagnostics".into(),
+                command: TSSERVER_REQUEST_COMMAND.into(),
+                arguments: Some(vec![
+                    json!("geterrForProject"),
+                    json!({ "file": path, "delay": 0 }),
+                    json!({ "isAsync": true }),
+                ]),
+            })
+            .collect()
+    }
+
+    fn supports_workspace_folders(&self) -> bool {
+        true
+    }
+
     fn language_ids(&self) -> HashMap<String, String> {
         HashMap::from_iter([
             ("TypeScript".into(), "typescript".into()),
@@ -172,6 +272,55 @@ impl LspAdapter for TypeScriptLspAdapter {
     }
 }
 
+/// Returns the names of the plugins in the `compilerOptions` of the project's
+/// `tsconfig.json` or `jsconfig.json` whose packages are installed in its `node_modules`.
+async fn workspace_typescript_plugins(root_path: &Path) -> Vec<String> {
+    let mut plugins = Vec::<String>::new();
+    for config_name in ["tsconfig.json", "jsconfig.json"] {
+        let Ok(content) = fs::read_to_string(root_path.join(config_name)).await else {
+            continue;
+        };
+        let Some(config) = parse_json_with_comments::<Value>(&content).log_err() else {
+            continue;
+        };
+        let names = config
+            .pointer("/compilerOptions/plugins")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|plugin| plugin.get("name")?.as_str());
+        for name in names {
+            if plugins.iter().any(|plugin| plugin == name) {
+                continue;
+            }
+            let package_json = root_path
+                .join("node_modules")
+                .join(name)
+                .join("package.json");
+            if fs::metadata(&package_json).await.is_ok() {
+                plugins.push(name.to_string());
+            } else {
+                log::warn!("tsserver plugin {name} is not installed in {root_path:?}");
+            }
+        }
+    }
+    plugins
+}
+
+/// Returns the closest directory above the file with a `tsconfig.json` or `jsconfig.json`,
+/// which is the root of the tsserver project that the file belongs to. Files outside of
+/// any configured project are grouped by their own directory.
+async fn typescript_project_dir(path: &Path) -> PathBuf {
+    for dir in path.ancestors().skip(1) {
+        for config_name in ["tsconfig.json", "jsconfig.json"] {
+            if fs::metadata(dir.join(config_name)).await.is_ok() {
+                return dir.to_path_buf();
+            }
+        }
+    }
+    path.parent().unwrap_or(path).to_path_buf()
+}
+
 async fn get_cached_ts_server_binary(
     container_dir: PathBuf,
     node: &dyn NodeRuntime,
@@ -216,18 +365,16 @@ impl EsLintLspAdapter {
 impl LspAdapter for EsLintLspAdapter {
     fn workspace_configuration(&self, workspace_root: &Path, _: &mut AppContext) -> Value {
         json!({
-            "": {
-                "validate": "on",
-                "rulesCustomizations": [],
-                "run": "onType",
-                "nodePath": null,
-                "workingDirectory": {"mode": "auto"},
-                "workspaceFolder": {
-                    "uri": workspace_root,
-                    "name": workspace_root.file_name()
-                        .unwrap_or_else(|| workspace_root.as_os_str()),
-                },
-            }
+            "validate": "on",
+            "rulesCustomizations": [],
+            "run": "onType",
+            "nodePath": null,
+            "workingDirectory": {"mode": "auto"},
+            "workspaceFolder": {
+                "uri": workspace_root,
+                "name": workspace_root.file_name()
+                    .unwrap_or_else(|| workspace_root.as_os_str()),
+            },
         })
     }
 
@@ -256,6 +403,7 @@ impl LspAdapter for EsLintLspAdapter {
         Ok(Box::new(GitHubLspBinaryVersion {
             name: release.tag_name,
             url: release.tarball_url,
+            sha256: None,
         }))
     }
 
@@ -270,11 +418,10 @@ impl LspAdapter for EsLintLspAdapter {
         let server_path = destination_path.join(Self::SERVER_PATH);
 
         if fs::metadata(&server_path).await.is_err() {
-            remove_matching(&container_dir, |entry| entry != destination_path).await;
+            // Removes what's left of a previous attempt to install this version.
+            remove_matching(&container_dir, |entry| entry == destination_path).await;
 
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
+            let mut response = download_release(&*delegate.http_client(), &version)
                 .await
                 .map_err(|err| anyhow!("error downloading release: {}", err))?;
             let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
@@ -327,6 +474,54 @@ impl LspAdapter for EsLintLspAdapter {
     fn initialization_options(&self) -> Option<serde_json::Value> {
         None
     }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_eslint_version(entry)
+    }
+
+    async fn check_server_binary(&self, binary: &LanguageServerBinary) -> Result<()> {
+        check_node_server_script(binary).await?;
+        // The compiled server loads its dependencies from the server's own packages.
+        let server_path = Path::new(&binary.arguments[0]);
+        let packages_dir = server_path
+            .parent()
+            .and_then(Path::parent)
+            .map(|server_dir| server_dir.join("node_modules"))
+            .ok_or_else(|| anyhow!("invalid server path {server_path:?}"))?;
+        fs::metadata(&packages_dir)
+            .await
+            .with_context(|| format!("missing server dependencies {packages_dir:?}"))?;
+        Ok(())
+    }
+}
+
+/// Checks that the script of a server that runs on Node, which is its first argument,
+/// exists and parses, which it doesn't when its installation was cut short.
+async fn check_node_server_script(binary: &LanguageServerBinary) -> Result<()> {
+    let script = binary
+        .arguments
+        .first()
+        .ok_or_else(|| anyhow!("missing server script"))?;
+    let output = smol::process::Command::new(&binary.path)
+        .arg("--check")
+        .arg(script)
+        .output()
+        .await
+        .context("failed to run node")?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "server script {script:?} is invalid: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
+fn is_eslint_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("vscode-eslint-")
+    })
 }
 
 async fn get_cached_eslint_server_binary(
@@ -334,13 +529,17 @@ async fn get_cached_eslint_server_binary(
     node: &dyn NodeRuntime,
 ) -> Option<LanguageServerBinary> {
     async_maybe!({
-        // This is unfortunate but we don't know what the version is to build a path directly
-        let mut dir = fs::read_dir(&container_dir).await?;
-        let first = dir.next().await.ok_or(anyhow!("missing first file"))??;
-        if !first.file_type().await?.is_dir() {
-            return Err(anyhow!("First entry is not a directory"));
+        // This is unfortunate but we don't know what the version is to build a path directly.
+        // Versions whose installation failed before the server was compiled are skipped.
+        let mut server_path = None;
+        for version_path in matching_by_recency(&container_dir, is_eslint_version).await {
+            let path = version_path.join(EsLintLspAdapter::SERVER_PATH);
+            if fs::metadata(&path).await.is_ok() {
+                server_path = Some(path);
+                break;
+            }
         }
-        let server_path = first.path().join(EsLintLspAdapter::SERVER_PATH);
+        let server_path = server_path.ok_or_else(|| anyhow!("no compiled cached version"))?;
 
         Ok(LanguageServerBinary {
             path: node.binary_path().await?,
@@ -353,7 +552,12 @@ async fn get_cached_eslint_server_binary(
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use gpui::{Context, TestAppContext};
+    use language::language_settings::AllLanguageSettings;
+    use node_runtime::FakeNodeRuntime;
+    use settings::SettingsStore;
+    use std::num::NonZeroU32;
     use text::BufferId;
     use unindent::Unindent;
 
@@ -401,4 +605,168 @@ mod tests {
             ]
         );
     }
+
+    #[gpui::test]
+    async fn test_autoindent(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let test_settings = SettingsStore::test(cx);
+            cx.set_global(test_settings);
+            language::init(cx);
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |s| {
+                    s.defaults.tab_size = NonZeroU32::new(2);
+                });
+            });
+        });
+        let language = crate::languages::language(
+            "typescript",
+            tree_sitter_typescript::language_typescript(),
+            None,
+        )
+        .await;
+
+        let text = r#"
+            function a(items: Item[]) {
+              const names = items
+                .filter((item) => {
+                  return item.enabled;
+                })
+                .map((item) => item.name);
+
+              switch (names.length) {
+                case 0:
+                  return null;
+                case 1:
+                case 2:
+                  return names[0];
+                default: {
+                  break;
+                }
+              }
+
+              return names.length > 2 &&
+                names[2];
+            }
+        "#
+        .unindent();
+
+        cx.new_model(|cx| {
+            let mut buffer =
+                language::Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), "")
+                    .with_language(language, cx);
+            buffer.assert_autoindents(&text, cx);
+            buffer
+        });
+    }
+
+    #[gpui::test]
+    async fn test_workspace_typescript_plugins() {
+        let root = tempfile::tempdir().unwrap();
+        let root_path = root.path();
+        std::fs::write(
+            root_path.join("tsconfig.json"),
+            r#"{
+                // Comments and trailing commas are allowed, as in tsc.
+                "compilerOptions": {
+                    "plugins": [
+                        { "name": "@styled/typescript-styled-plugin" },
+                        { "name": "@0no-co/graphqlsp", "schema": "./schema.graphql" },
+                    ],
+                },
+            }"#,
+        )
+        .unwrap();
+        let plugin_dir = root_path.join("node_modules/@styled/typescript-styled-plugin");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(plugin_dir.join("package.json"), "{}").unwrap();
+
+        // Plugins that aren't installed are left out, since tsserver would fail to load them.
+        assert_eq!(
+            workspace_typescript_plugins(root_path).await,
+            ["@styled/typescript-styled-plugin"]
+        );
+
+        let adapter = TypeScriptLspAdapter::new(FakeNodeRuntime::new());
+        assert_eq!(
+            adapter
+                .root_specific_initialization_options(root_path)
+                .await,
+            Some(json!({
+                "plugins": [{
+                    "name": "@styled/typescript-styled-plugin",
+                    "location": root_path,
+                }],
+            }))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_refresh_diagnostics_commands() {
+        let root = tempfile::tempdir().unwrap();
+        let root_path = root.path();
+        for dir in ["app/src", "lib/src", "scripts"] {
+            std::fs::create_dir_all(root_path.join(dir)).unwrap();
+        }
+        std::fs::write(root_path.join("app/tsconfig.json"), "{}").unwrap();
+        std::fs::write(root_path.join("lib/jsconfig.json"), "{}").unwrap();
+
+        let adapter = TypeScriptLspAdapter::new(FakeNodeRuntime::new());
+        let commands = adapter
+            .refresh_diagnostics_commands(vec![
+                root_path.join("app/src/a.ts"),
+                root_path.join("app/src/b.ts"),
+                root_path.join("app/index.ts"),
+                root_path.join("lib/src/c.js"),
+                root_path.join("scripts/d.ts"),
+            ])
+            .await;
+
+        // One request per project is enough, since tsserver checks every file of it.
+        assert_eq!(
+            commands
+                .iter()
+                .map(|command| {
+                    assert_eq!(command.command, TSSERVER_REQUEST_COMMAND);
+                    let arguments = command.arguments.as_ref().unwrap();
+                    assert_eq!(arguments[0], json!("geterrForProject"));
+                    assert_eq!(arguments[2], json!({ "isAsync": true }));
+                    arguments[1]["file"].clone()
+                })
+                .collect::<Vec<_>>(),
+            [
+                json!(root_path.join("app/src/a.ts")),
+                json!(root_path.join("lib/src/c.js")),
+                json!(root_path.join("scripts/d.ts")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_move_to_file_destination() {
+        let adapter = TypeScriptLspAdapter::new(FakeNodeRuntime::new());
+        let refactor = |action: &str| lsp::CodeAction {
+            title: action.into(),
+            command: Some(lsp::Command {
+                title: action.into(),
+                command: APPLY_REFACTORING_COMMAND.into(),
+                arguments: Some(vec![json!({
+                    "file": "/dir/a.ts",
+                    "refactor": action,
+                    "action": action,
+                })]),
+            }),
+            ..Default::default()
+        };
+
+        assert!(!adapter.code_action_requires_destination(&refactor("Move to a new file")));
+
+        let mut move_to_file = refactor(MOVE_TO_FILE_ACTION);
+        assert!(adapter.code_action_requires_destination(&move_to_file));
+        assert_eq!(adapter.code_action_destination(&move_to_file), None);
+        adapter.set_code_action_destination(&mut move_to_file, Path::new("/dir/b.ts"));
+        assert_eq!(
+            adapter.code_action_destination(&move_to_file),
+            Some(PathBuf::from("/dir/b.ts"))
+        );
+    }
 }
@@ -0,0 +1,3 @@
+(program
+    (import_statement
+        source: (string) @path) @import)
@@ -7,8 +7,20 @@
     (assignment_expression)
     (if_statement)
     (for_statement)
+    (return_statement)
+    (binary_expression)
+    (ternary_expression)
 ] @indent
 
+; The body of a `case` is indented as soon as its first line is started.
+(
+    [
+        (switch_case)
+        (switch_default)
+    ] @indent
+    (#set! hanging)
+)
+
 (_ "[" "]" @end) @indent
 (_ "<" ">" @end) @indent
 (_ "{" "}" @end) @indent
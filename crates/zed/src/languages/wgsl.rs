@@ -0,0 +1,162 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use gpui::AppContext;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
+use smol::fs;
+use std::env::consts::{ARCH, OS};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::archive::download_binary_atomically;
+use util::async_maybe;
+use util::fs::matching_by_recency;
+use util::github::latest_github_release;
+use util::{github::GitHubLspBinaryVersion, ResultExt};
+
+pub struct WgslLspAdapter;
+
+#[async_trait]
+impl LspAdapter for WgslLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("wgsl-analyzer".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "wgsl"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = latest_github_release(
+            "wgsl-analyzer/wgsl-analyzer",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+        let asset_name = asset_name()?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
+
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let binary_path = container_dir.join(format!("wgsl_analyzer-{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_binary_atomically(
+                &*delegate.http_client(),
+                &version.url,
+                version.sha256.as_deref(),
+                &binary_path,
+            )
+            .await
+            .context("error downloading release")?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_wgsl_analyzer_version(entry)
+    }
+
+    fn workspace_configuration(&self, _workspace_root: &Path, _: &mut AppContext) -> Value {
+        // The server asks for its whole configuration, and doesn't fill in the parts
+        // that are missing.
+        json!({
+            "wgsl-analyzer": {
+                "customImports": {},
+                "shaderDefs": [],
+                "trace": {
+                    "extension": false,
+                    "server": false,
+                },
+                "inlayHints": {
+                    "enabled": true,
+                    "typeHints": true,
+                    "parameterHints": true,
+                    "structLayoutHints": false,
+                    "typeVerbosity": "compact",
+                },
+                "diagnostics": {
+                    "typeErrors": true,
+                    "nagaParsingErrors": true,
+                    "nagaValidationErrors": true,
+                    "nagaVersion": "main",
+                },
+            }
+        })
+    }
+}
+
+fn asset_name() -> Result<&'static str> {
+    Ok(match (OS, ARCH) {
+        ("macos", "x86_64") => "wgsl_analyzer-darwin-x64",
+        ("macos", "aarch64") => "wgsl_analyzer-darwin-arm64",
+        ("linux", "x86_64") => "wgsl_analyzer-linux-x64",
+        ("linux", "aarch64") => "wgsl_analyzer-linux-arm64",
+        (os, arch) => bail!("Running on unsupported platform: {os} {arch}"),
+    })
+}
+
+fn is_wgsl_analyzer_version(entry: &Path) -> bool {
+    entry.file_name().map_or(false, |name| {
+        name.to_string_lossy().starts_with("wgsl_analyzer-")
+    })
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let latest = matching_by_recency(&container_dir, is_wgsl_analyzer_version)
+            .await
+            .into_iter()
+            .next();
+
+        anyhow::Ok(LanguageServerBinary {
+            path: latest.ok_or_else(|| anyhow!("no cached binary"))?,
+            arguments: Vec::new(),
+        })
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,104 @@
+(identifier) @variable
+
+(type_declaration) @type
+
+(int_literal) @number
+(float_literal) @number
+(bool_literal) @boolean
+
+(function_declaration
+    (identifier) @function)
+
+(parameter
+    (variable_identifier_declaration
+        (identifier) @variable.parameter))
+
+(struct_declaration
+    (identifier) @type)
+
+(struct_member
+    (variable_identifier_declaration
+        (identifier) @property))
+
+(type_constructor_or_function_call_expression
+    (type_declaration) @function)
+
+(attribute
+    (identifier) @attribute)
+
+[
+    "bitcast"
+    "discard"
+    "enable"
+    "fallthrough"
+    "fn"
+    "let"
+    "override"
+    "struct"
+    "type"
+    "var"
+] @keyword
+
+[
+    "break"
+    "case"
+    "continue"
+    "continuing"
+    "default"
+    "else"
+    "for"
+    "if"
+    "loop"
+    "return"
+    "switch"
+    "while"
+] @keyword
+
+[
+    "&"
+    "&&"
+    "/"
+    "!"
+    "="
+    "=="
+    "!="
+    ">"
+    ">="
+    ">>"
+    "<"
+    "<="
+    "<<"
+    "%"
+    "-"
+    "+"
+    "|"
+    "||"
+    "*"
+    "~"
+    "^"
+    "++"
+    "--"
+    "@"
+] @operator
+
+[
+    ","
+    "."
+    ":"
+    ";"
+    "->"
+] @punctuation.delimiter
+
+[
+    "("
+    ")"
+    "["
+    "]"
+    "{"
+    "}"
+] @punctuation.bracket
+
+[
+    (line_comment)
+    (block_comment)
+] @comment
@@ -0,0 +1,11 @@
+(struct_declaration
+    "struct" @context
+    (identifier) @name) @item
+
+(struct_member
+    (variable_identifier_declaration
+        (identifier) @name)) @item
+
+(function_declaration
+    "fn" @context
+    (identifier) @name) @item
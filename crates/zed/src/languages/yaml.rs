@@ -7,10 +7,14 @@ use language::{
 };
 use lsp::LanguageServerBinary;
 use node_runtime::NodeRuntime;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
+use settings::Settings;
 use smol::fs;
 use std::{
     any::Any,
+    collections::BTreeMap,
     ffi::OsString,
     path::{Path, PathBuf},
     sync::Arc,
@@ -23,6 +27,49 @@ fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
     vec![server_path.into(), "--stdio".into()]
 }
 
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct YamlSettings {
+    pub schemas: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct YamlSettingsContent {
+    /// The JSON schemas that YAML files are validated against, keyed by the glob
+    /// that the files' paths are matched with. Globs that don't start with `/` match
+    /// anywhere in the project.
+    ///
+    /// Default: schemas for Docker Compose files, GitHub Actions workflows and
+    /// Kubernetes manifests
+    schemas: Option<BTreeMap<String, String>>,
+}
+
+impl Settings for YamlSettings {
+    const KEY: Option<&'static str> = Some("yaml");
+
+    type FileContent = YamlSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// yaml-language-server maps each schema to the globs of the files that use it, whereas
+/// the settings map each glob to its schema, so that a glob can only have one schema.
+fn schema_associations(schemas: &BTreeMap<String, String>) -> BTreeMap<&str, Vec<&str>> {
+    let mut associations = BTreeMap::<&str, Vec<&str>>::new();
+    for (glob, schema) in schemas {
+        associations.entry(schema).or_default().push(glob);
+    }
+    associations
+}
+
 pub struct YamlLspAdapter {
     node: Arc<dyn NodeRuntime>,
 }
@@ -92,10 +139,13 @@ impl LspAdapter for YamlLspAdapter {
     ) -> Option<LanguageServerBinary> {
         get_cached_server_binary(container_dir, &*self.node).await
     }
+
     fn workspace_configuration(&self, _workspace_root: &Path, cx: &mut AppContext) -> Value {
+        let settings = YamlSettings::get_global(cx);
         serde_json::json!({
             "yaml": {
-                "keyOrdering": false
+                "keyOrdering": false,
+                "schemas": schema_associations(&settings.schemas),
             },
             "[yaml]": {
                 "editor.tabSize": all_language_settings(None, cx)
@@ -136,3 +186,27 @@ async fn get_cached_server_binary(
     .await
     .log_err()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_associations() {
+        let schemas = BTreeMap::from_iter([
+            ("compose.yml".to_string(), "compose.json".to_string()),
+            (
+                "/.github/workflows/*".to_string(),
+                "workflow.json".to_string(),
+            ),
+            ("compose.yaml".to_string(), "compose.json".to_string()),
+        ]);
+        assert_eq!(
+            schema_associations(&schemas),
+            BTreeMap::from_iter([
+                ("compose.json", vec!["compose.yaml", "compose.yml"]),
+                ("workflow.json", vec!["/.github/workflows/*"]),
+            ])
+        );
+    }
+}
@@ -1,15 +1,19 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
+use futures::io::BufReader;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
-use smol::fs;
-use std::env::consts::ARCH;
-use std::{any::Any, path::PathBuf};
+use smol::{fs, process::Command};
+use std::env::consts::{ARCH, OS};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
 use util::async_maybe;
-use util::github::latest_github_release;
+use util::fs::matching_by_recency;
+use util::github::{download_release, github_release_with_tag, latest_github_release};
 use util::{github::GitHubLspBinaryVersion, ResultExt};
 
 pub struct ZlsAdapter;
@@ -28,18 +32,30 @@ impl LspAdapter for ZlsAdapter {
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let release =
-            latest_github_release("zigtools/zls", true, false, delegate.http_client()).await?;
-        let asset_name = format!("zls-{ARCH}-macos.tar.gz");
+        let release = match installed_zig_version().await {
+            // zls is released alongside each tagged Zig release and only supports that
+            // version, so prefer the release matching the installed Zig.
+            Some(zig_version) => {
+                github_release_with_tag("zigtools/zls", &zig_version, delegate.http_client())
+                    .await
+                    .log_err()
+            }
+            None => None,
+        };
+        let release = match release {
+            Some(release) => release,
+            None => {
+                latest_github_release("zigtools/zls", true, false, delegate.http_client()).await?
+            }
+        };
+
+        let asset_name = asset_name()?;
         let asset = release
             .assets
             .iter()
             .find(|asset| asset.name == asset_name)
             .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url.clone(),
-        };
+        let version = GitHubLspBinaryVersion::new(release.tag_name, asset);
 
         Ok(Box::new(version) as Box<_>)
     }
@@ -51,24 +67,24 @@ impl LspAdapter for ZlsAdapter {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let binary_path = container_dir.join("bin/zls");
+        let version_dir = container_dir.join(format!("zls_{}", version.name));
+        let binary_path = binary_path(&version_dir);
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
+            let mut response = download_release(&*delegate.http_client(), &version)
                 .await
                 .context("error downloading release")?;
             let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
             let archive = Archive::new(decompressed_bytes);
-            archive.unpack(container_dir).await?;
+            archive.unpack(&version_dir).await?;
+
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
         }
 
-        fs::set_permissions(
-            &binary_path,
-            <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
-        )
-        .await?;
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: vec![],
@@ -94,31 +110,61 @@ impl LspAdapter for ZlsAdapter {
                 binary
             })
     }
+
+    fn is_server_version(&self, entry: &Path) -> bool {
+        is_zls_version(entry)
+    }
+}
+
+/// Returns the version of the Zig compiler on the `PATH`, if it's a tagged release.
+/// Development builds have no matching zls release.
+async fn installed_zig_version() -> Option<String> {
+    let output = Command::new("zig").arg("version").output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8(output.stdout).ok()?;
+    let version = version.trim();
+    if version.is_empty() || version.contains('-') {
+        return None;
+    }
+    Some(version.to_string())
+}
+
+fn asset_name() -> Result<String> {
+    let os = match OS {
+        "macos" => "macos",
+        "linux" => "linux",
+        other => bail!("Running on unsupported os: {other}"),
+    };
+    Ok(format!("zls-{ARCH}-{os}.tar.gz"))
+}
+
+fn binary_path(version_dir: &Path) -> PathBuf {
+    version_dir.join("bin").join("zls")
+}
+
+fn is_zls_version(entry: &Path) -> bool {
+    entry
+        .file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with("zls_"))
 }
 
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last_binary_path = None;
-        let mut entries = fs::read_dir(&container_dir).await?;
-        while let Some(entry) = entries.next().await {
-            let entry = entry?;
-            if entry.file_type().await?.is_file()
-                && entry
-                    .file_name()
-                    .to_str()
-                    .map_or(false, |name| name == "zls")
-            {
-                last_binary_path = Some(entry.path());
-            }
-        }
-
-        if let Some(path) = last_binary_path {
+        let version_dir = matching_by_recency(&container_dir, is_zls_version)
+            .await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no cached binary"))?;
+        let path = binary_path(&version_dir);
+        if path.exists() {
             Ok(LanguageServerBinary {
                 path,
                 arguments: Vec::new(),
             })
         } else {
-            Err(anyhow!("no cached binary"))
+            Err(anyhow!("missing zls binary in directory {:?}", version_dir))
         }
     })
     .await
@@ -0,0 +1,15 @@
+(Decl
+  (FnProto
+    "fn" @context
+    function: (_) @name)) @item
+
+(VarDecl
+  ["const" "var"] @context
+  variable_type_function: (_) @name
+  (ErrorUnionExpr
+    (SuffixExpr
+      (ContainerDecl)))) @item
+
+(TestDecl
+  "test" @context
+  (STRINGLITERALSINGLE) @name) @item
@@ -14,6 +14,7 @@ use fs::RealFs;
 #[cfg(target_os = "macos")]
 use fsevent::StreamFlags;
 use futures::StreamExt;
+use gem_runtime::RealGemRuntime;
 use gpui::{App, AppContext, AsyncAppContext, Context, SemanticVersion, Task};
 use isahc::{prelude::Configurable, Request};
 use language::LanguageRegistry;
@@ -56,8 +57,11 @@ use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
 use workspace::{AppState, WorkspaceStore};
 use zed::{
     app_menus, build_window_options, ensure_only_instance, handle_cli_connection,
-    handle_keymap_file_changes, initialize_workspace, languages, IsOnlyInstance, OpenListener,
-    OpenRequest,
+    handle_keymap_file_changes,
+    headless::{self, HeadlessArgs, JoinArgs},
+    initialize_workspace, languages,
+    remote_files::{self, RemotePath},
+    IsOnlyInstance, OpenListener, OpenRequest,
 };
 
 #[global_allocator]
@@ -70,12 +74,32 @@ fn main() {
     init_paths();
     init_logger();
 
-    if ensure_only_instance() != IsOnlyInstance::Yes {
+    let (headless_args, join_args) = match HeadlessArgs::parse(env::args().skip(1))
+        .and_then(|headless_args| Ok((headless_args, JoinArgs::parse(env::args().skip(1))?)))
+    {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error:#}");
+            std::process::exit(1);
+        }
+    };
+    // A headless host, or a client that joins one, may run alongside the Zed that the user
+    // works in.
+    if headless_args.is_none()
+        && join_args.is_none()
+        && ensure_only_instance() != IsOnlyInstance::Yes
+    {
         return;
     }
 
     log::info!("========== starting zed ==========");
-    let app = App::new().with_assets(Assets);
+    // Headless hosts run on servers, where there may be no display to connect to.
+    let app = if headless_args.is_some() {
+        App::headless()
+    } else {
+        App::new()
+    }
+    .with_assets(Assets);
 
     let (installation_id, existing_installation_id_found) = app
         .background_executor()
@@ -150,9 +174,10 @@ fn main() {
         languages.set_language_server_download_dir(paths::LANGUAGES_DIR.clone());
         let languages = Arc::new(languages);
         let node_runtime = RealNodeRuntime::new(http.clone());
+        let gem_runtime = RealGemRuntime::new(http.clone());
 
         language::init(cx);
-        languages::init(languages.clone(), node_runtime.clone(), cx);
+        languages::init(languages.clone(), node_runtime.clone(), gem_runtime, cx);
         let user_store = cx.new_model(|cx| UserStore::new(client.clone(), cx));
         let workspace_store = cx.new_model(|cx| WorkspaceStore::new(client.clone(), cx));
 
@@ -174,7 +199,13 @@ fn main() {
         );
         assistant::init(cx);
 
-        extension::init(fs.clone(), languages.clone(), ThemeRegistry::global(cx), cx);
+        extension::init(
+            fs.clone(),
+            http.clone(),
+            languages.clone(),
+            ThemeRegistry::global(cx),
+            cx,
+        );
 
         load_user_themes_in_background(fs.clone(), cx);
         #[cfg(target_os = "macos")]
@@ -235,6 +266,13 @@ fn main() {
         recent_projects::init(cx);
 
         go_to_line::init(cx);
+        remote_files::init(cx);
+        clipboard_history::init(cx);
+        hex_editor::init(cx);
+        hooks::init(cx);
+        audit_log::init(cx);
+        secrets::init(cx);
+        secrets_ui::init(cx);
         file_finder::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
@@ -248,13 +286,32 @@ fn main() {
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
         theme_selector::init(cx);
+        undo_tree::init(cx);
         language_tools::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         collab_ui::init(&app_state, cx);
+        zed::menu_bar_extra::init(&app_state, cx);
+        zed::native_notifications::init(&app_state, cx);
         feedback::init(cx);
         markdown_preview::init(cx);
+        module_graph::init(cx);
+        time_tracking::init(fs.clone(), http.clone(), cx);
         welcome::init(cx);
+        extensions_ui::init(cx);
+
+        if let Some(headless_args) = headless_args {
+            let host = headless::host_project(headless_args, app_state, cx);
+            cx.spawn(|_| async move {
+                if let Err(error) = host.await {
+                    log::error!("failed to host project: {error:?}");
+                    eprintln!("{error:#}");
+                    std::process::exit(1);
+                }
+            })
+            .detach();
+            return;
+        }
 
         cx.set_menus(app_menus());
         initialize_workspace(app_state.clone(), cx);
@@ -264,7 +321,12 @@ fn main() {
             #[cfg(not(target_os = "linux"))]
             upload_panics_and_crashes(http.clone(), cx);
             cx.activate(true);
-            let urls = collect_url_args();
+            // A client's arguments name the channel it joins, not paths to open.
+            let urls = if join_args.is_none() {
+                collect_url_args()
+            } else {
+                Vec::new()
+            };
             if !urls.is_empty() {
                 listener.open_urls(&urls)
             }
@@ -299,55 +361,63 @@ fn main() {
             .detach();
         }
 
-        match open_rx.try_next() {
-            Ok(Some(OpenRequest::Paths { paths })) => {
-                open_paths_and_log_errs(&paths, &app_state, cx)
-            }
-            Ok(Some(OpenRequest::CliConnection { connection })) => {
-                let app_state = app_state.clone();
-                cx.spawn(move |cx| handle_cli_connection(connection, app_state, cx))
-                    .detach();
-            }
-            Ok(Some(OpenRequest::JoinChannel { channel_id })) => {
-                triggered_authentication = true;
-                let app_state = app_state.clone();
-                let client = client.clone();
-                cx.spawn(|cx| async move {
-                    // ignore errors here, we'll show a generic "not signed in"
-                    let _ = authenticate(client, &cx).await;
-                    cx.update(|cx| workspace::join_channel(channel_id, app_state, None, cx))?
+        if let Some(join_args) = join_args {
+            triggered_authentication = true;
+            headless::join_project(join_args, app_state.clone(), cx).detach_and_log_err(cx);
+        } else {
+            match open_rx.try_next() {
+                Ok(Some(OpenRequest::Paths { paths })) => {
+                    open_paths_and_log_errs(&paths, &app_state, cx)
+                }
+                Ok(Some(OpenRequest::RemotePaths { paths })) => {
+                    remote_files::open_remote_paths(paths, &app_state, cx).detach_and_log_err(cx)
+                }
+                Ok(Some(OpenRequest::CliConnection { connection })) => {
+                    let app_state = app_state.clone();
+                    cx.spawn(move |cx| handle_cli_connection(connection, app_state, cx))
+                        .detach();
+                }
+                Ok(Some(OpenRequest::JoinChannel { channel_id })) => {
+                    triggered_authentication = true;
+                    let app_state = app_state.clone();
+                    let client = client.clone();
+                    cx.spawn(|cx| async move {
+                        // ignore errors here, we'll show a generic "not signed in"
+                        let _ = authenticate(client, &cx).await;
+                        cx.update(|cx| workspace::join_channel(channel_id, app_state, None, cx))?
+                            .await?;
+                        anyhow::Ok(())
+                    })
+                    .detach_and_log_err(cx);
+                }
+                Ok(Some(OpenRequest::OpenChannelNotes {
+                    channel_id,
+                    heading,
+                })) => {
+                    triggered_authentication = true;
+                    let app_state = app_state.clone();
+                    let client = client.clone();
+                    cx.spawn(|mut cx| async move {
+                        // ignore errors here, we'll show a generic "not signed in"
+                        let _ = authenticate(client, &cx).await;
+                        let workspace_window =
+                            workspace::get_any_active_workspace(app_state, cx.clone()).await?;
+                        let workspace = workspace_window.root_view(&cx)?;
+                        cx.update_window(workspace_window.into(), |_, cx| {
+                            ChannelView::open(channel_id, heading, workspace, cx)
+                        })?
                         .await?;
-                    anyhow::Ok(())
-                })
-                .detach_and_log_err(cx);
-            }
-            Ok(Some(OpenRequest::OpenChannelNotes {
-                channel_id,
-                heading,
-            })) => {
-                triggered_authentication = true;
-                let app_state = app_state.clone();
-                let client = client.clone();
-                cx.spawn(|mut cx| async move {
-                    // ignore errors here, we'll show a generic "not signed in"
-                    let _ = authenticate(client, &cx).await;
-                    let workspace_window =
-                        workspace::get_any_active_workspace(app_state, cx.clone()).await?;
-                    let workspace = workspace_window.root_view(&cx)?;
-                    cx.update_window(workspace_window.into(), |_, cx| {
-                        ChannelView::open(channel_id, heading, workspace, cx)
-                    })?
-                    .await?;
-                    anyhow::Ok(())
-                })
-                .detach_and_log_err(cx);
+                        anyhow::Ok(())
+                    })
+                    .detach_and_log_err(cx);
+                }
+                Ok(None) | Err(_) => cx
+                    .spawn({
+                        let app_state = app_state.clone();
+                        |cx| async move { restore_or_create_workspace(&app_state, cx).await }
+                    })
+                    .detach(),
             }
-            Ok(None) | Err(_) => cx
-                .spawn({
-                    let app_state = app_state.clone();
-                    |cx| async move { restore_or_create_workspace(&app_state, cx).await }
-                })
-                .detach(),
         }
 
         let app_state = app_state.clone();
@@ -358,6 +428,13 @@ fn main() {
                         cx.update(|cx| open_paths_and_log_errs(&paths, &app_state, cx))
                             .ok();
                     }
+                    OpenRequest::RemotePaths { paths } => {
+                        cx.update(|cx| {
+                            remote_files::open_remote_paths(paths, &app_state, cx)
+                                .detach_and_log_err(cx)
+                        })
+                        .ok();
+                    }
                     OpenRequest::CliConnection { connection } => {
                         let app_state = app_state.clone();
                         cx.spawn(move |cx| {
@@ -857,7 +934,7 @@ fn collect_url_args() -> Vec<String> {
         .filter_map(|arg| match std::fs::canonicalize(Path::new(&arg)) {
             Ok(path) => Some(format!("file://{}", path.to_string_lossy())),
             Err(error) => {
-                if let Some(_) = parse_zed_link(&arg) {
+                if parse_zed_link(&arg).is_some() || RemotePath::is_remote_url(&arg) {
                     Some(arg)
                 } else {
                     log::error!("error parsing path argument: {}", error);
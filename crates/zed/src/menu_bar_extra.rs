@@ -0,0 +1,132 @@
+use call::ActiveCall;
+use collab_ui::{LeaveCall, ToggleMute};
+use gpui::{impl_actions, AppContext, Global, Menu, MenuItem, Subscription};
+use serde::Deserialize;
+use settings::{Settings, SettingsStore};
+use std::{path::PathBuf, sync::Arc};
+use util::{paths::PathExt, ResultExt};
+use workspace::{AppState, NewWindow, Open, Workspace, WorkspaceSettings, WORKSPACE_DB};
+use zed_actions::Quit;
+
+const MAX_RECENT_PROJECTS: usize = 8;
+
+/// Opens one of the recent projects listed in the menu bar extra.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct OpenRecentProject {
+    pub paths: Vec<PathBuf>,
+}
+
+impl_actions!(zed, [OpenRecentProject]);
+
+#[derive(Default)]
+struct MenuBarExtra {
+    _room_subscription: Option<Subscription>,
+}
+
+impl Global for MenuBarExtra {}
+
+/// Keeps the menu bar extra in sync with the `show_menu_bar_extra` setting, the active
+/// call and the recent projects. Its actions are handled globally, so they keep working
+/// after all windows have been closed.
+pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
+    cx.on_action({
+        let app_state = Arc::downgrade(app_state);
+        move |action: &OpenRecentProject, cx| {
+            if let Some(app_state) = app_state.upgrade() {
+                workspace::open_paths(&action.paths, &app_state, None, cx).detach_and_log_err(cx);
+            }
+        }
+    });
+
+    cx.set_global(MenuBarExtra::default());
+    cx.observe_global::<SettingsStore>(refresh).detach();
+    cx.observe_new_views(|_: &mut Workspace, cx| refresh(cx))
+        .detach();
+    cx.observe(&ActiveCall::global(cx), |active_call, cx| {
+        let room_subscription = active_call
+            .read(cx)
+            .room()
+            .cloned()
+            .map(|room| cx.observe(&room, |_, cx| refresh(cx)));
+        cx.global_mut::<MenuBarExtra>()._room_subscription = room_subscription;
+        refresh(cx);
+    })
+    .detach();
+    refresh(cx);
+}
+
+fn refresh(cx: &mut AppContext) {
+    if !WorkspaceSettings::get_global(cx).show_menu_bar_extra {
+        cx.set_menu_bar_extra(None);
+        return;
+    }
+
+    cx.spawn(|cx| async move {
+        let recent_projects = WORKSPACE_DB
+            .recent_workspaces_on_disk()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .take(MAX_RECENT_PROJECTS)
+            .map(|(_, location)| location.paths().to_vec())
+            .collect::<Vec<_>>();
+        cx.update(|cx| set_menu(recent_projects, cx)).log_err();
+    })
+    .detach();
+}
+
+fn set_menu(recent_projects: Vec<Vec<PathBuf>>, cx: &mut AppContext) {
+    // The setting may have been disabled while the recent projects were loading.
+    if !WorkspaceSettings::get_global(cx).show_menu_bar_extra {
+        return;
+    }
+
+    let project_names = recent_projects
+        .iter()
+        .map(|paths| {
+            paths
+                .iter()
+                .map(|path| path.compact().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect::<Vec<_>>();
+
+    let mut title = "Zed";
+    let mut items = Vec::new();
+    if let Some(room) = ActiveCall::global(cx).read(cx).room() {
+        let is_muted = room.read(cx).is_muted();
+        title = if is_muted {
+            "Zed (Muted)"
+        } else {
+            "Zed (In Call)"
+        };
+        items.push(MenuItem::action(
+            if is_muted {
+                "Unmute Microphone"
+            } else {
+                "Mute Microphone"
+            },
+            ToggleMute,
+        ));
+        items.push(MenuItem::action("Leave Call", LeaveCall));
+        items.push(MenuItem::separator());
+    }
+
+    items.push(MenuItem::action("New Window", NewWindow));
+    items.push(MenuItem::action("Open…", Open));
+    if !recent_projects.is_empty() {
+        items.push(MenuItem::submenu(Menu {
+            name: "Open Recent",
+            items: recent_projects
+                .into_iter()
+                .zip(&project_names)
+                .map(|(paths, name)| MenuItem::action(name, OpenRecentProject { paths }))
+                .collect(),
+        }));
+    }
+    items.push(MenuItem::separator());
+    items.push(MenuItem::action("Quit Zed", Quit));
+
+    cx.set_menu_bar_extra(Some(Menu { name: title, items }));
+}
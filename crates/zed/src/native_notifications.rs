@@ -0,0 +1,129 @@
+use anyhow::Result;
+use call::ActiveCall;
+use futures::StreamExt;
+use gpui::AppContext;
+use language::LanguageServerBinaryStatus;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use workspace::{AppState, Workspace};
+
+#[derive(Clone, Deserialize)]
+pub struct NativeNotificationSettings {
+    pub language_server_installed: bool,
+    pub collaborator_joined: bool,
+    pub incoming_call: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NativeNotificationSettingsContent {
+    /// Whether to notify when a language server finishes downloading.
+    ///
+    /// Default: true
+    pub language_server_installed: Option<bool>,
+    /// Whether to notify when a collaborator joins one of your shared projects.
+    ///
+    /// Default: true
+    pub collaborator_joined: Option<bool>,
+    /// Whether to notify when a contact calls you.
+    ///
+    /// Default: true
+    pub incoming_call: Option<bool>,
+}
+
+impl Settings for NativeNotificationSettings {
+    const KEY: Option<&'static str> = Some("native_notifications");
+
+    type FileContent = NativeNotificationSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// Shows notifications through the operating system for events that happen while Zed
+/// is in the background, so they aren't missed until the next time a window is focused.
+pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
+    NativeNotificationSettings::register(cx);
+
+    let mut binary_statuses = app_state.languages.language_server_binary_statuses();
+    cx.spawn(|cx| async move {
+        while let Some((language, status)) = binary_statuses.next().await {
+            if let LanguageServerBinaryStatus::Downloaded = status {
+                cx.update(|cx| {
+                    notify(
+                        |settings| settings.language_server_installed,
+                        "Language server installed",
+                        &format!("The {} language server is ready.", language.name()),
+                        cx,
+                    )
+                })
+                .ok();
+            }
+        }
+    })
+    .detach();
+
+    let mut incoming_call = ActiveCall::global(cx).read(cx).incoming();
+    cx.spawn(|cx| async move {
+        while let Some(incoming_call) = incoming_call.next().await {
+            if let Some(incoming_call) = incoming_call {
+                cx.update(|cx| {
+                    notify(
+                        |settings| settings.incoming_call,
+                        "Incoming call",
+                        &format!(
+                            "{} is calling you.",
+                            incoming_call.calling_user.github_login
+                        ),
+                        cx,
+                    )
+                })
+                .ok();
+            }
+        }
+    })
+    .detach();
+
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        cx.subscribe(workspace.project(), |_, project, event, cx| {
+            if let project::Event::CollaboratorJoined(peer_id) = event {
+                let project = project.read(cx);
+                let Some(collaborator) = project.collaborators().get(peer_id) else {
+                    return;
+                };
+                let Some(user) = project
+                    .user_store()
+                    .read(cx)
+                    .get_cached_user(collaborator.user_id)
+                else {
+                    return;
+                };
+                notify(
+                    |settings| settings.collaborator_joined,
+                    "Collaborator joined",
+                    &format!("{} joined your project.", user.github_login),
+                    cx,
+                );
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn notify(
+    enabled: impl FnOnce(&NativeNotificationSettings) -> bool,
+    title: &str,
+    body: &str,
+    cx: &mut AppContext,
+) {
+    if cx.active_window().is_none() && enabled(NativeNotificationSettings::get_global(cx)) {
+        cx.show_notification(title, body);
+    }
+}
@@ -1,7 +1,11 @@
+use crate::remote_files::{self, RemotePath};
 use anyhow::{anyhow, Context, Result};
 use cli::{ipc, IpcHandshake};
 use cli::{ipc::IpcSender, CliRequest, CliResponse};
 use collections::HashMap;
+use editor::external_decorations::{
+    clear_external_decorations, parse_external_decorations, set_external_decorations,
+};
 use editor::scroll::Autoscroll;
 use editor::Editor;
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
@@ -27,6 +31,9 @@ pub enum OpenRequest {
     Paths {
         paths: Vec<PathBuf>,
     },
+    RemotePaths {
+        paths: Vec<RemotePath>,
+    },
     CliConnection {
         connection: (mpsc::Receiver<CliRequest>, IpcSender<CliResponse>),
     },
@@ -76,6 +83,8 @@ impl OpenListener {
             self.handle_cli_connection(server_name)
         } else if let Some(request_path) = urls.first().and_then(|url| parse_zed_link(url)) {
             self.handle_zed_url_scheme(request_path)
+        } else if urls.iter().any(|url| RemotePath::is_remote_url(url)) {
+            self.handle_remote_urls(urls)
         } else {
             self.handle_file_urls(urls)
         };
@@ -125,6 +134,21 @@ impl OpenListener {
         None
     }
 
+    fn handle_remote_urls(&self, urls: &[String]) -> Option<OpenRequest> {
+        let paths = urls
+            .iter()
+            .filter_map(|url| {
+                let path = RemotePath::parse(url);
+                if path.is_none() {
+                    log::error!("invalid remote url: {}", url);
+                }
+                path
+            })
+            .collect();
+
+        Some(OpenRequest::RemotePaths { paths })
+    }
+
     fn handle_file_urls(&self, urls: &[String]) -> Option<OpenRequest> {
         let paths: Vec<_> = urls
             .iter()
@@ -176,6 +200,23 @@ pub async fn handle_cli_connection(
     if let Some(request) = requests.next().await {
         match request {
             CliRequest::Open { paths, wait } => {
+                let (remote_urls, paths): (Vec<_>, Vec<_>) = paths
+                    .into_iter()
+                    .partition(|path| RemotePath::is_remote_url(path));
+                if !remote_urls.is_empty() {
+                    let errored = open_remote_urls(&remote_urls, &app_state, &responses, &mut cx)
+                        .await
+                        .is_err();
+                    if paths.is_empty() {
+                        responses
+                            .send(CliResponse::Exit {
+                                status: i32::from(errored),
+                            })
+                            .log_err();
+                        return;
+                    }
+                }
+
                 let mut caret_positions = HashMap::default();
 
                 let paths = if paths.is_empty() {
@@ -322,6 +363,64 @@ pub async fn handle_cli_connection(
                     })
                     .log_err();
             }
+            CliRequest::SetDecorations {
+                namespace,
+                decorations,
+                working_directory,
+            } => {
+                let result =
+                    parse_external_decorations(&decorations, Path::new(&working_directory))
+                        .and_then(|decorations| {
+                            cx.update(|cx| set_external_decorations(namespace, decorations, cx))
+                        });
+                if let Err(error) = &result {
+                    responses
+                        .send(CliResponse::Stderr {
+                            message: format!("error setting decorations: {error:#}"),
+                        })
+                        .log_err();
+                }
+                responses
+                    .send(CliResponse::Exit {
+                        status: i32::from(result.is_err()),
+                    })
+                    .log_err();
+            }
+            CliRequest::ClearDecorations { namespace } => {
+                let errored = cx
+                    .update(|cx| clear_external_decorations(&namespace, cx))
+                    .is_err();
+                responses
+                    .send(CliResponse::Exit {
+                        status: i32::from(errored),
+                    })
+                    .log_err();
+            }
         }
     }
 }
+
+async fn open_remote_urls(
+    urls: &[String],
+    app_state: &Arc<AppState>,
+    responses: &IpcSender<CliResponse>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let mut remote_paths = Vec::new();
+    for url in urls {
+        let remote_path =
+            RemotePath::parse(url).ok_or_else(|| anyhow!("invalid remote url: {url}"))?;
+        remote_paths.push(remote_path);
+    }
+    let result = cx
+        .update(|cx| remote_files::open_remote_paths(remote_paths, app_state, cx))?
+        .await;
+    if let Err(error) = &result {
+        responses
+            .send(CliResponse::Stderr {
+                message: format!("{error:#}"),
+            })
+            .log_err();
+    }
+    result
+}
@@ -0,0 +1,427 @@
+use anyhow::{anyhow, Context as _, Result};
+use gpui::{AppContext, Global, Task, ViewContext};
+use smol::process::Command;
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use util::paths;
+use workspace::{AppState, Toast, Workspace};
+
+const UPLOAD_FAILURE_TOAST_ID: usize = 0x5f7a7e11;
+
+/// A file or directory on an SSH host, addressed by an `sftp://user@host:port/path` or
+/// `scp://` url. A path starting with `/~/` is relative to the user's home directory.
+///
+/// Remote files are copied into a local directory when they are opened, and copied back
+/// when they are saved. This uses the system's `scp`, which authenticates with the SSH
+/// agent or the user's keys, and never prompts for a password. It transfers files over
+/// SFTP, so that remote paths are taken literally rather than expanded by the remote shell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemotePath {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    pub path: String,
+}
+
+impl RemotePath {
+    pub fn is_remote_url(url: &str) -> bool {
+        url.starts_with("sftp://") || url.starts_with("scp://")
+    }
+
+    pub fn parse(url: &str) -> Option<Self> {
+        let rest = url
+            .strip_prefix("sftp://")
+            .or_else(|| url.strip_prefix("scp://"))?;
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let (user, host_and_port) = match authority.rsplit_once('@') {
+            Some((user, host_and_port)) => (Some(user.to_string()), host_and_port),
+            None => (None, authority),
+        };
+        let (host, port) = match host_and_port.split_once(':') {
+            Some((host, port)) => (host, Some(port.parse().ok()?)),
+            None => (host_and_port, None),
+        };
+        if !is_valid_ssh_name(host) || !user.as_deref().map_or(true, is_valid_ssh_name) {
+            return None;
+        }
+
+        let path = urlencoding::decode(path).ok()?;
+        // The path is mirrored under the local remote files directory, which it must not
+        // escape.
+        if path.split('/').any(|component| component == "..") {
+            return None;
+        }
+        // scp resolves relative paths against the home directory.
+        let path = match path.strip_prefix('~') {
+            Some(path) => path.trim_start_matches('/').to_string(),
+            None => format!("/{path}"),
+        };
+        // The root and home directories would be copied as a whole.
+        if path.trim_matches('/').is_empty() {
+            return None;
+        }
+        Some(Self {
+            user,
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+
+    /// Where the remote file is copied to while it is being edited.
+    pub fn local_path(&self) -> PathBuf {
+        let mut dir_name = self.host.clone();
+        if let Some(user) = &self.user {
+            dir_name = format!("{user}@{dir_name}");
+        }
+        if let Some(port) = self.port {
+            dir_name = format!("{dir_name}_{port}");
+        }
+        let mut local_path = paths::REMOTE_FILES_DIR.join(dir_name);
+        if !self.path.starts_with('/') {
+            local_path.push("~");
+        }
+        local_path.push(self.path.trim_start_matches('/'));
+        local_path
+    }
+
+    fn is_local_path_contained(local_path: &Path) -> bool {
+        local_path.starts_with(&*paths::REMOTE_FILES_DIR)
+            && !local_path
+                .components()
+                .any(|component| component == Component::ParentDir)
+    }
+
+    fn scp_location(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{user}@{}:{}", self.host, self.path),
+            None => format!("{}:{}", self.host, self.path),
+        }
+    }
+
+    fn join(&self, relative_path: &Path) -> Self {
+        let mut path = self.path.trim_end_matches('/').to_string();
+        for component in relative_path.components() {
+            path.push('/');
+            path.push_str(&component.as_os_str().to_string_lossy());
+        }
+        Self {
+            path,
+            ..self.clone()
+        }
+    }
+}
+
+/// The remote files and directories that have been opened, so that their local copies
+/// can be uploaded when saved.
+#[derive(Default)]
+struct RemoteFiles {
+    roots: Vec<(PathBuf, RemotePath)>,
+}
+
+impl Global for RemoteFiles {}
+
+impl RemoteFiles {
+    fn remote_path_for(&self, local_path: &Path) -> Option<RemotePath> {
+        self.roots.iter().find_map(|(local_root, remote_root)| {
+            let relative_path = local_path.strip_prefix(local_root).ok()?;
+            Some(remote_root.join(relative_path))
+        })
+    }
+}
+
+pub fn init(cx: &mut AppContext) {
+    cx.set_global(RemoteFiles::default());
+    // The project reports each save of a buffer once, however many editors show it.
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        cx.subscribe(workspace.project(), |_, project, event, cx| {
+            if let project::Event::BufferSaved(project_path) = event {
+                if let Some(local_path) = project.read(cx).absolute_path(project_path, cx) {
+                    upload_saved_file(local_path, cx);
+                }
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Downloads the given remote files and directories, and opens their local copies.
+pub fn open_remote_paths(
+    remote_paths: Vec<RemotePath>,
+    app_state: &Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let app_state = app_state.clone();
+    cx.spawn(|mut cx| async move {
+        let mut local_paths = Vec::new();
+        for remote_path in remote_paths {
+            let local_path = remote_path.local_path();
+            // Downloading again would replace the local copy under its open buffers,
+            // discarding their unsaved edits, so the open copy is used as is.
+            if cx.update(|cx| is_local_copy_open(&local_path, cx))? {
+                log::info!(
+                    "not downloading {} again, as its local copy is open",
+                    remote_path.scp_location()
+                );
+            } else {
+                download(&remote_path, &local_path).await?;
+            }
+            cx.update(|cx| {
+                let roots = &mut cx.global_mut::<RemoteFiles>().roots;
+                roots.retain(|(local_root, _)| local_root != &local_path);
+                roots.push((local_path.clone(), remote_path));
+            })?;
+            local_paths.push(local_path);
+        }
+
+        let (_, results) = cx
+            .update(|cx| workspace::open_paths(&local_paths, &app_state, None, cx))?
+            .await?;
+        for result in results {
+            if let Some(Err(error)) = result {
+                log::error!("error opening remote file: {error:?}");
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Whether the local copy at `local_path`, or a file in it, is open in any workspace.
+fn is_local_copy_open(local_path: &Path, cx: &AppContext) -> bool {
+    cx.windows()
+        .into_iter()
+        .filter_map(|window| window.downcast::<Workspace>())
+        .any(|workspace| {
+            workspace
+                .read_with(cx, |workspace, cx| {
+                    workspace
+                        .project()
+                        .read(cx)
+                        .opened_buffers()
+                        .iter()
+                        .any(|buffer| {
+                            buffer
+                                .read(cx)
+                                .file()
+                                .and_then(|file| file.as_local())
+                                .map_or(false, |file| file.abs_path(cx).starts_with(local_path))
+                        })
+                })
+                .unwrap_or(false)
+        })
+}
+
+/// Whether a user or host name can be passed to scp. Names starting with `-` would be
+/// taken as options.
+fn is_valid_ssh_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name != "."
+        && name != ".."
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+}
+
+async fn download(remote_path: &RemotePath, local_path: &Path) -> Result<()> {
+    if !RemotePath::is_local_path_contained(local_path) {
+        return Err(anyhow!(
+            "refusing to download {} outside of {}",
+            remote_path.scp_location(),
+            paths::REMOTE_FILES_DIR.display()
+        ));
+    }
+    if let Some(parent) = local_path.parent() {
+        smol::fs::create_dir_all(parent).await?;
+    }
+    // Start from a fresh copy, as `scp -r` copies a directory into an existing one.
+    if smol::fs::metadata(local_path).await.is_ok() {
+        if local_path.is_dir() {
+            smol::fs::remove_dir_all(local_path).await?;
+        } else {
+            smol::fs::remove_file(local_path).await?;
+        }
+    }
+
+    let source = remote_path.scp_location();
+    run_scp(
+        remote_path,
+        &[source.as_str(), &local_path.to_string_lossy()],
+    )
+    .await
+    .with_context(|| format!("failed to download {source}"))
+}
+
+async fn upload(local_path: &Path, remote_path: &RemotePath) -> Result<()> {
+    let destination = remote_path.scp_location();
+    run_scp(
+        remote_path,
+        &[&local_path.to_string_lossy(), destination.as_str()],
+    )
+    .await
+    .with_context(|| format!("failed to upload {destination}"))
+}
+
+/// The arguments to scp for copying between the given locations. `-s` makes scp use the
+/// SFTP protocol, as the legacy protocol passes remote paths to the remote shell, which
+/// would split them at spaces and run any command substitutions in them.
+fn scp_args(remote_path: &RemotePath, locations: &[&str]) -> Vec<String> {
+    let mut args = vec!["-r", "-q", "-s", "-o", "BatchMode=yes"]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    if let Some(port) = remote_path.port {
+        args.push("-P".into());
+        args.push(port.to_string());
+    }
+    args.push("--".into());
+    args.extend(locations.iter().map(|location| location.to_string()));
+    args
+}
+
+async fn run_scp(remote_path: &RemotePath, locations: &[&str]) -> Result<()> {
+    let output = Command::new("scp")
+        .args(scp_args(remote_path, locations))
+        .output()
+        .await
+        .context("failed to run scp")?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn upload_saved_file(local_path: PathBuf, cx: &mut ViewContext<Workspace>) {
+    let Some(remote_path) = cx.global::<RemoteFiles>().remote_path_for(&local_path) else {
+        return;
+    };
+
+    cx.spawn(|workspace, mut cx| async move {
+        if let Err(error) = upload(&local_path, &remote_path).await {
+            log::error!("{error:?}");
+            workspace.update(&mut cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(UPLOAD_FAILURE_TOAST_ID, format!("{error:#}")),
+                    cx,
+                )
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_path() {
+        assert_eq!(
+            RemotePath::parse("sftp://me@example.com:2222/var/www/index.html"),
+            Some(RemotePath {
+                user: Some("me".into()),
+                host: "example.com".into(),
+                port: Some(2222),
+                path: "/var/www/index.html".into(),
+            })
+        );
+        assert_eq!(
+            RemotePath::parse("scp://example.com/~/notes%20and%20todos.md"),
+            Some(RemotePath {
+                user: None,
+                host: "example.com".into(),
+                port: None,
+                path: "notes and todos.md".into(),
+            })
+        );
+        assert_eq!(RemotePath::parse("sftp://me@/etc/hosts"), None);
+        assert_eq!(RemotePath::parse("sftp://example.com:ssh/etc/hosts"), None);
+        assert_eq!(RemotePath::parse("file:///etc/hosts"), None);
+
+        assert_eq!(RemotePath::parse("sftp://example.com/srv/../../etc"), None);
+        assert_eq!(RemotePath::parse("sftp://example.com/~/%2E%2E/etc"), None);
+        assert_eq!(
+            RemotePath::parse("sftp://-oProxyCommand=id/etc/hosts"),
+            None
+        );
+        assert_eq!(
+            RemotePath::parse("sftp://-oProxyCommand=id@example.com/etc"),
+            None
+        );
+        assert_eq!(RemotePath::parse("sftp://../etc/hosts"), None);
+
+        assert_eq!(RemotePath::parse("sftp://example.com"), None);
+        assert_eq!(RemotePath::parse("sftp://example.com/"), None);
+        assert_eq!(RemotePath::parse("sftp://example.com//"), None);
+        assert_eq!(RemotePath::parse("sftp://example.com/~"), None);
+        assert_eq!(RemotePath::parse("sftp://example.com/~/"), None);
+    }
+
+    #[test]
+    fn test_scp_args_pass_paths_literally() {
+        let remote_path =
+            RemotePath::parse("sftp://me@example.com:2222/srv/my%20app/$(touch%20pwned);id")
+                .unwrap();
+        assert_eq!(remote_path.path, "/srv/my app/$(touch pwned);id");
+
+        let source = remote_path.scp_location();
+        assert_eq!(source, "me@example.com:/srv/my app/$(touch pwned);id");
+        assert_eq!(
+            scp_args(&remote_path, &[&source, "/tmp/copy"]),
+            [
+                "-r",
+                "-q",
+                "-s",
+                "-o",
+                "BatchMode=yes",
+                "-P",
+                "2222",
+                "--",
+                "me@example.com:/srv/my app/$(touch pwned);id",
+                "/tmp/copy",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_local_path_stays_in_remote_files_dir() {
+        let remote_path = RemotePath::parse("sftp://me@example.com/~/notes.md").unwrap();
+        assert!(RemotePath::is_local_path_contained(
+            &remote_path.local_path()
+        ));
+        assert!(!RemotePath::is_local_path_contained(
+            &paths::REMOTE_FILES_DIR
+                .join("example.com")
+                .join("..")
+                .join("..")
+        ));
+        assert!(!RemotePath::is_local_path_contained(Path::new("/etc")));
+    }
+
+    #[test]
+    fn test_remote_path_for_local_copy() {
+        let remote_root = RemotePath::parse("sftp://me@example.com/srv/app").unwrap();
+        let local_root = remote_root.local_path();
+        let remote_files = RemoteFiles {
+            roots: vec![(local_root.clone(), remote_root)],
+        };
+        assert_eq!(
+            remote_files
+                .remote_path_for(&local_root.join("src").join("main.rs"))
+                .map(|remote_path| remote_path.path),
+            Some("/srv/app/src/main.rs".into())
+        );
+        assert_eq!(
+            remote_files.remote_path_for(Path::new("/srv/app/src/main.rs")),
+            None
+        );
+    }
+}
@@ -1,7 +1,11 @@
 mod app_menus;
+pub mod headless;
 pub mod languages;
+pub mod menu_bar_extra;
+pub mod native_notifications;
 mod only_instance;
 mod open_listener;
+pub mod remote_files;
 
 pub use app_menus::*;
 use assistant::AssistantPanel;
@@ -26,6 +30,7 @@ use search::project_search::ProjectSearchBar;
 use settings::{initial_local_settings_content, KeymapFile, Settings, SettingsStore};
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
 use terminal_view::terminal_panel::{self, TerminalPanel};
+use theme::ThemeSettings;
 use util::{
     asset_str,
     paths::{self, LOCAL_SETTINGS_RELATIVE_PATH},
@@ -36,9 +41,9 @@ use welcome::BaseKeymap;
 use workspace::Pane;
 use workspace::{
     create_and_open_local_file, notifications::simple_message_notification::MessageNotification,
-    open_new, AppState, NewFile, NewWindow, Workspace, WorkspaceSettings,
+    open_new, AppState, NewFile, NewWindow, Workspace,
 };
-use zed_actions::{OpenBrowser, OpenSettings, OpenZedUrl, Quit};
+use zed_actions::{ForceQuit, OpenBrowser, OpenSettings, OpenZedUrl, Quit};
 
 actions!(
     zed,
@@ -60,7 +65,9 @@ actions!(
         ResetBufferFontSize,
         ResetDatabase,
         ShowAll,
+        ShowLanguageServerDiskUsage,
         ToggleFullScreen,
+        TogglePresentationMode,
         Zoom,
     ]
 );
@@ -70,6 +77,7 @@ pub fn init(cx: &mut AppContext) {
     cx.on_action(|_: &HideOthers, cx| cx.hide_other_apps());
     cx.on_action(|_: &ShowAll, cx| cx.unhide_other_apps());
     cx.on_action(quit);
+    cx.on_action(|_: &ForceQuit, cx| cx.quit());
 }
 
 pub fn build_window_options(
@@ -125,9 +133,12 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
         let feedback_button =
             cx.new_view(|_| feedback::deploy_feedback_button::DeployFeedbackButton::new(workspace));
         let cursor_position = cx.new_view(|_| editor::items::CursorPosition::new());
+        let focus_session_indicator =
+            cx.new_view(|cx| time_tracking::FocusSessionIndicator::new(workspace, cx));
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
+            status_bar.add_right_item(focus_session_indicator, cx);
             status_bar.add_right_item(feedback_button, cx);
             status_bar.add_right_item(copilot, cx);
             status_bar.add_right_item(active_buffer_language, cx);
@@ -212,6 +223,16 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 theme::adjust_font_size(cx, |size| *size -= px(1.0))
             })
             .register_action(move |_, _: &ResetBufferFontSize, cx| theme::reset_font_size(cx))
+            .register_action(|workspace, _: &TogglePresentationMode, cx| {
+                // Uses the sizes from the local settings of the project, if it has any.
+                let location = workspace
+                    .visible_worktrees(cx)
+                    .next()
+                    .map(|worktree| worktree.read(cx).id().to_usize());
+                let settings = ThemeSettings::get(location.map(|id| (id, Path::new(""))), cx)
+                    .presentation_mode;
+                theme::toggle_presentation_mode(settings, cx);
+            })
             .register_action(|_, _: &install_cli::Install, cx| {
                 cx.spawn(|_, cx| async move {
                     install_cli::install_cli(cx.deref())
@@ -232,6 +253,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                     cx,
                 );
             })
+            .register_action(|workspace, _: &ShowLanguageServerDiskUsage, cx| {
+                show_language_server_disk_usage(workspace, cx);
+            })
             .register_action(
                 move |workspace: &mut Workspace,
                       _: &OpenTelemetryLog,
@@ -383,7 +407,6 @@ fn about(_: &mut Workspace, _: &About, cx: &mut gpui::ViewContext<Workspace>) {
 }
 
 fn quit(_: &Quit, cx: &mut AppContext) {
-    let should_confirm = WorkspaceSettings::get_global(cx).confirm_quit;
     cx.spawn(|mut cx| async move {
         let mut workspace_windows = cx.update(|cx| {
             cx.windows()
@@ -399,23 +422,10 @@ fn quit(_: &Quit, cx: &mut AppContext) {
         })
         .log_err();
 
-        if let (true, Some(workspace)) = (should_confirm, workspace_windows.first().copied()) {
-            let answer = workspace
-                .update(&mut cx, |_, cx| {
-                    cx.prompt(
-                        PromptLevel::Info,
-                        "Are you sure you want to quit?",
-                        None,
-                        &["Quit", "Cancel"],
-                    )
-                })
-                .log_err();
-
-            if let Some(answer) = answer {
-                let answer = answer.await.ok();
-                if answer != Some(0) {
-                    return Ok(());
-                }
+        let prompt = cx.update(|cx| workspace::prompt_to_quit(&workspace_windows, "Quit", cx))?;
+        if let Some(prompt) = prompt {
+            if prompt.await.ok() != Some(0) {
+                return Ok(());
             }
         }
 
@@ -670,6 +680,54 @@ fn open_telemetry_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Works
     }).detach();
 }
 
+fn show_language_server_disk_usage(_: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    cx.spawn(|workspace, mut cx| async move {
+        let mut usages = Vec::new();
+        if let Some(mut entries) = smol::fs::read_dir(paths::LANGUAGES_DIR.as_path())
+            .await
+            .log_err()
+        {
+            while let Some(entry) = entries.next().await {
+                let path = entry?.path();
+                let usage = util::fs::disk_usage(&path).await?;
+                usages.push((path.file_name().unwrap_or_default().to_owned(), usage));
+            }
+        }
+        usages.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut text = String::from("# Language Server Disk Usage\n\n");
+        if usages.is_empty() {
+            text.push_str("No language servers have been downloaded.\n");
+        } else {
+            text.push_str("| Language server | Size |\n| --- | ---: |\n");
+            for (name, usage) in &usages {
+                text.push_str(&format!(
+                    "| {} | {:.1} MB |\n",
+                    name.to_string_lossy(),
+                    *usage as f64 / 1_000_000.
+                ));
+            }
+            let total = usages.iter().map(|(_, usage)| usage).sum::<u64>();
+            text.push_str(&format!(
+                "| **Total** | **{:.1} MB** |\n\nDownloaded to `{}`.\n",
+                total as f64 / 1_000_000.,
+                paths::LANGUAGES_DIR.display()
+            ));
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            open_bundled_file(
+                workspace,
+                text.into(),
+                "Language Server Disk Usage",
+                "Markdown",
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 fn open_bundled_file(
     workspace: &mut Workspace,
     text: Cow<'static, str>,
@@ -2705,7 +2763,8 @@ mod tests {
         languages.set_executor(cx.background_executor().clone());
         let languages = Arc::new(languages);
         let node_runtime = node_runtime::FakeNodeRuntime::new();
-        languages::init(languages.clone(), node_runtime, cx);
+        let gem_runtime = gem_runtime::FakeGemRuntime::new();
+        languages::init(languages.clone(), node_runtime, gem_runtime, cx);
         for name in languages.language_names() {
             languages.language_for_name(&name);
         }